@@ -0,0 +1,27 @@
+/// This module provides a coin with a tracked supply, for use in API tests
+module TestAccount::SupplyTestCoin {
+    use Std::ASCII;
+    use Std::Signer;
+    use AptosFramework::Coin::{Self, BurnCapability, MintCapability};
+
+    struct SupplyTestCoin {}
+
+    struct Capabilities has key {
+        mint_cap: MintCapability<SupplyTestCoin>,
+        burn_cap: BurnCapability<SupplyTestCoin>,
+    }
+
+    public(script) fun initialize_and_mint(account: signer, amount: u64) {
+        let (mint_cap, burn_cap) = Coin::initialize<SupplyTestCoin>(
+            &account,
+            ASCII::string(b"Supply Test Coin"),
+            ASCII::string(b"STC"),
+            6,
+            true,
+        );
+        Coin::register_internal<SupplyTestCoin>(&account);
+        let coins = Coin::mint<SupplyTestCoin>(amount, &mint_cap);
+        Coin::deposit<SupplyTestCoin>(Signer::address_of(&account), coins);
+        move_to(&account, Capabilities { mint_cap, burn_cap });
+    }
+}
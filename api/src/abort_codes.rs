@@ -0,0 +1,52 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{AbortCodeParam, AddressParam, MoveIdentifierParam},
+};
+
+use aptos_api_types::{Error, MoveAbort, Response};
+use aptos_types::vm_status::AbortLocation;
+use move_deps::move_core_types::language_storage::ModuleId;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+// GET /abort_codes/<address>/<module_name>/<code>
+pub fn get_abort_code(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("abort_codes" / AddressParam / MoveIdentifierParam / AbortCodeParam)
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_get_abort_code)
+        .with(metrics("get_abort_code"))
+        .boxed()
+}
+
+/// Explains an abort code raised by a module of the framework release, from the error map generated
+/// from the doc comments of the error constants of the framework.
+async fn handle_get_abort_code(
+    address: AddressParam,
+    module_name: MoveIdentifierParam,
+    code: AbortCodeParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_abort_code")?;
+    let module_id = ModuleId::new(
+        address.parse("account address")?.into(),
+        module_name.parse("module name")?,
+    );
+    let code = code.parse("abort code")?;
+    let ledger_info = context.get_latest_ledger_info().await?;
+
+    let abort = MoveAbort::new(&AbortLocation::Module(module_id), code);
+    if abort.reason.is_none() {
+        return Err(Error::not_found(
+            "abort code",
+            format!("{} of module {}", code, abort.location),
+            ledger_info.version(),
+        )
+        .into());
+    }
+    Ok(Response::new(ledger_info, &abort)?)
+}
@@ -4,32 +4,56 @@
 use crate::{
     context::Context,
     failpoint::fail_point,
+    fields::Fields,
     metrics::metrics,
-    param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
+    page::{page_read_error, Page},
+    param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, Param},
     version::Version,
 };
 
 use aptos_api_types::{
-    AccountData, Address, AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response,
-    TransactionId,
+    AccountActivity, AccountData, AccountEventHandle, AccountNonExistenceProof, AccountResources,
+    AccountResourcesBatchRequest, AccountSequenceNumbers, ActivitySummary, Address, AptosErrorCode,
+    AsConverter, CreatedModule, CreatedResource, CrsnWindow, Delegation, Error, LedgerInfo,
+    MoveModule, MoveModuleBytecode, Response, StakePool, Token, TokenData, TransactionId, U64,
 };
 use aptos_types::{
-    account_config::AccountResource,
+    account_address::AccountAddress,
+    account_config::{
+        AccountResource, CollectionsResource, DelegationTable, DelegationTableEntry,
+        StakePoolResource, TokenDepositEvent, TokenStoreResource,
+    },
     account_state::AccountState,
+    account_view::AccountView,
     event::{EventHandle, EventKey},
 };
 
 use anyhow::Result;
-use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
+use aptos_types::{
+    access_path::{AccessPath, Path},
+    state_store::state_key::StateKey,
+};
 use move_deps::move_core_types::{
     identifier::Identifier,
     language_storage::{ResourceKey, StructTag},
     move_resource::MoveStructType,
     value::MoveValue,
 };
-use std::convert::TryInto;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::{TryFrom, TryInto},
+};
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
+/// Number of most recent items of each kind in an account activity summary.
+const RECENT_ACTIVITY_ITEMS: u16 = 10;
+
+#[derive(Clone, Debug, Deserialize)]
+struct ModulesOptions {
+    include_bytecode: Option<Param<bool>>,
+}
+
 // GET /accounts/<address>
 pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam)
@@ -46,50 +70,306 @@ pub fn get_account_resources(context: Context) -> BoxedFilter<(impl Reply,)> {
         .and(warp::get())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .and(warp::query::<Fields>())
+        .map(|address, ctx, version: Version, fields| (version.version, address, fields, ctx))
         .untuple_one()
         .and_then(handle_get_account_resources)
         .with(metrics("get_account_resources"))
         .boxed()
 }
 
+// POST /accounts/resources/batch
+pub fn get_account_resources_batch(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / "resources" / "batch")
+        .and(warp::post())
+        .and(context.content_length_limit_filter())
+        .and(warp::body::json::<AccountResourcesBatchRequest>())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|body, ctx, version: Version| (version.version, body, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_resources_batch)
+        .with(metrics("get_account_resources_batch"))
+        .boxed()
+}
+
 // GET /accounts/<address>/modules
 pub fn get_account_modules(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "modules")
         .and(warp::get())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .and(warp::query::<Page>())
+        .and(warp::query::<ModulesOptions>())
+        .map(|address, ctx, version: Version, page, options| {
+            (version.version, address, page, options, ctx)
+        })
         .untuple_one()
         .and_then(handle_get_account_modules)
         .with(metrics("get_account_modules"))
         .boxed()
 }
 
+// GET /accounts/<address>/sequence_numbers
+pub fn get_account_sequence_numbers(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "sequence_numbers")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_get_account_sequence_numbers)
+        .with(metrics("get_account_sequence_numbers"))
+        .boxed()
+}
+
+// GET /accounts/<address>/stake_pool
+pub fn get_account_stake_pool(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "stake_pool")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_stake_pool)
+        .with(metrics("get_account_stake_pool"))
+        .boxed()
+}
+
+// GET /accounts/<address>/tokens?start=<sequence_number>&limit=<page_size>
+pub fn get_account_tokens(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "tokens")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .and(warp::query::<Page>())
+        .map(|address, ctx, version: Version, page| (version.version, address, page, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_tokens)
+        .with(metrics("get_account_tokens"))
+        .boxed()
+}
+
+// GET /accounts/<address>/event_handles
+pub fn get_account_event_handles(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "event_handles")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_event_handles)
+        .with(metrics("get_account_event_handles"))
+        .boxed()
+}
+
+// GET /accounts/<address>/activity
+pub fn get_account_activity(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "activity")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_activity)
+        .with(metrics("get_account_activity"))
+        .boxed()
+}
+
+// GET /accounts/<address>/non_existence_proof
+pub fn get_account_non_existence_proof(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "non_existence_proof")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_non_existence_proof)
+        .with(metrics("get_account_non_existence_proof"))
+        .boxed()
+}
+
 async fn handle_get_account(
     address: AddressParam,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account")?;
-    Ok(Account::new(None, address, context)?.account()?)
+    Ok(Account::new(None, address, context)
+        .await?
+        .account()
+        .await?)
+}
+
+async fn handle_get_account_sequence_numbers(
+    address: AddressParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_sequence_numbers")?;
+    Ok(Account::new(None, address, context)
+        .await?
+        .sequence_numbers()
+        .await?)
 }
 
 async fn handle_get_account_resources(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    fields: Fields,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_resources")?;
-    Ok(Account::new(ledger_version, address, context)?.resources()?)
+    let account = Account::new(ledger_version, address, context).await?;
+    Ok(fields.apply(account.resources().await?)?)
+}
+
+async fn handle_get_account_resources_batch(
+    ledger_version: Option<LedgerVersionParam>,
+    body: AccountResourcesBatchRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_resources_batch")?;
+    Ok(resources_batch(ledger_version, body, context).await?)
 }
 
 async fn handle_get_account_modules(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    page: Page,
+    options: ModulesOptions,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_modules")?;
-    Ok(Account::new(ledger_version, address, context)?.modules()?)
+    let include_bytecode = options
+        .include_bytecode
+        .map(|include| include.parse("include_bytecode"))
+        .transpose()?
+        .unwrap_or(true);
+    Ok(Account::new(ledger_version, address, context)
+        .await?
+        .modules(page, include_bytecode)
+        .await?)
+}
+
+async fn handle_get_account_stake_pool(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_stake_pool")?;
+    Ok(Account::new(ledger_version, address, context)
+        .await?
+        .stake_pool()
+        .await?)
+}
+
+async fn handle_get_account_tokens(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    page: Page,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_tokens")?;
+    Ok(Account::new(ledger_version, address, context)
+        .await?
+        .tokens(page)
+        .await?)
+}
+
+async fn handle_get_account_event_handles(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_event_handles")?;
+    Ok(Account::new(ledger_version, address, context)
+        .await?
+        .event_handles()
+        .await?)
+}
+
+async fn handle_get_account_activity(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_activity")?;
+    Ok(Account::new(ledger_version, address, context)
+        .await?
+        .activity()
+        .await?)
+}
+
+async fn handle_get_account_non_existence_proof(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_non_existence_proof")?;
+    Ok(Account::new(ledger_version, address, context)
+        .await?
+        .non_existence_proof()
+        .await?)
+}
+
+/// Returns the resources of every requested account, in the order of the request. All accounts
+/// are read in one batched storage read at the same ledger version.
+async fn resources_batch(
+    ledger_version: Option<LedgerVersionParam>,
+    body: AccountResourcesBatchRequest,
+    context: Context,
+) -> Result<impl Reply, Error> {
+    let AccountResourcesBatchRequest {
+        addresses,
+        resource_type,
+    } = body;
+    let max_batch_size = context.api_config().max_account_resources_batch_size;
+    if addresses.len() > max_batch_size {
+        return Err(Error::limit_exceeded(
+            AptosErrorCode::BatchTooLarge,
+            "number of addresses",
+            addresses.len() as u64,
+            max_batch_size as u64,
+        ));
+    }
+    let resource_type: Option<StructTag> = resource_type
+        .map(|typ| typ.try_into().map_err(Error::bad_request))
+        .transpose()?;
+
+    let latest_ledger_info = context.get_latest_ledger_info().await?;
+    let ledger_version = ledger_version
+        .map(|v| v.parse("ledger version"))
+        .unwrap_or_else(|| Ok(latest_ledger_info.version()))?;
+    if ledger_version > latest_ledger_info.version() {
+        return Err(Error::not_found(
+            "ledger",
+            TransactionId::Version(ledger_version),
+            latest_ledger_info.version(),
+        ));
+    }
+
+    let account_addresses: Vec<_> = addresses.iter().map(|address| (*address).into()).collect();
+    let account_states = context
+        .get_account_states(&account_addresses, ledger_version)
+        .await?;
+    let resolver = context.move_resolver()?;
+    let converter = resolver
+        .as_converter()
+        .with_layout_cache(context.layout_cache(latest_ledger_info.version())?);
+    let batch = addresses
+        .into_iter()
+        .zip(account_states)
+        .map(|(address, account_state)| {
+            let resources = match &account_state {
+                Some(account_state) => converter.try_into_resources(
+                    account_state.get_resources().filter(|(typ, _)| {
+                        resource_type
+                            .as_ref()
+                            .map_or(true, |resource_type| typ == resource_type)
+                    }),
+                )?,
+                None => vec![],
+            };
+            Ok(AccountResources { address, resources })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Response::new(latest_ledger_info, &batch)
 }
 
 pub(crate) struct Account {
@@ -100,12 +380,12 @@ pub(crate) struct Account {
 }
 
 impl Account {
-    pub fn new(
+    pub async fn new(
         ledger_version: Option<LedgerVersionParam>,
         address: AddressParam,
         context: Context,
     ) -> Result<Self, Error> {
-        let latest_ledger_info = context.get_latest_ledger_info()?;
+        let latest_ledger_info = context.get_latest_ledger_info().await?;
         let ledger_version = ledger_version
             .map(|v| v.parse("ledger version"))
             .unwrap_or_else(|| Ok(latest_ledger_info.version()))?;
@@ -126,47 +406,454 @@ impl Account {
         })
     }
 
-    pub fn account(self) -> Result<impl Reply, Error> {
+    pub async fn account(self) -> Result<impl Reply, Error> {
+        let account = AccountData {
+            crsn: self.crsn_window().await?,
+            ..self.account_resource().await?.into()
+        };
+
+        Response::new(self.latest_ledger_info, &account)
+    }
+
+    pub async fn sequence_numbers(self) -> Result<impl Reply, Error> {
+        let committed = self.account_resource().await?.sequence_number();
+        let pending = self
+            .context
+            .get_pending_sequence_numbers(self.address.into())
+            .await?;
+
+        Response::new(
+            self.latest_ledger_info,
+            &AccountSequenceNumbers::new(committed, pending),
+        )
+    }
+
+    /// Proves that the account does not exist, against the latest signed ledger info.
+    pub async fn non_existence_proof(self) -> Result<impl Reply, Error> {
+        if self.account_resource_bytes().await?.is_some() {
+            return Err(Error::bad_request(format!(
+                "account {} exists at ledger version {}",
+                self.address, self.ledger_version
+            ))
+            .aptos_error_code(AptosErrorCode::AccountExists));
+        }
+
+        let ledger_info_with_signatures = self
+            .context
+            .get_latest_ledger_info_with_signatures()
+            .await?;
+        let proof = self
+            .context
+            .get_account_non_existence_proof(
+                self.address.into(),
+                self.ledger_version,
+                ledger_info_with_signatures.ledger_info().version(),
+            )
+            .await?;
+        let non_existence_proof = AccountNonExistenceProof {
+            version: self.ledger_version.into(),
+            ledger_info_with_signatures: bcs::to_bytes(&ledger_info_with_signatures)
+                .map_err(anyhow::Error::from)?
+                .into(),
+            proof: bcs::to_bytes(&proof).map_err(anyhow::Error::from)?.into(),
+        };
+        Response::new(self.latest_ledger_info, &non_existence_proof)
+    }
+
+    /// Reads only the `0x1::Account::Account` resource, an account exists iff it has one.
+    async fn account_resource(&self) -> Result<AccountResource, Error> {
+        let account_resource: AccountResource = self
+            .account_resource_bytes()
+            .await?
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?
+            .ok_or_else(|| self.account_not_found())?;
+
+        Ok(account_resource)
+    }
+
+    /// The nonce window of the account if it uses CRSNs. The CRSN resource is only looked up when
+    /// the `crsn` feature is enabled, as the framework of this release does not publish it.
+    #[cfg(feature = "crsn")]
+    async fn crsn_window(&self) -> Result<Option<CrsnWindow>, Error> {
+        use aptos_types::account_config::CRSNResource;
+
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            self.address.into(),
+            CRSNResource::struct_tag(),
+        )));
+        let crsn: Option<CRSNResource> = self
+            .context
+            .get_state_value(&state_key, self.ledger_version)
+            .await?
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?;
+
+        Ok(crsn.as_ref().map(CrsnWindow::from))
+    }
+
+    #[cfg(not(feature = "crsn"))]
+    async fn crsn_window(&self) -> Result<Option<CrsnWindow>, Error> {
+        Ok(None)
+    }
+
+    async fn account_resource_bytes(&self) -> Result<Option<Vec<u8>>, Error> {
         let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
             self.address.into(),
             AccountResource::struct_tag(),
         )));
 
-        let state_value = self
+        Ok(self
             .context
-            .get_state_value(&state_key, self.ledger_version)?;
+            .get_state_value(&state_key, self.ledger_version)
+            .await?)
+    }
 
-        let account_resource: AccountResource = state_value
+    pub async fn stake_pool(self) -> Result<impl Reply, Error> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            self.address.into(),
+            StakePoolResource::struct_tag(),
+        )));
+        let pool: StakePoolResource = self
+            .context
+            .get_state_value(&state_key, self.ledger_version)
+            .await?
             .map(|bytes| bcs::from_bytes(&bytes))
             .transpose()
             .map_err(anyhow::Error::from)?
-            .ok_or_else(|| self.resource_not_found(&AccountResource::struct_tag()))?;
+            .ok_or_else(|| self.resource_not_found(&StakePoolResource::struct_tag()))?;
 
-        let account: AccountData = account_resource.into();
+        let stake_pool = StakePool::new(
+            &pool,
+            self.delegations(pool.active()).await?,
+            self.delegations(pool.inactive()).await?,
+            self.delegations(pool.pending_active()).await?,
+            self.delegations(pool.pending_inactive()).await?,
+        );
+        Response::new(self.latest_ledger_info, &stake_pool)
+    }
 
-        Response::new(self.latest_ledger_info, &account)
+    /// Walks the linked list of a delegation table from its head.
+    async fn delegations(&self, table: &DelegationTable) -> Result<Vec<Delegation>, Error> {
+        let mut delegations = vec![];
+        let mut next = table.head();
+        while let Some(delegator) = next {
+            // guards against a corrupted list looping forever
+            if delegations.len() as u64 >= table.len() {
+                return Err(Error::internal(anyhow::anyhow!(
+                    "delegation table {} has more entries than its length {}",
+                    table.handle(),
+                    table.len()
+                )));
+            }
+            let bytes = self
+                .context
+                .get_state_value(&table.entry_state_key(&delegator), self.ledger_version)
+                .await?
+                .ok_or_else(|| {
+                    Error::internal(anyhow::anyhow!(
+                        "delegation of {} not found in table {}",
+                        delegator,
+                        table.handle()
+                    ))
+                })?;
+            let entry: DelegationTableEntry =
+                bcs::from_bytes(&bytes).map_err(anyhow::Error::from)?;
+            delegations.push(Delegation::new(delegator.into(), entry.delegation()));
+            next = entry.next();
+        }
+        Ok(delegations)
+    }
+
+    /// Lists the tokens held by the account. Table items can't be enumerated, so the token ids
+    /// are collected from the deposit events of the token store, in the order they were first
+    /// received, and the tokens withdrawn since are left out. The pages are ranges of deposit
+    /// events, so that a request reads a bounded number of them.
+    pub async fn tokens(self, page: Page) -> Result<impl Reply, Error> {
+        let store: Option<TokenStoreResource> = self
+            .state_value(
+                &self.resource_state_key(self.address.into(), &TokenStoreResource::struct_tag()),
+            )
+            .await?;
+        let store = match store {
+            Some(store) => store,
+            // no token was ever deposited to the account
+            None => return Response::new(self.latest_ledger_info, &Vec::<Token>::new()),
+        };
+
+        let deposit_events = store.deposit_events();
+        let start = page.start(0, u64::MAX)?;
+        let limit = page.limit(self.context.api_config().max_events_page_size)?;
+        let events = if start < deposit_events.count() {
+            self.context
+                .get_events(deposit_events.key(), start, limit, self.ledger_version)
+                .await
+                .map_err(page_read_error)?
+        } else {
+            vec![]
+        };
+        let cursor = Page::next_start(start, events.len(), limit, Some(deposit_events.count()));
+
+        let mut token_ids = vec![];
+        let mut seen = HashSet::new();
+        for event in events {
+            let deposit = TokenDepositEvent::try_from_bytes(event.event_data())?;
+            if seen.insert(deposit.id().clone()) {
+                token_ids.push(deposit.id().clone());
+            }
+        }
+
+        let mut collections = HashMap::new();
+        let mut tokens = vec![];
+        for id in token_ids {
+            let token: Option<aptos_types::account_config::Token> = self
+                .state_value(&store.tokens().entry_state_key(&id)?)
+                .await?;
+            let amount = match token {
+                Some(token) if token.value() > 0 => token.value(),
+                _ => continue,
+            };
+            tokens.push(Token {
+                id: (&id).into(),
+                amount: amount.into(),
+                data: self
+                    .token_data(&id, &mut collections)
+                    .await?
+                    .as_ref()
+                    .map(TokenData::from),
+            });
+        }
+        Ok(Response::new(self.latest_ledger_info, &tokens)?.with_cursor(cursor))
+    }
+
+    /// Looks up the token data in the collections of the creator of the token, caching the
+    /// collections resource of each creator.
+    async fn token_data(
+        &self,
+        id: &aptos_types::account_config::TokenId,
+        collections: &mut HashMap<AccountAddress, Option<CollectionsResource>>,
+    ) -> Result<Option<aptos_types::account_config::TokenData>, Error> {
+        let creator = id.creator();
+        if !collections.contains_key(&creator) {
+            let state_key = self.resource_state_key(creator, &CollectionsResource::struct_tag());
+            collections.insert(creator, self.state_value(&state_key).await?);
+        }
+        match &collections[&creator] {
+            Some(creator_collections) => {
+                self.state_value(&creator_collections.token_data().entry_state_key(id)?)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn resource_state_key(&self, address: AccountAddress, struct_tag: &StructTag) -> StateKey {
+        StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            struct_tag.clone(),
+        )))
+    }
+
+    async fn state_value<T: DeserializeOwned>(
+        &self,
+        state_key: &StateKey,
+    ) -> Result<Option<T>, Error> {
+        Ok(self
+            .context
+            .get_state_value(state_key, self.ledger_version)
+            .await?
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?)
     }
 
-    pub fn resources(self) -> Result<impl Reply, Error> {
+    pub async fn resources(self) -> Result<Response, Error> {
+        let account_state = self.account_state().await?;
         let resources = self
             .context
             .move_resolver()?
             .as_converter()
-            .try_into_resources(self.account_state()?.get_resources())?;
+            .with_layout_cache(
+                self.context
+                    .layout_cache(self.latest_ledger_info.version())?,
+            )
+            .try_into_resources(account_state.get_resources())?;
         Response::new(self.latest_ledger_info, &resources)
     }
 
-    pub fn modules(self) -> Result<impl Reply, Error> {
-        let modules = self
-            .account_state()?
-            .into_modules()
-            .map(MoveModuleBytecode::new)
-            .map(|m| m.try_parse_abi())
+    /// Lists the modules of the account, all of them unless a page is requested, in the order of
+    /// their access paths. Without their bytecode, only the ABIs of the modules are returned.
+    pub async fn modules(self, page: Page, include_bytecode: bool) -> Result<impl Reply, Error> {
+        let mut modules: Vec<Vec<u8>> = self.account_state().await?.into_modules().collect();
+        let mut pagination = None;
+        if page.is_requested() {
+            let total_items = modules.len() as u64;
+            let start = page.start(0, u64::MAX)?;
+            let limit = page.limit(self.context.api_config().max_page_size)?;
+            modules = modules
+                .into_iter()
+                .skip(usize::try_from(start).unwrap_or(usize::MAX))
+                .take(limit as usize)
+                .collect();
+            let cursor = Page::next_start(start, modules.len(), limit, Some(total_items));
+            pagination = Some((total_items, cursor));
+        }
+        let modules = modules
+            .into_iter()
+            .map(|bytes| MoveModuleBytecode::new(bytes).try_parse_abi())
             .collect::<Result<Vec<MoveModuleBytecode>>>()?;
-        Response::new(self.latest_ledger_info, &modules)
+
+        let response = if include_bytecode {
+            Response::new(self.latest_ledger_info, &modules)?
+        } else {
+            let abis = modules
+                .into_iter()
+                .map(|module| {
+                    module.abi.ok_or_else(|| {
+                        Error::internal(anyhow::anyhow!(
+                            "invalid bytecode of a module of account {}",
+                            self.address
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<MoveModule>, Error>>()?;
+            Response::new(self.latest_ledger_info, &abis)?
+        };
+        Ok(match pagination {
+            Some((total_items, cursor)) => {
+                response.with_total_items(total_items).with_cursor(cursor)
+            }
+            None => response,
+        })
+    }
+
+    /// Lists the event handles of the resources of the account, in the order of their creation
+    /// numbers, so that clients can discover the event streams of the account.
+    pub async fn event_handles(self) -> Result<impl Reply, Error> {
+        let account_state = self.account_state().await?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+
+        let mut event_handles = vec![];
+        for (typ, bytes) in account_state.get_resources() {
+            for (field, event_type, handle) in converter.find_event_handles(&typ, bytes)? {
+                event_handles.push(AccountEventHandle {
+                    resource_type: typ.clone().into(),
+                    field,
+                    key: (*handle.key()).into(),
+                    creation_number: handle.key().get_creation_number().into(),
+                    event_type: event_type.into(),
+                    counter: handle.count().into(),
+                });
+            }
+        }
+        event_handles.sort_by_key(|handle| handle.creation_number.0);
+        Response::new(self.latest_ledger_info, &event_handles)
     }
 
-    pub fn find_event_key(
+    /// Summarizes the activity of the account from the secondary indexes of the storage: sent
+    /// transactions from the account transaction index, received coins from the deposit events of
+    /// the coin store, and modules and resources ordered by the version they were created at,
+    /// from the state value index.
+    pub async fn activity(self) -> Result<impl Reply, Error> {
+        let account_state = self.account_state().await?;
+        let deposits = match account_state.get_coin_store_resource()? {
+            Some(coin_store) if coin_store.deposit_events().count() > 0 => {
+                let deposit_events = coin_store.deposit_events();
+                let events = self
+                    .context
+                    .get_events_descending(
+                        deposit_events.key(),
+                        deposit_events.count() - 1,
+                        RECENT_ACTIVITY_ITEMS,
+                        self.ledger_version,
+                    )
+                    .await?;
+                Some((deposit_events.count(), events))
+            }
+            _ => None,
+        };
+        let sequence_number = account_state
+            .get_account_resource()?
+            .ok_or_else(|| self.account_not_found())?
+            .sequence_number();
+        let recent_transactions = self
+            .context
+            .get_account_transactions(
+                self.address.into(),
+                sequence_number.saturating_sub(RECENT_ACTIVITY_ITEMS as u64),
+                RECENT_ACTIVITY_ITEMS,
+                self.ledger_version,
+            )
+            .await
+            .map_err(page_read_error)?;
+        let mut timestamps = vec![];
+        for txn in &recent_transactions {
+            timestamps.push(self.context.get_block_timestamp(txn.version).await?);
+        }
+
+        let mut modules = vec![];
+        let mut resources = vec![];
+        for (path, _) in account_state.iter() {
+            let state_key =
+                StateKey::AccessPath(AccessPath::new(self.address.into(), path.clone()));
+            let version = self
+                .context
+                .get_creation_version(&state_key, self.ledger_version)
+                .await?
+                .map(U64::from);
+            match Path::try_from(path).map_err(anyhow::Error::from)? {
+                Path::Code(module_id) => modules.push(CreatedModule {
+                    module: module_id.into(),
+                    version,
+                }),
+                Path::Resource(struct_tag) => resources.push(CreatedResource {
+                    typ: struct_tag.into(),
+                    version,
+                }),
+            }
+        }
+
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter().with_layout_cache(
+            self.context
+                .layout_cache(self.latest_ledger_info.version())?,
+        );
+        let sent_transactions = ActivitySummary {
+            count: sequence_number.into(),
+            recent: recent_transactions
+                .into_iter()
+                .zip(timestamps)
+                .rev()
+                .map(|(txn, timestamp)| converter.try_into_onchain_transaction(timestamp, txn))
+                .collect::<Result<_>>()?,
+        };
+
+        let received_coin_events = match deposits {
+            Some((count, events)) => ActivitySummary {
+                count: count.into(),
+                recent: converter.try_into_events(&events)?,
+            },
+            None => ActivitySummary {
+                count: 0.into(),
+                recent: vec![],
+            },
+        };
+
+        let activity = AccountActivity {
+            sent_transactions,
+            received_coin_events,
+            modules: most_recent(modules, |module| module.version.map(|version| version.0)),
+            resources: most_recent(resources, |resource| {
+                resource.version.map(|version| version.0)
+            }),
+        };
+        Response::new(self.latest_ledger_info, &activity)
+    }
+
+    pub async fn find_event_key(
         &self,
         struct_tag_param: MoveStructTagParam,
         field_name_param: MoveIdentifierParam,
@@ -174,7 +861,7 @@ impl Account {
         let struct_tag: StructTag = struct_tag_param.parse("event handle struct")?.try_into()?;
         let field_name = field_name_param.parse("event handle field name")?;
 
-        let resource = self.find_resource(&struct_tag)?;
+        let resource = self.find_resource(&struct_tag).await?;
 
         let (_id, value) = resource
             .into_iter()
@@ -193,11 +880,11 @@ impl Account {
         Ok(*event_handle.key())
     }
 
-    pub fn find_resource(
+    pub async fn find_resource(
         &self,
         struct_tag: &StructTag,
     ) -> Result<Vec<(Identifier, MoveValue)>, Error> {
-        let account_state = self.account_state()?;
+        let account_state = self.account_state().await?;
         let (typ, data) = account_state
             .get_resources()
             .find(|(tag, _data)| tag == struct_tag)
@@ -209,10 +896,11 @@ impl Account {
             .move_struct_fields(&typ, data)?)
     }
 
-    fn account_state(&self) -> Result<AccountState, Error> {
+    async fn account_state(&self) -> Result<AccountState, Error> {
         let state = self
             .context
-            .get_account_state(self.address.into(), self.ledger_version)?
+            .get_account_state(self.address.into(), self.ledger_version)
+            .await?
             .ok_or_else(|| self.account_not_found())?;
         Ok(state)
     }
@@ -226,6 +914,7 @@ impl Account {
             ),
             self.latest_ledger_info.version(),
         )
+        .aptos_error_code(AptosErrorCode::AccountNotFound)
     }
 
     fn resource_not_found(&self, struct_tag: &StructTag) -> Error {
@@ -250,3 +939,15 @@ impl Account {
         )
     }
 }
+
+/// Keeps the `RECENT_ACTIVITY_ITEMS` items created last, most recent first.
+/// The items created at an unknown version come last.
+fn most_recent<T>(mut items: Vec<T>, version: impl Fn(&T) -> Option<u64>) -> ActivitySummary<T> {
+    let count = items.len() as u64;
+    items.sort_by_key(|item| std::cmp::Reverse(version(item)));
+    items.truncate(RECENT_ACTIVITY_ITEMS as usize);
+    ActivitySummary {
+        count: count.into(),
+        recent: items,
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::transaction::Version;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+const DEFAULT_CAPACITY: usize = 100_000;
+const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Bounded cache of recently committed transaction hashes and their versions.
+///
+/// The cache sits in front of the (persistent) transaction-by-hash index in storage: clients
+/// retrying a submission after a timeout usually retry within a few minutes, so recently looked
+/// up hashes are kept in memory and the database remains the source of truth across restarts.
+pub(crate) struct CommittedTransactionCache {
+    capacity: usize,
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    versions: HashMap<HashValue, (Version, Instant)>,
+    // insertion order, used for evicting the oldest entries once the capacity is reached
+    order: VecDeque<HashValue>,
+}
+
+impl Default for CommittedTransactionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, DEFAULT_TTL)
+    }
+}
+
+impl CommittedTransactionCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    pub fn get(&self, hash: &HashValue) -> Option<Version> {
+        let inner = self.inner.lock();
+        match inner.versions.get(hash) {
+            Some((version, inserted_at)) if inserted_at.elapsed() < self.ttl => Some(*version),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&self, hash: HashValue, version: Version) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        if inner
+            .versions
+            .insert(hash, (version, Instant::now()))
+            .is_none()
+        {
+            inner.order.push_back(hash);
+        }
+        while inner.order.len() > self.capacity {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.versions.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommittedTransactionCache;
+    use aptos_crypto::HashValue;
+    use std::time::Duration;
+
+    #[test]
+    fn test_get_and_insert() {
+        let cache = CommittedTransactionCache::new(10, Duration::from_secs(60));
+        let hash = HashValue::random();
+        assert_eq!(cache.get(&hash), None);
+        cache.insert(hash, 5);
+        assert_eq!(cache.get(&hash), Some(5));
+    }
+
+    #[test]
+    fn test_evict_oldest_entry_when_full() {
+        let cache = CommittedTransactionCache::new(2, Duration::from_secs(60));
+        let hashes: Vec<_> = (0..3).map(|_| HashValue::random()).collect();
+        for (version, hash) in hashes.iter().enumerate() {
+            cache.insert(*hash, version as u64);
+        }
+        assert_eq!(cache.get(&hashes[0]), None);
+        assert_eq!(cache.get(&hashes[1]), Some(1));
+        assert_eq!(cache.get(&hashes[2]), Some(2));
+    }
+
+    #[test]
+    fn test_expired_entry_is_ignored() {
+        let cache = CommittedTransactionCache::new(10, Duration::from_secs(0));
+        let hash = HashValue::random();
+        cache.insert(hash, 1);
+        assert_eq!(cache.get(&hash), None);
+    }
+}
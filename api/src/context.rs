@@ -1,11 +1,24 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api_types::{Error, LedgerInfo, TransactionOnChainData};
+use crate::{
+    committed_txns::CommittedTransactionCache,
+    endpoint_groups::endpoint_group,
+    idempotency::{IdempotencyCache, Reservation},
+    submission_batcher::SubmissionBatcher,
+    validators::EpochStatsCache,
+};
+use aptos_api_types::{
+    Error, LayoutCache, LedgerInfo, TransactionOnChainData, X_APTOS_MIN_VERSION,
+};
 use aptos_config::config::ApiConfig;
 use aptos_crypto::HashValue;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_mempool::{
+    MempoolClientRequest, MempoolClientSender, MempoolUtilization, SubmissionStatus,
+};
 use aptos_types::{
+    access_path::Path,
     account_address::AccountAddress,
     account_state::AccountState,
     chain_id::ChainId,
@@ -13,30 +26,56 @@ use aptos_types::{
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{SignedTransaction, TransactionWithProof},
+    write_set::WriteOp,
+};
+use storage_interface::{
+    async_reader::AsyncDbReader, errors::AptosDbError, BlockGasStats, DbReader, LedgerReader, Order,
 };
-use storage_interface::{DbReader, Order};
 
 use anyhow::{ensure, format_err, Result};
-use aptos_state_view::StateView;
 use aptos_types::{
-    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix},
+    state_store::{
+        state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValueWithProof,
+    },
     transaction::Version,
 };
 use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
 use futures::{channel::oneshot, SinkExt};
-use std::{convert::Infallible, sync::Arc};
+use move_deps::move_core_types::language_storage::StructTag;
+use std::{convert::Infallible, sync::Arc, time::Duration};
 use storage_interface::state_view::{DbStateView, DbStateViewAtVersion, LatestDbStateView};
-use warp::{filters::BoxedFilter, Filter, Reply};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use warp::{
+    filters::{path::FullPath, BoxedFilter},
+    http::{header::CONTENT_LENGTH, Method, StatusCode},
+    Filter, Rejection, Reply,
+};
 
 // Context holds application scope context
 #[derive(Clone)]
 pub struct Context {
     chain_id: ChainId,
     db: Arc<dyn DbReader>,
+    // reads that may hit the disk, run off the tokio worker threads
+    async_db: AsyncDbReader,
     mp_sender: MempoolClientSender,
-    api_config: ApiConfig,
+    api_config: Arc<RwLock<ApiConfig>>,
+    committed_txns: Arc<CommittedTransactionCache>,
+    submissions: Arc<IdempotencyCache>,
+    submission_batcher: Arc<SubmissionBatcher>,
+    // permits of the open event streams
+    event_streams: Arc<Semaphore>,
+    max_event_streams: usize,
+    layouts: Arc<LayoutCache>,
+    // the version up to which republished modules have been evicted from `layouts`
+    layouts_version: Arc<Mutex<Option<Version>>>,
+    epoch_stats: Arc<EpochStatsCache>,
 }
 
+/// Beyond this many transactions since the last use of the layout cache, the cache is cleared
+/// instead of scanning the write sets of all these transactions for republished modules.
+const MAX_LAYOUT_CACHE_SYNC_TRANSACTIONS: u64 = 10_000;
+
 impl Context {
     pub fn new(
         chain_id: ChainId,
@@ -44,20 +83,72 @@ impl Context {
         mp_sender: MempoolClientSender,
         api_config: ApiConfig,
     ) -> Self {
+        let submissions = Arc::new(IdempotencyCache::new(
+            api_config.idempotency_cache_capacity,
+            Duration::from_secs(api_config.idempotency_key_ttl_secs),
+        ));
+        let max_event_streams = api_config.max_event_streams;
         Self {
             chain_id,
+            async_db: AsyncDbReader::new(db.clone()),
             db,
             mp_sender,
-            api_config,
+            api_config: Arc::new(RwLock::new(api_config)),
+            committed_txns: Arc::new(CommittedTransactionCache::default()),
+            submissions,
+            submission_batcher: Arc::new(SubmissionBatcher::default()),
+            event_streams: Arc::new(Semaphore::new(max_event_streams)),
+            max_event_streams,
+            layouts: Arc::new(LayoutCache::default()),
+            layouts_version: Arc::new(Mutex::new(None)),
+            epoch_stats: Arc::new(EpochStatsCache::default()),
         }
     }
 
+    /// Returns the cache of the validator stats of the completed epochs.
+    pub(crate) fn epoch_stats_cache(&self) -> &EpochStatsCache {
+        &self.epoch_stats
+    }
+
     pub fn move_resolver(&self) -> Result<RemoteStorageOwned<DbStateView>> {
         self.db
             .latest_state_view()
             .map(|state_view| state_view.into_move_resolver())
     }
 
+    /// Returns the cache of the struct layouts used to render Move values of the state at
+    /// `version`, after evicting the layouts depending on modules published up to `version`.
+    /// Returns `None` for a version older than the ones the cache has been used with, as modules
+    /// may have been republished since.
+    pub fn layout_cache(&self, version: Version) -> Result<Option<&LayoutCache>> {
+        let mut layouts_version = self.layouts_version.lock();
+        match *layouts_version {
+            Some(synced_version) if version < synced_version => return Ok(None),
+            Some(synced_version) if version == synced_version => {}
+            Some(synced_version)
+                if version - synced_version <= MAX_LAYOUT_CACHE_SYNC_TRANSACTIONS =>
+            {
+                let modules: Vec<_> = self
+                    .db
+                    .get_write_sets(synced_version + 1, version + 1)?
+                    .iter()
+                    .flat_map(|write_set| write_set.iter())
+                    .filter_map(|(state_key, _)| match state_key {
+                        StateKey::AccessPath(access_path) => match access_path.get_path() {
+                            Path::Code(module_id) => Some(module_id),
+                            Path::Resource(_) => None,
+                        },
+                        _ => None,
+                    })
+                    .collect();
+                self.layouts.invalidate_modules(&modules);
+            }
+            _ => self.layouts.clear(),
+        }
+        *layouts_version = Some(version);
+        Ok(Some(&self.layouts))
+    }
+
     pub fn state_view_at_version(&self, version: Version) -> Result<DbStateView> {
         self.db.state_view_at_version(Some(version))
     }
@@ -67,7 +158,94 @@ impl Context {
     }
 
     pub fn content_length_limit(&self) -> u64 {
-        self.api_config.content_length_limit()
+        self.api_config.read().content_length_limit()
+    }
+
+    pub fn api_config(&self) -> ApiConfig {
+        self.api_config.read().clone()
+    }
+
+    /// Applies an updated API config to the requests served from now on.
+    pub fn update_api_config(&self, api_config: ApiConfig) {
+        *self.api_config.write() = api_config;
+    }
+
+    /// Rejects requests without a content length or with a body larger than the content length
+    /// limit. Unlike `warp::body::content_length_limit`, the limit is read for every request, so
+    /// updates of the API config apply to the routes already built.
+    pub fn content_length_limit_filter(&self) -> BoxedFilter<()> {
+        warp::header::optional::<u64>(CONTENT_LENGTH.as_str())
+            .and(self.clone().filter())
+            .and_then(|content_length: Option<u64>, context: Context| async move {
+                match content_length {
+                    None => Err::<(), Rejection>(
+                        Error::new(
+                            StatusCode::LENGTH_REQUIRED,
+                            "A content-length header is required".to_owned(),
+                        )
+                        .into(),
+                    ),
+                    Some(length) if length > context.content_length_limit() => Err(Error::new(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        "The request payload is too large".to_owned(),
+                    )
+                    .into()),
+                    Some(_) => Ok(()),
+                }
+            })
+            .untuple_one()
+            .boxed()
+    }
+
+    /// Rejects reads with an `X-Aptos-Min-Version` header when the node has not reached that
+    /// ledger version yet, so that a client behind a load balancer never reads a state older
+    /// than one it has already seen.
+    pub fn min_version_filter(&self) -> BoxedFilter<()> {
+        warp::method()
+            .and(warp::header::optional::<String>(X_APTOS_MIN_VERSION))
+            .and(self.clone().filter())
+            .and_then(
+                |method: Method, min_version: Option<String>, context: Context| async move {
+                    let min_version = match min_version {
+                        Some(min_version) if method == Method::GET => min_version,
+                        _ => return Ok::<(), Rejection>(()),
+                    };
+                    let min_version = min_version.parse::<u64>().map_err(|_| {
+                        Error::bad_request(format!(
+                            "invalid {} header: {}",
+                            X_APTOS_MIN_VERSION, min_version
+                        ))
+                    })?;
+                    let ledger_version = context.get_latest_ledger_info().await?.version();
+                    if ledger_version < min_version {
+                        return Err(
+                            Error::ledger_version_behind(min_version, ledger_version).into()
+                        );
+                    }
+                    Ok(())
+                },
+            )
+            .untuple_one()
+            .boxed()
+    }
+
+    /// Rejects the requests to the endpoint groups disabled by the API config with 403.
+    pub fn endpoint_group_filter(&self) -> BoxedFilter<()> {
+        warp::method()
+            .and(warp::path::full())
+            .and(self.clone().filter())
+            .and_then(
+                |method: Method, path: FullPath, context: Context| async move {
+                    match endpoint_group(&method, path.as_str()) {
+                        Some(group) if !context.api_config.read().is_enabled(group) => {
+                            Err(Error::endpoint_disabled(group.as_str()).into())
+                        }
+                        _ => Ok::<(), Rejection>(()),
+                    }
+                },
+            )
+            .untuple_one()
+            .boxed()
     }
 
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
@@ -75,6 +253,25 @@ impl Context {
     }
 
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
+        let (window_ms, max_batch_size) = {
+            let api_config = self.api_config.read();
+            (
+                api_config.submission_batch_window_ms,
+                api_config.max_submission_batch_size,
+            )
+        };
+        if window_ms > 0 && max_batch_size > 1 {
+            return self
+                .submission_batcher
+                .submit(
+                    &self.mp_sender,
+                    txn,
+                    Duration::from_millis(window_ms),
+                    max_batch_size,
+                )
+                .await;
+        }
+
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
             .clone()
@@ -84,111 +281,257 @@ impl Context {
         callback.await?
     }
 
-    pub fn get_latest_ledger_info(&self) -> Result<LedgerInfo, Error> {
+    pub async fn get_latest_ledger_info(&self) -> Result<LedgerInfo, Error> {
         Ok(LedgerInfo::new(
             &self.chain_id(),
-            &self.get_latest_ledger_info_with_signatures()?,
+            &self.get_latest_ledger_info_with_signatures().await?,
         ))
     }
 
-    pub fn get_latest_ledger_info_with_signatures(&self) -> Result<LedgerInfoWithSignatures> {
-        self.db.get_latest_ledger_info()
+    pub async fn get_latest_ledger_info_with_signatures(&self) -> Result<LedgerInfoWithSignatures> {
+        self.async_db
+            .ledger(|db| db.get_latest_ledger_info())
+            .await
+            .map_err(Into::into)
     }
 
-    pub fn get_state_value(&self, state_key: &StateKey, version: u64) -> Result<Option<Vec<u8>>> {
-        self.db
-            .state_view_at_version(Some(version))?
-            .get_state_value(state_key)
+    /// Returns the ledger info that ended `epoch`, the version it was committed at is the last
+    /// version of the epoch.
+    pub async fn get_epoch_ending_ledger_info(
+        &self,
+        epoch: u64,
+    ) -> Result<LedgerInfoWithSignatures> {
+        self.async_db
+            .ledger(move |db| {
+                db.get_epoch_ending_ledger_infos(epoch, epoch + 1)?
+                    .ledger_info_with_sigs
+                    .pop()
+                    .ok_or_else(|| {
+                        format_err!("epoch ending ledger info not found for epoch {}", epoch)
+                    })
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_state_value(
+        &self,
+        state_key: &StateKey,
+        version: u64,
+    ) -> Result<Option<Vec<u8>>> {
+        let state_key = state_key.clone();
+        self.async_db
+            .state(move |db| {
+                db.get_state_value_with_proof_by_version(&state_key, version)
+                    .map(|(value, _proof)| {
+                        // a value without bytes is a deleted one, deemed non-existent
+                        value.and_then(|value| value.maybe_bytes)
+                    })
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_first_write_version(
+        &self,
+        state_key: &StateKey,
+        version: u64,
+    ) -> Result<Option<u64>> {
+        let state_key = state_key.clone();
+        self.async_db
+            .state(move |db| db.get_first_write_version(&state_key, version))
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_creation_version(
+        &self,
+        state_key: &StateKey,
+        version: u64,
+    ) -> Result<Option<u64>> {
+        let state_key = state_key.clone();
+        self.async_db
+            .state(move |db| db.get_creation_version(&state_key, version))
+            .await
+            .map_err(Into::into)
     }
 
-    pub fn get_account_state(
+    pub async fn get_write_versions(
+        &self,
+        state_key: &StateKey,
+        start_version: u64,
+        limit: u16,
+        version: u64,
+    ) -> Result<Vec<u64>> {
+        let state_key = state_key.clone();
+        self.async_db
+            .state(move |db| {
+                db.get_write_versions(&state_key, start_version, limit as u64, version)
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_account_state(
         &self,
         address: AccountAddress,
         version: u64,
     ) -> Result<Option<AccountState>> {
-        AccountState::from_access_paths_and_values(
-            &self
-                .db
-                .get_state_values_by_key_prefix(&StateKeyPrefix::from(address), version)?,
-        )
+        self.async_db
+            .state(move |db| {
+                AccountState::from_access_paths_and_values(
+                    &db.get_state_values_by_key_prefix(&StateKeyPrefix::from(address), version)?,
+                )
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Batched version of `get_account_state`, returning the state of each address in order.
+    pub async fn get_account_states(
+        &self,
+        addresses: &[AccountAddress],
+        version: u64,
+    ) -> Result<Vec<Option<AccountState>>> {
+        let key_prefixes: Vec<StateKeyPrefix> = addresses
+            .iter()
+            .map(|address| StateKeyPrefix::from(*address))
+            .collect();
+        self.async_db
+            .state(move |db| {
+                db.get_state_values_by_key_prefixes(&key_prefixes, version)?
+                    .iter()
+                    .map(AccountState::from_access_paths_and_values)
+                    .collect()
+            })
+            .await
+            .map_err(Into::into)
     }
 
-    pub fn get_block_timestamp(&self, version: u64) -> Result<u64> {
-        self.db.get_block_timestamp(version)
+    pub async fn get_account_non_existence_proof(
+        &self,
+        address: AccountAddress,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<StateValueWithProof> {
+        self.async_db
+            .state(move |db| db.get_account_non_existence_proof(address, version, ledger_version))
+            .await
+            .map_err(Into::into)
     }
 
-    pub fn get_transactions(
+    pub async fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        self.async_db
+            .ledger(move |db| db.get_block_timestamp(version))
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_transactions(
         &self,
         start_version: u64,
         limit: u16,
         ledger_version: u64,
-    ) -> Result<Vec<TransactionOnChainData>> {
-        let data = self
-            .db
-            .get_transaction_outputs(start_version, limit as u64, ledger_version)?;
-
-        let txn_start_version = data
-            .first_transaction_output_version
-            .ok_or_else(|| format_err!("no start version from database"))?;
-        ensure!(
-            txn_start_version == start_version,
-            "invalid start version from database: {} != {}",
-            txn_start_version,
-            start_version
-        );
-
-        let infos = data.proof.transaction_infos;
-        let transactions_and_outputs = data.transactions_and_outputs;
-
-        ensure!(
-            transactions_and_outputs.len() == infos.len(),
-            "invalid data size from database: {}, {}",
-            transactions_and_outputs.len(),
-            infos.len(),
-        );
-
-        transactions_and_outputs
-            .into_iter()
-            .zip(infos.into_iter())
-            .enumerate()
-            .map(|(i, ((txn, txn_output), info))| {
-                let version = start_version + i as u64;
-                let (write_set, events, _, _) = txn_output.unpack();
-                self.get_accumulator_root_hash(version)
-                    .map(|h| (version, txn, info, events, h, write_set).into())
+    ) -> Result<Vec<TransactionOnChainData>, AptosDbError> {
+        self.async_db
+            .ledger(move |db| {
+                let data =
+                    db.get_transaction_outputs(start_version, limit as u64, ledger_version)?;
+
+                let txn_start_version = data
+                    .first_transaction_output_version
+                    .ok_or_else(|| format_err!("no start version from database"))?;
+                ensure!(
+                    txn_start_version == start_version,
+                    "invalid start version from database: {} != {}",
+                    txn_start_version,
+                    start_version
+                );
+
+                let infos = data.proof.transaction_infos;
+                let transactions_and_outputs = data.transactions_and_outputs;
+
+                ensure!(
+                    transactions_and_outputs.len() == infos.len(),
+                    "invalid data size from database: {}, {}",
+                    transactions_and_outputs.len(),
+                    infos.len(),
+                );
+
+                transactions_and_outputs
+                    .into_iter()
+                    .zip(infos.into_iter())
+                    .enumerate()
+                    .map(|(i, ((txn, txn_output), info))| {
+                        let version = start_version + i as u64;
+                        let (write_set, events, _, _) = txn_output.unpack();
+                        db.get_accumulator_root_hash(version)
+                            .map(|h| (version, txn, info, events, h, write_set).into())
+                    })
+                    .collect()
             })
-            .collect()
+            .await
     }
 
-    pub fn get_account_transactions(
+    pub async fn get_account_transactions(
         &self,
         address: AccountAddress,
         start_seq_number: u64,
         limit: u16,
         ledger_version: u64,
-    ) -> Result<Vec<TransactionOnChainData>> {
-        let txns = self.db.get_account_transactions(
-            address,
-            start_seq_number,
-            limit as u64,
-            true,
-            ledger_version,
-        )?;
-        txns.into_inner()
-            .into_iter()
-            .map(|t| self.convert_into_transaction_on_chain_data(t))
-            .collect::<Result<Vec<_>>>()
+    ) -> Result<Vec<TransactionOnChainData>, AptosDbError> {
+        self.async_db
+            .ledger(move |db| {
+                let txns = db.get_account_transactions(
+                    address,
+                    start_seq_number,
+                    limit as u64,
+                    true,
+                    ledger_version,
+                )?;
+                txns.into_inner()
+                    .into_iter()
+                    .map(|t| convert_into_transaction_on_chain_data(db, t))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .await
     }
 
-    pub fn get_transaction_by_hash(
+    pub async fn get_transaction_by_hash(
         &self,
         hash: HashValue,
         ledger_version: u64,
     ) -> Result<Option<TransactionOnChainData>> {
-        self.db
-            .get_transaction_by_hash(hash, ledger_version, true)?
-            .map(|t| self.convert_into_transaction_on_chain_data(t))
-            .transpose()
+        self.async_db
+            .ledger(move |db| {
+                db.get_transaction_by_hash(hash, ledger_version, true)?
+                    .map(|t| convert_into_transaction_on_chain_data(db, t))
+                    .transpose()
+            })
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_state_diff(
+        &self,
+        first_version: u64,
+        last_version: u64,
+    ) -> Result<Vec<(StateKey, WriteOp)>> {
+        self.async_db
+            .state(move |db| db.get_state_diff(first_version, last_version))
+            .await
+            .map_err(Into::into)
+    }
+
+    pub async fn get_block_gas_stats(
+        &self,
+        ledger_version: u64,
+        limit: u64,
+    ) -> Result<Vec<BlockGasStats>> {
+        self.async_db
+            .ledger(move |db| db.get_block_gas_stats(ledger_version, limit))
+            .await
+            .map_err(Into::into)
     }
 
     pub async fn get_pending_transaction_by_hash(
@@ -206,45 +549,156 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
-    pub fn get_transaction_by_version(
+    /// Returns the version at which the transaction with the given hash was committed, if any.
+    /// Recently seen hashes are served from memory, falling back to the storage index.
+    pub async fn get_committed_transaction_version(
+        &self,
+        hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<Option<u64>> {
+        if let Some(version) = self.committed_txns.get(&hash) {
+            return Ok(Some(version));
+        }
+        let version = self
+            .async_db
+            .ledger(move |db| {
+                Ok(db
+                    .get_transaction_by_hash(hash, ledger_version, false)?
+                    .map(|txn| txn.version))
+            })
+            .await?;
+        if let Some(version) = version {
+            self.committed_txns.insert(hash, version);
+        }
+        Ok(version)
+    }
+
+    /// Reserves `idempotency_key` for submitting the transaction with hash `txn_hash`, or returns
+    /// the submission already made with it.
+    pub(crate) fn reserve_submission(
+        &self,
+        idempotency_key: &str,
+        txn_hash: HashValue,
+    ) -> Reservation {
+        self.submissions.reserve(idempotency_key, txn_hash)
+    }
+
+    /// Takes a permit for an event stream, held until the stream is closed, unless
+    /// `api.max_event_streams` streams are open already.
+    pub(crate) fn try_acquire_event_stream(&self) -> Result<OwnedSemaphorePermit, Error> {
+        self.event_streams
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| Error::too_many_event_streams(self.max_event_streams))
+    }
+
+    pub async fn get_pending_sequence_numbers(&self, address: AccountAddress) -> Result<Vec<u64>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetPendingSequenceNumbers(
+                address, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    pub async fn get_mempool_utilization(&self) -> Result<MempoolUtilization> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetUtilization(req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    pub async fn get_transaction_by_version(
         &self,
         version: u64,
         ledger_version: u64,
     ) -> Result<TransactionOnChainData> {
-        self.convert_into_transaction_on_chain_data(self.db.get_transaction_by_version(
-            version,
-            ledger_version,
-            true,
-        )?)
+        self.async_db
+            .ledger(move |db| {
+                convert_into_transaction_on_chain_data(
+                    db,
+                    db.get_transaction_by_version(version, ledger_version, true)?,
+                )
+            })
+            .await
+            .map_err(Into::into)
     }
 
-    pub fn get_accumulator_root_hash(&self, version: u64) -> Result<HashValue> {
-        self.db.get_accumulator_root_hash(version)
+    pub async fn get_accumulator_root_hash(&self, version: u64) -> Result<HashValue> {
+        self.async_db
+            .ledger(move |db| db.get_accumulator_root_hash(version))
+            .await
+            .map_err(Into::into)
     }
 
-    fn convert_into_transaction_on_chain_data(
+    pub async fn get_events(
         &self,
-        txn: TransactionWithProof,
-    ) -> Result<TransactionOnChainData> {
-        // the type is Vec<(Transaction, TransactionOutput)> - given we have one transaction here, there should only ever be one value in this array
-        let (_, txn_output) = &self
-            .db
-            .get_transaction_outputs(txn.version, 1, txn.version)?
-            .transactions_and_outputs[0];
-        self.get_accumulator_root_hash(txn.version)
-            .map(|h| (txn, h, txn_output).into())
+        event_key: &EventKey,
+        start: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<ContractEvent>, AptosDbError> {
+        let event_key = *event_key;
+        let events = self
+            .async_db
+            .events(move |db| db.get_events(&event_key, start, Order::Ascending, limit as u64))
+            .await?;
+        Ok(events
+            .into_iter()
+            .filter(|(version, _event)| version <= &ledger_version)
+            .map(|(_, event)| event)
+            .collect::<Vec<_>>())
+    }
+
+    /// Returns up to `limit` events of type `struct_tag` starting at the `start_index`-th event of
+    /// the transaction at `start_version`, with the version and index of each event.
+    pub async fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: u64,
+        start_index: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(u64, u64, ContractEvent)>> {
+        let struct_tag = struct_tag.clone();
+        self.async_db
+            .events(move |db| {
+                db.get_events_by_type(
+                    &struct_tag,
+                    start_version,
+                    start_index,
+                    limit as u64,
+                    ledger_version,
+                )
+            })
+            .await
+            .map_err(Into::into)
     }
 
-    pub fn get_events(
+    /// Returns up to `limit` events of `event_key` with a sequence number of at most `start`,
+    /// newest first.
+    pub async fn get_events_descending(
         &self,
         event_key: &EventKey,
         start: u64,
         limit: u16,
         ledger_version: u64,
     ) -> Result<Vec<ContractEvent>> {
+        let event_key = *event_key;
         let events = self
-            .db
-            .get_events(event_key, start, Order::Ascending, limit as u64)?;
+            .async_db
+            .events(move |db| db.get_events(&event_key, start, Order::Descending, limit as u64))
+            .await?;
         Ok(events
             .into_iter()
             .filter(|(version, _event)| version <= &ledger_version)
@@ -253,6 +707,18 @@ impl Context {
     }
 
     pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
-        super::health_check::health_check_route(self.db.clone())
+        super::health_check::health_check_route(self.async_db.clone())
     }
 }
+
+fn convert_into_transaction_on_chain_data(
+    db: &dyn LedgerReader,
+    txn: TransactionWithProof,
+) -> Result<TransactionOnChainData> {
+    // the type is Vec<(Transaction, TransactionOutput)> - given we have one transaction here, there should only ever be one value in this array
+    let (_, txn_output) = &db
+        .get_transaction_outputs(txn.version, 1, txn.version)?
+        .transactions_and_outputs[0];
+    db.get_accumulator_root_hash(txn.version)
+        .map(|h| (txn, h, txn_output).into())
+}
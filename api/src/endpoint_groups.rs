@@ -0,0 +1,120 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::EndpointGroup;
+use warp::http::Method;
+
+/// Returns the group of the endpoint serving `method` and `path`, `None` for the endpoints that
+/// are always served and for unknown paths, which are left to the routes to reject.
+pub fn endpoint_group(method: &Method, path: &str) -> Option<EndpointGroup> {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    let group = match segments.as_slice() {
+        ["accounts", _, "non_existence_proof"] => EndpointGroup::StateProofs,
+        ["accounts", _, "transactions"] => EndpointGroup::Transactions,
+        ["accounts", _, "events", _, _, "stream"] => EndpointGroup::EventStreams,
+        ["accounts", _, "events", ..] => EndpointGroup::Events,
+        ["accounts", _, "resource", ..] => EndpointGroup::State,
+        ["accounts", ..] => EndpointGroup::Accounts,
+        ["transactions"] if *method == Method::POST => EndpointGroup::TransactionSubmission,
+        ["transactions", ..] => EndpointGroup::Transactions,
+        ["events", key, "stream"] if *key != "by_type" => EndpointGroup::EventStreams,
+        ["events", "by_type", _, "stream"] => EndpointGroup::EventStreams,
+        ["events", ..] => EndpointGroup::Events,
+        ["state" | "coins" | "tables" | "modules", ..]
+        | ["gas_schedule" | "script_function_allow_list" | "features" | "state_diff"] => {
+            EndpointGroup::State
+        }
+        ["estimate_gas_price"] => EndpointGroup::GasEstimation,
+        ["validators", ..] => EndpointGroup::Validators,
+        ["abort_codes", ..] => EndpointGroup::AbortCodes,
+        _ => return None,
+    };
+    Some(group)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::endpoint_group;
+    use aptos_config::config::EndpointGroup;
+    use warp::http::Method;
+
+    #[test]
+    fn test_endpoint_group() {
+        let cases = [
+            (Method::GET, "/", None),
+            (Method::GET, "/ledger_info", None),
+            (Method::GET, "/-/healthy", None),
+            (Method::GET, "/accounts/0x1", Some(EndpointGroup::Accounts)),
+            (
+                Method::POST,
+                "/accounts/resources/batch",
+                Some(EndpointGroup::Accounts),
+            ),
+            (
+                Method::GET,
+                "/accounts/0x1/non_existence_proof",
+                Some(EndpointGroup::StateProofs),
+            ),
+            (
+                Method::GET,
+                "/accounts/0x1/transactions",
+                Some(EndpointGroup::Transactions),
+            ),
+            (
+                Method::GET,
+                "/accounts/0x1/resource/0x1::Coin::CoinInfo",
+                Some(EndpointGroup::State),
+            ),
+            (
+                Method::GET,
+                "/transactions",
+                Some(EndpointGroup::Transactions),
+            ),
+            (
+                Method::POST,
+                "/transactions",
+                Some(EndpointGroup::TransactionSubmission),
+            ),
+            (
+                Method::POST,
+                "/transactions/signing_message",
+                Some(EndpointGroup::Transactions),
+            ),
+            (
+                Method::GET,
+                "/events/0x01/stream",
+                Some(EndpointGroup::EventStreams),
+            ),
+            (
+                Method::GET,
+                "/events/by_type/stream",
+                Some(EndpointGroup::Events),
+            ),
+            (
+                Method::GET,
+                "/events/by_type/0x1::Coin::DepositEvent/stream",
+                Some(EndpointGroup::EventStreams),
+            ),
+            (
+                Method::GET,
+                "/accounts/0x1/events/0x1::Block::BlockMetadata/new_block_events/stream",
+                Some(EndpointGroup::EventStreams),
+            ),
+            (
+                Method::GET,
+                "/accounts/0x1/events/0x1::Block::BlockMetadata/new_block_events",
+                Some(EndpointGroup::Events),
+            ),
+            (Method::GET, "/features", Some(EndpointGroup::State)),
+            (
+                Method::GET,
+                "/estimate_gas_price",
+                Some(EndpointGroup::GasEstimation),
+            ),
+            (Method::GET, "/invalid_path", None),
+        ];
+        for (method, path, group) in cases {
+            assert_eq!(endpoint_group(&method, path), group, "{} {}", method, path);
+        }
+    }
+}
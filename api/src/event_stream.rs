@@ -0,0 +1,366 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! WebSocket streams of events.
+//!
+//! A stream subscribes either to the events of an event key, given directly or as the event
+//! handle of an account, or to the events of a Move type across all event keys. A subscriber
+//! passes the position of the first event it wants: a sequence number for an event key, a ledger
+//! version and event index for a type. The events already committed are sent first, read from
+//! storage page by page, followed by a caught up message. New events are then sent as they are
+//! committed, so a client reconnecting from the position following the last event it received
+//! misses no event.
+//!
+//! The number of open streams is capped by `api.max_event_streams`, and storage is polled for new
+//! events at a fixed interval whatever the client sends.
+
+use crate::{
+    accounts::Account,
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    page::page_read_error,
+    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam, Param},
+};
+
+use aptos_api_types::{AsConverter, Error, EventStreamMessage, VersionedEvent};
+
+use anyhow::Result;
+use aptos_logger::warn;
+use aptos_types::event::EventKey;
+use futures::{Sink, SinkExt, StreamExt};
+use move_deps::move_core_types::language_storage::StructTag;
+use serde::Deserialize;
+use std::{convert::TryInto, time::Duration};
+use tokio::{sync::OwnedSemaphorePermit, time::MissedTickBehavior};
+use warp::{
+    filters::BoxedFilter,
+    ws::{Message, WebSocket, Ws},
+    Filter, Rejection, Reply,
+};
+
+/// Number of events read from storage at a time.
+const EVENT_PAGE_SIZE: u16 = 100;
+
+/// How often storage is checked for new events once a stream caught up.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// WebSocket close code for a stream aborted by an error of the server.
+const CLOSE_INTERNAL_ERROR: u16 = 1011;
+
+#[derive(Clone, Debug, Deserialize)]
+struct StreamParams {
+    start: Option<Param<u64>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct TypeStreamParams {
+    start: Option<Param<u64>>,
+    start_index: Option<Param<u64>>,
+}
+
+// GET /events/<event_key>/stream
+pub fn stream_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("events" / EventKeyParam / "stream")
+        .and(warp::get())
+        .and(warp::query::<StreamParams>())
+        .and(warp::ws())
+        .and(context.filter())
+        .and_then(handle_stream_events_by_event_key)
+        .with(metrics("stream_events_by_event_key"))
+        .boxed()
+}
+
+// GET /accounts/<address>/events/<event_handle_struct>/<field_name>/stream
+pub fn stream_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!(
+        "accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam / "stream"
+    )
+    .and(warp::get())
+    .and(warp::query::<StreamParams>())
+    .and(warp::ws())
+    .and(context.filter())
+    .and_then(handle_stream_events_by_event_handle)
+    .with(metrics("stream_events_by_event_handle"))
+    .boxed()
+}
+
+// GET /events/by_type/<event_type>/stream
+pub fn stream_events_by_type(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("events" / "by_type" / MoveStructTagParam / "stream")
+        .and(warp::get())
+        .and(warp::query::<TypeStreamParams>())
+        .and(warp::ws())
+        .and(context.filter())
+        .and_then(handle_stream_events_by_type)
+        .with(metrics("stream_events_by_type"))
+        .boxed()
+}
+
+async fn handle_stream_events_by_event_key(
+    event_key: EventKeyParam,
+    params: StreamParams,
+    ws: Ws,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events_by_event_key")?;
+    let key = event_key.parse("event key")?.into();
+    open_stream(ws, context, Subscription::key(key, params)?)
+}
+
+async fn handle_stream_events_by_event_handle(
+    address: AddressParam,
+    struct_tag: MoveStructTagParam,
+    field_name: MoveIdentifierParam,
+    params: StreamParams,
+    ws: Ws,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events_by_event_handle")?;
+    let key = Account::new(None, address, context.clone())
+        .await?
+        .find_event_key(struct_tag, field_name)
+        .await?;
+    open_stream(ws, context, Subscription::key(key, params)?)
+}
+
+async fn handle_stream_events_by_type(
+    event_type: MoveStructTagParam,
+    params: TypeStreamParams,
+    ws: Ws,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events_by_type")?;
+    let struct_tag: StructTag = event_type
+        .parse("event type")?
+        .try_into()
+        .map_err(|e| Error::invalid_param("event type", e))?;
+    let next_version = params
+        .start
+        .map(|start| start.parse("start"))
+        .transpose()?
+        .unwrap_or(0);
+    let next_index = params
+        .start_index
+        .map(|index| index.parse("start_index"))
+        .transpose()?
+        .unwrap_or(0);
+    let subscription = Subscription::Type {
+        struct_tag,
+        next_version,
+        next_index,
+    };
+    open_stream(ws, context, subscription)
+}
+
+fn open_stream(
+    ws: Ws,
+    context: Context,
+    subscription: Subscription,
+) -> Result<impl Reply, Rejection> {
+    // Taken before the upgrade, so that a client over the limit gets an HTTP error.
+    let permit = context.try_acquire_event_stream()?;
+    Ok(ws.on_upgrade(move |socket| {
+        EventStream {
+            subscription,
+            context,
+            _permit: permit,
+        }
+        .run(socket)
+    }))
+}
+
+/// The events a stream sends, along the position of the next one.
+#[derive(Debug)]
+enum Subscription {
+    /// The events of an event key.
+    Key {
+        key: EventKey,
+        next_sequence_number: u64,
+    },
+    /// The events of a Move type, across all event keys. The next event is the one at
+    /// `next_index` among the events of the type emitted by the transaction at `next_version`,
+    /// or the first one of a later transaction.
+    Type {
+        struct_tag: StructTag,
+        next_version: u64,
+        next_index: u64,
+    },
+}
+
+impl Subscription {
+    fn key(key: EventKey, params: StreamParams) -> Result<Self, Error> {
+        let next_sequence_number = params
+            .start
+            .map(|start| start.parse("start"))
+            .transpose()?
+            .unwrap_or(0);
+        Ok(Subscription::Key {
+            key,
+            next_sequence_number,
+        })
+    }
+}
+
+struct EventStream {
+    subscription: Subscription,
+    context: Context,
+    // released once the stream is closed
+    _permit: OwnedSemaphorePermit,
+}
+
+impl EventStream {
+    async fn run(mut self, socket: WebSocket) {
+        let (mut sender, mut receiver) = socket.split();
+        let mut poll_interval = tokio::time::interval(POLL_INTERVAL);
+        poll_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut caught_up = false;
+        loop {
+            let (messages, page_full) = match self.next_messages().await {
+                Ok(next_messages) => next_messages,
+                Err(error) => {
+                    warn!(
+                        subscription = ?self.subscription,
+                        error = ?error,
+                        "Closing event stream after failing to read events"
+                    );
+                    let _ = sender
+                        .send(Message::close_with(CLOSE_INTERNAL_ERROR, "internal error"))
+                        .await;
+                    return;
+                }
+            };
+            for message in messages {
+                if send(&mut sender, &message).await.is_err() {
+                    return;
+                }
+            }
+            if page_full {
+                continue;
+            }
+            if !caught_up {
+                if send(&mut sender, &self.caught_up_message()).await.is_err() {
+                    return;
+                }
+                caught_up = true;
+            }
+
+            // Frames of the client, e.g. pings, are discarded without polling storage earlier.
+            loop {
+                tokio::select! {
+                    _ = poll_interval.tick() => break,
+                    message = receiver.next() => match message {
+                        Some(Ok(message)) if !message.is_close() => {},
+                        _ => return,
+                    },
+                }
+            }
+        }
+    }
+
+    /// Returns the messages of the next page of events, and whether the page is full, i.e. more
+    /// events may be committed already.
+    async fn next_messages(&mut self) -> Result<(Vec<EventStreamMessage>, bool), Error> {
+        let ledger_version = self.context.get_latest_ledger_info().await?.version();
+        // the versions and indices of the events of a stream by type
+        let (positions, contract_events) = match &mut self.subscription {
+            Subscription::Key {
+                key,
+                next_sequence_number,
+            } => {
+                let contract_events = self
+                    .context
+                    .get_events(key, *next_sequence_number, EVENT_PAGE_SIZE, ledger_version)
+                    .await
+                    .map_err(page_read_error)?;
+                if let Some(last) = contract_events.last() {
+                    *next_sequence_number = last.sequence_number() + 1;
+                }
+                (None, contract_events)
+            }
+            Subscription::Type {
+                struct_tag,
+                next_version,
+                next_index,
+            } => {
+                let events = self
+                    .context
+                    .get_events_by_type(
+                        struct_tag,
+                        *next_version,
+                        *next_index,
+                        EVENT_PAGE_SIZE,
+                        ledger_version,
+                    )
+                    .await?;
+                match events.last() {
+                    Some((version, event_index, _)) if events.len() == EVENT_PAGE_SIZE as usize => {
+                        *next_version = *version;
+                        *next_index = event_index + 1;
+                    }
+                    // All the events up to the ledger version have been read, the following
+                    // polls start from the next transaction.
+                    _ => {
+                        *next_version = (*next_version).max(ledger_version + 1);
+                        *next_index = 0;
+                    }
+                }
+                let (positions, contract_events) = events
+                    .into_iter()
+                    .map(|(version, event_index, event)| ((version, event_index), event))
+                    .unzip::<_, _, Vec<_>, Vec<_>>();
+                (Some(positions), contract_events)
+            }
+        };
+        let page_full = contract_events.len() == EVENT_PAGE_SIZE as usize;
+
+        let resolver = self.context.move_resolver()?;
+        let events = resolver
+            .as_converter()
+            .with_layout_cache(self.context.layout_cache(ledger_version)?)
+            .try_into_events(&contract_events)?;
+        let messages = match positions {
+            None => events
+                .into_iter()
+                .map(|event| EventStreamMessage::Event { event })
+                .collect(),
+            Some(positions) => positions
+                .into_iter()
+                .zip(events)
+                .map(
+                    |((version, event_index), event)| EventStreamMessage::VersionedEvent {
+                        event: VersionedEvent {
+                            version: version.into(),
+                            event_index: event_index.into(),
+                            event,
+                        },
+                    },
+                )
+                .collect(),
+        };
+        Ok((messages, page_full))
+    }
+
+    fn caught_up_message(&self) -> EventStreamMessage {
+        match &self.subscription {
+            Subscription::Key {
+                next_sequence_number,
+                ..
+            } => EventStreamMessage::CaughtUp {
+                sequence_number: (*next_sequence_number).into(),
+            },
+            Subscription::Type { next_version, .. } => EventStreamMessage::CaughtUpToVersion {
+                version: next_version.saturating_sub(1).into(),
+            },
+        }
+    }
+}
+
+async fn send(
+    sender: &mut (impl Sink<Message, Error = warp::Error> + Unpin),
+    message: &EventStreamMessage,
+) -> Result<()> {
+    let text = serde_json::to_string(message)?;
+    sender.send(Message::text(text)).await?;
+    Ok(())
+}
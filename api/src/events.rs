@@ -6,16 +6,38 @@ use crate::{
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
-    page::Page,
-    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam},
+    page::{page_read_error, Page},
+    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam, Param},
 };
 
-use aptos_api_types::{AsConverter, Error, LedgerInfo, Response};
+use aptos_api_types::{AsConverter, Error, LedgerInfo, Response, VersionedEvent};
 
 use anyhow::Result;
 use aptos_types::event::EventKey;
+use move_deps::move_core_types::language_storage::StructTag;
+use serde::Deserialize;
+use std::convert::TryInto;
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
+/// Position of the first event of a page of events by type within the transaction at the page's
+/// `start` version.
+#[derive(Clone, Debug, Deserialize)]
+struct EventIndex {
+    start_index: Option<Param<u64>>,
+}
+
+// GET /events/by_type/<event_type>
+pub fn get_events_by_type(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("events" / "by_type" / MoveStructTagParam)
+        .and(warp::get())
+        .and(warp::query::<Page>())
+        .and(warp::query::<EventIndex>())
+        .and(context.filter())
+        .and_then(handle_get_events_by_type)
+        .with(metrics("get_events_by_type"))
+        .boxed()
+}
+
 // GET /events/<event_key>
 pub fn get_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("events" / EventKeyParam)
@@ -44,7 +66,10 @@ async fn handle_get_events_by_event_key(
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_key")?;
-    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page)?)
+    Ok(Events::new(event_key.parse("event key")?.into(), context)
+        .await?
+        .list(page)
+        .await?)
 }
 
 async fn handle_get_events_by_event_handle(
@@ -55,9 +80,33 @@ async fn handle_get_events_by_event_handle(
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_handle")?;
-    let key =
-        Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
-    Ok(Events::new(key, context)?.list(page)?)
+    let key = Account::new(None, address, context.clone())
+        .await?
+        .find_event_key(struct_tag, field_name)
+        .await?;
+    Ok(Events::new(key, context).await?.list(page).await?)
+}
+
+async fn handle_get_events_by_type(
+    event_type: MoveStructTagParam,
+    page: Page,
+    event_index: EventIndex,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_events_by_type")?;
+    let struct_tag: StructTag = event_type
+        .parse("event type")?
+        .try_into()
+        .map_err(|e| Error::invalid_param("event type", e))?;
+    let start_index = event_index
+        .start_index
+        .map(|index| index.parse("start_index"))
+        .transpose()?
+        .unwrap_or(0);
+    Ok(EventsByType::new(struct_tag, context)
+        .await?
+        .list(page, start_index)
+        .await?)
 }
 
 struct Events {
@@ -67,8 +116,8 @@ struct Events {
 }
 
 impl Events {
-    fn new(key: EventKey, context: Context) -> Result<Self, Error> {
-        let ledger_info = context.get_latest_ledger_info()?;
+    async fn new(key: EventKey, context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info().await?;
         Ok(Self {
             key,
             ledger_info,
@@ -76,16 +125,75 @@ impl Events {
         })
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
-        let contract_events = self.context.get_events(
-            &self.key,
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
-        )?;
+    pub async fn list(self, page: Page) -> Result<impl Reply, Error> {
+        let start = page.start(0, u64::MAX)?;
+        let limit = page.limit(self.context.api_config().max_events_page_size)?;
+        let contract_events = self
+            .context
+            .get_events(&self.key, start, limit, self.ledger_info.version())
+            .await
+            .map_err(page_read_error)?;
+        let cursor = Page::next_start(start, contract_events.len(), limit, None);
+
+        let resolver = self.context.move_resolver()?;
+        let events = resolver
+            .as_converter()
+            .with_layout_cache(self.context.layout_cache(self.ledger_info.version())?)
+            .try_into_events(&contract_events)?;
+        Ok(Response::new(self.ledger_info, &events)?.with_cursor(cursor))
+    }
+}
+
+struct EventsByType {
+    struct_tag: StructTag,
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl EventsByType {
+    async fn new(struct_tag: StructTag, context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info().await?;
+        Ok(Self {
+            struct_tag,
+            ledger_info,
+            context,
+        })
+    }
+
+    /// Events of pruned transactions are skipped, a client pages through the whole feed by
+    /// requesting the next page with `start` set to the version of the last event received and
+    /// `start_index` to its index plus one.
+    pub async fn list(self, page: Page, start_index: u64) -> Result<impl Reply, Error> {
+        let ledger_version = self.ledger_info.version();
+        let events = self
+            .context
+            .get_events_by_type(
+                &self.struct_tag,
+                page.start(0, ledger_version)?,
+                start_index,
+                page.limit(self.context.api_config().max_events_page_size)?,
+                ledger_version,
+            )
+            .await?;
 
         let resolver = self.context.move_resolver()?;
-        let events = resolver.as_converter().try_into_events(&contract_events)?;
-        Response::new(self.ledger_info, &events)
+        let contract_events = events
+            .iter()
+            .map(|(_, _, event)| event.clone())
+            .collect::<Vec<_>>();
+        let converted = resolver
+            .as_converter()
+            .with_layout_cache(self.context.layout_cache(ledger_version)?)
+            .try_into_events(&contract_events)?;
+        let versioned_events = events
+            .into_iter()
+            .zip(converted)
+            .map(|((version, event_index, _), event)| VersionedEvent {
+                version: version.into(),
+                event_index: event_index.into(),
+                event,
+            })
+            .collect::<Vec<_>>();
+        Response::new(self.ledger_info, &versioned_events)
     }
 }
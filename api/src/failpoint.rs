@@ -1,14 +1,70 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-#[allow(unused_imports)]
-use anyhow::{format_err, Result};
+//! Fail injection for the endpoints. Failpoints are only compiled with the `failpoints` feature:
+//! without it `fail_point` is a no-op and no failpoint is registered, so production builds have
+//! no fail-injection branch.
+
 use aptos_api_types::Error;
 
-#[allow(unused_variables)]
-#[inline]
+/// Failpoints evaluated by the endpoints, see `fail_point`.
+#[cfg(feature = "failpoints")]
+pub const FAILPOINTS: &[&str] = &[
+    "api::endpoint_index",
+    "api::endpoint_get_ledger_info",
+    "api::endpoint_get_account",
+    "api::endpoint_get_account_sequence_numbers",
+    "api::endpoint_get_account_resources",
+    "api::endpoint_get_account_resources_batch",
+    "api::endpoint_get_account_modules",
+    "api::endpoint_get_account_activity",
+    "api::endpoint_get_account_stake_pool",
+    "api::endpoint_get_account_tokens",
+    "api::endpoint_get_account_event_handles",
+    "api::endpoint_get_account_non_existence_proof",
+    "api::endpoint_get_events_by_event_key",
+    "api::endpoint_get_events_by_type",
+    "api::endpoint_get_events_by_event_handle",
+    "api::endpoint_stream_events_by_event_key",
+    "api::endpoint_stream_events_by_event_handle",
+    "api::endpoint_stream_events_by_type",
+    "api::endpoint_estimate_gas_price",
+    "api::endpoint_query_resource",
+    "api::endpoint_get_account_module",
+    "api::endpoint_get_coin_info",
+    "api::endpoint_get_coin_supply",
+    "api::endpoint_get_table_item",
+    "api::endpoint_get_gas_schedule",
+    "api::endpoint_get_script_function_allow_list",
+    "api::endpoint_get_features",
+    "api::endpoint_get_state_diff",
+    "api::endpoint_get_module_history",
+    "api::endpoint_verify_module",
+    "api::endpoint_get_transaction",
+    "api::endpoint_trace_transaction",
+    "api::endpoint_get_transactions",
+    "api::endpoint_get_account_transactions",
+    "api::endpoint_submit_json_transactions",
+    "api::endpoint_submit_bcs_transactions",
+    "api::endpoint_create_signing_message",
+    "api::endpoint_authenticate_json_transaction",
+    "api::endpoint_authenticate_bcs_transaction",
+    "api::endpoint_get_validators",
+    "api::endpoint_get_validator_stats",
+    "api::endpoint_get_abort_code",
+];
+
+/// Fails with an internal error when the failpoint `api::{name}` is active.
+#[cfg(feature = "failpoints")]
 pub fn fail_point(name: &str) -> Result<(), Error> {
-    Ok(fail::fail_point!(format!("api::{}", name).as_str(), |_| {
-        Err(format_err!("unexpected internal error for {}", name).into())
-    }))
+    aptos_failpoints::fail_point!(format!("api::{}", name).as_str(), |_| {
+        Err(anyhow::format_err!("unexpected internal error for {}", name).into())
+    });
+    Ok(())
+}
+
+#[cfg(not(feature = "failpoints"))]
+#[inline(always)]
+pub fn fail_point(_name: &str) -> Result<(), Error> {
+    Ok(())
 }
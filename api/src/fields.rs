@@ -0,0 +1,113 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{Error, Response};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// The `fields` query param: a comma separated list of the fields to keep in the response, e.g.
+/// `?fields=hash,success,timestamp`, so that clients only needing a few fields don't download
+/// whole transactions. Nested fields are selected with dots, e.g. `payload.function`. The fields
+/// are selected in each item of a list, and missing fields are left out.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Fields {
+    fields: Option<String>,
+}
+
+impl Fields {
+    pub fn apply(&self, response: Response) -> Result<Response, Error> {
+        let fields = match &self.fields {
+            Some(fields) => fields,
+            None => return Ok(response),
+        };
+        let paths = fields
+            .split(',')
+            .map(str::trim)
+            .map(|field| field.split('.').collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        if paths
+            .iter()
+            .any(|path| path.iter().any(|name| name.is_empty()))
+        {
+            return Err(Error::invalid_param("fields", fields));
+        }
+        let paths = paths.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        let body: Value = serde_json::from_slice(&response.body)?;
+        Ok(Response {
+            body: serde_json::to_vec(&project(body, &paths))?,
+            ..response
+        })
+    }
+}
+
+/// Keeps the fields of `value` selected by `paths`, in the order they are requested.
+fn project(value: Value, paths: &[&[&str]]) -> Value {
+    match value {
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(|item| project(item, paths)).collect())
+        }
+        Value::Object(mut object) => {
+            let mut projected = Map::new();
+            for (name, _) in paths.iter().filter_map(|path| path.split_first()) {
+                if projected.contains_key(*name) {
+                    continue;
+                }
+                let field = match object.remove(*name) {
+                    Some(field) => field,
+                    None => continue,
+                };
+                let nested_paths = paths
+                    .iter()
+                    .filter_map(|path| path.split_first())
+                    .filter(|(first, _)| first == name)
+                    .map(|(_, rest)| rest)
+                    .collect::<Vec<_>>();
+                let field = if nested_paths.iter().any(|rest| rest.is_empty()) {
+                    field
+                } else {
+                    project(field, &nested_paths)
+                };
+                projected.insert(name.to_string(), field);
+            }
+            Value::Object(projected)
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::project;
+    use serde_json::json;
+
+    #[test]
+    fn test_project_fields() {
+        let txn = json!({
+            "hash": "0x1",
+            "success": true,
+            "payload": {"function": "0x1::Coin::transfer", "arguments": ["0x2", "1"]},
+            "events": [{"key": "0x3", "data": {}}, {"key": "0x4", "data": {}}],
+        });
+        assert_eq!(
+            project(txn.clone(), &[&["success"], &["hash"], &["missing"]]),
+            json!({"success": true, "hash": "0x1"})
+        );
+        assert_eq!(
+            project(
+                json!([txn.clone(), txn.clone()]),
+                &[&["payload", "function"], &["events", "key"]]
+            ),
+            json!([
+                {"payload": {"function": "0x1::Coin::transfer"}, "events": [{"key": "0x3"}, {"key": "0x4"}]},
+                {"payload": {"function": "0x1::Coin::transfer"}, "events": [{"key": "0x3"}, {"key": "0x4"}]},
+            ])
+        );
+        // a whole field wins over its nested fields
+        assert_eq!(
+            project(txn, &[&["payload", "function"], &["payload"]])["payload"]["arguments"],
+            json!(["0x2", "1"])
+        );
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, failpoint::fail_point, metrics::metrics};
+
+use aptos_api_types::{Error, GasEstimation, LedgerInfo, Response};
+
+use aptos_config::config::GasPriceFloorStep;
+use aptos_mempool::MempoolUtilization;
+use aptos_types::{
+    on_chain_config::{access_path_for_config, GasSchedule, OnChainConfig},
+    state_store::state_key::StateKey,
+};
+use move_deps::move_core_types::gas_schedule::GasAlgebra;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// Maximum number of recent blocks the estimation looks at.
+const MAX_BLOCKS: u64 = 100;
+/// Only blocks within this window before the latest block are considered, so that the estimate
+/// follows the current load rather than the load of the last busy period.
+const WINDOW_USECS: u64 = 60 * 1_000_000;
+
+const DEPRIORITIZED_PERCENTILE: usize = 25;
+const NORMAL_PERCENTILE: usize = 50;
+const PRIORITIZED_PERCENTILE: usize = 90;
+
+// GET /estimate_gas_price
+pub fn estimate_gas_price(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("estimate_gas_price")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_estimate_gas_price)
+        .with(metrics("estimate_gas_price"))
+        .boxed()
+}
+
+async fn handle_estimate_gas_price(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_estimate_gas_price")?;
+    let gas_price_floor = gas_price_floor(&context).await?;
+    Ok(GasEstimator::new(context)
+        .await?
+        .estimate(gas_price_floor)
+        .await?)
+}
+
+/// Returns the minimum gas unit price of submitted transactions given the current mempool
+/// utilization, see `ApiConfig::gas_price_floor`.
+pub(crate) async fn gas_price_floor(context: &Context) -> Result<u64, Error> {
+    let steps = context.api_config().gas_price_floor;
+    if steps.is_empty() {
+        return Ok(0);
+    }
+    let utilization = context.get_mempool_utilization().await?;
+    Ok(gas_price_floor_at(&steps, utilization))
+}
+
+/// The price of the highest step reached by `utilization`, 0 if none is.
+fn gas_price_floor_at(steps: &[GasPriceFloorStep], utilization: MempoolUtilization) -> u64 {
+    let percent = match utilization.capacity {
+        0 => 100,
+        capacity => (utilization.size * 100 / capacity) as u64,
+    };
+    steps
+        .iter()
+        .filter(|step| percent >= step.mempool_utilization_percent)
+        .max_by_key(|step| step.mempool_utilization_percent)
+        .map_or(0, |step| step.min_gas_unit_price)
+}
+
+struct GasEstimator {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl GasEstimator {
+    async fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info().await?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    pub async fn estimate(self, gas_price_floor: u64) -> Result<impl Reply, Error> {
+        let ledger_version = self.ledger_info.version();
+        let blocks = self
+            .context
+            .get_block_gas_stats(ledger_version, MAX_BLOCKS)
+            .await?;
+        // blocks are ordered from the latest
+        let mut prices: Vec<u64> = match blocks.first() {
+            Some(latest) => {
+                let since = latest.timestamp_usecs.saturating_sub(WINDOW_USECS);
+                blocks
+                    .iter()
+                    .take_while(|block| block.timestamp_usecs >= since)
+                    .flat_map(|block| block.gas_unit_prices.iter().copied())
+                    .collect()
+            }
+            None => vec![],
+        };
+        prices.sort_unstable();
+
+        let min_price = self
+            .min_price_per_gas_unit(ledger_version)
+            .await?
+            .max(gas_price_floor);
+        let estimate = |percentile| percentile_price(&prices, percentile).max(min_price);
+        Response::new(
+            self.ledger_info,
+            &GasEstimation {
+                deprioritized_gas_estimate: estimate(DEPRIORITIZED_PERCENTILE).into(),
+                gas_estimate: estimate(NORMAL_PERCENTILE).into(),
+                prioritized_gas_estimate: estimate(PRIORITIZED_PERCENTILE).into(),
+                gas_price_floor: gas_price_floor.into(),
+            },
+        )
+    }
+
+    async fn min_price_per_gas_unit(&self, ledger_version: u64) -> Result<u64, Error> {
+        let state_key = StateKey::AccessPath(access_path_for_config(GasSchedule::CONFIG_ID));
+        let bytes = self
+            .context
+            .get_state_value(&state_key, ledger_version)
+            .await?
+            .ok_or_else(|| {
+                Error::not_found("Gas schedule", GasSchedule::CONFIG_ID, ledger_version)
+            })?;
+        let gas_schedule = GasSchedule::deserialize_into_config(&bytes).map_err(Error::internal)?;
+        Ok(gas_schedule
+            .cost_table
+            .gas_constants
+            .min_price_per_gas_unit
+            .get())
+    }
+}
+
+/// Nearest-rank percentile of sorted `prices`, 0 if there are none.
+fn percentile_price(prices: &[u64], percentile: usize) -> u64 {
+    if prices.is_empty() {
+        return 0;
+    }
+    let rank = (prices.len() * percentile + 99) / 100;
+    prices[rank.saturating_sub(1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gas_price_floor_at, percentile_price};
+    use aptos_config::config::GasPriceFloorStep;
+    use aptos_mempool::MempoolUtilization;
+
+    #[test]
+    fn test_percentile_price() {
+        assert_eq!(percentile_price(&[], 50), 0);
+        assert_eq!(percentile_price(&[7], 25), 7);
+        assert_eq!(percentile_price(&[7], 90), 7);
+
+        let prices: Vec<u64> = (1..=10).collect();
+        assert_eq!(percentile_price(&prices, 25), 3);
+        assert_eq!(percentile_price(&prices, 50), 5);
+        assert_eq!(percentile_price(&prices, 90), 9);
+        assert_eq!(percentile_price(&prices, 100), 10);
+    }
+
+    #[test]
+    fn test_gas_price_floor_at() {
+        let steps = vec![
+            GasPriceFloorStep {
+                mempool_utilization_percent: 90,
+                min_gas_unit_price: 100,
+            },
+            GasPriceFloorStep {
+                mempool_utilization_percent: 50,
+                min_gas_unit_price: 10,
+            },
+        ];
+        let floor =
+            |size, capacity| gas_price_floor_at(&steps, MempoolUtilization { size, capacity });
+        assert_eq!(floor(0, 1000), 0);
+        assert_eq!(floor(499, 1000), 0);
+        assert_eq!(floor(500, 1000), 10);
+        assert_eq!(floor(899, 1000), 10);
+        assert_eq!(floor(900, 1000), 100);
+        assert_eq!(floor(1000, 1000), 100);
+        assert_eq!(floor(0, 0), 100);
+        assert_eq!(
+            gas_price_floor_at(
+                &[],
+                MempoolUtilization {
+                    size: 1,
+                    capacity: 1
+                }
+            ),
+            0
+        );
+    }
+}
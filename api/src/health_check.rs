@@ -4,10 +4,9 @@
 use anyhow::{ensure, Result};
 use std::{
     ops::Sub,
-    sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use storage_interface::DbReader;
+use storage_interface::async_reader::AsyncDbReader;
 use warp::{filters::BoxedFilter, reject, Filter, Reply};
 
 // HealthCheckParams is optional params for different layer's health check.
@@ -23,7 +22,7 @@ struct HealthCheckParams {
 struct HealthCheckError;
 impl reject::Reject for HealthCheckError {}
 
-pub fn health_check_route(health_aptos_db: Arc<dyn DbReader>) -> BoxedFilter<(impl Reply,)> {
+pub fn health_check_route(health_aptos_db: AsyncDbReader) -> BoxedFilter<(impl Reply,)> {
     warp::path!("-" / "healthy")
         .and(warp::path::end())
         .and(warp::query().map(move |params: HealthCheckParams| params))
@@ -35,12 +34,13 @@ pub fn health_check_route(health_aptos_db: Arc<dyn DbReader>) -> BoxedFilter<(im
 
 async fn health_check(
     params: HealthCheckParams,
-    db: Arc<dyn DbReader>,
+    db: AsyncDbReader,
     now: SystemTime,
 ) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
     if let Some(duration) = params.duration_secs {
         let ledger_info = db
-            .get_latest_ledger_info()
+            .ledger(|db| db.get_latest_ledger_info())
+            .await
             .map_err(|_| reject::custom(HealthCheckError))?;
         let timestamp = ledger_info.ledger_info().timestamp_usecs();
 
@@ -0,0 +1,295 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::Error;
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+/// Request header carrying the client chosen key of a transaction submission.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Maximum length of an idempotency key.
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// Bounded cache of the results of transaction submissions, keyed by idempotency key.
+///
+/// Clients retrying a submission after a timeout send the same key again and get the result of
+/// the original submission instead of submitting the transaction a second time. A key is reserved
+/// before its transaction is submitted, so that a retry racing the original request waits for its
+/// result rather than submitting concurrently. Unlike `CommittedTransactionCache` there is no
+/// persistent source of truth behind it: once an entry is evicted or the node restarts, a retry is
+/// submitted like a new request.
+pub(crate) struct IdempotencyCache {
+    capacity: usize,
+    ttl: Duration,
+    inner: Mutex<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: HashMap<String, Entry>,
+    // insertion order along the id of each entry, used for evicting the oldest entries once the
+    // capacity is reached
+    order: VecDeque<(String, u64)>,
+    next_id: u64,
+}
+
+struct Entry {
+    id: u64,
+    txn_hash: HashValue,
+    state: EntryState,
+}
+
+enum EntryState {
+    /// The transaction is being submitted, the receiver is notified once the reservation is
+    /// released.
+    InFlight(watch::Receiver<()>),
+    Done(Result<Vec<u8>, Error>, Instant),
+}
+
+/// The outcome of reserving an idempotency key.
+pub(crate) enum Reservation {
+    /// The key is new, the caller submits the transaction and completes the reservation.
+    Reserved(ReservedKey),
+    /// The transaction is being submitted by another request. The receiver is notified once that
+    /// request is done, after which the key can be reserved again.
+    InFlight(watch::Receiver<()>),
+    /// The result of the submission of the same transaction, i.e. the JSON encoded pending
+    /// transaction or the error the submission failed with.
+    Done(Result<Vec<u8>, Error>),
+    /// The key was used for another transaction.
+    Reused,
+}
+
+/// A reserved idempotency key. Dropping it without calling `complete` releases the key, e.g. after
+/// an internal error or when the request is cancelled, so that the next request submits the
+/// transaction again.
+pub(crate) struct ReservedKey {
+    cache: Arc<IdempotencyCache>,
+    key: String,
+    id: u64,
+    // dropped along the reservation, which wakes up the requests waiting on it
+    _released: watch::Sender<()>,
+}
+
+impl IdempotencyCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Reserves `key` for submitting the transaction with hash `txn_hash`, unless the key is
+    /// already in use.
+    pub fn reserve(self: &Arc<Self>, key: &str, txn_hash: HashValue) -> Reservation {
+        let (released, receiver) = watch::channel(());
+        if self.capacity == 0 {
+            // nothing is cached, every request submits its transaction
+            return Reservation::Reserved(ReservedKey {
+                cache: self.clone(),
+                key: key.to_owned(),
+                id: u64::MAX,
+                _released: released,
+            });
+        }
+
+        let mut inner = self.inner.lock();
+        if let Some(entry) = inner.entries.get(key) {
+            let expired = matches!(&entry.state, EntryState::Done(_, done_at) if done_at.elapsed() >= self.ttl);
+            if !expired {
+                if entry.txn_hash != txn_hash {
+                    return Reservation::Reused;
+                }
+                return match &entry.state {
+                    EntryState::InFlight(receiver) => Reservation::InFlight(receiver.clone()),
+                    EntryState::Done(result, _) => Reservation::Done(result.clone()),
+                };
+            }
+        }
+
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.entries.insert(
+            key.to_owned(),
+            Entry {
+                id,
+                txn_hash,
+                state: EntryState::InFlight(receiver),
+            },
+        );
+        inner.order.push_back((key.to_owned(), id));
+        while inner.order.len() > self.capacity {
+            if let Some((oldest, oldest_id)) = inner.order.pop_front() {
+                if inner.entries.get(&oldest).map(|entry| entry.id) == Some(oldest_id) {
+                    inner.entries.remove(&oldest);
+                }
+            }
+        }
+        Reservation::Reserved(ReservedKey {
+            cache: self.clone(),
+            key: key.to_owned(),
+            id,
+            _released: released,
+        })
+    }
+
+    fn complete(&self, key: &str, id: u64, result: Result<Vec<u8>, Error>) {
+        let mut inner = self.inner.lock();
+        if let Some(entry) = inner.entries.get_mut(key) {
+            if entry.id == id {
+                entry.state = EntryState::Done(result, Instant::now());
+            }
+        }
+    }
+
+    fn release(&self, key: &str, id: u64) {
+        let mut inner = self.inner.lock();
+        if matches!(
+            inner.entries.get(key),
+            Some(entry) if entry.id == id && matches!(entry.state, EntryState::InFlight(_))
+        ) {
+            inner.entries.remove(key);
+        }
+    }
+}
+
+impl ReservedKey {
+    /// Records the result of the submission, which is replayed to the requests with the same key
+    /// until it expires.
+    pub fn complete(self, result: Result<Vec<u8>, Error>) {
+        self.cache.complete(&self.key, self.id, result);
+    }
+}
+
+impl Drop for ReservedKey {
+    fn drop(&mut self) {
+        self.cache.release(&self.key, self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdempotencyCache, Reservation};
+    use aptos_api_types::Error;
+    use aptos_crypto::HashValue;
+    use std::{sync::Arc, time::Duration};
+
+    fn complete(
+        cache: &IdempotencyCache,
+        key: &str,
+        hash: HashValue,
+        result: Result<Vec<u8>, Error>,
+    ) {
+        match cache.reserve(key, hash) {
+            Reservation::Reserved(reserved) => reserved.complete(result),
+            _ => panic!("key {} should be free", key),
+        }
+    }
+
+    fn replayed(
+        cache: &IdempotencyCache,
+        key: &str,
+        hash: HashValue,
+    ) -> Option<Result<Vec<u8>, Error>> {
+        match cache.reserve(key, hash) {
+            Reservation::Done(result) => Some(result),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_reserve_and_complete() {
+        let cache = Arc::new(IdempotencyCache::new(10, Duration::from_secs(60)));
+        let hash = HashValue::random();
+        complete(&cache, "key", hash, Ok(b"pending".to_vec()));
+        assert_eq!(replayed(&cache, "key", hash), Some(Ok(b"pending".to_vec())));
+        assert!(matches!(
+            cache.reserve("key", HashValue::random()),
+            Reservation::Reused
+        ));
+
+        let other = HashValue::random();
+        complete(&cache, "other", other, Err(Error::bad_request("rejected")));
+        assert_eq!(
+            replayed(&cache, "other", other),
+            Some(Err(Error::bad_request("rejected")))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_key_is_waited_on() {
+        let cache = Arc::new(IdempotencyCache::new(10, Duration::from_secs(60)));
+        let hash = HashValue::random();
+        let reserved = match cache.reserve("key", hash) {
+            Reservation::Reserved(reserved) => reserved,
+            _ => panic!("key should be free"),
+        };
+        let mut receiver = match cache.reserve("key", hash) {
+            Reservation::InFlight(receiver) => receiver,
+            _ => panic!("key should be in flight"),
+        };
+        assert!(matches!(
+            cache.reserve("key", HashValue::random()),
+            Reservation::Reused
+        ));
+
+        reserved.complete(Ok(b"pending".to_vec()));
+        // the sender is dropped along the reservation
+        assert!(receiver.changed().await.is_err());
+        assert_eq!(replayed(&cache, "key", hash), Some(Ok(b"pending".to_vec())));
+    }
+
+    #[test]
+    fn test_dropped_reservation_releases_key() {
+        let cache = Arc::new(IdempotencyCache::new(10, Duration::from_secs(60)));
+        let hash = HashValue::random();
+        drop(cache.reserve("key", hash));
+        assert!(matches!(
+            cache.reserve("key", HashValue::random()),
+            Reservation::Reserved(_)
+        ));
+    }
+
+    #[test]
+    fn test_evict_oldest_entry_when_full() {
+        let cache = Arc::new(IdempotencyCache::new(2, Duration::from_secs(60)));
+        let hashes: Vec<_> = (0..3).map(|_| HashValue::random()).collect();
+        for (key, hash) in ["a", "b", "c"].iter().zip(&hashes) {
+            complete(&cache, key, *hash, Ok(key.as_bytes().to_vec()));
+        }
+        assert!(matches!(
+            cache.reserve("a", hashes[0]),
+            Reservation::Reserved(_)
+        ));
+        assert_eq!(replayed(&cache, "c", hashes[2]), Some(Ok(b"c".to_vec())));
+    }
+
+    #[test]
+    fn test_expired_entry_is_ignored() {
+        let cache = Arc::new(IdempotencyCache::new(10, Duration::from_secs(0)));
+        complete(&cache, "key", HashValue::random(), Ok(b"pending".to_vec()));
+        assert!(matches!(
+            cache.reserve("key", HashValue::random()),
+            Reservation::Reserved(_)
+        ));
+    }
+
+    #[test]
+    fn test_zero_capacity_caches_nothing() {
+        let cache = Arc::new(IdempotencyCache::new(0, Duration::from_secs(60)));
+        let hash = HashValue::random();
+        complete(&cache, "key", hash, Ok(b"pending".to_vec()));
+        assert!(matches!(
+            cache.reserve("key", hash),
+            Reservation::Reserved(_)
+        ));
+    }
+}
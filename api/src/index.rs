@@ -2,17 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accounts,
+    abort_codes, accounts,
     context::Context,
-    events,
+    event_stream, events,
     failpoint::fail_point,
-    log,
+    gas_estimation, log,
     metrics::{metrics, status_metrics},
-    state, transactions,
+    param::Param,
+    state, transactions, validators,
 };
-use aptos_api_types::{Error, Response};
+use aptos_api_types::{Error, IndexResponse, Response, X_APTOS_MIN_VERSION};
 
-use std::convert::Infallible;
+use serde::Deserialize;
+use std::{
+    convert::Infallible,
+    time::{Duration, Instant},
+};
 use warp::{
     body::BodyDeserializeError,
     cors::CorsForbidden,
@@ -25,29 +30,68 @@ use warp::{
 const OPEN_API_HTML: &str = include_str!("../doc/spec.html");
 const OPEN_API_SPEC: &str = include_str!("../doc/openapi.yaml");
 
+/// How long `GET /ledger_info` waits for `wait_for_version` by default, and at most.
+const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_WAIT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often the ledger version is checked while waiting for `wait_for_version`.
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
-    index(context.clone())
-        .or(openapi_spec())
-        .or(accounts::get_account(context.clone()))
-        .or(accounts::get_account_resources(context.clone()))
-        .or(accounts::get_account_modules(context.clone()))
-        .or(transactions::get_transaction(context.clone()))
-        .or(transactions::get_transactions(context.clone()))
-        .or(transactions::get_account_transactions(context.clone()))
-        .or(transactions::submit_bcs_transactions(context.clone()))
-        .or(transactions::submit_json_transactions(context.clone()))
-        .or(transactions::create_signing_message(context.clone()))
-        .or(events::get_events_by_event_key(context.clone()))
-        .or(events::get_events_by_event_handle(context.clone()))
-        .or(state::get_account_resource(context.clone()))
-        .or(state::get_account_module(context.clone()))
-        .or(state::get_table_item(context.clone()))
-        .or(context.health_check_route().with(metrics("health_check")))
+    context
+        .endpoint_group_filter()
+        .and(context.min_version_filter())
+        .and(
+            index(context.clone())
+                .or(get_ledger_info(context.clone()))
+                .or(openapi_spec())
+                .or(accounts::get_account(context.clone()))
+                .or(accounts::get_account_resources(context.clone()))
+                .or(accounts::get_account_resources_batch(context.clone()))
+                .or(accounts::get_account_modules(context.clone()))
+                .or(accounts::get_account_activity(context.clone()))
+                .or(accounts::get_account_event_handles(context.clone()))
+                .or(accounts::get_account_sequence_numbers(context.clone()))
+                .or(accounts::get_account_stake_pool(context.clone()))
+                .or(accounts::get_account_tokens(context.clone()))
+                .or(accounts::get_account_non_existence_proof(context.clone()))
+                .or(transactions::get_transaction(context.clone()))
+                .or(transactions::trace_transaction(context.clone()))
+                .or(transactions::get_transactions(context.clone()))
+                .or(transactions::get_account_transactions(context.clone()))
+                .or(transactions::submit_bcs_transactions(context.clone()))
+                .or(transactions::submit_json_transactions(context.clone()))
+                .or(transactions::create_signing_message(context.clone()))
+                .or(transactions::authenticate_bcs_transaction(context.clone()))
+                .or(transactions::authenticate_json_transaction(context.clone()))
+                .or(events::get_events_by_type(context.clone()))
+                .or(events::get_events_by_event_key(context.clone()))
+                .or(events::get_events_by_event_handle(context.clone()))
+                .or(event_stream::stream_events_by_event_key(context.clone()))
+                .or(event_stream::stream_events_by_event_handle(context.clone()))
+                .or(event_stream::stream_events_by_type(context.clone()))
+                .or(state::get_account_resource(context.clone()))
+                .or(state::get_account_module(context.clone()))
+                .or(state::get_table_item(context.clone()))
+                .or(state::get_coin_info(context.clone()))
+                .or(state::get_coin_supply(context.clone()))
+                .or(state::get_gas_schedule(context.clone()))
+                .or(state::get_script_function_allow_list(context.clone()))
+                .or(state::get_features(context.clone()))
+                .or(gas_estimation::estimate_gas_price(context.clone()))
+                .or(state::get_state_diff(context.clone()))
+                .or(state::verify_module(context.clone()))
+                .or(state::get_module_history(context.clone()))
+                .or(validators::get_validators(context.clone()))
+                .or(validators::get_validator_stats(context.clone()))
+                .or(abort_codes::get_abort_code(context.clone()))
+                .or(context.health_check_route().with(metrics("health_check"))),
+        )
         .with(
             warp::cors()
                 .allow_any_origin()
                 .allow_methods(vec!["POST", "GET"])
-                .allow_headers(vec![header::CONTENT_TYPE]),
+                .allow_headers(vec![header::CONTENT_TYPE.as_str(), X_APTOS_MIN_VERSION]),
         )
         .recover(handle_rejection)
         .with(log::logger())
@@ -82,8 +126,73 @@ pub fn index(context: Context) -> BoxedFilter<(impl Reply,)> {
 
 pub async fn handle_index(context: Context) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_index")?;
-    let info = context.get_latest_ledger_info()?;
-    Ok(Response::new(info.clone(), &info)?)
+    let info = context.get_latest_ledger_info().await?;
+    Ok(Response::new(info.clone(), &IndexResponse::new(info))?)
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct WaitForVersion {
+    wait_for_version: Option<Param<u64>>,
+    timeout_ms: Option<Param<u64>>,
+}
+
+impl WaitForVersion {
+    fn timeout(&self) -> Result<Duration, Error> {
+        let timeout = self
+            .timeout_ms
+            .clone()
+            .map(|timeout| timeout.parse("timeout_ms"))
+            .transpose()?
+            .map_or(DEFAULT_WAIT_TIMEOUT, Duration::from_millis);
+        if timeout > MAX_WAIT_TIMEOUT {
+            return Err(Error::invalid_param(
+                "timeout_ms",
+                format!(
+                    "{}, exceed limit {}",
+                    timeout.as_millis(),
+                    MAX_WAIT_TIMEOUT.as_millis()
+                ),
+            ));
+        }
+        Ok(timeout)
+    }
+}
+
+// GET /ledger_info?wait_for_version=<version>&timeout_ms=<timeout>
+pub fn get_ledger_info(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("ledger_info")
+        .and(warp::get())
+        .and(warp::query::<WaitForVersion>())
+        .and(context.filter())
+        .and_then(handle_get_ledger_info)
+        .with(metrics("wait_for_ledger_info"))
+        .boxed()
+}
+
+/// Returns the ledger info once the ledger reaches `wait_for_version`, so that a client can
+/// read its own writes from a node behind a load balancer. The latest ledger info is returned
+/// when the timeout expires first, the client tells the two apart from the ledger version.
+async fn handle_get_ledger_info(
+    params: WaitForVersion,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_ledger_info")?;
+    let deadline = Instant::now() + params.timeout()?;
+    let wait_for_version = params
+        .wait_for_version
+        .map(|version| version.parse("wait_for_version"))
+        .transpose()?;
+    let mut info = context.get_latest_ledger_info().await?;
+    if let Some(version) = wait_for_version {
+        while info.version() < version && Instant::now() < deadline {
+            tokio::time::sleep(
+                WAIT_POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now())),
+            )
+            .await;
+            info = context.get_latest_ledger_info().await?;
+        }
+    }
+    Ok(Response::new(info.clone(), &IndexResponse::new(info))?)
 }
 
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
@@ -121,6 +230,10 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     let mut rep = reply::with_status(body, code).into_response();
     rep.headers_mut()
         .insert("access-control-allow-origin", HeaderValue::from_static("*"));
+    if code == StatusCode::SERVICE_UNAVAILABLE {
+        rep.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+    }
     Ok(rep)
 }
 
@@ -1,10 +1,17 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod abort_codes;
 mod accounts;
+mod committed_txns;
 mod context;
+mod endpoint_groups;
+mod event_stream;
 mod events;
+mod fields;
+mod gas_estimation;
 mod health_check;
+mod idempotency;
 mod index;
 pub(crate) mod log;
 mod metrics;
@@ -12,9 +19,12 @@ mod page;
 pub(crate) mod param;
 pub mod runtime;
 mod state;
+mod submission_batcher;
+mod transaction_limits;
 mod transactions;
+mod validators;
 pub(crate) mod version;
 
 mod failpoint;
-#[cfg(any(test))]
+#[cfg(test)]
 pub(crate) mod tests;
@@ -8,10 +8,12 @@ use aptos_api_types::{Error, TransactionId};
 use anyhow::Result;
 use serde::Deserialize;
 use std::num::NonZeroU16;
+use storage_interface::errors::AptosDbError;
 
 const DEFAULT_PAGE_SIZE: u16 = 25;
-const MAX_PAGE_SIZE: u16 = 1000;
 
+/// The `start` and `limit` query params of the paged endpoints. The maximum `limit` of each
+/// endpoint comes from the API config, see `ApiConfig::max_*_page_size`.
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Page {
     start: Option<TransactionVersionParam>,
@@ -35,19 +37,152 @@ impl Page {
         Ok(version)
     }
 
-    pub fn limit(&self) -> Result<u16, Error> {
+    /// Whether a page is asked for at all, for the endpoints returning everything by default.
+    pub fn is_requested(&self) -> bool {
+        self.start.is_some() || self.limit.is_some()
+    }
+
+    /// The requested page size, defaulting to 25 or `max_page_size` if lower.
+    pub fn limit(&self, max_page_size: u16) -> Result<u16, Error> {
         let limit = self
             .limit
             .clone()
             .map(|v| v.parse("limit"))
-            .unwrap_or_else(|| Ok(NonZeroU16::new(DEFAULT_PAGE_SIZE).unwrap()))?
-            .get();
-        if limit > MAX_PAGE_SIZE {
+            .transpose()?
+            .map_or_else(|| DEFAULT_PAGE_SIZE.min(max_page_size), NonZeroU16::get);
+        if limit > max_page_size {
             return Err(Error::invalid_param(
                 "limit",
-                format!("{}, exceed limit {}", limit, MAX_PAGE_SIZE),
+                format!("{}, exceed limit {}", limit, max_page_size),
             ));
         }
         Ok(limit)
     }
+
+    /// The `start` of the page following a page of `count` items from `start`, if any: a page
+    /// shorter than `limit` is the last one, and so is a page reaching `end`, the exclusive end
+    /// of the list, when it is known.
+    pub fn next_start(start: u64, count: usize, limit: u16, end: Option<u64>) -> Option<u64> {
+        if count == 0 || count < limit as usize {
+            return None;
+        }
+        let next = start.checked_add(count as u64)?;
+        match end {
+            Some(end) if next >= end => None,
+            _ => Some(next),
+        }
+    }
+}
+
+/// Maps the error of reading the page asked for by the client. The requests the storage refuses,
+/// e.g. too many items, are bad requests. Any other error is internal, including items the storage
+/// should have but does not find.
+pub(crate) fn page_read_error(e: AptosDbError) -> Error {
+    match e {
+        AptosDbError::TooManyRequested(..) | AptosDbError::InvalidArgument(_) => {
+            Error::bad_request(e)
+        }
+        AptosDbError::NotFound(_) => Error::internal(e.into()),
+        AptosDbError::Other(e) => Error::internal(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{page_read_error, AptosDbError, Page};
+    use proptest::prelude::*;
+    use std::str::FromStr;
+
+    fn page(start: Option<u64>, limit: Option<&str>) -> Page {
+        Page {
+            start: start.map(|start| FromStr::from_str(&start.to_string()).unwrap()),
+            limit: limit.map(|limit| FromStr::from_str(limit).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_page_read_error() {
+        let err = page_read_error(AptosDbError::TooManyRequested(11, 10));
+        assert_eq!(err.code, 400);
+
+        let err = page_read_error(
+            anyhow::Error::from(AptosDbError::InvalidArgument("limit should > 0".to_owned()))
+                .context("reading the page")
+                .into(),
+        );
+        assert_eq!(err.code, 400);
+
+        // Storage is expected to have every item of a page within the ledger version.
+        let err = page_read_error(AptosDbError::NotFound("Txn 5".to_owned()));
+        assert_eq!(err.code, 500);
+
+        let err = page_read_error(anyhow::format_err!("I/O error").into());
+        assert_eq!(err.code, 500);
+    }
+
+    #[test]
+    fn test_limit_zero_is_invalid() {
+        let err = page(None, Some("0")).limit(1000).unwrap_err();
+        assert_eq!(err.code, 400);
+    }
+
+    #[test]
+    fn test_default_limit_is_bounded_by_max() {
+        assert_eq!(page(None, None).limit(1000).unwrap(), 25);
+        assert_eq!(page(None, None).limit(10).unwrap(), 10);
+    }
+
+    #[test]
+    fn test_next_start_does_not_overflow() {
+        assert_eq!(Page::next_start(u64::MAX - 1, 1, 1, None), Some(u64::MAX));
+        assert_eq!(Page::next_start(u64::MAX, 1, 1, None), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_start_beyond_max_is_not_found(max in any::<u64>(), offset in 1..1000u64) {
+            let start = max.saturating_add(offset);
+            prop_assume!(start > max);
+            let err = page(Some(start), None).start(0, max).unwrap_err();
+            prop_assert_eq!(err.code, 404);
+        }
+
+        #[test]
+        fn test_start_within_max(max in any::<u64>(), start in any::<u64>()) {
+            let start = start.min(max);
+            prop_assert_eq!(page(Some(start), None).start(0, max).unwrap(), start);
+        }
+
+        #[test]
+        fn test_limit_within_max(max in 1..=u16::MAX, limit in 1..=u16::MAX) {
+            let result = page(None, Some(&limit.to_string())).limit(max);
+            if limit <= max {
+                prop_assert_eq!(result.unwrap(), limit);
+            } else {
+                prop_assert_eq!(result.unwrap_err().code, 400);
+            }
+        }
+
+        #[test]
+        fn test_next_start_stays_within_the_list(
+            start in any::<u64>(),
+            count in 0..2000usize,
+            limit in 1..=1000u16,
+            end in proptest::option::of(any::<u64>()),
+        ) {
+            let count = count.min(limit as usize);
+            match Page::next_start(start, count, limit, end) {
+                Some(next) => {
+                    prop_assert_eq!(count, limit as usize);
+                    prop_assert_eq!(next, start + count as u64);
+                    prop_assert!(end.map_or(true, |end| next < end));
+                }
+                None => prop_assert!(
+                    count < limit as usize
+                        || start.checked_add(count as u64).is_none()
+                        || end.map_or(false, |end| start + count as u64 >= end)
+                ),
+            }
+        }
+    }
 }
@@ -8,6 +8,7 @@ use serde::{Deserialize, Deserializer};
 
 use std::{convert::Infallible, str::FromStr};
 
+pub type AbortCodeParam = Param<u64>;
 pub type AddressParam = Param<Address>;
 pub type EventKeyParam = Param<EventKey>;
 pub type LedgerVersionParam = Param<u64>;
@@ -3,41 +3,107 @@
 
 use crate::{context::Context, index};
 
+use anyhow::Context as _;
 use aptos_config::config::{ApiConfig, NodeConfig};
+use aptos_logger::warn;
 use aptos_mempool::MempoolClientSender;
 use aptos_types::chain_id::ChainId;
+use config_notifications::ApiConfigListener;
+use futures::{channel::oneshot, future::BoxFuture, Future, FutureExt, StreamExt};
 use storage_interface::DbReader;
 use warp::{Filter, Reply};
 
-use std::{convert::Infallible, net::SocketAddr, sync::Arc};
-use tokio::runtime::{Builder, Runtime};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+    runtime::{Builder, Runtime},
+    task::JoinHandle,
+};
 
 /// Creates HTTP server (warp-based) serves for both REST and JSON-RPC API.
 /// When api and json-rpc are configured with same port, both API will be served for the port.
 /// When api and json-rpc are configured with different port, both API will be served for
 /// both ports.
-/// Returns corresponding Tokio runtime
+/// Updates of the API config received on `config_updates` apply without restarting the server.
+/// Returns the runtime serving the API
 pub fn bootstrap(
     config: &NodeConfig,
     chain_id: ChainId,
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
-) -> anyhow::Result<Runtime> {
+    mut config_updates: ApiConfigListener,
+) -> anyhow::Result<ApiRuntime> {
     let runtime = Builder::new_multi_thread()
         .thread_name("api")
         .enable_all()
         .build()
         .expect("[api] failed to create runtime");
 
+    #[cfg(feature = "failpoints")]
+    aptos_failpoints::register(crate::failpoint::FAILPOINTS);
+
     let api_config = config.api.clone();
     let api = WebServer::from(api_config.clone());
+    let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
+    // Binding happens here, so that the address is known (e.g. when the port is 0).
+    let guard = runtime.enter();
+    let context = Context::new(chain_id, db, mp_sender, api_config);
+    let updated_context = context.clone();
     runtime.spawn(async move {
-        let context = Context::new(chain_id, db, mp_sender, api_config);
-        let routes = index::routes(context);
-        api.serve(routes).await;
+        while let Some(api_config) = config_updates.next().await {
+            updated_context.update_api_config(api_config);
+        }
     });
-    Ok(runtime)
+    let routes = index::routes(context);
+    let (address, server) = api.bind(routes, async move {
+        // A dropped sender shuts the server down too.
+        let _ = shutdown_receiver.await;
+    })?;
+    let server = runtime.spawn(server);
+    drop(guard);
+    Ok(ApiRuntime {
+        runtime,
+        server,
+        shutdown_sender,
+        address,
+    })
+}
+
+/// The runtime serving the API, see `bootstrap`.
+pub struct ApiRuntime {
+    runtime: Runtime,
+    server: JoinHandle<()>,
+    shutdown_sender: oneshot::Sender<()>,
+    address: SocketAddr,
+}
+
+impl ApiRuntime {
+    /// The address the API is served on, with the port picked by the OS if the configured one
+    /// is 0.
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Stops accepting connections and waits up to `timeout` for the requests being served to
+    /// complete, then drops the runtime. Returns false if requests were still being served.
+    pub fn shutdown(self, timeout: Duration) -> bool {
+        let Self {
+            runtime,
+            server,
+            shutdown_sender,
+            ..
+        } = self;
+        let _ = shutdown_sender.send(());
+        let drained =
+            runtime.block_on(async { tokio::time::timeout(timeout, server).await.is_ok() });
+        if !drained {
+            warn!(
+                "API requests still in flight after {:?}, dropping them",
+                timeout
+            );
+        }
+        drained
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -66,20 +132,37 @@ impl WebServer {
         }
     }
 
-    pub async fn serve<F>(&self, routes: F)
+    /// Binds the address and returns it, with the server serving `routes` until `shutdown`
+    /// completes, then waiting for the requests being served. Must be called within a runtime.
+    /// Fails if the address can't be bound, e.g. when it is already in use.
+    pub fn bind<F, S>(
+        &self,
+        routes: F,
+        shutdown: S,
+    ) -> anyhow::Result<(SocketAddr, BoxFuture<'static, ()>)>
     where
         F: Filter<Error = Infallible> + Clone + Sync + Send + 'static,
         F::Extract: Reply,
+        S: Future<Output = ()> + Send + 'static,
     {
         match &self.tls_cert_path {
-            None => warp::serve(routes).bind(self.address).await,
+            None => {
+                let (address, server) = warp::serve(routes)
+                    .try_bind_with_graceful_shutdown(self.address, shutdown)
+                    .with_context(|| format!("Failed to bind the API to {}", self.address))?;
+                Ok((address, server.boxed()))
+            }
             Some(cert_path) => {
-                warp::serve(routes)
+                // The TLS server of warp only has a binding method that panics on failure, so
+                // check the address can be bound first.
+                std::net::TcpListener::bind(self.address)
+                    .with_context(|| format!("Failed to bind the API to {}", self.address))?;
+                let (address, server) = warp::serve(routes)
                     .tls()
                     .cert_path(cert_path)
                     .key_path(self.tls_key_path.as_ref().unwrap())
-                    .bind(self.address)
-                    .await
+                    .bind_with_graceful_shutdown(self.address, shutdown);
+                Ok((address, server.boxed()))
             }
         }
     }
@@ -91,6 +174,7 @@ mod tests {
 
     use aptos_config::config::NodeConfig;
     use aptos_types::chain_id::ChainId;
+    use config_notifications::ConfigNotificationService;
 
     use crate::{
         runtime::bootstrap,
@@ -104,6 +188,26 @@ mod tests {
         bootstrap_with_config(cfg);
     }
 
+    #[test]
+    fn test_bootstrap_fails_when_address_in_use() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut cfg = NodeConfig::default();
+        cfg.api.address = listener.local_addr().unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let context = runtime.block_on(new_test_context_async(
+            "test_bootstrap_fails_when_address_in_use",
+        ));
+        let ret = bootstrap(
+            &cfg,
+            ChainId::test(),
+            context.db.clone(),
+            context.mempool.ac_client.clone(),
+            ConfigNotificationService::new(cfg.clone()).subscribe_to_api_updates(),
+        );
+        assert!(ret.is_err());
+    }
+
     pub fn bootstrap_with_config(cfg: NodeConfig) {
         let runtime = tokio::runtime::Runtime::new().unwrap();
         let context = runtime.block_on(new_test_context_async(
@@ -114,6 +218,7 @@ mod tests {
             ChainId::test(),
             context.db.clone(),
             context.mempool.ac_client.clone(),
+            ConfigNotificationService::new(cfg.clone()).subscribe_to_api_updates(),
         );
         assert!(ret.is_ok());
 
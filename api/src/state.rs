@@ -4,24 +4,45 @@
 use crate::{
     context::Context,
     failpoint::fail_point,
+    fields::Fields,
     metrics::metrics,
+    page::Page,
     param::{
-        AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, TableHandleParam,
+        AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam,
+        TableHandleParam, TransactionVersionParam,
     },
     version::Version,
 };
 use anyhow::anyhow;
 use aptos_api_types::{
-    AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response, TableItemRequest, TransactionId,
+    AsConverter, CoinInfo, CoinSupply, Error, LedgerInfo, ModuleChange, ModuleChangeKind,
+    ModuleVerificationError, ModuleVerificationRequest, ModuleVerificationResult,
+    MoveModuleBytecode, Response, StateDiff, TableItemRequest, TransactionId,
+};
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::{coin_info_struct_tag, CoinInfoResource},
+    on_chain_config::{
+        access_path_for_config, Features, GasSchedule, OnChainConfig, ScriptFunctionAllowList,
+    },
+    state_store::state_key::StateKey,
+    transaction::Transaction,
 };
-use aptos_state_view::StateView;
-use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
 use aptos_vm::data_cache::AsMoveResolver;
-use move_deps::move_core_types::{
-    account_address::AccountAddress,
-    identifier::Identifier,
-    language_storage::{ModuleId, ResourceKey, StructTag},
+use move_deps::{
+    move_binary_format::{
+        access::ModuleAccess, compatibility::Compatibility, errors::Location,
+        file_format::CompiledModule, normalized,
+    },
+    move_bytecode_source_map::source_map::SourceMap,
+    move_bytecode_verifier,
+    move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, ResourceKey, StructTag, TypeTag},
+    },
 };
+use serde::Deserialize;
 use std::convert::TryInto;
 use storage_interface::state_view::DbStateView;
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
@@ -32,8 +53,9 @@ pub fn get_account_resource(context: Context) -> BoxedFilter<(impl Reply,)> {
         .and(warp::get())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, struct_tag, ctx, version: Version| {
-            (version.version, address, struct_tag, ctx)
+        .and(warp::query::<Fields>())
+        .map(|address, struct_tag, ctx, version: Version, fields| {
+            (version.version, address, struct_tag, fields, ctx)
         })
         .untuple_one()
         .and_then(handle_get_account_resource)
@@ -54,13 +76,37 @@ pub fn get_account_module(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// GET /coins/<coin_type>
+pub fn get_coin_info(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("coins" / MoveStructTagParam)
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|coin_type, ctx, version: Version| (version.version, coin_type, ctx))
+        .untuple_one()
+        .and_then(handle_get_coin_info)
+        .with(metrics("get_coin_info"))
+        .boxed()
+}
+
+// GET /coins/<coin_type>/supply
+pub fn get_coin_supply(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("coins" / MoveStructTagParam / "supply")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|coin_type, ctx, version: Version| (version.version, coin_type, ctx))
+        .untuple_one()
+        .and_then(handle_get_coin_supply)
+        .with(metrics("get_coin_supply"))
+        .boxed()
+}
+
 // GET /tables/<table_handle>/item
 pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("tables" / TableHandleParam / "item")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            context.content_length_limit(),
-        ))
+        .and(context.content_length_limit_filter())
         .and(warp::body::json::<TableItemRequest>())
         .and(context.filter())
         .and(warp::query::<Version>())
@@ -71,21 +117,103 @@ pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// GET /gas_schedule
+pub fn get_gas_schedule(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("gas_schedule")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|ctx, version: Version| (version.version, ctx))
+        .untuple_one()
+        .and_then(handle_get_gas_schedule)
+        .with(metrics("get_gas_schedule"))
+        .boxed()
+}
+
+// GET /script_function_allow_list
+pub fn get_script_function_allow_list(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("script_function_allow_list")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|ctx, version: Version| (version.version, ctx))
+        .untuple_one()
+        .and_then(handle_get_script_function_allow_list)
+        .with(metrics("get_script_function_allow_list"))
+        .boxed()
+}
+
+// GET /features
+pub fn get_features(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("features")
+        .and(warp::get())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|ctx, version: Version| (version.version, ctx))
+        .untuple_one()
+        .and_then(handle_get_features)
+        .with(metrics("get_features"))
+        .boxed()
+}
+
+// GET /state_diff?first_version=<first_version>&last_version=<last_version>
+pub fn get_state_diff(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("state_diff")
+        .and(warp::get())
+        .and(warp::query::<StateDiffRange>())
+        .and(context.filter())
+        .and_then(handle_get_state_diff)
+        .with(metrics("get_state_diff"))
+        .boxed()
+}
+
+// POST /modules/verify
+pub fn verify_module(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("modules" / "verify")
+        .and(warp::post())
+        .and(context.content_length_limit_filter())
+        .and(warp::body::json::<ModuleVerificationRequest>())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|body, ctx, version: Version| (version.version, body, ctx))
+        .untuple_one()
+        .and_then(handle_verify_module)
+        .with(metrics("verify_module"))
+        .boxed()
+}
+
+// GET /modules/<address>/<module_name>/history?start=<start_version>&limit=<page_size>
+pub fn get_module_history(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("modules" / AddressParam / MoveIdentifierParam / "history")
+        .and(warp::get())
+        .and(warp::query::<Page>())
+        .and(context.filter())
+        .and_then(handle_get_module_history)
+        .with(metrics("get_module_history"))
+        .boxed()
+}
+
 async fn handle_get_account_resource(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
     struct_tag: MoveStructTagParam,
+    fields: Fields,
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_query_resource")?;
     let struct_tag = struct_tag.parse("struct tag")?;
-    Ok(State::new(ledger_version, context)?.resource(
-        address.parse("account address")?.into(),
-        struct_tag
-            .clone()
-            .try_into()
-            .map_err(|_| Error::invalid_param("resource_type", struct_tag))?,
-    )?)
+    let state = State::new(ledger_version, context.clone()).await?;
+    let response = state
+        .resource(
+            address.parse("account address")?.into(),
+            struct_tag
+                .clone()
+                .try_into()
+                .map_err(|_| Error::invalid_param("resource_type", struct_tag))?,
+            &context,
+        )
+        .await?;
+    Ok(fields.apply(response)?)
 }
 
 async fn handle_get_account_module(
@@ -95,10 +223,49 @@ async fn handle_get_account_module(
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_module")?;
-    Ok(State::new(ledger_version, context)?.module(
-        address.parse("account address")?.into(),
-        name.parse("module name")?,
-    )?)
+    let state = State::new(ledger_version, context).await?;
+    Ok(state
+        .module(
+            address.parse("account address")?.into(),
+            name.parse("module name")?,
+        )
+        .await?)
+}
+
+async fn handle_get_coin_info(
+    ledger_version: Option<LedgerVersionParam>,
+    coin_type: MoveStructTagParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_coin_info")?;
+    let coin_type = coin_type.parse("coin type")?;
+    let state = State::new(ledger_version, context).await?;
+    Ok(state
+        .coin_info(
+            coin_type
+                .clone()
+                .try_into()
+                .map_err(|_| Error::invalid_param("coin_type", coin_type))?,
+        )
+        .await?)
+}
+
+async fn handle_get_coin_supply(
+    ledger_version: Option<LedgerVersionParam>,
+    coin_type: MoveStructTagParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_coin_supply")?;
+    let coin_type = coin_type.parse("coin type")?;
+    let state = State::new(ledger_version, context).await?;
+    Ok(state
+        .coin_supply(
+            coin_type
+                .clone()
+                .try_into()
+                .map_err(|_| Error::invalid_param("coin_type", coin_type))?,
+        )
+        .await?)
 }
 
 async fn handle_get_table_item(
@@ -108,21 +275,101 @@ async fn handle_get_table_item(
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_table_item")?;
-    Ok(State::new(ledger_version, context)?.table_item(handle.parse("table handle")?, body)?)
+    let state = State::new(ledger_version, context).await?;
+    Ok(state
+        .table_item(handle.parse("table handle")?, body)
+        .await?)
+}
+
+async fn handle_get_gas_schedule(
+    ledger_version: Option<LedgerVersionParam>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_gas_schedule")?;
+    let state = State::new(ledger_version, context).await?;
+    Ok(state.gas_schedule().await?)
+}
+
+async fn handle_get_script_function_allow_list(
+    ledger_version: Option<LedgerVersionParam>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_script_function_allow_list")?;
+    let state = State::new(ledger_version, context).await?;
+    Ok(state.script_function_allow_list().await?)
+}
+
+async fn handle_get_features(
+    ledger_version: Option<LedgerVersionParam>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_features")?;
+    let state = State::new(ledger_version, context).await?;
+    Ok(state.features().await?)
+}
+
+async fn handle_get_state_diff(
+    range: StateDiffRange,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_state_diff")?;
+    let first_version = range.first_version.parse("first_version")?;
+    let state = State::new(range.last_version, context.clone()).await?;
+    Ok(state.state_diff(first_version, &context).await?)
+}
+
+async fn handle_verify_module(
+    ledger_version: Option<LedgerVersionParam>,
+    body: ModuleVerificationRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_verify_module")?;
+    let state = State::new(ledger_version, context).await?;
+    Ok(state.verify_module(body).await?)
+}
+
+async fn handle_get_module_history(
+    address: AddressParam,
+    name: MoveIdentifierParam,
+    page: Page,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_module_history")?;
+    let state = State::new(None, context.clone()).await?;
+    Ok(state
+        .module_history(
+            address.parse("account address")?.into(),
+            name.parse("module name")?,
+            page,
+            &context,
+        )
+        .await?)
+}
+
+/// Maximum number of versions a single state diff request may span.
+const MAX_STATE_DIFF_VERSIONS: u64 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+struct StateDiffRange {
+    first_version: TransactionVersionParam,
+    /// Defaults to the latest ledger version.
+    last_version: Option<LedgerVersionParam>,
 }
 
 pub(crate) struct State {
+    // only resolves the modules needed to render values, which are read through `context`
     state_view: DbStateView,
     ledger_version: aptos_types::transaction::Version,
     latest_ledger_info: LedgerInfo,
+    context: Context,
 }
 
 impl State {
-    pub fn new(
+    pub async fn new(
         ledger_version: Option<LedgerVersionParam>,
         context: Context,
     ) -> Result<Self, Error> {
-        let latest_ledger_info = context.get_latest_ledger_info()?;
+        let latest_ledger_info = context.get_latest_ledger_info().await?;
         let ledger_version = ledger_version
             .map(|v| v.parse("ledger version"))
             .unwrap_or_else(|| Ok(latest_ledger_info.version()))?;
@@ -141,37 +388,51 @@ impl State {
             state_view,
             ledger_version,
             latest_ledger_info,
+            context,
         })
     }
 
-    pub fn resource(
+    async fn state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self
+            .context
+            .get_state_value(state_key, self.ledger_version)
+            .await?)
+    }
+
+    pub async fn resource(
         self,
         address: AccountAddress,
         struct_tag: StructTag,
-    ) -> Result<impl Reply, Error> {
+        context: &Context,
+    ) -> Result<Response, Error> {
         let resource_key = ResourceKey::new(address, struct_tag.clone());
         let access_path = AccessPath::resource_access_path(resource_key.clone());
         let state_key = StateKey::AccessPath(access_path);
         let bytes = self
-            .state_view
-            .get_state_value(&state_key)?
+            .state_value(&state_key)
+            .await?
             .ok_or_else(|| Error::not_found("Resource", resource_key, self.ledger_version))?;
 
         let resource = self
             .state_view
             .as_move_resolver()
             .as_converter()
+            .with_layout_cache(context.layout_cache(self.ledger_version)?)
             .try_into_resource(&struct_tag, &bytes)?;
         Response::new(self.latest_ledger_info, &resource)
     }
 
-    pub fn module(self, address: AccountAddress, name: Identifier) -> Result<impl Reply, Error> {
+    pub async fn module(
+        self,
+        address: AccountAddress,
+        name: Identifier,
+    ) -> Result<impl Reply, Error> {
         let module_id = ModuleId::new(address, name);
         let access_path = AccessPath::code_access_path(module_id.clone());
         let state_key = StateKey::AccessPath(access_path);
         let bytes = self
-            .state_view
-            .get_state_value(&state_key)?
+            .state_value(&state_key)
+            .await?
             .ok_or_else(|| Error::not_found("Module", module_id, self.ledger_version))?;
 
         let module = MoveModuleBytecode::new(bytes)
@@ -180,7 +441,257 @@ impl State {
         Response::new(self.latest_ledger_info, &module)
     }
 
-    pub fn table_item(self, handle: u128, body: TableItemRequest) -> Result<impl Reply, Error> {
+    /// Lists the transactions that published and upgraded the module, oldest first, from the
+    /// index of state writes so that no replay is needed. If the module was published before the
+    /// state snapshot the node was restored from, the history starts with the upgrades after it.
+    pub async fn module_history(
+        self,
+        address: AccountAddress,
+        name: Identifier,
+        page: Page,
+        context: &Context,
+    ) -> Result<impl Reply, Error> {
+        let module_id = ModuleId::new(address, name);
+        let state_key = StateKey::AccessPath(AccessPath::code_access_path(module_id.clone()));
+        let first_version = context
+            .get_first_write_version(&state_key, self.ledger_version)
+            .await?
+            .ok_or_else(|| Error::not_found("Module", module_id, self.ledger_version))?;
+        let publish_version = context
+            .get_creation_version(&state_key, self.ledger_version)
+            .await?;
+        let start_version = page.start(first_version, self.ledger_version)?;
+        let limit = page.limit(context.api_config().max_page_size)?;
+        let versions = context
+            .get_write_versions(&state_key, start_version, limit, self.ledger_version)
+            .await?;
+        // the versions are sparse, the next page starts after the last one
+        let cursor = versions
+            .last()
+            .filter(|_| versions.len() == limit as usize)
+            .and_then(|version| version.checked_add(1));
+
+        let mut changes = vec![];
+        for version in versions {
+            // the first write of a module restored from a state snapshot isn't a transaction's
+            if publish_version.is_none() && version == first_version {
+                continue;
+            }
+            let txn = context
+                .get_transaction_by_version(version, self.ledger_version)
+                .await?;
+            let sender = match &txn.transaction {
+                Transaction::UserTransaction(signed_txn) => Some(signed_txn.sender().into()),
+                _ => None,
+            };
+            changes.push(ModuleChange {
+                kind: if Some(version) == publish_version {
+                    ModuleChangeKind::Publish
+                } else {
+                    ModuleChangeKind::Upgrade
+                },
+                version: version.into(),
+                transaction_hash: txn.info.transaction_hash().into(),
+                sender,
+                timestamp: context.get_block_timestamp(version).await?.into(),
+            });
+        }
+        Ok(Response::new(self.latest_ledger_info, &changes)?.with_cursor(cursor))
+    }
+
+    pub async fn gas_schedule(self) -> Result<impl Reply, Error> {
+        let access_path = access_path_for_config(GasSchedule::CONFIG_ID);
+        let bytes = self
+            .state_value(&StateKey::AccessPath(access_path))
+            .await?
+            .ok_or_else(|| {
+                Error::not_found("Gas schedule", GasSchedule::CONFIG_ID, self.ledger_version)
+            })?;
+        let gas_schedule = GasSchedule::deserialize_into_config(&bytes).map_err(Error::internal)?;
+        Response::new(
+            self.latest_ledger_info,
+            &aptos_api_types::GasSchedule::from(&gas_schedule.cost_table),
+        )
+    }
+
+    /// The allow list isn't published at genesis, no allow list is the same as a disabled one.
+    pub async fn script_function_allow_list(self) -> Result<impl Reply, Error> {
+        let access_path = access_path_for_config(ScriptFunctionAllowList::CONFIG_ID);
+        let allow_list = match self.state_value(&StateKey::AccessPath(access_path)).await? {
+            Some(bytes) => {
+                ScriptFunctionAllowList::deserialize_into_config(&bytes).map_err(Error::internal)?
+            }
+            None => ScriptFunctionAllowList::default(),
+        };
+        Response::new(
+            self.latest_ledger_info,
+            &aptos_api_types::ScriptFunctionAllowList::from(&allow_list),
+        )
+    }
+
+    /// The features config isn't published at genesis, no feature is enabled until it is.
+    pub async fn features(self) -> Result<impl Reply, Error> {
+        let access_path = access_path_for_config(Features::CONFIG_ID);
+        let features = match self.state_value(&StateKey::AccessPath(access_path)).await? {
+            Some(bytes) => Features::deserialize_into_config(&bytes).map_err(Error::internal)?,
+            None => Features::default(),
+        };
+        Response::new(
+            self.latest_ledger_info,
+            &aptos_api_types::Features::from(&features),
+        )
+    }
+
+    /// Returns the net state changes between `first_version` and the ledger version this state
+    /// was created at, so that downstream systems can materialize state incrementally.
+    pub async fn state_diff(
+        self,
+        first_version: u64,
+        context: &Context,
+    ) -> Result<impl Reply, Error> {
+        let last_version = self.ledger_version;
+        if first_version > last_version {
+            return Err(Error::invalid_param(
+                "first_version",
+                format!(
+                    "{}, greater than last version {}",
+                    first_version, last_version
+                ),
+            ));
+        }
+        if last_version - first_version > MAX_STATE_DIFF_VERSIONS {
+            return Err(Error::invalid_param(
+                "first_version",
+                format!(
+                    "{}, range to last version {} exceeds limit {}",
+                    first_version, last_version, MAX_STATE_DIFF_VERSIONS
+                ),
+            ));
+        }
+
+        let diff = context.get_state_diff(first_version, last_version).await?;
+        let resolver = self.state_view.as_move_resolver();
+        let converter = resolver.as_converter();
+        let changes = diff
+            .into_iter()
+            .map(|(state_key, op)| converter.try_into_write_set_change(state_key, op))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Response::new(
+            self.latest_ledger_info,
+            &StateDiff {
+                first_version: first_version.into(),
+                last_version: last_version.into(),
+                changes,
+            },
+        )
+    }
+
+    /// Runs the bytecode verifier on the given module and, if a module with the same id is
+    /// published at the requested ledger version, checks that the new module is an upgrade
+    /// compatible with it. Nothing is published.
+    pub async fn verify_module(self, body: ModuleVerificationRequest) -> Result<impl Reply, Error> {
+        let source_map = body
+            .source_map
+            .map(|source_map| bcs::from_bytes::<SourceMap>(source_map.inner()))
+            .transpose()
+            .map_err(|e| Error::invalid_request_body(format!("invalid source map: {}", e)))?;
+        let module = match CompiledModule::deserialize(body.bytecode.inner()) {
+            Ok(module) => module,
+            Err(err) => {
+                let err = err.finish(Location::Undefined);
+                let result = ModuleVerificationResult::new(
+                    None,
+                    vec![ModuleVerificationError::from(&err)],
+                    None,
+                );
+                return Response::new(self.latest_ledger_info, &result);
+            }
+        };
+        let module_id = module.self_id();
+
+        let mut errors = vec![];
+        if let Err(err) = move_bytecode_verifier::verify_module(&module) {
+            errors.push(ModuleVerificationError::new(
+                &err,
+                source_map.as_ref(),
+                body.source.as_deref(),
+            ));
+        }
+
+        let access_path = AccessPath::code_access_path(module_id.clone());
+        let compatibility = match self.state_value(&StateKey::AccessPath(access_path)).await? {
+            Some(bytes) => {
+                let old_module = CompiledModule::deserialize(&bytes).map_err(|e| {
+                    Error::internal(anyhow!(
+                        "failed to deserialize published module {}: {:?}",
+                        module_id,
+                        e
+                    ))
+                })?;
+                let compatibility = Compatibility::check(
+                    &normalized::Module::new(&old_module),
+                    &normalized::Module::new(&module),
+                );
+                Some(compatibility.into())
+            }
+            None => None,
+        };
+
+        let result = ModuleVerificationResult::new(Some(module_id.into()), errors, compatibility);
+        Response::new(self.latest_ledger_info, &result)
+    }
+
+    pub async fn coin_info(self, coin_type: StructTag) -> Result<impl Reply, Error> {
+        let info = self.coin_info_resource(&coin_type).await?;
+        Response::new(
+            self.latest_ledger_info,
+            &CoinInfo::new(coin_type.into(), &info),
+        )
+    }
+
+    /// The supply is aggregated by the coin info resource itself, which mints and burns update,
+    /// so it is available at any ledger version without replaying them.
+    pub async fn coin_supply(self, coin_type: StructTag) -> Result<impl Reply, Error> {
+        let supply = self
+            .coin_info_resource(&coin_type)
+            .await?
+            .supply()
+            .ok_or_else(|| Error::not_found("Coin supply", &coin_type, self.ledger_version))?;
+        Response::new(
+            self.latest_ledger_info,
+            &CoinSupply {
+                coin_type: coin_type.into(),
+                supply: supply.into(),
+            },
+        )
+    }
+
+    async fn coin_info_resource(
+        &self,
+        coin_type: &StructTag,
+    ) -> Result<CoinInfoResource<()>, Error> {
+        // The coin info is published under the account defining the coin type.
+        let resource_key = ResourceKey::new(
+            coin_type.address,
+            coin_info_struct_tag(TypeTag::Struct(coin_type.clone())),
+        );
+        let access_path = AccessPath::resource_access_path(resource_key);
+        let bytes = self
+            .state_value(&StateKey::AccessPath(access_path))
+            .await?
+            .ok_or_else(|| Error::not_found("Coin", coin_type, self.ledger_version))?;
+
+        // The coin type is only known at runtime, it is not needed to deserialize the resource.
+        bcs::from_bytes(&bytes)
+            .map_err(|e| Error::internal(anyhow!("failed to deserialize coin info: {}", e)))
+    }
+
+    pub async fn table_item(
+        self,
+        handle: u128,
+        body: TableItemRequest,
+    ) -> Result<impl Reply, Error> {
         let TableItemRequest {
             key_type,
             value_type,
@@ -189,24 +700,28 @@ impl State {
         let key_type = key_type.try_into()?;
         let value_type = value_type.try_into()?;
 
-        let resolver = self.state_view.as_move_resolver();
-        let converter = resolver.as_converter();
-
-        let vm_key = converter
-            .try_into_vm_value(&key_type, key.clone())
-            .map_err(Error::bad_request)?;
-        let raw_key = vm_key
-            .undecorate()
-            .simple_serialize()
-            .ok_or_else(|| Error::internal(anyhow!("Key failed to serialize.")))?;
+        let raw_key = {
+            let resolver = self.state_view.as_move_resolver();
+            let vm_key = resolver
+                .as_converter()
+                .try_into_vm_value(&key_type, key.clone())
+                .map_err(Error::bad_request)?;
+            vm_key
+                .undecorate()
+                .simple_serialize()
+                .ok_or_else(|| Error::internal(anyhow!("Key failed to serialize.")))?
+        };
 
         let state_key = StateKey::table_item(handle, raw_key);
         let bytes = self
-            .state_view
-            .get_state_value(&state_key)?
+            .state_value(&state_key)
+            .await?
             .ok_or_else(|| Error::not_found("table handle or item", key, self.ledger_version))?;
 
-        let move_value = converter.try_into_move_value(&value_type, &bytes)?;
+        let resolver = self.state_view.as_move_resolver();
+        let move_value = resolver
+            .as_converter()
+            .try_into_move_value(&value_type, &bytes)?;
         Response::new(self.latest_ledger_info, &move_value)
     }
 }
@@ -0,0 +1,73 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_types::transaction::SignedTransaction;
+use futures::{channel::oneshot, SinkExt};
+use std::{mem, sync::Arc, time::Duration};
+
+type PendingSubmission = (SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>);
+
+/// Groups the transactions submitted through the API within a short window into a single
+/// mempool request, so that mempool validates them together and takes its lock once per batch
+/// instead of once per transaction.
+///
+/// The first submission of a batch schedules its flush after the window; a batch reaching the
+/// maximum size is flushed right away.
+#[derive(Default)]
+pub(crate) struct SubmissionBatcher {
+    pending: Mutex<Vec<PendingSubmission>>,
+}
+
+impl SubmissionBatcher {
+    pub async fn submit(
+        self: &Arc<Self>,
+        mp_sender: &MempoolClientSender,
+        txn: SignedTransaction,
+        window: Duration,
+        max_batch_size: usize,
+    ) -> Result<SubmissionStatus> {
+        let (req_sender, callback) = oneshot::channel();
+        let full_batch = {
+            let mut pending = self.pending.lock();
+            pending.push((txn, req_sender));
+            if pending.len() == 1 {
+                // The flush runs on its own task: it must not depend on this request still being
+                // served once the window has passed.
+                let batcher = self.clone();
+                let mp_sender = mp_sender.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(window).await;
+                    let batch = mem::take(&mut *batcher.pending.lock());
+                    flush(mp_sender, batch).await;
+                });
+            }
+            if pending.len() >= max_batch_size {
+                Some(mem::take(&mut *pending))
+            } else {
+                None
+            }
+        };
+        if let Some(batch) = full_batch {
+            flush(mp_sender.clone(), batch).await;
+        }
+
+        callback.await?
+    }
+}
+
+async fn flush(mut mp_sender: MempoolClientSender, batch: Vec<PendingSubmission>) {
+    if batch.is_empty() {
+        return;
+    }
+    // On failure the callbacks are dropped along with the request, failing the submissions.
+    if let Err(e) = mp_sender
+        .send(MempoolClientRequest::SubmitTransactionBatch(batch))
+        .await
+    {
+        warn!("Failed to submit a batch of transactions to mempool: {}", e);
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+
+#[tokio::test]
+async fn test_get_abort_code() {
+    let context = new_test_context(current_function_name!());
+    // Errors::invalid_argument(EINSUFFICIENT_BALANCE)
+    let resp = context.get("/abort_codes/0x1/Coin/1287").await;
+    assert_eq!(resp["location"], "0x1::Coin");
+    assert_eq!(resp["code"], "1287");
+    assert_eq!(resp["category"], "INVALID_ARGUMENT");
+    assert_eq!(resp["reason"], "EINSUFFICIENT_BALANCE");
+}
+
+#[tokio::test]
+async fn test_get_unknown_abort_code() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/abort_codes/0x1/Coin/255")
+        .await;
+    context
+        .expect_status_code(404)
+        .get("/abort_codes/0x1/NotAModule/1287")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_abort_code_invalid_code() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/abort_codes/0x1/Coin/abc")
+        .await;
+}
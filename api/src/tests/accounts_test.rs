@@ -3,9 +3,20 @@
 
 use crate::{
     current_function_name,
-    tests::{find_value, new_test_context},
+    tests::{assert_json, find_value, new_test_context},
 };
-use serde_json::json;
+use aptos_api_types::{HexEncodedBytes, X_APTOS_CURSOR};
+use aptos_config::config::ApiConfig;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::AccountResource,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::{state_key::StateKey, state_value::StateValueWithProof},
+};
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
 
 #[tokio::test]
 async fn test_get_account_resources_returns_empty_array_for_account_has_no_resources() {
@@ -105,6 +116,112 @@ async fn test_account_modules_structs() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_account_modules_pagination() {
+    let context = new_test_context(current_function_name!());
+    let address = "0x1";
+
+    let all = context.get(&account_modules(address)).await;
+    let all = all.as_array().unwrap();
+    assert!(all.len() > 3);
+
+    let page = context
+        .get(&format!("{}?start=1&limit=2", account_modules(address)))
+        .await;
+    assert_eq!(page.as_array().unwrap(), &all[1..3]);
+
+    let past_the_end = context
+        .get(&format!("{}?start={}", account_modules(address), all.len()))
+        .await;
+    assert_eq!(past_the_end, json!([]));
+}
+
+#[tokio::test]
+async fn test_account_modules_without_bytecode() {
+    let context = new_test_context(current_function_name!());
+    let address = "0x1";
+
+    let all = context.get(&account_modules(address)).await;
+    let abis = context
+        .get(&format!(
+            "{}?include_bytecode=false",
+            account_modules(address)
+        ))
+        .await;
+    let abis = abis.as_array().unwrap();
+    assert_eq!(abis.len(), all.as_array().unwrap().len());
+    for (abi, module) in abis.iter().zip(all.as_array().unwrap()) {
+        assert_eq!(abi, &module["abi"]);
+        assert!(abi.get("bytecode").is_none());
+    }
+
+    context
+        .expect_status_code(400)
+        .get(&format!("{}?include_bytecode=no", account_modules(address)))
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_account_resources_batch() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .post(
+            "/accounts/resources/batch",
+            json!({
+                "addresses": ["0xA550C18", "0xf", "0x1"],
+                "resource_type": "0x1::Account::Account",
+            }),
+        )
+        .await;
+
+    let batch = resp.as_array().unwrap();
+    assert_eq!(batch.len(), 3);
+    assert_eq!(batch[0]["address"], "0xa550c18");
+    assert_eq!(batch[1]["address"], "0xf");
+    assert_eq!(batch[2]["address"], "0x1");
+    // 0xf does not exist
+    assert_eq!(batch[1]["resources"], json!([]));
+    for account in [&batch[0], &batch[2]].iter() {
+        let resources = account["resources"].as_array().unwrap();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0]["type"], "0x1::Account::Account");
+    }
+}
+
+#[tokio::test]
+async fn test_get_account_resources_batch_without_filter() {
+    let context = new_test_context(current_function_name!());
+    let batch = context
+        .post(
+            "/accounts/resources/batch",
+            json!({ "addresses": ["0xA550C18"] }),
+        )
+        .await;
+    let resources = context.get("/accounts/0xA550C18/resources").await;
+    assert_eq!(batch[0]["resources"], resources);
+}
+
+#[tokio::test]
+async fn test_get_account_resources_batch_too_large() {
+    let context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        max_account_resources_batch_size: 1,
+        ..ApiConfig::default()
+    });
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/accounts/resources/batch",
+            json!({ "addresses": ["0x1", "0xA550C18"] }),
+        )
+        .await;
+    assert_eq!(resp["aptos_error_code"], "batch_too_large");
+    assert_eq!(
+        resp["aptos_error_details"],
+        json!({"limit": 1, "actual": 2})
+    );
+}
+
 #[tokio::test]
 async fn test_get_account_resources_by_ledger_version() {
     let mut context = new_test_context(current_function_name!());
@@ -134,6 +251,48 @@ async fn test_get_account_resources_by_ledger_version() {
     assert_eq!(root_account["data"]["sequence_number"], "0");
 }
 
+#[tokio::test]
+async fn test_get_account_activity() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let root = context.root_account().address().to_hex_literal();
+    let activity = context.get(&format!("/accounts/{}/activity", root)).await;
+    assert_eq!(activity["sent_transactions"]["count"], "1");
+    let recent = activity["sent_transactions"]["recent"].as_array().unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0]["hash"], txn.committed_hash().to_hex_literal());
+
+    let resources = &activity["resources"];
+    assert!(resources["count"].as_str().unwrap().parse::<u64>().unwrap() > 0);
+    assert!(resources["recent"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|resource| resource["type"] == "0x1::Account::Account" && resource["version"] == "0"));
+
+    // The new account didn't send anything yet.
+    let activity = context
+        .get(&format!(
+            "/accounts/{}/activity",
+            account.address().to_hex_literal()
+        ))
+        .await;
+    assert_eq!(activity["sent_transactions"]["count"], "0");
+    assert_eq!(activity["modules"], json!({"count": "0", "recent": []}));
+}
+
+#[tokio::test]
+async fn test_get_account_activity_not_found() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/accounts/0xf/activity")
+        .await;
+}
+
 #[tokio::test]
 async fn test_get_account_resources_by_ledger_version_is_too_large() {
     let mut context = new_test_context(current_function_name!());
@@ -204,6 +363,269 @@ async fn test_get_core_account_data_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_sequence_numbers_with_gap() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account1 = context.gen_account();
+    let account2 = context.gen_account();
+
+    let txn = context.create_user_account_by(&mut root_account, &account1);
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    // skip sequence number 1, so that the following transaction is parked in mempool
+    *root_account.sequence_number_mut() += 1;
+    let txn = context.create_user_account_by(&mut root_account, &account2);
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/sequence_numbers",
+            root_account.address().to_hex_literal()
+        ))
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "committed_sequence_number": "0",
+            "pending_sequence_numbers": ["0", "2"],
+            "gaps": [{"start": "1", "end": "1"}],
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_account_sequence_numbers_not_found() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/accounts/0xf/sequence_numbers")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_account_stake_pool() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/stake_pool",
+            context.validator_owner.to_hex_literal()
+        ))
+        .await;
+
+    // genesis delegates stake from the root account to each validator before it joins
+    let active = resp["active"].as_array().unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0]["delegator"], "0xa550c18");
+    assert_eq!(active[0]["rewards"], "0");
+    assert_eq!(active[0]["locked_until_secs"], "100000");
+    assert_eq!(resp["voting_power"], active[0]["coins"]);
+    assert_eq!(resp["pending_active"], json!([]));
+    assert_eq!(resp["inactive"], json!([]));
+}
+
+#[tokio::test]
+async fn test_get_account_stake_pool_not_found() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/accounts/0xa550c18/stake_pool")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_account_tokens() {
+    let mut context = new_test_context(current_function_name!());
+    let mut account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let bytes = |s: &str| HexEncodedBytes::from(s.as_bytes().to_vec()).to_string();
+    context
+        .api_execute_txn(
+            &mut account,
+            json!({
+                "type": "script_function_payload",
+                "function": "0x1::Token::create_unlimited_collection_script",
+                "type_arguments": [],
+                "arguments": [bytes("Collection"), bytes("A collection"), bytes("https://aptos.dev")],
+            }),
+        )
+        .await;
+    context
+        .api_execute_txn(
+            &mut account,
+            json!({
+                "type": "script_function_payload",
+                "function": "0x1::Token::create_unlimited_token_script",
+                "type_arguments": [],
+                "arguments": [
+                    bytes("Collection"),
+                    bytes("Token"),
+                    bytes("A token"),
+                    true,
+                    "2",
+                    bytes("https://aptos.dev"),
+                ],
+            }),
+        )
+        .await;
+
+    let address = account.address().to_hex_literal();
+    let resp = context.get(&format!("/accounts/{}/tokens", address)).await;
+    assert_json(
+        resp,
+        json!([{
+            "id": {
+                "creator": address,
+                "collection": "Collection",
+                "name": "Token",
+            },
+            "amount": "2",
+            "data": {
+                "collection": "Collection",
+                "name": "Token",
+                "description": "A token",
+                "uri": "https://aptos.dev",
+                "maximum": null,
+                "supply": "2",
+            },
+        }]),
+    );
+
+    // Each deposit event is a page of one token
+    context
+        .api_execute_txn(
+            &mut account,
+            json!({
+                "type": "script_function_payload",
+                "function": "0x1::Token::create_unlimited_token_script",
+                "type_arguments": [],
+                "arguments": [
+                    bytes("Collection"),
+                    bytes("Other token"),
+                    bytes("Another token"),
+                    true,
+                    "1",
+                    bytes("https://aptos.dev"),
+                ],
+            }),
+        )
+        .await;
+    let path = format!("/accounts/{}/tokens?limit=1", address);
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&path))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()[X_APTOS_CURSOR], "1");
+    let tokens: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(tokens[0]["id"]["name"], "Token");
+
+    let path = format!("/accounts/{}/tokens?start=1&limit=1", address);
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&path))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get(X_APTOS_CURSOR).is_none());
+    let tokens: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(tokens.as_array().unwrap().len(), 1);
+    assert_eq!(tokens[0]["id"]["name"], "Other token");
+}
+
+#[tokio::test]
+async fn test_get_account_tokens_without_token_store() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/accounts/0xa550c18/tokens").await;
+    assert_json(resp, json!([]));
+}
+
+#[tokio::test]
+async fn test_get_account_event_handles() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/accounts/0xa550c18/event_handles").await;
+
+    let creation_numbers: Vec<u64> = resp
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|handle| handle["creation_number"].as_str().unwrap().parse().unwrap())
+        .collect();
+    assert!(creation_numbers.windows(2).all(|w| w[0] < w[1]));
+
+    let reconfiguration_events = find_value(&resp, |handle| {
+        handle["resource_type"] == "0x1::Reconfiguration::Configuration"
+    });
+    assert_json(
+        reconfiguration_events,
+        json!({
+            "resource_type": "0x1::Reconfiguration::Configuration",
+            "field": "events",
+            "key": "0x0500000000000000000000000000000000000000000000000000000000000000000000000a550c18",
+            "creation_number": "5",
+            "event_type": "0x1::Reconfiguration::NewEpochEvent",
+            "counter": "1",
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_event_handles_of_account_not_found() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/accounts/0xf/event_handles")
+        .await;
+    assert_eq!(resp["aptos_error_code"], json!("account_not_found"));
+}
+
+#[tokio::test]
+async fn test_get_account_non_existence_proof() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/accounts/0xf/non_existence_proof").await;
+
+    let version: u64 = resp["version"].as_str().unwrap().parse().unwrap();
+    let ledger_info_with_signatures: LedgerInfoWithSignatures =
+        from_hex_bcs(&resp["ledger_info_with_signatures"]);
+    let proof: StateValueWithProof = from_hex_bcs(&resp["proof"]);
+    assert!(proof.value.is_none());
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        AccountAddress::from_hex_literal("0xf").unwrap(),
+        AccountResource::struct_tag(),
+    )));
+    proof
+        .verify(
+            ledger_info_with_signatures.ledger_info(),
+            version,
+            state_key,
+        )
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_account_non_existence_proof_of_existing_account() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/accounts/0xa550c18/non_existence_proof")
+        .await;
+    assert_eq!(resp["aptos_error_code"], "account_exists");
+}
+
+fn from_hex_bcs<T: DeserializeOwned>(value: &serde_json::Value) -> T {
+    let bytes: Vec<u8> = value
+        .as_str()
+        .unwrap()
+        .parse::<HexEncodedBytes>()
+        .unwrap()
+        .into();
+    bcs::from_bytes(&bytes).unwrap()
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
@@ -2,11 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{current_function_name, tests::new_test_context};
-use aptos_api_types::{new_vm_ascii_string, AsConverter, MoveConverter, MoveType};
+use aptos_api_types::{AsConverter, MoveType};
+use aptos_move_value::new_ascii_string;
 use aptos_vm::{data_cache::AsMoveResolver, move_vm_ext::MoveResolverExt};
-use move_deps::move_core_types::{
-    account_address::AccountAddress,
-    value::{MoveStruct, MoveValue as VmMoveValue},
+use move_deps::{
+    move_core_types::{
+        account_address::AccountAddress,
+        value::{MoveStruct, MoveValue as VmMoveValue},
+    },
+    move_resource_viewer::MoveValueAnnotator,
 };
 use serde::Serialize;
 use serde_json::json;
@@ -19,33 +23,32 @@ async fn test_value_conversion() {
 
     let state_view = context.latest_state_view();
     let resolver = state_view.as_move_resolver();
-    let converter = resolver.as_converter();
 
-    assert_value_conversion(&converter, "u8", 1i32, VmMoveValue::U8(1));
-    assert_value_conversion(&converter, "u64", "1", VmMoveValue::U64(1));
-    assert_value_conversion(&converter, "u128", "1", VmMoveValue::U128(1));
-    assert_value_conversion(&converter, "bool", true, VmMoveValue::Bool(true));
-    assert_value_conversion(&converter, "address", "0x1", VmMoveValue::Address(address));
+    assert_value_conversion(&resolver, "u8", 1i32, VmMoveValue::U8(1));
+    assert_value_conversion(&resolver, "u64", "1", VmMoveValue::U64(1));
+    assert_value_conversion(&resolver, "u128", "1", VmMoveValue::U128(1));
+    assert_value_conversion(&resolver, "bool", true, VmMoveValue::Bool(true));
+    assert_value_conversion(&resolver, "address", "0x1", VmMoveValue::Address(address));
     assert_value_conversion(
-        &converter,
+        &resolver,
         "0x1::ASCII::String",
         "hello",
-        new_vm_ascii_string("hello"),
+        new_ascii_string("hello"),
     );
     assert_value_conversion(
-        &converter,
+        &resolver,
         "vector<u8>",
         "0x0102",
         VmMoveValue::Vector(vec![VmMoveValue::U8(1), VmMoveValue::U8(2)]),
     );
     assert_value_conversion(
-        &converter,
+        &resolver,
         "vector<u64>",
         ["1", "2"],
         VmMoveValue::Vector(vec![VmMoveValue::U64(1), VmMoveValue::U64(2)]),
     );
     assert_value_conversion(
-        &converter,
+        &resolver,
         "0x1::GUID::ID",
         json!({"addr": "0x1", "creation_num": "1"}),
         VmMoveValue::Struct(MoveStruct::Runtime(vec![
@@ -53,14 +56,23 @@ async fn test_value_conversion() {
             VmMoveValue::Address(address),
         ])),
     );
+    assert_value_conversion(
+        &resolver,
+        "0x1::Option::Option<u64>",
+        json!({"vec": ["1"]}),
+        VmMoveValue::Struct(MoveStruct::Runtime(vec![VmMoveValue::Vector(vec![
+            VmMoveValue::U64(1),
+        ])])),
+    );
 }
 
-fn assert_value_conversion<'r, R: MoveResolverExt, V: Serialize>(
-    converter: &MoveConverter<'r, R>,
+fn assert_value_conversion<R: MoveResolverExt, V: Serialize>(
+    resolver: &R,
     json_move_type: &str,
     json_value: V,
     expected_vm_value: VmMoveValue,
 ) {
+    let converter = resolver.as_converter();
     let move_type: MoveType = serde_json::from_value(json!(json_move_type)).unwrap();
     let type_tag = move_type.try_into().unwrap();
     let vm_value = converter
@@ -72,4 +84,17 @@ fn assert_value_conversion<'r, R: MoveResolverExt, V: Serialize>(
     let move_value_back = converter.try_into_move_value(&type_tag, &vm_bytes).unwrap();
     let json_value_back = serde_json::to_value(move_value_back).unwrap();
     assert_eq!(json_value_back, json!(json_value));
+
+    // The standalone converter agrees with the API in both directions.
+    let layout = MoveValueAnnotator::new(resolver)
+        .get_type_layout_with_types(&type_tag)
+        .unwrap();
+    assert_eq!(
+        aptos_move_value::json_to_bcs(&layout, json!(json_value)).unwrap(),
+        vm_bytes
+    );
+    assert_eq!(
+        aptos_move_value::bcs_to_json(&layout, &vm_bytes).unwrap(),
+        json!(json_value)
+    );
 }
@@ -2,11 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::EventStreamMessage;
+use aptos_config::config::ApiConfig;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
+use warp::test::WsClient;
 
 static EVENT_KEY: &str =
     "0x0500000000000000000000000000000000000000000000000000000000000000000000000a550c18";
 
+static NEW_BLOCK_EVENT_KEY: &str =
+    "0x0600000000000000000000000000000000000000000000000000000000000000000000000a550c18";
+
 #[tokio::test]
 async fn test_get_events() {
     let mut context = new_test_context(current_function_name!());
@@ -130,3 +137,174 @@ async fn test_get_events_by_struct_type_has_generic_type_parameter() {
     let resp = context.expect_status_code(404).get(path.as_str()).await;
     context.check_golden_output(resp);
 }
+
+#[tokio::test]
+async fn test_get_events_by_type() {
+    let mut context = new_test_context(current_function_name!());
+    context.commit_block(&[]).await;
+    context.commit_block(&[]).await;
+
+    let events = context
+        .get("/events/by_type/0x1::Block::NewBlockEvent")
+        .await;
+    let events = events.as_array().unwrap();
+    assert!(events.len() >= 2);
+    for event in events {
+        assert_eq!(event["type"], "0x1::Block::NewBlockEvent");
+    }
+
+    // Resume after the first event.
+    let first = &events[0];
+    let next_index: u64 = first["event_index"].as_str().unwrap().parse().unwrap();
+    let resp = context
+        .get(&format!(
+            "/events/by_type/0x1::Block::NewBlockEvent?start={}&start_index={}&limit=1",
+            first["version"].as_str().unwrap(),
+            next_index + 1
+        ))
+        .await;
+    assert_eq!(resp.as_array().unwrap(), &events[1..2]);
+}
+
+#[tokio::test]
+async fn test_get_events_by_invalid_type() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::Block")
+        .await;
+}
+
+#[tokio::test]
+async fn test_stream_events_backfills_committed_events() {
+    let context = new_test_context(current_function_name!());
+    let events = context.get(format!("/events/{}", EVENT_KEY).as_str()).await;
+
+    let mut client = context
+        .connect_websocket(&format!("/events/{}/stream?start=0", EVENT_KEY))
+        .await;
+    match recv_stream_message(&mut client).await {
+        EventStreamMessage::Event { event } => {
+            assert_eq!(serde_json::to_value(event).unwrap(), events[0])
+        }
+        message => panic!("unexpected message: {:?}", message),
+    }
+    assert_eq!(
+        recv_stream_message(&mut client).await,
+        EventStreamMessage::CaughtUp {
+            sequence_number: 1.into()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_stream_events_sends_new_events_after_caught_up() {
+    let mut context = new_test_context(current_function_name!());
+    let mut client = context
+        .connect_websocket(&format!("/events/{}/stream", NEW_BLOCK_EVENT_KEY))
+        .await;
+    let next_sequence_number = loop {
+        match recv_stream_message(&mut client).await {
+            EventStreamMessage::Event { .. } => continue,
+            EventStreamMessage::CaughtUp { sequence_number } => break sequence_number,
+            message => panic!("unexpected message: {:?}", message),
+        }
+    };
+
+    context.commit_block(&[]).await;
+    match recv_stream_message(&mut client).await {
+        EventStreamMessage::Event { event } => {
+            assert_eq!(event.sequence_number, next_sequence_number);
+            assert_eq!(event.typ.to_string(), "0x1::Block::NewBlockEvent");
+        }
+        message => panic!("unexpected message: {:?}", message),
+    }
+}
+
+async fn recv_stream_message(client: &mut WsClient) -> EventStreamMessage {
+    let message = client.recv().await.unwrap();
+    serde_json::from_str(message.to_str().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_stream_events_by_event_handle() {
+    let mut context = new_test_context(current_function_name!());
+    let mut client = context
+        .connect_websocket(
+            "/accounts/0xa550c18/events/0x1::Block::BlockMetadata/new_block_events/stream",
+        )
+        .await;
+    let next_sequence_number = loop {
+        match recv_stream_message(&mut client).await {
+            EventStreamMessage::Event { .. } => continue,
+            EventStreamMessage::CaughtUp { sequence_number } => break sequence_number,
+            message => panic!("unexpected message: {:?}", message),
+        }
+    };
+
+    context.commit_block(&[]).await;
+    match recv_stream_message(&mut client).await {
+        EventStreamMessage::Event { event } => {
+            assert_eq!(event.sequence_number, next_sequence_number);
+            assert_eq!(event.typ.to_string(), "0x1::Block::NewBlockEvent");
+        }
+        message => panic!("unexpected message: {:?}", message),
+    }
+}
+
+#[tokio::test]
+async fn test_stream_events_by_type() {
+    let mut context = new_test_context(current_function_name!());
+    context.commit_block(&[]).await;
+    let mut client = context
+        .connect_websocket("/events/by_type/0x1::Block::NewBlockEvent/stream")
+        .await;
+    let caught_up_version = loop {
+        match recv_stream_message(&mut client).await {
+            EventStreamMessage::VersionedEvent { event } => {
+                assert_eq!(event.event.typ.to_string(), "0x1::Block::NewBlockEvent")
+            }
+            EventStreamMessage::CaughtUpToVersion { version } => break version,
+            message => panic!("unexpected message: {:?}", message),
+        }
+    };
+
+    context.commit_block(&[]).await;
+    match recv_stream_message(&mut client).await {
+        EventStreamMessage::VersionedEvent { event } => {
+            assert!(event.version.0 > caught_up_version.0);
+            assert_eq!(event.event.typ.to_string(), "0x1::Block::NewBlockEvent");
+        }
+        message => panic!("unexpected message: {:?}", message),
+    }
+}
+
+#[tokio::test]
+async fn test_stream_events_rejected_over_max_event_streams() {
+    let context = new_test_context(current_function_name!());
+    let mut permits = vec![];
+    while let Ok(permit) = context.context.try_acquire_event_stream() {
+        permits.push(permit);
+    }
+    assert_eq!(permits.len(), ApiConfig::default().max_event_streams);
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .path(&format!("/events/{}/stream", EVENT_KEY))
+                .header("connection", "upgrade")
+                .header("upgrade", "websocket")
+                .header("sec-websocket-version", "13")
+                .header("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="),
+        )
+        .await;
+    assert_eq!(resp.status(), 503);
+    let body: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["aptos_error_code"], "too_many_event_streams");
+
+    // A closed stream releases its permit.
+    permits.pop();
+    context
+        .connect_websocket(&format!("/events/{}/stream", EVENT_KEY))
+        .await;
+}
@@ -0,0 +1,147 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    index,
+    tests::{fixture_db::new_fixture_db, golden_output::GoldenOutputs, pretty},
+};
+use aptos_api_types::{
+    mime_types, X_APTOS_CHAIN_ID, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+};
+use aptos_config::config::ApiConfig;
+use aptos_mempool::{MempoolClientRequest, MempoolUtilization};
+use aptos_types::{
+    chain_id::ChainId,
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+};
+use futures::{channel::mpsc, StreamExt};
+use serde_json::Value;
+use std::sync::Arc;
+use storage_interface::{in_memory_db::InMemoryDb, LedgerReader};
+use warp::http::header::CONTENT_TYPE;
+
+/// Creates a context serving the API from the fixture ledger, see `new_fixture_db`.
+///
+/// The mempool behind the context accepts every transaction without validating it, and holds
+/// none, so that submissions are answered from the request alone.
+pub fn new_fixture_context(test_name: &'static str) -> FixtureContext {
+    let db = Arc::new(new_fixture_db());
+    let (mp_sender, mp_receiver) = mpsc::channel(16);
+    tokio::spawn(accept_transactions(mp_receiver));
+    FixtureContext {
+        context: Context::new(ChainId::test(), db.clone(), mp_sender, ApiConfig::default()),
+        db,
+        expect_status_code: 200,
+        test_name,
+        golden_output: None,
+    }
+}
+
+#[derive(Clone)]
+pub struct FixtureContext {
+    pub context: Context,
+    pub db: Arc<InMemoryDb>,
+    expect_status_code: u16,
+    test_name: &'static str,
+    golden_output: Option<GoldenOutputs>,
+}
+
+impl FixtureContext {
+    pub fn check_golden_output(&mut self, msg: Value) {
+        if self.golden_output.is_none() {
+            self.golden_output = Some(GoldenOutputs::new(self.test_name.replace(':', "_")));
+        }
+        self.golden_output.as_ref().unwrap().log_json(&msg);
+    }
+
+    pub fn expect_status_code(&self, status_code: u16) -> FixtureContext {
+        let mut ret = self.clone();
+        ret.expect_status_code = status_code;
+        ret
+    }
+
+    pub async fn get(&self, path: &str) -> Value {
+        self.execute(warp::test::request().method("GET").path(path))
+            .await
+    }
+
+    pub async fn post(&self, path: &str, body: Value) -> Value {
+        self.execute(warp::test::request().method("POST").path(path).json(&body))
+            .await
+    }
+
+    pub async fn post_bcs_txn(&self, path: &str, body: impl AsRef<[u8]>) -> Value {
+        self.execute(
+            warp::test::request()
+                .method("POST")
+                .path(path)
+                .header(CONTENT_TYPE, mime_types::BCS_SIGNED_TRANSACTION)
+                .body(body),
+        )
+        .await
+    }
+
+    pub async fn execute(&self, req: warp::test::RequestBuilder) -> Value {
+        let resp = req.reply(&index::routes(self.context.clone())).await;
+
+        let headers = resp.headers();
+        assert_eq!(headers[CONTENT_TYPE], mime_types::JSON);
+
+        let body = serde_json::from_slice(resp.body()).expect("response body is JSON");
+        assert_eq!(
+            self.expect_status_code,
+            resp.status(),
+            "\nresponse: {}",
+            pretty(&body)
+        );
+
+        if self.expect_status_code < 300 {
+            let ledger_info = self.db.get_latest_ledger_info().unwrap();
+            assert_eq!(headers[X_APTOS_CHAIN_ID], "4");
+            assert_eq!(
+                headers[X_APTOS_LEDGER_VERSION],
+                ledger_info.ledger_info().version().to_string()
+            );
+            assert_eq!(
+                headers[X_APTOS_LEDGER_TIMESTAMP],
+                ledger_info.ledger_info().timestamp_usecs().to_string()
+            );
+        }
+
+        body
+    }
+}
+
+/// Answers mempool requests as an empty mempool accepting every transaction would.
+async fn accept_transactions(mut requests: mpsc::Receiver<MempoolClientRequest>) {
+    let accepted = || Ok((MempoolStatus::new(MempoolStatusCode::Accepted), None));
+    while let Some(request) = requests.next().await {
+        // the API may stop waiting for an answer, e.g. on a timeout
+        match request {
+            MempoolClientRequest::SubmitTransaction(_, callback) => {
+                let _ = callback.send(accepted());
+            }
+            MempoolClientRequest::SubmitTransactionBatch(txns) => {
+                for (_, callback) in txns {
+                    let _ = callback.send(accepted());
+                }
+            }
+            MempoolClientRequest::GetTransactionByHash(_, callback) => {
+                let _ = callback.send(None);
+            }
+            MempoolClientRequest::GetPendingSequenceNumbers(_, callback) => {
+                let _ = callback.send(vec![]);
+            }
+            MempoolClientRequest::GetUtilization(callback) => {
+                let _ = callback.send(MempoolUtilization {
+                    size: 0,
+                    capacity: 1,
+                });
+            }
+            MempoolClientRequest::GetSnapshot(callback) => {
+                let _ = callback.send(vec![]);
+            }
+        }
+    }
+}
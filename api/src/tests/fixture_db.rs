@@ -0,0 +1,143 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::{aptos_root_address, AccountResource},
+    block_info::BlockInfo,
+    block_metadata::{new_block_event_key, BlockMetadata, NewBlockEvent},
+    contract_event::ContractEvent,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{ExecutionStatus, Transaction, TransactionInfo, TransactionToCommit},
+    write_set::WriteSet,
+};
+use move_deps::{
+    move_binary_format::{access::ModuleAccess, CompiledModule},
+    move_core_types::{
+        identifier::Identifier,
+        language_storage::{StructTag, TypeTag},
+        move_resource::MoveResource,
+    },
+};
+use std::collections::HashMap;
+use storage_interface::{in_memory_db::InMemoryDb, DbWriter, LedgerReader};
+
+/// Epoch of every block in the fixture ledger.
+pub const FIXTURE_EPOCH: u64 = 1;
+
+/// Block timestamps of the fixture ledger, one block metadata transaction per block.
+pub const FIXTURE_BLOCK_TIMESTAMPS: [u64; 2] = [1_000_000, 2_000_000];
+
+/// Creates an `InMemoryDb` holding a small, fixed ledger: the framework modules, the root account
+/// and one block metadata transaction per entry of `FIXTURE_BLOCK_TIMESTAMPS`.
+///
+/// Unlike AptosDB bootstrapped from genesis, nothing is executed, so the responses built from it
+/// only change when the fixture (or the API) changes. The state is committed along with the first
+/// block but left out of its write set, which keeps the framework modules out of the transaction
+/// responses.
+pub fn new_fixture_db() -> InMemoryDb {
+    let mut state = HashMap::new();
+    for blob in cached_framework_packages::module_blobs() {
+        let module = CompiledModule::deserialize(blob).expect("framework module is valid");
+        state.insert(
+            StateKey::AccessPath(AccessPath::code_access_path(module.self_id())),
+            StateValue::from(blob.clone()),
+        );
+    }
+    let root = AccountResource::new(2, vec![1; 32], aptos_root_address());
+    state.insert(
+        StateKey::AccessPath(AccessPath::new(
+            aptos_root_address(),
+            AccountResource::resource_path(),
+        )),
+        StateValue::from(bcs::to_bytes(&root).unwrap()),
+    );
+
+    let txns_to_commit: Vec<_> = FIXTURE_BLOCK_TIMESTAMPS
+        .iter()
+        .enumerate()
+        .map(|(i, timestamp)| {
+            let state_updates = if i == 0 {
+                std::mem::take(&mut state)
+            } else {
+                HashMap::new()
+            };
+            new_block(i as u64 + 1, *timestamp, state_updates)
+        })
+        .collect();
+
+    let db = InMemoryDb::new();
+    db.save_transactions(&txns_to_commit, 0, None)
+        .expect("fixture transactions are committed");
+
+    let version = txns_to_commit.len() as u64 - 1;
+    let ledger_info = LedgerInfo::new(
+        BlockInfo::new(
+            FIXTURE_EPOCH,
+            txns_to_commit.len() as u64,
+            HashValue::zero(),
+            db.get_accumulator_root_hash(version).unwrap(),
+            version,
+            *FIXTURE_BLOCK_TIMESTAMPS.last().unwrap(),
+            None,
+        ),
+        HashValue::zero(),
+    );
+    db.save_ledger_infos(&[LedgerInfoWithSignatures::new(
+        ledger_info,
+        Default::default(),
+    )])
+    .expect("fixture ledger info is saved");
+    db
+}
+
+/// Builds the block metadata transaction of the `round`-th block, ready to be committed. The
+/// transaction emits a `NewBlockEvent` and has an empty write set.
+fn new_block(
+    round: u64,
+    timestamp: u64,
+    state_updates: HashMap<StateKey, StateValue>,
+) -> TransactionToCommit {
+    let proposer = aptos_root_address();
+    let votes = vec![true];
+    let event = NewBlockEvent::new(FIXTURE_EPOCH, round, votes.clone(), proposer, timestamp);
+    let event = ContractEvent::new(
+        new_block_event_key(),
+        round - 1,
+        TypeTag::Struct(StructTag {
+            address: AccountAddress::ONE,
+            module: Identifier::new("Block").unwrap(),
+            name: Identifier::new("NewBlockEvent").unwrap(),
+            type_params: vec![],
+        }),
+        bcs::to_bytes(&event).unwrap(),
+    );
+
+    let txn = Transaction::BlockMetadata(BlockMetadata::new(
+        HashValue::new([round as u8; HashValue::LENGTH]),
+        FIXTURE_EPOCH,
+        round,
+        votes,
+        proposer,
+        timestamp,
+    ));
+    let info = TransactionInfo::new(
+        txn.hash(),
+        HashValue::zero(),
+        HashValue::zero(),
+        Some(HashValue::zero()),
+        0,
+        ExecutionStatus::Success,
+    );
+    TransactionToCommit::new(
+        txn,
+        info,
+        state_updates,
+        None,
+        WriteSet::default(),
+        vec![event],
+    )
+}
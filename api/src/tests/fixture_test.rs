@@ -0,0 +1,235 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::fixture_context::new_fixture_context};
+use aptos_api_types::HashValue;
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
+};
+use aptos_types::{
+    chain_id::ChainId,
+    transaction::{RawTransaction, ScriptFunction, SignedTransaction},
+};
+use move_deps::move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+};
+use serde_json::{json, Value};
+use std::convert::TryFrom;
+use storage_interface::LedgerReader;
+
+// The new block event handle of the root account: creation number 6 at 0xa550c18.
+const NEW_BLOCK_EVENT_KEY: &str =
+    "0x0600000000000000000000000000000000000000000000000000000000000000000000000a550c18";
+
+// The fixture mempool does not verify signatures, any well formed key and signature do.
+const PUBLIC_KEY: &str = "b9c6ee1630ef3e711144a648db06bbb2284f7274cfbee53ffcee503cc1a49200";
+const SIGNATURE: &str = "368cd71ddb9e8710823f1fb4feeaa2fe3cf814e56c1b151161157ce8090822809344962b8f10ae141387484aca5c5ffd32a6ddb18973b37c7c847ebdf7493504";
+
+/// The root account creating account 0x2, as a `UserTransactionRequest` without signature.
+fn create_account_request() -> Value {
+    json!({
+        "sender": "0xa550c18",
+        "sequence_number": "0",
+        "max_gas_amount": "2000",
+        "gas_unit_price": "0",
+        "expiration_timestamp_secs": u64::MAX.to_string(),
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::Account::create_account",
+            "type_arguments": [],
+            "arguments": ["0x2"],
+        },
+    })
+}
+
+/// The transaction of `create_account_request`, signed with `SIGNATURE`.
+fn create_account_transaction() -> SignedTransaction {
+    let raw_txn = RawTransaction::new_script_function(
+        AccountAddress::from_hex_literal("0xa550c18").unwrap(),
+        0,
+        ScriptFunction::new(
+            ModuleId::new(AccountAddress::ONE, Identifier::new("Account").unwrap()),
+            Identifier::new("create_account").unwrap(),
+            vec![],
+            vec![bcs::to_bytes(&AccountAddress::from_hex_literal("0x2").unwrap()).unwrap()],
+        ),
+        2000,
+        0,
+        u64::MAX,
+        ChainId::test(),
+    );
+    SignedTransaction::new(
+        raw_txn,
+        Ed25519PublicKey::try_from(hex::decode(PUBLIC_KEY).unwrap().as_slice()).unwrap(),
+        Ed25519Signature::try_from(hex::decode(SIGNATURE).unwrap().as_slice()).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_get_index() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context.get("/").await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_account() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context.get("/accounts/0xa550c18").await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_account_not_found() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context.expect_status_code(404).get("/accounts/0x2").await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_account_resources() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context.get("/accounts/0xa550c18/resources").await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_account_resources_by_ledger_version_is_too_large() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/accounts/0xa550c18/resources?version=2")
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_account_resource() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context
+        .get("/accounts/0xa550c18/resource/0x1::Account::Account")
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_account_resource_not_found() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/accounts/0x2/resource/0x1::Account::Account")
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_transactions() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context.get("/transactions").await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_version() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context.get("/transactions/1").await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_version_not_found() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context.expect_status_code(404).get("/transactions/2").await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_hash() {
+    let mut context = new_fixture_context(current_function_name!());
+    let txn = context.db.get_transaction_by_version(0, 1, false).unwrap();
+    let hash = HashValue::from(txn.transaction.hash());
+    let resp = context.get(&format!("/transactions/{}", hash)).await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_get_events_by_event_key() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context
+        .get(&format!("/events/{}", NEW_BLOCK_EVENT_KEY))
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_create_signing_message() {
+    let mut context = new_fixture_context(current_function_name!());
+    let resp = context
+        .post("/transactions/signing_message", create_account_request())
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_create_signing_message_rejects_invalid_json() {
+    let mut context = new_fixture_context(current_function_name!());
+    let req = warp::test::request()
+        .header("content-type", "application/json")
+        .method("POST")
+        .body("invalid json")
+        .path("/transactions/signing_message");
+    let resp = context.expect_status_code(400).execute(req).await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_submit_json_transaction() {
+    let mut context = new_fixture_context(current_function_name!());
+    let mut req = create_account_request();
+    req["signature"] = json!({
+        "type": "ed25519_signature",
+        "public_key": format!("0x{}", PUBLIC_KEY),
+        "signature": format!("0x{}", SIGNATURE),
+    });
+    let resp = context
+        .expect_status_code(202)
+        .post("/transactions", req)
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_submit_bcs_transaction() {
+    let mut context = new_fixture_context(current_function_name!());
+    let body = bcs::to_bytes(&create_account_transaction()).unwrap();
+    let resp = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", body)
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_invalid_json() {
+    let mut context = new_fixture_context(current_function_name!());
+    let req = warp::test::request()
+        .header("content-type", "application/json")
+        .method("POST")
+        .body("invalid json")
+        .path("/transactions");
+    let resp = context.expect_status_code(400).execute(req).await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_invalid_content_type() {
+    let mut context = new_fixture_context(current_function_name!());
+    let req = warp::test::request()
+        .header("content-type", "invalid")
+        .method("POST")
+        .body("text")
+        .path("/transactions");
+    let resp = context.expect_status_code(415).execute(req).await;
+    context.check_golden_output(resp);
+}
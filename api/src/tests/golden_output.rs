@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::tests::pretty;
 use goldenfile::Mint;
+use serde_json::Value;
 use std::{
     fmt::Debug,
     fs::File,
@@ -43,6 +45,14 @@ impl GoldenOutputs {
     pub fn log(&self, msg: &str) {
         self.file.lock().unwrap().write_all(msg.as_bytes()).unwrap();
     }
+
+    /// Logs the pretty printed `msg`, with all hashes blanked out as they are not stable across
+    /// runs.
+    pub fn log_json(&self, msg: &Value) {
+        let msg = pretty(msg);
+        let re = regex::Regex::new("hash\": \".*\"").unwrap();
+        self.log(&re.replace_all(&msg, "hash\": \"\""));
+    }
 }
 
 impl Debug for GoldenOutputs {
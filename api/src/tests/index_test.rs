@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::X_APTOS_MIN_VERSION;
+use aptos_config::config::{ApiConfig, EndpointGroup};
 use serde_json::json;
 
 #[tokio::test]
@@ -11,6 +13,143 @@ async fn test_get_ledger_info() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_wait_for_committed_version() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let resp = context
+        .get(&format!("/ledger_info?wait_for_version={}", version))
+        .await;
+    assert_eq!(resp, context.get("/").await);
+}
+
+#[tokio::test]
+async fn test_wait_for_version_until_committed() {
+    let mut context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let waiter = context.clone();
+    let (resp, _) = tokio::join!(
+        waiter.get(&format!(
+            "/ledger_info?wait_for_version={}&timeout_ms=10000",
+            version + 1
+        )),
+        async {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            context.commit_block(&[]).await;
+        }
+    );
+    assert_eq!(resp["ledger_version"], json!((version + 1).to_string()));
+}
+
+#[tokio::test]
+async fn test_wait_for_version_times_out() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let resp = context
+        .get(&format!(
+            "/ledger_info?wait_for_version={}&timeout_ms=100",
+            version + 1
+        ))
+        .await;
+    assert_eq!(resp["ledger_version"], json!(version.to_string()));
+}
+
+#[tokio::test]
+async fn test_wait_for_version_timeout_exceeds_limit() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/ledger_info?wait_for_version=1&timeout_ms=60001")
+        .await;
+    assert_eq!(
+        resp["message"],
+        json!("invalid parameter timeout_ms: 60001, exceed limit 60000")
+    );
+}
+
+#[tokio::test]
+async fn test_read_with_reached_min_version() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions")
+                .header(X_APTOS_MIN_VERSION, version.to_string()),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+}
+
+#[tokio::test]
+async fn test_read_with_min_version_ahead_of_ledger() {
+    let context = new_test_context(current_function_name!());
+    let version = context.get_latest_ledger_info().version();
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions")
+                .header(X_APTOS_MIN_VERSION, (version + 1).to_string()),
+        )
+        .await;
+    assert_eq!(resp.status(), 503);
+    assert_eq!(resp.headers()["retry-after"], "1");
+    let body: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(body["aptos_error_code"], json!("ledger_version_behind"));
+    assert_eq!(body["aptos_ledger_version"], json!(version.to_string()));
+}
+
+#[tokio::test]
+async fn test_read_with_invalid_min_version() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/")
+                .header(X_APTOS_MIN_VERSION, "latest"),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test]
+async fn test_disabled_endpoint_group() {
+    let context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        disabled_endpoint_groups: vec![EndpointGroup::TransactionSubmission],
+        ..ApiConfig::default()
+    });
+
+    let resp = context
+        .expect_status_code(403)
+        .post("/transactions", json!({}))
+        .await;
+    assert_eq!(resp["aptos_error_code"], json!("endpoint_disabled"));
+    assert_eq!(
+        resp["aptos_error_details"],
+        json!({"endpoint_group": "transaction_submission"})
+    );
+    // The other endpoints of the same path are still served.
+    context.get("/transactions").await;
+    context.expect_status_code(404).get("/invalid_path").await;
+}
+
+#[tokio::test]
+async fn test_reenabled_endpoint_group() {
+    let context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        disabled_endpoint_groups: vec![EndpointGroup::Accounts],
+        ..ApiConfig::default()
+    });
+    context.expect_status_code(403).get("/accounts/0x1").await;
+
+    context.context.update_api_config(ApiConfig::default());
+    context.get("/accounts/0x1").await;
+}
+
 #[tokio::test]
 async fn test_returns_not_found_for_the_invalid_path() {
     let mut context = new_test_context(current_function_name!());
@@ -48,6 +187,39 @@ async fn test_openapi_spec() {
     }
 }
 
+#[test]
+fn test_openapi_spec_refs_resolve() {
+    let spec: serde_yaml::Value =
+        serde_yaml::from_str(include_str!("../../doc/openapi.yaml")).unwrap();
+    let mut refs = vec![];
+    collect_refs(&spec, &mut refs);
+    assert!(!refs.is_empty());
+    for reference in refs {
+        let path = reference
+            .strip_prefix("#/")
+            .unwrap_or_else(|| panic!("Not a local $ref: {}", reference));
+        let target = path.split('/').try_fold(&spec, |value, key| value.get(key));
+        assert!(target.is_some(), "Unresolved $ref: {}", reference);
+    }
+}
+
+fn collect_refs(value: &serde_yaml::Value, refs: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            for (key, value) in mapping {
+                match (key.as_str(), value.as_str()) {
+                    (Some("$ref"), Some(reference)) => refs.push(reference.to_owned()),
+                    _ => collect_refs(value, refs),
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(values) => {
+            values.iter().for_each(|value| collect_refs(value, refs))
+        }
+        _ => (),
+    }
+}
+
 #[tokio::test]
 async fn test_cors() {
     let context = new_test_context(current_function_name!());
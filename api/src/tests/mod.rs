@@ -1,9 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod abort_codes_test;
 mod accounts_test;
 mod converter_test;
 mod events_test;
+mod fixture_context;
+mod fixture_db;
+mod fixture_test;
 mod golden_output;
 mod index_test;
 mod invalid_post_request_test;
@@ -11,6 +15,7 @@ mod state_test;
 mod string_resource_test;
 mod test_context;
 mod transactions_test;
+mod validators_test;
 
 use serde_json::Value;
 pub use test_context::{new_test_context, TestContext};
@@ -3,10 +3,16 @@
 
 use crate::{
     current_function_name,
-    tests::{new_test_context, TestContext},
+    tests::{assert_json, new_test_context, TestContext},
 };
+use aptos_api_types::HexEncodedBytes;
 use aptos_sdk::types::LocalAccount;
-use move_deps::{move_core_types::account_address::AccountAddress, move_package::BuildConfig};
+use move_deps::{
+    move_binary_format::{access::ModuleAccess, file_format::Bytecode},
+    move_compiler::compiled_unit::CompiledUnit,
+    move_core_types::{account_address::AccountAddress, parser::parse_struct_tag},
+    move_package::{compilation::compiled_package::CompiledPackage, BuildConfig},
+};
 use serde::Serialize;
 use serde_json::{json, Value};
 use std::{convert::TryInto, path::PathBuf};
@@ -20,6 +26,26 @@ async fn test_get_account_resource() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_resource_with_fields() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .get(&format!(
+            "{}?fields=type,data.sequence_number",
+            get_account_resource("0xA550C18", "0x1::Account::Account")
+        ))
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "type": "0x1::Account::Account",
+            "data": {
+                "sequence_number": "0",
+            },
+        }),
+    );
+}
+
 #[tokio::test]
 async fn test_get_account_resource_by_invalid_address() {
     let mut context = new_test_context(current_function_name!());
@@ -176,6 +202,380 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test]
+async fn test_get_coin_info() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/coins/0x1::TestCoin::TestCoin").await;
+    assert_json(
+        resp,
+        json!({
+            "coin_type": "0x1::TestCoin::TestCoin",
+            "name": "Test Coin",
+            "symbol": "TC",
+            "decimals": "6",
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_coin_info_not_found() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/coins/0x1::TestCoin::NoCoin")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_coin_supply_not_tracked() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/coins/0x1::TestCoin::TestCoin/supply")
+        .await;
+    assert_eq!(
+        resp["message"],
+        json!("Coin supply not found by 0x1::TestCoin::TestCoin")
+    );
+}
+
+#[tokio::test]
+async fn test_get_coin_supply() {
+    let mut context = new_test_context(current_function_name!());
+    let mut account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let module = build_test_module(account.address(), "SupplyTestCoin").await;
+    context
+        .api_publish_module(&mut account, module.try_into().unwrap())
+        .await;
+    context
+        .api_execute_script_function(
+            &mut account,
+            "SupplyTestCoin::initialize_and_mint",
+            json!([]),
+            json!(["1000"]),
+        )
+        .await;
+
+    let coin_type = format!(
+        "{}::SupplyTestCoin::SupplyTestCoin",
+        account.address().to_hex_literal()
+    );
+    let resp = context.get(&format!("/coins/{}/supply", coin_type)).await;
+    assert_json(
+        resp,
+        json!({
+            "coin_type": coin_type,
+            "supply": "1000",
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_coin_supply_coin_not_found() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get("/coins/0x1::TestCoin::NoCoin/supply")
+        .await;
+    assert_eq!(
+        resp["message"],
+        json!("Coin not found by 0x1::TestCoin::NoCoin")
+    );
+}
+
+#[tokio::test]
+async fn test_get_gas_schedule() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/gas_schedule").await;
+    assert_eq!(
+        resp["gas_constants"]["max_transaction_size_in_bytes"],
+        json!("262144")
+    );
+    assert!(!resp["instruction_table"].as_array().unwrap().is_empty());
+    assert!(!resp["native_table"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_get_script_function_allow_list_not_published() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/script_function_allow_list").await;
+    assert_eq!(
+        resp,
+        json!({
+            "enabled": false,
+            "allowed_functions": [],
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_get_features_not_published() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/features").await;
+    assert_eq!(resp, json!({ "enabled_features": [] }));
+}
+
+#[tokio::test]
+async fn test_get_state_diff() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context.get("/state_diff?first_version=0").await;
+    assert_eq!(resp["first_version"], json!("0"));
+    assert_eq!(resp["last_version"], json!("2"));
+    let address = account.address().to_hex_literal();
+    let changes = resp["changes"].as_array().unwrap();
+    assert!(changes.iter().any(|change| {
+        change["type"] == "write_resource"
+            && change["address"] == json!(address)
+            && change["data"]["type"] == "0x1::Account::Account"
+    }));
+
+    // nothing changed after the latest version
+    let resp = context.get("/state_diff?first_version=2").await;
+    assert_eq!(resp["changes"], json!([]));
+}
+
+#[tokio::test]
+async fn test_get_state_diff_with_invalid_range() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/state_diff?first_version=1&last_version=0")
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": "invalid parameter first_version: 1, greater than last version 0",
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_published_module() {
+    let context = new_test_context(current_function_name!());
+    let module = context.get(&get_account_module("0x1", "GUID")).await;
+    let resp = context
+        .post("/modules/verify", json!({ "bytecode": module["bytecode"] }))
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "module": "0x1::GUID",
+            "verified": true,
+            "errors": [],
+            "compatibility": {
+                "struct_and_function_linking": true,
+                "struct_layout": true,
+            },
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_verify_unpublished_module() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let module = build_test_module(account.address(), "TableTestData").await;
+    let resp = context
+        .post(
+            "/modules/verify",
+            json!({ "bytecode": HexEncodedBytes::from(module).to_string() }),
+        )
+        .await;
+    assert_eq!(resp["verified"], json!(true));
+    assert_eq!(resp["errors"], json!([]));
+    assert_eq!(resp["compatibility"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_verify_invalid_module_bytecode() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .post("/modules/verify", json!({ "bytecode": "0x0102" }))
+        .await;
+    assert_eq!(resp["module"], Value::Null);
+    assert_eq!(resp["verified"], json!(false));
+    assert_eq!(resp["errors"][0]["status_code"], json!("BAD_MAGIC"));
+}
+
+#[tokio::test]
+async fn test_verify_module_error_source_location() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let package = build_test_package(account.address());
+    let unit = package
+        .root_compiled_units
+        .iter()
+        .find(|unit| {
+            matches!(&unit.unit, CompiledUnit::Module(named) if named.name.as_str() == "TableTestData")
+        })
+        .unwrap();
+    let (mut module, source_map) = match &unit.unit {
+        CompiledUnit::Module(named) => (named.module.clone(), named.source_map.clone()),
+        CompiledUnit::Script(_) => panic!("The test package only has modules"),
+    };
+    let source = std::fs::read_to_string(&unit.source_path).unwrap();
+
+    // popping the empty stack at the start of make_test_tables fails verification
+    let function = module
+        .function_defs()
+        .iter()
+        .position(|def| {
+            let handle = module.function_handle_at(def.function);
+            module.identifier_at(handle.name).as_str() == "make_test_tables"
+        })
+        .unwrap();
+    module.function_defs[function].code.as_mut().unwrap().code[0] = Bytecode::Pop;
+    let mut bytecode = vec![];
+    module.serialize(&mut bytecode).unwrap();
+
+    let resp = context
+        .post(
+            "/modules/verify",
+            json!({
+                "bytecode": HexEncodedBytes::from(bytecode).to_string(),
+                "source_map": HexEncodedBytes::from(bcs::to_bytes(&source_map).unwrap()).to_string(),
+                "source": source,
+            }),
+        )
+        .await;
+    assert_eq!(resp["verified"], json!(false));
+    let error = &resp["errors"][0];
+    assert_eq!(
+        error["status_code"],
+        json!("NEGATIVE_STACK_SIZE_WITHIN_BLOCK")
+    );
+    let function_line = source
+        .lines()
+        .position(|line| line.contains("fun make_test_tables"))
+        .unwrap() as u64
+        + 1;
+    let line = error["source_location"]["line"].as_u64().unwrap();
+    assert!(line >= function_line, "{} < {}", line, function_line);
+}
+
+#[tokio::test]
+async fn test_verify_module_invalid_source_map() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let module = build_test_module(account.address(), "TableTestData").await;
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/modules/verify",
+            json!({
+                "bytecode": HexEncodedBytes::from(module).to_string(),
+                "source_map": "0x0102",
+            }),
+        )
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("invalid source map"));
+}
+
+#[tokio::test]
+async fn test_republished_module_evicts_resource_layouts() {
+    let mut context = new_test_context(current_function_name!());
+    let ctx = &mut context;
+    let mut account = ctx.gen_account();
+    let txn = ctx.create_user_account(&account);
+    ctx.commit_block(&vec![txn]).await;
+    make_test_tables(ctx, &mut account).await;
+
+    let resource = format!(
+        "{}::TableTestData::TestTables",
+        account.address().to_hex_literal()
+    );
+    let struct_tag = parse_struct_tag(&resource).unwrap();
+    let tables = ctx
+        .api_get_account_resource(&account, resource.clone())
+        .await;
+    let version = ctx.get_latest_ledger_info().version();
+    let layouts = ctx.context.layout_cache(version).unwrap().unwrap();
+    assert!(layouts.get(&struct_tag).is_some());
+
+    let module = build_test_module(account.address(), "TableTestData").await;
+    ctx.api_publish_module(&mut account, module.try_into().unwrap())
+        .await;
+    let version = ctx.get_latest_ledger_info().version();
+    let layouts = ctx.context.layout_cache(version).unwrap().unwrap();
+    assert!(layouts.get(&struct_tag).is_none());
+
+    assert_eq!(
+        ctx.api_get_account_resource(&account, resource).await,
+        tables
+    );
+    assert!(layouts.get(&struct_tag).is_some());
+}
+
+#[tokio::test]
+async fn test_get_module_history() {
+    let mut context = new_test_context(current_function_name!());
+    let ctx = &mut context;
+    let mut account = ctx.gen_account();
+    let txn = ctx.create_user_account(&account);
+    ctx.commit_block(&vec![txn]).await;
+
+    let module = build_test_module(account.address(), "TableTestData").await;
+    ctx.api_publish_module(&mut account, module.clone().try_into().unwrap())
+        .await;
+    let published = ctx.get_latest_ledger_info().version();
+    ctx.api_publish_module(&mut account, module.try_into().unwrap())
+        .await;
+    let upgraded = ctx.get_latest_ledger_info().version();
+
+    let address = account.address().to_hex_literal();
+    let history = ctx
+        .get(&get_module_history(&address, "TableTestData"))
+        .await;
+    let history = history.as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["kind"], json!("publish"));
+    assert_eq!(history[0]["version"], json!(published.to_string()));
+    assert_eq!(history[0]["sender"], json!(address));
+    assert_eq!(history[1]["kind"], json!("upgrade"));
+    assert_eq!(history[1]["version"], json!(upgraded.to_string()));
+
+    let page = ctx
+        .get(&format!(
+            "{}?start={}&limit=1",
+            get_module_history(&address, "TableTestData"),
+            published + 1
+        ))
+        .await;
+    assert_eq!(page.as_array().unwrap().len(), 1);
+    assert_eq!(page[0]["kind"], json!("upgrade"));
+}
+
+#[tokio::test]
+async fn test_get_genesis_module_history() {
+    let context = new_test_context(current_function_name!());
+    let history = context.get(&get_module_history("0x1", "GUID")).await;
+    assert_eq!(history.as_array().unwrap().len(), 1);
+    assert_eq!(history[0]["kind"], json!("publish"));
+    assert_eq!(history[0]["version"], json!("0"));
+    assert_eq!(history[0]["sender"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_get_module_history_not_found() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(404)
+        .get(&get_module_history("0x1", "NotExist"))
+        .await;
+    assert_eq!(resp["code"], json!(404));
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -184,12 +584,16 @@ fn get_account_module(address: &str, name: &str) -> String {
     format!("/accounts/{}/module/{}", address, name)
 }
 
+fn get_module_history(address: &str, name: &str) -> String {
+    format!("/modules/{}/{}/history", address, name)
+}
+
 fn get_table_item(handle: u128) -> String {
     format!("/tables/{}/item", handle)
 }
 
 async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
-    let module = build_test_module(account.address()).await;
+    let module = build_test_module(account.address(), "TableTestData").await;
 
     ctx.api_publish_module(account, module.try_into().unwrap())
         .await;
@@ -202,7 +606,20 @@ async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
     .await
 }
 
-async fn build_test_module(account: AccountAddress) -> Vec<u8> {
+async fn build_test_module(account: AccountAddress, name: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    build_test_package(account)
+        .root_modules_map()
+        .iter_modules()
+        .into_iter()
+        .find(|module| module.self_id().name().as_str() == name)
+        .unwrap()
+        .serialize(&mut out)
+        .unwrap();
+    out
+}
+
+fn build_test_package(account: AccountAddress) -> CompiledPackage {
     let package_dir = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"))
         .parent()
         .unwrap()
@@ -213,19 +630,9 @@ async fn build_test_module(account: AccountAddress) -> Vec<u8> {
         additional_named_addresses: [("TestAccount".to_string(), account)].into(),
         ..Default::default()
     };
-    let package = build_config
+    build_config
         .compile_package(&package_dir, &mut Vec::new())
-        .unwrap();
-
-    let mut out = Vec::new();
-    package
-        .root_modules_map()
-        .iter_modules()
-        .first()
         .unwrap()
-        .serialize(&mut out)
-        .unwrap();
-    out
 }
 
 async fn api_get_table_item<T: Serialize>(
@@ -35,7 +35,7 @@ use executor::db_bootstrapper;
 use executor_types::BlockExecutorTrait;
 use hyper::Response;
 use mempool_notifications::MempoolNotificationSender;
-use storage_interface::DbReaderWriter;
+use storage_interface::{DbReaderWriter, LedgerReader};
 
 use crate::tests::golden_output::GoldenOutputs;
 use executor::block_executor::BlockExecutor;
@@ -129,11 +129,7 @@ impl TestContext {
             self.golden_output = Some(GoldenOutputs::new(self.test_name.replace(':', "_")));
         }
 
-        let msg = pretty(&msg);
-        let re = regex::Regex::new("hash\": \".*\"").unwrap();
-        let msg = re.replace_all(&msg, "hash\": \"\"");
-
-        self.golden_output.as_ref().unwrap().log(&msg);
+        self.golden_output.as_ref().unwrap().log_json(&msg);
     }
 
     pub fn rng(&mut self) -> &mut rand::rngs::StdRng {
@@ -191,12 +187,16 @@ impl TestContext {
     }
 
     pub fn get_latest_ledger_info(&self) -> aptos_api_types::LedgerInfo {
-        self.context.get_latest_ledger_info().unwrap()
+        aptos_api_types::LedgerInfo::new(
+            &self.context.chain_id(),
+            &self.db.get_latest_ledger_info().unwrap(),
+        )
     }
 
-    pub fn get_transactions(&self, start: u64, limit: u16) -> Vec<TransactionOnChainData> {
+    pub async fn get_transactions(&self, start: u64, limit: u16) -> Vec<TransactionOnChainData> {
         self.context
             .get_transactions(start, limit, self.get_latest_ledger_info().version())
+            .await
             .unwrap()
     }
 
@@ -355,6 +355,14 @@ impl TestContext {
         .await
     }
 
+    pub async fn connect_websocket(&self, path: &str) -> warp::test::WsClient {
+        warp::test::ws()
+            .path(path)
+            .handshake(index::routes(self.context.clone()))
+            .await
+            .unwrap()
+    }
+
     pub async fn reply(&self, req: warp::test::RequestBuilder) -> Response<Bytes> {
         req.reply(&index::routes(self.context.clone())).await
     }
@@ -403,10 +411,7 @@ impl TestContext {
         root_hash: HashValue,
         block_size: usize,
     ) -> LedgerInfoWithSignatures {
-        let parent = self
-            .context
-            .get_latest_ledger_info_with_signatures()
-            .unwrap();
+        let parent = self.db.get_latest_ledger_info().unwrap();
         let epoch = parent.ledger_info().epoch();
         let version = parent.ledger_info().version() + (block_size as u64);
         let info = LedgerInfo::new(
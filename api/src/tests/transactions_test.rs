@@ -6,15 +6,20 @@ use crate::{
     tests::{assert_json, new_test_context, pretty, TestContext},
 };
 
-use aptos_api_types::HexEncodedBytes;
+use aptos_api_types::{
+    mime_types, HexEncodedBytes, X_APTOS_CURSOR, X_APTOS_MIN_VERSION, X_APTOS_TOTAL_ITEMS,
+};
+use aptos_config::config::{ApiConfig, GasPriceFloorStep};
 use aptos_crypto::{
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
     PrivateKey, SigningKey, Uniform,
 };
-use aptos_sdk::types::LocalAccount;
+use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_types::{
     access_path::{AccessPath, Path},
     account_address::AccountAddress,
+    chain_id::ChainId,
+    mempool_status::MempoolStatusCode,
     transaction::{
         authenticator::{AuthenticationKey, TransactionAuthenticator},
         ChangeSet, Script, ScriptFunction, SignedTransaction,
@@ -30,7 +35,8 @@ use move_deps::move_core_types::{
     language_storage::{ModuleId, StructTag, TypeTag},
 };
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
-use serde_json::json;
+use serde_json::{json, Value};
+use warp::http::header::CONTENT_TYPE;
 
 #[tokio::test]
 async fn test_deserialize_genesis_transaction() {
@@ -113,6 +119,108 @@ async fn test_get_transactions_param_limit_exceeds_limit() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_transactions_limit_exceeds_configured_max_page_size() {
+    let context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        max_transactions_page_size: 10,
+        ..ApiConfig::default()
+    });
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?limit=11")
+        .await;
+    assert_eq!(
+        resp["message"],
+        json!("invalid parameter limit: 11, exceed limit 10")
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_fields() {
+    let context = new_test_context(current_function_name!());
+    let txns = context
+        .get("/transactions?start=0&limit=2&fields=version,hash,success")
+        .await;
+    let expected = context.get("/transactions?start=0&limit=2").await;
+    assert_json(
+        txns,
+        json!([
+            {
+                "version": expected[0]["version"],
+                "hash": expected[0]["hash"],
+                "success": expected[0]["success"],
+            },
+            {
+                "version": expected[1]["version"],
+                "hash": expected[1]["hash"],
+                "success": expected[1]["success"],
+            },
+        ]),
+    );
+}
+
+#[tokio::test]
+async fn test_get_transaction_with_nested_fields() {
+    let context = new_test_context(current_function_name!());
+    let txn = context
+        .get("/transactions/0?fields=type,changes.type")
+        .await;
+    let changes = txn["changes"].as_array().unwrap();
+    assert!(!changes.is_empty());
+    for change in changes {
+        assert_eq!(
+            change.as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["type"]
+        );
+    }
+    assert_eq!(txn.as_object().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_empty_field() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?fields=hash,,version")
+        .await;
+    assert_eq!(
+        resp["message"],
+        json!("invalid parameter fields: hash,,version")
+    );
+}
+
+#[tokio::test]
+async fn test_get_transactions_pagination_headers() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions?start=0&limit=1"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()[X_APTOS_TOTAL_ITEMS],
+        (ledger_version + 1).to_string()
+    );
+    assert_eq!(resp.headers()[X_APTOS_CURSOR], "1");
+
+    // the last page has no cursor
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&format!("/transactions?start={}", ledger_version)),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get(X_APTOS_CURSOR).is_none());
+}
+
 #[tokio::test]
 async fn test_get_transactions_output_user_transaction_with_script_function_payload() {
     let mut context = new_test_context(current_function_name!());
@@ -142,7 +250,7 @@ async fn test_get_transactions_output_user_transaction_with_module_payload() {
     let txns = context.get("/transactions?start=2").await;
     assert_eq!(1, txns.as_array().unwrap().len());
 
-    let expected_txns = context.get_transactions(2, 1);
+    let expected_txns = context.get_transactions(2, 1).await;
     assert_eq!(1, expected_txns.len());
 
     assert_json(
@@ -240,6 +348,125 @@ async fn test_post_bcs_format_transaction() {
         .await;
 }
 
+#[tokio::test]
+async fn test_submit_and_get_committed_transaction_return_min_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    // A submitted transaction is not committed yet, the token is the version it was accepted at.
+    let accepted_version = context.get_latest_ledger_info().version();
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("POST")
+                .path("/transactions")
+                .header("content-type", mime_types::BCS_SIGNED_TRANSACTION)
+                .body(bcs::to_bytes(&txn).unwrap()),
+        )
+        .await;
+    assert_eq!(resp.status(), 202);
+    assert_eq!(
+        resp.headers()[X_APTOS_MIN_VERSION],
+        accepted_version.to_string()
+    );
+
+    let hash = txn.clone().committed_hash();
+    let resp = context
+        .reply(warp::test::request().path(&format!("/transactions/{}", hash.to_hex_literal())))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get(X_APTOS_MIN_VERSION).is_none());
+
+    context.commit_block(&vec![txn]).await;
+    let version = context.get_latest_ledger_info().version();
+    let resp = context
+        .reply(warp::test::request().path(&format!("/transactions/{}", hash.to_hex_literal())))
+        .await;
+    assert_eq!(resp.status(), 200);
+    // The user transaction is the last one of the block.
+    assert_eq!(resp.headers()[X_APTOS_MIN_VERSION], version.to_string());
+}
+
+#[tokio::test]
+async fn test_authenticate_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let resp = context
+        .post_bcs_txn("/transactions/authenticate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    let authentication_key = context.root_account().authentication_key().to_vec();
+    assert_json(
+        resp,
+        json!({
+            "valid": true,
+            "signers": [{
+                "address": context.root_account().address().to_hex_literal(),
+                "authentication_key": HexEncodedBytes::from(authentication_key.clone()),
+                "account_authentication_key": HexEncodedBytes::from(authentication_key),
+                "authentication_key_matches": true,
+            }],
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_authenticate_transaction_with_invalid_signature() {
+    let mut context = new_test_context(current_function_name!());
+    let txn = context.create_invalid_signature_transaction();
+    let resp = context
+        .post_bcs_txn("/transactions/authenticate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["valid"], json!(false));
+    assert!(resp["signature_error"].is_string());
+    assert_eq!(
+        resp["signers"][0]["authentication_key_matches"],
+        json!(true)
+    );
+}
+
+#[tokio::test]
+async fn test_authenticate_transaction_signed_with_another_key() {
+    let mut context = new_test_context(current_function_name!());
+    let root_account = context.root_account();
+    let mut signer = LocalAccount::new(
+        root_account.address(),
+        Ed25519PrivateKey::generate(context.rng()),
+        root_account.sequence_number(),
+    );
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut signer, &account);
+    let resp = context
+        .post_bcs_txn("/transactions/authenticate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["valid"], json!(false));
+    assert!(resp.get("signature_error").is_none());
+    assert_eq!(
+        resp["signers"][0]["authentication_key"],
+        json!(HexEncodedBytes::from(signer.authentication_key().to_vec()))
+    );
+    assert_eq!(
+        resp["signers"][0]["authentication_key_matches"],
+        json!(false)
+    );
+}
+
+#[tokio::test]
+async fn test_authenticate_transaction_of_account_not_found() {
+    let mut context = new_test_context(current_function_name!());
+    let mut sender = context.gen_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut sender, &account);
+    let resp = context
+        .post_bcs_txn("/transactions/authenticate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["valid"], json!(false));
+    assert!(resp["signers"][0]
+        .get("account_authentication_key")
+        .is_none());
+}
+
 #[tokio::test]
 async fn test_post_invalid_bcs_format_transaction() {
     let mut context = new_test_context(current_function_name!());
@@ -283,6 +510,56 @@ async fn test_post_transaction_rejected_by_mempool() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_post_already_committed_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["aptos_error_code"], "transaction_already_committed");
+    assert_eq!(
+        resp["message"],
+        format!(
+            "transaction {} is already committed at version 2",
+            aptos_api_types::HashValue::from(txn.committed_hash())
+        )
+    );
+}
+
+#[tokio::test]
+async fn test_post_transaction_with_mismatched_chain_id() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let mut root_account = context.root_account();
+    let txn = root_account.sign_with_transaction_builder(
+        TransactionFactory::new(ChainId::new(2))
+            .create_user_account(account.public_key())
+            .expiration_timestamp_secs(u64::MAX),
+    );
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": "transaction chain id 2 does not match the node chain id 4",
+            "aptos_error_code": "chain_id_mismatch",
+            "aptos_error_details": {
+                "node_chain_id": 4,
+                "transaction_chain_id": 2,
+            },
+        }),
+    );
+}
+
 #[ignore]
 #[tokio::test]
 async fn test_multi_agent_signed_transaction() {
@@ -476,6 +753,51 @@ async fn test_get_transaction_by_version() {
     assert_json(resp, txns[0].clone())
 }
 
+#[tokio::test]
+async fn test_trace_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let committed = context.get("/transactions/2").await;
+    let trace = context
+        .get(&format!(
+            "/transactions/{}/trace",
+            committed["hash"].as_str().unwrap()
+        ))
+        .await;
+    assert_eq!(trace["version"], committed["version"]);
+    assert_eq!(trace["hash"], committed["hash"]);
+    assert_eq!(trace["success"], true);
+    assert_eq!(trace["gas_used"], committed["gas_used"]);
+
+    let phases: Vec<&str> = trace["phases"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|phase| phase["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        phases,
+        vec!["prologue", "intrinsic", "execution", "storage", "epilogue"]
+    );
+}
+
+#[tokio::test]
+async fn test_trace_non_user_transaction() {
+    let mut context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions/0/trace")
+        .await;
+    assert_eq!(
+        resp["message"],
+        "transaction version(0) is not a user transaction"
+    );
+}
+
 #[tokio::test]
 async fn test_get_pending_transaction_by_hash() {
     let mut context = new_test_context(current_function_name!());
@@ -1085,6 +1407,130 @@ async fn test_submit_transaction_rejects_payload_too_large_json_body() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_submit_transaction_rejects_payload_too_large_after_api_config_update() {
+    let mut context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        content_length_limit: Some(16),
+        ..ApiConfig::default()
+    });
+
+    let resp = context
+        .expect_status_code(413)
+        .post_bcs_txn("/transactions", gen_string(17).as_bytes())
+        .await;
+    context.check_golden_output(resp);
+}
+
+#[tokio::test]
+async fn test_submit_transactions_in_batch_window() {
+    let mut context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        submission_batch_window_ms: 5,
+        ..ApiConfig::default()
+    });
+    let mut root_account = context.root_account();
+    let account1 = context.gen_account();
+    let account2 = context.gen_account();
+    let txn1 = context.create_user_account_by(&mut root_account, &account1);
+    let txn2 = context.create_user_account_by(&mut root_account, &account2);
+
+    let (status1, status2) = futures::future::join(
+        context.context.submit_transaction(txn1),
+        context.context.submit_transaction(txn2),
+    )
+    .await;
+    assert_eq!(status1.unwrap().0.code, MempoolStatusCode::Accepted);
+    assert_eq!(status2.unwrap().0.code, MempoolStatusCode::Accepted);
+    context.commit_mempool_txns(2).await;
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_too_many_arguments() {
+    let mut context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        max_transaction_arguments: 0,
+        ..ApiConfig::default()
+    });
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": "number of arguments 1 exceeds the limit 0",
+            "aptos_error_code": "too_many_transaction_arguments",
+            "aptos_error_details": {
+                "limit": 0,
+                "actual": 1,
+            },
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_payload_over_limit() {
+    let mut context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        max_transaction_payload_bytes: 8,
+        ..ApiConfig::default()
+    });
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["aptos_error_code"], "transaction_payload_too_large");
+    assert_eq!(resp["aptos_error_details"]["limit"], 8);
+}
+
+#[tokio::test]
+async fn test_create_signing_message_rejects_long_vector_argument() {
+    let mut context = new_test_context(current_function_name!());
+    context.context.update_api_config(ApiConfig {
+        max_vector_argument_length: 16,
+        ..ApiConfig::default()
+    });
+    let sender = context.root_account();
+    let body = json!({
+        "sender": sender.address().to_hex_literal(),
+        "sequence_number": sender.sequence_number().to_string(),
+        "gas_unit_price": "0",
+        "max_gas_amount": "1000000",
+        "expiration_timestamp_secs": u64::MAX.to_string(),
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::Account::rotate_authentication_key",
+            "type_arguments": [],
+            "arguments": [format!("0x{}", hex::encode([0u8; 17]))],
+        },
+    });
+
+    let resp = context
+        .expect_status_code(400)
+        .post("/transactions/signing_message", body)
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": "vector argument length 17 exceeds the limit 16",
+            "aptos_error_code": "vector_argument_too_long",
+            "aptos_error_details": {
+                "limit": 16,
+                "actual": 17,
+            },
+        }),
+    );
+}
+
 #[tokio::test]
 async fn test_submit_transaction_rejects_invalid_content_type() {
     let mut context = new_test_context(current_function_name!());
@@ -1165,6 +1611,94 @@ async fn test_create_signing_message_rejects_no_content_length_request() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_estimate_gas_price() {
+    let mut context = new_test_context(current_function_name!());
+    let expected = json!({
+        "deprioritized_gas_estimate": "0",
+        "gas_estimate": "0",
+        "prioritized_gas_estimate": "0",
+        "gas_price_floor": "0",
+    });
+    // no blocks committed yet, the estimates fall back to the minimum gas unit price
+    let resp = context.get("/estimate_gas_price").await;
+    assert_json(resp, expected);
+
+    // the estimates follow the prices paid in the latest block
+    let mut root = context.root_account();
+    let factory = context.transaction_factory();
+    let txns: Vec<_> = (1..=4)
+        .map(|gas_unit_price| {
+            let account = context.gen_account();
+            root.sign_with_transaction_builder(
+                factory
+                    .create_user_account(account.public_key())
+                    .gas_unit_price(gas_unit_price)
+                    .expiration_timestamp_secs(u64::MAX),
+            )
+        })
+        .collect();
+    context.commit_block(&txns).await;
+    let resp = context.get("/estimate_gas_price").await;
+    assert_json(
+        resp,
+        json!({
+            "deprioritized_gas_estimate": "1",
+            "gas_estimate": "2",
+            "prioritized_gas_estimate": "4",
+            "gas_price_floor": "0",
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_gas_unit_price_below_floor() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let gas_price_floor = txn.gas_unit_price() + 1;
+    context.context.update_api_config(ApiConfig {
+        gas_price_floor: vec![GasPriceFloorStep {
+            mempool_utilization_percent: 0,
+            min_gas_unit_price: gas_price_floor,
+        }],
+        ..ApiConfig::default()
+    });
+
+    let resp = context.get("/estimate_gas_price").await;
+    let floor = json!(gas_price_floor.to_string());
+    assert_json(
+        resp,
+        json!({
+            "deprioritized_gas_estimate": floor,
+            "gas_estimate": floor,
+            "prioritized_gas_estimate": floor,
+            "gas_price_floor": floor,
+        }),
+    );
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions", &bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": format!(
+                "gas unit price {} is below the current gas price floor {}, mempool is congested",
+                txn.gas_unit_price(),
+                gas_price_floor
+            ),
+            "aptos_error_code": "gas_unit_price_below_floor",
+            "aptos_error_details": {
+                "gas_price_floor": gas_price_floor,
+                "gas_unit_price": txn.gas_unit_price(),
+            },
+        }),
+    );
+}
+
 fn gen_string(len: u64) -> String {
     let mut rng = thread_rng();
     std::iter::repeat(())
@@ -1173,3 +1707,68 @@ fn gen_string(len: u64) -> String {
         .map(char::from)
         .collect()
 }
+
+async fn post_bcs_txn_with_idempotency_key(
+    context: &TestContext,
+    txn: &SignedTransaction,
+    key: &str,
+) -> Value {
+    context
+        .execute(
+            warp::test::request()
+                .method("POST")
+                .path("/transactions")
+                .header(CONTENT_TYPE, mime_types::BCS_SIGNED_TRANSACTION)
+                .header("Idempotency-Key", key)
+                .body(bcs::to_bytes(txn).unwrap()),
+        )
+        .await
+}
+
+#[tokio::test]
+async fn test_resubmit_transaction_with_idempotency_key() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let resp =
+        post_bcs_txn_with_idempotency_key(&context.expect_status_code(202), &txn, "payment-1")
+            .await;
+    context.commit_block(&vec![txn.clone()]).await;
+
+    // Without the key the retry would be rejected as already committed.
+    let retry_resp =
+        post_bcs_txn_with_idempotency_key(&context.expect_status_code(202), &txn, "payment-1")
+            .await;
+    assert_eq!(retry_resp, resp);
+}
+
+#[tokio::test]
+async fn test_submit_transaction_rejects_reused_idempotency_key() {
+    let mut context = new_test_context(current_function_name!());
+    let account1 = context.gen_account();
+    let account2 = context.gen_account();
+    let txn1 = context.create_user_account(&account1);
+    let txn2 = context.create_user_account(&account2);
+
+    post_bcs_txn_with_idempotency_key(&context.expect_status_code(202), &txn1, "payment-1").await;
+    let resp =
+        post_bcs_txn_with_idempotency_key(&context.expect_status_code(400), &txn2, "payment-1")
+            .await;
+    assert_eq!(resp["aptos_error_code"], "idempotency_key_reused");
+}
+
+#[tokio::test]
+async fn test_concurrent_submissions_with_idempotency_key() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let accepted = context.expect_status_code(202);
+    let (resp, retry_resp) = futures::join!(
+        post_bcs_txn_with_idempotency_key(&accepted, &txn, "payment-1"),
+        post_bcs_txn_with_idempotency_key(&accepted, &txn, "payment-1"),
+    );
+    assert_eq!(retry_resp, resp);
+    assert_eq!(context.mempool.get_txns(10), vec![txn]);
+}
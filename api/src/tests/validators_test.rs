@@ -0,0 +1,74 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_get_current_validator_set() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/validators").await;
+    assert_eq!(resp["epoch"], "1");
+    // epoch 1 starts right after the genesis transaction
+    assert_eq!(resp["start_version"], "0");
+    assert!(!resp["validators"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_get_validator_set_by_epoch() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/validators?epoch=1").await;
+    assert_eq!(resp, context.get("/validators").await);
+}
+
+#[tokio::test]
+async fn test_get_validator_set_by_unknown_epoch() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/validators?epoch=0")
+        .await;
+    context
+        .expect_status_code(404)
+        .get("/validators?epoch=100")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_validator_set_by_invalid_epoch() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/validators?epoch=abc")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_validator_stats() {
+    let mut context = new_test_context(current_function_name!());
+    assert_eq!(context.get("/validators/stats").await, json!([]));
+
+    context.commit_block(&[]).await;
+    context.commit_block(&[]).await;
+    let resp = context.get("/validators/stats?epochs=2").await;
+    let stats = resp.as_array().unwrap();
+    assert_eq!(stats.len(), 1);
+    // the blocks committed by the test context are all in round 1 of epoch 0
+    assert_eq!(stats[0]["epoch"], "0");
+    assert_eq!(stats[0]["blocks"], "2");
+    assert_eq!(stats[0]["failed_rounds"], "0");
+    assert_eq!(stats[0]["validators"][0]["proposals"], "2");
+}
+
+#[tokio::test]
+async fn test_get_validator_stats_with_invalid_epochs() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/validators/stats?epochs=0")
+        .await;
+    context
+        .expect_status_code(400)
+        .get("/validators/stats?epochs=11")
+        .await;
+}
@@ -0,0 +1,101 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Limits on submitted transactions, see the `max_*` fields of `ApiConfig`.
+//!
+//! The limits are checked before a transaction reaches mempool. JSON requests are also checked
+//! before their arguments are converted, so that a request body within the content length limit
+//! cannot be amplified into much larger Move values.
+
+use aptos_api_types::{AptosErrorCode, Error, UserTransactionRequest};
+use aptos_config::config::ApiConfig;
+use aptos_types::transaction::TransactionPayload;
+use serde_json::Value;
+
+/// Checks the script and arguments of a JSON transaction request.
+pub fn check_request(config: &ApiConfig, request: &UserTransactionRequest) -> Result<(), Error> {
+    let arguments = match &request.payload {
+        aptos_api_types::TransactionPayload::ScriptFunctionPayload(payload) => &payload.arguments,
+        aptos_api_types::TransactionPayload::ScriptPayload(payload) => {
+            check_script_size(config, payload.code.bytecode.inner().len())?;
+            &payload.arguments
+        }
+        aptos_api_types::TransactionPayload::ModuleBundlePayload(_)
+        | aptos_api_types::TransactionPayload::WriteSetPayload(_) => return Ok(()),
+    };
+    check_argument_count(config, arguments.len())?;
+    for argument in arguments {
+        check_limit(
+            AptosErrorCode::VectorArgumentTooLong,
+            "vector argument length",
+            longest_vector(argument) as u64,
+            config.max_vector_argument_length as u64,
+        )?;
+    }
+    Ok(())
+}
+
+/// Checks the payload of a transaction, whether submitted as BCS or converted from JSON.
+pub fn check_payload(config: &ApiConfig, payload: &TransactionPayload) -> Result<(), Error> {
+    let size = bcs::serialized_size(payload).map_err(Error::invalid_request_body)?;
+    check_limit(
+        AptosErrorCode::TransactionPayloadTooLarge,
+        "transaction payload size",
+        size as u64,
+        config.max_transaction_payload_bytes,
+    )?;
+    match payload {
+        TransactionPayload::Script(script) => {
+            check_script_size(config, script.code().len())?;
+            check_argument_count(config, script.args().len())
+        }
+        TransactionPayload::ScriptFunction(script_function) => {
+            check_argument_count(config, script_function.args().len())
+        }
+        TransactionPayload::ModuleBundle(_) | TransactionPayload::WriteSet(_) => Ok(()),
+    }
+}
+
+fn check_script_size(config: &ApiConfig, size: usize) -> Result<(), Error> {
+    check_limit(
+        AptosErrorCode::ScriptTooLarge,
+        "script size",
+        size as u64,
+        config.max_script_bytes,
+    )
+}
+
+fn check_argument_count(config: &ApiConfig, count: usize) -> Result<(), Error> {
+    check_limit(
+        AptosErrorCode::TooManyTransactionArguments,
+        "number of arguments",
+        count as u64,
+        config.max_transaction_arguments as u64,
+    )
+}
+
+fn check_limit(
+    error_code: AptosErrorCode,
+    what: &str,
+    actual: u64,
+    limit: u64,
+) -> Result<(), Error> {
+    if actual > limit {
+        return Err(Error::limit_exceeded(error_code, what, actual, limit));
+    }
+    Ok(())
+}
+
+/// Returns the length of the longest vector in a JSON argument. Vectors are JSON arrays, or
+/// strings for `vector<u8>` (hex-encoded) and Move strings.
+fn longest_vector(value: &Value) -> usize {
+    match value {
+        Value::Array(values) => values
+            .iter()
+            .map(longest_vector)
+            .fold(values.len(), usize::max),
+        Value::Object(fields) => fields.values().map(longest_vector).max().unwrap_or(0),
+        Value::String(s) => s.strip_prefix("0x").map_or(s.len(), |hex| hex.len() / 2),
+        Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+    }
+}
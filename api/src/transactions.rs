@@ -4,22 +4,33 @@
 use crate::{
     context::Context,
     failpoint::fail_point,
+    fields::Fields,
+    gas_estimation,
+    idempotency::{Reservation, IDEMPOTENCY_KEY_HEADER, MAX_IDEMPOTENCY_KEY_LENGTH},
     metrics::metrics,
-    page::Page,
+    page::{page_read_error, Page},
     param::{AddressParam, TransactionIdParam},
+    transaction_limits,
 };
 
 use aptos_api_types::{
-    mime_types::BCS_SIGNED_TRANSACTION, AsConverter, Error, LedgerInfo, Response, Transaction,
-    TransactionData, TransactionId, TransactionOnChainData, TransactionSigningMessage,
-    UserTransactionRequest,
+    mime_types::BCS_SIGNED_TRANSACTION, AsConverter, Error, LedgerInfo, Response,
+    SignerAuthentication, Transaction, TransactionAuthentication, TransactionData, TransactionId,
+    TransactionOnChainData, TransactionSigningMessage, TransactionTrace, UserTransactionRequest,
 };
+use aptos_crypto::HashValue;
 use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::AccountResource,
     mempool_status::MempoolStatusCode,
+    state_store::state_key::StateKey,
     transaction::{RawTransaction, SignedTransaction},
 };
+use aptos_vm::AptosVM;
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
 
-use anyhow::Result;
+use anyhow::{format_err, Result};
 use warp::{
     filters::BoxedFilter,
     http::{header::CONTENT_TYPE, StatusCode},
@@ -30,17 +41,29 @@ use warp::{
 pub fn get_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / TransactionIdParam)
         .and(warp::get())
+        .and(warp::query::<Fields>())
         .and(context.filter())
         .and_then(handle_get_transaction)
         .with(metrics("get_transaction"))
         .boxed()
 }
 
+// GET /transactions/{txn-hash / version}/trace
+pub fn trace_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / TransactionIdParam / "trace")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_trace_transaction)
+        .with(metrics("trace_transaction"))
+        .boxed()
+}
+
 // GET /transactions?start={u64}&limit={u16}
 pub fn get_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(warp::query::<Fields>())
         .and(context.filter())
         .and_then(handle_get_transactions)
         .with(metrics("get_transactions"))
@@ -52,6 +75,7 @@ pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)>
     warp::path!("accounts" / AddressParam / "transactions")
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(warp::query::<Fields>())
         .and(context.filter())
         .and_then(handle_get_account_transactions)
         .with(metrics("get_account_transactions"))
@@ -62,9 +86,8 @@ pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)>
 pub fn submit_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            context.content_length_limit(),
-        ))
+        .and(context.content_length_limit_filter())
+        .and(warp::header::optional::<String>(IDEMPOTENCY_KEY_HEADER))
         .and(warp::body::json::<UserTransactionRequest>())
         .and(context.filter())
         .and_then(handle_submit_json_transactions)
@@ -81,13 +104,12 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     // handler, the invalid header error should be ignored.
     warp::path!("transactions")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            context.content_length_limit(),
-        ))
+        .and(context.content_length_limit_filter())
         .and(warp::header::exact(
             CONTENT_TYPE.as_str(),
             BCS_SIGNED_TRANSACTION,
         ))
+        .and(warp::header::optional::<String>(IDEMPOTENCY_KEY_HEADER))
         .and(warp::body::bytes())
         .and(context.filter())
         .and_then(handle_submit_bcs_transactions)
@@ -99,9 +121,7 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
 pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / "signing_message")
         .and(warp::post())
-        .and(warp::body::content_length_limit(
-            context.content_length_limit(),
-        ))
+        .and(context.content_length_limit_filter())
         .and(warp::body::json::<UserTransactionRequest>())
         .and(context.filter())
         .and_then(handle_create_signing_message)
@@ -109,48 +129,127 @@ pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// POST /transactions/authenticate with JSON
+pub fn authenticate_json_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "authenticate")
+        .and(warp::post())
+        .and(context.content_length_limit_filter())
+        .and(warp::body::json::<UserTransactionRequest>())
+        .and(context.filter())
+        .and_then(handle_authenticate_json_transaction)
+        .with(metrics("authenticate_json_transaction"))
+        .boxed()
+}
+
+// POST /transactions/authenticate with BCS
+pub fn authenticate_bcs_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // See `submit_bcs_transactions` for the content-type matching.
+    warp::path!("transactions" / "authenticate")
+        .and(warp::post())
+        .and(context.content_length_limit_filter())
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION,
+        ))
+        .and(warp::body::bytes())
+        .and(context.filter())
+        .and_then(handle_authenticate_bcs_transaction)
+        .with(metrics("authenticate_bcs_transaction"))
+        .boxed()
+}
+
 async fn handle_get_transaction(
     id: TransactionIdParam,
+    fields: Fields,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transaction")?;
-    Ok(Transactions::new(context)?
+    let response = Transactions::new(context)
+        .await?
         .get_transaction(id.parse("transaction hash or version")?)
+        .await?;
+    Ok(fields.apply(response)?)
+}
+
+async fn handle_trace_transaction(
+    id: TransactionIdParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_trace_transaction")?;
+    Ok(Transactions::new(context)
+        .await?
+        .trace(id.parse("transaction hash or version")?)
         .await?)
 }
 
-async fn handle_get_transactions(page: Page, context: Context) -> Result<impl Reply, Rejection> {
+async fn handle_get_transactions(
+    page: Page,
+    fields: Fields,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transactions")?;
-    Ok(Transactions::new(context)?.list(page)?)
+    Ok(fields.apply(Transactions::new(context).await?.list(page).await?)?)
 }
 
 async fn handle_get_account_transactions(
     address: AddressParam,
     page: Page,
+    fields: Fields,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_transactions")?;
-    Ok(Transactions::new(context)?.list_by_account(address, page)?)
+    Ok(fields.apply(
+        Transactions::new(context)
+            .await?
+            .list_by_account(address, page)
+            .await?,
+    )?)
 }
 
 async fn handle_submit_json_transactions(
+    idempotency_key: Option<String>,
     body: UserTransactionRequest,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_submit_json_transactions")?;
-    Ok(Transactions::new(context)?
-        .create_from_request(body)
+    Ok(Transactions::new(context)
+        .await?
+        .create_from_request(body, idempotency_key)
         .await?)
 }
 
 async fn handle_submit_bcs_transactions(
+    idempotency_key: Option<String>,
     body: bytes::Bytes,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_submit_bcs_transactions")?;
     let txn = bcs::from_bytes(&body)
         .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
-    Ok(Transactions::new(context)?.create(txn).await?)
+    Ok(Transactions::new(context)
+        .await?
+        .create(txn, idempotency_key)
+        .await?)
+}
+
+async fn handle_authenticate_json_transaction(
+    body: UserTransactionRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_authenticate_json_transaction")?;
+    let transactions = Transactions::new(context).await?;
+    let txn = transactions.signed_transaction(body)?;
+    Ok(transactions.authenticate(txn).await?)
+}
+
+async fn handle_authenticate_bcs_transaction(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_authenticate_bcs_transaction")?;
+    let txn = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context).await?.authenticate(txn).await?)
 }
 
 async fn handle_create_signing_message(
@@ -158,7 +257,7 @@ async fn handle_create_signing_message(
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_create_signing_message")?;
-    Ok(Transactions::new(context)?.signing_message(body)?)
+    Ok(Transactions::new(context).await?.signing_message(body)?)
 }
 
 struct Transactions {
@@ -167,8 +266,8 @@ struct Transactions {
 }
 
 impl Transactions {
-    fn new(context: Context) -> Result<Self, Error> {
-        let ledger_info = context.get_latest_ledger_info()?;
+    async fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info().await?;
         Ok(Self {
             ledger_info,
             context,
@@ -178,9 +277,15 @@ impl Transactions {
     pub async fn create_from_request(
         self,
         req: UserTransactionRequest,
+        idempotency_key: Option<String>,
     ) -> Result<impl Reply, Error> {
-        let txn = self
-            .context
+        let txn = self.signed_transaction(req)?;
+        self.create(txn, idempotency_key).await
+    }
+
+    fn signed_transaction(&self, req: UserTransactionRequest) -> Result<SignedTransaction, Error> {
+        transaction_limits::check_request(&self.context.api_config(), &req)?;
+        self.context
             .move_resolver()?
             .as_converter()
             .try_into_signed_transaction(req, self.context.chain_id())
@@ -189,25 +294,171 @@ impl Transactions {
                     "failed to create SignedTransaction from UserTransactionRequest: {}",
                     e
                 ))
-            })?;
-        self.create(txn).await
+            })
     }
 
-    pub async fn create(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
+    /// Checks the signatures of `txn`, and that its signers signed with the authentication keys
+    /// of their accounts at the latest ledger version, without executing it.
+    pub async fn authenticate(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
+        let authenticator = txn.authenticator();
+        let mut signers = vec![];
+        for (address, signer) in std::iter::once((txn.sender(), authenticator.sender())).chain(
+            authenticator
+                .secondary_signer_addreses()
+                .into_iter()
+                .zip(authenticator.secondary_signers()),
+        ) {
+            let authentication_key = signer.authentication_key().to_vec();
+            let account_authentication_key = self
+                .account_resource(address)
+                .await?
+                .map(|account| account.authentication_key().to_vec());
+            signers.push(SignerAuthentication {
+                address: address.into(),
+                authentication_key_matches: account_authentication_key.as_ref()
+                    == Some(&authentication_key),
+                authentication_key: authentication_key.into(),
+                account_authentication_key: account_authentication_key.map(Into::into),
+            });
+        }
+        let signature_error = txn.check_signature().err().map(|err| err.to_string());
+
+        let authentication = TransactionAuthentication {
+            valid: signature_error.is_none()
+                && signers
+                    .iter()
+                    .all(|signer| signer.authentication_key_matches),
+            signature_error,
+            signers,
+        };
+        Response::new(self.ledger_info, &authentication)
+    }
+
+    async fn account_resource(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Option<AccountResource>, Error> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            AccountResource::struct_tag(),
+        )));
+        Ok(self
+            .context
+            .get_state_value(&state_key, self.ledger_info.version())
+            .await?
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?)
+    }
+
+    /// Submits `txn` to mempool. A submission with an idempotency key that was already used for
+    /// the same transaction replays the result of the first submission instead, so that clients
+    /// can safely retry after a timeout.
+    pub async fn create(
+        self,
+        txn: SignedTransaction,
+        idempotency_key: Option<String>,
+    ) -> Result<impl Reply, Error> {
+        transaction_limits::check_payload(&self.context.api_config(), txn.payload())?;
+        let hash = txn.clone().committed_hash();
+
+        let reserved = match &idempotency_key {
+            Some(key) => {
+                if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+                    return Err(Error::bad_request(format!(
+                        "invalid {} header: the key must be 1 to {} characters long",
+                        IDEMPOTENCY_KEY_HEADER, MAX_IDEMPOTENCY_KEY_LENGTH
+                    )));
+                }
+                loop {
+                    match self.context.reserve_submission(key, hash) {
+                        Reservation::Reserved(reserved) => break Some(reserved),
+                        Reservation::InFlight(mut released) => {
+                            // woken up once the other request is done, its result is then
+                            // replayed or, if it failed, the key can be reserved again
+                            let _ = released.changed().await;
+                        }
+                        Reservation::Done(result) => return self.reply(result),
+                        Reservation::Reused => return Err(Error::idempotency_key_reused(key)),
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let result = self.submit(txn, hash).await;
+        if let Some(reserved) = reserved {
+            // Internal errors are usually transient, dropping the reservation lets a retry submit
+            // the transaction again.
+            if !matches!(&result, Err(e) if e.status_code().is_server_error()) {
+                reserved.complete(result.clone());
+            }
+        }
+        self.reply(result)
+    }
+
+    fn reply(self, result: Result<Vec<u8>, Error>) -> Result<impl Reply, Error> {
+        // The transaction is not committed yet, the reads following it should not see a state
+        // older than the one it was accepted at.
+        let min_version = self.ledger_info.version();
+        let resp = Response {
+            ledger_info: self.ledger_info,
+            body: result?,
+            total_items: None,
+            cursor: None,
+            min_version: Some(min_version),
+        };
+        Ok(reply::with_status(resp, StatusCode::ACCEPTED))
+    }
+
+    /// Returns the JSON encoded pending transaction once mempool accepted `txn`.
+    async fn submit(&self, txn: SignedTransaction, hash: HashValue) -> Result<Vec<u8>, Error> {
+        // Check the chain id before the transaction reaches mempool, a mismatch would only
+        // surface as an opaque VM validation error.
+        if txn.chain_id() != self.context.chain_id() {
+            return Err(Error::chain_id_mismatch(
+                self.context.chain_id().id(),
+                txn.chain_id().id(),
+            ));
+        }
+
+        // Reject resubmissions of already committed transactions up front, otherwise the client
+        // gets a confusing sequence number error from mempool.
+        if let Some(version) = self
+            .context
+            .get_committed_transaction_version(hash, self.ledger_info.version())
+            .await?
+        {
+            return Err(Error::transaction_already_committed(
+                aptos_api_types::HashValue::from(hash),
+                version,
+            )
+            .aptos_ledger_version(self.ledger_info.version()));
+        }
+
+        let gas_price_floor = gas_estimation::gas_price_floor(&self.context).await?;
+        if txn.gas_unit_price() < gas_price_floor {
+            return Err(Error::gas_unit_price_below_floor(
+                txn.gas_unit_price(),
+                gas_price_floor,
+            ));
+        }
+
         let (mempool_status, vm_status_opt) = self.context.submit_transaction(txn.clone()).await?;
         match mempool_status.code {
             MempoolStatusCode::Accepted => {
                 let resolver = self.context.move_resolver()?;
                 let pending_txn = resolver.as_converter().try_into_pending_transaction(txn)?;
-                let resp = Response::new(self.ledger_info, &pending_txn)?;
-                Ok(reply::with_status(resp, StatusCode::ACCEPTED))
+                Ok(serde_json::to_vec(&pending_txn)?)
             }
-            MempoolStatusCode::VmError => Err(Error::bad_request(format!(
-                "invalid transaction: {}",
+            MempoolStatusCode::VmError => Err(Error::invalid_transaction(
                 vm_status_opt
                     .map(|s| format!("{:?}", s))
-                    .unwrap_or_else(|| "UNKNOWN".to_owned())
-            ))),
+                    .unwrap_or_else(|| "UNKNOWN".to_owned()),
+            )),
+            MempoolStatusCode::SenderNotAllowed => Err(Error::sender_not_allowed(
+                aptos_api_types::Address::from(txn.sender()),
+            )),
             _ => Err(Error::bad_request(format!(
                 "transaction is rejected: {}",
                 mempool_status,
@@ -215,9 +466,9 @@ impl Transactions {
         }
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
+    pub async fn list(self, page: Page) -> Result<Response, Error> {
         let ledger_version = self.ledger_info.version();
-        let limit = page.limit()?;
+        let limit = page.limit(self.context.api_config().max_transactions_page_size)?;
         let last_page_start = if ledger_version > (limit as u64) {
             ledger_version - (limit as u64)
         } else {
@@ -227,30 +478,54 @@ impl Transactions {
 
         let data = self
             .context
-            .get_transactions(start_version, limit, ledger_version)?;
-
-        self.render_transactions(data)
+            .get_transactions(start_version, limit, ledger_version)
+            .await
+            .map_err(page_read_error)?;
+
+        let total_items = ledger_version + 1;
+        let cursor = Page::next_start(start_version, data.len(), limit, Some(total_items));
+        Ok(self
+            .render_transactions(data)
+            .await?
+            .with_total_items(total_items)
+            .with_cursor(cursor))
     }
 
-    pub fn list_by_account(self, address: AddressParam, page: Page) -> Result<impl Reply, Error> {
-        let data = self.context.get_account_transactions(
-            address.parse("account address")?.into(),
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
-        )?;
-        self.render_transactions(data)
+    pub async fn list_by_account(
+        self,
+        address: AddressParam,
+        page: Page,
+    ) -> Result<Response, Error> {
+        let start_sequence_number = page.start(0, u64::MAX)?;
+        let limit = page.limit(self.context.api_config().max_transactions_page_size)?;
+        let data = self
+            .context
+            .get_account_transactions(
+                address.parse("account address")?.into(),
+                start_sequence_number,
+                limit,
+                self.ledger_info.version(),
+            )
+            .await
+            .map_err(page_read_error)?;
+        let cursor = Page::next_start(start_sequence_number, data.len(), limit, None);
+        Ok(self.render_transactions(data).await?.with_cursor(cursor))
     }
 
-    fn render_transactions(self, data: Vec<TransactionOnChainData>) -> Result<impl Reply, Error> {
+    async fn render_transactions(
+        self,
+        data: Vec<TransactionOnChainData>,
+    ) -> Result<Response, Error> {
         if data.is_empty() {
             let txns: Vec<Transaction> = vec![];
             return Response::new(self.ledger_info, &txns);
         }
         let first_version = data[0].version;
-        let mut timestamp = self.context.get_block_timestamp(first_version)?;
+        let mut timestamp = self.context.get_block_timestamp(first_version).await?;
         let resolver = self.context.move_resolver()?;
-        let converter = resolver.as_converter();
+        let converter = resolver
+            .as_converter()
+            .with_layout_cache(self.context.layout_cache(self.ledger_info.version())?);
         let txns: Vec<Transaction> = data
             .into_iter()
             .map(|t| {
@@ -264,30 +539,100 @@ impl Transactions {
         Response::new(self.ledger_info, &txns)
     }
 
-    pub async fn get_transaction(self, id: TransactionId) -> Result<impl Reply, Error> {
+    pub async fn get_transaction(self, id: TransactionId) -> Result<Response, Error> {
         let txn_data = match id.clone() {
             TransactionId::Hash(hash) => self.get_by_hash(hash.into()).await?,
-            TransactionId::Version(version) => self.get_by_version(version)?,
+            TransactionId::Version(version) => self.get_by_version(version).await?,
         }
         .ok_or_else(|| self.transaction_not_found(id))?;
 
-        let resolver = self.context.move_resolver()?;
+        // The consistency token of a committed transaction is its version: reads from any node
+        // with that minimum version see the writes of the transaction.
+        let mut min_version = None;
         let txn = match txn_data {
             TransactionData::OnChain(txn) => {
-                let timestamp = self.context.get_block_timestamp(txn.version)?;
-                resolver
+                min_version = Some(txn.version);
+                let timestamp = self.context.get_block_timestamp(txn.version).await?;
+                let resolver = self.context.move_resolver()?;
+                let converter = resolver
                     .as_converter()
-                    .try_into_onchain_transaction(timestamp, txn)?
+                    .with_layout_cache(self.context.layout_cache(self.ledger_info.version())?);
+                converter.try_into_onchain_transaction(timestamp, txn)?
             }
             TransactionData::Pending(txn) => {
-                resolver.as_converter().try_into_pending_transaction(*txn)?
+                let resolver = self.context.move_resolver()?;
+                let converter = resolver.as_converter();
+                converter.try_into_pending_transaction(*txn)?
+            }
+        };
+
+        let resp = Response::new(self.ledger_info, &txn)?;
+        Ok(match min_version {
+            Some(min_version) => resp.with_min_version(min_version),
+            None => resp,
+        })
+    }
+
+    /// Re-executes a committed user transaction and returns the gas and time spent in each
+    /// phase of its execution. Only served by debug builds, re-execution is too expensive to
+    /// expose on production nodes.
+    pub async fn trace(self, id: TransactionId) -> Result<impl Reply, Error> {
+        if !cfg!(debug_assertions) {
+            return Err(Error::bad_request(
+                "transaction tracing is only available in debug builds",
+            ));
+        }
+
+        let ledger_version = self.ledger_info.version();
+        let txn = match id.clone() {
+            TransactionId::Hash(hash) => {
+                self.context
+                    .get_transaction_by_hash(hash.into(), ledger_version)
+                    .await?
+            }
+            TransactionId::Version(version) if version <= ledger_version => Some(
+                self.context
+                    .get_transaction_by_version(version, ledger_version)
+                    .await?,
+            ),
+            TransactionId::Version(_) => None,
+        }
+        .ok_or_else(|| self.transaction_not_found(id.clone()))?;
+        let user_txn = match txn.transaction {
+            aptos_types::transaction::Transaction::UserTransaction(user_txn) => user_txn,
+            _ => {
+                return Err(Error::bad_request(format!(
+                    "transaction {} is not a user transaction",
+                    id
+                )))
             }
         };
 
-        Response::new(self.ledger_info, &txn)
+        // The genesis transaction is never a user transaction, so `txn.version` is positive.
+        let state_view = self.context.state_view_at_version(txn.version - 1)?;
+        let (vm_status, output, trace) = AptosVM::trace_user_transaction(user_txn, &state_view)
+            .map_err(|status| {
+                Error::internal(format_err!(
+                    "failed to re-execute transaction {}: {:?}",
+                    id,
+                    status
+                ))
+            })?;
+
+        Response::new(
+            self.ledger_info,
+            &TransactionTrace::new(
+                txn.version,
+                txn.info.transaction_hash(),
+                &vm_status,
+                &output,
+                &trace,
+            ),
+        )
     }
 
     pub fn signing_message(self, txn: UserTransactionRequest) -> Result<impl Reply, Error> {
+        transaction_limits::check_request(&self.context.api_config(), &txn)?;
         let resolver = self.context.move_resolver()?;
         let raw_txn: RawTransaction = resolver
             .as_converter()
@@ -306,25 +651,25 @@ impl Transactions {
         Error::not_found("transaction", id, self.ledger_info.version())
     }
 
-    fn get_by_version(&self, version: u64) -> Result<Option<TransactionData>> {
+    async fn get_by_version(&self, version: u64) -> Result<Option<TransactionData>> {
         if version > self.ledger_info.version() {
             return Ok(None);
         }
         Ok(Some(
             self.context
-                .get_transaction_by_version(version, self.ledger_info.version())?
+                .get_transaction_by_version(version, self.ledger_info.version())
+                .await?
                 .into(),
         ))
     }
 
     // This function looks for the transaction by hash in database and then mempool,
     // because the period a transaction stay in the mempool is likely short.
-    // Although the mempool get transation is async, but looking up txn in database is a sync call,
-    // thus we keep it simple and call them in sequence.
     async fn get_by_hash(&self, hash: aptos_crypto::HashValue) -> Result<Option<TransactionData>> {
         let from_db = self
             .context
-            .get_transaction_by_hash(hash, self.ledger_info.version())?;
+            .get_transaction_by_hash(hash, self.ledger_info.version())
+            .await?;
         Ok(match from_db {
             None => self
                 .context
@@ -0,0 +1,464 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, failpoint::fail_point, metrics::metrics, param::Param};
+
+use aptos_api_types::{
+    EpochValidatorSet, EpochValidatorStats, Error, LedgerInfo, Response, ValidatorInfo,
+    ValidatorStats,
+};
+
+use anyhow::anyhow;
+use aptos_infallible::Mutex;
+use aptos_types::{
+    account_address::AccountAddress,
+    block_metadata::{new_block_event_key, NewBlockEvent},
+    on_chain_config::{access_path_for_config, OnChainConfig, ValidatorSet},
+    state_store::state_key::StateKey,
+    validator_verifier::ValidatorVerifier,
+};
+use serde::Deserialize;
+use std::{collections::BTreeMap, num::NonZeroU64};
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// Maximum number of epochs summarized by a stats request.
+const MAX_STATS_EPOCHS: u64 = 10;
+
+/// Number of block events read from storage at a time.
+const BLOCK_EVENT_PAGE_SIZE: u16 = 1000;
+
+/// Number of completed epochs whose stats are cached, enough to serve any stats request from the
+/// cache but for the current epoch.
+const MAX_CACHED_EPOCHS: usize = MAX_STATS_EPOCHS as usize;
+
+#[derive(Clone, Debug, Deserialize)]
+struct EpochQuery {
+    epoch: Option<Param<u64>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct EpochsQuery {
+    epochs: Option<Param<NonZeroU64>>,
+}
+
+// GET /validators?epoch=<epoch>
+pub fn get_validators(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("validators")
+        .and(warp::get())
+        .and(warp::query::<EpochQuery>())
+        .and(context.filter())
+        .and_then(handle_get_validators)
+        .with(metrics("get_validators"))
+        .boxed()
+}
+
+async fn handle_get_validators(
+    query: EpochQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_validators")?;
+    let epoch = query.epoch.map(|epoch| epoch.parse("epoch")).transpose()?;
+    Ok(Validators::new(context).await?.validator_set(epoch).await?)
+}
+
+// GET /validators/stats?epochs=<epochs>
+pub fn get_validator_stats(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("validators" / "stats")
+        .and(warp::get())
+        .and(warp::query::<EpochsQuery>())
+        .and(context.filter())
+        .and_then(handle_get_validator_stats)
+        .with(metrics("get_validator_stats"))
+        .boxed()
+}
+
+async fn handle_get_validator_stats(
+    query: EpochsQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_validator_stats")?;
+    let epochs = query
+        .epochs
+        .map(|epochs| epochs.parse("epochs"))
+        .transpose()?
+        .map_or(1, NonZeroU64::get);
+    if epochs > MAX_STATS_EPOCHS {
+        return Err(Error::invalid_param(
+            "epochs",
+            format!("{}, exceed limit {}", epochs, MAX_STATS_EPOCHS),
+        )
+        .into());
+    }
+    Ok(Validators::new(context).await?.stats(epochs).await?)
+}
+
+struct Validators {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Validators {
+    async fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info().await?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    /// Renders the validator set of `epoch` (the current epoch if `None`). The validator set of
+    /// an epoch is the `ValidatorSet` config as of the version that ended the previous epoch.
+    pub async fn validator_set(self, epoch: Option<u64>) -> Result<impl Reply, Error> {
+        // The latest ledger info may end an epoch (e.g. the genesis ledger info ends epoch 0), in
+        // which case the validator set of the next epoch is already in effect.
+        let current_epoch = self
+            .context
+            .get_latest_ledger_info_with_signatures()
+            .await?
+            .ledger_info()
+            .next_block_epoch();
+        let epoch = epoch.unwrap_or(current_epoch);
+        // epoch 0 only consists of the genesis transaction and has no validator set
+        if epoch == 0 || epoch > current_epoch {
+            return Err(Error::not_found(
+                "validator set",
+                format!("epoch {}", epoch),
+                self.ledger_info.version(),
+            ));
+        }
+
+        let (start_version, validator_set) = self.read_validator_set(epoch).await?;
+        let validators = validator_set
+            .payload()
+            .map(ValidatorInfo::try_from_validator_info)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Response::new(
+            self.ledger_info,
+            &EpochValidatorSet {
+                epoch: epoch.into(),
+                start_version: start_version.into(),
+                validators,
+            },
+        )
+    }
+
+    /// Renders the participation of the validators in consensus during the last `epochs` epochs,
+    /// newest first. The epochs are summarized from the `NewBlockEvent`s of the committed blocks,
+    /// the current epoch only up to the latest committed block. The block events of a completed
+    /// epoch never change, so its stats are cached and its events are only read once.
+    pub async fn stats(self, epochs: u64) -> Result<impl Reply, Error> {
+        let ledger_version = self.ledger_info.version();
+        let mut stats: Vec<EpochValidatorStats> = vec![];
+        // the epoch of the latest block event, all the epochs before it are completed
+        let mut latest_epoch = None;
+        // the epoch being summarized and the sequence number of its earliest block event read
+        let mut current: Option<(EpochBlocks, u64)> = None;
+        let mut start = u64::max_value();
+        'pages: loop {
+            let events = self
+                .context
+                .get_events_descending(
+                    &new_block_event_key(),
+                    start,
+                    BLOCK_EVENT_PAGE_SIZE,
+                    ledger_version,
+                )
+                .await?;
+            for contract_event in &events {
+                let event: NewBlockEvent =
+                    bcs::from_bytes(contract_event.event_data()).map_err(|e| {
+                        Error::internal(anyhow!("failed to deserialize block event: {}", e))
+                    })?;
+                let latest = *latest_epoch.get_or_insert(event.epoch());
+                if let Some((blocks, first_event)) = &mut current {
+                    if blocks.epoch == event.epoch() {
+                        blocks.add(&event);
+                        *first_event = contract_event.sequence_number();
+                        continue;
+                    }
+                }
+                // `event` is the last block of its epoch, the epoch before is fully summarized
+                if let Some((blocks, first_event)) = current.take() {
+                    stats.push(self.epoch_stats(blocks, first_event, latest).await?);
+                }
+                if stats.len() as u64 == epochs {
+                    break 'pages;
+                }
+                match self.context.epoch_stats_cache().get(event.epoch()) {
+                    Some(cached) => {
+                        stats.push(cached.stats);
+                        if stats.len() as u64 == epochs || cached.first_event == 0 {
+                            break 'pages;
+                        }
+                        // skip the rest of the block events of the cached epoch
+                        start = cached.first_event - 1;
+                        continue 'pages;
+                    }
+                    None => {
+                        let mut blocks = EpochBlocks::new(event.epoch());
+                        blocks.add(&event);
+                        current = Some((blocks, contract_event.sequence_number()));
+                    }
+                }
+            }
+            match events.last() {
+                Some(last)
+                    if events.len() == BLOCK_EVENT_PAGE_SIZE as usize
+                        && last.sequence_number() > 0 =>
+                {
+                    start = last.sequence_number() - 1;
+                }
+                _ => break,
+            }
+        }
+        // the earliest block events have been reached
+        if let Some((blocks, first_event)) = current {
+            let latest_epoch = latest_epoch.unwrap_or(blocks.epoch);
+            stats.push(self.epoch_stats(blocks, first_event, latest_epoch).await?);
+        }
+        Response::new(self.ledger_info, &stats)
+    }
+
+    /// Summarizes the `blocks` of an epoch, whose earliest block event is `first_event`, and
+    /// caches the stats if the epoch is completed, i.e. before `latest_epoch`.
+    async fn epoch_stats(
+        &self,
+        blocks: EpochBlocks,
+        first_event: u64,
+        latest_epoch: u64,
+    ) -> Result<EpochValidatorStats, Error> {
+        let epoch = blocks.epoch;
+        let validators = self.validator_addresses(epoch).await?;
+        let stats = blocks.into_stats(&validators);
+        if epoch < latest_epoch {
+            self.context.epoch_stats_cache().insert(
+                epoch,
+                CompletedEpoch {
+                    stats: stats.clone(),
+                    first_event,
+                },
+            );
+        }
+        Ok(stats)
+    }
+
+    /// Reads the validator set of `epoch` and the version the epoch started at.
+    async fn read_validator_set(&self, epoch: u64) -> Result<(u64, ValidatorSet), Error> {
+        let start_version = self
+            .context
+            .get_epoch_ending_ledger_info(epoch - 1)
+            .await?
+            .ledger_info()
+            .version();
+        let state_key = StateKey::AccessPath(access_path_for_config(ValidatorSet::CONFIG_ID));
+        let bytes = self
+            .context
+            .get_state_value(&state_key, start_version)
+            .await?
+            .ok_or_else(|| {
+                Error::internal(anyhow!(
+                    "validator set not found at version {}",
+                    start_version
+                ))
+            })?;
+        let validator_set: ValidatorSet = bcs::from_bytes(&bytes)
+            .map_err(|e| Error::internal(anyhow!("failed to deserialize validator set: {}", e)))?;
+
+        Ok((start_version, validator_set))
+    }
+
+    /// Returns the addresses of the validator set of `epoch` in the order of the vote bitmaps of
+    /// its blocks, i.e. sorted. Epoch 0 has no validator set.
+    async fn validator_addresses(&self, epoch: u64) -> Result<Vec<AccountAddress>, Error> {
+        if epoch == 0 {
+            return Ok(vec![]);
+        }
+        let (_, validator_set) = self.read_validator_set(epoch).await?;
+        Ok(ValidatorVerifier::from(&validator_set)
+            .get_ordered_account_addresses_iter()
+            .collect())
+    }
+}
+
+/// The stats of a completed epoch.
+#[derive(Clone)]
+pub(crate) struct CompletedEpoch {
+    stats: EpochValidatorStats,
+    // sequence number of the first block event of the epoch
+    first_event: u64,
+}
+
+/// The stats of the latest completed epochs, evicting the earliest ones.
+#[derive(Default)]
+pub(crate) struct EpochStatsCache {
+    epochs: Mutex<BTreeMap<u64, CompletedEpoch>>,
+}
+
+impl EpochStatsCache {
+    fn get(&self, epoch: u64) -> Option<CompletedEpoch> {
+        self.epochs.lock().get(&epoch).cloned()
+    }
+
+    fn insert(&self, epoch: u64, completed: CompletedEpoch) {
+        let mut epochs = self.epochs.lock();
+        epochs.insert(epoch, completed);
+        while epochs.len() > MAX_CACHED_EPOCHS {
+            let earliest = *epochs.keys().next().unwrap();
+            epochs.remove(&earliest);
+        }
+    }
+}
+
+/// The committed blocks of an epoch.
+struct EpochBlocks {
+    epoch: u64,
+    // highest round of the blocks, rounds start at 1 in every epoch
+    max_round: u64,
+    // number of blocks proposed by a validator, i.e. excluding NIL blocks
+    blocks: u64,
+    proposals: BTreeMap<AccountAddress, u64>,
+    // number of votes at each position of the vote bitmaps, and number of bitmaps
+    votes: Vec<u64>,
+    bitmaps: u64,
+}
+
+impl EpochBlocks {
+    fn new(epoch: u64) -> Self {
+        Self {
+            epoch,
+            max_round: 0,
+            blocks: 0,
+            proposals: BTreeMap::new(),
+            votes: vec![],
+            bitmaps: 0,
+        }
+    }
+
+    fn add(&mut self, event: &NewBlockEvent) {
+        self.max_round = self.max_round.max(event.round());
+        // NIL blocks are proposed by the reserved address 0x0
+        if event.proposer() != AccountAddress::ZERO {
+            self.blocks += 1;
+            *self.proposals.entry(event.proposer()).or_default() += 1;
+        }
+        let bitmap = event.previous_block_votes();
+        if self.bitmaps == 0 {
+            self.votes = vec![0; bitmap.len()];
+        }
+        if bitmap.len() == self.votes.len() {
+            self.bitmaps += 1;
+            for (votes, voted) in self.votes.iter_mut().zip(bitmap) {
+                if *voted {
+                    *votes += 1;
+                }
+            }
+        }
+    }
+
+    /// `validators` are the addresses of the validator set of the epoch, in the order of the vote
+    /// bitmaps. Votes are only counted if the bitmaps match the validator set.
+    fn into_stats(self, validators: &[AccountAddress]) -> EpochValidatorStats {
+        let votes_known = !validators.is_empty() && validators.len() == self.votes.len();
+        let mut stats: Vec<_> = validators
+            .iter()
+            .enumerate()
+            .map(|(i, address)| {
+                let votes = if votes_known { self.votes[i] } else { 0 };
+                let missed_votes = if votes_known { self.bitmaps - votes } else { 0 };
+                ValidatorStats {
+                    address: (*address).into(),
+                    proposals: self.proposals.get(address).copied().unwrap_or(0).into(),
+                    votes: votes.into(),
+                    missed_votes: missed_votes.into(),
+                }
+            })
+            .collect();
+        // proposers missing from the validator set, e.g. in epoch 0
+        for (address, proposals) in &self.proposals {
+            if !validators.contains(address) {
+                stats.push(ValidatorStats {
+                    address: (*address).into(),
+                    proposals: (*proposals).into(),
+                    votes: 0.into(),
+                    missed_votes: 0.into(),
+                });
+            }
+        }
+        EpochValidatorStats {
+            epoch: self.epoch.into(),
+            blocks: self.blocks.into(),
+            failed_rounds: self.max_round.saturating_sub(self.blocks).into(),
+            validators: stats,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CompletedEpoch, EpochBlocks, EpochStatsCache, MAX_CACHED_EPOCHS};
+    use aptos_api_types::Address;
+    use aptos_types::{account_address::AccountAddress, block_metadata::NewBlockEvent};
+
+    #[test]
+    fn test_epoch_blocks_into_stats() {
+        let validators = vec![AccountAddress::random(), AccountAddress::random()];
+        let mut blocks = EpochBlocks::new(2);
+        // round 2 timed out and round 4 committed a NIL block
+        for (round, proposer, votes) in [
+            (1, validators[0], vec![true, true]),
+            (3, validators[1], vec![true, false]),
+            (4, AccountAddress::ZERO, vec![true, false]),
+            (5, validators[0], vec![true, true]),
+        ] {
+            blocks.add(&NewBlockEvent::new(2, round, votes, proposer, 0));
+        }
+
+        let stats = blocks.into_stats(&validators);
+        assert_eq!(stats.epoch.0, 2);
+        assert_eq!(stats.blocks.0, 3);
+        assert_eq!(stats.failed_rounds.0, 2);
+        assert_eq!(stats.validators.len(), 2);
+        assert_eq!(stats.validators[0].proposals.0, 2);
+        assert_eq!(stats.validators[0].votes.0, 4);
+        assert_eq!(stats.validators[0].missed_votes.0, 0);
+        assert_eq!(stats.validators[1].proposals.0, 1);
+        assert_eq!(stats.validators[1].votes.0, 2);
+        assert_eq!(stats.validators[1].missed_votes.0, 2);
+    }
+
+    #[test]
+    fn test_epoch_blocks_without_validator_set() {
+        let proposer = AccountAddress::random();
+        let mut blocks = EpochBlocks::new(0);
+        blocks.add(&NewBlockEvent::new(0, 1, vec![true], proposer, 0));
+
+        let stats = blocks.into_stats(&[]);
+        assert_eq!(stats.validators.len(), 1);
+        assert_eq!(stats.validators[0].address, Address::from(proposer));
+        assert_eq!(stats.validators[0].proposals.0, 1);
+        assert_eq!(stats.validators[0].votes.0, 0);
+    }
+
+    #[test]
+    fn test_epoch_stats_cache_evicts_earliest_epochs() {
+        let cache = EpochStatsCache::default();
+        let num_epochs = MAX_CACHED_EPOCHS as u64 + 2;
+        for epoch in 0..num_epochs {
+            cache.insert(
+                epoch,
+                CompletedEpoch {
+                    stats: EpochBlocks::new(epoch).into_stats(&[]),
+                    first_event: epoch * 10,
+                },
+            );
+        }
+
+        assert!(cache.get(0).is_none());
+        assert!(cache.get(1).is_none());
+        for epoch in 2..num_epochs {
+            let cached = cache.get(epoch).unwrap();
+            assert_eq!(cached.stats.epoch.0, epoch);
+            assert_eq!(cached.first_event, epoch * 10);
+        }
+    }
+}
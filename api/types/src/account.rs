@@ -1,15 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{HexEncodedBytes, U64};
+use crate::{Address, EventKey, HexEncodedBytes, MoveResource, MoveStructTag, MoveType, U64};
 
-use aptos_types::account_config::AccountResource;
+use aptos_types::account_config::{AccountResource, CRSNResource};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AccountData {
     pub sequence_number: U64,
     pub authentication_key: HexEncodedBytes,
+    /// The nonce window of an account using conflict resistant sequence numbers (CRSN), omitted
+    /// for the accounts using sequential sequence numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crsn: Option<CrsnWindow>,
 }
 
 impl From<AccountResource> for AccountData {
@@ -17,6 +21,181 @@ impl From<AccountResource> for AccountData {
         Self {
             sequence_number: ar.sequence_number().into(),
             authentication_key: ar.authentication_key().to_vec().into(),
+            crsn: None,
         }
     }
 }
+
+/// The transactions of a CRSN account are accepted in any order as long as their sequence
+/// number is in `[min_nonce, min_nonce + size)` and was not used yet.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CrsnWindow {
+    pub min_nonce: U64,
+    pub size: U64,
+}
+
+impl From<&CRSNResource> for CrsnWindow {
+    fn from(crsn: &CRSNResource) -> Self {
+        Self {
+            min_nonce: crsn.min_nonce().into(),
+            size: crsn.size().into(),
+        }
+    }
+}
+
+/// Proof that an account does not exist at `version`, so that light clients can verify it rather
+/// than trust a 404. Both fields are BCS encoded: the `StateValueWithProof` of the account's
+/// `0x1::Account::Account` resource carries no value and is verified against the ledger info,
+/// whose signatures are verified against the validator set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountNonExistenceProof {
+    pub version: U64,
+    pub ledger_info_with_signatures: HexEncodedBytes,
+    pub proof: HexEncodedBytes,
+}
+
+/// An event handle of the resources of an account. `field` is the path of the handle in the
+/// resource, a top level field can be passed to
+/// `GET /accounts/{address}/events/{event_handle_struct}/{field_name}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountEventHandle {
+    pub resource_type: MoveStructTag,
+    pub field: String,
+    pub key: EventKey,
+    pub creation_number: U64,
+    pub event_type: MoveType,
+    /// Number of events emitted so far, the sequence number of the next event.
+    pub counter: U64,
+}
+
+/// Request of `POST /accounts/resources/batch`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountResourcesBatchRequest {
+    pub addresses: Vec<Address>,
+    /// Only return resources of this type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<MoveStructTag>,
+}
+
+/// The resources of one account, an account that does not exist has no resources.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountResources {
+    pub address: Address,
+    pub resources: Vec<MoveResource>,
+}
+
+/// Sequence number diagnostics of an account: the committed sequence number, the sequence numbers
+/// of its transactions waiting in mempool and the gaps in between, which keep later transactions
+/// parked until the missing ones are submitted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountSequenceNumbers {
+    pub committed_sequence_number: U64,
+    pub pending_sequence_numbers: Vec<U64>,
+    pub gaps: Vec<SequenceNumberRange>,
+}
+
+/// Inclusive range of sequence numbers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SequenceNumberRange {
+    pub start: U64,
+    pub end: U64,
+}
+
+impl AccountSequenceNumbers {
+    /// `pending` must be sorted in ascending order; sequence numbers lower than the committed
+    /// one are stale and ignored.
+    pub fn new(committed_sequence_number: u64, pending: Vec<u64>) -> Self {
+        let mut gaps = vec![];
+        let mut next = committed_sequence_number;
+        let pending: Vec<u64> = pending
+            .into_iter()
+            .filter(|seq| *seq >= committed_sequence_number)
+            .collect();
+        for seq in &pending {
+            if *seq > next {
+                gaps.push(SequenceNumberRange {
+                    start: next.into(),
+                    end: (seq - 1).into(),
+                });
+            }
+            // the pending sequence numbers are sorted, none follows u64::MAX
+            next = seq.saturating_add(1);
+        }
+        Self {
+            committed_sequence_number: committed_sequence_number.into(),
+            pending_sequence_numbers: pending.into_iter().map(U64::from).collect(),
+            gaps,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        account::SequenceNumberRange, AccountData, AccountSequenceNumbers, CrsnWindow, U64,
+    };
+    use serde_json::json;
+
+    fn range(start: u64, end: u64) -> SequenceNumberRange {
+        SequenceNumberRange {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+
+    #[test]
+    fn test_no_gaps() {
+        let seqs = AccountSequenceNumbers::new(3, vec![3, 4, 5]);
+        assert!(seqs.gaps.is_empty());
+        assert_eq!(seqs.pending_sequence_numbers, vec![U64(3), U64(4), U64(5)]);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let seqs = AccountSequenceNumbers::new(3, vec![1, 5, 6, 9]);
+        assert_eq!(seqs.gaps, vec![range(3, 4), range(7, 8)]);
+        assert_eq!(seqs.pending_sequence_numbers, vec![U64(5), U64(6), U64(9)]);
+    }
+
+    #[test]
+    fn test_max_sequence_number() {
+        let seqs = AccountSequenceNumbers::new(u64::MAX - 1, vec![u64::MAX - 1, u64::MAX]);
+        assert!(seqs.gaps.is_empty());
+        assert_eq!(
+            seqs.pending_sequence_numbers,
+            vec![U64(u64::MAX - 1), U64(u64::MAX)]
+        );
+
+        let seqs = AccountSequenceNumbers::new(0, vec![u64::MAX]);
+        assert_eq!(seqs.gaps, vec![range(0, u64::MAX - 1)]);
+    }
+
+    #[test]
+    fn test_account_data_crsn_json() {
+        let mut account = AccountData {
+            sequence_number: 3.into(),
+            authentication_key: vec![1, 2].into(),
+            crsn: None,
+        };
+        let value = serde_json::to_value(&account).unwrap();
+        assert_eq!(
+            value,
+            json!({"sequence_number": "3", "authentication_key": "0x0102"})
+        );
+        assert_eq!(
+            serde_json::from_value::<AccountData>(value).unwrap(),
+            account
+        );
+
+        account.crsn = Some(CrsnWindow {
+            min_nonce: 10.into(),
+            size: 128.into(),
+        });
+        let value = serde_json::to_value(&account).unwrap();
+        assert_eq!(value["crsn"], json!({"min_nonce": "10", "size": "128"}));
+        assert_eq!(
+            serde_json::from_value::<AccountData>(value).unwrap(),
+            account
+        );
+    }
+}
@@ -0,0 +1,62 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, Event, HashValue, MoveModuleId, MoveStructTag, Transaction, U64};
+
+use serde::{Deserialize, Serialize};
+
+/// Counts and most recent items of the activity of an account, so that explorers can render an
+/// account page without an indexer.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct AccountActivity {
+    pub sent_transactions: ActivitySummary<Transaction>,
+    pub received_coin_events: ActivitySummary<Event>,
+    pub modules: ActivitySummary<CreatedModule>,
+    pub resources: ActivitySummary<CreatedResource>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ActivitySummary<T> {
+    pub count: U64,
+    /// Most recent first.
+    pub recent: Vec<T>,
+}
+
+/// A module published under the account.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CreatedModule {
+    pub module: MoveModuleId,
+    /// Version of the transaction that published the module, unknown if it was published before
+    /// the state snapshot the node was restored from.
+    pub version: Option<U64>,
+}
+
+/// A resource held by the account.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CreatedResource {
+    #[serde(rename = "type")]
+    pub typ: MoveStructTag,
+    /// Version of the transaction that created the resource, unknown if it was created before
+    /// the state snapshot the node was restored from.
+    pub version: Option<U64>,
+}
+
+/// A transaction that wrote the code of a module, the first one published it and each of the
+/// following ones upgraded it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ModuleChange {
+    pub kind: ModuleChangeKind,
+    /// Version of the transaction that wrote the module.
+    pub version: U64,
+    pub transaction_hash: HashValue,
+    /// Absent for the genesis and write set transactions.
+    pub sender: Option<Address>,
+    pub timestamp: U64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleChangeKind {
+    Publish,
+    Upgrade,
+}
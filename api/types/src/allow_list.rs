@@ -0,0 +1,22 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// JSON representation of the on-chain script function allow list.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ScriptFunctionAllowList {
+    /// Any script function can be called when the allow list isn't enabled.
+    pub enabled: bool,
+    /// Functions named `<address>::<module>::<function>`.
+    pub allowed_functions: Vec<String>,
+}
+
+impl From<&aptos_types::on_chain_config::ScriptFunctionAllowList> for ScriptFunctionAllowList {
+    fn from(allow_list: &aptos_types::on_chain_config::ScriptFunctionAllowList) -> Self {
+        Self {
+            enabled: allow_list.enabled(),
+            allowed_functions: allow_list.allowed_functions(),
+        }
+    }
+}
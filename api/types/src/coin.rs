@@ -0,0 +1,38 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{MoveStructTag, U64};
+
+use aptos_types::account_config::CoinInfoResource;
+use serde::{Deserialize, Serialize};
+
+/// Metadata of a coin type, so that wallets can render balances without hard-coding it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CoinInfo {
+    pub coin_type: MoveStructTag,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: U64,
+    /// Amount of the coin in existence, absent if the supply is not tracked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supply: Option<U64>,
+}
+
+impl CoinInfo {
+    pub fn new<T>(coin_type: MoveStructTag, info: &CoinInfoResource<T>) -> Self {
+        Self {
+            coin_type,
+            name: info.name().to_owned(),
+            symbol: info.symbol().to_owned(),
+            decimals: info.decimals().into(),
+            supply: info.supply().map(Into::into),
+        }
+    }
+}
+
+/// Amount of a coin type in existence, kept up to date on every mint and burn.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CoinSupply {
+    pub coin_type: MoveStructTag,
+    pub supply: U64,
+}
@@ -3,18 +3,20 @@
 
 use crate::{
     transaction::{ModuleBundlePayload, StateCheckpointTransaction},
-    Bytecode, DirectWriteSet, Event, HexEncodedBytes, MoveFunction, MoveModuleBytecode,
-    MoveResource, MoveScriptBytecode, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
-    ScriptPayload, ScriptWriteSet, Transaction, TransactionInfo, TransactionOnChainData,
-    TransactionPayload, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    Bytecode, DirectWriteSet, Event, HexEncodedBytes, LayoutCache, MoveAbort, MoveFunction,
+    MoveModuleBytecode, MoveModuleId, MoveResource, MoveScriptBytecode, MoveValue,
+    ScriptFunctionId, ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction,
+    TransactionInfo, TransactionOnChainData, TransactionPayload, UserTransactionRequest, WriteSet,
+    WriteSetChange, WriteSetPayload,
 };
-use anyhow::{bail, ensure, format_err, Result};
+use anyhow::{ensure, format_err, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_transaction_builder::error_explain;
 use aptos_types::{
     access_path::{AccessPath, Path},
     chain_id::ChainId,
     contract_event::ContractEvent,
+    event::{EventHandle, EventKey},
     state_store::state_key::StateKey,
     transaction::{
         ExecutionStatus, ModuleBundle, RawTransaction, Script, ScriptFunction, SignedTransaction,
@@ -28,29 +30,39 @@ use move_deps::{
     move_core_types,
     move_core_types::{
         identifier::Identifier,
-        language_storage::{ModuleId, StructTag, TypeTag},
-        value::{MoveStructLayout, MoveTypeLayout},
+        language_storage::{ModuleId, StructTag, TypeTag, CORE_CODE_ADDRESS},
+        value::MoveTypeLayout,
     },
-    move_resource_viewer::MoveValueAnnotator,
+    move_resource_viewer::{AnnotatedMoveStruct, AnnotatedMoveValue, MoveValueAnnotator},
 };
 use serde_json::Value;
 use std::{
     convert::{TryFrom, TryInto},
     iter::IntoIterator,
     rc::Rc,
+    sync::Arc,
 };
 
 pub struct MoveConverter<'a, R: ?Sized> {
     inner: MoveValueAnnotator<'a, R>,
+    layouts: Option<&'a LayoutCache>,
 }
 
 impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
     pub fn new(inner: &'a R) -> Self {
         Self {
             inner: MoveValueAnnotator::new(inner),
+            layouts: None,
         }
     }
 
+    /// Renders resources and events with the struct layouts of `layouts`, computed layouts are
+    /// added to it. The cache must be in sync with the modules of the resolver.
+    pub fn with_layout_cache(mut self, layouts: Option<&'a LayoutCache>) -> Self {
+        self.layouts = layouts;
+        self
+    }
+
     pub fn try_into_resources<'b>(
         &self,
         data: impl Iterator<Item = (StructTag, &'b [u8])>,
@@ -60,7 +72,30 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
     }
 
     pub fn try_into_resource<'b>(&self, typ: &StructTag, bytes: &'b [u8]) -> Result<MoveResource> {
-        self.inner.view_resource(typ, bytes)?.try_into()
+        match self.layouts {
+            Some(layouts) => Ok(MoveResource {
+                typ: typ.clone().into(),
+                data: aptos_move_value::bcs_to_json(&self.struct_layout(layouts, typ)?, bytes)?
+                    .try_into()?,
+            }),
+            None => self.inner.view_resource(typ, bytes)?.try_into(),
+        }
+    }
+
+    /// Finds the event handles of a resource, including the handles nested in structs and
+    /// vectors, along with the path of their field, e.g. `config.events` or `handles[0]`, and the
+    /// type of their events.
+    pub fn find_event_handles(
+        &self,
+        typ: &StructTag,
+        bytes: &[u8],
+    ) -> Result<Vec<(String, TypeTag, EventHandle)>> {
+        let resource = self.inner.view_resource(typ, bytes)?;
+        let mut handles = vec![];
+        for (name, value) in &resource.value {
+            find_event_handles(name.to_string(), value, &mut handles)?;
+        }
+        Ok(handles)
     }
 
     pub fn move_struct_fields<'b>(
@@ -123,6 +158,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
             gas_used: info.gas_used().into(),
             success: info.status().is_success(),
             vm_status: self.explain_vm_status(info.status()),
+            vm_error: self.try_into_vm_error(info.status()),
             accumulator_root_hash: accumulator_root_hash.into(),
             // TODO: the resource value is interpreted by the type definition at the version of the converter, not the version of the tx: must be fixed before we allow module updates
             changes: write_set
@@ -288,10 +324,16 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
     pub fn try_into_events(&self, events: &[ContractEvent]) -> Result<Vec<Event>> {
         let mut ret = vec![];
         for event in events {
-            let data = self
-                .inner
-                .view_value(event.type_tag(), event.event_data())?;
-            ret.push((event, MoveValue::try_from(data)?.json()?).into());
+            let data = match (self.layouts, event.type_tag()) {
+                (Some(layouts), TypeTag::Struct(typ)) => aptos_move_value::bcs_to_json(
+                    &self.struct_layout(layouts, typ)?,
+                    event.event_data(),
+                )?,
+                (_, typ) => {
+                    MoveValue::try_from(self.inner.view_value(typ, event.event_data())?)?.json()?
+                }
+            };
+            ret.push((event, data).into());
         }
         Ok(ret)
     }
@@ -472,95 +514,41 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
     ) -> Result<move_core_types::value::MoveValue> {
         let layout = self.inner.get_type_layout_with_types(type_tag)?;
 
-        self.try_into_vm_value_from_layout(&layout, val)
+        aptos_move_value::json_to_move_value(&layout, val)
     }
 
-    fn try_into_vm_value_from_layout(
-        &self,
-        layout: &MoveTypeLayout,
-        val: Value,
-    ) -> Result<move_core_types::value::MoveValue> {
-        use move_deps::move_core_types::value::MoveValue::*;
-
-        Ok(match layout {
-            MoveTypeLayout::Bool => Bool(serde_json::from_value::<bool>(val)?),
-            MoveTypeLayout::U8 => U8(serde_json::from_value::<u8>(val)?),
-            MoveTypeLayout::U64 => serde_json::from_value::<crate::U64>(val)?.into(),
-            MoveTypeLayout::U128 => serde_json::from_value::<crate::U128>(val)?.into(),
-            MoveTypeLayout::Address => serde_json::from_value::<crate::Address>(val)?.into(),
-            MoveTypeLayout::Vector(item_layout) => {
-                self.try_into_vm_value_vector(item_layout.as_ref(), val)?
-            }
-            MoveTypeLayout::Struct(struct_layout) => {
-                self.try_into_vm_value_struct(struct_layout, val)?
+    fn try_into_vm_error(&self, status: &ExecutionStatus) -> Option<VmError> {
+        match status {
+            ExecutionStatus::Success => None,
+            ExecutionStatus::OutOfGas => Some(VmError::OutOfGas),
+            ExecutionStatus::MoveAbort { location, code } => {
+                Some(VmError::MoveAbort(MoveAbort::new(location, *code)))
             }
-            MoveTypeLayout::Signer => {
-                bail!("unexpected move type {:?} for value {:?}", layout, val)
+            ExecutionStatus::ExecutionFailure {
+                location,
+                function,
+                code_offset,
+            } => {
+                let (location, function) = match location {
+                    AbortLocation::Module(module_id) => (
+                        MoveModuleId::from(module_id.clone()).to_string(),
+                        self.explain_function_index(module_id, function)
+                            .unwrap_or_else(|_| format!("<#{} function>", function)),
+                    ),
+                    AbortLocation::Script => {
+                        ("script".to_owned(), format!("<#{} function>", function))
+                    }
+                };
+                Some(VmError::ExecutionFailure {
+                    location,
+                    function,
+                    code_offset: *code_offset,
+                })
             }
-        })
-    }
-
-    pub fn try_into_vm_value_vector(
-        &self,
-        layout: &MoveTypeLayout,
-        val: Value,
-    ) -> Result<move_core_types::value::MoveValue> {
-        if matches!(layout, MoveTypeLayout::U8) {
-            Ok(serde_json::from_value::<HexEncodedBytes>(val)?.into())
-        } else if let Value::Array(list) = val {
-            let vals = list
-                .into_iter()
-                .map(|v| self.try_into_vm_value_from_layout(layout, v))
-                .collect::<Result<_>>()?;
-
-            Ok(move_core_types::value::MoveValue::Vector(vals))
-        } else {
-            bail!("expected vector<{:?}>, but got: {:?}", layout, val)
-        }
-    }
-
-    pub fn try_into_vm_value_struct(
-        &self,
-        layout: &MoveStructLayout,
-        val: Value,
-    ) -> Result<move_core_types::value::MoveValue> {
-        let (struct_tag, field_layouts) =
-            if let MoveStructLayout::WithTypes { type_, fields } = layout {
-                (type_, fields)
-            } else {
-                bail!(
-                    "Expecting `MoveStructLayout::WithTypes`, getting {:?}",
-                    layout
-                );
-            };
-        if MoveValue::is_ascii_string(struct_tag) {
-            let string = val
-                .as_str()
-                .ok_or_else(|| format_err!("failed to parse ASCII::String."))?;
-            return Ok(new_vm_ascii_string(string));
+            ExecutionStatus::MiscellaneousError(code) => Some(VmError::MiscellaneousError {
+                status_code: code.map(|code| format!("{:?}", code)),
+            }),
         }
-
-        let mut field_values = if let Value::Object(fields) = val {
-            fields
-        } else {
-            bail!("Expecting a JSON Map for struct.");
-        };
-
-        let fields = field_layouts
-            .iter()
-            .map(|field_layout| {
-                let name = field_layout.name.as_str();
-                let value = field_values
-                    .remove(name)
-                    .ok_or_else(|| format_err!("field {} not found.", name))?;
-                let move_value = self.try_into_vm_value_from_layout(&field_layout.layout, value)?;
-                Ok(move_value)
-            })
-            .collect::<Result<_>>()?;
-
-        Ok(move_core_types::value::MoveValue::Struct(
-            move_core_types::value::MoveStruct::Runtime(fields),
-        ))
     }
 
     fn explain_vm_status(&self, status: &ExecutionStatus) -> String {
@@ -618,6 +606,16 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         self.inner.view_value(typ, bytes)?.try_into()
     }
 
+    fn struct_layout(&self, layouts: &LayoutCache, typ: &StructTag) -> Result<Arc<MoveTypeLayout>> {
+        if let Some(layout) = layouts.get(typ) {
+            return Ok(layout);
+        }
+        let layout = self
+            .inner
+            .get_type_layout_with_types(&TypeTag::Struct(typ.clone()))?;
+        Ok(layouts.insert(typ.clone(), layout))
+    }
+
     fn explain_function_index(&self, module_id: &ModuleId, function: &u16) -> Result<String> {
         let code = self.inner.get_module(&module_id.clone())? as Rc<dyn Bytecode>;
         let func = code.function_handle_at(FunctionHandleIndex::new(*function));
@@ -626,6 +624,82 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
     }
 }
 
+fn find_event_handles(
+    path: String,
+    value: &AnnotatedMoveValue,
+    handles: &mut Vec<(String, TypeTag, EventHandle)>,
+) -> Result<()> {
+    match value {
+        AnnotatedMoveValue::Struct(handle) if is_event_handle(&handle.type_) => {
+            let event_type = handle
+                .type_
+                .type_params
+                .first()
+                .cloned()
+                .ok_or_else(|| format_err!("event handle without event type: {}", path))?;
+            handles.push((path, event_type, into_event_handle(handle)?));
+        }
+        AnnotatedMoveValue::Struct(value) => {
+            for (name, field) in &value.value {
+                find_event_handles(format!("{}.{}", path, name), field, handles)?;
+            }
+        }
+        AnnotatedMoveValue::Vector(_, items) => {
+            for (i, item) in items.iter().enumerate() {
+                find_event_handles(format!("{}[{}]", path, i), item, handles)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn is_event_handle(typ: &StructTag) -> bool {
+    typ.address == CORE_CODE_ADDRESS
+        && typ.module.as_str() == "Event"
+        && typ.name.as_str() == "EventHandle"
+}
+
+/// `0x1::Event::EventHandle` is `{ counter, guid: { len_bytes, guid: { id: { creation_num, addr } } } }`.
+fn into_event_handle(handle: &AnnotatedMoveStruct) -> Result<EventHandle> {
+    let id = struct_field(struct_field(struct_field(handle, "guid")?, "guid")?, "id")?;
+    match (
+        field(handle, "counter")?,
+        field(id, "creation_num")?,
+        field(id, "addr")?,
+    ) {
+        (
+            AnnotatedMoveValue::U64(counter),
+            AnnotatedMoveValue::U64(creation_num),
+            AnnotatedMoveValue::Address(addr),
+        ) => Ok(EventHandle::new(
+            EventKey::new_from_address(addr, *creation_num),
+            *counter,
+        )),
+        _ => Err(format_err!("invalid event handle: {:?}", handle)),
+    }
+}
+
+fn field<'a>(value: &'a AnnotatedMoveStruct, name: &str) -> Result<&'a AnnotatedMoveValue> {
+    value
+        .value
+        .iter()
+        .find(|(id, _)| id.as_str() == name)
+        .map(|(_, field)| field)
+        .ok_or_else(|| format_err!("field {} not found in {}", name, value.type_))
+}
+
+fn struct_field<'a>(value: &'a AnnotatedMoveStruct, name: &str) -> Result<&'a AnnotatedMoveStruct> {
+    match field(value, name)? {
+        AnnotatedMoveValue::Struct(field) => Ok(field),
+        _ => Err(format_err!(
+            "field {} of {} is not a struct",
+            name,
+            value.type_
+        )),
+    }
+}
+
 pub trait AsConverter<R> {
     fn as_converter(&self) -> MoveConverter<R>;
 }
@@ -635,17 +709,3 @@ impl<R: MoveResolverExt> AsConverter<R> for R {
         MoveConverter::new(self)
     }
 }
-
-pub fn new_vm_ascii_string(string: &str) -> move_core_types::value::MoveValue {
-    use move_deps::move_core_types::value::{MoveStruct, MoveValue};
-
-    let byte_vector = MoveValue::Vector(
-        string
-            .as_bytes()
-            .iter()
-            .map(|byte| MoveValue::U8(*byte))
-            .collect(),
-    );
-    let move_string = MoveStruct::Runtime(vec![byte_vector]);
-    MoveValue::Struct(move_string)
-}
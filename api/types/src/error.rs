@@ -10,6 +10,78 @@ use warp::{http::StatusCode, reject::Reject};
 
 use crate::U64;
 
+/// Machine readable error codes for errors that clients are expected to handle
+/// programmatically, in addition to the HTTP status code.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AptosErrorCode {
+    /// The submitted transaction has already been committed to the ledger.
+    TransactionAlreadyCommitted,
+    /// The submitted transaction was signed for a different chain than the node's.
+    ChainIdMismatch,
+    /// The payload of the submitted transaction exceeds `max_transaction_payload_bytes`.
+    TransactionPayloadTooLarge,
+    /// The script of the submitted transaction exceeds `max_script_bytes`.
+    ScriptTooLarge,
+    /// The submitted transaction has more than `max_transaction_arguments` arguments.
+    TooManyTransactionArguments,
+    /// A vector argument of the submitted transaction exceeds `max_vector_argument_length`.
+    VectorArgumentTooLong,
+    /// The `Idempotency-Key` of the submission was already used for a different transaction.
+    IdempotencyKeyReused,
+    /// The requested account does not exist at the requested ledger version.
+    AccountNotFound,
+    /// The requested account exists, so its non-existence cannot be proven.
+    AccountExists,
+    /// A batch request has more items than the node allows.
+    BatchTooLarge,
+    /// The gas unit price of the submitted transaction is below the current gas price floor.
+    GasUnitPriceBelowFloor,
+    /// The sender of the submitted transaction is not allowed by the node's sender admission policy.
+    SenderNotAllowed,
+    /// The submitted transaction was discarded by the VM validation, e.g. for an invalid signature.
+    InvalidTransaction,
+    /// The node has not reached the ledger version of the `X-Aptos-Min-Version` header yet.
+    LedgerVersionBehind,
+    /// The requested endpoint belongs to a group of endpoints disabled on the node.
+    EndpointDisabled,
+    /// The node has `max_event_streams` event streams open already.
+    TooManyEventStreams,
+    /// A code added after this version, which the node never returns itself. Clients deserialize
+    /// the codes they do not know into it, rather than failing on the whole error.
+    #[serde(other)]
+    Unknown,
+}
+
+/// Structured information attached to an error, complementing the `aptos_error_code`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum ErrorDetails {
+    ChainIdMismatch {
+        node_chain_id: u8,
+        transaction_chain_id: u8,
+    },
+    LimitExceeded {
+        limit: u64,
+        actual: u64,
+    },
+    GasUnitPriceBelowFloor {
+        gas_price_floor: u64,
+        gas_unit_price: u64,
+    },
+    /// The name of the VM status code, e.g. `SEQUENCE_NUMBER_TOO_OLD`.
+    VmStatus {
+        vm_status: String,
+    },
+    /// The name of the disabled group, e.g. `transaction_submission`.
+    EndpointDisabled {
+        endpoint_group: String,
+    },
+    /// Details added after this version, which the node never returns itself. Clients keep the
+    /// details of the shapes they do not know as is, rather than failing on the whole error.
+    Other(serde_json::Value),
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Error {
     pub code: u16,
@@ -17,6 +89,10 @@ pub struct Error {
     /// Diem blockchain latest onchain ledger version.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aptos_ledger_version: Option<U64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aptos_error_code: Option<AptosErrorCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aptos_error_details: Option<ErrorDetails>,
 }
 
 impl Error {
@@ -25,6 +101,8 @@ impl Error {
             code: code.as_u16(),
             message,
             aptos_ledger_version: None,
+            aptos_error_code: None,
+            aptos_error_details: None,
         }
     }
 
@@ -64,6 +142,114 @@ impl Error {
         self.aptos_ledger_version = Some(ledger_version.into());
         self
     }
+
+    pub fn aptos_error_code(mut self, error_code: AptosErrorCode) -> Self {
+        self.aptos_error_code = Some(error_code);
+        self
+    }
+
+    pub fn aptos_error_details(mut self, details: ErrorDetails) -> Self {
+        self.aptos_error_details = Some(details);
+        self
+    }
+
+    pub fn chain_id_mismatch(node_chain_id: u8, transaction_chain_id: u8) -> Self {
+        Self::bad_request(format!(
+            "transaction chain id {} does not match the node chain id {}",
+            transaction_chain_id, node_chain_id
+        ))
+        .aptos_error_code(AptosErrorCode::ChainIdMismatch)
+        .aptos_error_details(ErrorDetails::ChainIdMismatch {
+            node_chain_id,
+            transaction_chain_id,
+        })
+    }
+
+    /// A transaction exceeding one of the API limits, `what` names the limited quantity.
+    pub fn limit_exceeded(error_code: AptosErrorCode, what: &str, actual: u64, limit: u64) -> Self {
+        Self::bad_request(format!("{} {} exceeds the limit {}", what, actual, limit))
+            .aptos_error_code(error_code)
+            .aptos_error_details(ErrorDetails::LimitExceeded { limit, actual })
+    }
+
+    pub fn transaction_already_committed<S: Display>(hash: S, version: u64) -> Self {
+        Self::bad_request(format!(
+            "transaction {} is already committed at version {}",
+            hash, version
+        ))
+        .aptos_error_code(AptosErrorCode::TransactionAlreadyCommitted)
+    }
+
+    pub fn gas_unit_price_below_floor(gas_unit_price: u64, gas_price_floor: u64) -> Self {
+        Self::bad_request(format!(
+            "gas unit price {} is below the current gas price floor {}, mempool is congested",
+            gas_unit_price, gas_price_floor
+        ))
+        .aptos_error_code(AptosErrorCode::GasUnitPriceBelowFloor)
+        .aptos_error_details(ErrorDetails::GasUnitPriceBelowFloor {
+            gas_price_floor,
+            gas_unit_price,
+        })
+    }
+
+    pub fn sender_not_allowed<S: Display>(sender: S) -> Self {
+        Self::new(
+            StatusCode::FORBIDDEN,
+            format!("sender {} is not allowed to submit transactions", sender),
+        )
+        .aptos_error_code(AptosErrorCode::SenderNotAllowed)
+    }
+
+    /// A transaction discarded by the VM validation with the status code named `vm_status`.
+    pub fn invalid_transaction(vm_status: String) -> Self {
+        Self::bad_request(format!("invalid transaction: {}", vm_status))
+            .aptos_error_code(AptosErrorCode::InvalidTransaction)
+            .aptos_error_details(ErrorDetails::VmStatus { vm_status })
+    }
+
+    /// A read requiring the ledger version `min_version` from a node still at `ledger_version`,
+    /// clients retry later or on another node.
+    pub fn ledger_version_behind(min_version: u64, ledger_version: u64) -> Self {
+        Self::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "ledger version {} is behind the requested minimum version {}",
+                ledger_version, min_version
+            ),
+        )
+        .aptos_ledger_version(ledger_version)
+        .aptos_error_code(AptosErrorCode::LedgerVersionBehind)
+    }
+
+    pub fn endpoint_disabled(endpoint_group: &str) -> Self {
+        Self::new(
+            StatusCode::FORBIDDEN,
+            format!("endpoint group {} is disabled on this node", endpoint_group),
+        )
+        .aptos_error_code(AptosErrorCode::EndpointDisabled)
+        .aptos_error_details(ErrorDetails::EndpointDisabled {
+            endpoint_group: endpoint_group.to_owned(),
+        })
+    }
+
+    pub fn idempotency_key_reused<S: Display>(key: S) -> Self {
+        Self::bad_request(format!(
+            "idempotency key {:?} was already used for a different transaction",
+            key.to_string()
+        ))
+        .aptos_error_code(AptosErrorCode::IdempotencyKeyReused)
+    }
+
+    pub fn too_many_event_streams(max_event_streams: usize) -> Self {
+        Self::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "the node has the maximum of {} event streams open already",
+                max_event_streams
+            ),
+        )
+        .aptos_error_code(AptosErrorCode::TooManyEventStreams)
+    }
 }
 
 impl fmt::Display for Error {
@@ -92,7 +278,7 @@ impl From<serde_json::error::Error> for Error {
 
 #[cfg(test)]
 mod tests {
-    use crate::error::Error;
+    use crate::error::{AptosErrorCode, Error, ErrorDetails};
     use warp::http::StatusCode;
 
     #[test]
@@ -117,6 +303,115 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_serialize_error_code() {
+        let err = Error::transaction_already_committed("0x1", 10).aptos_ledger_version(12);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "code": 400,
+                "message": "transaction 0x1 is already committed at version 10",
+                "aptos_ledger_version": "12",
+                "aptos_error_code": "transaction_already_committed",
+            })
+        )
+    }
+
+    #[test]
+    fn test_deserialize_unknown_error_code() {
+        let err: Error = serde_json::from_value(serde_json::json!({
+            "code": 400,
+            "message": "something new",
+            "aptos_error_code": "added_in_a_later_version",
+        }))
+        .unwrap();
+        assert_eq!(err.aptos_error_code, Some(AptosErrorCode::Unknown));
+
+        let err: Error = serde_json::from_value(serde_json::json!({
+            "code": 400,
+            "message": "transaction 0x1 is already committed at version 10",
+            "aptos_error_code": "transaction_already_committed",
+        }))
+        .unwrap();
+        assert_eq!(
+            err.aptos_error_code,
+            Some(AptosErrorCode::TransactionAlreadyCommitted)
+        );
+    }
+
+    #[test]
+    fn test_unknown_error_details_round_trip() {
+        let json = serde_json::json!({
+            "code": 400,
+            "message": "something new",
+            "aptos_error_code": "added_in_a_later_version",
+            "aptos_error_details": {"new_detail": [1, 2], "limit": "not a number"},
+        });
+        let err: Error = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(
+            err.aptos_error_details,
+            Some(ErrorDetails::Other(serde_json::json!({
+                "new_detail": [1, 2],
+                "limit": "not a number",
+            })))
+        );
+        assert_eq!(serde_json::to_value(&err).unwrap(), json);
+
+        // Known shapes still deserialize into their variant.
+        let err: Error = serde_json::from_value(serde_json::json!({
+            "code": 400,
+            "message": "limit exceeded",
+            "aptos_error_details": {"limit": 10, "actual": 11},
+        }))
+        .unwrap();
+        assert_eq!(
+            err.aptos_error_details,
+            Some(ErrorDetails::LimitExceeded {
+                limit: 10,
+                actual: 11
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_error_details() {
+        let err = Error::chain_id_mismatch(4, 2);
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "code": 400,
+                "message": "transaction chain id 2 does not match the node chain id 4",
+                "aptos_error_code": "chain_id_mismatch",
+                "aptos_error_details": {
+                    "node_chain_id": 4,
+                    "transaction_chain_id": 2,
+                },
+            })
+        )
+    }
+
+    #[test]
+    fn test_serialize_limit_exceeded() {
+        let err = Error::limit_exceeded(
+            AptosErrorCode::TooManyTransactionArguments,
+            "number of arguments",
+            3,
+            2,
+        );
+        assert_eq!(
+            serde_json::to_value(&err).unwrap(),
+            serde_json::json!({
+                "code": 400,
+                "message": "number of arguments 3 exceeds the limit 2",
+                "aptos_error_code": "too_many_transaction_arguments",
+                "aptos_error_details": {
+                    "limit": 2,
+                    "actual": 3,
+                },
+            })
+        )
+    }
+
     #[test]
     fn test_internal_error() {
         let err = Error::internal(anyhow::format_err!("hello"));
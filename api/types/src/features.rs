@@ -0,0 +1,23 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// JSON representation of the protocol features enabled on chain.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Features {
+    /// Names of the enabled features, e.g. `compact_proposals`.
+    pub enabled_features: Vec<String>,
+}
+
+impl From<&aptos_types::on_chain_config::Features> for Features {
+    fn from(features: &aptos_types::on_chain_config::Features) -> Self {
+        Self {
+            enabled_features: features
+                .enabled_features()
+                .into_iter()
+                .map(|flag| flag.name().to_string())
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+
+use move_deps::move_core_types::gas_schedule::{self, CostTable, GasAlgebra};
+use serde::{Deserialize, Serialize};
+
+/// JSON representation of the on-chain gas schedule, so that clients can estimate the cost of a
+/// transaction without submitting it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GasSchedule {
+    /// Cost of each bytecode instruction, indexed by opcode.
+    pub instruction_table: Vec<GasCost>,
+    /// Cost of each native function, indexed by native function id.
+    pub native_table: Vec<GasCost>,
+    pub gas_constants: GasConstants,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GasCost {
+    pub instruction_gas: U64,
+    pub memory_gas: U64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GasConstants {
+    pub global_memory_per_byte_cost: U64,
+    pub global_memory_per_byte_write_cost: U64,
+    pub min_transaction_gas_units: U64,
+    pub large_transaction_cutoff: U64,
+    pub intrinsic_gas_per_byte: U64,
+    pub maximum_number_of_gas_units: U64,
+    pub min_price_per_gas_unit: U64,
+    pub max_price_per_gas_unit: U64,
+    pub max_transaction_size_in_bytes: U64,
+    pub gas_unit_scaling_factor: U64,
+    pub default_account_size: U64,
+}
+
+impl From<&CostTable> for GasSchedule {
+    fn from(table: &CostTable) -> Self {
+        Self {
+            instruction_table: table.instruction_table.iter().map(GasCost::from).collect(),
+            native_table: table.native_table.iter().map(GasCost::from).collect(),
+            gas_constants: (&table.gas_constants).into(),
+        }
+    }
+}
+
+impl From<&gas_schedule::GasCost> for GasCost {
+    fn from(cost: &gas_schedule::GasCost) -> Self {
+        Self {
+            instruction_gas: cost.instruction_gas.get().into(),
+            memory_gas: cost.memory_gas.get().into(),
+        }
+    }
+}
+
+impl From<&gas_schedule::GasConstants> for GasConstants {
+    fn from(constants: &gas_schedule::GasConstants) -> Self {
+        Self {
+            global_memory_per_byte_cost: constants.global_memory_per_byte_cost.get().into(),
+            global_memory_per_byte_write_cost: constants
+                .global_memory_per_byte_write_cost
+                .get()
+                .into(),
+            min_transaction_gas_units: constants.min_transaction_gas_units.get().into(),
+            large_transaction_cutoff: constants.large_transaction_cutoff.get().into(),
+            intrinsic_gas_per_byte: constants.intrinsic_gas_per_byte.get().into(),
+            maximum_number_of_gas_units: constants.maximum_number_of_gas_units.get().into(),
+            min_price_per_gas_unit: constants.min_price_per_gas_unit.get().into(),
+            max_price_per_gas_unit: constants.max_price_per_gas_unit.get().into(),
+            max_transaction_size_in_bytes: constants.max_transaction_size_in_bytes.into(),
+            gas_unit_scaling_factor: constants.gas_unit_scaling_factor.into(),
+            default_account_size: constants.default_account_size.get().into(),
+        }
+    }
+}
+
+/// Gas unit prices suggested from the prices paid by recently committed transactions.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct GasEstimation {
+    /// Price for transactions that can wait for a quiet period.
+    pub deprioritized_gas_estimate: U64,
+    pub gas_estimate: U64,
+    /// Price for transactions that should be committed ahead of most others.
+    pub prioritized_gas_estimate: U64,
+    /// Minimum gas unit price accepted for submission given the current mempool load.
+    pub gas_price_floor: U64,
+}
@@ -0,0 +1,218 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_infallible::RwLock;
+use move_deps::move_core_types::{
+    language_storage::{ModuleId, StructTag},
+    value::{MoveStructLayout, MoveTypeLayout},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Cache of the type layouts of Move structs, keyed by struct tag.
+///
+/// Computing the layout of a struct loads and deserializes the module declaring it and the modules
+/// declaring the types of its fields, which dominates the cost of rendering a resource or an event
+/// as JSON. A layout stays valid until one of these modules is republished, so the cache tracks the
+/// modules each layout depends on and its owner calls `invalidate_modules` with the modules
+/// written by newly committed transactions.
+///
+/// Once the capacity is reached, layouts that are not cached yet are computed for every use until
+/// invalidated layouts make room again.
+pub struct LayoutCache {
+    capacity: usize,
+    inner: RwLock<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    layouts: HashMap<StructTag, Arc<MoveTypeLayout>>,
+    // the struct tags of the cached layouts depending on each module
+    dependents: HashMap<ModuleId, HashSet<StructTag>>,
+}
+
+impl Default for LayoutCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl LayoutCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: RwLock::new(Inner::default()),
+        }
+    }
+
+    pub fn get(&self, struct_tag: &StructTag) -> Option<Arc<MoveTypeLayout>> {
+        self.inner.read().layouts.get(struct_tag).cloned()
+    }
+
+    /// Caches the layout of `struct_tag`, which must be a layout with types (see
+    /// `MoveValueAnnotator::get_type_layout_with_types`), and returns it.
+    pub fn insert(&self, struct_tag: StructTag, layout: MoveTypeLayout) -> Arc<MoveTypeLayout> {
+        let layout = Arc::new(layout);
+        let mut inner = self.inner.write();
+        if inner.layouts.len() >= self.capacity {
+            return layout;
+        }
+        let mut modules = HashSet::new();
+        collect_modules(&layout, &mut modules);
+        for module in modules {
+            inner
+                .dependents
+                .entry(module)
+                .or_default()
+                .insert(struct_tag.clone());
+        }
+        inner.layouts.insert(struct_tag, layout.clone());
+        layout
+    }
+
+    /// Evicts the layouts depending on any of `modules`.
+    pub fn invalidate_modules<'a>(&self, modules: impl IntoIterator<Item = &'a ModuleId>) {
+        let mut inner = self.inner.write();
+        for module in modules {
+            if let Some(struct_tags) = inner.dependents.remove(module) {
+                for struct_tag in struct_tags {
+                    inner.layouts.remove(&struct_tag);
+                }
+            }
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut inner = self.inner.write();
+        inner.layouts.clear();
+        inner.dependents.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().layouts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Collects the modules declaring the structs of `layout`. Type arguments only matter through the
+/// fields using them, whose layouts are collected as well.
+fn collect_modules(layout: &MoveTypeLayout, modules: &mut HashSet<ModuleId>) {
+    match layout {
+        MoveTypeLayout::Vector(item_layout) => collect_modules(item_layout, modules),
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes { type_, fields }) => {
+            modules.insert(ModuleId::new(type_.address, type_.module.clone()));
+            for field in fields {
+                collect_modules(&field.layout, modules);
+            }
+        }
+        MoveTypeLayout::Struct(MoveStructLayout::WithFields(fields)) => {
+            for field in fields {
+                collect_modules(&field.layout, modules);
+            }
+        }
+        MoveTypeLayout::Struct(MoveStructLayout::Runtime(layouts)) => {
+            for layout in layouts {
+                collect_modules(layout, modules);
+            }
+        }
+        MoveTypeLayout::Bool
+        | MoveTypeLayout::U8
+        | MoveTypeLayout::U64
+        | MoveTypeLayout::U128
+        | MoveTypeLayout::Address
+        | MoveTypeLayout::Signer => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LayoutCache;
+    use move_deps::move_core_types::{
+        account_address::AccountAddress,
+        identifier::Identifier,
+        language_storage::{ModuleId, StructTag, CORE_CODE_ADDRESS},
+        value::{MoveFieldLayout, MoveStructLayout, MoveTypeLayout},
+    };
+
+    fn struct_tag(address: AccountAddress, module: &str, name: &str) -> StructTag {
+        StructTag {
+            address,
+            module: Identifier::new(module).unwrap(),
+            name: Identifier::new(name).unwrap(),
+            type_params: vec![],
+        }
+    }
+
+    fn struct_layout(struct_tag: &StructTag, fields: Vec<MoveTypeLayout>) -> MoveTypeLayout {
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: struct_tag.clone(),
+            fields: fields
+                .into_iter()
+                .enumerate()
+                .map(|(i, layout)| {
+                    MoveFieldLayout::new(Identifier::new(format!("f{}", i)).unwrap(), layout)
+                })
+                .collect(),
+        })
+    }
+
+    fn module_id(struct_tag: &StructTag) -> ModuleId {
+        ModuleId::new(struct_tag.address, struct_tag.module.clone())
+    }
+
+    #[test]
+    fn test_get_and_insert() {
+        let cache = LayoutCache::new(10);
+        let coin = struct_tag(CORE_CODE_ADDRESS, "Coin", "Coin");
+        assert!(cache.get(&coin).is_none());
+        let layout = struct_layout(&coin, vec![MoveTypeLayout::U64]);
+        cache.insert(coin.clone(), layout.clone());
+        assert_eq!(*cache.get(&coin).unwrap(), layout);
+    }
+
+    #[test]
+    fn test_invalidate_modules_of_nested_structs() {
+        let cache = LayoutCache::new(10);
+        let address = AccountAddress::from_hex_literal("0xa").unwrap();
+        let guid = struct_tag(CORE_CODE_ADDRESS, "GUID", "GUID");
+        let holder = struct_tag(address, "Message", "MessageHolder");
+        let other = struct_tag(address, "Other", "Other");
+        let guid_layout = struct_layout(&guid, vec![MoveTypeLayout::Address]);
+        cache.insert(guid.clone(), guid_layout.clone());
+        cache.insert(
+            holder.clone(),
+            struct_layout(&holder, vec![MoveTypeLayout::Vector(Box::new(guid_layout))]),
+        );
+        cache.insert(other.clone(), struct_layout(&other, vec![]));
+
+        cache.invalidate_modules(&[module_id(&guid)]);
+        assert!(cache.get(&guid).is_none());
+        assert!(cache.get(&holder).is_none());
+        assert!(cache.get(&other).is_some());
+
+        cache.invalidate_modules(&[module_id(&other)]);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_skip_insert_when_full() {
+        let cache = LayoutCache::new(1);
+        let coin = struct_tag(CORE_CODE_ADDRESS, "Coin", "Coin");
+        let guid = struct_tag(CORE_CODE_ADDRESS, "GUID", "GUID");
+        cache.insert(coin.clone(), struct_layout(&coin, vec![]));
+        let layout = struct_layout(&guid, vec![]);
+        assert_eq!(*cache.insert(guid.clone(), layout.clone()), layout);
+        assert!(cache.get(&guid).is_none());
+
+        cache.invalidate_modules(&[module_id(&coin)]);
+        cache.insert(guid.clone(), layout);
+        assert!(cache.get(&guid).is_some());
+    }
+}
@@ -3,7 +3,10 @@
 
 use crate::U64;
 
-use aptos_types::{chain_id::ChainId, ledger_info::LedgerInfoWithSignatures};
+use aptos_types::{
+    chain_id::{ChainId, NamedChain},
+    ledger_info::LedgerInfoWithSignatures,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -34,3 +37,26 @@ impl LedgerInfo {
         self.ledger_timestamp.into()
     }
 }
+
+/// Response of the index endpoint: the latest ledger info along with the chain name, so that
+/// clients can easily tell which network they are talking to before signing transactions.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct IndexResponse {
+    #[serde(flatten)]
+    pub ledger_info: LedgerInfo,
+    /// Name of the chain if the chain id belongs to a well-known network, e.g. `TESTNET`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_name: Option<String>,
+}
+
+impl IndexResponse {
+    pub fn new(ledger_info: LedgerInfo) -> Self {
+        let chain_name = NamedChain::from_chain_id(&ChainId::new(ledger_info.chain_id))
+            .ok()
+            .map(|chain| chain.to_string());
+        Self {
+            ledger_info,
+            chain_name,
+        }
+    }
+}
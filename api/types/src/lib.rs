@@ -2,40 +2,76 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod account;
+mod activity;
 mod address;
+mod allow_list;
 mod bytecode;
+mod coin;
 mod convert;
 mod error;
 mod event_key;
+mod features;
+mod gas_schedule;
 mod hash;
+mod layout_cache;
 mod ledger_info;
 pub mod mime_types;
+mod module_verification;
 mod move_types;
 mod response;
+mod stake;
 mod table;
+mod token;
+mod trace;
 mod transaction;
+mod validator;
+mod vm_error;
 
-pub use account::AccountData;
+pub use account::{
+    AccountData, AccountEventHandle, AccountNonExistenceProof, AccountResources,
+    AccountResourcesBatchRequest, AccountSequenceNumbers, CrsnWindow, SequenceNumberRange,
+};
+pub use activity::{
+    AccountActivity, ActivitySummary, CreatedModule, CreatedResource, ModuleChange,
+    ModuleChangeKind,
+};
 pub use address::Address;
+pub use allow_list::ScriptFunctionAllowList;
 pub use bytecode::Bytecode;
-pub use convert::{new_vm_ascii_string, AsConverter, MoveConverter};
-pub use error::Error;
+pub use coin::{CoinInfo, CoinSupply};
+pub use convert::{AsConverter, MoveConverter};
+pub use error::{AptosErrorCode, Error, ErrorDetails};
 pub use event_key::EventKey;
+pub use features::Features;
+pub use gas_schedule::{GasConstants, GasCost, GasEstimation, GasSchedule};
 pub use hash::HashValue;
-pub use ledger_info::LedgerInfo;
+pub use layout_cache::LayoutCache;
+pub use ledger_info::{IndexResponse, LedgerInfo};
+pub use module_verification::{
+    ModuleCompatibility, ModuleVerificationError, ModuleVerificationErrorIndex,
+    ModuleVerificationErrorOffset, ModuleVerificationRequest, ModuleVerificationResult,
+    SourceLocation,
+};
 pub use move_types::{
     HexEncodedBytes, MoveFunction, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
     MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType, MoveValue, ScriptFunctionId,
     U128, U64,
 };
 pub use response::{
-    Response, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    Response, X_APTOS_CHAIN_ID, X_APTOS_CURSOR, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP,
+    X_APTOS_LEDGER_VERSION, X_APTOS_MIN_VERSION, X_APTOS_TOTAL_ITEMS,
 };
+pub use stake::{Delegation, StakePool};
 pub use table::TableItemRequest;
+pub use token::{Token, TokenData, TokenId};
+pub use trace::{TracePhase, TransactionTrace};
 pub use transaction::{
-    BlockMetadataTransaction, DirectWriteSet, Event, GenesisTransaction, PendingTransaction,
-    ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction, TransactionData,
-    TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
-    TransactionSigningMessage, UserTransaction, UserTransactionRequest, WriteSet, WriteSetChange,
+    BlockMetadataTransaction, DirectWriteSet, Event, EventStreamMessage, GenesisTransaction,
+    PendingTransaction, ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, SignerAuthentication,
+    StateDiff, Transaction, TransactionAuthentication, TransactionData, TransactionId,
+    TransactionInfo, TransactionOnChainData, TransactionPayload, TransactionSigningMessage,
+    UserTransaction, UserTransactionRequest, VersionedEvent, WriteSet, WriteSetChange,
     WriteSetPayload,
 };
+pub use validator::{EpochValidatorSet, EpochValidatorStats, ValidatorInfo, ValidatorStats};
+pub use vm_error::{MoveAbort, VmError};
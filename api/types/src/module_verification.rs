@@ -0,0 +1,221 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{HexEncodedBytes, MoveModuleId};
+
+use move_deps::{
+    move_binary_format::{
+        compatibility::Compatibility,
+        errors::{Location, VMError},
+        file_format::{FunctionDefinitionIndex, StructDefinitionIndex},
+        IndexKind,
+    },
+    move_bytecode_source_map::source_map::SourceMap,
+};
+use serde::{Deserialize, Serialize};
+
+/// Body of `POST /modules/verify`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleVerificationRequest {
+    pub bytecode: HexEncodedBytes,
+    /// BCS encoded source map of the module, written by the Move compiler next to the bytecode
+    /// (`build/<package>/source_maps/<module>.mvsm`), to locate the errors in the source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_map: Option<HexEncodedBytes>,
+    /// Source file of the module, to turn the locations of the source map into lines.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// Result of running the bytecode verifier (and, when the module is already published, the
+/// upgrade compatibility checks) against a module without publishing it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleVerificationResult {
+    /// `None` when the bytecode could not be deserialized into a module.
+    pub module: Option<MoveModuleId>,
+    pub verified: bool,
+    pub errors: Vec<ModuleVerificationError>,
+    /// `None` when no module with the same id exists on chain.
+    pub compatibility: Option<ModuleCompatibility>,
+}
+
+impl ModuleVerificationResult {
+    pub fn new(
+        module: Option<MoveModuleId>,
+        errors: Vec<ModuleVerificationError>,
+        compatibility: Option<ModuleCompatibility>,
+    ) -> Self {
+        let verified = errors.is_empty()
+            && compatibility
+                .as_ref()
+                .map(ModuleCompatibility::is_fully_compatible)
+                .unwrap_or(true);
+        Self {
+            module,
+            verified,
+            errors,
+            compatibility,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleVerificationError {
+    /// Name of the VM status code, e.g. `NEGATIVE_STACK_SIZE_WITHIN_BLOCK`.
+    pub status_code: String,
+    pub message: Option<String>,
+    /// Module the error is located in, if any.
+    pub location: Option<MoveModuleId>,
+    pub indices: Vec<ModuleVerificationErrorIndex>,
+    pub offsets: Vec<ModuleVerificationErrorOffset>,
+    /// Where the error is in the source, if a source map was given and covers it.
+    pub source_location: Option<SourceLocation>,
+}
+
+/// A span of the source file of a module.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// Byte offset of the start of the span.
+    pub start: u32,
+    /// Byte offset of the end of the span.
+    pub end: u32,
+    /// Line of the start of the span, from 1; `None` if the source was not given.
+    pub line: Option<u32>,
+    /// Column of the start of the span in bytes, from 1; `None` if the source was not given.
+    pub column: Option<u32>,
+}
+
+impl SourceLocation {
+    pub fn new(start: u32, end: u32, source: Option<&str>) -> Self {
+        let (line, column) = match source.and_then(|source| source.as_bytes().get(..start as usize))
+        {
+            Some(before) => {
+                let line = before.iter().filter(|byte| **byte == b'\n').count() as u32 + 1;
+                let line_start = before
+                    .iter()
+                    .rposition(|byte| *byte == b'\n')
+                    .map_or(0, |newline| newline + 1);
+                (Some(line), Some((before.len() - line_start) as u32 + 1))
+            }
+            None => (None, None),
+        };
+        Self {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleVerificationErrorIndex {
+    pub kind: String,
+    pub index: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleVerificationErrorOffset {
+    pub function_index: u16,
+    pub code_offset: u16,
+}
+
+impl From<&VMError> for ModuleVerificationError {
+    fn from(err: &VMError) -> Self {
+        Self::new(err, None, None)
+    }
+}
+
+impl ModuleVerificationError {
+    /// Converts the error, locating it in the source with `source_map`, if given: at its first
+    /// bytecode offset, or else at the first function or struct definition it refers to.
+    pub fn new(err: &VMError, source_map: Option<&SourceMap>, source: Option<&str>) -> Self {
+        let source_location = source_map.and_then(|source_map| {
+            let loc = match err.offsets().first() {
+                Some((function, offset)) => source_map.get_code_location(*function, *offset).ok(),
+                None => err.indices().iter().find_map(|(kind, index)| match kind {
+                    IndexKind::FunctionDefinition => source_map
+                        .get_function_source_map(FunctionDefinitionIndex(*index))
+                        .ok()
+                        .map(|function| function.definition_location),
+                    IndexKind::StructDefinition => source_map
+                        .get_struct_source_map(StructDefinitionIndex(*index))
+                        .ok()
+                        .map(|strukt| strukt.definition_location),
+                    _ => None,
+                }),
+            }?;
+            Some(SourceLocation::new(loc.start(), loc.end(), source))
+        });
+        Self {
+            status_code: format!("{:?}", err.major_status()),
+            message: err.message().cloned(),
+            location: match err.location() {
+                Location::Module(id) => Some(id.clone().into()),
+                Location::Undefined | Location::Script => None,
+            },
+            indices: err
+                .indices()
+                .iter()
+                .map(|(kind, index)| ModuleVerificationErrorIndex {
+                    kind: kind.to_string(),
+                    index: *index,
+                })
+                .collect(),
+            offsets: err
+                .offsets()
+                .iter()
+                .map(|(function, offset)| ModuleVerificationErrorOffset {
+                    function_index: function.0,
+                    code_offset: *offset,
+                })
+                .collect(),
+            source_location,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModuleCompatibility {
+    /// Public structs and functions keep their signatures, so dependent modules still link.
+    pub struct_and_function_linking: bool,
+    /// Struct layouts are unchanged, so published data can still be read.
+    pub struct_layout: bool,
+}
+
+impl ModuleCompatibility {
+    pub fn is_fully_compatible(&self) -> bool {
+        self.struct_and_function_linking && self.struct_layout
+    }
+}
+
+impl From<Compatibility> for ModuleCompatibility {
+    fn from(compatibility: Compatibility) -> Self {
+        Self {
+            struct_and_function_linking: compatibility.struct_and_function_linking,
+            struct_layout: compatibility.struct_layout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SourceLocation;
+
+    #[test]
+    fn test_source_location_line_and_column() {
+        let source = "module M {\n    fun f() {}\n}\n";
+        let start = source.find("fun").unwrap() as u32;
+        let loc = SourceLocation::new(start, start + 3, Some(source));
+        assert_eq!((loc.line, loc.column), (Some(2), Some(5)));
+
+        let loc = SourceLocation::new(0, 6, Some(source));
+        assert_eq!((loc.line, loc.column), (Some(1), Some(1)));
+
+        // the span is still returned without the source, or past its end
+        let loc = SourceLocation::new(start, start + 3, None);
+        assert_eq!((loc.start, loc.line, loc.column), (start, None, None));
+        let loc = SourceLocation::new(1000, 1003, Some(source));
+        assert_eq!((loc.line, loc.column), (None, None));
+    }
+}
@@ -4,7 +4,7 @@
 use crate::{Address, Bytecode};
 
 use anyhow::{bail, format_err};
-use aptos_types::{account_config::CORE_CODE_ADDRESS, event::EventKey, transaction::Module};
+use aptos_types::{event::EventKey, transaction::Module};
 use move_deps::{
     move_binary_format::{
         access::ModuleAccess,
@@ -262,6 +262,20 @@ impl TryFrom<AnnotatedMoveStruct> for MoveStructValue {
     }
 }
 
+impl TryFrom<serde_json::Value> for MoveStructValue {
+    type Error = anyhow::Error;
+    fn try_from(value: serde_json::Value) -> anyhow::Result<Self> {
+        match value {
+            serde_json::Value::Object(fields) => fields
+                .into_iter()
+                .map(|(name, value)| Ok((Identifier::new(name)?, value)))
+                .collect::<anyhow::Result<_>>()
+                .map(Self),
+            _ => bail!("expected a JSON object for a struct value, got: {}", value),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum MoveValue {
     U8(u8),
@@ -281,9 +295,7 @@ impl MoveValue {
     }
 
     pub fn is_ascii_string(st: &StructTag) -> bool {
-        st.address == CORE_CODE_ADDRESS
-            && st.name.to_string() == "String"
-            && st.module.to_string() == "ASCII"
+        aptos_move_value::is_ascii_string(st)
     }
 
     pub fn convert_ascii_string(v: AnnotatedMoveStruct) -> anyhow::Result<MoveValue> {
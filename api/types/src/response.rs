@@ -11,10 +11,22 @@ pub const X_APTOS_CHAIN_ID: &str = "X-Aptos-Chain-Id";
 pub const X_APTOS_EPOCH: &str = "X-Aptos-Epoch";
 pub const X_APTOS_LEDGER_VERSION: &str = "X-Aptos-Ledger-Version";
 pub const X_APTOS_LEDGER_TIMESTAMP: &str = "X-Aptos-Ledger-TimestampUsec";
+pub const X_APTOS_TOTAL_ITEMS: &str = "X-Aptos-Total-Items";
+pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
+/// Consistency token: returned with a committed transaction, its version, and accepted on reads to
+/// require the node to have reached at least that ledger version.
+pub const X_APTOS_MIN_VERSION: &str = "X-Aptos-Min-Version";
 
 pub struct Response {
     pub ledger_info: LedgerInfo,
     pub body: Vec<u8>,
+    /// Number of items of the list paged through, when known.
+    pub total_items: Option<u64>,
+    /// `start` of the next page of a paged list, absent on the last page.
+    pub cursor: Option<u64>,
+    /// Consistency token for the reads following a submitted or committed transaction, see
+    /// `X_APTOS_MIN_VERSION`.
+    pub min_version: Option<u64>,
 }
 
 impl Response {
@@ -22,8 +34,26 @@ impl Response {
         Ok(Self {
             ledger_info,
             body: serde_json::to_vec(body)?,
+            total_items: None,
+            cursor: None,
+            min_version: None,
         })
     }
+
+    pub fn with_total_items(mut self, total_items: u64) -> Self {
+        self.total_items = Some(total_items);
+        self
+    }
+
+    pub fn with_cursor(mut self, cursor: Option<u64>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+
+    pub fn with_min_version(mut self, min_version: u64) -> Self {
+        self.min_version = Some(min_version);
+        self
+    }
 }
 
 impl warp::Reply for Response {
@@ -42,6 +72,15 @@ impl warp::Reply for Response {
             self.ledger_info.ledger_timestamp.into(),
         );
         headers.insert(X_APTOS_EPOCH, self.ledger_info.epoch.into());
+        if let Some(total_items) = self.total_items {
+            headers.insert(X_APTOS_TOTAL_ITEMS, total_items.into());
+        }
+        if let Some(cursor) = self.cursor {
+            headers.insert(X_APTOS_CURSOR, cursor.into());
+        }
+        if let Some(min_version) = self.min_version {
+            headers.insert(X_APTOS_MIN_VERSION, min_version.into());
+        }
 
         res
     }
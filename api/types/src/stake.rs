@@ -0,0 +1,56 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, U64};
+
+use aptos_types::account_config::StakePoolResource;
+use serde::{Deserialize, Serialize};
+
+/// Stake delegated to a validator, with the delegations of each table resolved.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct StakePool {
+    pub voting_power: U64,
+    pub next_epoch_voting_power: U64,
+    pub active: Vec<Delegation>,
+    pub inactive: Vec<Delegation>,
+    pub pending_active: Vec<Delegation>,
+    pub pending_inactive: Vec<Delegation>,
+}
+
+impl StakePool {
+    pub fn new(
+        pool: &StakePoolResource,
+        active: Vec<Delegation>,
+        inactive: Vec<Delegation>,
+        pending_active: Vec<Delegation>,
+        pending_inactive: Vec<Delegation>,
+    ) -> Self {
+        Self {
+            voting_power: pool.voting_power().into(),
+            next_epoch_voting_power: pool.next_epoch_voting_power().into(),
+            active,
+            inactive,
+            pending_active,
+            pending_inactive,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Delegation {
+    pub delegator: Address,
+    pub coins: U64,
+    pub rewards: U64,
+    pub locked_until_secs: U64,
+}
+
+impl Delegation {
+    pub fn new(delegator: Address, delegation: &aptos_types::account_config::Delegation) -> Self {
+        Self {
+            delegator,
+            coins: delegation.coins().into(),
+            rewards: delegation.rewards().into(),
+            locked_until_secs: delegation.locked_until_secs().into(),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, U64};
+
+use serde::{Deserialize, Serialize};
+
+/// A token held by an account, with the metadata published by its creator.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Token {
+    pub id: TokenId,
+    pub amount: U64,
+    /// Absent if the creator no longer publishes the token data.
+    pub data: Option<TokenData>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TokenId {
+    pub creator: Address,
+    pub collection: String,
+    pub name: String,
+}
+
+impl From<&aptos_types::account_config::TokenId> for TokenId {
+    fn from(id: &aptos_types::account_config::TokenId) -> Self {
+        Self {
+            creator: id.creator().into(),
+            collection: id.collection().to_owned(),
+            name: id.name().to_owned(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TokenData {
+    pub collection: String,
+    pub name: String,
+    pub description: String,
+    pub uri: String,
+    pub maximum: Option<U64>,
+    /// Only tracked for the tokens created with `monitor_supply`.
+    pub supply: Option<U64>,
+}
+
+impl From<&aptos_types::account_config::TokenData> for TokenData {
+    fn from(data: &aptos_types::account_config::TokenData) -> Self {
+        Self {
+            collection: data.collection().to_owned(),
+            name: data.name().to_owned(),
+            description: data.description().to_owned(),
+            uri: data.uri().to_owned(),
+            maximum: data.maximum().map(U64::from),
+            supply: data.supply().map(U64::from),
+        }
+    }
+}
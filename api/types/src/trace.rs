@@ -0,0 +1,62 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{HashValue, U64};
+use aptos_types::{
+    transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
+    vm_status::VMStatus,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Gas and time spent in each phase of a committed user transaction, measured by re-executing
+/// it against the state it was executed on. Not itemized any further than the phases.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TransactionTrace {
+    pub version: U64,
+    pub hash: HashValue,
+    /// Outcome of the re-execution, matches the committed transaction.
+    pub success: bool,
+    pub vm_status: String,
+    pub gas_used: U64,
+    pub execution_time_us: U64,
+    /// In execution order.
+    pub phases: Vec<TracePhase>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TracePhase {
+    /// One of `prologue`, `intrinsic`, `execution`, `storage`, `epilogue` or
+    /// `failure_epilogue`.
+    pub name: String,
+    pub gas_used: U64,
+    pub time_us: U64,
+}
+
+impl TransactionTrace {
+    pub fn new(
+        version: u64,
+        hash: aptos_crypto::HashValue,
+        vm_status: &VMStatus,
+        output: &TransactionOutput,
+        trace: &aptos_vm::transaction_trace::TransactionTrace,
+    ) -> Self {
+        Self {
+            version: version.into(),
+            hash: hash.into(),
+            success: output.status() == &TransactionStatus::Keep(ExecutionStatus::Success),
+            vm_status: format!("{:?}", vm_status),
+            gas_used: output.gas_used().into(),
+            execution_time_us: (trace.total_time().as_micros() as u64).into(),
+            phases: trace
+                .phases
+                .iter()
+                .map(|phase| TracePhase {
+                    name: phase.name.to_string(),
+                    gas_used: phase.gas_used.into(),
+                    time_us: (phase.time.as_micros() as u64).into(),
+                })
+                .collect(),
+        }
+    }
+}
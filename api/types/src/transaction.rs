@@ -3,7 +3,7 @@
 
 use crate::{
     Address, EventKey, HashValue, HexEncodedBytes, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStructTag, MoveType, MoveValue, ScriptFunctionId, U64,
+    MoveScriptBytecode, MoveStructTag, MoveType, MoveValue, ScriptFunctionId, VmError, U64,
 };
 
 use anyhow::bail;
@@ -288,6 +288,9 @@ pub struct TransactionInfo {
     pub gas_used: U64,
     pub success: bool,
     pub vm_status: String,
+    /// The reason of the failure of a failed transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_error: Option<VmError>,
     pub accumulator_root_hash: HashValue,
     pub changes: Vec<WriteSetChange>,
 }
@@ -370,6 +373,41 @@ impl From<(&ContractEvent, serde_json::Value)> for Event {
     }
 }
 
+/// An event along with its position in the ledger, see `GET /events/by_type/{event_type}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VersionedEvent {
+    /// Version of the transaction that emitted the event.
+    pub version: U64,
+    /// Index of the event among the events emitted by the transaction.
+    pub event_index: U64,
+    #[serde(flatten)]
+    pub event: Event,
+}
+
+/// A message of an event stream, see `GET /events/{event_key}/stream` and
+/// `GET /events/by_type/{event_type}/stream`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventStreamMessage {
+    Event {
+        event: Event,
+    },
+    /// An event of a stream by event type, along its position in the ledger.
+    VersionedEvent {
+        event: VersionedEvent,
+    },
+    /// Sent once all the events committed when the stream started have been sent, the following
+    /// events are sent as they are committed. `sequence_number` is the one of the next event.
+    CaughtUp {
+        sequence_number: U64,
+    },
+    /// The `CaughtUp` message of a stream by event type: the events up to `version` have been
+    /// sent.
+    CaughtUpToVersion {
+        version: U64,
+    },
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum GenesisPayload {
@@ -493,6 +531,15 @@ impl WriteSetChange {
     }
 }
 
+/// Net state changes between the states at `first_version` and `last_version`: for every state
+/// key written in `(first_version, last_version]`, the last change applied to it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateDiff {
+    pub first_version: U64,
+    pub last_version: U64,
+    pub changes: Vec<WriteSetChange>,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TransactionSignature {
@@ -814,3 +861,27 @@ impl TransactionSigningMessage {
         }
     }
 }
+
+/// Result of `POST /transactions/authenticate`: whether the signatures of a transaction are
+/// valid, and whether its signers hold the authentication keys of their accounts. The
+/// transaction is valid when both hold, it is not executed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionAuthentication {
+    pub valid: bool,
+    /// Why the signature verification failed, absent when all the signatures are valid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature_error: Option<String>,
+    /// The sender first, followed by the secondary signers.
+    pub signers: Vec<SignerAuthentication>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignerAuthentication {
+    pub address: Address,
+    /// Authentication key of the public key(s) the signer signed with.
+    pub authentication_key: HexEncodedBytes,
+    /// Authentication key of the account, absent when the account does not exist.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_authentication_key: Option<HexEncodedBytes>,
+    pub authentication_key_matches: bool,
+}
@@ -0,0 +1,68 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HexEncodedBytes, U64};
+
+use anyhow::Result;
+use aptos_crypto::ValidCryptoMaterial;
+use aptos_types::{network_address::NetworkAddress, validator_info};
+use serde::{Deserialize, Serialize};
+
+/// The validator set that was in charge of consensus during an epoch.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EpochValidatorSet {
+    pub epoch: U64,
+    /// Version at which the epoch started, i.e. the version the validator set was read at.
+    pub start_version: U64,
+    pub validators: Vec<ValidatorInfo>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ValidatorInfo {
+    pub address: Address,
+    pub voting_power: U64,
+    pub consensus_public_key: HexEncodedBytes,
+    pub validator_network_addresses: Vec<String>,
+    pub fullnode_network_addresses: Vec<String>,
+}
+
+impl ValidatorInfo {
+    pub fn try_from_validator_info(info: &validator_info::ValidatorInfo) -> Result<Self> {
+        let config = info.config();
+        Ok(Self {
+            address: (*info.account_address()).into(),
+            voting_power: info.consensus_voting_power().into(),
+            consensus_public_key: config.consensus_public_key.to_bytes().into(),
+            validator_network_addresses: to_strings(config.validator_network_addresses()?),
+            fullnode_network_addresses: to_strings(config.fullnode_network_addresses()?),
+        })
+    }
+}
+
+/// The participation of the validators in consensus during an epoch, derived from the blocks
+/// committed in the epoch.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct EpochValidatorStats {
+    pub epoch: U64,
+    /// Number of committed blocks proposed by a validator.
+    pub blocks: U64,
+    /// Number of rounds without a committed block proposed by a validator, i.e. the rounds that
+    /// timed out.
+    pub failed_rounds: U64,
+    pub validators: Vec<ValidatorStats>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ValidatorStats {
+    pub address: Address,
+    /// Number of committed blocks proposed by the validator.
+    pub proposals: U64,
+    /// Number of committed blocks carrying the vote of the validator for their parent block.
+    pub votes: U64,
+    /// Number of committed blocks missing the vote of the validator for their parent block.
+    pub missed_votes: U64,
+}
+
+fn to_strings(addresses: Vec<NetworkAddress>) -> Vec<String> {
+    addresses.iter().map(ToString::to_string).collect()
+}
@@ -0,0 +1,62 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{MoveModuleId, U64};
+use aptos_transaction_builder::error_explain;
+use aptos_types::vm_status::AbortLocation;
+use serde::{Deserialize, Serialize};
+
+/// Machine readable reason of a failed transaction, complementing its human readable
+/// `vm_status`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum VmError {
+    MoveAbort(MoveAbort),
+    ExecutionFailure {
+        /// The module id of the failed function, or `script`.
+        location: String,
+        function: String,
+        code_offset: u16,
+    },
+    OutOfGas,
+    /// An error of the VM outside of the execution of Move code, e.g. an invalid argument.
+    MiscellaneousError {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        status_code: Option<String>,
+    },
+}
+
+/// A Move abort, with the names of its category and reason when the aborting module is known to
+/// the error map of the framework release, e.g. `INVALID_ARGUMENT` and `EINSUFFICIENT_BALANCE`.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct MoveAbort {
+    /// The module id of the aborting module, or `script`.
+    pub location: String,
+    pub code: U64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// The description of the reason.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl MoveAbort {
+    pub fn new(location: &AbortLocation, code: u64) -> Self {
+        let (location, explanation) = match location {
+            AbortLocation::Module(module_id) => (
+                MoveModuleId::from(module_id.clone()).to_string(),
+                error_explain::get_explanation(module_id, code),
+            ),
+            AbortLocation::Script => ("script".to_owned(), None),
+        };
+        Self {
+            location,
+            code: code.into(),
+            category: explanation.as_ref().map(|ec| ec.category.code_name.clone()),
+            reason: explanation.as_ref().map(|ec| ec.reason.code_name.clone()),
+            description: explanation.map(|ec| ec.reason.code_description),
+        }
+    }
+}
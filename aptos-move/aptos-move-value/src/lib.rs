@@ -0,0 +1,435 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Conversion of Move values between BCS and the JSON representation used by the REST API.
+//!
+//! The JSON representation of a Move value depends on its type:
+//!   * `u8` and `bool` are JSON numbers and booleans,
+//!   * `u64` and `u128` are strings, JSON parsers commonly lose precision above 2^53,
+//!   * `address` is a hex string with the `0x` prefix and without leading zeros,
+//!   * `vector<u8>` is a hex string with the `0x` prefix, other vectors are arrays,
+//!   * `0x1::ASCII::String` is a string,
+//!   * `0x1::Option::Option<T>` is `{"vec": []}` or `{"vec": [value]}`; `null` and a bare `value`
+//!     are accepted as input too,
+//!   * other structs are objects with one property per field, sorted by field name.
+//!
+//! The conversions are driven by type layouts with types (`MoveStructLayout::WithTypes`), e.g. from
+//! `MoveValueAnnotator::get_type_layout_with_types`. Generic type parameters are already
+//! instantiated in such layouts, so generic structs need no special handling.
+
+use anyhow::{bail, format_err, Result};
+use move_deps::move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{StructTag, CORE_CODE_ADDRESS},
+    value::{MoveFieldLayout, MoveStruct, MoveStructLayout, MoveTypeLayout, MoveValue},
+};
+use serde_json::{json, Map, Value};
+
+/// Converts the JSON representation of a value of type `layout` into its BCS bytes.
+pub fn json_to_bcs(layout: &MoveTypeLayout, json: Value) -> Result<Vec<u8>> {
+    json_to_move_value(layout, json)?
+        .simple_serialize()
+        .ok_or_else(|| format_err!("failed to serialize Move value of type {:?}", layout))
+}
+
+/// Converts the BCS bytes of a value of type `layout` into its JSON representation.
+pub fn bcs_to_json(layout: &MoveTypeLayout, bytes: &[u8]) -> Result<Value> {
+    let value = MoveValue::simple_deserialize(bytes, layout)?;
+    move_value_to_json(layout, &value)
+}
+
+/// Converts the JSON representation of a value of type `layout` into a `MoveValue`. Structs are
+/// of the `MoveStruct::Runtime` flavor, which serializes into the same BCS bytes as stored on chain.
+pub fn json_to_move_value(layout: &MoveTypeLayout, json: Value) -> Result<MoveValue> {
+    Ok(match layout {
+        MoveTypeLayout::Bool => MoveValue::Bool(serde_json::from_value::<bool>(json)?),
+        MoveTypeLayout::U8 => MoveValue::U8(serde_json::from_value::<u8>(json)?),
+        MoveTypeLayout::U64 => {
+            let s = serde_json::from_value::<String>(json)?;
+            MoveValue::U64(s.parse().map_err(|e| {
+                format_err!("parse u64 string {:?} failed, caused by error: {}", s, e)
+            })?)
+        }
+        MoveTypeLayout::U128 => {
+            MoveValue::U128(serde_json::from_value::<String>(json)?.parse::<u128>()?)
+        }
+        MoveTypeLayout::Address => {
+            MoveValue::Address(parse_address(&serde_json::from_value::<String>(json)?)?)
+        }
+        MoveTypeLayout::Vector(item_layout) => json_to_move_vector(item_layout, json)?,
+        MoveTypeLayout::Struct(struct_layout) => json_to_move_struct(struct_layout, json)?,
+        MoveTypeLayout::Signer => bail!("unexpected move type {:?} for value {:?}", layout, json),
+    })
+}
+
+/// Converts a `MoveValue` of type `layout` into its JSON representation. The value can be of any
+/// struct flavor, field names are taken from the layout.
+pub fn move_value_to_json(layout: &MoveTypeLayout, value: &MoveValue) -> Result<Value> {
+    Ok(match (layout, value) {
+        (MoveTypeLayout::Bool, MoveValue::Bool(v)) => json!(v),
+        (MoveTypeLayout::U8, MoveValue::U8(v)) => json!(v),
+        (MoveTypeLayout::U64, MoveValue::U64(v)) => json!(v.to_string()),
+        (MoveTypeLayout::U128, MoveValue::U128(v)) => json!(v.to_string()),
+        (MoveTypeLayout::Address, MoveValue::Address(v)) => json!(v.to_hex_literal()),
+        (MoveTypeLayout::Vector(item_layout), MoveValue::Vector(values)) => {
+            if matches!(item_layout.as_ref(), MoveTypeLayout::U8) {
+                json!(format!("0x{}", hex::encode(bytes(values)?)))
+            } else {
+                Value::Array(
+                    values
+                        .iter()
+                        .map(|v| move_value_to_json(item_layout, v))
+                        .collect::<Result<_>>()?,
+                )
+            }
+        }
+        (MoveTypeLayout::Struct(struct_layout), MoveValue::Struct(move_struct)) => {
+            move_struct_to_json(struct_layout, move_struct)?
+        }
+        _ => bail!(
+            "Move value {:?} does not match the type {:?}",
+            value,
+            layout
+        ),
+    })
+}
+
+/// Returns the Move `0x1::ASCII::String` value of `string`.
+pub fn new_ascii_string(string: &str) -> MoveValue {
+    let byte_vector = MoveValue::Vector(string.bytes().map(MoveValue::U8).collect());
+    MoveValue::Struct(MoveStruct::Runtime(vec![byte_vector]))
+}
+
+pub fn is_ascii_string(struct_tag: &StructTag) -> bool {
+    is_core_struct(struct_tag, "ASCII", "String")
+}
+
+pub fn is_option(struct_tag: &StructTag) -> bool {
+    is_core_struct(struct_tag, "Option", "Option")
+}
+
+fn is_core_struct(struct_tag: &StructTag, module: &str, name: &str) -> bool {
+    struct_tag.address == CORE_CODE_ADDRESS
+        && struct_tag.module.as_str() == module
+        && struct_tag.name.as_str() == name
+}
+
+/// Parses an address with or without the `0x` prefix, the former may omit leading zeros.
+fn parse_address(s: &str) -> Result<AccountAddress> {
+    AccountAddress::from_hex_literal(s)
+        .or_else(|_| AccountAddress::from_hex(s))
+        .map_err(|_| format_err!("invalid account address {:?}", s))
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|e| {
+        format_err!(
+            "decode hex-encoded string({:?}) failed, caused by error: {}",
+            s,
+            e
+        )
+    })
+}
+
+fn json_to_move_vector(item_layout: &MoveTypeLayout, json: Value) -> Result<MoveValue> {
+    if matches!(item_layout, MoveTypeLayout::U8) {
+        let bytes = parse_hex(&serde_json::from_value::<String>(json)?)?;
+        Ok(MoveValue::Vector(
+            bytes.into_iter().map(MoveValue::U8).collect(),
+        ))
+    } else if let Value::Array(values) = json {
+        Ok(MoveValue::Vector(
+            values
+                .into_iter()
+                .map(|v| json_to_move_value(item_layout, v))
+                .collect::<Result<_>>()?,
+        ))
+    } else {
+        bail!("expected vector<{:?}>, but got: {:?}", item_layout, json)
+    }
+}
+
+fn json_to_move_struct(layout: &MoveStructLayout, json: Value) -> Result<MoveValue> {
+    let (struct_tag, field_layouts) = struct_layout_with_types(layout)?;
+    if is_ascii_string(struct_tag) {
+        let string = json
+            .as_str()
+            .ok_or_else(|| format_err!("failed to parse ASCII::String."))?;
+        return Ok(new_ascii_string(string));
+    }
+    if is_option(struct_tag) && !is_option_object(&json) {
+        let vec_layout = &field_layouts
+            .first()
+            .ok_or_else(|| format_err!("Option struct has no field"))?
+            .layout;
+        let item_layout = match vec_layout {
+            MoveTypeLayout::Vector(item_layout) => item_layout,
+            _ => bail!("Option struct field is not a vector: {:?}", vec_layout),
+        };
+        let items = match json {
+            Value::Null => vec![],
+            value => vec![json_to_move_value(item_layout, value)?],
+        };
+        return Ok(MoveValue::Struct(MoveStruct::Runtime(vec![
+            MoveValue::Vector(items),
+        ])));
+    }
+
+    let mut field_values = if let Value::Object(fields) = json {
+        fields
+    } else {
+        bail!("Expecting a JSON Map for struct.");
+    };
+    let fields = field_layouts
+        .iter()
+        .map(|field_layout| {
+            let name = field_layout.name.as_str();
+            let value = field_values
+                .remove(name)
+                .ok_or_else(|| format_err!("field {} not found.", name))?;
+            json_to_move_value(&field_layout.layout, value)
+        })
+        .collect::<Result<_>>()?;
+    Ok(MoveValue::Struct(MoveStruct::Runtime(fields)))
+}
+
+/// Whether `json` is the canonical `{"vec": [...]}` representation of an option. It takes
+/// precedence over the bare value shorthand, e.g. for `Option<Option<u64>>`.
+fn is_option_object(json: &Value) -> bool {
+    matches!(json, Value::Object(fields) if fields.len() == 1 && fields.get("vec").map_or(false, Value::is_array))
+}
+
+fn move_struct_to_json(layout: &MoveStructLayout, move_struct: &MoveStruct) -> Result<Value> {
+    let (struct_tag, field_layouts) = struct_layout_with_types(layout)?;
+    let values: Vec<&MoveValue> = match move_struct {
+        MoveStruct::Runtime(values) => values.iter().collect(),
+        MoveStruct::WithFields(fields) | MoveStruct::WithTypes { fields, .. } => {
+            fields.iter().map(|(_, value)| value).collect()
+        }
+    };
+    if values.len() != field_layouts.len() {
+        bail!(
+            "Move struct {:?} does not match the type {}",
+            move_struct,
+            struct_tag
+        );
+    }
+    if is_ascii_string(struct_tag) {
+        if let Some(MoveValue::Vector(v)) = values.first() {
+            return Ok(json!(String::from_utf8(bytes(v)?)?));
+        }
+        bail!("expect ASCII::String, but failed to decode struct value");
+    }
+
+    let mut fields = field_layouts
+        .iter()
+        .zip(values)
+        .map(|(field_layout, value)| {
+            Ok((
+                field_layout.name.to_string(),
+                move_value_to_json(&field_layout.layout, value)?,
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(Value::Object(fields.into_iter().collect::<Map<_, _>>()))
+}
+
+fn struct_layout_with_types(layout: &MoveStructLayout) -> Result<(&StructTag, &[MoveFieldLayout])> {
+    if let MoveStructLayout::WithTypes { type_, fields } = layout {
+        Ok((type_, fields))
+    } else {
+        bail!(
+            "Expecting `MoveStructLayout::WithTypes`, getting {:?}",
+            layout
+        )
+    }
+}
+
+fn bytes(values: &[MoveValue]) -> Result<Vec<u8>> {
+    values
+        .iter()
+        .map(|v| match v {
+            MoveValue::U8(byte) => Ok(*byte),
+            _ => bail!("expected u8, but got: {:?}", v),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_deps::move_core_types::{identifier::Identifier, language_storage::TypeTag};
+
+    fn struct_layout(
+        module: &str,
+        name: &str,
+        type_params: Vec<TypeTag>,
+        fields: Vec<(&str, MoveTypeLayout)>,
+    ) -> MoveTypeLayout {
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag {
+                address: CORE_CODE_ADDRESS,
+                module: Identifier::new(module).unwrap(),
+                name: Identifier::new(name).unwrap(),
+                type_params,
+            },
+            fields: fields
+                .into_iter()
+                .map(|(name, layout)| MoveFieldLayout::new(Identifier::new(name).unwrap(), layout))
+                .collect(),
+        })
+    }
+
+    fn ascii_string_layout() -> MoveTypeLayout {
+        struct_layout(
+            "ASCII",
+            "String",
+            vec![],
+            vec![(
+                "bytes",
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+            )],
+        )
+    }
+
+    fn option_layout(item_layout: MoveTypeLayout, item_type: TypeTag) -> MoveTypeLayout {
+        struct_layout(
+            "Option",
+            "Option",
+            vec![item_type],
+            vec![("vec", MoveTypeLayout::Vector(Box::new(item_layout)))],
+        )
+    }
+
+    fn assert_round_trip(layout: &MoveTypeLayout, json: Value) {
+        let bytes = json_to_bcs(layout, json.clone()).unwrap();
+        assert_eq!(bcs_to_json(layout, &bytes).unwrap(), json);
+    }
+
+    #[test]
+    fn test_primitives() {
+        assert_round_trip(&MoveTypeLayout::Bool, json!(true));
+        assert_round_trip(&MoveTypeLayout::U8, json!(1));
+        assert_round_trip(&MoveTypeLayout::U64, json!(u64::MAX.to_string()));
+        assert_round_trip(&MoveTypeLayout::U128, json!(u128::MAX.to_string()));
+        assert_round_trip(&MoveTypeLayout::Address, json!("0x1"));
+        assert_eq!(
+            json_to_bcs(&MoveTypeLayout::U64, json!("1")).unwrap(),
+            1u64.to_le_bytes().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_vectors() {
+        let bytes_layout = MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8));
+        assert_round_trip(&bytes_layout, json!("0x0102"));
+        assert_round_trip(&bytes_layout, json!("0x"));
+        assert_eq!(
+            json_to_bcs(&bytes_layout, json!("0102")).unwrap(),
+            vec![2, 1, 2]
+        );
+
+        let nested_layout = MoveTypeLayout::Vector(Box::new(MoveTypeLayout::Vector(Box::new(
+            MoveTypeLayout::U64,
+        ))));
+        assert_round_trip(&nested_layout, json!([["1", "2"], [], ["3"]]));
+    }
+
+    #[test]
+    fn test_nested_structs() {
+        let id_layout = struct_layout(
+            "GUID",
+            "ID",
+            vec![],
+            vec![
+                ("creation_num", MoveTypeLayout::U64),
+                ("addr", MoveTypeLayout::Address),
+            ],
+        );
+        let layout = struct_layout(
+            "Token",
+            "Token",
+            vec![],
+            vec![
+                ("id", id_layout),
+                ("name", ascii_string_layout()),
+                (
+                    "tags",
+                    MoveTypeLayout::Vector(Box::new(ascii_string_layout())),
+                ),
+            ],
+        );
+        let json = json!({
+            "id": {"creation_num": "1", "addr": "0x1"},
+            "name": "hello",
+            "tags": ["a", "b"],
+        });
+        assert_round_trip(&layout, json.clone());
+        // Properties are sorted by field name rather than in declaration order.
+        assert_eq!(
+            serde_json::to_string(
+                &bcs_to_json(&layout, &json_to_bcs(&layout, json.clone()).unwrap()).unwrap()
+            )
+            .unwrap(),
+            r#"{"id":{"addr":"0x1","creation_num":"1"},"name":"hello","tags":["a","b"]}"#
+        );
+
+        // Field order of the JSON object does not matter, the BCS bytes follow the layout.
+        let reordered = json!({
+            "tags": ["a", "b"],
+            "name": "hello",
+            "id": {"addr": "0x1", "creation_num": "1"},
+        });
+        assert_eq!(
+            json_to_bcs(&layout, reordered).unwrap(),
+            json_to_bcs(&layout, json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_options() {
+        let layout = option_layout(MoveTypeLayout::U64, TypeTag::U64);
+        assert_round_trip(&layout, json!({"vec": []}));
+        assert_round_trip(&layout, json!({"vec": ["1"]}));
+        assert_eq!(
+            json_to_bcs(&layout, json!(null)).unwrap(),
+            json_to_bcs(&layout, json!({"vec": []})).unwrap()
+        );
+        assert_eq!(
+            json_to_bcs(&layout, json!("1")).unwrap(),
+            json_to_bcs(&layout, json!({"vec": ["1"]})).unwrap()
+        );
+
+        // The canonical representation takes precedence over the shorthand.
+        let option_type = match &layout {
+            MoveTypeLayout::Struct(MoveStructLayout::WithTypes { type_, .. }) => type_.clone(),
+            _ => unreachable!(),
+        };
+        let nested_layout = option_layout(layout, TypeTag::Struct(option_type));
+        assert_eq!(
+            json_to_bcs(&nested_layout, json!({"vec": []})).unwrap(),
+            vec![0]
+        );
+        assert_eq!(
+            json_to_bcs(&nested_layout, json!({"vec": [{"vec": []}]})).unwrap(),
+            vec![1, 0]
+        );
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(json_to_bcs(&MoveTypeLayout::U64, json!(1)).is_err());
+        assert!(json_to_bcs(&MoveTypeLayout::U8, json!(256)).is_err());
+        assert!(json_to_bcs(&MoveTypeLayout::Address, json!("invalid")).is_err());
+        assert!(json_to_bcs(&MoveTypeLayout::Signer, json!("0x1")).is_err());
+        assert!(json_to_bcs(&ascii_string_layout(), json!({"bytes": "0x"})).is_err());
+        assert!(bcs_to_json(&MoveTypeLayout::U64, &[1]).is_err());
+
+        let runtime_layout =
+            MoveTypeLayout::Struct(MoveStructLayout::Runtime(vec![MoveTypeLayout::U64]));
+        assert!(json_to_bcs(&runtime_layout, json!({})).is_err());
+    }
+}
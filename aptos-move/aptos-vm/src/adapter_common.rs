@@ -1,7 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::*, data_cache::StateViewCache};
+use crate::{
+    counters::*,
+    data_cache::StateViewCache,
+    instrumented_state_view::{BlockAccessProfile, InstrumentedStateView},
+};
 use anyhow::Result;
 use aptos_state_view::StateView;
 use aptos_types::{
@@ -163,6 +167,17 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
     adapter: &A,
     transactions: Vec<Transaction>,
     data_cache: &mut StateViewCache<S>,
+) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
+    execute_block_impl_with_profile(adapter, transactions, data_cache, None)
+}
+
+/// Same as `execute_block_impl`, additionally recording the reads and writes of each executed
+/// transaction into `profile` when one is provided.
+pub(crate) fn execute_block_impl_with_profile<A: VMAdapter, S: StateView>(
+    adapter: &A,
+    transactions: Vec<Transaction>,
+    data_cache: &mut StateViewCache<S>,
+    mut profile: Option<&mut BlockAccessProfile>,
 ) -> Result<Vec<(VMStatus, TransactionOutput)>, VMStatus> {
     let mut result = vec![];
     let mut should_restart = false;
@@ -199,11 +214,23 @@ pub(crate) fn execute_block_impl<A: VMAdapter, S: StateView>(
             debug!(log_context, "Retry after reconfiguration");
             continue;
         };
-        let (vm_status, output, sender) = adapter.execute_single_transaction(
-            &txn,
-            &data_cache.as_move_resolver(),
-            &log_context,
-        )?;
+        let (vm_status, output, sender) = match profile.as_mut() {
+            Some(profile) => {
+                let instrumented_view = InstrumentedStateView::new(&*data_cache);
+                let res = adapter.execute_single_transaction(
+                    &txn,
+                    &instrumented_view.as_move_resolver(),
+                    &log_context,
+                )?;
+                profile.record(idx, instrumented_view.into_reads(), &res.1);
+                res
+            }
+            None => adapter.execute_single_transaction(
+                &txn,
+                &data_cache.as_move_resolver(),
+                &log_context,
+            )?,
+        };
         if !output.status().is_discarded() {
             data_cache.push_write_set(output.write_set());
         } else {
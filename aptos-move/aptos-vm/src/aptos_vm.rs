@@ -11,13 +11,15 @@ use crate::{
         charge_global_write_gas_usage, get_transaction_output, AptosVMImpl, AptosVMInternals,
     },
     counters::*,
-    data_cache::StateViewCache,
+    data_cache::{AsMoveResolver, StateViewCache},
     errors::expect_only_successful_execution,
+    instrumented_state_view::BlockAccessProfile,
     logging::AdapterLogSchema,
     move_vm_ext::{MoveResolverExt, SessionExt, SessionId},
     script_to_script_function,
     system_module_names::*,
     transaction_metadata::TransactionMetadata,
+    transaction_trace::{record_phase, record_unmetered_phase, PhaseTimer, TransactionTrace},
     VMExecutor, VMValidator,
 };
 use anyhow::Result;
@@ -25,9 +27,13 @@ use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_state_view::StateView;
 use aptos_types::{
+    access_path::AccessPath,
     account_config,
     block_metadata::BlockMetadata,
-    on_chain_config::{VMConfig, VMPublishingOption, Version},
+    on_chain_config::{
+        Features, GasSchedule, ScriptFunctionAllowList, VMPublishingOption, Version,
+    },
+    state_store::state_key::StateKey,
     transaction::{
         ChangeSet, ExecutionStatus, ModuleBundle, SignatureCheckedTransaction, SignedTransaction,
         Transaction, TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
@@ -40,8 +46,9 @@ use fail::fail_point;
 use move_deps::{
     move_binary_format::{
         access::ModuleAccess,
+        compatibility::Compatibility,
         errors::{verification_error, Location, VMResult},
-        CompiledModule, IndexKind,
+        normalized, CompiledModule, IndexKind,
     },
     move_core_types::{
         account_address::AccountAddress,
@@ -63,6 +70,7 @@ use std::{
 };
 
 static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
+static STATE_VIEW_PROFILING: OnceCell<bool> = OnceCell::new();
 
 #[derive(Clone)]
 pub struct AptosVM(pub(crate) AptosVMImpl);
@@ -82,17 +90,32 @@ impl AptosVM {
 
     pub fn init_with_config(
         version: Version,
-        on_chain_config: VMConfig,
+        gas_schedule: GasSchedule,
         publishing_option: VMPublishingOption,
     ) -> Self {
         info!("Adapter restarted for Validation");
         AptosVM(AptosVMImpl::init_with_config(
             version,
-            on_chain_config,
+            gas_schedule,
             publishing_option,
         ))
     }
 
+    /// Replaces the gas schedule this VM charges transactions with.
+    pub fn set_gas_schedule(&mut self, gas_schedule: GasSchedule) {
+        self.0.set_gas_schedule(gas_schedule)
+    }
+
+    /// Replaces the script function allow list this VM validates transactions against.
+    pub fn set_script_function_allow_list(&mut self, allow_list: ScriptFunctionAllowList) {
+        self.0.set_script_function_allow_list(allow_list)
+    }
+
+    /// Replaces the protocol features this VM gates transactions on.
+    pub fn set_features(&mut self, features: Features) {
+        self.0.set_features(features)
+    }
+
     /// Sets execution concurrency level when invoked the first time.
     pub fn set_concurrency_level_once(mut concurrency_level: usize) {
         concurrency_level = min(concurrency_level, num_cpus::get());
@@ -109,6 +132,18 @@ impl AptosVM {
         }
     }
 
+    /// Enables or disables per block state view profiling when invoked the first time.
+    pub fn set_state_view_profiling_once(enabled: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        STATE_VIEW_PROFILING.set(enabled).ok();
+    }
+
+    /// Whether blocks are executed with an instrumented state view and their access profile
+    /// dumped at debug level. Disabled unless set.
+    pub fn is_state_view_profiling_enabled() -> bool {
+        STATE_VIEW_PROFILING.get().copied().unwrap_or(false)
+    }
+
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals::new(&self.0)
     }
@@ -263,10 +298,13 @@ impl AptosVM {
         gas_status: &mut GasStatus,
         txn_data: &TransactionMetadata,
         log_context: &AdapterLogSchema,
+        mut trace: Option<&mut TransactionTrace>,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         gas_status.set_metering(false);
+        let timer = PhaseTimer::start(gas_status);
         self.0
             .run_success_epilogue(&mut session, gas_status, txn_data, log_context)?;
+        record_phase(&mut trace, "epilogue", timer, gas_status);
 
         Ok((
             VMStatus::Executed,
@@ -287,6 +325,7 @@ impl AptosVM {
         txn_data: &TransactionMetadata,
         payload: &TransactionPayload,
         log_context: &AdapterLogSchema,
+        mut trace: Option<&mut TransactionTrace>,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         fail_point!("move_adapter::execute_script_or_script_function", |_| {
             Err(VMStatus::Error(
@@ -296,11 +335,14 @@ impl AptosVM {
 
         // Run the execution logic
         {
+            let timer = PhaseTimer::start(gas_status);
             gas_status
                 .charge_intrinsic_gas(txn_data.transaction_size())
                 .map_err(|e| e.into_vm_status())?;
+            record_phase(&mut trace, "intrinsic", timer, gas_status);
 
-            match payload {
+            let timer = PhaseTimer::start(gas_status);
+            let execution_result = match payload {
                 TransactionPayload::Script(script) => {
                     let remapped_script = script_to_script_function::remapping(script.code());
                     let mut senders = vec![txn_data.sender()];
@@ -356,12 +398,18 @@ impl AptosVM {
                 TransactionPayload::ModuleBundle(_) | TransactionPayload::WriteSet(_) => {
                     return Err(VMStatus::Error(StatusCode::UNREACHABLE));
                 }
-            }
-            .map_err(|e| e.into_vm_status())?;
+            };
+            // Record the phase before bailing out, failed transactions are charged too.
+            record_phase(&mut trace, "execution", timer, gas_status);
+            execution_result.map_err(|e| e.into_vm_status())?;
 
-            charge_global_write_gas_usage(gas_status, &session, &txn_data.sender())?;
+            let timer = PhaseTimer::start(gas_status);
+            let storage_result =
+                charge_global_write_gas_usage(gas_status, &session, &txn_data.sender());
+            record_phase(&mut trace, "storage", timer, gas_status);
+            storage_result?;
 
-            self.success_transaction_cleanup(session, gas_status, txn_data, log_context)
+            self.success_transaction_cleanup(session, gas_status, txn_data, log_context, trace)
         }
     }
 
@@ -392,6 +440,53 @@ impl AptosVM {
         Ok(())
     }
 
+    /// Checks that the modules of the bundle already published under `sender` are upgraded
+    /// compatibly: public function signatures and struct layouts are preserved.  Only runs at
+    /// admission, so that incompatible upgrades are rejected before reaching execution.
+    fn check_module_upgrades_compatible(
+        sender: AccountAddress,
+        module_bundle: &ModuleBundle,
+        state_view: &impl StateView,
+        log_context: &AdapterLogSchema,
+    ) -> Result<(), StatusCode> {
+        for module_blob in module_bundle.iter() {
+            // Malformed modules and modules published under another address are rejected at
+            // execution
+            let new_module = match CompiledModule::deserialize(module_blob.code()) {
+                Ok(module) => module,
+                Err(_) => continue,
+            };
+            let module_id = new_module.self_id();
+            if module_id.address() != &sender {
+                continue;
+            }
+
+            let access_path = AccessPath::code_access_path(module_id.clone());
+            let old_blob = match state_view.get_state_value(&StateKey::AccessPath(access_path)) {
+                Ok(Some(blob)) => blob,
+                Ok(None) => continue,
+                Err(_) => return Err(StatusCode::STORAGE_ERROR),
+            };
+            let old_module = CompiledModule::deserialize(&old_blob)
+                .map_err(|_| StatusCode::CODE_DESERIALIZATION_ERROR)?;
+            let compatibility = Compatibility::check(
+                &normalized::Module::new(&old_module),
+                &normalized::Module::new(&new_module),
+            );
+            if !compatibility.struct_and_function_linking || !compatibility.struct_layout {
+                debug!(
+                    *log_context,
+                    "Rejected incompatible upgrade of module {}: linking compatible {}, layout compatible {}",
+                    module_id,
+                    compatibility.struct_and_function_linking,
+                    compatibility.struct_layout,
+                );
+                return Err(StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE);
+            }
+        }
+        Ok(())
+    }
+
     fn execute_modules<S: MoveResolverExt>(
         &self,
         mut session: SessionExt<S>,
@@ -399,6 +494,7 @@ impl AptosVM {
         txn_data: &TransactionMetadata,
         modules: &ModuleBundle,
         log_context: &AdapterLogSchema,
+        mut trace: Option<&mut TransactionTrace>,
     ) -> Result<(VMStatus, TransactionOutput), VMStatus> {
         fail_point!("move_adapter::execute_module", |_| {
             Err(VMStatus::Error(
@@ -413,25 +509,64 @@ impl AptosVM {
             account_config::CORE_CODE_ADDRESS
         };
 
+        let timer = PhaseTimer::start(gas_status);
         gas_status
             .charge_intrinsic_gas(txn_data.transaction_size())
             .map_err(|e| e.into_vm_status())?;
+        record_phase(&mut trace, "intrinsic", timer, gas_status);
 
+        let timer = PhaseTimer::start(gas_status);
         Self::verify_module_bundle(&mut session, modules)?;
-        session
-            .publish_module_bundle(modules.clone().into_inner(), module_address, gas_status)
-            .map_err(|e| e.into_vm_status())?;
+        let execution_result =
+            session.publish_module_bundle(modules.clone().into_inner(), module_address, gas_status);
+        record_phase(&mut trace, "execution", timer, gas_status);
+        execution_result.map_err(|e| e.into_vm_status())?;
+
+        let timer = PhaseTimer::start(gas_status);
+        let storage_result =
+            charge_global_write_gas_usage(gas_status, &session, &txn_data.sender());
+        record_phase(&mut trace, "storage", timer, gas_status);
+        storage_result?;
+
+        self.success_transaction_cleanup(session, gas_status, txn_data, log_context, trace)
+    }
 
-        charge_global_write_gas_usage(gas_status, &session, &txn_data.sender())?;
+    pub(crate) fn execute_user_transaction<S: MoveResolverExt>(
+        &self,
+        storage: &S,
+        txn: &SignatureCheckedTransaction,
+        log_context: &AdapterLogSchema,
+    ) -> (VMStatus, TransactionOutput) {
+        self.execute_user_transaction_impl(storage, txn, log_context, None)
+    }
 
-        self.success_transaction_cleanup(session, gas_status, txn_data, log_context)
+    /// Re-executes `txn` against `state_view` and returns its output along with the gas and
+    /// time spent in each phase of its execution. Meant for debugging, nothing is committed.
+    pub fn trace_user_transaction(
+        txn: SignedTransaction,
+        state_view: &impl StateView,
+    ) -> Result<(VMStatus, TransactionOutput, TransactionTrace), VMStatus> {
+        let txn = txn
+            .check_signature()
+            .map_err(|_| VMStatus::Error(StatusCode::INVALID_SIGNATURE))?;
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let mut trace = TransactionTrace::default();
+        let (vm_status, output) = vm.execute_user_transaction_impl(
+            &state_view.as_move_resolver(),
+            &txn,
+            &log_context,
+            Some(&mut trace),
+        );
+        Ok((vm_status, output, trace))
     }
 
-    pub(crate) fn execute_user_transaction<S: MoveResolverExt>(
+    fn execute_user_transaction_impl<S: MoveResolverExt>(
         &self,
         storage: &S,
         txn: &SignatureCheckedTransaction,
         log_context: &AdapterLogSchema,
+        mut trace: Option<&mut TransactionTrace>,
     ) -> (VMStatus, TransactionOutput) {
         macro_rules! unwrap_or_discard {
             ($res: expr) => {
@@ -444,6 +579,7 @@ impl AptosVM {
 
         // Revalidate the transaction.
         let mut session = self.0.new_session(storage, SessionId::txn(txn));
+        let timer = PhaseTimer::start_unmetered();
         if let Err(err) = validate_signature_checked_transaction::<S, Self>(
             self,
             &mut session,
@@ -453,6 +589,7 @@ impl AptosVM {
         ) {
             return discard_error_vm_status(err);
         };
+        record_unmetered_phase(&mut trace, "prologue", timer);
 
         let gas_schedule = unwrap_or_discard!(self.0.get_gas_schedule(log_context));
         let txn_data = TransactionMetadata::new(txn);
@@ -467,10 +604,16 @@ impl AptosVM {
                     &txn_data,
                     payload,
                     log_context,
+                    trace.as_deref_mut(),
                 ),
-            TransactionPayload::ModuleBundle(m) => {
-                self.execute_modules(session, &mut gas_status, &txn_data, m, log_context)
-            }
+            TransactionPayload::ModuleBundle(m) => self.execute_modules(
+                session,
+                &mut gas_status,
+                &txn_data,
+                m,
+                log_context,
+                trace.as_deref_mut(),
+            ),
             TransactionPayload::WriteSet(_) => {
                 return discard_error_vm_status(VMStatus::Error(StatusCode::UNREACHABLE));
             }
@@ -489,13 +632,16 @@ impl AptosVM {
                 if txn_status.is_discarded() {
                     discard_error_vm_status(err)
                 } else {
-                    self.failed_transaction_cleanup_and_keep_vm_status(
+                    let timer = PhaseTimer::start(&gas_status);
+                    let res = self.failed_transaction_cleanup_and_keep_vm_status(
                         err,
                         &mut gas_status,
                         &txn_data,
                         storage,
                         log_context,
-                    )
+                    );
+                    record_phase(&mut trace, "failure_epilogue", timer, &gas_status);
+                    res
                 }
             }
         }
@@ -803,6 +949,27 @@ impl AptosVM {
         BLOCK_TRANSACTION_COUNT.observe(count as f64);
         Ok(res)
     }
+
+    /// Alternate form of 'execute_block_and_keep_vm_status' that also returns the keys each
+    /// transaction read and wrote, along with the time spent fetching them from `state_view`.
+    /// Transactions are always executed sequentially so reads are attributed precisely.
+    pub fn execute_block_with_access_profile(
+        transactions: Vec<Transaction>,
+        state_view: &impl StateView,
+    ) -> Result<(Vec<(VMStatus, TransactionOutput)>, BlockAccessProfile), VMStatus> {
+        let mut state_view_cache = StateViewCache::new(state_view);
+        let count = transactions.len();
+        let vm = AptosVM::new(&state_view_cache);
+        let mut profile = BlockAccessProfile::default();
+        let res = adapter_common::execute_block_impl_with_profile(
+            &vm,
+            transactions,
+            &mut state_view_cache,
+            Some(&mut profile),
+        )?;
+        BLOCK_TRANSACTION_COUNT.observe(count as f64);
+        Ok((res, profile))
+    }
 }
 
 // Executor external API
@@ -823,7 +990,15 @@ impl VMExecutor for AptosVM {
         });
 
         let concurrency_level = Self::get_concurrency_level();
-        if concurrency_level > 1 {
+        if Self::is_state_view_profiling_enabled() {
+            let (output, profile) =
+                Self::execute_block_with_access_profile(transactions, state_view)?;
+            profile.log(state_view.id());
+            Ok(output
+                .into_iter()
+                .map(|(_vm_status, txn_output)| txn_output)
+                .collect())
+        } else if concurrency_level > 1 {
             let (result, _) = crate::parallel_executor::ParallelAptosVM::execute_block(
                 transactions,
                 state_view,
@@ -850,6 +1025,10 @@ impl VMValidator for AptosVM {
     /// 2. The script to be executed is under given specific configuration.
     /// 3. Invokes `Account.prologue`, which checks properties such as the transaction has the
     /// right sequence number and the sender has enough balance to pay for the gas.
+    ///    Script functions must also be in the on-chain allow list, if one is enabled; this is
+    ///    checked with the prologue, so that it is enforced at execution too.
+    /// 4. When module publishing is open, modules that are already published are upgraded
+    ///    compatibly.
     /// TBD:
     /// 1. Transaction arguments matches the main function's type signature.
     ///    We don't check this item for now and would execute the check at execution time.
@@ -858,7 +1037,31 @@ impl VMValidator for AptosVM {
         transaction: SignedTransaction,
         state_view: &impl StateView,
     ) -> VMValidatorResult {
-        validate_signed_transaction(self, transaction, state_view)
+        let sender = transaction.sender();
+        let module_bundle = match transaction.payload() {
+            TransactionPayload::ModuleBundle(module_bundle) => Some(module_bundle.clone()),
+            _ => None,
+        };
+
+        let result = validate_signed_transaction(self, transaction, state_view);
+        if let (None, Some(module_bundle)) = (result.status(), module_bundle) {
+            let log_context = AdapterLogSchema::new(state_view.id(), 0);
+            let is_open_module = self
+                .0
+                .publishing_option(&log_context)
+                .map_or(false, VMPublishingOption::is_open_module);
+            if is_open_module {
+                if let Err(status) = Self::check_module_upgrades_compatible(
+                    sender,
+                    &module_bundle,
+                    state_view,
+                    &log_context,
+                ) {
+                    return VMValidatorResult::error(status);
+                }
+            }
+        }
+        result
     }
 }
 
@@ -896,7 +1099,13 @@ impl VMAdapter for AptosVM {
                 self.0.check_gas(&txn_data, log_context)?;
                 self.0.run_script_prologue(session, &txn_data, log_context)
             }
-            TransactionPayload::ScriptFunction(_) => {
+            TransactionPayload::ScriptFunction(script_function) => {
+                if let Some(allow_list) = self.0.script_function_allow_list() {
+                    if !allow_list.is_allowed(script_function.module(), script_function.function())
+                    {
+                        return Err(VMStatus::Error(StatusCode::UNKNOWN_SCRIPT));
+                    }
+                }
                 // NOTE: Script and ScriptFunction shares the same prologue
                 self.0.check_gas(&txn_data, log_context)?;
                 self.0.run_script_prologue(session, &txn_data, log_context)
@@ -17,7 +17,8 @@ use aptos_types::{
     account_config,
     account_config::ChainSpecificAccountInfo,
     on_chain_config::{
-        ConfigStorage, OnChainConfig, VMConfig, VMPublishingOption, Version, APTOS_VERSION_3,
+        ConfigStorage, FeatureFlag, Features, GasSchedule, OnChainConfig, ScriptFunctionAllowList,
+        VMPublishingOption, Version, APTOS_VERSION_3,
     },
     transaction::{ExecutionStatus, TransactionOutput, TransactionStatus},
     vm_status::{StatusCode, VMStatus},
@@ -45,9 +46,11 @@ use std::sync::Arc;
 /// A wrapper to make VMRuntime standalone and thread safe.
 pub struct AptosVMImpl {
     move_vm: Arc<MoveVmExt>,
-    on_chain_config: Option<VMConfig>,
+    gas_schedule: Option<GasSchedule>,
     version: Option<Version>,
     publishing_option: Option<VMPublishingOption>,
+    script_function_allow_list: Option<ScriptFunctionAllowList>,
+    features: Features,
     chain_account_info: Option<ChainSpecificAccountInfo>,
 }
 
@@ -58,9 +61,11 @@ impl AptosVMImpl {
             .expect("should be able to create Move VM; check if there are duplicated natives");
         let mut vm = Self {
             move_vm: Arc::new(inner),
-            on_chain_config: None,
+            gas_schedule: None,
             version: None,
             publishing_option: None,
+            script_function_allow_list: None,
+            features: Features::default(),
             chain_account_info: None,
         };
         vm.load_configs_impl(&RemoteStorage::new(state));
@@ -70,20 +75,40 @@ impl AptosVMImpl {
 
     pub fn init_with_config(
         version: Version,
-        on_chain_config: VMConfig,
+        gas_schedule: GasSchedule,
         publishing_option: VMPublishingOption,
     ) -> Self {
         let inner = MoveVmExt::new()
             .expect("should be able to create Move VM; check if there are duplicated natives");
         Self {
             move_vm: Arc::new(inner),
-            on_chain_config: Some(on_chain_config),
+            gas_schedule: Some(gas_schedule),
             version: Some(version),
             publishing_option: Some(publishing_option),
+            script_function_allow_list: None,
+            features: Features::default(),
             chain_account_info: None,
         }
     }
 
+    /// Replaces the gas schedule loaded from storage, e.g. with the one delivered by a
+    /// reconfiguration notification.
+    pub fn set_gas_schedule(&mut self, gas_schedule: GasSchedule) {
+        self.gas_schedule = Some(gas_schedule);
+    }
+
+    /// Replaces the script function allow list loaded from storage, e.g. with the one delivered by
+    /// a reconfiguration notification.
+    pub fn set_script_function_allow_list(&mut self, allow_list: ScriptFunctionAllowList) {
+        self.script_function_allow_list = Some(allow_list);
+    }
+
+    /// Replaces the features loaded from storage, e.g. with the ones delivered by a
+    /// reconfiguration notification.
+    pub fn set_features(&mut self, features: Features) {
+        self.features = features;
+    }
+
     /// Provides access to some internal APIs of the VM.
     pub fn internals(&self) -> AptosVMInternals {
         AptosVMInternals(self)
@@ -109,10 +134,29 @@ impl AptosVMImpl {
         })
     }
 
+    /// `None` when no allow list is published, any script function can be called then.
+    pub(crate) fn script_function_allow_list(&self) -> Option<&ScriptFunctionAllowList> {
+        self.script_function_allow_list.as_ref()
+    }
+
+    /// No feature is enabled when the features config isn't published.
+    pub(crate) fn is_feature_enabled(&self, flag: FeatureFlag) -> bool {
+        self.features.is_enabled(flag)
+    }
+
+    /// Multi-agent transactions are supported from `APTOS_VERSION_3` on, or earlier if their
+    /// feature is enabled.
+    fn supports_multi_agent(&self) -> Result<bool, VMStatus> {
+        Ok(self.get_version()? >= APTOS_VERSION_3
+            || self.is_feature_enabled(FeatureFlag::MultiAgentTransactions))
+    }
+
     fn load_configs_impl<S: ConfigStorage>(&mut self, data_cache: &S) {
-        self.on_chain_config = VMConfig::fetch_config(data_cache);
+        self.gas_schedule = GasSchedule::fetch_config(data_cache);
         self.version = Version::fetch_config(data_cache);
         self.publishing_option = VMPublishingOption::fetch_config(data_cache);
+        self.script_function_allow_list = ScriptFunctionAllowList::fetch_config(data_cache);
+        self.features = Features::fetch_config(data_cache).unwrap_or_default();
     }
 
     // TODO: Move this to an on-chain config once those are a part of the core framework
@@ -132,9 +176,9 @@ impl AptosVMImpl {
     }
 
     pub fn get_gas_schedule(&self, log_context: &AdapterLogSchema) -> Result<&CostTable, VMStatus> {
-        self.on_chain_config
+        self.gas_schedule
             .as_ref()
-            .map(|config| &config.gas_schedule)
+            .map(|gas_schedule| &gas_schedule.cost_table)
             .ok_or_else(|| {
                 log_context.alert();
                 error!(*log_context, "VM Startup Failed. Gas Schedule Not Found");
@@ -265,7 +309,8 @@ impl AptosVMImpl {
             .iter()
             .map(|preimage| MoveValue::vector_u8(HashValue::sha3_256_of(preimage).to_vec()))
             .collect();
-        let args = if self.get_version()? >= APTOS_VERSION_3 && txn_data.is_multi_agent() {
+        let is_multi_agent = self.supports_multi_agent()? && txn_data.is_multi_agent();
+        let args = if is_multi_agent {
             vec![
                 MoveValue::Signer(txn_data.sender),
                 MoveValue::U64(txn_sequence_number),
@@ -289,12 +334,11 @@ impl AptosVMImpl {
                 MoveValue::vector_u8(txn_data.script_hash.clone()),
             ]
         };
-        let prologue_function_name =
-            if self.get_version()? >= APTOS_VERSION_3 && txn_data.is_multi_agent() {
-                &chain_specific_info.multi_agent_prologue_name
-            } else {
-                &chain_specific_info.script_prologue_name
-            };
+        let prologue_function_name = if is_multi_agent {
+            &chain_specific_info.multi_agent_prologue_name
+        } else {
+            &chain_specific_info.script_prologue_name
+        };
         session
             .execute_function_bypass_visibility(
                 &chain_specific_info.module_id(),
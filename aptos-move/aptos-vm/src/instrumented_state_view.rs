@@ -0,0 +1,138 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `StateView` wrapper that records what a transaction reads from storage and how long
+//! each fetch takes. Block execution uses it when state view profiling is enabled to build a
+//! per-block access profile, which is the input for partitioning transactions for parallel
+//! execution and for investigating slow blocks.
+
+use crate::logging::AdapterLogSchema;
+use anyhow::Result;
+use aptos_logger::prelude::*;
+use aptos_state_view::{StateView, StateViewId};
+use aptos_types::{state_store::state_key::StateKey, transaction::TransactionOutput};
+use serde::Serialize;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A single read issued against the wrapped `StateView`.
+#[derive(Clone, Debug, Serialize)]
+pub struct StateRead {
+    pub key: StateKey,
+    /// Size of the value read, `None` if the key does not exist or the read failed.
+    pub size: Option<usize>,
+    pub fetch_time_us: u64,
+}
+
+/// Records every `get_state_value` call forwarded to the underlying `StateView`.
+pub struct InstrumentedStateView<'a, S> {
+    state_view: &'a S,
+    reads: Mutex<Vec<StateRead>>,
+}
+
+impl<'a, S: StateView> InstrumentedStateView<'a, S> {
+    pub fn new(state_view: &'a S) -> Self {
+        Self {
+            state_view,
+            reads: Mutex::new(vec![]),
+        }
+    }
+
+    /// Consumes the view and returns the reads in the order they were issued.
+    pub fn into_reads(self) -> Vec<StateRead> {
+        self.reads.into_inner().unwrap()
+    }
+}
+
+impl<'a, S: StateView> StateView for InstrumentedStateView<'a, S> {
+    fn id(&self) -> StateViewId {
+        self.state_view.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        let timer = Instant::now();
+        let result = self.state_view.get_state_value(state_key);
+        let fetch_time = timer.elapsed();
+
+        self.reads.lock().unwrap().push(StateRead {
+            key: state_key.clone(),
+            size: result.as_ref().ok().and_then(|v| v.as_ref().map(Vec::len)),
+            fetch_time_us: fetch_time.as_micros() as u64,
+        });
+        result
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.state_view.is_genesis()
+    }
+}
+
+/// The keys a single transaction read and wrote.
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionAccessProfile {
+    /// Position of the transaction in the block.
+    pub index: usize,
+    pub reads: Vec<StateRead>,
+    /// Keys in the write set of the transaction output, empty if the transaction was discarded.
+    pub writes: Vec<StateKey>,
+}
+
+impl TransactionAccessProfile {
+    pub fn total_fetch_time(&self) -> Duration {
+        Duration::from_micros(self.reads.iter().map(|read| read.fetch_time_us).sum())
+    }
+}
+
+/// Per transaction reads and writes of a block, in execution order. Transactions skipped
+/// because of a reconfiguration earlier in the block are not included.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BlockAccessProfile {
+    pub transactions: Vec<TransactionAccessProfile>,
+}
+
+impl BlockAccessProfile {
+    pub(crate) fn record(
+        &mut self,
+        index: usize,
+        reads: Vec<StateRead>,
+        output: &TransactionOutput,
+    ) {
+        let writes = if output.status().is_discarded() {
+            vec![]
+        } else {
+            output
+                .write_set()
+                .iter()
+                .map(|(key, _)| key.clone())
+                .collect()
+        };
+        self.transactions.push(TransactionAccessProfile {
+            index,
+            reads,
+            writes,
+        });
+    }
+
+    pub fn total_fetch_time(&self) -> Duration {
+        self.transactions
+            .iter()
+            .map(TransactionAccessProfile::total_fetch_time)
+            .sum()
+    }
+
+    /// Dumps the profile as JSON at debug level.
+    pub fn log(&self, view_id: StateViewId) {
+        let log_context = AdapterLogSchema::new(view_id, 0);
+        match serde_json::to_string(self) {
+            Ok(profile) => debug!(
+                log_context,
+                "State view access profile, total fetch time {:?}: {}",
+                self.total_fetch_time(),
+                profile
+            ),
+            Err(e) => warn!(log_context, "Failed to serialize access profile: {:?}", e),
+        }
+    }
+}
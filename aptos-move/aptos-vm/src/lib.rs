@@ -114,6 +114,7 @@ mod adapter_common;
 pub mod aptos_vm;
 mod aptos_vm_impl;
 mod errors;
+pub mod instrumented_state_view;
 pub mod logging;
 pub mod move_vm_ext;
 pub mod natives;
@@ -122,6 +123,7 @@ pub mod read_write_set_analysis;
 pub mod script_to_script_function;
 pub mod system_module_names;
 pub mod transaction_metadata;
+pub mod transaction_trace;
 
 pub use crate::aptos_vm::AptosVM;
 
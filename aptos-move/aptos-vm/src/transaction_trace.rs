@@ -0,0 +1,95 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Itemized gas and time spent by the VM in each phase of a user transaction, recorded when a
+//! transaction is re-executed with `AptosVM::trace_user_transaction`.
+//!
+//! The Move VM charges `GasStatus` directly from its interpreter and natives, without a hook to
+//! observe each charge, so the `execution` phase is the finest grain: the gas of single
+//! instructions or native calls can't be attributed.
+
+use move_deps::{
+    move_core_types::gas_schedule::GasAlgebra, move_vm_types::gas_schedule::GasStatus,
+};
+use std::time::{Duration, Instant};
+
+/// A phase of the execution of a user transaction.
+#[derive(Clone, Debug)]
+pub struct TracePhase {
+    /// One of `prologue`, `intrinsic`, `execution`, `storage`, `epilogue` or
+    /// `failure_epilogue`.
+    pub name: &'static str,
+    pub gas_used: u64,
+    pub time: Duration,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TransactionTrace {
+    pub phases: Vec<TracePhase>,
+}
+
+impl TransactionTrace {
+    pub fn gas_used(&self) -> u64 {
+        self.phases.iter().map(|phase| phase.gas_used).sum()
+    }
+
+    pub fn total_time(&self) -> Duration {
+        self.phases.iter().map(|phase| phase.time).sum()
+    }
+}
+
+/// Marks the beginning of a phase, see `record_phase`.
+pub(crate) struct PhaseTimer {
+    start: Instant,
+    remaining_gas: u64,
+}
+
+impl PhaseTimer {
+    pub(crate) fn start(gas_status: &GasStatus) -> Self {
+        Self {
+            start: Instant::now(),
+            remaining_gas: gas_status.remaining_gas().get(),
+        }
+    }
+
+    /// Starts a phase that runs before gas metering is set up.
+    pub(crate) fn start_unmetered() -> Self {
+        Self {
+            start: Instant::now(),
+            remaining_gas: 0,
+        }
+    }
+}
+
+/// Appends the phase started by `timer` to `trace`, if the transaction is being traced.
+pub(crate) fn record_phase(
+    trace: &mut Option<&mut TransactionTrace>,
+    name: &'static str,
+    timer: PhaseTimer,
+    gas_status: &GasStatus,
+) {
+    if let Some(trace) = trace {
+        trace.phases.push(TracePhase {
+            name,
+            gas_used: timer
+                .remaining_gas
+                .saturating_sub(gas_status.remaining_gas().get()),
+            time: timer.start.elapsed(),
+        });
+    }
+}
+
+/// Appends a phase started with `PhaseTimer::start_unmetered` to `trace`.
+pub(crate) fn record_unmetered_phase(
+    trace: &mut Option<&mut TransactionTrace>,
+    name: &'static str,
+    timer: PhaseTimer,
+) {
+    if let Some(trace) = trace {
+        trace.phases.push(TracePhase {
+            name,
+            gas_used: 0,
+            time: timer.start.elapsed(),
+        });
+    }
+}
@@ -34,6 +34,7 @@ use aptos_types::{
 };
 use aptos_vm::{
     data_cache::{AsMoveResolver, RemoteStorage},
+    instrumented_state_view::BlockAccessProfile,
     move_vm_ext::{MoveVmExt, SessionId},
     parallel_executor::ParallelAptosVM,
     AptosVM, VMExecutor, VMValidator,
@@ -312,6 +313,21 @@ impl FakeExecutor {
         )
     }
 
+    /// Alternate form of 'execute_block' that also returns the keys each transaction read
+    /// and wrote
+    pub fn execute_block_with_access_profile(
+        &self,
+        txn_block: Vec<SignedTransaction>,
+    ) -> Result<(Vec<(VMStatus, TransactionOutput)>, BlockAccessProfile), VMStatus> {
+        AptosVM::execute_block_with_access_profile(
+            txn_block
+                .into_iter()
+                .map(Transaction::UserTransaction)
+                .collect(),
+            &self.data_store,
+        )
+    }
+
     /// Executes the transaction as a singleton block and applies the resulting write set to the
     /// data store. Panics if execution fails
     pub fn execute_and_apply(&mut self, transaction: SignedTransaction) -> TransactionOutput {
@@ -354,3 +354,66 @@ pub fn test_publishing_allow_modules() {
         &TransactionStatus::Keep(ExecutionStatus::Success)
     );
 }
+
+// Incompatible upgrades are rejected by the validator when publishing is open, compatible ones are
+// left to execution
+#[test]
+pub fn incompatible_upgrade_rejected_at_validation() {
+    let mut executor = FakeExecutor::from_genesis_with_options(VMPublishingOption::open());
+
+    let sequence_number = 2;
+    let account = executor.create_raw_account_data(1_000_000, sequence_number);
+    executor.add_account_data(&account);
+    let compile = |program: &str| {
+        compile_module(&program.replace("##ADDRESS##", &account.address().to_hex())).1
+    };
+
+    let txn = account
+        .account()
+        .transaction()
+        .module(compile(
+            "
+            module 0x##ADDRESS##.M {
+                struct T { f: u64 }
+            }
+            ",
+        ))
+        .sequence_number(sequence_number)
+        .sign();
+    executor.execute_and_apply(txn);
+
+    let layout_incompatible = account
+        .account()
+        .transaction()
+        .module(compile(
+            "
+            module 0x##ADDRESS##.M {
+                struct T { f: bool }
+            }
+            ",
+        ))
+        .sequence_number(sequence_number + 1)
+        .sign();
+    assert_eq!(
+        executor.verify_transaction(layout_incompatible).status(),
+        Some(StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE)
+    );
+
+    let layout_compatible = account
+        .account()
+        .transaction()
+        .module(compile(
+            "
+            module 0x##ADDRESS##.M {
+                struct T { f: u64 }
+                struct U { g: bool }
+            }
+            ",
+        ))
+        .sequence_number(sequence_number + 1)
+        .sign();
+    assert_eq!(
+        executor.verify_transaction(layout_compatible).status(),
+        None
+    );
+}
@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_transaction_builder::aptos_stdlib;
-use aptos_types::{on_chain_config::Version, transaction::TransactionStatus};
+use aptos_types::{
+    on_chain_config::{access_path_for_config, OnChainConfig, ScriptFunctionAllowList, Version},
+    state_store::state_key::StateKey,
+    transaction::{ExecutionStatus, TransactionStatus},
+    vm_status::StatusCode,
+    write_set::{WriteOp, WriteSetMut},
+};
 use aptos_vm::AptosVM;
 use language_e2e_tests::{
-    common_transactions::peer_to_peer_txn, test_with_different_versions,
+    common_transactions::peer_to_peer_txn, executor::FakeExecutor, test_with_different_versions,
     versioning::CURRENT_RELEASE_VERSIONS,
 };
 
@@ -67,3 +73,45 @@ fn drop_txn_after_reconfiguration() {
     }
     }
 }
+
+#[test]
+fn script_function_allow_list_is_enforced_at_execution() {
+    let mut executor = FakeExecutor::from_genesis_file();
+    let sender = executor.create_raw_account_data(1_000_000, 10);
+    let receiver = executor.create_raw_account_data(100_000, 10);
+    executor.add_account_data(&sender);
+    executor.add_account_data(&receiver);
+
+    // Publish an allow list that doesn't include the transfer function, as if it was enabled
+    // after the transaction was validated
+    let allow_list = ScriptFunctionAllowList::new(true, vec!["0x1::Version::set".to_string()]);
+    let write_set = WriteSetMut::new(vec![(
+        StateKey::AccessPath(access_path_for_config(ScriptFunctionAllowList::CONFIG_ID)),
+        WriteOp::Value(bcs::to_bytes(&allow_list).unwrap()),
+    )])
+    .freeze()
+    .unwrap();
+    executor.apply_write_set(&write_set);
+
+    let txn = peer_to_peer_txn(sender.account(), receiver.account(), 10, 1000);
+    let output = executor.execute_transaction(txn.clone());
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(StatusCode::UNKNOWN_SCRIPT)
+    );
+
+    // Once the function is allowed, the transaction is executed
+    let allow_list = ScriptFunctionAllowList::new(true, vec!["0x1::Coin::transfer".to_string()]);
+    let write_set = WriteSetMut::new(vec![(
+        StateKey::AccessPath(access_path_for_config(ScriptFunctionAllowList::CONFIG_ID)),
+        WriteOp::Value(bcs::to_bytes(&allow_list).unwrap()),
+    )])
+    .freeze()
+    .unwrap();
+    executor.apply_write_set(&write_set);
+    let output = executor.execute_transaction(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+}
@@ -217,6 +217,48 @@ fn few_peer_to_peer_with_event() {
     }
 }
 
+#[test]
+fn peer_to_peer_access_profile() {
+    test_with_different_versions! {CURRENT_RELEASE_VERSIONS, |test_env| {
+        let mut executor = test_env.executor;
+
+        let sender = executor.create_raw_account_data(3_000_000, 10);
+        let receiver = executor.create_raw_account_data(3_000_000, 10);
+        executor.add_account_data(&sender);
+        executor.add_account_data(&receiver);
+
+        let txns = vec![
+            peer_to_peer_txn(sender.account(), receiver.account(), 10, 1_000),
+            peer_to_peer_txn(sender.account(), receiver.account(), 11, 1_000),
+        ];
+        let (output, profile) = executor.execute_block_with_access_profile(txns).unwrap();
+        assert_eq!(output.len(), 2);
+        assert_eq!(profile.transactions.len(), 2);
+
+        for (idx, ((_, txn_output), txn_profile)) in
+            output.iter().zip(profile.transactions.iter()).enumerate()
+        {
+            assert_eq!(
+                txn_output.status(),
+                &TransactionStatus::Keep(ExecutionStatus::Success)
+            );
+            assert_eq!(txn_profile.index, idx);
+            assert!(!txn_profile.reads.is_empty());
+            let writes: Vec<_> = txn_output.write_set().iter().map(|(key, _)| key).collect();
+            assert_eq!(txn_profile.writes.iter().collect::<Vec<_>>(), writes);
+        }
+
+        // Both transfers touch the sender's account, so the second one reads what the first
+        // one wrote.
+        let first_writes = &profile.transactions[0].writes;
+        assert!(profile.transactions[1]
+            .reads
+            .iter()
+            .any(|read| first_writes.contains(&read.key)));
+    }
+    }
+}
+
 // Holder for transaction data; arguments to transactions.
 pub(crate) struct TxnInfo {
     pub sender: Account,
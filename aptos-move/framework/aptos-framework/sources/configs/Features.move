@@ -0,0 +1,69 @@
+/// Maintains the set of protocol features enabled on this network, as a bit vector indexed by
+/// feature. New transaction types and behaviors are gated on their feature, so they can be
+/// switched on at a reconfiguration rather than with a new release.
+module AptosFramework::Features {
+    use Std::Vector;
+    use AptosFramework::Reconfiguration;
+    use AptosFramework::SystemAddresses;
+
+    /// Feature `i` is enabled if bit `i % 8` of byte `i / 8` is set.
+    struct Features has key {
+        features: vector<u8>,
+    }
+
+    /// Enables the features in `enable` and disables the ones in `disable`, publishing the set
+    /// if it doesn't exist yet.
+    public(script) fun change_feature_flags(
+        account: signer,
+        enable: vector<u64>,
+        disable: vector<u64>,
+    ) acquires Features {
+        SystemAddresses::assert_core_resource(&account);
+
+        if (!exists<Features>(@CoreResources)) {
+            move_to(&account, Features { features: Vector::empty() });
+        };
+        let features = &mut borrow_global_mut<Features>(@CoreResources).features;
+
+        let i = 0;
+        let n = Vector::length(&enable);
+        while (i < n) {
+            set(features, *Vector::borrow(&enable, i), true);
+            i = i + 1;
+        };
+        let i = 0;
+        let n = Vector::length(&disable);
+        while (i < n) {
+            set(features, *Vector::borrow(&disable, i), false);
+            i = i + 1;
+        };
+
+        Reconfiguration::reconfigure();
+    }
+
+    /// Whether `feature` is enabled.
+    public fun is_enabled(feature: u64): bool acquires Features {
+        if (!exists<Features>(@CoreResources)) {
+            return false
+        };
+        let features = &borrow_global<Features>(@CoreResources).features;
+        let byte_index = feature / 8;
+        let bit_mask = 1 << ((feature % 8) as u8);
+        byte_index < Vector::length(features)
+            && (*Vector::borrow(features, byte_index) & bit_mask) != 0
+    }
+
+    fun set(features: &mut vector<u8>, feature: u64, include: bool) {
+        let byte_index = feature / 8;
+        let bit_mask = 1 << ((feature % 8) as u8);
+        while (Vector::length(features) <= byte_index) {
+            Vector::push_back(features, 0)
+        };
+        let entry = Vector::borrow_mut(features, byte_index);
+        if (include) {
+            *entry = *entry | bit_mask
+        } else {
+            *entry = *entry & (0xff ^ bit_mask)
+        }
+    }
+}
@@ -0,0 +1,32 @@
+/// Maintains the script functions user transactions are allowed to call, for permissioned
+/// networks. Changes take effect at the reconfiguration they trigger, without restarting nodes.
+module AptosFramework::ScriptFunctionAllowList {
+    use AptosFramework::Reconfiguration;
+    use AptosFramework::SystemAddresses;
+
+    /// Functions are named `<address>::<module>::<function>`, e.g. `0x1::Coin::transfer`.
+    struct ScriptFunctionAllowList has key {
+        /// Any script function can be called if the list isn't enabled, or isn't published.
+        enabled: bool,
+        allowed_functions: vector<vector<u8>>,
+    }
+
+    /// Publishes or replaces the allow list.
+    public(script) fun set_allow_list(
+        account: signer,
+        enabled: bool,
+        allowed_functions: vector<vector<u8>>,
+    ) acquires ScriptFunctionAllowList {
+        SystemAddresses::assert_core_resource(&account);
+
+        if (exists<ScriptFunctionAllowList>(@CoreResources)) {
+            let config = borrow_global_mut<ScriptFunctionAllowList>(@CoreResources);
+            config.enabled = enabled;
+            config.allowed_functions = allowed_functions;
+        } else {
+            move_to(&account, ScriptFunctionAllowList { enabled, allowed_functions });
+        };
+
+        Reconfiguration::reconfigure();
+    }
+}
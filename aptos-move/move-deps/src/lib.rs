@@ -3,6 +3,7 @@
 
 pub use move_abigen;
 pub use move_binary_format;
+pub use move_bytecode_source_map;
 pub use move_bytecode_utils;
 pub use move_bytecode_verifier;
 pub use move_cli;
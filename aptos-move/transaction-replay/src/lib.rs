@@ -14,6 +14,7 @@ use aptos_types::{
     contract_event::{ContractEvent, EventWithProof},
     event::EventKey,
     transaction::{ChangeSet, Transaction, TransactionOutput, Version, WriteSetPayload},
+    vm_status::VMStatus,
     write_set::WriteOp,
 };
 use aptos_validator_interface::{AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView};
@@ -21,6 +22,7 @@ use aptos_vm::{
     data_cache::{AsMoveResolver, RemoteStorage},
     logging::AdapterLogSchema,
     move_vm_ext::{MoveVmExt, SessionId},
+    transaction_trace::TransactionTrace,
     AptosVM, VMExecutor,
 };
 use move_deps::{
@@ -74,6 +76,22 @@ impl AptosDebugger {
             .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
     }
 
+    /// Re-executes the user transaction committed at `version`, itemizing the gas and time
+    /// spent in each phase of its execution.
+    pub fn trace_transaction_at_version(
+        &self,
+        version: Version,
+    ) -> Result<(VMStatus, TransactionOutput, TransactionTrace)> {
+        let txn = match self.debugger.get_committed_transactions(version, 1)?.pop() {
+            Some(Transaction::UserTransaction(txn)) => txn,
+            Some(txn) => bail!("Not a user transaction at version {}: {:?}", version, txn),
+            None => bail!("No transaction at version {}", version),
+        };
+        let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+        AptosVM::trace_user_transaction(txn, &state_view)
+            .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))
+    }
+
     pub fn execute_past_transactions(
         &self,
         mut begin: Version,
@@ -1,15 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{bail, Result};
+use anyhow::{bail, format_err, Result};
+use aptos_transaction_builder::error_explain;
 use aptos_transaction_replay::AptosDebugger;
 use aptos_types::{
     account_address::AccountAddress,
     event::EventKey,
     transaction::{TransactionPayload, Version},
+    vm_status::{AbortLocation, VMStatus},
 };
 use difference::Changeset;
-use move_deps::move_core_types::effects::ChangeSet;
+use move_deps::move_core_types::{
+    effects::ChangeSet, identifier::Identifier, language_storage::ModuleId,
+};
 use std::{fs, path::PathBuf};
 use structopt::StructOpt;
 
@@ -40,6 +44,10 @@ enum Command {
         account: AccountAddress,
         seq: u64,
     },
+    /// Re-execute the user transaction at `version`, printing the gas and time spent in each
+    /// phase of its execution. Instructions and natives are not itemized.
+    #[structopt(name = "trace-transaction")]
+    TraceTransaction { version: Version },
     /// Execute a writeset as if it is signed by the Root and print the result.
     #[structopt(name = "replay-writeset")]
     ReplayWriteSetAtVersion {
@@ -72,6 +80,14 @@ enum Command {
     /// Get the bytecode for all Framework modules at `version`
     #[structopt(name = "get-modules")]
     GetModules { version: Version },
+    /// Explain the abort `code` raised by the framework module `module`, e.g. `0x1::Coin`, from
+    /// the error map of the framework release.
+    #[structopt(name = "explain-abort")]
+    ExplainAbort {
+        #[structopt(parse(try_from_str = parse_module_id))]
+        module: ModuleId,
+        code: u64,
+    },
     #[structopt(name = "bisect-transaction")]
     BisectTransaction {
         #[structopt(parse(from_os_str))]
@@ -85,8 +101,36 @@ enum Command {
     },
 }
 
+fn parse_module_id(s: &str) -> Result<ModuleId> {
+    let (address, name) = s
+        .split_once("::")
+        .ok_or_else(|| format_err!("expected <address>::<module name>, got {}", s))?;
+    Ok(ModuleId::new(
+        AccountAddress::from_hex_literal(address)?,
+        Identifier::new(name)?,
+    ))
+}
+
+fn explain_abort(module: &ModuleId, code: u64) -> String {
+    match error_explain::get_explanation(module, code) {
+        Some(explanation) => format!(
+            "{} - {}\n{}\n{}",
+            explanation.category.code_name,
+            explanation.reason.code_name,
+            explanation.category.code_description,
+            explanation.reason.code_description
+        ),
+        None => format!("no explanation of code {} of {}", code, module),
+    }
+}
+
 fn main() -> Result<()> {
     let opt = Opt::from_args();
+    // Explaining an abort only needs the error map built into the tool.
+    if let Command::ExplainAbort { module, code } = &opt.cmd {
+        println!("{}", explain_abort(module, *code));
+        return Ok(());
+    }
     let debugger = if let Some(p) = opt.db {
         AptosDebugger::db(p)?
     } else {
@@ -126,6 +170,20 @@ fn main() -> Result<()> {
                 debugger.execute_past_transactions(version, 1, opt.save_write_sets)
             );
         }
+        Command::TraceTransaction { version } => {
+            let (vm_status, output, trace) = debugger.trace_transaction_at_version(version)?;
+            println!("Status: {:?}, gas used: {}", vm_status, output.gas_used());
+            if let VMStatus::MoveAbort(AbortLocation::Module(module), code) = &vm_status {
+                println!("Abort: {}", explain_abort(module, *code));
+            }
+            for phase in &trace.phases {
+                println!(
+                    "{:>16}: {:>10} gas {:>12?}",
+                    phase.name, phase.gas_used, phase.time
+                );
+            }
+            println!("Total time: {:?}", trace.total_time());
+        }
         Command::ReplayWriteSetAtVersion {
             write_set_blob_path: path,
             version,
@@ -201,6 +259,7 @@ fn main() -> Result<()> {
                 debugger.get_aptos_framework_modules_at_version(version, opt.save_write_sets)?;
             println!("Fetched {} modules", modules.len())
         }
+        Command::ExplainAbort { .. } => unreachable!("explained before attaching the debugger"),
         Command::BisectTransaction {
             sender,
             script_path,
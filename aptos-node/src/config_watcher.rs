@@ -0,0 +1,100 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::NodeConfig;
+use aptos_logger::{prelude::*, Filter, Logger};
+use config_notifications::{ConfigNotificationService, LoggerConfigListener};
+use futures::StreamExt;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    runtime::{Builder, Runtime},
+    signal::unix::{signal, SignalKind},
+    time::interval,
+};
+
+/// How often the config file is checked for modifications.
+const CONFIG_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Reloads the config at `config_path` on SIGHUP and whenever the file is modified, and applies it
+/// through `config_notifications`. See `ConfigNotificationService` for the fields that can change
+/// without a restart. Returns the runtime the watcher runs on.
+pub fn start_config_watcher(
+    config_path: PathBuf,
+    mut config_notifications: ConfigNotificationService,
+    logger: Option<Arc<Logger>>,
+) -> Runtime {
+    let runtime = Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("config-watcher")
+        .enable_all()
+        .build()
+        .expect("Failed to create config watcher runtime");
+
+    if let Some(logger) = logger {
+        runtime.spawn(update_logger(
+            logger,
+            config_notifications.subscribe_to_logger_updates(),
+        ));
+    }
+
+    runtime.spawn(async move {
+        let mut sighup = signal(SignalKind::hangup()).expect("Failed to listen for SIGHUP");
+        let mut check_interval = interval(CONFIG_FILE_CHECK_INTERVAL);
+        let mut last_modified = modified_time(&config_path);
+        loop {
+            let reload = tokio::select! {
+                _ = sighup.recv() => {
+                    info!("Received SIGHUP, reloading the node config");
+                    true
+                },
+                _ = check_interval.tick() => {
+                    let modified = modified_time(&config_path);
+                    let changed = modified != last_modified;
+                    last_modified = modified;
+                    if changed {
+                        info!("Node config file modified, reloading it");
+                    }
+                    changed
+                },
+            };
+            if !reload {
+                continue;
+            }
+
+            match NodeConfig::load(&config_path) {
+                Ok(config) => {
+                    let updated = config_notifications.reload(&config);
+                    info!(updated = ?updated, "Reloaded the node config");
+                }
+                Err(error) => error!(
+                    error = ?error,
+                    "Failed to reload the node config, keeping the current one"
+                ),
+            }
+        }
+    });
+    runtime
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Applies the log level of every logger config update. The level replaces the local logging
+/// filter, including one set through the debug interface.
+async fn update_logger(logger: Arc<Logger>, mut config_updates: LoggerConfigListener) {
+    while let Some(logger_config) = config_updates.next().await {
+        info!(level = ?logger_config.level, "Updating local logging level");
+        logger.set_filter(
+            Filter::builder()
+                .filter_level(logger_config.level.into())
+                .build(),
+        );
+    }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Running a node in the current process, for integration tests and tools which would otherwise
+//! spawn the binary and parse its logs to find out when and where it is up.
+
+use crate::{preflight, setup_environment, AptosHandle};
+use aptos_config::config::NodeConfig;
+use aptos_mempool::MempoolClientSender;
+use config_notifications::ConfigNotificationService;
+use std::{fmt, net::SocketAddr, sync::Arc};
+use storage_interface::DbReader;
+
+/// A node started by `start_node`, which runs until it is shut down or dropped.
+pub struct NodeHandle {
+    config: NodeConfig,
+    handle: AptosHandle,
+    preflight_warnings: Vec<preflight::PreflightFailure>,
+}
+
+impl NodeHandle {
+    pub fn config(&self) -> &NodeConfig {
+        &self.config
+    }
+
+    /// The preflight checks which failed without preventing the node from starting, e.g. the
+    /// short resources of the host when `preflight.enforce_resources` isn't set.
+    pub fn preflight_warnings(&self) -> &[preflight::PreflightFailure] {
+        &self.preflight_warnings
+    }
+
+    /// Reads the DB of the node, which is kept up to date as the node commits.
+    pub fn db_reader(&self) -> Arc<dyn DbReader> {
+        self.handle.db.clone()
+    }
+
+    /// Submits transactions to mempool, as the API does.
+    pub fn mempool_client(&self) -> MempoolClientSender {
+        self.handle.mempool_client.clone()
+    }
+
+    /// The address the API is served on. If `api.address` has port 0, the port is the one the
+    /// OS picked.
+    pub fn api_address(&self) -> SocketAddr {
+        self.handle.api.address()
+    }
+
+    /// Shuts the node down, as on SIGTERM, see `AptosHandle::shutdown`.
+    pub fn shutdown(self) {
+        self.handle.shutdown(&self.config)
+    }
+}
+
+/// Why `start_node` failed.
+#[derive(Debug)]
+pub enum StartNodeError {
+    /// The preflight checks failed, nothing was started.
+    Preflight(Vec<preflight::PreflightFailure>),
+    /// A component failed to start, e.g. the API address is already in use.
+    Setup(anyhow::Error),
+}
+
+impl fmt::Display for StartNodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Preflight(failures) => {
+                write!(f, "Preflight checks failed:")?;
+                for failure in failures {
+                    write!(f, " {}", failure)?;
+                }
+                Ok(())
+            }
+            Self::Setup(error) => write!(f, "Failed to start the node: {:#}", error),
+        }
+    }
+}
+
+/// Runs the preflight checks, then starts a node with `config` and returns once all its
+/// components are started (for a validator, once state sync reached the waypoint), without
+/// waiting for a signal. Unlike `start`, the logger, the panic handler and the failpoints are
+/// left to the caller, and the config is not reloaded.
+///
+/// The metrics, debug and storage services listen on the configured ports, so that several nodes
+/// in a process need distinct ports, e.g. from `NodeConfig::randomize_ports`.
+pub fn start_node(config: &NodeConfig) -> Result<NodeHandle, StartNodeError> {
+    let preflight_report = preflight::run_preflight_checks(config);
+    if !preflight_report.failures.is_empty() {
+        return Err(StartNodeError::Preflight(preflight_report.failures));
+    }
+
+    let mut config_notifications = ConfigNotificationService::new(config.clone());
+    let handle = setup_environment(config, None, &mut config_notifications)
+        .map_err(StartNodeError::Setup)?;
+    Ok(NodeHandle {
+        config: config.clone(),
+        handle,
+        preflight_warnings: preflight_report.warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shutdown::run_shutdown_steps;
+    use aptos_genesis_tool::validator_builder::ValidatorBuilder;
+    use aptos_temppath::TempPath;
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::{
+        thread,
+        time::{Duration, Instant},
+    };
+
+    #[test]
+    fn test_start_node() {
+        let dir = TempPath::new();
+        dir.create_as_dir().unwrap();
+        let mut template = NodeConfig::default_for_validator();
+        // short resources only warn, unless they are enforced
+        template.preflight.check_resources = true;
+        template.preflight.enforce_resources = false;
+        template.preflight.min_free_disk_space_bytes = u64::MAX;
+        let (_, _, _, validators) = ValidatorBuilder::new(
+            dir.path(),
+            cached_framework_packages::module_blobs().to_vec(),
+        )
+        .template(template)
+        .randomize_first_validator_ports(true)
+        .build(StdRng::from_seed([0; 32]))
+        .unwrap();
+        let mut config = validators[0].config.clone();
+        // the OS picks the API port
+        config.api.address.set_port(0);
+
+        let node = start_node(&config).unwrap();
+        assert_eq!(node.api_address().ip(), config.api.address.ip());
+        assert_ne!(node.api_address().port(), 0);
+        assert!(node
+            .preflight_warnings()
+            .iter()
+            .any(|warning| warning.check == "disk_space"));
+
+        // the validator commits blocks on its own
+        let db = node.db_reader();
+        let start_version = db.get_latest_version().unwrap();
+        let deadline = Instant::now() + Duration::from_secs(60);
+        while db.get_latest_version().unwrap() <= start_version {
+            assert!(Instant::now() < deadline, "The node never committed");
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        // the components writing to storage are stopped before it is flushed
+        let NodeHandle { config, handle, .. } = node;
+        let steps = handle.shutdown_steps(&config);
+        let names: Vec<_> = steps.iter().map(|step| step.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "api",
+                "mempool",
+                "consensus",
+                "state sync",
+                "storage",
+                "other components"
+            ]
+        );
+        run_shutdown_steps(steps, Instant::now() + Duration::from_secs(10));
+        let version = db.get_latest_version().unwrap();
+        thread::sleep(Duration::from_secs(1));
+        assert_eq!(db.get_latest_version().unwrap(), version);
+    }
+
+    #[test]
+    fn test_preflight_failures_are_returned() {
+        let dir = TempPath::new();
+        let mut config = NodeConfig::default_for_validator();
+        config.set_data_dir(dir.path().to_path_buf());
+        let failures = match start_node(&config) {
+            Err(StartNodeError::Preflight(failures)) => failures,
+            Err(error) => panic!("Unexpected error: {}", error),
+            Ok(_) => panic!("A config without waypoint must not start"),
+        };
+        assert!(failures.iter().any(|failure| failure.check == "waypoint"));
+    }
+}
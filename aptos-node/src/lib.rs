@@ -1,7 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api::runtime::bootstrap as bootstrap_api;
+use anyhow::{bail, Context};
+use aptos_api::runtime::{bootstrap as bootstrap_api, ApiRuntime};
 use aptos_config::{
     config::{
         AptosDataClientConfig, DataStreamingServiceConfig, NetworkConfig, NodeConfig,
@@ -13,7 +14,8 @@ use aptos_config::{
 use aptos_data_client::aptosnet::AptosNetDataClient;
 use aptos_infallible::RwLock;
 use aptos_logger::{prelude::*, Logger};
-use aptos_metrics::{get_public_json_metrics, metric_server};
+use aptos_mempool::{DeadLetterQueue, MempoolClientSender};
+use aptos_metrics::{get_public_json_metrics, metric_pusher, metric_server};
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_telemetry::{
     constants::{
@@ -24,12 +26,17 @@ use aptos_telemetry::{
 };
 use aptos_time_service::TimeService;
 use aptos_types::{
-    account_config::aptos_root_address, account_view::AccountView, chain_id::ChainId,
-    move_resource::MoveStorage, on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint,
+    account_config::aptos_root_address,
+    account_view::AccountView,
+    chain_id::ChainId,
+    move_resource::MoveStorage,
+    on_chain_config::{ON_CHAIN_CONFIG_REGISTRY, OPTIONAL_ON_CHAIN_CONFIGS},
+    waypoint::Waypoint,
 };
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
 use backup_service::start_backup_service;
+use config_notifications::ConfigNotificationService;
 use consensus::consensus_provider::start_consensus;
 use consensus_notifications::ConsensusNotificationListener;
 use data_streaming_service::{
@@ -53,12 +60,9 @@ use std::{
     io::Write,
     net::ToSocketAddrs,
     path::PathBuf,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
+    sync::Arc,
     thread,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use storage_interface::{state_view::DbStateViewAtVersion, DbReaderWriter};
 use storage_service::start_storage_service_with_db;
@@ -69,22 +73,33 @@ use storage_service_server::{
 use tokio::runtime::{Builder, Runtime};
 use tokio_stream::wrappers::IntervalStream;
 
+mod config_watcher;
+mod embedded;
+pub mod preflight;
+mod shutdown;
+
+pub use embedded::{start_node, NodeHandle, StartNodeError};
+
 const AC_SMP_CHANNEL_BUFFER_SIZE: usize = 1_024;
 const INTRA_NODE_CHANNEL_BUFFER_SIZE: usize = 1;
 const MEMPOOL_NETWORK_CHANNEL_BUFFER_SIZE: usize = 1_024;
 
 pub struct AptosHandle {
-    _api: Runtime,
+    api: ApiRuntime,
     _backup: Runtime,
-    _consensus_runtime: Option<Runtime>,
+    consensus_runtime: Option<Runtime>,
+    db: Arc<AptosDB>,
     _debug: NodeDebugService,
-    _mempool: Runtime,
+    mempool: Runtime,
+    mempool_client: MempoolClientSender,
     _network_runtimes: Vec<Runtime>,
     _state_sync_runtimes: StateSyncRuntimes,
     _telemetry_runtime: Runtime,
 }
 
-pub fn start(config: &NodeConfig, log_file: Option<PathBuf>) {
+/// Starts the node and runs it until it receives SIGTERM or SIGINT. If `config_path` is set, the
+/// config is reloaded from it on SIGHUP or when the file changes, see `config_watcher`.
+pub fn start(config: &NodeConfig, config_path: Option<PathBuf>, log_file: Option<PathBuf>) {
     crash_handler::setup_panic_handler();
 
     let mut logger = aptos_logger::Logger::new();
@@ -104,23 +119,44 @@ pub fn start(config: &NodeConfig, log_file: Option<PathBuf>) {
     // Let's now log some important information, since the logger is set up
     info!(config = config, "Loaded AptosNode config");
 
-    if fail::has_failpoints() {
+    if aptos_failpoints::is_enabled() {
         warn!("Failpoints is enabled");
         if let Some(failpoints) = &config.failpoints {
-            for (point, actions) in failpoints {
-                fail::cfg(point, actions).expect("fail to set actions for failpoint");
-            }
+            aptos_failpoints::configure(failpoints).expect("fail to set actions for failpoint");
         }
     } else if config.failpoints.is_some() {
         warn!("failpoints is set in config, but the binary doesn't compile with this feature");
     }
 
-    let _node_handle = setup_environment(config, logger);
-    let term = Arc::new(AtomicBool::new(false));
-
-    while !term.load(Ordering::Acquire) {
-        std::thread::park();
+    let preflight_report = preflight::run_preflight_checks(config);
+    for warning in &preflight_report.warnings {
+        warn!(
+            check = warning.check,
+            "Preflight check failed: {}", warning.message
+        );
+        eprintln!("Preflight check failed: {}", warning);
+    }
+    if !preflight_report.failures.is_empty() {
+        for failure in &preflight_report.failures {
+            error!(
+                check = failure.check,
+                "Preflight check failed: {}", failure.message
+            );
+            eprintln!("Preflight check failed: {}", failure);
+        }
+        eprintln!("The node cannot start, fix the issues above and restart it");
+        aptos_logger::flush();
+        std::process::exit(1);
     }
+
+    let mut config_notifications = ConfigNotificationService::new(config.clone());
+    let node_handle = setup_environment(config, logger.clone(), &mut config_notifications)
+        .expect("Failed to start the node");
+    let _config_watcher = config_path.map(|config_path| {
+        config_watcher::start_config_watcher(config_path, config_notifications, logger)
+    });
+    shutdown::wait_for_termination_signal();
+    node_handle.shutdown(config);
 }
 
 pub fn load_test_environment<R>(
@@ -169,6 +205,8 @@ pub fn load_test_environment<R>(
         if lazy {
             template.consensus.mempool_poll_count = u64::MAX;
         }
+        // a local testnet runs on whatever machine it is started on
+        template.preflight.check_resources = false;
 
         let builder = aptos_genesis_tool::validator_builder::ValidatorBuilder::new(
             &config_path,
@@ -214,27 +252,32 @@ pub fn load_test_environment<R>(
 
     println!("\nAptos is running, press ctrl-c to exit\n");
 
-    start(&config, Some(log_file))
+    start(&config, Some(validator_config_path), Some(log_file))
 }
 
 // Fetch chain ID from on-chain resource
-fn fetch_chain_id(db: &DbReaderWriter) -> ChainId {
+fn fetch_chain_id(db: &DbReaderWriter) -> anyhow::Result<ChainId> {
     let synced_version = (&*db.reader)
         .fetch_synced_version()
-        .expect("[aptos-node] failed fetching synced version.");
+        .context("[aptos-node] failed fetching synced version")?;
     let db_state_view = db
         .reader
         .state_view_at_version(Some(synced_version))
-        .expect("[aptos-node] failed to create db state view");
-    db_state_view
+        .context("[aptos-node] failed to create db state view")?;
+    Ok(db_state_view
         .as_account_with_state_view(&aptos_root_address())
         .get_chain_id_resource()
-        .expect("[aptos-node] failed to get chain ID resource")
-        .expect("[aptos-node] missing chain ID resource")
-        .chain_id()
+        .context("[aptos-node] failed to get chain ID resource")?
+        .context("[aptos-node] missing chain ID resource")?
+        .chain_id())
 }
 
-fn setup_debug_interface(config: &NodeConfig, logger: Option<Arc<Logger>>) -> NodeDebugService {
+fn setup_debug_interface(
+    config: &NodeConfig,
+    logger: Option<Arc<Logger>>,
+    dead_letters: DeadLetterQueue,
+    mempool_client: MempoolClientSender,
+) -> NodeDebugService {
     let addr = format!(
         "{}:{}",
         config.debug_interface.address, config.debug_interface.admission_control_node_debug_port,
@@ -244,7 +287,7 @@ fn setup_debug_interface(config: &NodeConfig, logger: Option<Arc<Logger>>) -> No
     .next()
     .unwrap();
 
-    NodeDebugService::new(addr, logger, config)
+    NodeDebugService::new(addr, logger, config, dead_letters, mempool_client)
 }
 
 fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
@@ -414,7 +457,14 @@ async fn periodic_telemetry_dump(node_config: NodeConfig, db: DbReaderWriter) {
                 }
 
                 // get some data we do not currently have metrics for
-                let chain_id = fetch_chain_id(&db).id(); // get the chain_id as its u8 id for consistency of schema
+                // get the chain_id as its u8 id for consistency of schema
+                let chain_id = match fetch_chain_id(&db) {
+                    Ok(chain_id) => chain_id.id(),
+                    Err(error) => {
+                        warn!(error = ?error, "unable to fetch the chain id");
+                        continue;
+                    }
+                };
                 let peer_id = match node_config.peer_id() {
                     Some(p) => p.to_string(),
                     None => String::new()
@@ -452,7 +502,13 @@ async fn periodic_state_dump(node_config: NodeConfig, db: DbReaderWriter) {
                 info!(config = node_config, args = args, "config and command line arguments");
             }
             _ = version_interval.select_next_some() => {
-                let chain_id = fetch_chain_id(&db);
+                let chain_id = match fetch_chain_id(&db) {
+                    Ok(chain_id) => chain_id,
+                    Err(error) => {
+                        warn!(error = ?error, "unable to fetch the chain id");
+                        continue;
+                    }
+                };
                 let ledger_info = if let Ok(ledger_info) = db.reader.get_latest_ledger_info() {
                     ledger_info
                 } else {
@@ -474,8 +530,19 @@ async fn periodic_state_dump(node_config: NodeConfig, db: DbReaderWriter) {
     }
 }
 
-pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>) -> AptosHandle {
-    let debug_if = setup_debug_interface(node_config, logger);
+pub fn setup_environment(
+    node_config: &NodeConfig,
+    logger: Option<Arc<Logger>>,
+    config_notifications: &mut ConfigNotificationService,
+) -> anyhow::Result<AptosHandle> {
+    let dead_letters = DeadLetterQueue::new(node_config.mempool.dead_letter_queue_capacity);
+    let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
+    let debug_if = setup_debug_interface(
+        node_config,
+        logger,
+        dead_letters.clone(),
+        mp_client_sender.clone(),
+    );
 
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
@@ -488,13 +555,14 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
 
     let mut instant = Instant::now();
     let (aptos_db, db_rw) = DbReaderWriter::wrap(
-        AptosDB::open(
+        AptosDB::open_with_cold_store(
             &node_config.storage.dir(),
+            node_config.storage.cold_storage_dir.as_deref(),
             false, /* readonly */
             node_config.storage.storage_pruner_config,
             node_config.storage.rocksdb_config,
         )
-        .expect("DB should open."),
+        .with_context(|| format!("Failed to open the DB at {:?}", node_config.storage.dir()))?,
     );
     let _simple_storage_service = start_storage_service_with_db(node_config, Arc::clone(&aptos_db));
     let backup_service = start_backup_service(
@@ -506,18 +574,30 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
     // if there's genesis txn and waypoint, commit it if the result matches.
     if let Some(genesis) = get_genesis_txn(node_config) {
         maybe_bootstrap::<AptosVM>(&db_rw, genesis, genesis_waypoint)
-            .expect("Db-bootstrapper should not fail.");
+            .context("Failed to bootstrap the DB with the genesis transaction")?;
     } else {
         info!("Genesis txn not provided, it's fine if you don't expect to apply it otherwise please double check config");
     }
     AptosVM::set_concurrency_level_once(node_config.execution.concurrency_level as usize);
+    AptosVM::set_state_view_profiling_once(node_config.execution.profile_state_view);
 
     debug!(
         "Storage service started in {} ms",
         instant.elapsed().as_millis()
     );
 
-    let chain_id = fetch_chain_id(&db_rw);
+    let chain_id = fetch_chain_id(&db_rw)?;
+    if let Some(metrics_push) = &node_config.debug_interface.metrics_push {
+        let mut labels: Vec<_> = metrics_push.labels.clone().into_iter().collect();
+        labels.push(("role".to_string(), node_config.base.role.to_string()));
+        labels.push(("chain".to_string(), chain_id.to_string()));
+        metric_pusher::start_push_client(
+            metrics_push.gateway_url.clone(),
+            Duration::from_millis(metrics_push.push_interval_ms),
+            metrics_push.job.clone(),
+            labels,
+        );
+    }
     let mut network_runtimes = vec![];
     let mut state_sync_network_handles = vec![];
     let mut mempool_network_handles = vec![];
@@ -527,19 +607,19 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
 
     // Create an event subscription service so that components can be notified of events and reconfigs
     let mut event_subscription_service = EventSubscriptionService::new(
-        ON_CHAIN_CONFIG_REGISTRY,
+        &[ON_CHAIN_CONFIG_REGISTRY, OPTIONAL_ON_CHAIN_CONFIGS].concat(),
         Arc::new(RwLock::new(db_rw.clone())),
     );
     let mempool_reconfig_subscription = event_subscription_service
         .subscribe_to_reconfigurations()
-        .unwrap();
+        .context("Failed to subscribe mempool to reconfigurations")?;
 
     // Create a consensus subscription for reconfiguration events (if this node is a validator).
     let consensus_reconfig_subscription = if node_config.base.role.is_validator() {
         Some(
             event_subscription_service
                 .subscribe_to_reconfigurations()
-                .unwrap(),
+                .context("Failed to subscribe consensus to reconfigurations")?,
         )
     } else {
         None
@@ -553,17 +633,16 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
 
     // Instantiate every network and collect the requisite endpoints for state_sync, mempool, and consensus.
     let mut network_ids = HashSet::new();
-    network_configs.iter().for_each(|config| {
+    for config in &network_configs {
         let network_id = config.network_id;
         // Guarantee there is only one of this network
-        if network_ids.contains(&network_id) {
-            panic!(
+        if !network_ids.insert(network_id) {
+            bail!(
                 "Duplicate NetworkId: '{}'.  Can't start node with duplicate networks",
                 network_id
             );
         }
-        network_ids.insert(network_id);
-    });
+    }
     let network_ids: Vec<_> = network_ids.into_iter().collect();
 
     let peer_metadata_storage = PeerMetadataStorage::new(&network_ids);
@@ -573,7 +652,12 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
             .thread_name(format!("network-{}", network_config.network_id))
             .enable_all()
             .build()
-            .expect("Failed to start runtime. Won't be able to start networking.");
+            .with_context(|| {
+                format!(
+                    "Failed to start the runtime of the {} network",
+                    network_config.network_id
+                )
+            })?;
 
         // Entering here gives us a runtime to instantiate all the pieces of the builder
         let _enter = runtime.enter();
@@ -619,9 +703,8 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         // Perform steps relevant specifically to Validator networks.
         if network_id.is_validator_network() {
             // A valid config is allowed to have at most one ValidatorNetwork
-            // TODO:  `expect_none` would be perfect here, once it is stable.
             if consensus_network_handles.is_some() {
-                panic!("There can be at most one validator network!");
+                bail!("There can be at most one validator network!");
             }
 
             consensus_network_handles = Some(
@@ -630,6 +713,11 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
             );
         }
 
+        // Seeds can be updated by reloading the config.
+        network_builder.add_config_update_listener(
+            config_notifications.subscribe_to_network_updates(network_id),
+        );
+
         let network_context = network_builder.network_context();
         network_builder.build(runtime.handle().clone());
         network_builder.start();
@@ -662,9 +750,13 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         db_rw.clone(),
     );
 
-    let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
-
-    let api_runtime = bootstrap_api(node_config, chain_id, aptos_db, mp_client_sender).unwrap();
+    let api_runtime = bootstrap_api(
+        node_config,
+        chain_id,
+        aptos_db.clone(),
+        mp_client_sender.clone(),
+        config_notifications.subscribe_to_api_updates(),
+    )?;
 
     let mut consensus_runtime = None;
     let (consensus_to_mempool_sender, consensus_requests) = channel(INTRA_NODE_CHANNEL_BUFFER_SIZE);
@@ -679,6 +771,8 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         mempool_listener,
         mempool_reconfig_subscription,
         peer_metadata_storage.clone(),
+        config_notifications.subscribe_to_mempool_updates(),
+        dead_letters,
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
@@ -720,20 +814,22 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         .thread_name("aptos-telemetry")
         .enable_all()
         .build()
-        .expect("Failed to create aptos telemetry runtime!");
+        .context("Failed to create aptos telemetry runtime")?;
 
     telemery_runtime
         .handle()
         .spawn(periodic_telemetry_dump(node_config.to_owned(), db_rw));
 
-    AptosHandle {
-        _api: api_runtime,
+    Ok(AptosHandle {
+        api: api_runtime,
         _backup: backup_service,
-        _consensus_runtime: consensus_runtime,
+        consensus_runtime,
+        db: aptos_db,
         _debug: debug_if,
-        _mempool: mempool,
+        mempool,
+        mempool_client: mp_client_sender,
         _network_runtimes: network_runtimes,
         _state_sync_runtimes: state_sync_runtimes,
         _telemetry_runtime: telemery_runtime,
-    }
+    })
 }
@@ -72,8 +72,9 @@ fn main() {
             rng,
         );
     } else {
-        let config = NodeConfig::load(args.config.unwrap()).expect("Failed to load node config");
+        let config_path = args.config.unwrap();
+        let config = NodeConfig::load(&config_path).expect("Failed to load node config");
         println!("Using node config {:?}", &config);
-        aptos_node::start(&config, None);
+        aptos_node::start(&config, Some(config_path), None);
     };
 }
@@ -0,0 +1,445 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks run at startup, before the node opens its DB and starts its services, so that a node
+//! which cannot run fails right away with what to fix, rather than panicking minutes later in
+//! whichever component first hits the problem.
+
+use aptos_config::config::{NodeConfig, WaypointConfig};
+use aptos_types::network_address::parse_ip_tcp;
+use aptosdb::AptosDB;
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    net::{IpAddr, Ipv4Addr},
+    path::{Path, PathBuf},
+};
+use sysinfo::{DiskExt, RefreshKind, System, SystemExt};
+
+/// A failed check, and what to do about it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreflightFailure {
+    pub check: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for PreflightFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.check, self.message)
+    }
+}
+
+impl PreflightFailure {
+    fn new(check: &'static str, message: String) -> Self {
+        Self { check, message }
+    }
+}
+
+/// The checks which failed. The node does not start if any check fails, and only warns about
+/// the short resources of the host unless `preflight.enforce_resources` is set.
+#[derive(Debug, Default)]
+pub struct PreflightReport {
+    pub failures: Vec<PreflightFailure>,
+    pub warnings: Vec<PreflightFailure>,
+}
+
+/// Runs all the checks.
+pub fn run_preflight_checks(node_config: &NodeConfig) -> PreflightReport {
+    let mut failures = check_config(node_config);
+    failures.extend(check_storage_dirs(node_config));
+    failures.extend(check_schema_version(node_config));
+    let mut report = PreflightReport {
+        failures,
+        warnings: vec![],
+    };
+    if node_config.preflight.check_resources {
+        let mut resources: Vec<_> = check_disk_space(node_config).into_iter().collect();
+        resources.extend(check_open_files_limit(node_config));
+        if node_config.preflight.enforce_resources {
+            report.failures.extend(resources);
+        } else {
+            report.warnings.extend(resources);
+        }
+    }
+    report
+}
+
+/// Checks that the services enabled by the config are consistent with the role of the node, and
+/// with each other.
+fn check_config(node_config: &NodeConfig) -> Vec<PreflightFailure> {
+    let mut failures = vec![];
+    if node_config.base.role.is_validator() {
+        if node_config.validator_network.is_none() {
+            failures.push(PreflightFailure::new(
+                "role",
+                "a validator needs a validator_network".into(),
+            ));
+        }
+    } else {
+        if node_config.validator_network.is_some() {
+            failures.push(PreflightFailure::new(
+                "role",
+                "a full node must not have a validator_network, remove it or set base.role to validator".into(),
+            ));
+        }
+        if node_config.full_node_networks.is_empty() {
+            failures.push(PreflightFailure::new(
+                "role",
+                "a full node needs at least one network in full_node_networks to sync from".into(),
+            ));
+        }
+    }
+    if matches!(node_config.base.waypoint, WaypointConfig::None) {
+        failures.push(PreflightFailure::new(
+            "waypoint",
+            "base.waypoint must be set, e.g. from_file pointing to the waypoint of the genesis"
+                .into(),
+        ));
+    }
+
+    let mut listeners: HashMap<u16, Vec<(&'static str, IpAddr)>> = HashMap::new();
+    let mut listen = |name, ip, port| {
+        // port 0 is picked by the OS
+        if port != 0 {
+            listeners.entry(port).or_default().push((name, ip));
+        }
+    };
+    if node_config.api.enabled {
+        listen(
+            "api.address",
+            node_config.api.address.ip(),
+            node_config.api.address.port(),
+        );
+    }
+    listen(
+        "storage.address",
+        node_config.storage.address.ip(),
+        node_config.storage.address.port(),
+    );
+    listen(
+        "storage.backup_service_address",
+        node_config.storage.backup_service_address.ip(),
+        node_config.storage.backup_service_address.port(),
+    );
+    let debug_interface = &node_config.debug_interface;
+    let debug_ip = debug_interface
+        .address
+        .parse()
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+    listen(
+        "debug_interface.metrics_server_port",
+        debug_ip,
+        debug_interface.metrics_server_port,
+    );
+    listen(
+        "debug_interface.public_metrics_server_port",
+        debug_ip,
+        debug_interface.public_metrics_server_port,
+    );
+    listen(
+        "debug_interface.admission_control_node_debug_port",
+        debug_ip,
+        debug_interface.admission_control_node_debug_port,
+    );
+    for network in node_config
+        .validator_network
+        .iter()
+        .chain(&node_config.full_node_networks)
+    {
+        if let Some(((ip, port), _)) = parse_ip_tcp(network.listen_address.as_slice()) {
+            let name = if network.network_id.is_validator_network() {
+                "validator_network.listen_address"
+            } else {
+                "full_node_networks.listen_address"
+            };
+            listen(name, ip, port);
+        }
+    }
+    let mut ports: Vec<_> = listeners.into_iter().collect();
+    ports.sort_by_key(|(port, _)| *port);
+    for (port, listeners) in ports {
+        for (i, (name, ip)) in listeners.iter().enumerate() {
+            let conflict = listeners[..i].iter().find(|(_, other_ip)| {
+                ip == other_ip || ip.is_unspecified() || other_ip.is_unspecified()
+            });
+            if let Some((other_name, _)) = conflict {
+                failures.push(PreflightFailure::new(
+                    "ports",
+                    format!(
+                        "{} and {} both listen on port {}, change one of them",
+                        other_name, name, port
+                    ),
+                ));
+            }
+        }
+    }
+    failures
+}
+
+/// Checks that the directories of the DB can be written to.
+fn check_storage_dirs(node_config: &NodeConfig) -> Vec<PreflightFailure> {
+    let mut failures = vec![];
+    let dir = node_config.storage.dir();
+    if let Some(cold_storage_dir) = &node_config.storage.cold_storage_dir {
+        if cold_storage_dir == &dir {
+            failures.push(PreflightFailure::new(
+                "storage",
+                format!(
+                    "storage.cold_storage_dir is the DB directory {}, use a separate directory",
+                    dir.display()
+                ),
+            ));
+        }
+        failures.extend(check_writable("storage", cold_storage_dir));
+    }
+    failures.extend(check_writable("storage", &dir));
+    failures
+}
+
+/// Checks that the existing DB, if any, is not at a schema version newer than the one of the node,
+/// e.g. after a downgrade. Older schemas are migrated when the DB is opened.
+fn check_schema_version(node_config: &NodeConfig) -> Option<PreflightFailure> {
+    AptosDB::check_schema_version(node_config.storage.dir())
+        .err()
+        .map(|error| PreflightFailure::new("schema_version", error.to_string()))
+}
+
+fn check_writable(check: &'static str, dir: &Path) -> Option<PreflightFailure> {
+    let probe = dir.join(".preflight");
+    let result = fs::create_dir_all(dir)
+        .and_then(|_| fs::write(&probe, b""))
+        .and_then(|_| fs::remove_file(&probe));
+    result.err().map(|error| {
+        PreflightFailure::new(
+            check,
+            format!(
+                "cannot write to {}: {}, check that it exists and is owned by the user running the node",
+                dir.display(),
+                error
+            ),
+        )
+    })
+}
+
+/// Checks the free space of the disk holding the DB.
+fn check_disk_space(node_config: &NodeConfig) -> Option<PreflightFailure> {
+    let dir = existing_ancestor(&node_config.storage.dir())?;
+    let system = System::new_with_specifics(RefreshKind::new().with_disks_list());
+    let disk = system
+        .disks()
+        .iter()
+        .filter(|disk| dir.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count())?;
+    let required = node_config.preflight.min_free_disk_space_bytes;
+    (disk.available_space() < required).then(|| {
+        PreflightFailure::new(
+            "disk_space",
+            format!(
+                "{} has {} bytes free on {}, at least {} are required (preflight.min_free_disk_space_bytes)",
+                dir.display(),
+                disk.available_space(),
+                disk.mount_point().display(),
+                required
+            ),
+        )
+    })
+}
+
+/// The canonical path of the closest ancestor of `path` which exists, as the DB directory may not
+/// be created yet.
+fn existing_ancestor(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find_map(|ancestor| ancestor.canonicalize().ok())
+}
+
+/// Checks the soft limit on open files of the process. Only available on Linux.
+fn check_open_files_limit(node_config: &NodeConfig) -> Vec<PreflightFailure> {
+    fs::read_to_string("/proc/self/limits")
+        .ok()
+        .and_then(|limits| parse_open_files_limit(&limits))
+        .map_or_else(Vec::new, |limit| check_open_files(node_config, limit))
+}
+
+/// Checks the open files limit `limit` against the config: it must be at least the required one,
+/// and leave room for the sockets of the node on top of the files of RocksDB.
+fn check_open_files(node_config: &NodeConfig, limit: u64) -> Vec<PreflightFailure> {
+    let mut failures = vec![];
+    let required = node_config.preflight.min_open_files_limit;
+    if limit < required {
+        failures.push(PreflightFailure::new(
+            "open_files",
+            format!(
+                "the open files limit is {}, at least {} is required (preflight.min_open_files_limit), raise it with `ulimit -n` or LimitNOFILE",
+                limit, required
+            ),
+        ));
+    }
+    // RocksDB keeps every file open when `max_open_files` is -1.
+    let max_open_files = node_config.storage.rocksdb_config.max_open_files;
+    if max_open_files < 0 && limit != u64::MAX {
+        failures.push(PreflightFailure::new(
+            "open_files",
+            format!(
+                "storage.rocksdb_config.max_open_files is {} (unlimited), which needs an unlimited open files limit, but it is {}, set a limit below it",
+                max_open_files, limit
+            ),
+        ));
+    } else if max_open_files >= 0 && max_open_files as u64 >= limit {
+        failures.push(PreflightFailure::new(
+            "open_files",
+            format!(
+                "storage.rocksdb_config.max_open_files ({}) leaves no room for the sockets of the node under the open files limit ({}), lower it",
+                max_open_files, limit
+            ),
+        ));
+    }
+    failures
+}
+
+/// Parses the soft limit on open files out of `/proc/<pid>/limits`.
+fn parse_open_files_limit(limits: &str) -> Option<u64> {
+    let line = limits
+        .lines()
+        .find(|line| line.starts_with("Max open files"))?;
+    match line["Max open files".len()..].split_whitespace().next()? {
+        "unlimited" => Some(u64::MAX),
+        soft => soft.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_config::config::{RocksdbConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+    use aptos_temppath::TempPath;
+    use aptos_types::waypoint::Waypoint;
+
+    fn validator_config() -> NodeConfig {
+        let mut config = NodeConfig::default_for_validator();
+        config.base.waypoint = WaypointConfig::FromConfig(Waypoint::default());
+        config
+    }
+
+    fn checks(failures: &[PreflightFailure]) -> Vec<&'static str> {
+        failures.iter().map(|failure| failure.check).collect()
+    }
+
+    #[test]
+    fn test_default_configs_pass() {
+        assert_eq!(check_config(&validator_config()), vec![]);
+
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.base.waypoint = WaypointConfig::FromConfig(Waypoint::default());
+        assert_eq!(check_config(&config), vec![]);
+    }
+
+    #[test]
+    fn test_role_mismatch() {
+        let mut config = validator_config();
+        config.validator_network = None;
+        assert_eq!(checks(&check_config(&config)), vec!["role"]);
+
+        let mut config = NodeConfig::default_for_public_full_node();
+        config.base.waypoint = WaypointConfig::FromConfig(Waypoint::default());
+        config.full_node_networks.clear();
+        assert_eq!(checks(&check_config(&config)), vec!["role"]);
+    }
+
+    #[test]
+    fn test_missing_waypoint() {
+        let mut config = validator_config();
+        config.base.waypoint = WaypointConfig::None;
+        assert_eq!(checks(&check_config(&config)), vec!["waypoint"]);
+    }
+
+    #[test]
+    fn test_open_files() {
+        let mut config = validator_config();
+        config.storage.rocksdb_config.max_open_files = 5000;
+        let required = config.preflight.min_open_files_limit;
+        assert_eq!(check_open_files(&config, required), vec![]);
+        assert_eq!(
+            checks(&check_open_files(&config, required - 1)),
+            vec!["open_files"]
+        );
+
+        config.storage.rocksdb_config.max_open_files = required as i32;
+        assert_eq!(
+            checks(&check_open_files(&config, required)),
+            vec!["open_files"]
+        );
+
+        // -1 is unlimited, which is fine with an unlimited limit only
+        config.storage.rocksdb_config.max_open_files = -1;
+        assert_eq!(check_open_files(&config, u64::MAX), vec![]);
+        assert_eq!(
+            checks(&check_open_files(&config, required)),
+            vec!["open_files"]
+        );
+        // the config alone never fails on it
+        assert_eq!(check_config(&config), vec![]);
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let dir = TempPath::new();
+        let mut config = validator_config();
+        config.set_data_dir(dir.path().to_path_buf());
+        // no DB yet
+        assert_eq!(check_schema_version(&config), None);
+
+        drop(
+            AptosDB::open(
+                &config.storage.dir(),
+                false, /* readonly */
+                NO_OP_STORAGE_PRUNER_CONFIG,
+                RocksdbConfig::default(),
+            )
+            .unwrap(),
+        );
+        assert_eq!(check_schema_version(&config), None);
+    }
+
+    #[test]
+    fn test_port_collision() {
+        let mut config = validator_config();
+        config.storage.backup_service_address = config.api.address;
+        let failures = check_config(&config);
+        assert_eq!(checks(&failures), vec!["ports"]);
+        assert!(failures[0].message.contains("api.address"));
+        assert!(failures[0]
+            .message
+            .contains("storage.backup_service_address"));
+
+        // the API does not listen when disabled
+        config.api.enabled = false;
+        assert_eq!(check_config(&config), vec![]);
+    }
+
+    #[test]
+    fn test_storage_dirs() {
+        let dir = TempPath::new();
+        let mut config = validator_config();
+        config.set_data_dir(dir.path().to_path_buf());
+        assert_eq!(check_storage_dirs(&config), vec![]);
+
+        config.storage.cold_storage_dir = Some(config.storage.dir());
+        assert_eq!(checks(&check_storage_dirs(&config)), vec!["storage"]);
+    }
+
+    #[test]
+    fn test_parse_open_files_limit() {
+        let limits = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 1048576              files
+Max locked memory         8388608              8388608              bytes
+";
+        assert_eq!(parse_open_files_limit(limits), Some(1024));
+        assert_eq!(
+            parse_open_files_limit(&limits.replace("1024 ", "unlimited ")),
+            Some(u64::MAX)
+        );
+        assert_eq!(parse_open_files_limit("Limit Soft Limit"), None);
+    }
+}
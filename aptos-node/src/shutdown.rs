@@ -0,0 +1,149 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AptosHandle;
+use aptos_config::config::NodeConfig;
+use aptos_logger::prelude::*;
+use std::time::{Duration, Instant};
+use tokio::{
+    runtime::Builder,
+    signal::unix::{signal, SignalKind},
+};
+
+/// Blocks until the process receives SIGTERM or SIGINT.
+pub fn wait_for_termination_signal() {
+    let runtime = Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("Failed to create signal runtime");
+    runtime.block_on(async {
+        let mut sigterm = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+        tokio::select! {
+            _ = sigterm.recv() => info!("Received SIGTERM"),
+            _ = tokio::signal::ctrl_c() => info!("Received SIGINT"),
+        }
+    });
+}
+
+/// A step of the node shutdown, run with the shutdown deadline.
+pub(crate) struct ShutdownStep {
+    pub(crate) name: &'static str,
+    run: Box<dyn FnOnce(Instant)>,
+}
+
+impl ShutdownStep {
+    fn new(name: &'static str, run: impl FnOnce(Instant) + 'static) -> Self {
+        Self {
+            name,
+            run: Box::new(run),
+        }
+    }
+}
+
+/// Runs `steps` in order, each only getting what is left until `deadline`.
+pub(crate) fn run_shutdown_steps(steps: Vec<ShutdownStep>, deadline: Instant) {
+    for step in steps {
+        (step.run)(deadline);
+        info!("Shutdown step done: {}", step.name);
+    }
+}
+
+fn remaining(deadline: Instant) -> Duration {
+    deadline.saturating_duration_since(Instant::now())
+}
+
+impl AptosHandle {
+    /// Shuts the node down within `base.shutdown_deadline_ms`, see `shutdown_steps`.
+    pub fn shutdown(self, node_config: &NodeConfig) {
+        let deadline_ms = node_config.base.shutdown_deadline_ms;
+        let deadline = Instant::now() + Duration::from_millis(deadline_ms);
+        info!(deadline_ms = deadline_ms, "Shutting down");
+
+        run_shutdown_steps(self.shutdown_steps(node_config), deadline);
+
+        if remaining(deadline).is_zero() {
+            warn!(
+                deadline_ms = deadline_ms,
+                "Shutdown deadline passed, some components may have been dropped abruptly"
+            );
+        }
+    }
+
+    /// The steps of the shutdown, in order:
+    ///   1. stop accepting API requests and let the ones in flight complete,
+    ///   2. give mempool one broadcast round to forward the last submitted transactions,
+    ///   3. stop consensus and then state sync, the components writing to storage,
+    ///   4. flush storage, which nothing writes to anymore,
+    ///   5. drop the other components.
+    pub(crate) fn shutdown_steps(self, node_config: &NodeConfig) -> Vec<ShutdownStep> {
+        let AptosHandle {
+            api,
+            _backup: backup,
+            consensus_runtime,
+            db,
+            _debug: debug,
+            mempool,
+            mempool_client,
+            _network_runtimes: network_runtimes,
+            _state_sync_runtimes: state_sync_runtimes,
+            _telemetry_runtime: telemetry_runtime,
+        } = self;
+        let broadcast_interval =
+            Duration::from_millis(node_config.mempool.shared_mempool_tick_interval_ms);
+
+        vec![
+            // Dropping the API drops its mempool client, so no transaction comes in anymore.
+            ShutdownStep::new("api", move |deadline| {
+                api.shutdown(remaining(deadline));
+                drop(mempool_client);
+            }),
+            ShutdownStep::new("mempool", move |deadline| {
+                std::thread::sleep(broadcast_interval.min(remaining(deadline)));
+                mempool.shutdown_timeout(remaining(deadline));
+            }),
+            ShutdownStep::new("consensus", move |deadline| {
+                if let Some(consensus_runtime) = consensus_runtime {
+                    consensus_runtime.shutdown_timeout(remaining(deadline));
+                }
+            }),
+            ShutdownStep::new("state sync", move |_| drop(state_sync_runtimes)),
+            ShutdownStep::new("storage", move |_| match db.flush() {
+                Ok(()) => info!("Storage flushed"),
+                Err(e) => error!(error = ?e, "Failed to flush storage"),
+            }),
+            ShutdownStep::new("other components", move |_| {
+                drop((backup, debug, network_runtimes, telemetry_runtime))
+            }),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[test]
+    fn test_run_shutdown_steps_in_order() {
+        let deadline = Instant::now() + Duration::from_secs(60);
+        let runs = Rc::new(RefCell::new(vec![]));
+        let steps = vec!["first", "second", "third"]
+            .into_iter()
+            .map(|name| {
+                let runs = runs.clone();
+                ShutdownStep::new(name, move |step_deadline| {
+                    runs.borrow_mut().push((name, step_deadline))
+                })
+            })
+            .collect();
+        run_shutdown_steps(steps, deadline);
+        assert_eq!(
+            *runs.borrow(),
+            vec![
+                ("first", deadline),
+                ("second", deadline),
+                ("third", deadline)
+            ]
+        );
+    }
+}
@@ -0,0 +1,314 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Notifications for node config changes that are applied without restarting the node.
+//!
+//! The node reloads its config file on SIGHUP or when the file is modified, and hands the new
+//! config to the [`ConfigNotificationService`]. Only the fields that are safe to change at runtime
+//! are taken from it:
+//!   * `api.content_length_limit`, the `api.max_*` limits, `api.gas_price_floor`,
+//!     `api.submission_batch_window_ms` and `api.disabled_endpoint_groups`,
+//!   * `logger.level`,
+//!   * `mempool.capacity` and `mempool.capacity_per_user`,
+//!   * `seeds` and `seed_addrs` of every network.
+//!
+//! Changes to any other field are ignored until the node restarts. Each subsystem subscribes to
+//! its section of the config and receives the whole section whenever a reloadable field of it
+//! changes.
+
+use aptos_config::{
+    config::{ApiConfig, LoggerConfig, MempoolConfig, NetworkConfig, NodeConfig},
+    network_id::NetworkId,
+};
+use aptos_logger::prelude::*;
+use channel::{aptos_channel, message_queues::QueueStyle};
+use futures::{stream::FusedStream, Stream};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+// Only the latest value of a section matters, so older updates are dropped when a subscriber
+// falls behind.
+const CONFIG_UPDATE_CHANNEL_SIZE: usize = 1;
+
+/// A subscription listener for updates of the API config.
+pub type ApiConfigListener = ConfigUpdateListener<ApiConfig>;
+
+/// A subscription listener for updates of the logger config.
+pub type LoggerConfigListener = ConfigUpdateListener<LoggerConfig>;
+
+/// A subscription listener for updates of the mempool config.
+pub type MempoolConfigListener = ConfigUpdateListener<MempoolConfig>;
+
+/// A subscription listener for updates of the config of one network.
+pub type NetworkConfigListener = ConfigUpdateListener<NetworkConfig>;
+
+/// The sections of the node config that can be updated at runtime.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ConfigSection {
+    Api,
+    Logger,
+    Mempool,
+    Network(NetworkId),
+}
+
+/// The service that applies reloaded configs and notifies the subscribers of each section.
+pub struct ConfigNotificationService {
+    config: NodeConfig,
+    api_subscribers: Subscribers<ApiConfig>,
+    logger_subscribers: Subscribers<LoggerConfig>,
+    mempool_subscribers: Subscribers<MempoolConfig>,
+    network_subscribers: HashMap<NetworkId, Subscribers<NetworkConfig>>,
+}
+
+impl ConfigNotificationService {
+    /// Creates the service for a node started with `config`.
+    pub fn new(config: NodeConfig) -> Self {
+        Self {
+            config,
+            api_subscribers: Subscribers::default(),
+            logger_subscribers: Subscribers::default(),
+            mempool_subscribers: Subscribers::default(),
+            network_subscribers: HashMap::new(),
+        }
+    }
+
+    /// Returns the config currently in effect: the initial config with the reloadable fields of
+    /// the last reloaded config.
+    pub fn config(&self) -> &NodeConfig {
+        &self.config
+    }
+
+    /// Returns a listener for updates of the API config.
+    pub fn subscribe_to_api_updates(&mut self) -> ApiConfigListener {
+        self.api_subscribers.subscribe()
+    }
+
+    /// Returns a listener for updates of the logger config.
+    pub fn subscribe_to_logger_updates(&mut self) -> LoggerConfigListener {
+        self.logger_subscribers.subscribe()
+    }
+
+    /// Returns a listener for updates of the mempool config.
+    pub fn subscribe_to_mempool_updates(&mut self) -> MempoolConfigListener {
+        self.mempool_subscribers.subscribe()
+    }
+
+    /// Returns a listener for updates of the config of the network `network_id`.
+    pub fn subscribe_to_network_updates(&mut self, network_id: NetworkId) -> NetworkConfigListener {
+        self.network_subscribers
+            .entry(network_id)
+            .or_default()
+            .subscribe()
+    }
+
+    /// Applies the reloadable fields of `new_config` and notifies the subscribers of every section
+    /// that changed. Returns the updated sections.
+    pub fn reload(&mut self, new_config: &NodeConfig) -> Vec<ConfigSection> {
+        let mut config = self.config.clone();
+        config.api.content_length_limit = new_config.api.content_length_limit;
+        config.api.max_transaction_payload_bytes = new_config.api.max_transaction_payload_bytes;
+        config.api.max_script_bytes = new_config.api.max_script_bytes;
+        config.api.max_transaction_arguments = new_config.api.max_transaction_arguments;
+        config.api.max_vector_argument_length = new_config.api.max_vector_argument_length;
+        config.api.max_account_resources_batch_size =
+            new_config.api.max_account_resources_batch_size;
+        config.api.gas_price_floor = new_config.api.gas_price_floor.clone();
+        config.api.submission_batch_window_ms = new_config.api.submission_batch_window_ms;
+        config.api.max_submission_batch_size = new_config.api.max_submission_batch_size;
+        config.api.max_transactions_page_size = new_config.api.max_transactions_page_size;
+        config.api.max_events_page_size = new_config.api.max_events_page_size;
+        config.api.max_page_size = new_config.api.max_page_size;
+        config.api.disabled_endpoint_groups = new_config.api.disabled_endpoint_groups.clone();
+        config.logger.level = new_config.logger.level;
+        config.mempool.capacity = new_config.mempool.capacity;
+        config.mempool.capacity_per_user = new_config.mempool.capacity_per_user;
+        for network in config
+            .validator_network
+            .iter_mut()
+            .chain(config.full_node_networks.iter_mut())
+        {
+            if let Some(new_network) = find_network(new_config, network.network_id) {
+                network.seeds = new_network.seeds.clone();
+                network.seed_addrs = new_network.seed_addrs.clone();
+            }
+        }
+        if &config != new_config {
+            warn!("The reloaded config changes fields that cannot be updated at runtime, these changes are ignored until the node restarts");
+        }
+
+        let mut updated = vec![];
+        if config.api != self.config.api {
+            self.api_subscribers.notify(&config.api);
+            updated.push(ConfigSection::Api);
+        }
+        if config.logger != self.config.logger {
+            self.logger_subscribers.notify(&config.logger);
+            updated.push(ConfigSection::Logger);
+        }
+        if config.mempool != self.config.mempool {
+            self.mempool_subscribers.notify(&config.mempool);
+            updated.push(ConfigSection::Mempool);
+        }
+        for network in networks(&config) {
+            if find_network(&self.config, network.network_id) != Some(network) {
+                if let Some(subscribers) = self.network_subscribers.get_mut(&network.network_id) {
+                    subscribers.notify(network);
+                }
+                updated.push(ConfigSection::Network(network.network_id));
+            }
+        }
+
+        self.config = config;
+        updated
+    }
+}
+
+fn networks(config: &NodeConfig) -> impl Iterator<Item = &NetworkConfig> {
+    config
+        .validator_network
+        .iter()
+        .chain(config.full_node_networks.iter())
+}
+
+fn find_network(config: &NodeConfig, network_id: NetworkId) -> Option<&NetworkConfig> {
+    networks(config).find(|network| network.network_id == network_id)
+}
+
+/// The subscribers to one section of the config.
+struct Subscribers<T> {
+    notification_senders: Vec<aptos_channel::Sender<(), T>>,
+}
+
+impl<T> Default for Subscribers<T> {
+    fn default() -> Self {
+        Self {
+            notification_senders: vec![],
+        }
+    }
+}
+
+impl<T: Clone> Subscribers<T> {
+    fn subscribe(&mut self) -> ConfigUpdateListener<T> {
+        let (notification_sender, notification_receiver) =
+            aptos_channel::new(QueueStyle::KLAST, CONFIG_UPDATE_CHANNEL_SIZE, None);
+        self.notification_senders.push(notification_sender);
+        ConfigUpdateListener {
+            notification_receiver,
+        }
+    }
+
+    /// Sends `section` to every subscriber. Subscribers that dropped their listener are removed.
+    fn notify(&mut self, section: &T) {
+        self.notification_senders
+            .retain(|sender| sender.push((), section.clone()).is_ok());
+    }
+}
+
+/// The component responsible for listening to updates of a config section.
+#[derive(Debug)]
+pub struct ConfigUpdateListener<T> {
+    notification_receiver: aptos_channel::Receiver<(), T>,
+}
+
+impl<T> Stream for ConfigUpdateListener<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().notification_receiver).poll_next(cx)
+    }
+}
+
+impl<T> FusedStream for ConfigUpdateListener<T> {
+    fn is_terminated(&self) -> bool {
+        self.notification_receiver.is_terminated()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{network_address::NetworkAddress, PeerId};
+    use futures::{FutureExt, StreamExt};
+
+    fn node_config() -> NodeConfig {
+        let mut config = NodeConfig::default();
+        config
+            .full_node_networks
+            .push(NetworkConfig::network_with_id(NetworkId::Public));
+        config
+    }
+
+    #[test]
+    fn test_reload_notifies_updated_sections() {
+        let config = node_config();
+        let mut service = ConfigNotificationService::new(config.clone());
+        let mut api_listener = service.subscribe_to_api_updates();
+        let mut logger_listener = service.subscribe_to_logger_updates();
+        let mut mempool_listener = service.subscribe_to_mempool_updates();
+
+        let mut new_config = config;
+        new_config.api.content_length_limit = Some(1024);
+        new_config.mempool.capacity = 10;
+        new_config.mempool.capacity_per_user = 1;
+        assert_eq!(
+            service.reload(&new_config),
+            vec![ConfigSection::Api, ConfigSection::Mempool]
+        );
+        assert_eq!(service.config(), &new_config);
+
+        let api_config = api_listener.next().now_or_never().unwrap().unwrap();
+        assert_eq!(api_config.content_length_limit, Some(1024));
+        let mempool_config = mempool_listener.next().now_or_never().unwrap().unwrap();
+        assert_eq!(mempool_config.capacity, 10);
+        assert_eq!(mempool_config.capacity_per_user, 1);
+        assert!(logger_listener.next().now_or_never().is_none());
+
+        // Reloading the same config again does not notify anyone.
+        assert!(service.reload(&new_config).is_empty());
+        assert!(api_listener.next().now_or_never().is_none());
+    }
+
+    #[test]
+    fn test_reload_updates_seeds() {
+        let config = node_config();
+        let mut service = ConfigNotificationService::new(config.clone());
+        let mut network_listener = service.subscribe_to_network_updates(NetworkId::Public);
+
+        let peer_id = PeerId::random();
+        let address: NetworkAddress = "/ip4/127.0.0.1/tcp/6180".parse().unwrap();
+        let mut new_config = config;
+        new_config.full_node_networks[0]
+            .seed_addrs
+            .insert(peer_id, vec![address.clone()]);
+        assert_eq!(
+            service.reload(&new_config),
+            vec![ConfigSection::Network(NetworkId::Public)]
+        );
+
+        let network_config = network_listener.next().now_or_never().unwrap().unwrap();
+        assert_eq!(network_config.seed_addrs[&peer_id], vec![address]);
+    }
+
+    #[test]
+    fn test_reload_ignores_other_fields() {
+        let config = node_config();
+        let mut service = ConfigNotificationService::new(config.clone());
+        let mut api_listener = service.subscribe_to_api_updates();
+
+        let mut new_config = config.clone();
+        new_config.api.address.set_port(1);
+        new_config.mempool.shared_mempool_tick_interval_ms += 1;
+        new_config.full_node_networks[0].max_outbound_connections += 1;
+        new_config
+            .full_node_networks
+            .push(NetworkConfig::network_with_id(NetworkId::Vfn));
+        assert!(service.reload(&new_config).is_empty());
+        assert_eq!(service.config(), &config);
+        assert!(api_listener.next().now_or_never().is_none());
+    }
+}
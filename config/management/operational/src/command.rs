@@ -2,9 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    account_resource::SimplifiedAccountResource, validator_config::DecodedValidatorConfig,
-    validator_set::DecryptedValidatorInfo, validator_state::VerifyValidatorStateResult,
-    TransactionContext,
+    account_resource::SimplifiedAccountResource, safety_storage::SafetyStorageSummary,
+    validator_config::DecodedValidatorConfig, validator_set::DecryptedValidatorInfo,
+    validator_state::VerifyValidatorStateResult, TransactionContext,
 };
 use aptos_config::config::Peer;
 use aptos_crypto::{ed25519::Ed25519PublicKey, x25519};
@@ -29,6 +29,8 @@ pub enum Command {
     CreateValidator(crate::governance::CreateValidator),
     #[structopt(about = "Create a new validator operator account")]
     CreateValidatorOperator(crate::governance::CreateValidatorOperator),
+    #[structopt(about = "Export the safety rules storage, including private keys, to a file")]
+    ExportSafetyStorage(crate::safety_storage::ExportSafetyStorage),
     #[structopt(about = "Extract a trusted peer identity from an x25519 PrivateKey file")]
     ExtractPeerFromFile(crate::keys::ExtractPeerFromFile),
     #[structopt(about = "Extract a trusted peer identity from storage")]
@@ -41,8 +43,14 @@ pub enum Command {
     ExtractPublicKey(crate::keys::ExtractPublicKey),
     #[structopt(about = "Generate a PrivateKey to a file")]
     GenerateKey(crate::keys::GenerateKey),
+    #[structopt(about = "Import the safety rules storage from a file")]
+    ImportSafetyStorage(crate::safety_storage::ImportSafetyStorage),
+    #[structopt(about = "Displays the safety rules storage without private keys")]
+    InspectSafetyStorage(crate::safety_storage::InspectSafetyStorage),
     #[structopt(about = "Set the waypoint in the validator storage")]
     InsertWaypoint(aptos_management::waypoint::InsertWaypoint),
+    #[structopt(about = "Copy the safety rules storage to another backend")]
+    MigrateSafetyStorage(crate::safety_storage::MigrateSafetyStorage),
     #[structopt(about = "Prints an account from the validator storage")]
     PrintAccount(crate::print::PrintAccount),
     #[structopt(about = "Prints an ed25519 public key from the validator storage")]
@@ -85,13 +93,17 @@ pub enum CommandName {
     CheckValidatorSetEndpoints,
     CreateValidator,
     CreateValidatorOperator,
+    ExportSafetyStorage,
     ExtractPeerFromFile,
     ExtractPeerFromStorage,
     ExtractPeersFromKeys,
     ExtractPrivateKey,
     ExtractPublicKey,
     GenerateKey,
+    ImportSafetyStorage,
+    InspectSafetyStorage,
     InsertWaypoint,
+    MigrateSafetyStorage,
     PrintAccount,
     PrintKey,
     PrintXKey,
@@ -118,13 +130,17 @@ impl From<&Command> for CommandName {
             Command::CheckValidatorSetEndpoints(_) => CommandName::CheckValidatorSetEndpoints,
             Command::CreateValidator(_) => CommandName::CreateValidator,
             Command::CreateValidatorOperator(_) => CommandName::CreateValidatorOperator,
+            Command::ExportSafetyStorage(_) => CommandName::ExportSafetyStorage,
             Command::ExtractPrivateKey(_) => CommandName::ExtractPrivateKey,
             Command::ExtractPublicKey(_) => CommandName::ExtractPublicKey,
             Command::ExtractPeerFromFile(_) => CommandName::ExtractPeerFromFile,
             Command::ExtractPeerFromStorage(_) => CommandName::ExtractPeerFromStorage,
             Command::ExtractPeersFromKeys(_) => CommandName::ExtractPeersFromKeys,
             Command::GenerateKey(_) => CommandName::GenerateKey,
+            Command::ImportSafetyStorage(_) => CommandName::ImportSafetyStorage,
+            Command::InspectSafetyStorage(_) => CommandName::InspectSafetyStorage,
             Command::InsertWaypoint(_) => CommandName::InsertWaypoint,
+            Command::MigrateSafetyStorage(_) => CommandName::MigrateSafetyStorage,
             Command::PrintAccount(_) => CommandName::PrintAccount,
             Command::PrintKey(_) => CommandName::PrintKey,
             Command::PrintXKey(_) => CommandName::PrintXKey,
@@ -153,13 +169,17 @@ impl std::fmt::Display for CommandName {
             CommandName::CheckValidatorSetEndpoints => "check-validator-set-endpoints",
             CommandName::CreateValidator => "create-validator",
             CommandName::CreateValidatorOperator => "create-validator-operator",
+            CommandName::ExportSafetyStorage => "export-safety-storage",
             CommandName::ExtractPrivateKey => "extract-private-key",
             CommandName::ExtractPublicKey => "extract-public-key",
             CommandName::ExtractPeerFromFile => "extract-peer-from-file",
             CommandName::ExtractPeerFromStorage => "extract-peer-from-storage",
             CommandName::ExtractPeersFromKeys => "extract-peers-from-keys",
             CommandName::GenerateKey => "generate-key",
+            CommandName::ImportSafetyStorage => "import-safety-storage",
+            CommandName::InspectSafetyStorage => "inspect-safety-storage",
             CommandName::InsertWaypoint => "insert-waypoint",
+            CommandName::MigrateSafetyStorage => "migrate-safety-storage",
             CommandName::PrintAccount => "print-account",
             CommandName::PrintKey => "print-key",
             CommandName::PrintXKey => "print-x-key",
@@ -194,6 +214,10 @@ impl Command {
                 Self::print_transaction_context(cmd.execute().await.map(|(txn_ctx, _)| txn_ctx))
             }
             Command::InsertWaypoint(cmd) => Self::print_success(cmd.execute()),
+            Command::ExportSafetyStorage(cmd) => Self::print_success(cmd.execute()),
+            Command::ImportSafetyStorage(cmd) => Self::print_success(cmd.execute()),
+            Command::InspectSafetyStorage(cmd) => Self::pretty_print(cmd.execute()),
+            Command::MigrateSafetyStorage(cmd) => Self::print_success(cmd.execute()),
             Command::ExtractPeerFromFile(cmd) => Self::pretty_print(cmd.execute()),
             Command::ExtractPeerFromStorage(cmd) => Self::pretty_print(cmd.execute()),
             Command::ExtractPeersFromKeys(cmd) => Self::pretty_print(cmd.execute()),
@@ -312,6 +336,14 @@ impl Command {
         )
     }
 
+    pub async fn export_safety_storage(self) -> Result<(), Error> {
+        execute_command!(
+            self,
+            Command::ExportSafetyStorage,
+            CommandName::ExportSafetyStorage
+        )
+    }
+
     pub async fn extract_private_key(self) -> Result<(), Error> {
         execute_command!(
             self,
@@ -356,10 +388,34 @@ impl Command {
         execute_command!(self, Command::GenerateKey, CommandName::GenerateKey)
     }
 
+    pub async fn import_safety_storage(self) -> Result<(), Error> {
+        execute_command!(
+            self,
+            Command::ImportSafetyStorage,
+            CommandName::ImportSafetyStorage
+        )
+    }
+
+    pub async fn inspect_safety_storage(self) -> Result<SafetyStorageSummary, Error> {
+        execute_command!(
+            self,
+            Command::InspectSafetyStorage,
+            CommandName::InspectSafetyStorage
+        )
+    }
+
     pub async fn insert_waypoint(self) -> Result<(), Error> {
         execute_command!(self, Command::InsertWaypoint, CommandName::InsertWaypoint)
     }
 
+    pub async fn migrate_safety_storage(self) -> Result<(), Error> {
+        execute_command!(
+            self,
+            Command::MigrateSafetyStorage,
+            CommandName::MigrateSafetyStorage
+        )
+    }
+
     pub async fn print_account(self) -> Result<AccountAddress, Error> {
         execute_command!(self, Command::PrintAccount, CommandName::PrintAccount)
     }
@@ -18,13 +18,16 @@ use rand::SeedableRng;
 use serde::Serialize;
 use std::{
     collections::{HashMap, HashSet},
-    fs::{read, File},
+    fs::{read, File, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
     str::FromStr,
 };
 use structopt::StructOpt;
 
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
 #[derive(Debug, StructOpt)]
 pub struct GenerateKey {
     /// Location to store the key
@@ -351,9 +354,28 @@ fn save_to_yaml<T: Serialize>(path: &Path, input_name: &str, item: &T) -> Result
     write_file(path, input_name, yaml.as_bytes())
 }
 
-fn write_file(path: &Path, input_name: &str, contents: &[u8]) -> Result<(), Error> {
+pub(crate) fn write_file(path: &Path, input_name: &str, contents: &[u8]) -> Result<(), Error> {
     let mut file = File::create(path).map_err(|e| Error::IO(input_name.to_string(), e))?;
     file.write_all(contents)
         .map_err(|e| Error::IO(input_name.to_string(), e))?;
     Ok(())
 }
+
+/// Writes a file only its owner can read and write, for contents such as private keys. Fails if
+/// the file already exists, rather than keeping the permissions it has.
+pub(crate) fn write_private_file(
+    path: &Path,
+    input_name: &str,
+    contents: &[u8],
+) -> Result<(), Error> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options
+        .open(path)
+        .map_err(|e| Error::IO(input_name.to_string(), e))?;
+    file.write_all(contents)
+        .map_err(|e| Error::IO(input_name.to_string(), e))?;
+    Ok(())
+}
@@ -11,6 +11,7 @@ pub mod keys;
 mod owner;
 mod print;
 pub mod rest_client;
+pub mod safety_storage;
 mod validate_transaction;
 mod validator_config;
 mod validator_set;
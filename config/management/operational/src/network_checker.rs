@@ -16,7 +16,7 @@ use futures::{AsyncReadExt, AsyncWriteExt};
 use netcore::transport::tcp::{resolve_and_connect, TcpSocket};
 use network::{
     noise::{HandshakeAuthMode, NoiseUpgrader},
-    protocols::wire::handshake::v1::ProtocolIdSet,
+    protocols::wire::handshake::v1::{Capabilities, ProtocolIdSet},
     transport::{upgrade_outbound, UpgradeContext, SUPPORTED_MESSAGING_PROTOCOL},
 };
 use std::{collections::BTreeMap, sync::Arc};
@@ -175,6 +175,7 @@ fn build_upgrade_context(
         ),
         HANDSHAKE_VERSION,
         supported_protocols,
+        Capabilities::baseline(),
         chain_id,
         network_id,
     ))
@@ -0,0 +1,196 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::keys::write_private_file;
+use aptos_crypto::{ed25519::Ed25519PublicKey, PrivateKey};
+use aptos_global_constants::SAFETY_DATA;
+use aptos_management::{
+    config::ConfigPath,
+    error::Error,
+    secure_backend::{SecureBackend, ValidatorBackend},
+};
+use aptos_secure_storage::{KVStorage, Storage};
+use aptos_types::{account_address::AccountAddress, waypoint::Waypoint};
+use consensus_types::safety_data::SafetyData;
+use safety_rules::{PersistentSafetyStorage, SafetyStorageSnapshot};
+use serde::Serialize;
+use std::{convert::TryInto, fs::read, path::PathBuf, str::FromStr};
+use structopt::StructOpt;
+
+aptos_management::secure_backend!(
+    InspectedBackend,
+    validator_backend,
+    "validator configuration",
+    "snapshot-file"
+);
+
+fn validator_storage(
+    config: &ConfigPath,
+    validator_backend: &Option<SecureBackend>,
+) -> Result<Storage, Error> {
+    let config = config
+        .load()?
+        .override_validator_backend(validator_backend)?;
+    Ok(Storage::from(&config.validator_backend))
+}
+
+fn export_snapshot(storage: Storage) -> Result<SafetyStorageSnapshot, Error> {
+    PersistentSafetyStorage::new(storage, false)
+        .export_snapshot()
+        .map_err(|e| Error::StorageReadError("validator", SAFETY_DATA, e.to_string()))
+}
+
+fn import_snapshot(storage: Storage, snapshot: SafetyStorageSnapshot) -> Result<(), Error> {
+    PersistentSafetyStorage::import_snapshot(storage, snapshot, false)
+        .map(|_| ())
+        .map_err(|e| Error::StorageWriteError("validator", SAFETY_DATA, e.to_string()))
+}
+
+fn has_safety_data(storage: &Storage) -> Result<bool, Error> {
+    match storage.get::<SafetyData>(SAFETY_DATA) {
+        Ok(_) => Ok(true),
+        Err(aptos_secure_storage::Error::KeyNotSet(_)) => Ok(false),
+        Err(e) => Err(Error::StorageReadError(
+            "validator",
+            SAFETY_DATA,
+            e.to_string(),
+        )),
+    }
+}
+
+fn load_snapshot(path: PathBuf) -> Result<SafetyStorageSnapshot, Error> {
+    let data = read(&path).map_err(|err| {
+        Error::UnableToReadFile(path.to_str().unwrap().to_string(), err.to_string())
+    })?;
+    serde_json::from_slice(&data).map_err(|err| {
+        Error::UnableToParseFile(path.to_str().unwrap().to_string(), err.to_string())
+    })
+}
+
+/// Writes the safety rules storage, including the consensus and execution private keys, to a
+/// new file only readable by its owner. The validator must be stopped while its storage is
+/// exported.
+#[derive(Debug, StructOpt)]
+pub struct ExportSafetyStorage {
+    #[structopt(flatten)]
+    config: ConfigPath,
+    #[structopt(flatten)]
+    validator_backend: ValidatorBackend,
+    /// Location to write the snapshot, which must not exist yet
+    #[structopt(long)]
+    snapshot_file: PathBuf,
+}
+
+impl ExportSafetyStorage {
+    pub fn execute(self) -> Result<(), Error> {
+        let storage = validator_storage(&self.config, &self.validator_backend.validator_backend)?;
+        let snapshot = export_snapshot(storage)?;
+        let encoded = serde_json::to_vec_pretty(&snapshot)
+            .map_err(|err| Error::UnexpectedError(err.to_string()))?;
+        write_private_file(&self.snapshot_file, "safety rules snapshot", &encoded)
+    }
+}
+
+/// Restores a snapshot written by `ExportSafetyStorage`. Refused if the storage already holds
+/// data of another validator or safety data ahead of the snapshot, and unless forced if it holds
+/// no safety data to check the snapshot against.
+#[derive(Debug, StructOpt)]
+pub struct ImportSafetyStorage {
+    #[structopt(flatten)]
+    config: ConfigPath,
+    #[structopt(flatten)]
+    validator_backend: ValidatorBackend,
+    /// Location of the snapshot
+    #[structopt(long)]
+    snapshot_file: PathBuf,
+    /// Import into a backend without safety data, e.g. one that was lost. The snapshot must be
+    /// the latest one, an older one would roll back the rounds the validator voted in
+    #[structopt(long)]
+    force: bool,
+}
+
+impl ImportSafetyStorage {
+    pub fn execute(self) -> Result<(), Error> {
+        let snapshot = load_snapshot(self.snapshot_file)?;
+        let storage = validator_storage(&self.config, &self.validator_backend.validator_backend)?;
+        if !self.force && !has_safety_data(&storage)? {
+            return Err(Error::CommandArgumentError(
+                "The validator backend holds no safety data to check the snapshot is not stale \
+                against, use --force to import it anyway"
+                    .to_string(),
+            ));
+        }
+        import_snapshot(storage, snapshot)
+    }
+}
+
+/// Copies the safety rules storage from the validator backend to another backend, e.g., from
+/// on-disk storage to Vault, with the same checks as `ImportSafetyStorage`.
+#[derive(Debug, StructOpt)]
+pub struct MigrateSafetyStorage {
+    #[structopt(flatten)]
+    config: ConfigPath,
+    #[structopt(flatten)]
+    validator_backend: ValidatorBackend,
+    /// Backend to copy the safety rules storage to, in the same format as --validator-backend
+    #[structopt(long)]
+    destination_backend: SecureBackend,
+}
+
+impl MigrateSafetyStorage {
+    pub fn execute(self) -> Result<(), Error> {
+        let storage = validator_storage(&self.config, &self.validator_backend.validator_backend)?;
+        let snapshot = export_snapshot(storage)?;
+        let destination: aptos_config::config::SecureBackend =
+            self.destination_backend.try_into()?;
+        import_snapshot(Storage::from(&destination), snapshot)
+    }
+}
+
+/// The contents of a safety rules storage without the private keys.
+#[derive(Debug, Serialize)]
+pub struct SafetyStorageSummary {
+    pub author: AccountAddress,
+    pub safety_data: SafetyData,
+    pub waypoint: Waypoint,
+    pub consensus_public_key: Ed25519PublicKey,
+    pub execution_public_key: Ed25519PublicKey,
+}
+
+impl From<SafetyStorageSnapshot> for SafetyStorageSummary {
+    fn from(snapshot: SafetyStorageSnapshot) -> Self {
+        Self {
+            author: snapshot.author,
+            safety_data: snapshot.safety_data,
+            waypoint: snapshot.waypoint,
+            consensus_public_key: snapshot.consensus_key.public_key(),
+            execution_public_key: snapshot.execution_key.public_key(),
+        }
+    }
+}
+
+/// Displays the safety rules storage of the validator backend, or of a snapshot file if one is
+/// given.
+#[derive(Debug, StructOpt)]
+pub struct InspectSafetyStorage {
+    #[structopt(flatten)]
+    config: ConfigPath,
+    #[structopt(flatten)]
+    validator_backend: InspectedBackend,
+    /// Location of a snapshot to display instead of the validator backend
+    #[structopt(long)]
+    snapshot_file: Option<PathBuf>,
+}
+
+impl InspectSafetyStorage {
+    pub fn execute(self) -> Result<SafetyStorageSummary, Error> {
+        let snapshot = match self.snapshot_file {
+            Some(path) => load_snapshot(path)?,
+            None => export_snapshot(validator_storage(
+                &self.config,
+                &self.validator_backend.validator_backend,
+            )?)?,
+        };
+        Ok(snapshot.into())
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     account_resource::SimplifiedAccountResource,
     command::{Command, CommandName},
     keys::{EncodingType, KeyType},
+    safety_storage::SafetyStorageSummary,
     validator_config::DecodedValidatorConfig,
     validator_set::DecryptedValidatorInfo,
     validator_state::VerifyValidatorStateResult,
@@ -204,6 +205,25 @@ impl OperationalTool {
         Ok(Command::from_iter(args.split_whitespace()))
     }
 
+    pub async fn export_safety_storage(
+        &self,
+        snapshot_file: &Path,
+        backend: &config::SecureBackend,
+    ) -> Result<(), Error> {
+        let args = format!(
+            "
+                {command}
+                --snapshot-file {snapshot_file}
+                --validator-backend {backend_args}
+            ",
+            command = command(TOOL_NAME, CommandName::ExportSafetyStorage),
+            snapshot_file = snapshot_file.to_str().unwrap(),
+            backend_args = backend_args(backend)?,
+        );
+        let command = Command::from_iter(args.split_whitespace());
+        command.export_safety_storage().await
+    }
+
     pub async fn extract_public_key(
         &self,
         key_name: &str,
@@ -304,6 +324,57 @@ impl OperationalTool {
         command.extract_peers_from_keys().await
     }
 
+    pub async fn import_safety_storage(
+        &self,
+        snapshot_file: &Path,
+        backend: &config::SecureBackend,
+    ) -> Result<(), Error> {
+        let args = format!(
+            "
+                {command}
+                --snapshot-file {snapshot_file}
+                --validator-backend {backend_args}
+            ",
+            command = command(TOOL_NAME, CommandName::ImportSafetyStorage),
+            snapshot_file = snapshot_file.to_str().unwrap(),
+            backend_args = backend_args(backend)?,
+        );
+        let command = Command::from_iter(args.split_whitespace());
+        command.import_safety_storage().await
+    }
+
+    pub async fn inspect_safety_storage(
+        &self,
+        backend: &config::SecureBackend,
+    ) -> Result<SafetyStorageSummary, Error> {
+        let args = format!(
+            "
+                {command}
+                --validator-backend {backend_args}
+            ",
+            command = command(TOOL_NAME, CommandName::InspectSafetyStorage),
+            backend_args = backend_args(backend)?,
+        );
+        let command = Command::from_iter(args.split_whitespace());
+        command.inspect_safety_storage().await
+    }
+
+    pub async fn inspect_safety_storage_snapshot(
+        &self,
+        snapshot_file: &Path,
+    ) -> Result<SafetyStorageSummary, Error> {
+        let args = format!(
+            "
+                {command}
+                --snapshot-file {snapshot_file}
+            ",
+            command = command(TOOL_NAME, CommandName::InspectSafetyStorage),
+            snapshot_file = snapshot_file.to_str().unwrap(),
+        );
+        let command = Command::from_iter(args.split_whitespace());
+        command.inspect_safety_storage().await
+    }
+
     pub async fn insert_waypoint(
         &self,
         waypoint: Waypoint,
@@ -326,6 +397,25 @@ impl OperationalTool {
         command.insert_waypoint().await
     }
 
+    pub async fn migrate_safety_storage(
+        &self,
+        backend: &config::SecureBackend,
+        destination_backend: &config::SecureBackend,
+    ) -> Result<(), Error> {
+        let args = format!(
+            "
+                {command}
+                --validator-backend {backend_args}
+                --destination-backend {destination_backend_args}
+            ",
+            command = command(TOOL_NAME, CommandName::MigrateSafetyStorage),
+            backend_args = backend_args(backend)?,
+            destination_backend_args = backend_args(destination_backend)?,
+        );
+        let command = Command::from_iter(args.split_whitespace());
+        command.migrate_safety_storage().await
+    }
+
     pub async fn print_account(
         &self,
         account_name: &str,
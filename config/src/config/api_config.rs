@@ -18,11 +18,106 @@ pub struct ApiConfig {
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    /// Limits on submitted transactions, checked before they reach mempool.
+    pub max_transaction_payload_bytes: u64,
+    pub max_script_bytes: u64,
+    pub max_transaction_arguments: usize,
+    /// The longest vector argument, e.g. a JSON array or a hex-encoded `vector<u8>`.
+    pub max_vector_argument_length: usize,
+    /// The number of addresses a `POST /accounts/resources/batch` request can read.
+    pub max_account_resources_batch_size: usize,
+    /// The minimum gas unit price of submitted transactions as mempool fills up. There is no floor
+    /// by default.
+    pub gas_price_floor: Vec<GasPriceFloorStep>,
+    /// How long submitted transactions wait for others to be sent to mempool with them in a
+    /// single request, which lowers the per-transaction overhead of mempool at high submission
+    /// rates at the cost of this added latency. 0 sends every transaction on its own.
+    pub submission_batch_window_ms: u64,
+    /// A batch reaching this many transactions is sent to mempool without waiting for the end of
+    /// the window.
+    pub max_submission_batch_size: usize,
+    /// The largest pages of transactions and events a request can ask for. Storage serves at most
+    /// 5000 items per read.
+    pub max_transactions_page_size: u16,
+    pub max_events_page_size: u16,
+    /// The largest page of the other paged lists, e.g. the modules of an account.
+    pub max_page_size: u16,
+    /// Groups of endpoints the node does not serve, e.g. `transaction_submission` on a read-only
+    /// node. Their requests are rejected with 403.
+    pub disabled_endpoint_groups: Vec<EndpointGroup>,
+    /// The number of idempotency keys of transaction submissions remembered, 0 disables them.
+    /// Read at startup.
+    pub idempotency_cache_capacity: usize,
+    /// How long the result of a submission is replayed to requests with the same idempotency key.
+    pub idempotency_key_ttl_secs: u64,
+    /// The number of event streams open at once, further streams are rejected with 503. Read at
+    /// startup.
+    pub max_event_streams: usize,
+}
+
+/// A step of the gas price floor: once mempool holds at least `mempool_utilization_percent` of its
+/// capacity, transactions with a gas unit price below `min_gas_unit_price` are rejected. The floor
+/// is the price of the highest step reached.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasPriceFloorStep {
+    pub mempool_utilization_percent: u64,
+    pub min_gas_unit_price: u64,
+}
+
+/// A group of API endpoints that can be disabled. The index, the ledger info, the OpenAPI spec and
+/// the health check are always served.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndpointGroup {
+    /// Accounts and their resources, modules and tokens.
+    Accounts,
+    /// Proofs of state, i.e. `/accounts/{address}/non_existence_proof`.
+    StateProofs,
+    /// Reads of transactions, signing messages and signature checks.
+    Transactions,
+    /// `POST /transactions`.
+    TransactionSubmission,
+    Events,
+    /// `/events/{event_key}/stream`, which holds connections open.
+    EventStreams,
+    /// Single resources, modules, tables, coins and on-chain configs.
+    State,
+    GasEstimation,
+    Validators,
+    AbortCodes,
+}
+
+impl EndpointGroup {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndpointGroup::Accounts => "accounts",
+            EndpointGroup::StateProofs => "state_proofs",
+            EndpointGroup::Transactions => "transactions",
+            EndpointGroup::TransactionSubmission => "transaction_submission",
+            EndpointGroup::Events => "events",
+            EndpointGroup::EventStreams => "event_streams",
+            EndpointGroup::State => "state",
+            EndpointGroup::GasEstimation => "gas_estimation",
+            EndpointGroup::Validators => "validators",
+            EndpointGroup::AbortCodes => "abort_codes",
+        }
+    }
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 4 * 1024 * 1024; // 4mb
+pub const DEFAULT_MAX_TRANSACTION_PAYLOAD_BYTES: u64 = 1024 * 1024; // 1mb
+pub const DEFAULT_MAX_SCRIPT_BYTES: u64 = 64 * 1024; // 64kb
+pub const DEFAULT_MAX_TRANSACTION_ARGUMENTS: usize = 256;
+pub const DEFAULT_MAX_VECTOR_ARGUMENT_LENGTH: usize = 64 * 1024;
+pub const DEFAULT_MAX_ACCOUNT_RESOURCES_BATCH_SIZE: usize = 500;
+pub const DEFAULT_MAX_SUBMISSION_BATCH_SIZE: usize = 100;
+pub const DEFAULT_MAX_PAGE_SIZE: u16 = 1000;
+pub const DEFAULT_IDEMPOTENCY_CACHE_CAPACITY: usize = 10_000;
+pub const DEFAULT_IDEMPOTENCY_KEY_TTL_SECS: u64 = 60 * 60;
+pub const DEFAULT_MAX_EVENT_STREAMS: usize = 256;
 
 fn default_enabled() -> bool {
     true
@@ -38,6 +133,21 @@ impl Default for ApiConfig {
             tls_cert_path: None,
             tls_key_path: None,
             content_length_limit: None,
+            max_transaction_payload_bytes: DEFAULT_MAX_TRANSACTION_PAYLOAD_BYTES,
+            max_script_bytes: DEFAULT_MAX_SCRIPT_BYTES,
+            max_transaction_arguments: DEFAULT_MAX_TRANSACTION_ARGUMENTS,
+            max_vector_argument_length: DEFAULT_MAX_VECTOR_ARGUMENT_LENGTH,
+            max_account_resources_batch_size: DEFAULT_MAX_ACCOUNT_RESOURCES_BATCH_SIZE,
+            gas_price_floor: vec![],
+            submission_batch_window_ms: 0,
+            max_submission_batch_size: DEFAULT_MAX_SUBMISSION_BATCH_SIZE,
+            max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
+            max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
+            disabled_endpoint_groups: vec![],
+            idempotency_cache_capacity: DEFAULT_IDEMPOTENCY_CACHE_CAPACITY,
+            idempotency_key_ttl_secs: DEFAULT_IDEMPOTENCY_KEY_TTL_SECS,
+            max_event_streams: DEFAULT_MAX_EVENT_STREAMS,
         }
     }
 }
@@ -47,6 +157,10 @@ impl ApiConfig {
         self.address.set_port(utils::get_available_port());
     }
 
+    pub fn is_enabled(&self, group: EndpointGroup) -> bool {
+        !self.disabled_endpoint_groups.contains(&group)
+    }
+
     pub fn content_length_limit(&self) -> u64 {
         match self.content_length_limit {
             Some(v) => v,
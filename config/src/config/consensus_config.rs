@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::SafetyRulesConfig;
+use crate::config::{invariant, Error, SafetyRulesConfig};
 use aptos_types::{account_address::AccountAddress, block_info::Round};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, path::PathBuf};
@@ -26,6 +26,11 @@ pub struct ConsensusConfig {
     // the period = (poll_count - 1) * 30ms
     pub mempool_poll_count: u64,
     pub channel_size: usize,
+    // Broadcast proposals with the hashes of their transactions instead of the transactions
+    // themselves, the other validators resolve them from their mempool and fetch the missing ones
+    // from the proposer. The on-chain `CompactProposals` feature enables them on every validator.
+    pub compact_proposals: bool,
+    pub storage_backpressure: StorageBackpressureConfig,
 }
 
 impl Default for ConsensusConfig {
@@ -45,6 +50,38 @@ impl Default for ConsensusConfig {
             sync_only: false,
             mempool_poll_count: 20,
             channel_size: 30, // hard-coded
+            compact_proposals: false,
+            storage_backpressure: StorageBackpressureConfig::default(),
+        }
+    }
+}
+
+/// Slows down the proposals of the validator while storage commits are slow, so that execution
+/// does not run ahead of the disk and pile up executed blocks in memory. Above the target commit
+/// latency, proposals shrink in proportion to how far the latency is over the target, and are
+/// delayed by the excess latency.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageBackpressureConfig {
+    pub enabled: bool,
+    // Average latency of storage commits up to which proposals are not slowed down
+    pub target_commit_latency_ms: u64,
+    // Weight of the latest commit in the moving average of the commit latency, in percent
+    pub latency_smoothing_percent: u64,
+    // Proposals never shrink below this percentage of `max_block_size`
+    pub min_block_size_percent: u64,
+    // The longest a proposal is delayed
+    pub max_proposal_delay_ms: u64,
+}
+
+impl Default for StorageBackpressureConfig {
+    fn default() -> StorageBackpressureConfig {
+        StorageBackpressureConfig {
+            enabled: false,
+            target_commit_latency_ms: 500,
+            latency_smoothing_percent: 20,
+            min_block_size_percent: 10,
+            max_proposal_delay_ms: 500,
         }
     }
 }
@@ -53,6 +90,19 @@ impl ConsensusConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.safety_rules.set_data_dir(data_dir);
     }
+
+    /// Checks that a proposal delayed by storage backpressure can still be sent before its round
+    /// times out.
+    pub fn validate(&self) -> Result<(), Error> {
+        invariant(
+            !self.storage_backpressure.enabled
+                || self.storage_backpressure.max_proposal_delay_ms < self.round_initial_timeout_ms,
+            format!(
+                "The maximum proposal delay ({}ms) must be below the round timeout ({}ms)",
+                self.storage_backpressure.max_proposal_delay_ms, self.round_initial_timeout_ms
+            ),
+        )
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -76,3 +126,21 @@ pub struct LeaderReputationConfig {
     pub active_weights: u64,
     pub inactive_weights: u64,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_validate_max_proposal_delay() {
+        let mut config = ConsensusConfig::default();
+        config.storage_backpressure.max_proposal_delay_ms = config.round_initial_timeout_ms;
+        config.validate().unwrap();
+
+        config.storage_backpressure.enabled = true;
+        assert!(config.validate().is_err());
+
+        config.storage_backpressure.max_proposal_delay_ms = config.round_initial_timeout_ms - 1;
+        config.validate().unwrap();
+    }
+}
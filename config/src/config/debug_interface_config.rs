@@ -3,6 +3,7 @@
 
 use crate::utils;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -11,6 +12,9 @@ pub struct DebugInterfaceConfig {
     pub address: String,
     pub metrics_server_port: u16,
     pub public_metrics_server_port: u16,
+    /// Pushes metrics to a pushgateway in addition to serving them, for nodes that can't be
+    /// scraped. Disabled if `None`.
+    pub metrics_push: Option<MetricsPushConfig>,
 }
 
 impl Default for DebugInterfaceConfig {
@@ -20,6 +24,7 @@ impl Default for DebugInterfaceConfig {
             address: "0.0.0.0".to_string(),
             metrics_server_port: 9101,
             public_metrics_server_port: 9102,
+            metrics_push: None,
         }
     }
 }
@@ -31,3 +36,25 @@ impl DebugInterfaceConfig {
         self.public_metrics_server_port = utils::get_available_port();
     }
 }
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetricsPushConfig {
+    /// Base url of the Prometheus pushgateway, only plain http is supported.
+    pub gateway_url: String,
+    pub push_interval_ms: u64,
+    pub job: String,
+    /// Grouping labels added to the pushed metrics, on top of the node role and chain.
+    pub labels: BTreeMap<String, String>,
+}
+
+impl Default for MetricsPushConfig {
+    fn default() -> MetricsPushConfig {
+        MetricsPushConfig {
+            gateway_url: "http://127.0.0.1:9091".to_string(),
+            push_interval_ms: 15_000,
+            job: "aptos-node".to_string(),
+            labels: BTreeMap::new(),
+        }
+    }
+}
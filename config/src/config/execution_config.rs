@@ -20,6 +20,9 @@ pub struct ExecutionConfig {
     pub genesis_file_location: PathBuf,
     pub network_timeout_ms: u64,
     pub concurrency_level: u16,
+    /// Record the keys each transaction reads and writes, and dump them per block at debug
+    /// level. Forces sequential execution, so only meant for debugging.
+    pub profile_state_view: bool,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -47,6 +50,7 @@ impl Default for ExecutionConfig {
             network_timeout_ms: 30_000,
             // Sequential execution by default.
             concurrency_level: 1,
+            profile_state_view: false,
         }
     }
 }
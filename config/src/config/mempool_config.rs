@@ -1,13 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
     pub capacity: usize,
     pub capacity_per_user: usize,
+    // number of transactions of a sender included in a block before the transactions of the other
+    // senders, the block is only filled up with more transactions of the sender after these
+    pub max_block_txns_per_sender: usize,
+    // number of the last transactions discarded during block execution kept for the debug interface
+    pub dead_letter_queue_capacity: usize,
     // number of failovers to broadcast to when the primary network is alive
     pub default_failovers: usize,
     pub max_broadcasts_per_peer: usize,
@@ -19,6 +26,8 @@ pub struct MempoolConfig {
     pub shared_mempool_tick_interval_ms: u64,
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
+    // restricts the senders of the transactions accepted from clients and peers
+    pub sender_admission_policy: SenderAdmissionPolicy,
 }
 
 impl Default for MempoolConfig {
@@ -33,9 +42,62 @@ impl Default for MempoolConfig {
             mempool_snapshot_interval_secs: 180,
             capacity: 1_000_000,
             capacity_per_user: 100,
+            max_block_txns_per_sender: 100,
+            dead_letter_queue_capacity: 1_000,
             default_failovers: 3,
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
+            sender_admission_policy: SenderAdmissionPolicy::AllowAll,
         }
     }
 }
+
+/// The senders whose transactions mempool accepts, for private deployments where only known
+/// accounts may transact. The policy applies to transactions submitted by clients and to
+/// transactions broadcast by peers alike, so every node of such a network should use it.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum SenderAdmissionPolicy {
+    AllowAll,
+    // Only transactions sent by these addresses are accepted
+    Allowlist { addresses: HashSet<AccountAddress> },
+    // Transactions sent by these addresses are rejected
+    Denylist { addresses: HashSet<AccountAddress> },
+}
+
+impl SenderAdmissionPolicy {
+    pub fn admits(&self, sender: &AccountAddress) -> bool {
+        match self {
+            SenderAdmissionPolicy::AllowAll => true,
+            SenderAdmissionPolicy::Allowlist { addresses } => addresses.contains(sender),
+            SenderAdmissionPolicy::Denylist { addresses } => !addresses.contains(sender),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sender_admission_policy() {
+        let allowed = AccountAddress::random();
+        let other = AccountAddress::random();
+
+        let policy: SenderAdmissionPolicy = serde_yaml::from_str(&format!(
+            "type: allowlist\naddresses:\n  - {}\n",
+            allowed.to_hex_literal()
+        ))
+        .unwrap();
+        assert!(policy.admits(&allowed));
+        assert!(!policy.admits(&other));
+
+        let policy = SenderAdmissionPolicy::Denylist {
+            addresses: vec![allowed].into_iter().collect(),
+        };
+        assert!(!policy.admits(&allowed));
+        assert!(policy.admits(&other));
+
+        assert!(SenderAdmissionPolicy::AllowAll.admits(&other));
+    }
+}
@@ -30,6 +30,8 @@ mod mempool_config;
 pub use mempool_config::*;
 mod network_config;
 pub use network_config::*;
+mod preflight_config;
+pub use preflight_config::*;
 mod secure_backend_config;
 pub use secure_backend_config::*;
 mod state_sync_config;
@@ -75,6 +77,8 @@ pub struct NodeConfig {
     #[serde(default)]
     pub api: ApiConfig,
     #[serde(default)]
+    pub preflight: PreflightConfig,
+    #[serde(default)]
     pub state_sync: StateSyncConfig,
     #[serde(default)]
     pub storage: StorageConfig,
@@ -82,6 +86,8 @@ pub struct NodeConfig {
     pub test: Option<TestConfig>,
     #[serde(default)]
     pub validator_network: Option<NetworkConfig>,
+    /// Actions of failpoints to set at startup, by failpoint name. Only applied by builds with
+    /// failpoints, see `aptos-failpoints`.
     #[serde(default)]
     pub failpoints: Option<HashMap<String, String>>,
 }
@@ -92,6 +98,9 @@ pub struct BaseConfig {
     data_dir: PathBuf,
     pub role: RoleType,
     pub waypoint: WaypointConfig,
+    /// Time given to the node to shut down gracefully on SIGTERM, before whatever is still running
+    /// is dropped. Should be shorter than the grace period of the process supervisor.
+    pub shutdown_deadline_ms: u64,
 }
 
 impl Default for BaseConfig {
@@ -100,6 +109,7 @@ impl Default for BaseConfig {
             data_dir: PathBuf::from("/opt/aptos/data"),
             role: RoleType::Validator,
             waypoint: WaypointConfig::None,
+            shutdown_deadline_ms: 30_000,
         }
     }
 }
@@ -255,6 +265,7 @@ impl NodeConfig {
         let input_dir = RootPath::new(input_path);
         config.execution.load(&input_dir)?;
 
+        config.consensus.validate()?;
         let mut config = config.validate_network_configs()?;
         config.set_data_dir(config.data_dir().to_path_buf());
         Ok(config)
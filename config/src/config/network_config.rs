@@ -7,7 +7,7 @@ use crate::{
     network_id::NetworkId,
     utils,
 };
-use aptos_crypto::{x25519, Uniform};
+use aptos_crypto::{ed25519::Ed25519PublicKey, x25519, Uniform};
 use aptos_secure_storage::{CryptoStorage, KVStorage, Storage};
 use aptos_types::{
     account_address::from_identity_public_key, network_address::NetworkAddress,
@@ -20,8 +20,9 @@ use rand::{
 use serde::{Deserialize, Serialize};
 use short_hex_str::AsShortHexStr;
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     convert::TryFrom,
+    fmt,
     path::PathBuf,
     string::ToString,
     time::Duration,
@@ -83,6 +84,12 @@ pub struct NetworkConfig {
     pub max_frame_size: usize,
     // Enables proxy protocol on incoming connections to get original source addresses
     pub enable_proxy_protocol: bool,
+    // The transport to connect to peers with. QUIC falls back to TCP for the peers that don't
+    // support it, and uses the port of the listen address over UDP.
+    pub transport_protocol: TransportProtocol,
+    // Codecs to compress large messages with, when the remote peer supports them too.
+    // Consensus and health check messages are never compressed. Empty disables compression.
+    pub compression_codecs: Vec<CompressionCodec>,
     // Interval to send healthcheck pings to peers
     pub ping_interval_ms: u64,
     // Timeout until a healthcheck ping is rejected
@@ -97,6 +104,8 @@ pub struct NetworkConfig {
     pub inbound_rate_limit_config: Option<RateLimitConfig>,
     // Outbound rate limiting configuration, if not specified, no rate limiting
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
+    // Per peer limits on the outbound bandwidth of each traffic class
+    pub outbound_qos_config: OutboundQosConfig,
 }
 
 impl Default for NetworkConfig {
@@ -118,6 +127,8 @@ impl NetworkConfig {
             seeds: PeerSet::default(),
             max_frame_size: MAX_FRAME_SIZE,
             enable_proxy_protocol: false,
+            transport_protocol: TransportProtocol::default(),
+            compression_codecs: Vec::new(),
             max_connection_delay_ms: MAX_CONNECTION_DELAY_MS,
             connectivity_check_interval_ms: CONNECTIVITY_CHECK_INTERVAL_MS,
             network_channel_size: NETWORK_CHANNEL_SIZE,
@@ -130,6 +141,7 @@ impl NetworkConfig {
             max_inbound_connections: MAX_INBOUND_CONNECTIONS,
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
+            outbound_qos_config: OutboundQosConfig::default(),
         };
         config.prepare_identity();
         config
@@ -202,6 +214,12 @@ impl NetworkConfig {
                 .ok_or_else(|| Error::InvariantViolation("No local IP".to_string()))?;
         }
 
+        if self.enable_proxy_protocol && self.transport_protocol == TransportProtocol::Quic {
+            return Err(Error::InvariantViolation(
+                "Proxy protocol is not supported over QUIC".to_string(),
+            ));
+        }
+
         self.prepare_identity();
         Ok(())
     }
@@ -303,6 +321,80 @@ impl NetworkConfig {
         }
         Ok(())
     }
+
+    /// Returns the seeds merged with the legacy `seed_addrs`, all keys included.
+    pub fn merge_seeds(&self) -> Result<PeerSet, Error> {
+        self.verify_seeds()?;
+        let mut seeds = self.seeds.clone();
+
+        // Merge old seed configuration with new seed configuration
+        // TODO(gnazario): Once fully migrated, remove `seed_addrs`
+        for (peer_id, addrs) in self.seed_addrs.iter() {
+            let peer = Peer::from_addrs(PeerRole::ValidatorFullNode, addrs.clone());
+            match seeds.get_mut(peer_id) {
+                Some(seed) => seed.extend(peer)?,
+                None => {
+                    seeds.insert(*peer_id, peer);
+                }
+            }
+        }
+
+        // Pull public keys out of addresses
+        seeds.values_mut().for_each(
+            |Peer {
+                 addresses, keys, ..
+             }| {
+                addresses
+                    .iter()
+                    .filter_map(NetworkAddress::find_noise_proto)
+                    .for_each(|pubkey| {
+                        keys.insert(pubkey);
+                    });
+            },
+        );
+        Ok(seeds)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportProtocol {
+    Tcp,
+    Quic,
+}
+
+impl Default for TransportProtocol {
+    fn default() -> Self {
+        TransportProtocol::Tcp
+    }
+}
+
+/// The codecs network messages can be compressed with. The discriminants are the bits the codecs
+/// are advertised with during the handshake, so they must not change.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionCodec {
+    Lz4 = 0,
+    Zstd = 1,
+}
+
+impl CompressionCodec {
+    pub fn all() -> &'static [CompressionCodec] {
+        &[CompressionCodec::Lz4, CompressionCodec::Zstd]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompressionCodec::Lz4 => "lz4",
+            CompressionCodec::Zstd => "zstd",
+        }
+    }
+}
+
+impl fmt::Display for CompressionCodec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -310,9 +402,22 @@ impl NetworkConfig {
 pub enum DiscoveryMethod {
     Onchain,
     File(PathBuf, Duration),
+    Dns(DnsDiscovery),
     None,
 }
 
+/// Resolves seed peers from the signed TXT records of a domain.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DnsDiscovery {
+    /// The domain whose TXT records list the seed peers
+    pub domain: String,
+    /// The key the TXT records must be signed with
+    pub verification_key: Ed25519PublicKey,
+    /// How often to resolve the seed peers again
+    pub interval_secs: u64,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case", tag = "type")]
 pub enum Identity {
@@ -389,6 +494,53 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// The classes outbound messages are scheduled by, from the highest to the lowest priority.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrafficClass {
+    Consensus,
+    StateSync,
+    Mempool,
+    Other,
+}
+
+impl TrafficClass {
+    pub fn all() -> &'static [TrafficClass] {
+        &[
+            TrafficClass::Consensus,
+            TrafficClass::StateSync,
+            TrafficClass::Mempool,
+            TrafficClass::Other,
+        ]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TrafficClass::Consensus => "consensus",
+            TrafficClass::StateSync => "state_sync",
+            TrafficClass::Mempool => "mempool",
+            TrafficClass::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for TrafficClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Limits on the bandwidth used to send messages to a single peer. Queued messages are always
+/// sent by priority of their traffic class, so these only have to cap the lower classes.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutboundQosConfig {
+    /// Maximum number of bytes/s sent to a peer across all traffic classes
+    pub peer_byte_rate: Option<usize>,
+    /// Maximum number of bytes/s sent to a peer for each traffic class, others are unlimited
+    pub class_byte_rates: BTreeMap<TrafficClass, usize>,
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
@@ -0,0 +1,33 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// The checks run by the node at startup, before it opens its DB and starts its services.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreflightConfig {
+    /// Whether to check the resources of the host (disk space, open files limit). The consistency
+    /// of the config is always checked.
+    pub check_resources: bool,
+    /// Whether the node refuses to start when the resources of the host are short, instead of
+    /// only warning about it.
+    pub enforce_resources: bool,
+    /// The free space required on the disk of the DB.
+    pub min_free_disk_space_bytes: u64,
+    /// The soft limit on open files required of the process. It must leave room for the sockets
+    /// of the node on top of the files of RocksDB, see `RocksdbConfig::max_open_files`.
+    pub min_open_files_limit: u64,
+}
+
+impl Default for PreflightConfig {
+    fn default() -> Self {
+        Self {
+            check_resources: true,
+            enforce_resources: false,
+            min_free_disk_space_bytes: 50 * 1024 * 1024 * 1024,
+            // The LimitNOFILE of the deployments
+            min_open_files_limit: 65536,
+        }
+    }
+}
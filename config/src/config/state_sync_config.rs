@@ -71,6 +71,7 @@ pub enum BootstrappingMode {
 pub enum ContinuousSyncingMode {
     ExecuteTransactions,     // Executes transactions to stay up-to-date
     ApplyTransactionOutputs, // Applies transaction outputs to stay up-to-date
+    Adaptive, // Applies transaction outputs when close to the peers, executes transactions otherwise
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -84,6 +85,8 @@ pub struct StateSyncDriverConfig {
     pub max_consecutive_stream_notifications: u64, // The max number of notifications to process per driver loop
     pub max_pending_data_chunks: u64, // The max number of data chunks pending execution or commit
     pub max_stream_wait_time_ms: u64, // The max time (ms) to wait for a data stream notification
+    pub max_lag_to_apply_outputs: u64, // The max num of versions behind the peers to apply outputs in adaptive mode
+    pub output_verification_sample_rate: u64, // Re-execute 1 in N chunks of outputs to verify them (0 to disable)
 }
 
 /// The default state sync driver config will be the one that gets (and keeps)
@@ -99,6 +102,8 @@ impl Default for StateSyncDriverConfig {
             max_consecutive_stream_notifications: 10,
             max_pending_data_chunks: 100,
             max_stream_wait_time_ms: 5000,
+            max_lag_to_apply_outputs: 5000,
+            output_verification_sample_rate: 0,
         }
     }
 }
@@ -108,6 +113,8 @@ impl Default for StateSyncDriverConfig {
 pub struct StorageServiceConfig {
     pub max_account_states_chunk_sizes: u64, // Max num of accounts per chunk
     pub max_concurrent_requests: u64,        // Max num of concurrent storage server tasks
+    pub max_concurrent_chunk_requests: u64,  // Max num of concurrent chunk requests (of all peers)
+    pub max_concurrent_requests_per_peer: u64, // Max num of concurrent requests of a single peer
     pub max_epoch_chunk_size: u64,           // Max num of epoch ending ledger infos per chunk
     pub max_lru_cache_size: u64,             // Max num of items in the lru cache before eviction
     pub max_network_channel_size: u64,       // Max num of pending network messages
@@ -122,6 +129,8 @@ impl Default for StorageServiceConfig {
         Self {
             max_account_states_chunk_sizes: 1000,
             max_concurrent_requests: 4000,
+            max_concurrent_chunk_requests: 100,
+            max_concurrent_requests_per_peer: 20,
             max_epoch_chunk_size: 100,
             max_lru_cache_size: 100,
             max_network_channel_size: 4000,
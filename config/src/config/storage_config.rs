@@ -46,6 +46,9 @@ pub struct StorageConfig {
     pub timeout_ms: u64,
     /// Rocksdb-specific configurations
     pub rocksdb_config: RocksdbConfig,
+    /// Directory of the cold store holding the ledger history moved out of the main DB, typically
+    /// on a cheaper disk. None if the node has no cold store.
+    pub cold_storage_dir: Option<PathBuf>,
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: StoragePrunerConfig = StoragePrunerConfig {
@@ -105,6 +108,7 @@ impl Default for StorageConfig {
             // Default read/write/connection timeout, in milliseconds
             timeout_ms: 30_000,
             rocksdb_config: RocksdbConfig::default(),
+            cold_storage_dir: None,
         }
     }
 }
@@ -0,0 +1,155 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block::Block,
+    block_data::{BlockData, BlockType},
+    common::{Author, Payload, Round},
+    proposal_msg::ProposalMsg,
+    quorum_cert::QuorumCert,
+    sync_info::SyncInfo,
+};
+use anyhow::ensure;
+use aptos_crypto::{ed25519::Ed25519Signature, hash::HashValue};
+use serde::{Deserialize, Serialize};
+use short_hex_str::AsShortHexStr;
+use std::fmt;
+
+/// A ProposalMsg whose payload is replaced by the hashes of its transactions.
+///
+/// The other validators usually have the transactions of a proposal in their mempool already, so
+/// sending the hashes instead of the transactions cuts the bandwidth of the proposer. Receivers
+/// rebuild the ProposalMsg from the transactions found locally and the missing ones retrieved from
+/// the proposer, and verify it like any proposal: the signature of the block covers the payload.
+/// When the payload cannot be resolved, the full block is retrieved from the proposer by its id.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CompactProposalMsg {
+    block_id: HashValue,
+    author: Author,
+    round: Round,
+    timestamp_usecs: u64,
+    quorum_cert: QuorumCert,
+    signature: Ed25519Signature,
+    /// The hashes of the transactions of the payload, in order.
+    payload_hashes: Vec<HashValue>,
+    sync_info: SyncInfo,
+}
+
+impl CompactProposalMsg {
+    /// Returns the compact form of `proposal_msg`, or None if its block has no payload to compact.
+    pub fn from_proposal(proposal_msg: &ProposalMsg) -> Option<Self> {
+        let block = proposal_msg.proposal();
+        match block.block_data().block_type() {
+            BlockType::Proposal { payload, author } if !payload.is_empty() => Some(Self {
+                block_id: block.id(),
+                author: *author,
+                round: block.round(),
+                timestamp_usecs: block.timestamp_usecs(),
+                quorum_cert: block.quorum_cert().clone(),
+                signature: block.signature()?.clone(),
+                payload_hashes: payload
+                    .iter()
+                    .map(|txn| txn.clone().committed_hash())
+                    .collect(),
+                sync_info: proposal_msg.sync_info().clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn block_id(&self) -> HashValue {
+        self.block_id
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.quorum_cert.certified_block().epoch()
+    }
+
+    pub fn round(&self) -> Round {
+        self.round
+    }
+
+    pub fn proposer(&self) -> Author {
+        self.author
+    }
+
+    pub fn payload_hashes(&self) -> &[HashValue] {
+        &self.payload_hashes
+    }
+
+    pub fn sync_info(&self) -> &SyncInfo {
+        &self.sync_info
+    }
+
+    /// Rebuilds the ProposalMsg with `payload`, which must hold the transactions of
+    /// `payload_hashes` in order: any other payload yields a block of another id and is rejected.
+    pub fn into_proposal(self, payload: Payload) -> anyhow::Result<ProposalMsg> {
+        ensure!(
+            payload.len() == self.payload_hashes.len(),
+            "Payload of {} txns for a compact proposal of {} txns",
+            payload.len(),
+            self.payload_hashes.len()
+        );
+        let block_data = BlockData::new_proposal(
+            payload,
+            self.author,
+            self.round,
+            self.timestamp_usecs,
+            self.quorum_cert,
+        );
+        let block = Block::new_proposal_from_block_data_and_signature(block_data, self.signature);
+        ensure!(
+            block.id() == self.block_id,
+            "Payload does not match the block {} of {}",
+            self.block_id,
+            self
+        );
+        Ok(ProposalMsg::new(block, self.sync_info))
+    }
+}
+
+impl fmt::Display for CompactProposalMsg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[compact proposal of round {} with {} txns from {}]",
+            self.round,
+            self.payload_hashes.len(),
+            self.author.short_str()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::block_test_utils::{certificate_for_genesis, random_payload};
+    use aptos_types::validator_signer::ValidatorSigner;
+
+    #[test]
+    fn test_compact_proposal_round_trip() {
+        let signer = ValidatorSigner::random(None);
+        let quorum_cert = certificate_for_genesis();
+        let payload = random_payload(5);
+        let block = Block::new_proposal(payload.clone(), 1, 1, quorum_cert.clone(), &signer);
+        let sync_info = SyncInfo::new(quorum_cert.clone(), quorum_cert.clone(), None, None);
+        let proposal_msg = ProposalMsg::new(block, sync_info.clone());
+
+        let compact = CompactProposalMsg::from_proposal(&proposal_msg).unwrap();
+        assert_eq!(compact.payload_hashes().len(), 5);
+        assert_eq!(compact.proposer(), signer.author());
+        assert_eq!(compact.block_id(), proposal_msg.proposal().id());
+        assert!(compact
+            .clone()
+            .into_proposal(payload[1..].to_vec())
+            .is_err());
+        let mut reordered = payload.clone();
+        reordered.swap(0, 1);
+        assert!(compact.clone().into_proposal(reordered).is_err());
+        assert_eq!(compact.into_proposal(payload).unwrap(), proposal_msg);
+
+        let empty_block = Block::new_proposal(vec![], 1, 1, quorum_cert, &signer);
+        let empty_proposal_msg = ProposalMsg::new(empty_block, sync_info);
+        assert!(CompactProposalMsg::from_proposal(&empty_proposal_msg).is_none());
+    }
+}
@@ -7,9 +7,11 @@ pub mod block;
 pub mod block_data;
 pub mod block_retrieval;
 pub mod common;
+pub mod compact_proposal_msg;
 pub mod epoch_retrieval;
 pub mod executed_block;
 pub mod experimental;
+pub mod payload_retrieval;
 pub mod proposal_msg;
 pub mod quorum_cert;
 pub mod safety_data;
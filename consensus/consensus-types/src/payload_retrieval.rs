@@ -0,0 +1,87 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::ensure;
+use aptos_crypto::hash::HashValue;
+use aptos_types::transaction::SignedTransaction;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// A request is sent for the transactions of a single block that are missing locally, so it is
+// bounded by the block size limit.
+pub const MAX_TXNS_PER_REQUEST: usize = 10_000;
+
+/// RPC to get the transactions of a compact proposal that are missing locally from its proposer.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PayloadRetrievalRequest {
+    hashes: Vec<HashValue>,
+}
+
+impl PayloadRetrievalRequest {
+    pub fn new(hashes: Vec<HashValue>) -> Self {
+        Self { hashes }
+    }
+
+    pub fn hashes(&self) -> &[HashValue] {
+        &self.hashes
+    }
+}
+
+impl fmt::Display for PayloadRetrievalRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[PayloadRetrievalRequest for {} txns]",
+            self.hashes.len()
+        )
+    }
+}
+
+/// Carries the requested transactions, in the order of the request. The transactions the
+/// responder does not have anymore are `None`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PayloadRetrievalResponse {
+    transactions: Vec<Option<SignedTransaction>>,
+}
+
+impl PayloadRetrievalResponse {
+    pub fn new(transactions: Vec<Option<SignedTransaction>>) -> Self {
+        Self { transactions }
+    }
+
+    /// Checks that the response answers `retrieval_request` and returns its transactions.
+    pub fn verify_and_take_transactions(
+        self,
+        retrieval_request: &PayloadRetrievalRequest,
+    ) -> anyhow::Result<Vec<Option<SignedTransaction>>> {
+        ensure!(
+            self.transactions.len() == retrieval_request.hashes().len(),
+            "wrong number of transactions returned, expect {}, get {}",
+            retrieval_request.hashes().len(),
+            self.transactions.len(),
+        );
+        for (hash, transaction) in retrieval_request.hashes().iter().zip(&self.transactions) {
+            if let Some(transaction) = transaction {
+                let returned_hash = transaction.clone().committed_hash();
+                ensure!(
+                    returned_hash == *hash,
+                    "unexpected transaction returned, expect {}, get {}",
+                    hash,
+                    returned_hash
+                );
+            }
+        }
+        Ok(self.transactions)
+    }
+}
+
+impl fmt::Display for PayloadRetrievalResponse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "[PayloadRetrievalResponse with {} of {} txns]",
+            self.transactions.iter().flatten().count(),
+            self.transactions.len()
+        )
+    }
+}
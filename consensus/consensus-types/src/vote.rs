@@ -6,14 +6,17 @@ use crate::{
     vote_data::VoteData,
 };
 use anyhow::{ensure, Context};
-use aptos_crypto::{ed25519::Ed25519Signature, hash::CryptoHash};
+use aptos_crypto::{ed25519::Ed25519Signature, hash::CryptoHash, HashValue};
 use aptos_types::{
     ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
 use serde::{Deserialize, Serialize};
 use short_hex_str::AsShortHexStr;
-use std::fmt::{Debug, Display, Formatter};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter},
+};
 
 /// Vote is the struct that is ultimately sent by the voter in response for
 /// receiving a proposal.
@@ -171,14 +174,7 @@ impl Vote {
     /// Verifies that the consensus data hash of LedgerInfo corresponds to the vote info,
     /// and then verifies the signature.
     pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
-        ensure!(
-            self.ledger_info.consensus_data_hash() == self.vote_data.hash(),
-            "Vote's hash mismatch with LedgerInfo"
-        );
-        ensure!(
-            self.timeout_signature.is_none() || self.two_chain_timeout.is_none(),
-            "Only one timeout should exist"
-        );
+        self.verify_well_formed(validator)?;
         validator
             .verify(self.author(), &self.ledger_info, &self.signature)
             .context("Failed to verify Vote")?;
@@ -188,18 +184,159 @@ impl Vote {
                 .context("Failed to verify Timeout Vote")?;
         }
         if let Some((timeout, signature)) = &self.two_chain_timeout {
+            validator
+                .verify(self.author(), &timeout.signing_format(), signature)
+                .context("Failed to verify 2-chain timeout signature")?;
+        }
+        Ok(())
+    }
+
+    /// Verifies `votes` like `verify` does and returns the result of each vote, in order.
+    ///
+    /// The signatures of the votes signing the same message, i.e. voting for the same block or
+    /// timing out the same round, are batch verified. If a batch fails, its signatures are verified
+    /// one by one so that only the votes with an invalid signature are rejected.
+    pub fn batch_verify(votes: &[&Vote], validator: &ValidatorVerifier) -> Vec<anyhow::Result<()>> {
+        let mut results: Vec<_> = votes
+            .iter()
+            .map(|vote| vote.verify_well_formed(validator))
+            .collect();
+        batch_verify_signatures(
+            votes,
+            validator,
+            &mut results,
+            |vote| Some((vote.ledger_info.clone(), &vote.signature)),
+            "Failed to verify Vote",
+        );
+        batch_verify_signatures(
+            votes,
+            validator,
+            &mut results,
+            |vote| {
+                vote.timeout_signature
+                    .as_ref()
+                    .map(|signature| (vote.generate_timeout(), signature))
+            },
+            "Failed to verify Timeout Vote",
+        );
+        batch_verify_signatures(
+            votes,
+            validator,
+            &mut results,
+            |vote| {
+                vote.two_chain_timeout
+                    .as_ref()
+                    .map(|(timeout, signature)| (timeout.signing_format(), signature))
+            },
+            "Failed to verify 2-chain timeout signature",
+        );
+        results
+    }
+
+    /// Checks everything but the signatures of the vote itself.
+    fn verify_well_formed(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        ensure!(
+            self.ledger_info.consensus_data_hash() == self.vote_data.hash(),
+            "Vote's hash mismatch with LedgerInfo"
+        );
+        ensure!(
+            self.timeout_signature.is_none() || self.two_chain_timeout.is_none(),
+            "Only one timeout should exist"
+        );
+        if let Some((timeout, _)) = &self.two_chain_timeout {
             ensure!(
                 (timeout.epoch(), timeout.round())
                     == (self.epoch(), self.vote_data.proposed().round()),
                 "2-chain timeout has different (epoch, round) than Vote"
             );
             timeout.verify(validator)?;
-            validator
-                .verify(self.author(), &timeout.signing_format(), signature)
-                .context("Failed to verify 2-chain timeout signature")?;
         }
         // Let us verify the vote data as well
         self.vote_data().verify()?;
         Ok(())
     }
 }
+
+/// Batch verifies the signatures returned by `signed_message` for the votes that are still valid,
+/// grouped by signed message, and records the failures in `results`.
+fn batch_verify_signatures<'a, T, F>(
+    votes: &[&'a Vote],
+    validator: &ValidatorVerifier,
+    results: &mut [anyhow::Result<()>],
+    signed_message: F,
+    error_context: &'static str,
+) where
+    T: CryptoHash + Serialize,
+    F: Fn(&'a Vote) -> Option<(T, &'a Ed25519Signature)>,
+{
+    let mut signatures_by_message: HashMap<
+        HashValue,
+        (T, Vec<(usize, Author, &Ed25519Signature)>),
+    > = HashMap::new();
+    for (index, vote) in votes.iter().enumerate() {
+        if results[index].is_err() {
+            continue;
+        }
+        if let Some((message, signature)) = signed_message(*vote) {
+            signatures_by_message
+                .entry(message.hash())
+                .or_insert_with(|| (message, vec![]))
+                .1
+                .push((index, vote.author(), signature));
+        }
+    }
+    for (message, signatures) in signatures_by_message.into_values() {
+        let authors_and_signatures: Vec<_> = signatures
+            .iter()
+            .map(|(_, author, signature)| (*author, *signature))
+            .collect();
+        let verified = validator.batch_verify_signatures(&message, &authors_and_signatures);
+        for ((index, _, _), result) in signatures.into_iter().zip(verified) {
+            if let Err(error) = result {
+                results[index] = Err(anyhow::Error::new(error).context(error_context));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Round;
+    use aptos_types::{block_info::BlockInfo, validator_verifier::random_validator_verifier};
+
+    fn vote(signer: &ValidatorSigner, round: Round) -> Vote {
+        let vote_data = VoteData::new(BlockInfo::random(round), BlockInfo::random(round - 1));
+        let ledger_info_placeholder = LedgerInfo::new(BlockInfo::empty(), HashValue::zero());
+        Vote::new(vote_data, signer.author(), ledger_info_placeholder, signer)
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let (signers, validator) = random_validator_verifier(4, None, false);
+        let mut votes: Vec<_> = signers.iter().map(|signer| vote(signer, 2)).collect();
+        votes.push(vote(&signers[0], 3));
+        let mut timeout_vote = vote(&signers[1], 3);
+        timeout_vote.add_timeout_signature(signers[1].sign(&timeout_vote.generate_timeout()));
+        votes.push(timeout_vote);
+        // A copy of the vote of another validator, batched with the valid votes of round 2.
+        votes.push(Vote::new_with_signature(
+            votes[1].vote_data().clone(),
+            signers[2].author(),
+            votes[1].ledger_info().clone(),
+            votes[1].signature().clone(),
+        ));
+
+        let results = Vote::batch_verify(&votes.iter().collect::<Vec<_>>(), &validator);
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.is_ok())
+                .collect::<Vec<_>>(),
+            vec![true, true, true, true, true, true, false]
+        );
+        for (vote, result) in votes.iter().zip(results) {
+            assert_eq!(vote.verify(&validator).is_ok(), result.is_ok());
+        }
+    }
+}
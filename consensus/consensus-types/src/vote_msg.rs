@@ -50,6 +50,31 @@ impl VoteMsg {
     }
 
     pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        self.verify_sync_info_consistency()?;
+        // We're not verifying SyncInfo here yet: we are going to verify it only in case we need
+        // it. This way we avoid verifying O(n) SyncInfo messages while aggregating the votes
+        // (O(n^2) signature verifications).
+        self.vote().verify(validator)
+    }
+
+    /// Verifies `vote_msgs` like `verify` does and returns the result of each message, in order.
+    /// The signatures of the votes are batch verified, see `Vote::batch_verify`.
+    pub fn batch_verify(
+        vote_msgs: &[&VoteMsg],
+        validator: &ValidatorVerifier,
+    ) -> Vec<anyhow::Result<()>> {
+        let votes: Vec<_> = vote_msgs.iter().map(|vote_msg| vote_msg.vote()).collect();
+        vote_msgs
+            .iter()
+            .zip(Vote::batch_verify(&votes, validator))
+            .map(|(vote_msg, vote_result)| {
+                vote_msg.verify_sync_info_consistency()?;
+                vote_result
+            })
+            .collect()
+    }
+
+    fn verify_sync_info_consistency(&self) -> anyhow::Result<()> {
         ensure!(
             self.vote().epoch() == self.sync_info.epoch(),
             "VoteMsg has different epoch"
@@ -64,9 +89,6 @@ impl VoteMsg {
                 "2-chain Timeout hqc should be less or equal than the sync info hqc"
             );
         }
-        // We're not verifying SyncInfo here yet: we are going to verify it only in case we need
-        // it. This way we avoid verifying O(n) SyncInfo messages while aggregating the votes
-        // (O(n^2) signature verifications).
-        self.vote().verify(validator)
+        Ok(())
     }
 }
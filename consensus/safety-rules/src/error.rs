@@ -55,6 +55,8 @@ pub enum Error {
     WaypointOutOfDate(u64, u64, u64, u64),
     #[error("Invalid Timeout: {0}")]
     InvalidTimeout(String),
+    #[error("Refusing to restore safety rules storage: {0}")]
+    UnsafeRestore(String),
 }
 
 impl From<serde_json::Error> for Error {
@@ -20,9 +20,14 @@ mod t_safety_rules;
 mod thread;
 
 pub use crate::{
-    consensus_state::ConsensusState, error::Error,
-    persistent_safety_storage::PersistentSafetyStorage, process::Process,
-    safety_rules::SafetyRules, safety_rules_manager::SafetyRulesManager,
+    consensus_state::ConsensusState,
+    error::Error,
+    persistent_safety_storage::{
+        check_safe_to_restore, PersistentSafetyStorage, SafetyStorageSnapshot,
+    },
+    process::Process,
+    safety_rules::SafetyRules,
+    safety_rules_manager::SafetyRulesManager,
     t_safety_rules::TSafetyRules,
 };
 
@@ -9,13 +9,14 @@ use crate::{
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
     hash::CryptoHash,
+    PrivateKey,
 };
 use aptos_global_constants::{CONSENSUS_KEY, EXECUTION_KEY, OWNER_ACCOUNT, SAFETY_DATA, WAYPOINT};
 use aptos_logger::prelude::*;
 use aptos_secure_storage::{CryptoStorage, KVStorage, Storage};
 use aptos_types::waypoint::Waypoint;
 use consensus_types::{common::Author, safety_data::SafetyData};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// SafetyRules needs an abstract storage interface to act as a common utility for storing
 /// persistent data to local disk, cloud, secrets managers, or even memory (for tests)
@@ -185,12 +186,136 @@ impl PersistentSafetyStorage {
         Ok(())
     }
 
+    /// Reads everything SafetyRules keeps in storage so that it can be moved to another
+    /// backend. The node must be stopped first, otherwise the snapshot may be behind the votes
+    /// it casts afterwards. Only the current version of the consensus key is exported.
+    pub fn export_snapshot(&mut self) -> Result<SafetyStorageSnapshot, Error> {
+        Ok(SafetyStorageSnapshot {
+            author: self.author()?,
+            safety_data: self.safety_data()?,
+            waypoint: self.waypoint()?,
+            consensus_key: self.internal_store.export_private_key(CONSENSUS_KEY)?,
+            execution_key: self.internal_store.export_private_key(EXECUTION_KEY)?,
+        })
+    }
+
+    /// Writes `snapshot` to `internal_store`, which may be empty or hold data from a previous
+    /// run of the same validator. The restore is refused if it belongs to another validator,
+    /// replaces an existing key, or rolls back the safety data already in `internal_store`, as
+    /// any of these could lead to voting twice in a round.
+    pub fn import_snapshot(
+        mut internal_store: Storage,
+        snapshot: SafetyStorageSnapshot,
+        enable_cached_safety_data: bool,
+    ) -> Result<Self, Error> {
+        match internal_store.get::<Author>(OWNER_ACCOUNT) {
+            Ok(response) if response.value != snapshot.author => {
+                return Err(Error::UnsafeRestore(format!(
+                    "storage belongs to {}, snapshot belongs to {}",
+                    response.value, snapshot.author
+                )));
+            }
+            Ok(_) | Err(aptos_secure_storage::Error::KeyNotSet(_)) => (),
+            Err(e) => return Err(e.into()),
+        }
+        match internal_store.get::<SafetyData>(SAFETY_DATA) {
+            Ok(response) => check_safe_to_restore(&response.value, &snapshot.safety_data)?,
+            Err(aptos_secure_storage::Error::KeyNotSet(_)) => (),
+            Err(e) => return Err(e.into()),
+        }
+        let waypoint = match internal_store.get::<Waypoint>(WAYPOINT) {
+            Ok(response) if response.value.version() > snapshot.waypoint.version() => {
+                response.value
+            }
+            Ok(_) | Err(aptos_secure_storage::Error::KeyNotSet(_)) => snapshot.waypoint,
+            Err(e) => return Err(e.into()),
+        };
+
+        Self::import_key(&mut internal_store, CONSENSUS_KEY, snapshot.consensus_key)?;
+        Self::import_key(&mut internal_store, EXECUTION_KEY, snapshot.execution_key)?;
+        internal_store.set(OWNER_ACCOUNT, snapshot.author)?;
+
+        let mut persistent_safety_storage = Self::new(internal_store, enable_cached_safety_data);
+        persistent_safety_storage.set_safety_data(snapshot.safety_data)?;
+        persistent_safety_storage.set_waypoint(&waypoint)?;
+        Ok(persistent_safety_storage)
+    }
+
+    /// Imports `key` unless the same key is already present.
+    fn import_key(
+        internal_store: &mut Storage,
+        name: &str,
+        key: Ed25519PrivateKey,
+    ) -> Result<(), Error> {
+        match internal_store.get_public_key(name) {
+            Ok(response) if response.public_key == key.public_key() => Ok(()),
+            Ok(_) => Err(Error::UnsafeRestore(format!(
+                "storage holds a different {}",
+                name
+            ))),
+            Err(aptos_secure_storage::Error::KeyNotSet(_)) => {
+                Ok(internal_store.import_private_key(name, key)?)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
     #[cfg(any(test, feature = "testing"))]
     pub fn internal_store(&mut self) -> &mut Storage {
         &mut self.internal_store
     }
 }
 
+/// The contents of a SafetyRules storage, see `PersistentSafetyStorage::export_snapshot`.
+#[derive(Deserialize, Serialize)]
+pub struct SafetyStorageSnapshot {
+    pub author: Author,
+    pub safety_data: SafetyData,
+    pub waypoint: Waypoint,
+    pub consensus_key: Ed25519PrivateKey,
+    pub execution_key: Ed25519PrivateKey,
+}
+
+/// Verifies that replacing `current` by `restored` does not move SafetyRules back to an earlier
+/// epoch or round, after which it could sign a second vote or timeout for a round it already
+/// signed.
+pub fn check_safe_to_restore(current: &SafetyData, restored: &SafetyData) -> Result<(), Error> {
+    if restored.epoch < current.epoch {
+        return Err(Error::UnsafeRestore(format!(
+            "restored epoch {} is behind current epoch {}",
+            restored.epoch, current.epoch
+        )));
+    }
+    if restored.epoch > current.epoch {
+        return Ok(());
+    }
+    for (name, restored_round, current_round) in [
+        (
+            "last voted round",
+            restored.last_voted_round,
+            current.last_voted_round,
+        ),
+        (
+            "preferred round",
+            restored.preferred_round,
+            current.preferred_round,
+        ),
+        (
+            "one-chain round",
+            restored.one_chain_round,
+            current.one_chain_round,
+        ),
+    ] {
+        if restored_round < current_round {
+            return Err(Error::UnsafeRestore(format!(
+                "restored {} {} is behind current {} {} in epoch {}",
+                name, restored_round, name, current_round, current.epoch
+            )));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -224,6 +349,123 @@ mod tests {
         }
     }
 
+    fn new_safety_storage(author: Author) -> PersistentSafetyStorage {
+        PersistentSafetyStorage::initialize(
+            Storage::from(InMemoryStorage::new()),
+            author,
+            Ed25519PrivateKey::generate_for_testing(),
+            Ed25519PrivateKey::generate_for_testing(),
+            Waypoint::default(),
+            true,
+        )
+    }
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let author = Author::random();
+        let mut source = new_safety_storage(author);
+        source
+            .set_safety_data(SafetyData::new(3, 7, 5, 6, None))
+            .unwrap();
+        let execution_key = source.execution_public_key().unwrap();
+
+        let snapshot = source.export_snapshot().unwrap();
+        let serialized = serde_json::to_vec(&snapshot).unwrap();
+        let snapshot: SafetyStorageSnapshot = serde_json::from_slice(&serialized).unwrap();
+
+        let mut destination = PersistentSafetyStorage::import_snapshot(
+            Storage::from(InMemoryStorage::new()),
+            snapshot,
+            true,
+        )
+        .unwrap();
+        assert_eq!(destination.author().unwrap(), author);
+        assert_eq!(
+            destination.safety_data().unwrap(),
+            SafetyData::new(3, 7, 5, 6, None)
+        );
+        assert_eq!(destination.waypoint().unwrap(), Waypoint::default());
+        assert_eq!(destination.execution_public_key().unwrap(), execution_key);
+        assert_eq!(
+            destination
+                .export_snapshot()
+                .unwrap()
+                .consensus_key
+                .public_key(),
+            source.export_snapshot().unwrap().consensus_key.public_key()
+        );
+    }
+
+    #[test]
+    fn test_import_over_same_validator() {
+        let mut source = new_safety_storage(Author::random());
+        source
+            .set_safety_data(SafetyData::new(2, 4, 3, 3, None))
+            .unwrap();
+        let stale_snapshot = source.export_snapshot().unwrap();
+        source
+            .set_safety_data(SafetyData::new(2, 9, 8, 8, None))
+            .unwrap();
+        let mut destination = PersistentSafetyStorage::import_snapshot(
+            Storage::from(InMemoryStorage::new()),
+            source.export_snapshot().unwrap(),
+            false,
+        )
+        .unwrap();
+
+        // Restoring older data over storage that has already voted further is rejected.
+        let internal_store = std::mem::replace(
+            destination.internal_store(),
+            Storage::from(InMemoryStorage::new()),
+        );
+        let error = PersistentSafetyStorage::import_snapshot(internal_store, stale_snapshot, false)
+            .err()
+            .unwrap();
+        assert!(matches!(error, Error::UnsafeRestore(_)));
+
+        // Re-importing the latest data is a no-op.
+        let internal_store = std::mem::replace(
+            destination.internal_store(),
+            Storage::from(InMemoryStorage::new()),
+        );
+        assert!(PersistentSafetyStorage::import_snapshot(
+            internal_store,
+            source.export_snapshot().unwrap(),
+            false
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_import_over_other_validator() {
+        let mut source = new_safety_storage(Author::random());
+        let mut other = new_safety_storage(Author::random());
+        let internal_store = std::mem::replace(
+            other.internal_store(),
+            Storage::from(InMemoryStorage::new()),
+        );
+        let error = PersistentSafetyStorage::import_snapshot(
+            internal_store,
+            source.export_snapshot().unwrap(),
+            false,
+        )
+        .err()
+        .unwrap();
+        assert!(matches!(error, Error::UnsafeRestore(_)));
+    }
+
+    #[test]
+    fn test_check_safe_to_restore() {
+        let current = SafetyData::new(5, 10, 8, 9, None);
+        assert!(check_safe_to_restore(&current, &current).is_ok());
+        assert!(check_safe_to_restore(&current, &SafetyData::new(5, 11, 8, 9, None)).is_ok());
+        assert!(check_safe_to_restore(&current, &SafetyData::new(6, 0, 0, 0, None)).is_ok());
+        assert!(check_safe_to_restore(&current, &SafetyData::new(4, 20, 20, 20, None)).is_err());
+        assert!(check_safe_to_restore(&current, &SafetyData::new(5, 9, 8, 9, None)).is_err());
+        assert!(check_safe_to_restore(&current, &SafetyData::new(5, 10, 7, 9, None)).is_err());
+        assert!(check_safe_to_restore(&current, &SafetyData::new(5, 10, 8, 8, None)).is_err());
+    }
+
     fn test_safety_data_counters(safety_storage: &mut PersistentSafetyStorage) {
         let safety_data = safety_storage.safety_data().unwrap();
         assert_eq!(safety_data.epoch, 1);
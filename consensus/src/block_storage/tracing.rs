@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::counters;
+use aptos_crypto::HashValue;
 use aptos_infallible::duration_since_epoch;
-use std::time::Duration;
+use consensus_types::{block::Block, common::Round};
+use std::time::{Duration, Instant};
+use tracing::{field, Span};
 
 pub struct BlockStage;
 
@@ -27,3 +30,77 @@ pub fn observe_block(timestamp: u64, stage: &'static str) {
             .observe(t.as_secs_f64());
     }
 }
+
+/// The stages a block goes through on the node, from pulling its payload to notifying the other
+/// components of its commit.
+pub struct PipelineStage;
+
+impl PipelineStage {
+    pub const PULL_PAYLOAD: &'static str = "pull_payload";
+    pub const EXECUTE: &'static str = "execute";
+    pub const NOTIFY_FAILED_TXNS: &'static str = "notify_failed_txns";
+    // Storage writes the state and the ledger of the committed blocks in a single batch.
+    pub const COMMIT: &'static str = "commit";
+    pub const NOTIFY_COMMIT: &'static str = "notify_commit";
+}
+
+/// Times a stage of the block pipeline from its creation until it is dropped, into the
+/// `aptos_consensus_block_pipeline_stage_seconds` histogram and a debug event carrying the
+/// duration. The stage runs in a `block_pipeline` span recording the epoch, the round and, once
+/// known, the id of the block, so that the logs of a slow block can be broken down by stage.
+pub struct PipelineStageTimer {
+    stage: &'static str,
+    span: Span,
+    start: Instant,
+}
+
+impl PipelineStageTimer {
+    pub fn for_round(stage: &'static str, epoch: u64, round: Round) -> Self {
+        let span = tracing::info_span!(
+            "block_pipeline",
+            stage,
+            epoch,
+            round,
+            block_id = field::Empty
+        );
+        Self {
+            stage,
+            span,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn for_block(stage: &'static str, block: &Block) -> Self {
+        let timer = Self::for_round(stage, block.epoch(), block.round());
+        timer.record_block_id(block.id());
+        timer
+    }
+
+    pub fn record_block_id(&self, block_id: HashValue) {
+        self.span.record("block_id", &field::display(block_id));
+    }
+
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    /// Runs `f` in the span of the stage.
+    pub fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.span.in_scope(f)
+    }
+}
+
+impl Drop for PipelineStageTimer {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed();
+        counters::BLOCK_PIPELINE_STAGE_SECONDS
+            .with_label_values(&[self.stage])
+            .observe(duration.as_secs_f64());
+        self.span.in_scope(|| {
+            tracing::debug!(
+                duration_us = duration.as_micros() as u64,
+                "block pipeline stage done"
+            )
+        });
+    }
+}
@@ -4,8 +4,10 @@
 use crate::{
     counters,
     epoch_manager::EpochManager,
+    liveness::storage_backpressure::StorageBackpressure,
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
+    payload_manager::PayloadManager,
     persistent_liveness_storage::StorageWriteProxy,
     state_computer::ExecutionProxy,
     txn_manager::MempoolProxy,
@@ -48,10 +50,14 @@ pub fn start_consensus(
         node_config.consensus.mempool_executed_txn_timeout_ms,
     ));
 
+    let storage_backpressure = Arc::new(StorageBackpressure::new(
+        node_config.consensus.storage_backpressure,
+    ));
     let state_computer = Arc::new(ExecutionProxy::new(
         Box::new(BlockExecutor::<AptosVM>::new(aptos_db)),
         txn_manager.clone(),
         state_sync_notifier,
+        storage_backpressure.clone(),
         runtime.handle(),
     ));
 
@@ -60,6 +66,7 @@ pub fn start_consensus(
     let (timeout_sender, timeout_receiver) = channel::new(1_024, &counters::PENDING_ROUND_TIMEOUTS);
     let (self_sender, self_receiver) = channel::new(1_024, &counters::PENDING_SELF_MESSAGES);
     network_sender.initialize(peer_metadata_storage);
+    let payload_manager = PayloadManager::new(txn_manager.clone(), network_sender.clone());
 
     let epoch_mgr = EpochManager::new(
         node_config,
@@ -70,10 +77,12 @@ pub fn start_consensus(
         txn_manager,
         state_computer,
         storage,
+        storage_backpressure,
         reconfig_events,
     );
 
     let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
+    let network_task = network_task.with_payload_manager(payload_manager, runtime.handle().clone());
 
     runtime.spawn(network_task.start());
     runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
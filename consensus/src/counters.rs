@@ -98,6 +98,24 @@ pub static COMMITTED_VOTES_IN_WINDOW: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Moving average of the latency of storage commits, as seen by the storage backpressure
+pub static STORAGE_COMMIT_LATENCY_AVERAGE_MS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_storage_commit_latency_average_ms",
+        "Moving average of the latency of storage commits in milliseconds"
+    )
+    .unwrap()
+});
+
+/// Size of the proposals of this validator after the storage backpressure
+pub static STORAGE_BACKPRESSURE_BLOCK_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_consensus_storage_backpressure_block_size",
+        "Maximum number of transactions in the proposals of this validator after the storage backpressure"
+    )
+    .unwrap()
+});
+
 /// The number of block events the LeaderReputation uses
 pub static LEADER_REPUTATION_WINDOW_SIZE: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -145,6 +163,17 @@ pub static TIMEOUT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("aptos_consensus_timeout_count", "Count the number of timeouts a node experienced since last restart (close to 0 in happy path).").unwrap()
 });
 
+/// Count of the rounds of each proposer by how they ended: `certified` with a quorum certificate,
+/// `timeout` with a timeout certificate.
+pub static PROPOSER_ROUNDS_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_proposer_rounds_count",
+        "Count of the rounds of each proposer by how they ended since last restart.",
+        &["proposer", "result"]
+    )
+    .unwrap()
+});
+
 /// The timeout of the current round.
 pub static ROUND_TIMEOUT_MS: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -225,12 +254,34 @@ pub static BLOCK_TRACING: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Histogram of the duration of each stage of the block pipeline, see `PipelineStage`.
+pub static BLOCK_PIPELINE_STAGE_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_consensus_block_pipeline_stage_seconds",
+        "Histogram of the duration of each stage of the block pipeline",
+        &["stage"]
+    )
+    .unwrap()
+});
+
 /// Histogram of the time it requires to wait before inserting blocks into block store.
 /// Measured as the block's timestamp minus local timestamp.
 pub static WAIT_DURATION_S: Lazy<DurationHistogram> = Lazy::new(|| {
     DurationHistogram::new(register_histogram!("aptos_consensus_wait_duration_s", "Histogram of the time it requires to wait before inserting blocks into block store. Measured as the block's timestamp minus the local timestamp.").unwrap())
 });
 
+/// Histogram of the time from the start of a round to the vote of this validator for the
+/// proposal of the round.
+pub static VOTE_LATENCY_S: Lazy<DurationHistogram> = Lazy::new(|| {
+    DurationHistogram::new(
+        register_histogram!(
+            "aptos_consensus_vote_latency_s",
+            "Histogram of the time from the start of a round to the vote of this validator for the proposal of the round."
+        )
+        .unwrap(),
+    )
+});
+
 ///////////////////
 // CHANNEL COUNTERS
 ///////////////////
@@ -17,6 +17,7 @@ use crate::{
         rotating_proposer_election::{choose_leader, RotatingProposer},
         round_proposer_election::RoundProposer,
         round_state::{ExponentialTimeInterval, RoundState},
+        storage_backpressure::StorageBackpressure,
     },
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
@@ -36,13 +37,16 @@ use aptos_types::{
     account_address::AccountAddress,
     epoch_change::EpochChangeProof,
     epoch_state::EpochState,
-    on_chain_config::{OnChainConfigPayload, OnChainConsensusConfig, ValidatorSet},
+    on_chain_config::{
+        FeatureFlag, Features, OnChainConfigPayload, OnChainConsensusConfig, ValidatorSet,
+    },
     validator_verifier::ValidatorVerifier,
 };
 use channel::{aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
     common::{Author, Round},
     epoch_retrieval::EpochRetrievalRequest,
+    vote_msg::VoteMsg,
 };
 use event_notifications::ReconfigNotificationListener;
 use futures::{
@@ -50,7 +54,7 @@ use futures::{
         mpsc::{unbounded, UnboundedSender},
         oneshot,
     },
-    SinkExt, StreamExt,
+    FutureExt, SinkExt, StreamExt,
 };
 use network::protocols::network::{ApplicationNetworkSender, Event};
 use safety_rules::SafetyRulesManager;
@@ -61,6 +65,10 @@ use std::{
     time::Duration,
 };
 
+/// Maximum number of consensus messages taken from the network queue at once. The votes among
+/// them have their signatures batch verified.
+const MAX_CONSENSUS_MESSAGE_BATCH_SIZE: usize = 256;
+
 #[allow(clippy::large_enum_variant)]
 pub enum LivenessStorageData {
     RecoveryData(RecoveryData),
@@ -88,6 +96,7 @@ pub struct EpochManager {
     txn_manager: Arc<dyn TxnManager>,
     commit_state_computer: Arc<dyn StateComputer>,
     storage: Arc<dyn PersistentLivenessStorage>,
+    storage_backpressure: Arc<StorageBackpressure>,
     safety_rules_manager: SafetyRulesManager,
     reconfig_events: ReconfigNotificationListener,
     // channels to buffer manager
@@ -110,6 +119,7 @@ impl EpochManager {
         txn_manager: Arc<dyn TxnManager>,
         commit_state_computer: Arc<dyn StateComputer>,
         storage: Arc<dyn PersistentLivenessStorage>,
+        storage_backpressure: Arc<StorageBackpressure>,
         reconfig_events: ReconfigNotificationListener,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
@@ -126,6 +136,7 @@ impl EpochManager {
             txn_manager,
             commit_state_computer,
             storage,
+            storage_backpressure,
             safety_rules_manager,
             reconfig_events,
             buffer_manager_msg_tx: None,
@@ -380,6 +391,7 @@ impl EpochManager {
         recovery_data: RecoveryData,
         epoch_state: EpochState,
         onchain_config: OnChainConsensusConfig,
+        features: Features,
     ) {
         let epoch = epoch_state.epoch;
         counters::EPOCH.set(epoch_state.epoch as i64);
@@ -415,6 +427,10 @@ impl EpochManager {
             self.network_sender.clone(),
             self.self_sender.clone(),
             epoch_state.verifier.clone(),
+        )
+        // Compact proposals can be enabled network-wide on chain, or on this node alone.
+        .with_compact_proposals(
+            self.config.compact_proposals || features.is_enabled(FeatureFlag::CompactProposals),
         );
 
         let safety_rules_container = Arc::new(Mutex::new(safety_rules));
@@ -448,7 +464,11 @@ impl EpochManager {
             self.txn_manager.clone(),
             self.time_service.clone(),
             self.config.max_block_size,
-        );
+            // Proposals that aren't shuffled are not voted for, so this must agree across
+            // validators and is only ever set on chain.
+            features.is_enabled(FeatureFlag::ShuffleTransactions),
+        )
+        .with_storage_backpressure(self.storage_backpressure.clone());
 
         let mut round_manager = RoundManager::new(
             epoch_state,
@@ -484,16 +504,59 @@ impl EpochManager {
         self.shutdown_current_processor().await;
 
         let onchain_config: OnChainConsensusConfig = payload.get().unwrap_or_default();
+        let features: Features = payload.get().unwrap_or_default();
         self.epoch_state = Some(epoch_state.clone());
 
         let initial_data = self
             .storage
             .start()
             .expect_recovery_data("Consensusdb is corrupted, need to do a backup and restore");
-        self.start_round_manager(initial_data, epoch_state, onchain_config)
+        self.start_round_manager(initial_data, epoch_state, onchain_config, features)
             .await;
     }
 
+    /// Processes messages in the order they were received. Consecutive votes of the current epoch
+    /// are verified together, batching the verification of their signatures.
+    async fn process_messages(&mut self, messages: Vec<(AccountAddress, ConsensusMsg)>) {
+        let epoch = self.epoch();
+        for group in group_votes(messages, epoch) {
+            match group {
+                MessageGroup::Votes(votes) if epoch == self.epoch() => self.process_votes(votes),
+                // The epoch changed while processing the previous messages.
+                MessageGroup::Votes(votes) => {
+                    for (peer_id, vote_msg) in votes {
+                        self.process_message_logging_errors(
+                            peer_id,
+                            ConsensusMsg::VoteMsg(vote_msg),
+                        )
+                        .await;
+                    }
+                }
+                MessageGroup::Message(peer_id, msg) => {
+                    self.process_message_logging_errors(peer_id, msg).await
+                }
+            }
+        }
+    }
+
+    async fn process_message_logging_errors(&mut self, peer_id: AccountAddress, msg: ConsensusMsg) {
+        if let Err(e) = self.process_message(peer_id, msg).await {
+            error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
+        }
+    }
+
+    fn process_votes(&mut self, votes: Vec<(AccountAddress, Box<VoteMsg>)>) {
+        let verified = monitor!(
+            "batch_verify_votes",
+            verify_votes(votes, &self.epoch_state().verifier)
+        );
+        for (peer_id, verified_event) in verified {
+            if let Err(e) = verified_event.and_then(|event| self.process_event(peer_id, event)) {
+                error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
+            }
+        }
+    }
+
     async fn process_message(
         &mut self,
         peer_id: AccountAddress,
@@ -645,9 +708,16 @@ impl EpochManager {
         loop {
             tokio::select! {
                 Some((peer, msg)) = network_receivers.consensus_messages.next() => {
-                    if let Err(e) = self.process_message(peer, msg).await {
-                        error!(epoch = self.epoch(), error = ?e, kind = error_kind(&e));
+                    let mut messages = vec![(peer, msg)];
+                    // Votes arrive in bursts at the end of each round, take the ones already
+                    // queued to verify them together.
+                    while messages.len() < MAX_CONSENSUS_MESSAGE_BATCH_SIZE {
+                        match network_receivers.consensus_messages.next().now_or_never() {
+                            Some(Some(message)) => messages.push(message),
+                            _ => break,
+                        }
                     }
+                    self.process_messages(messages).await;
                 }
                 Some(request) = network_receivers.block_retrieval.next() => {
                     self.process_block_retrieval(request);
@@ -664,3 +734,154 @@ impl EpochManager {
         }
     }
 }
+
+/// Consecutive messages processed together.
+enum MessageGroup {
+    /// Votes of the same epoch, with their signatures batch verified.
+    Votes(Vec<(AccountAddress, Box<VoteMsg>)>),
+    Message(AccountAddress, ConsensusMsg),
+}
+
+/// Groups the consecutive votes of `epoch` among `messages`, keeping the messages in order.
+fn group_votes(messages: Vec<(AccountAddress, ConsensusMsg)>, epoch: u64) -> Vec<MessageGroup> {
+    let mut groups = vec![];
+    let mut votes = vec![];
+    for (peer_id, msg) in messages {
+        match msg {
+            ConsensusMsg::VoteMsg(vote_msg) if vote_msg.epoch() == epoch => {
+                votes.push((peer_id, vote_msg));
+            }
+            msg => {
+                if !votes.is_empty() {
+                    groups.push(MessageGroup::Votes(std::mem::take(&mut votes)));
+                }
+                groups.push(MessageGroup::Message(peer_id, msg));
+            }
+        }
+    }
+    if !votes.is_empty() {
+        groups.push(MessageGroup::Votes(votes));
+    }
+    groups
+}
+
+/// Verifies `votes` like `UnverifiedEvent::verify` does, batching the verification of their
+/// signatures, and returns the verified events in order.
+fn verify_votes(
+    votes: Vec<(AccountAddress, Box<VoteMsg>)>,
+    verifier: &ValidatorVerifier,
+) -> Vec<(AccountAddress, anyhow::Result<VerifiedEvent>)> {
+    let vote_msgs: Vec<_> = votes
+        .iter()
+        .map(|(_, vote_msg)| vote_msg.as_ref())
+        .collect();
+    let results = VoteMsg::batch_verify(&vote_msgs, verifier);
+    votes
+        .into_iter()
+        .zip(results)
+        .map(|((peer_id, vote_msg), result)| {
+            let verified_event = match result.context("[EpochManager] Verify event") {
+                Ok(()) => Ok(VerifiedEvent::VoteMsg(vote_msg)),
+                Err(err) => {
+                    error!(
+                        SecurityEvent::ConsensusInvalidMessage,
+                        remote_peer = peer_id,
+                        error = ?err,
+                        unverified_event = UnverifiedEvent::VoteMsg(vote_msg)
+                    );
+                    Err(err)
+                }
+            };
+            (peer_id, verified_event)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{placeholder_ledger_info, placeholder_sync_info};
+    use aptos_types::{block_info::BlockInfo, validator_verifier::random_validator_verifier};
+    use consensus_types::{vote::Vote, vote_data::VoteData};
+
+    #[test]
+    fn test_process_messages_rejects_only_the_bad_vote() {
+        let (signers, verifier) = random_validator_verifier(4, None, false);
+        let vote_data = VoteData::new(BlockInfo::random(1), BlockInfo::random(0));
+        let votes: Vec<_> = signers
+            .iter()
+            .map(|signer| {
+                Vote::new(
+                    vote_data.clone(),
+                    signer.author(),
+                    placeholder_ledger_info(),
+                    signer,
+                )
+            })
+            .collect();
+        // The vote of the second validator, signed by the third one.
+        let bad_vote = Vote::new_with_signature(
+            vote_data,
+            signers[1].author(),
+            votes[1].ledger_info().clone(),
+            votes[2].signature().clone(),
+        );
+        let vote_msg = |peer_id: AccountAddress, vote: &Vote| {
+            (
+                peer_id,
+                ConsensusMsg::VoteMsg(Box::new(VoteMsg::new(
+                    vote.clone(),
+                    placeholder_sync_info(),
+                ))),
+            )
+        };
+        let messages = vec![
+            vote_msg(signers[0].author(), &votes[0]),
+            vote_msg(signers[1].author(), &bad_vote),
+            vote_msg(signers[2].author(), &votes[2]),
+            (
+                signers[3].author(),
+                ConsensusMsg::SyncInfo(Box::new(placeholder_sync_info())),
+            ),
+            vote_msg(signers[3].author(), &votes[3]),
+        ];
+
+        let mut groups = group_votes(messages, 1).into_iter();
+        let first_votes = match groups.next() {
+            Some(MessageGroup::Votes(votes)) => votes,
+            _ => panic!("expected the first votes to be grouped"),
+        };
+        assert!(matches!(
+            groups.next(),
+            Some(MessageGroup::Message(_, ConsensusMsg::SyncInfo(_)))
+        ));
+        let last_votes = match groups.next() {
+            Some(MessageGroup::Votes(votes)) => votes,
+            _ => panic!("expected the last vote to be grouped"),
+        };
+        assert!(groups.next().is_none());
+
+        let results: Vec<_> = verify_votes(first_votes, &verifier)
+            .into_iter()
+            .map(|(peer_id, result)| (peer_id, result.is_ok()))
+            .collect();
+        assert_eq!(
+            results,
+            vec![
+                (signers[0].author(), true),
+                (signers[1].author(), false),
+                (signers[2].author(), true),
+            ]
+        );
+        let results = verify_votes(last_votes, &verifier);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].1.is_ok());
+
+        // Votes of another epoch are left to the epoch check of each message.
+        let messages = vec![vote_msg(signers[0].author(), &votes[0])];
+        assert!(matches!(
+            group_votes(messages, 2).as_slice(),
+            [MessageGroup::Message(_, ConsensusMsg::VoteMsg(_))]
+        ));
+    }
+}
@@ -7,6 +7,8 @@ pub(crate) mod proposer_election;
 pub(crate) mod rotating_proposer_election;
 pub(crate) mod round_proposer_election;
 pub(crate) mod round_state;
+pub(crate) mod storage_backpressure;
+pub(crate) mod transaction_shuffler;
 
 #[cfg(test)]
 mod leader_reputation_test;
@@ -16,3 +18,7 @@ mod rotating_proposer_test;
 mod round_proposer_test;
 #[cfg(test)]
 mod round_state_test;
+#[cfg(test)]
+mod storage_backpressure_test;
+#[cfg(test)]
+mod transaction_shuffler_test;
@@ -2,7 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_storage::BlockReader, state_replication::TxnManager, util::time_service::TimeService,
+    block_storage::{
+        tracing::{PipelineStage, PipelineStageTimer},
+        BlockReader,
+    },
+    liveness::{
+        storage_backpressure::StorageBackpressure,
+        transaction_shuffler::{shuffle_seed, shuffle_transactions},
+    },
+    state_replication::TxnManager,
+    util::time_service::TimeService,
 };
 use anyhow::{bail, ensure, format_err, Context};
 use consensus_types::{
@@ -14,7 +23,8 @@ use consensus_types::{
 
 use aptos_infallible::Mutex;
 use futures::future::BoxFuture;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+use tracing::Instrument;
 
 #[cfg(test)]
 #[path = "proposal_generator_test.rs"]
@@ -41,6 +51,10 @@ pub struct ProposalGenerator {
     time_service: Arc<dyn TimeService>,
     // Max number of transactions to be added to a proposed block.
     max_block_size: u64,
+    // Whether the pulled transactions are deterministically shuffled before being proposed.
+    shuffle_transactions: bool,
+    // Shrinks and delays the proposals while storage commits are slow.
+    storage_backpressure: Option<Arc<StorageBackpressure>>,
     // Last round that a proposal was generated
     last_round_generated: Mutex<Round>,
 }
@@ -52,6 +66,7 @@ impl ProposalGenerator {
         txn_manager: Arc<dyn TxnManager>,
         time_service: Arc<dyn TimeService>,
         max_block_size: u64,
+        shuffle_transactions: bool,
     ) -> Self {
         Self {
             author,
@@ -59,14 +74,38 @@ impl ProposalGenerator {
             txn_manager,
             time_service,
             max_block_size,
+            shuffle_transactions,
+            storage_backpressure: None,
             last_round_generated: Mutex::new(0),
         }
     }
 
+    pub fn with_storage_backpressure(
+        mut self,
+        storage_backpressure: Arc<StorageBackpressure>,
+    ) -> Self {
+        self.storage_backpressure = Some(storage_backpressure);
+        self
+    }
+
     pub fn author(&self) -> Author {
         self.author
     }
 
+    pub fn shuffles_transactions(&self) -> bool {
+        self.shuffle_transactions
+    }
+
+    /// How long the caller should wait before generating the next proposal, to let a lagging
+    /// storage catch up.
+    pub fn proposal_delay(&self) -> Duration {
+        self.storage_backpressure
+            .as_ref()
+            .map_or(Duration::ZERO, |storage_backpressure| {
+                storage_backpressure.proposal_delay()
+            })
+    }
+
     /// Creates a NIL block proposal extending the highest certified block from the block store.
     pub fn generate_nil_block(&self, round: Round) -> anyhow::Result<Block> {
         let hqc = self.ensure_highest_quorum_cert(round)?;
@@ -128,21 +167,38 @@ impl ProposalGenerator {
                 .iter()
                 .any(|block| !block.payload().map_or(true, |txns| txns.is_empty()));
 
+            let mut max_block_size = self.max_block_size;
+            if let Some(storage_backpressure) = &self.storage_backpressure {
+                max_block_size = storage_backpressure.block_size(max_block_size);
+            }
+
             // All proposed blocks in a branch are guaranteed to have increasing timestamps
             // since their predecessor block will not be added to the BlockStore until
             // the local time exceeds it.
             let timestamp = self.time_service.get_current_timestamp();
 
+            let pull_timer = PipelineStageTimer::for_round(
+                PipelineStage::PULL_PAYLOAD,
+                hqc.certified_block().epoch(),
+                round,
+            );
             let payload = self
                 .txn_manager
                 .pull_txns(
-                    self.max_block_size,
+                    max_block_size,
                     exclude_payload,
                     wait_callback,
                     pending_ordering,
                 )
+                .instrument(pull_timer.span().clone())
                 .await
                 .context("Fail to retrieve txn")?;
+            drop(pull_timer);
+            let payload = if self.shuffle_transactions {
+                shuffle_transactions(shuffle_seed(hqc.certified_block().id(), round), payload)
+            } else {
+                payload
+            };
 
             (payload, timestamp.as_micros() as u64)
         };
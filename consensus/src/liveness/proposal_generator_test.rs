@@ -26,6 +26,7 @@ async fn test_proposal_generation_empty_tree() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        false,
     );
     let genesis = block_store.ordered_root();
 
@@ -57,6 +58,7 @@ async fn test_proposal_generation_parent() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        false,
     );
     let genesis = block_store.ordered_root();
     let a1 = inserter
@@ -108,6 +110,7 @@ async fn test_old_proposal_generation() {
         Arc::new(MockTransactionManager::new(None)),
         Arc::new(SimulatedTimeService::new()),
         1,
+        false,
     );
     let genesis = block_store.ordered_root();
     let a1 = inserter
@@ -142,6 +142,8 @@ pub struct RoundState {
     // a previous deadline expires.
     // Represents as Duration since UNIX_EPOCH.
     current_round_deadline: Duration,
+    // The time the current round started at, represented as Duration since UNIX_EPOCH.
+    current_round_start: Duration,
     // Service for timer
     time_service: Arc<dyn TimeService>,
     // To send local timeout events to the subscriber (e.g., SMR)
@@ -192,6 +194,7 @@ impl RoundState {
             highest_committed_round: 0,
             current_round: 0,
             current_round_deadline: time_service.get_current_timestamp(),
+            current_round_start: time_service.get_current_timestamp(),
             time_service,
             timeout_sender,
             pending_votes: PendingVotes::new(),
@@ -210,6 +213,13 @@ impl RoundState {
         self.current_round_deadline
     }
 
+    /// Returns the time elapsed since the start of the current round.
+    pub fn current_round_elapsed(&self) -> Duration {
+        self.time_service
+            .get_current_timestamp()
+            .saturating_sub(self.current_round_start)
+    }
+
     /// In case the local timeout corresponds to the current round, reset the timeout and
     /// return true. Otherwise ignore and return false.
     pub fn process_local_timeout(&mut self, round: Round) -> bool {
@@ -232,6 +242,7 @@ impl RoundState {
         if new_round > self.current_round {
             // Start a new round.
             self.current_round = new_round;
+            self.current_round_start = self.time_service.get_current_timestamp();
             self.pending_votes = PendingVotes::new();
             self.vote_sent = None;
             let timeout = self.setup_timeout();
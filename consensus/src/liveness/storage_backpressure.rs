@@ -0,0 +1,86 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::counters;
+use aptos_config::config::StorageBackpressureConfig;
+use aptos_infallible::Mutex;
+use std::time::Duration;
+
+/// Feeds the latency of storage commits back into the pacing of proposals: the commits are
+/// timed by the state computer and the proposal generator shrinks and delays its proposals while
+/// their moving average is above the target latency.
+pub struct StorageBackpressure {
+    config: StorageBackpressureConfig,
+    // Exponential moving average of the commit latency, `None` until the first commit.
+    average_commit_latency: Mutex<Option<Duration>>,
+}
+
+impl StorageBackpressure {
+    pub fn new(config: StorageBackpressureConfig) -> Self {
+        Self {
+            config,
+            average_commit_latency: Mutex::new(None),
+        }
+    }
+
+    pub fn record_commit_latency(&self, latency: Duration) {
+        let mut average = self.average_commit_latency.lock();
+        let updated = match *average {
+            None => latency,
+            Some(average) => {
+                let weight = self.config.latency_smoothing_percent.min(100) as u128;
+                Duration::from_micros(
+                    ((average.as_micros() * (100 - weight) + latency.as_micros() * weight) / 100)
+                        as u64,
+                )
+            }
+        };
+        *average = Some(updated);
+        counters::STORAGE_COMMIT_LATENCY_AVERAGE_MS.set(updated.as_millis() as i64);
+    }
+
+    pub fn average_commit_latency(&self) -> Option<Duration> {
+        *self.average_commit_latency.lock()
+    }
+
+    /// How many times the average commit latency is over the target, `None` when it is not.
+    fn overload(&self) -> Option<f64> {
+        if !self.config.enabled {
+            return None;
+        }
+        let target = Duration::from_millis(self.config.target_commit_latency_ms);
+        let average = self.average_commit_latency()?;
+        if target.is_zero() || average <= target {
+            return None;
+        }
+        Some(average.as_secs_f64() / target.as_secs_f64())
+    }
+
+    /// The size of the next proposal: `max_block_size` divided by the overload, but no less than
+    /// `min_block_size_percent` of it.
+    pub fn block_size(&self, max_block_size: u64) -> u64 {
+        let block_size = match self.overload() {
+            None => max_block_size,
+            Some(overload) => {
+                let min_block_size =
+                    max_block_size * self.config.min_block_size_percent.min(100) / 100;
+                ((max_block_size as f64 / overload) as u64)
+                    .max(min_block_size)
+                    .max(1)
+            }
+        };
+        counters::STORAGE_BACKPRESSURE_BLOCK_SIZE.set(block_size as i64);
+        block_size
+    }
+
+    /// How long to wait before proposing: the excess commit latency, up to
+    /// `max_proposal_delay_ms`.
+    pub fn proposal_delay(&self) -> Duration {
+        match (self.overload(), self.average_commit_latency()) {
+            (Some(_), Some(average)) => average
+                .saturating_sub(Duration::from_millis(self.config.target_commit_latency_ms))
+                .min(Duration::from_millis(self.config.max_proposal_delay_ms)),
+            _ => Duration::ZERO,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::liveness::storage_backpressure::StorageBackpressure;
+use aptos_config::config::StorageBackpressureConfig;
+use std::time::Duration;
+
+fn backpressure() -> StorageBackpressure {
+    StorageBackpressure::new(StorageBackpressureConfig {
+        enabled: true,
+        target_commit_latency_ms: 100,
+        latency_smoothing_percent: 50,
+        min_block_size_percent: 10,
+        max_proposal_delay_ms: 300,
+    })
+}
+
+#[test]
+fn test_no_backpressure_under_target() {
+    let backpressure = backpressure();
+    assert_eq!(backpressure.block_size(1000), 1000);
+    assert_eq!(backpressure.proposal_delay(), Duration::ZERO);
+
+    backpressure.record_commit_latency(Duration::from_millis(80));
+    assert_eq!(backpressure.block_size(1000), 1000);
+    assert_eq!(backpressure.proposal_delay(), Duration::ZERO);
+}
+
+#[test]
+fn test_backpressure_over_target() {
+    let backpressure = backpressure();
+    backpressure.record_commit_latency(Duration::from_millis(200));
+    assert_eq!(backpressure.block_size(1000), 500);
+    assert_eq!(backpressure.proposal_delay(), Duration::from_millis(100));
+
+    // Sustained slow commits hit the floors.
+    for _ in 0..20 {
+        backpressure.record_commit_latency(Duration::from_secs(10));
+    }
+    assert_eq!(backpressure.block_size(1000), 100);
+    assert_eq!(backpressure.proposal_delay(), Duration::from_millis(300));
+}
+
+#[test]
+fn test_latency_is_smoothed() {
+    let backpressure = backpressure();
+    backpressure.record_commit_latency(Duration::from_millis(100));
+    backpressure.record_commit_latency(Duration::from_millis(300));
+    assert_eq!(
+        backpressure.average_commit_latency(),
+        Some(Duration::from_millis(200))
+    );
+
+    // A single fast commit only partially releases the pressure.
+    backpressure.record_commit_latency(Duration::from_millis(0));
+    assert_eq!(
+        backpressure.average_commit_latency(),
+        Some(Duration::from_millis(100))
+    );
+    assert_eq!(backpressure.block_size(1000), 1000);
+}
+
+#[test]
+fn test_disabled_backpressure() {
+    let backpressure = StorageBackpressure::new(StorageBackpressureConfig::default());
+    backpressure.record_commit_latency(Duration::from_secs(10));
+    assert_eq!(backpressure.block_size(1000), 1000);
+    assert_eq!(backpressure.proposal_delay(), Duration::ZERO);
+}
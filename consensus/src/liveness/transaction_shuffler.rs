@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::liveness::proposer_election::next;
+use aptos_crypto::HashValue;
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use consensus_types::common::Round;
+use std::collections::{HashMap, VecDeque};
+
+/// Seed of the shuffle for a proposal. The id of the proposed block depends on its payload, so
+/// the proposal is identified by the block it extends and its round instead.
+pub fn shuffle_seed(parent_block_id: HashValue, round: Round) -> Vec<u8> {
+    let mut seed = parent_block_id.to_vec();
+    seed.extend_from_slice(&round.to_le_bytes());
+    seed
+}
+
+/// Deterministically reorders the transactions pulled for a proposal so that the order they
+/// were submitted in does not decide their position in the block.
+///
+/// Senders are sorted then permuted using a hash chain started from `seed`, and their
+/// transactions are interleaved one per sender at a time. Transactions of the same sender keep
+/// their relative order, so sequence numbers stay increasing within the block. The result doesn't
+/// depend on the order of `txns` across senders, which lets the voters check it, see
+/// [`is_shuffled`].
+pub fn shuffle_transactions(seed: Vec<u8>, txns: Vec<SignedTransaction>) -> Vec<SignedTransaction> {
+    let num_txns = txns.len();
+    let mut senders: Vec<AccountAddress> = vec![];
+    let mut txns_by_sender: HashMap<AccountAddress, VecDeque<SignedTransaction>> = HashMap::new();
+    for txn in txns {
+        let sender = txn.sender();
+        txns_by_sender
+            .entry(sender)
+            .or_insert_with(|| {
+                senders.push(sender);
+                VecDeque::new()
+            })
+            .push_back(txn);
+    }
+
+    // Fisher-Yates over the sorted senders.
+    senders.sort();
+    let mut state = seed;
+    for i in (1..senders.len()).rev() {
+        let j = (next(&mut state) % (i as u64 + 1)) as usize;
+        senders.swap(i, j);
+    }
+
+    let mut shuffled = Vec::with_capacity(num_txns);
+    while shuffled.len() < num_txns {
+        for sender in &senders {
+            if let Some(txn) = txns_by_sender.get_mut(sender).and_then(VecDeque::pop_front) {
+                shuffled.push(txn);
+            }
+        }
+    }
+    shuffled
+}
+
+/// Whether `txns` are in the order [`shuffle_transactions`] puts them in for `seed`, so that a
+/// proposer can't pick the order of the transactions it proposes.
+pub fn is_shuffled(seed: Vec<u8>, txns: &[SignedTransaction]) -> bool {
+    shuffle_transactions(seed, txns.to_vec()) == txns
+}
@@ -0,0 +1,87 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::liveness::transaction_shuffler::{is_shuffled, shuffle_seed, shuffle_transactions};
+use aptos_crypto::HashValue;
+use aptos_types::transaction::SignedTransaction;
+use consensus_types::block_test_utils::random_payload;
+
+fn payload_from_senders(num_senders: usize, txns_per_sender: usize) -> Vec<SignedTransaction> {
+    (0..num_senders)
+        .flat_map(|_| random_payload(txns_per_sender))
+        .collect()
+}
+
+#[test]
+fn test_shuffle_is_deterministic() {
+    let txns = payload_from_senders(10, 3);
+    let seed = shuffle_seed(HashValue::random(), 1);
+
+    let shuffled = shuffle_transactions(seed.clone(), txns.clone());
+    assert_eq!(shuffled, shuffle_transactions(seed, txns.clone()));
+
+    let other_seed = shuffle_seed(HashValue::random(), 1);
+    let mut sorted = shuffle_transactions(other_seed, txns.clone());
+    let mut expected = txns;
+    sorted.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    expected.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    assert_eq!(sorted, expected);
+}
+
+#[test]
+fn test_shuffle_interleaves_senders() {
+    let num_senders = 5;
+    let txns = payload_from_senders(num_senders, 4);
+    let shuffled = shuffle_transactions(shuffle_seed(HashValue::random(), 7), txns);
+    assert_eq!(shuffled.len(), 20);
+
+    // Every sender appears once per pass, and always in the same position within the pass.
+    let first_pass: Vec<_> = shuffled[..num_senders]
+        .iter()
+        .map(SignedTransaction::sender)
+        .collect();
+    for (i, txn) in shuffled.iter().enumerate() {
+        assert_eq!(txn.sender(), first_pass[i % num_senders]);
+        assert_eq!(txn.sequence_number(), (i / num_senders) as u64);
+    }
+}
+
+#[test]
+fn test_shuffle_uneven_senders() {
+    let mut txns = random_payload(3);
+    txns.extend(random_payload(1));
+    let shuffled = shuffle_transactions(shuffle_seed(HashValue::zero(), 0), txns.clone());
+    assert_eq!(shuffled.len(), txns.len());
+
+    // The transactions of each sender keep their relative order.
+    let long_sender = txns[0].sender();
+    let sequence_numbers: Vec<_> = shuffled
+        .iter()
+        .filter(|txn| txn.sender() == long_sender)
+        .map(SignedTransaction::sequence_number)
+        .collect();
+    assert_eq!(sequence_numbers, vec![0, 1, 2]);
+}
+
+#[test]
+fn test_is_shuffled() {
+    let txns = payload_from_senders(10, 2);
+    let seed = shuffle_seed(HashValue::random(), 3);
+    let shuffled = shuffle_transactions(seed.clone(), txns.clone());
+    assert!(is_shuffled(seed.clone(), &shuffled));
+    // The order the proposer pulled the transactions in doesn't matter.
+    let senders_reversed = txns.chunks(2).rev().flatten().cloned().collect();
+    assert_eq!(
+        shuffle_transactions(seed.clone(), senders_reversed),
+        shuffled
+    );
+
+    let mut reordered = shuffled.clone();
+    reordered.swap(0, 1);
+    assert!(!is_shuffled(seed, &reordered));
+    assert!(!is_shuffled(
+        shuffle_seed(HashValue::random(), 3),
+        &shuffled
+    ));
+    assert!(is_shuffled(shuffle_seed(HashValue::random(), 3), &[]));
+}
@@ -26,6 +26,7 @@ pub enum LogEvent {
     ReceiveEpochChangeProof,
     ReceiveEpochRetrieval,
     ReceiveMessageFromDifferentEpoch,
+    ReceivePayloadRetrieval,
     ReceiveProposal,
     ReceiveSyncInfo,
     ReceiveVote,
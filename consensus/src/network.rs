@@ -5,6 +5,7 @@ use crate::{
     counters,
     logging::LogEvent,
     network_interface::{ConsensusMsg, ConsensusNetworkEvents, ConsensusNetworkSender},
+    payload_manager::PayloadManager,
 };
 use anyhow::{anyhow, ensure};
 use aptos_logger::prelude::*;
@@ -13,12 +14,16 @@ use aptos_types::{
     account_address::AccountAddress, epoch_change::EpochChangeProof,
     ledger_info::LedgerInfoWithSignatures, validator_verifier::ValidatorVerifier,
 };
+use bounded_executor::BoundedExecutor;
 use bytes::Bytes;
 use channel::{self, aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse, MAX_BLOCKS_PER_REQUEST},
     common::Author,
+    compact_proposal_msg::CompactProposalMsg,
     experimental::commit_decision::CommitDecision,
+    payload_retrieval::MAX_TXNS_PER_REQUEST,
+    proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
 };
@@ -34,6 +39,13 @@ use std::{
     mem::{discriminant, Discriminant},
     time::Duration,
 };
+use tokio::runtime::Handle;
+
+/// Maximum number of compact proposals resolved concurrently, the ones received over it are dropped.
+const MAX_CONCURRENT_COMPACT_PROPOSALS: usize = 8;
+
+/// Maximum number of payload retrievals served concurrently, the ones received over it are dropped.
+const MAX_CONCURRENT_PAYLOAD_RETRIEVALS: usize = 64;
 
 /// The block retrieval request is used internally for implementing RPC: the callback is executed
 /// for carrying the response
@@ -65,6 +77,8 @@ pub struct NetworkSender {
     // Note that we do not support self rpc requests as it might cause infinite recursive calls.
     self_sender: channel::Sender<Event<ConsensusMsg>>,
     validators: ValidatorVerifier,
+    // Whether proposals are broadcast to the other validators as compact proposals.
+    compact_proposals: bool,
 }
 
 impl NetworkSender {
@@ -79,9 +93,16 @@ impl NetworkSender {
             network_sender,
             self_sender,
             validators,
+            compact_proposals: false,
         }
     }
 
+    /// Broadcasts proposals with the hashes of their transactions instead of the transactions.
+    pub fn with_compact_proposals(mut self, compact_proposals: bool) -> Self {
+        self.compact_proposals = compact_proposals;
+        self
+    }
+
     /// Tries to retrieve num of blocks backwards starting from id from the given peer: the function
     /// returns a future that is fulfilled with BlockRetrievalResponse.
     pub async fn request_block(
@@ -141,6 +162,43 @@ impl NetworkSender {
         }
     }
 
+    /// Broadcasts the proposal to all the participants like `broadcast`. When compact proposals
+    /// are enabled, the other validators receive the hashes of the transactions of the payload
+    /// instead of the transactions, while self always receives the full proposal.
+    pub async fn broadcast_proposal(&mut self, proposal_msg: ProposalMsg) {
+        let compact = if self.compact_proposals {
+            CompactProposalMsg::from_proposal(&proposal_msg)
+        } else {
+            None
+        };
+        let compact = match compact {
+            Some(compact) => compact,
+            None => {
+                return self
+                    .broadcast(ConsensusMsg::ProposalMsg(Box::new(proposal_msg)))
+                    .await
+            }
+        };
+
+        let self_msg = Event::Message(
+            self.author,
+            ConsensusMsg::ProposalMsg(Box::new(proposal_msg)),
+        );
+        if let Err(err) = self.self_sender.send(self_msg).await {
+            error!("Error broadcasting to self: {:?}", err);
+        }
+
+        let self_author = self.author;
+        let other_validators = self
+            .validators
+            .get_ordered_account_addresses_iter()
+            .filter(|author| author != &self_author);
+        let msg = ConsensusMsg::CompactProposalMsg(Box::new(compact));
+        if let Err(err) = self.network_sender.send_to_many(other_validators, msg) {
+            error!(error = ?err, "Error broadcasting compact proposal");
+        }
+    }
+
     /// Tries to send msg to given recipients.
     pub async fn send(&self, msg: ConsensusMsg, recipients: Vec<Author>) {
         let network_sender = self.network_sender.clone();
@@ -203,6 +261,15 @@ pub struct NetworkTask {
     >,
     block_retrieval_tx: aptos_channel::Sender<AccountAddress, IncomingBlockRetrievalRequest>,
     all_events: Box<dyn Stream<Item = Event<ConsensusMsg>> + Send + Unpin>,
+    // Resolves compact proposals and serves payload retrievals, both are rejected without it.
+    payload_tasks: Option<PayloadTasks>,
+}
+
+/// The PayloadManager with the bounded executors its tasks are spawned on.
+struct PayloadTasks {
+    payload_manager: PayloadManager,
+    compact_proposals: BoundedExecutor,
+    payload_retrievals: BoundedExecutor,
 }
 
 impl NetworkTask {
@@ -224,6 +291,7 @@ impl NetworkTask {
                 consensus_messages_tx,
                 block_retrieval_tx,
                 all_events,
+                payload_tasks: None,
             },
             NetworkReceivers {
                 consensus_messages,
@@ -232,9 +300,30 @@ impl NetworkTask {
         )
     }
 
+    /// Resolves compact proposals and serves payload retrievals with `payload_manager`, spawning a
+    /// bounded number of tasks on `executor`.
+    pub fn with_payload_manager(
+        mut self,
+        payload_manager: PayloadManager,
+        executor: Handle,
+    ) -> Self {
+        self.payload_tasks = Some(PayloadTasks {
+            payload_manager,
+            compact_proposals: BoundedExecutor::new(
+                MAX_CONCURRENT_COMPACT_PROPOSALS,
+                executor.clone(),
+            ),
+            payload_retrievals: BoundedExecutor::new(MAX_CONCURRENT_PAYLOAD_RETRIEVALS, executor),
+        });
+        self
+    }
+
     pub async fn start(mut self) {
         while let Some(message) = self.all_events.next().await {
             match message {
+                Event::Message(peer_id, ConsensusMsg::CompactProposalMsg(compact)) => {
+                    self.process_compact_proposal(peer_id, *compact);
+                }
                 Event::Message(peer_id, msg) => {
                     if let Err(e) = self
                         .consensus_messages_tx
@@ -271,6 +360,44 @@ impl NetworkTask {
                             warn!(error = ?e, "aptos channel closed");
                         }
                     }
+                    ConsensusMsg::PayloadRetrievalRequest(request) => {
+                        debug!(
+                            remote_peer = peer_id,
+                            event = LogEvent::ReceivePayloadRetrieval,
+                            "{}",
+                            request
+                        );
+                        if request.hashes().len() > MAX_TXNS_PER_REQUEST {
+                            warn!(
+                                remote_peer = peer_id,
+                                "Ignore payload retrieval with too many txns: {}",
+                                request.hashes().len()
+                            );
+                            continue;
+                        }
+                        let payload_tasks = match &self.payload_tasks {
+                            Some(payload_tasks) => payload_tasks,
+                            None => {
+                                warn!(remote_peer = peer_id, "Ignore payload retrieval");
+                                continue;
+                            }
+                        };
+                        let payload_manager = payload_tasks.payload_manager.clone();
+                        let task = async move {
+                            if let Err(e) = payload_manager
+                                .process_payload_retrieval(*request, protocol, callback)
+                                .await
+                            {
+                                warn!(remote_peer = peer_id, error = ?e);
+                            }
+                        };
+                        if payload_tasks.payload_retrievals.try_spawn(task).is_err() {
+                            warn!(
+                                remote_peer = peer_id,
+                                "Ignore payload retrieval: too many in progress"
+                            );
+                        }
+                    }
                     _ => {
                         warn!(remote_peer = peer_id, "Unexpected msg: {:?}", msg);
                         continue;
@@ -282,4 +409,48 @@ impl NetworkTask {
             }
         }
     }
+
+    /// Resolves the compact proposal in the background and queues the resulting proposal like a
+    /// proposal received from `peer_id`.
+    fn process_compact_proposal(&self, peer_id: AccountAddress, compact: CompactProposalMsg) {
+        let payload_tasks = match &self.payload_tasks {
+            Some(payload_tasks) => payload_tasks,
+            None => {
+                warn!(
+                    remote_peer = peer_id,
+                    "Ignore compact proposal: {}", compact
+                );
+                return;
+            }
+        };
+        let payload_manager = payload_tasks.payload_manager.clone();
+        let consensus_messages_tx = self.consensus_messages_tx.clone();
+        let task = async move {
+            match payload_manager.resolve(peer_id, compact).await {
+                Ok(proposal_msg) => {
+                    let msg = ConsensusMsg::ProposalMsg(Box::new(proposal_msg));
+                    if let Err(e) =
+                        consensus_messages_tx.push((peer_id, discriminant(&msg)), (peer_id, msg))
+                    {
+                        warn!(
+                            remote_peer = peer_id,
+                            error = ?e, "Error pushing consensus msg",
+                        );
+                    }
+                }
+                Err(e) => {
+                    warn!(
+                        remote_peer = peer_id,
+                        error = ?e, "Failed to resolve compact proposal",
+                    );
+                }
+            }
+        };
+        if payload_tasks.compact_proposals.try_spawn(task).is_err() {
+            warn!(
+                remote_peer = peer_id,
+                "Ignore compact proposal: too many in progress"
+            );
+        }
+    }
 }
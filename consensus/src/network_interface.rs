@@ -12,8 +12,10 @@ use async_trait::async_trait;
 use channel::{aptos_channel, message_queues::QueueStyle};
 use consensus_types::{
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse},
+    compact_proposal_msg::CompactProposalMsg,
     epoch_retrieval::EpochRetrievalRequest,
     experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    payload_retrieval::{PayloadRetrievalRequest, PayloadRetrievalResponse},
     proposal_msg::ProposalMsg,
     sync_info::SyncInfo,
     vote_msg::VoteMsg,
@@ -62,6 +64,13 @@ pub enum ConsensusMsg {
     /// than 2f + 1 signatures on the commit proposal. This part is not on the critical path, but
     /// it can save slow machines to quickly confirm the execution result.
     CommitDecisionMsg(Box<CommitDecision>),
+    /// CompactProposalMsg is a ProposalMsg whose payload is replaced by the hashes of its
+    /// transactions, resolved into a ProposalMsg by the receiver.
+    CompactProposalMsg(Box<CompactProposalMsg>),
+    /// RPC to get the transactions of a compact proposal missing from the local mempool.
+    PayloadRetrievalRequest(Box<PayloadRetrievalRequest>),
+    /// Carries the requested transactions, `None` for the ones the proposer does not have.
+    PayloadRetrievalResponse(Box<PayloadRetrievalResponse>),
 }
 
 /// The interface from Network to Consensus layer.
@@ -199,3 +208,7 @@ impl ApplicationNetworkSender<ConsensusMsg> for ConsensusNetworkSender {
             .await
     }
 }
+
+#[cfg(any(test, feature = "fuzzing"))]
+#[path = "network_interface_fuzzing.rs"]
+pub mod network_interface_fuzzing;
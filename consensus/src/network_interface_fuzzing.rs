@@ -0,0 +1,159 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Strategies for the consensus messages received from peers, used as corpus seeds by the
+//! fuzzers of their deserialization.
+
+use crate::network_interface::ConsensusMsg;
+use aptos_crypto::HashValue;
+use aptos_types::{
+    block_info::BlockInfo,
+    epoch_change::EpochChangeProof,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::SignedTransaction,
+    validator_signer::proptests::arb_signer,
+};
+use consensus_types::{
+    block::Block,
+    block_retrieval::{BlockRetrievalRequest, BlockRetrievalResponse, BlockRetrievalStatus},
+    compact_proposal_msg::CompactProposalMsg,
+    epoch_retrieval::EpochRetrievalRequest,
+    experimental::{commit_decision::CommitDecision, commit_vote::CommitVote},
+    payload_retrieval::{PayloadRetrievalRequest, PayloadRetrievalResponse},
+    proposal_msg::ProposalMsg,
+    quorum_cert::QuorumCert,
+    sync_info::SyncInfo,
+    vote::Vote,
+    vote_data::VoteData,
+    vote_msg::VoteMsg,
+};
+use proptest::{collection::vec, prelude::*};
+
+fn arb_quorum_cert() -> impl Strategy<Value = QuorumCert> {
+    (
+        any::<BlockInfo>(),
+        any::<BlockInfo>(),
+        any::<LedgerInfoWithSignatures>(),
+    )
+        .prop_map(|(proposed, parent, signed_ledger_info)| {
+            QuorumCert::new(VoteData::new(proposed, parent), signed_ledger_info)
+        })
+}
+
+fn arb_sync_info() -> impl Strategy<Value = SyncInfo> {
+    (arb_quorum_cert(), arb_quorum_cert()).prop_map(
+        |(highest_quorum_cert, highest_ordered_cert)| {
+            SyncInfo::new(highest_quorum_cert, highest_ordered_cert, None, None)
+        },
+    )
+}
+
+fn arb_block() -> impl Strategy<Value = Block> {
+    (
+        vec(any::<SignedTransaction>(), 0..10),
+        any::<u64>(),
+        any::<u64>(),
+        arb_quorum_cert(),
+        arb_signer(),
+    )
+        .prop_map(|(payload, round, timestamp_usecs, quorum_cert, signer)| {
+            Block::new_proposal(payload, round, timestamp_usecs, quorum_cert, &signer)
+        })
+}
+
+fn arb_vote_msg() -> impl Strategy<Value = VoteMsg> {
+    (
+        any::<BlockInfo>(),
+        any::<BlockInfo>(),
+        any::<LedgerInfo>(),
+        arb_signer(),
+        arb_sync_info(),
+    )
+        .prop_map(|(proposed, parent, ledger_info, signer, sync_info)| {
+            let vote = Vote::new(
+                VoteData::new(proposed, parent),
+                signer.author(),
+                ledger_info,
+                &signer,
+            );
+            VoteMsg::new(vote, sync_info)
+        })
+}
+
+fn arb_block_retrieval_status() -> impl Strategy<Value = BlockRetrievalStatus> {
+    prop_oneof![
+        Just(BlockRetrievalStatus::Succeeded),
+        Just(BlockRetrievalStatus::IdNotFound),
+        Just(BlockRetrievalStatus::NotEnoughBlocks),
+        Just(BlockRetrievalStatus::SucceededWithTarget),
+    ]
+}
+
+fn arb_proposal_msg() -> impl Strategy<Value = ProposalMsg> {
+    (arb_block(), arb_sync_info()).prop_map(|(block, sync_info)| ProposalMsg::new(block, sync_info))
+}
+
+/// Returns a strategy for every consensus message. Their fields are not bound to a validator set,
+/// so the signatures are well formed but do not verify, which the deserialization does not check.
+pub fn arb_consensus_msg() -> impl Strategy<Value = ConsensusMsg> {
+    prop_oneof![
+        (any::<HashValue>(), any::<u64>()).prop_map(|(block_id, num_blocks)| {
+            ConsensusMsg::BlockRetrievalRequest(Box::new(BlockRetrievalRequest::new(
+                block_id, num_blocks,
+            )))
+        }),
+        (any::<u64>(), any::<u64>()).prop_map(|(start_epoch, end_epoch)| {
+            ConsensusMsg::EpochRetrievalRequest(Box::new(EpochRetrievalRequest {
+                start_epoch,
+                end_epoch,
+            }))
+        }),
+        any::<EpochChangeProof>().prop_map(|proof| ConsensusMsg::EpochChangeProof(Box::new(proof))),
+        arb_sync_info().prop_map(|sync_info| ConsensusMsg::SyncInfo(Box::new(sync_info))),
+        arb_proposal_msg().prop_map(|proposal| ConsensusMsg::ProposalMsg(Box::new(proposal))),
+        (arb_block_retrieval_status(), vec(arb_block(), 0..3)).prop_map(|(status, blocks)| {
+            ConsensusMsg::BlockRetrievalResponse(Box::new(BlockRetrievalResponse::new(
+                status, blocks,
+            )))
+        }),
+        arb_vote_msg().prop_map(|vote_msg| ConsensusMsg::VoteMsg(Box::new(vote_msg))),
+        (any::<LedgerInfo>(), arb_signer()).prop_map(|(ledger_info, signer)| {
+            ConsensusMsg::CommitVoteMsg(Box::new(CommitVote::new(
+                signer.author(),
+                ledger_info,
+                &signer,
+            )))
+        }),
+        any::<LedgerInfoWithSignatures>().prop_map(|ledger_info| {
+            ConsensusMsg::CommitDecisionMsg(Box::new(CommitDecision::new(ledger_info)))
+        }),
+        arb_proposal_msg().prop_filter_map("the proposal has no payload", |proposal| {
+            CompactProposalMsg::from_proposal(&proposal)
+                .map(|compact| ConsensusMsg::CompactProposalMsg(Box::new(compact)))
+        }),
+        vec(any::<HashValue>(), 0..10).prop_map(|hashes| {
+            ConsensusMsg::PayloadRetrievalRequest(Box::new(PayloadRetrievalRequest::new(hashes)))
+        }),
+        vec(proptest::option::of(any::<SignedTransaction>()), 0..10).prop_map(|transactions| {
+            ConsensusMsg::PayloadRetrievalResponse(Box::new(PayloadRetrievalResponse::new(
+                transactions,
+            )))
+        }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(10))]
+
+        #[test]
+        fn test_consensus_msg_wire_round_trip(msg in arb_consensus_msg()) {
+            let bytes = bcs::to_bytes(&msg).unwrap();
+            let decoded: ConsensusMsg = bcs::from_bytes(&bytes).unwrap();
+            prop_assert_eq!(bcs::to_bytes(&decoded).unwrap(), bytes);
+        }
+    }
+}
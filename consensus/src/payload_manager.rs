@@ -0,0 +1,406 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    network_interface::{ConsensusMsg, ConsensusNetworkSender},
+    state_replication::TxnManager,
+};
+use anyhow::{anyhow, ensure, Context};
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_metrics::monitor;
+use aptos_types::transaction::SignedTransaction;
+use bytes::Bytes;
+use consensus_types::{
+    block_retrieval::{BlockRetrievalRequest, BlockRetrievalStatus},
+    common::Author,
+    compact_proposal_msg::CompactProposalMsg,
+    payload_retrieval::{PayloadRetrievalRequest, PayloadRetrievalResponse},
+    proposal_msg::ProposalMsg,
+};
+use futures::channel::oneshot;
+use network::{
+    protocols::{network::ApplicationNetworkSender, rpc::error::RpcError},
+    ProtocolId,
+};
+use std::{sync::Arc, time::Duration};
+
+/// Timeout for retrieving the missing transactions of a compact proposal from its proposer.
+const PAYLOAD_RETRIEVAL_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Timeout for retrieving the full block of a compact proposal whose payload cannot be resolved.
+const PROPOSAL_RETRIEVAL_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Resolves the payloads of compact proposals and serves the payload retrieval requests of the
+/// other validators, both from the local mempool.
+#[derive(Clone)]
+pub struct PayloadManager {
+    txn_manager: Arc<dyn TxnManager>,
+    network_sender: ConsensusNetworkSender,
+}
+
+impl PayloadManager {
+    pub fn new(txn_manager: Arc<dyn TxnManager>, network_sender: ConsensusNetworkSender) -> Self {
+        Self {
+            txn_manager,
+            network_sender,
+        }
+    }
+
+    /// Rebuilds the ProposalMsg of `compact` received from `peer_id`: the transactions are looked
+    /// up in the local mempool and the missing ones are retrieved from the proposer. If the payload
+    /// cannot be resolved that way, the full block is requested from the proposer instead.
+    pub async fn resolve(
+        &self,
+        peer_id: Author,
+        compact: CompactProposalMsg,
+    ) -> anyhow::Result<ProposalMsg> {
+        ensure!(
+            compact.proposer() == peer_id,
+            "Compact proposal from {} relayed by {}",
+            compact.proposer(),
+            peer_id
+        );
+        match self.resolve_payload(peer_id, compact.clone()).await {
+            Ok(proposal_msg) => Ok(proposal_msg),
+            Err(e) => {
+                warn!(
+                    remote_peer = peer_id,
+                    error = ?e,
+                    "Failed to resolve the payload of {}, retrieving the full proposal",
+                    compact
+                );
+                self.retrieve_proposal(peer_id, compact).await
+            }
+        }
+    }
+
+    async fn resolve_payload(
+        &self,
+        peer_id: Author,
+        compact: CompactProposalMsg,
+    ) -> anyhow::Result<ProposalMsg> {
+        let hashes = compact.payload_hashes().to_vec();
+        let mut transactions = self.txn_manager.get_transactions(hashes.clone()).await?;
+
+        let (missing_indices, missing_hashes): (Vec<_>, Vec<_>) = hashes
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| transactions[*i].is_none())
+            .unzip();
+        if !missing_hashes.is_empty() {
+            debug!(
+                remote_peer = peer_id,
+                "Retrieving {} of {} txns of {}",
+                missing_hashes.len(),
+                transactions.len(),
+                compact
+            );
+            let retrieved = self.retrieve(peer_id, missing_hashes).await?;
+            for (i, txn) in missing_indices.into_iter().zip(retrieved) {
+                transactions[i] = txn;
+            }
+        }
+
+        let payload = transactions
+            .into_iter()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow!("Proposer is missing txns of {}", compact))?;
+        compact.into_proposal(payload)
+    }
+
+    /// Retrieves the transactions of `hashes` from `peer_id`, `None` for the ones it does not have.
+    async fn retrieve(
+        &self,
+        peer_id: Author,
+        hashes: Vec<HashValue>,
+    ) -> anyhow::Result<Vec<Option<SignedTransaction>>> {
+        let request = PayloadRetrievalRequest::new(hashes);
+        let msg = ConsensusMsg::PayloadRetrievalRequest(Box::new(request.clone()));
+        let response_msg = monitor!(
+            "payload_retrieval",
+            self.network_sender
+                .send_rpc(peer_id, msg, PAYLOAD_RETRIEVAL_TIMEOUT)
+                .await?
+        );
+        let response = match response_msg {
+            ConsensusMsg::PayloadRetrievalResponse(resp) => *resp,
+            _ => return Err(anyhow!("Invalid response to request")),
+        };
+        response
+            .verify_and_take_transactions(&request)
+            .map_err(|e| {
+                error!(
+                    SecurityEvent::InvalidRetrievedPayload,
+                    remote_peer = peer_id,
+                    error = ?e,
+                );
+                e
+            })
+    }
+
+    /// Retrieves the block of `compact` from `peer_id` and rebuilds the ProposalMsg with it.
+    async fn retrieve_proposal(
+        &self,
+        peer_id: Author,
+        compact: CompactProposalMsg,
+    ) -> anyhow::Result<ProposalMsg> {
+        let request = BlockRetrievalRequest::new(compact.block_id(), 1);
+        let msg = ConsensusMsg::BlockRetrievalRequest(Box::new(request));
+        let response_msg = monitor!(
+            "proposal_retrieval",
+            self.network_sender
+                .send_rpc(peer_id, msg, PROPOSAL_RETRIEVAL_TIMEOUT)
+                .await?
+        );
+        let response = match response_msg {
+            ConsensusMsg::BlockRetrievalResponse(resp) => *resp,
+            _ => return Err(anyhow!("Invalid response to request")),
+        };
+        ensure!(
+            response.status() == BlockRetrievalStatus::Succeeded,
+            "Failed to retrieve the block of {}: {:?}",
+            compact,
+            response.status()
+        );
+        let block = match response.blocks().as_slice() {
+            [block] if block.id() == compact.block_id() => block.clone(),
+            _ => return Err(anyhow!("Unexpected blocks retrieved for {}", compact)),
+        };
+        Ok(ProposalMsg::new(block, compact.sync_info().clone()))
+    }
+
+    /// Serves a payload retrieval request from the local mempool.
+    pub async fn process_payload_retrieval(
+        &self,
+        request: PayloadRetrievalRequest,
+        protocol: ProtocolId,
+        response_sender: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> anyhow::Result<()> {
+        let transactions = self
+            .txn_manager
+            .get_transactions(request.hashes().to_vec())
+            .await?;
+        let response = Box::new(PayloadRetrievalResponse::new(transactions));
+        let response_bytes =
+            protocol.to_bytes(&ConsensusMsg::PayloadRetrievalResponse(response))?;
+        response_sender
+            .send(Ok(response_bytes.into()))
+            .map_err(|e| anyhow!("{:?}", e))
+            .context("[PayloadManager] Failed to process payload retrieval")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::MempoolError;
+    use aptos_config::network_id::NetworkId;
+    use aptos_types::validator_signer::ValidatorSigner;
+    use channel::{aptos_channel, message_queues::QueueStyle};
+    use consensus_types::{
+        block::{
+            block_test_utils::{certificate_for_genesis, random_payload},
+            Block,
+        },
+        block_retrieval::BlockRetrievalResponse,
+        common::Payload,
+        sync_info::SyncInfo,
+    };
+    use executor_types::StateComputeResult;
+    use futures::{future::BoxFuture, StreamExt};
+    use network::{
+        application::storage::PeerMetadataStorage,
+        peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+        protocols::{network::NewNetworkSender, wire::handshake::v1::ProtocolIdSet},
+        transport::ConnectionMetadata,
+    };
+    use std::{collections::HashMap, iter::FromIterator};
+
+    /// A TxnManager whose mempool holds a fixed set of transactions.
+    struct FixedTxnManager(HashMap<HashValue, SignedTransaction>);
+
+    impl FixedTxnManager {
+        fn new(txns: &[SignedTransaction]) -> Self {
+            Self(
+                txns.iter()
+                    .map(|txn| (txn.clone().committed_hash(), txn.clone()))
+                    .collect(),
+            )
+        }
+
+        fn get(&self, hashes: &[HashValue]) -> Vec<Option<SignedTransaction>> {
+            hashes
+                .iter()
+                .map(|hash| self.0.get(hash).cloned())
+                .collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl TxnManager for FixedTxnManager {
+        async fn pull_txns(
+            &self,
+            _max_size: u64,
+            _exclude_txns: Vec<&Payload>,
+            _callback: BoxFuture<'static, ()>,
+            _pending_ordering: bool,
+        ) -> Result<Payload, MempoolError> {
+            unimplemented!()
+        }
+
+        async fn get_transactions(
+            &self,
+            hashes: Vec<HashValue>,
+        ) -> Result<Vec<Option<SignedTransaction>>, MempoolError> {
+            Ok(self.get(&hashes))
+        }
+
+        async fn notify_failed_txn(
+            &self,
+            _block: &Block,
+            _compute_result: &StateComputeResult,
+        ) -> Result<(), MempoolError> {
+            Ok(())
+        }
+    }
+
+    /// Returns a network sender whose RPCs to `proposer` are served from the `proposer_txns` and
+    /// the `proposer_blocks`.
+    fn proposer_network(
+        proposer: Author,
+        proposer_txns: &[SignedTransaction],
+        proposer_blocks: Vec<Block>,
+    ) -> ConsensusNetworkSender {
+        let (network_reqs_tx, mut network_reqs_rx) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let mut network_sender = ConsensusNetworkSender::new(
+            PeerManagerRequestSender::new(network_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        );
+        let peer_metadata_storage = PeerMetadataStorage::new(&[NetworkId::Validator]);
+        let mut conn_meta = ConnectionMetadata::mock(proposer);
+        conn_meta.application_protocols = ProtocolIdSet::from_iter([ProtocolId::ConsensusRpcBcs]);
+        peer_metadata_storage.insert_connection(NetworkId::Validator, conn_meta);
+        network_sender.initialize(peer_metadata_storage);
+
+        let proposer_txns = FixedTxnManager::new(proposer_txns);
+        tokio::spawn(async move {
+            while let Some(request) = network_reqs_rx.next().await {
+                let request = match request {
+                    PeerManagerRequest::SendRpc(_, request) => request,
+                    PeerManagerRequest::SendDirectSend(..) => continue,
+                };
+                let response = match request.protocol_id.from_bytes(&request.data).unwrap() {
+                    ConsensusMsg::PayloadRetrievalRequest(request) => {
+                        ConsensusMsg::PayloadRetrievalResponse(Box::new(
+                            PayloadRetrievalResponse::new(proposer_txns.get(request.hashes())),
+                        ))
+                    }
+                    ConsensusMsg::BlockRetrievalRequest(request) => {
+                        let response = match proposer_blocks
+                            .iter()
+                            .find(|block| block.id() == request.block_id())
+                        {
+                            Some(block) => BlockRetrievalResponse::new(
+                                BlockRetrievalStatus::Succeeded,
+                                vec![block.clone()],
+                            ),
+                            None => BlockRetrievalResponse::new(
+                                BlockRetrievalStatus::IdNotFound,
+                                vec![],
+                            ),
+                        };
+                        ConsensusMsg::BlockRetrievalResponse(Box::new(response))
+                    }
+                    msg => panic!("Unexpected rpc: {:?}", msg),
+                };
+                let bytes = request.protocol_id.to_bytes(&response).unwrap();
+                request.res_tx.send(Ok(bytes.into())).unwrap();
+            }
+        });
+        network_sender
+    }
+
+    fn proposal_with_payload(signer: &ValidatorSigner, payload: Payload) -> ProposalMsg {
+        let quorum_cert = certificate_for_genesis();
+        let block = Block::new_proposal(payload, 1, 1, quorum_cert.clone(), signer);
+        ProposalMsg::new(
+            block,
+            SyncInfo::new(quorum_cert.clone(), quorum_cert, None, None),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_resolve_from_mempool() {
+        let signer = ValidatorSigner::random(None);
+        let payload = random_payload(5);
+        let proposal_msg = proposal_with_payload(&signer, payload.clone());
+        let compact = CompactProposalMsg::from_proposal(&proposal_msg).unwrap();
+
+        // The proposer has nothing to serve: all the txns come from the local mempool.
+        let payload_manager = PayloadManager::new(
+            Arc::new(FixedTxnManager::new(&payload)),
+            proposer_network(signer.author(), &[], vec![]),
+        );
+        assert_eq!(
+            payload_manager
+                .resolve(signer.author(), compact)
+                .await
+                .unwrap(),
+            proposal_msg
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_retrieves_missing_txns() {
+        let signer = ValidatorSigner::random(None);
+        let payload = random_payload(5);
+        let proposal_msg = proposal_with_payload(&signer, payload.clone());
+        let compact = CompactProposalMsg::from_proposal(&proposal_msg).unwrap();
+
+        // The proposer only serves the txns, not the block.
+        let payload_manager = PayloadManager::new(
+            Arc::new(FixedTxnManager::new(&payload[..2])),
+            proposer_network(signer.author(), &payload, vec![]),
+        );
+        assert_eq!(
+            payload_manager
+                .resolve(signer.author(), compact)
+                .await
+                .unwrap(),
+            proposal_msg
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_falls_back_to_the_full_proposal() {
+        let signer = ValidatorSigner::random(None);
+        let payload = random_payload(5);
+        let proposal_msg = proposal_with_payload(&signer, payload.clone());
+        let compact = CompactProposalMsg::from_proposal(&proposal_msg).unwrap();
+
+        // Neither side has the txns anymore, but the proposer still has the block.
+        let payload_manager = PayloadManager::new(
+            Arc::new(FixedTxnManager::new(&payload[..2])),
+            proposer_network(signer.author(), &[], vec![proposal_msg.proposal().clone()]),
+        );
+        assert_eq!(
+            payload_manager
+                .resolve(signer.author(), compact.clone())
+                .await
+                .unwrap(),
+            proposal_msg
+        );
+
+        // Without the block the compact proposal cannot be resolved.
+        let payload_manager = PayloadManager::new(
+            Arc::new(FixedTxnManager::new(&payload[..2])),
+            proposer_network(signer.author(), &[], vec![]),
+        );
+        assert!(payload_manager
+            .resolve(signer.author(), compact)
+            .await
+            .is_err());
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
         proposal_generator::ProposalGenerator,
         proposer_election::ProposerElection,
         round_state::{NewRoundEvent, NewRoundReason, RoundState, RoundStateLogSchema},
+        transaction_shuffler::{is_shuffled, shuffle_seed},
     },
     logging::{LogEvent, LogSchema},
     metrics_safety_rules::MetricsSafetyRules,
@@ -48,7 +49,11 @@ use futures::{channel::oneshot, FutureExt, StreamExt};
 use safety_rules::ConsensusState;
 use safety_rules::TSafetyRules;
 use serde::Serialize;
-use std::{mem::Discriminant, sync::Arc, time::Duration};
+use std::{
+    mem::Discriminant,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use termion::color::*;
 
 #[derive(Serialize, Clone)]
@@ -146,6 +151,9 @@ pub struct RoundManager {
     storage: Arc<dyn PersistentLivenessStorage>,
     sync_only: bool,
     onchain_config: OnChainConsensusConfig,
+    // Proposal held back by storage backpressure, generated by the event loop once the deadline
+    // passes if we're still in its round.
+    delayed_proposal: Option<(NewRoundEvent, Instant)>,
 }
 
 impl RoundManager {
@@ -183,6 +191,7 @@ impl RoundManager {
             storage,
             sync_only,
             onchain_config,
+            delayed_proposal: None,
         }
     }
 
@@ -220,34 +229,67 @@ impl RoundManager {
     ) -> anyhow::Result<()> {
         counters::CURRENT_ROUND.set(new_round_event.round as i64);
         counters::ROUND_TIMEOUT_MS.set(new_round_event.timeout.as_millis() as i64);
-        match new_round_event.reason {
+        let result = match new_round_event.reason {
             NewRoundReason::QCReady => {
                 counters::QC_ROUNDS_COUNT.inc();
+                "certified"
             }
             NewRoundReason::Timeout => {
                 counters::TIMEOUT_ROUNDS_COUNT.inc();
+                "timeout"
             }
         };
+        // The previous round ended with the certificate starting this round, round 0 is the
+        // genesis of the epoch and has no proposer.
+        let previous_round = new_round_event.round - 1;
+        if previous_round > 0 {
+            let proposer = self.proposer_election.get_valid_proposer(previous_round);
+            counters::PROPOSER_ROUNDS_COUNT
+                .with_label_values(&[&proposer.to_string(), result])
+                .inc();
+        }
         debug!(
             self.new_log(LogEvent::NewRound),
             reason = new_round_event.reason
         );
+        self.delayed_proposal = None;
         if self
             .proposer_election
             .is_valid_proposer(self.proposal_generator.author(), new_round_event.round)
         {
-            let proposal_msg = Box::new(self.generate_proposal(new_round_event).await?);
-            let mut network = self.network.clone();
-            #[cfg(feature = "failpoints")]
+            let delay = self.proposal_generator.proposal_delay();
+            if delay.is_zero() {
+                self.propose(new_round_event).await?;
+            } else {
+                self.delayed_proposal = Some((new_round_event, Instant::now() + delay));
+            }
+        }
+        Ok(())
+    }
+
+    /// Generates the proposal held back by storage backpressure, unless the round has moved on
+    /// while waiting.
+    async fn process_delayed_proposal(&mut self) -> anyhow::Result<()> {
+        match self.delayed_proposal.take() {
+            Some((new_round_event, _))
+                if new_round_event.round == self.round_state.current_round() =>
             {
-                self.attempt_to_inject_reconfiguration_error(&proposal_msg)
-                    .await?;
+                self.propose(new_round_event).await
             }
-            network
-                .broadcast(ConsensusMsg::ProposalMsg(proposal_msg))
-                .await;
-            counters::PROPOSALS_COUNT.inc();
+            _ => Ok(()),
+        }
+    }
+
+    async fn propose(&mut self, new_round_event: NewRoundEvent) -> anyhow::Result<()> {
+        let proposal_msg = Box::new(self.generate_proposal(new_round_event).await?);
+        let mut network = self.network.clone();
+        #[cfg(feature = "failpoints")]
+        {
+            self.attempt_to_inject_reconfiguration_error(&proposal_msg)
+                .await?;
         }
+        network.broadcast_proposal(*proposal_msg).await;
+        counters::PROPOSALS_COUNT.inc();
         Ok(())
     }
 
@@ -542,6 +584,18 @@ impl RoundManager {
             proposal,
         );
 
+        if self.proposal_generator.shuffles_transactions() {
+            ensure!(
+                is_shuffled(
+                    shuffle_seed(proposal.parent_id(), proposal.round()),
+                    proposal.payload().map_or(&[][..], Vec::as_slice),
+                ),
+                "[RoundManager] Proposal {} from {} doesn't have its transactions shuffled",
+                proposal,
+                author,
+            );
+        }
+
         let block_time_since_epoch = Duration::from_micros(proposal.timestamp_usecs());
 
         ensure!(
@@ -564,6 +618,7 @@ impl RoundManager {
             .proposer_election
             .get_valid_proposer(proposal_round + 1);
         debug!(self.new_log(LogEvent::Vote).remote_peer(author), "{}", vote);
+        counters::VOTE_LATENCY_S.observe_duration(self.round_state.current_round_elapsed());
 
         self.round_state.record_vote(vote.clone());
         let vote_msg = VoteMsg::new(vote, self.block_store.sync_info());
@@ -835,7 +890,33 @@ impl RoundManager {
         >,
     ) {
         info!(epoch = self.epoch_state().epoch, "RoundManager started");
-        while let Some((peer_id, event)) = event_rx.next().await {
+        loop {
+            let proposal_deadline = self
+                .delayed_proposal
+                .as_ref()
+                .map(|(_, deadline)| *deadline);
+            let delayed_proposal = async move {
+                match proposal_deadline {
+                    Some(deadline) => {
+                        tokio::time::sleep_until(tokio::time::Instant::from_std(deadline)).await
+                    }
+                    None => futures::future::pending().await,
+                }
+            };
+            let (peer_id, event) = tokio::select! {
+                next = event_rx.next() => match next {
+                    Some(next) => next,
+                    None => break,
+                },
+                _ = delayed_proposal => {
+                    let result = monitor!(
+                        "process_delayed_proposal",
+                        self.process_delayed_proposal().await
+                    );
+                    self.log_event_result(result);
+                    continue;
+                }
+            };
             let result = match event {
                 VerifiedEvent::ProposalMsg(proposal_msg) => {
                     monitor!(
@@ -871,17 +952,20 @@ impl RoundManager {
                 unexpected_event => unreachable!("Unexpected event: {:?}", unexpected_event),
             }
             .with_context(|| format!("from peer {}", peer_id));
+            self.log_event_result(result);
+        }
+        info!(epoch = self.epoch_state().epoch, "RoundManager stopped");
+    }
 
-            let round_state = self.round_state();
-            match result {
-                Ok(_) => trace!(RoundStateLogSchema::new(round_state)),
-                Err(e) => {
-                    counters::ERROR_COUNT.inc();
-                    error!(error = ?e, kind = error_kind(&e), RoundStateLogSchema::new(round_state));
-                }
+    fn log_event_result(&self, result: anyhow::Result<()>) {
+        let round_state = self.round_state();
+        match result {
+            Ok(_) => trace!(RoundStateLogSchema::new(round_state)),
+            Err(e) => {
+                counters::ERROR_COUNT.inc();
+                error!(error = ?e, kind = error_kind(&e), RoundStateLogSchema::new(round_state));
             }
         }
-        info!(epoch = self.epoch_state().epoch, "RoundManager stopped");
     }
 
     /// Given R1 <- B2 if R1 has the reconfiguration txn, we inject error on B2 if R1.round + 1 = B2.round
@@ -204,6 +204,7 @@ impl NodeSetup {
             Arc::new(MockTransactionManager::new(None)),
             time_service.clone(),
             1,
+            false,
         );
 
         let round_state = Self::create_round_state(time_service);
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    block_storage::tracing::{PipelineStage, PipelineStageTimer},
     counters,
     error::StateSyncError,
+    liveness::storage_backpressure::StorageBackpressure,
     state_replication::{StateComputer, StateComputerCommitCallBackType, TxnManager},
 };
 use anyhow::Result;
@@ -20,12 +22,14 @@ use consensus_types::{block::Block, executed_block::ExecutedBlock};
 use executor_types::{BlockExecutorTrait, Error as ExecutionError, StateComputeResult};
 use fail::fail_point;
 use futures::{SinkExt, StreamExt};
-use std::{boxed::Box, sync::Arc};
+use std::{boxed::Box, sync::Arc, time::Instant};
+use tracing::Instrument;
 
 type NotificationType = (
     Box<dyn FnOnce() + Send + Sync>,
     Vec<Transaction>,
     Vec<ContractEvent>,
+    PipelineStageTimer,
 );
 
 /// Basic communication with the Execution module;
@@ -36,6 +40,8 @@ pub struct ExecutionProxy {
     state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
     async_state_sync_notifier: channel::Sender<NotificationType>,
     validators: Mutex<Vec<AccountAddress>>,
+    // Paces the proposals by the latency of the commits.
+    storage_backpressure: Arc<StorageBackpressure>,
 }
 
 impl ExecutionProxy {
@@ -43,16 +49,20 @@ impl ExecutionProxy {
         executor: Box<dyn BlockExecutorTrait>,
         mempool_notifier: Arc<dyn TxnManager>,
         state_sync_notifier: Arc<dyn ConsensusNotificationSender>,
+        storage_backpressure: Arc<StorageBackpressure>,
         handle: &tokio::runtime::Handle,
     ) -> Self {
         let (tx, mut rx) =
             channel::new::<NotificationType>(10, &counters::PENDING_STATE_SYNC_NOTIFICATION);
         let notifier = state_sync_notifier.clone();
         handle.spawn(async move {
-            while let Some((callback, txns, reconfig_events)) = rx.next().await {
+            while let Some((callback, txns, reconfig_events, timer)) = rx.next().await {
                 if let Err(e) = monitor!(
                     "notify_state_sync",
-                    notifier.notify_new_commit(txns, reconfig_events).await
+                    notifier
+                        .notify_new_commit(txns, reconfig_events)
+                        .instrument(timer.span().clone())
+                        .await
                 ) {
                     error!(error = ?e, "Failed to notify state synchronizer");
                 }
@@ -66,6 +76,7 @@ impl ExecutionProxy {
             state_sync_notifier,
             async_state_sync_notifier: tx,
             validators: Mutex::new(vec![]),
+            storage_backpressure,
         }
     }
 }
@@ -91,21 +102,26 @@ impl StateComputer for ExecutionProxy {
         );
 
         // TODO: figure out error handling for the prologue txn
-        let compute_result = monitor!(
-            "execute_block",
-            self.executor.execute_block(
-                (
-                    block.id(),
-                    block.transactions_to_execute(&self.validators.lock())
-                ),
-                parent_block_id
-            )
-        )?;
+        let compute_result = PipelineStageTimer::for_block(PipelineStage::EXECUTE, block)
+            .in_scope(|| {
+                monitor!(
+                    "execute_block",
+                    self.executor.execute_block(
+                        (
+                            block.id(),
+                            block.transactions_to_execute(&self.validators.lock())
+                        ),
+                        parent_block_id
+                    )
+                )
+            })?;
 
         // notify mempool about failed transaction
+        let notify_timer = PipelineStageTimer::for_block(PipelineStage::NOTIFY_FAILED_TXNS, block);
         if let Err(e) = self
             .mempool_notifier
             .notify_failed_txn(block, &compute_result)
+            .instrument(notify_timer.span().clone())
             .await
         {
             error!(
@@ -132,11 +148,31 @@ impl StateComputer for ExecutionProxy {
             reconfig_events.extend(block.reconfig_event());
         }
 
-        monitor!(
-            "commit_block",
-            self.executor
-                .commit_blocks(block_ids, finality_proof.clone())?
+        let ledger_info = finality_proof.ledger_info();
+        let commit_timer = PipelineStageTimer::for_round(
+            PipelineStage::COMMIT,
+            ledger_info.epoch(),
+            ledger_info.round(),
+        );
+        commit_timer.record_block_id(ledger_info.consensus_block_id());
+        let commit_start = Instant::now();
+        commit_timer.in_scope(|| {
+            monitor!(
+                "commit_block",
+                self.executor
+                    .commit_blocks(block_ids, finality_proof.clone())
+            )
+        })?;
+        self.storage_backpressure
+            .record_commit_latency(commit_start.elapsed());
+        drop(commit_timer);
+
+        let notify_timer = PipelineStageTimer::for_round(
+            PipelineStage::NOTIFY_COMMIT,
+            ledger_info.epoch(),
+            ledger_info.round(),
         );
+        notify_timer.record_block_id(ledger_info.consensus_block_id());
 
         let blocks = blocks.to_vec();
         let wrapped_callback = move || {
@@ -144,7 +180,12 @@ impl StateComputer for ExecutionProxy {
         };
         self.async_state_sync_notifier
             .clone()
-            .send((Box::new(wrapped_callback), txns, reconfig_events))
+            .send((
+                Box::new(wrapped_callback),
+                txns,
+                reconfig_events,
+                notify_timer,
+            ))
             .await
             .expect("Failed to send async state sync notification");
 
@@ -4,7 +4,9 @@
 use crate::error::{MempoolError, StateSyncError};
 use anyhow::Result;
 use aptos_crypto::HashValue;
-use aptos_types::{epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures};
+use aptos_types::{
+    epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures, transaction::SignedTransaction,
+};
 use consensus_types::{block::Block, common::Payload, executed_block::ExecutedBlock};
 use executor_types::{Error as ExecutionError, StateComputeResult};
 use futures::future::BoxFuture;
@@ -30,6 +32,12 @@ pub trait TxnManager: Send + Sync {
         pending_ordering: bool,
     ) -> Result<Payload, MempoolError>;
 
+    /// Looks up transactions by hash, returning `None` for the ones that are not available.
+    async fn get_transactions(
+        &self,
+        hashes: Vec<HashValue>,
+    ) -> Result<Vec<Option<SignedTransaction>>, MempoolError>;
+
     /// Notifies TxnManager about the txns which failed execution. (Committed txns is notified by
     /// state sync.)
     async fn notify_failed_txn(
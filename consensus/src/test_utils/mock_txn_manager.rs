@@ -3,9 +3,10 @@
 
 use crate::{error::MempoolError, state_replication::TxnManager, txn_manager::MempoolProxy};
 use anyhow::Result;
+use aptos_crypto::HashValue;
 use aptos_mempool::ConsensusRequest;
 use aptos_types::{
-    transaction::{ExecutionStatus, TransactionStatus},
+    transaction::{ExecutionStatus, SignedTransaction, TransactionStatus},
     vm_status::StatusCode,
 };
 use consensus_types::{
@@ -56,6 +57,16 @@ impl TxnManager for MockTransactionManager {
         Ok(random_payload(10))
     }
 
+    async fn get_transactions(
+        &self,
+        hashes: Vec<HashValue>,
+    ) -> Result<Vec<Option<SignedTransaction>>, MempoolError> {
+        match &self.mempool_proxy {
+            Some(mempool_proxy) => mempool_proxy.get_transactions(hashes).await,
+            None => Ok(vec![None; hashes.len()]),
+        }
+    }
+
     async fn notify_failed_txn(
         &self,
         block: &Block,
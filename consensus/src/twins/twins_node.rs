@@ -4,6 +4,7 @@
 use crate::{
     counters,
     epoch_manager::EpochManager,
+    liveness::storage_backpressure::StorageBackpressure,
     network::NetworkTask,
     network_interface::{ConsensusNetworkEvents, ConsensusNetworkSender},
     network_tests::{NetworkPlayground, TwinId},
@@ -133,6 +134,9 @@ impl SMRNode {
             txn_manager,
             state_computer,
             storage.clone(),
+            Arc::new(StorageBackpressure::new(
+                config.consensus.storage_backpressure,
+            )),
             reconfig_listener,
         );
         let (network_task, network_receiver) = NetworkTask::new(network_events, self_receiver);
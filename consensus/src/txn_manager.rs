@@ -3,10 +3,13 @@
 
 use crate::{error::MempoolError, state_replication::TxnManager};
 use anyhow::{format_err, Result};
+use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
-use aptos_mempool::{ConsensusRequest, ConsensusResponse, TransactionSummary};
+use aptos_mempool::{
+    ConsensusRequest, ConsensusResponse, RejectedTransactionSummary, TransactionSummary,
+};
 use aptos_metrics::monitor;
-use aptos_types::transaction::TransactionStatus;
+use aptos_types::transaction::{SignedTransaction, TransactionStatus};
 use consensus_types::{block::Block, common::Payload};
 use executor_types::StateComputeResult;
 use fail::fail_point;
@@ -127,6 +130,40 @@ impl TxnManager for MempoolProxy {
         Ok(txns)
     }
 
+    async fn get_transactions(
+        &self,
+        hashes: Vec<HashValue>,
+    ) -> Result<Vec<Option<SignedTransaction>>, MempoolError> {
+        let (callback, callback_rcv) = oneshot::channel();
+        let req = ConsensusRequest::GetTransactionsRequest(hashes, callback);
+        // send to shared mempool
+        self.consensus_to_mempool_sender
+            .clone()
+            .try_send(req)
+            .map_err(anyhow::Error::from)?;
+        // wait for response
+        match monitor!(
+            "get_txns",
+            timeout(
+                Duration::from_millis(self.mempool_txn_pull_timeout_ms),
+                callback_rcv
+            )
+            .await
+        ) {
+            Err(_) => Err(anyhow::anyhow!(
+                "[consensus] did not receive GetTransactionsResponse on time"
+            )
+            .into()),
+            Ok(resp) => match resp.map_err(anyhow::Error::from)?? {
+                ConsensusResponse::GetTransactionsResponse(txns) => Ok(txns),
+                _ => Err(anyhow::anyhow!(
+                    "[consensus] did not receive expected GetTransactionsResponse"
+                )
+                .into()),
+            },
+        }
+    }
+
     async fn notify_failed_txn(
         &self,
         block: &Block,
@@ -142,10 +179,12 @@ impl TxnManager for MempoolProxy {
             .iter()
             .zip_eq(compute_results.compute_status().iter().skip(1))
         {
-            if let TransactionStatus::Discard(_) = status {
-                rejected_txns.push(TransactionSummary {
+            if let TransactionStatus::Discard(reason) = status {
+                rejected_txns.push(RejectedTransactionSummary {
                     sender: txn.sender(),
                     sequence_number: txn.sequence_number(),
+                    hash: txn.clone().committed_hash(),
+                    reason: *reason,
                 });
             }
         }
@@ -220,6 +220,24 @@ impl Ed25519Signature {
         }
         Ok(())
     }
+
+    /// Checks that batch verification gives the same result as `verify` for this signature: on top
+    /// of the malleability of S, that R and the public key are canonical encodings of torsion-free
+    /// points that are not of small order. Batch verification only checks the (combined)
+    /// cofactorless equation with random coefficients, so a torsion component in R or the public
+    /// key makes it accept, with some probability, signatures that `verify` rejects. Signatures
+    /// failing this check must be verified one by one.
+    pub fn check_batch_verifiable(
+        &self,
+        public_key: &Ed25519PublicKey,
+    ) -> std::result::Result<(), CryptoMaterialError> {
+        let bytes = self.to_bytes();
+        Ed25519Signature::check_malleability(&bytes)?;
+        let mut r_bytes = [0u8; 32];
+        r_bytes.copy_from_slice(&bytes[..32]);
+        check_strict_point(r_bytes)?;
+        check_strict_point(public_key.0.to_bytes())
+    }
 }
 
 ///////////////////////
@@ -423,8 +441,8 @@ impl Signature for Ed25519Signature {
         message: &T,
         keys_and_signatures: Vec<(Self::VerifyingKeyMaterial, Self)>,
     ) -> Result<()> {
-        for (_, sig) in keys_and_signatures.iter() {
-            Ed25519Signature::check_malleability(&sig.to_bytes())?
+        for (key, sig) in keys_and_signatures.iter() {
+            sig.check_batch_verifiable(key)?
         }
         let mut message_bytes = <T::Hasher as CryptoHasher>::seed().to_vec();
         bcs::serialize_into(&mut message_bytes, &message)
@@ -507,6 +525,24 @@ fn check_s_lt_l(s: &[u8]) -> bool {
     false
 }
 
+/// Check that `bytes` are the canonical encoding of a point of the prime order subgroup, other than
+/// the identity. Besides small order points, this rejects points with a torsion component, which the
+/// cofactorless batch equation accepts with non-negligible probability while `verify_strict` does
+/// not.
+fn check_strict_point(bytes: [u8; 32]) -> std::result::Result<(), CryptoMaterialError> {
+    let compressed = curve25519_dalek::edwards::CompressedEdwardsY(bytes);
+    let point = compressed
+        .decompress()
+        .ok_or(CryptoMaterialError::PointNotOnCurveError)?;
+    if point.compress() != compressed {
+        return Err(CryptoMaterialError::CanonicalRepresentationError);
+    }
+    if point.is_small_order() || !point.is_torsion_free() {
+        return Err(CryptoMaterialError::SmallSubgroupError);
+    }
+    Ok(())
+}
+
 #[cfg(any(test, feature = "fuzzing"))]
 use crate::test_utils::{self, KeyPair};
 
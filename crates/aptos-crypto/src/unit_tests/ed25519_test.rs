@@ -21,6 +21,7 @@ use ed25519_dalek::ed25519::signature::Verifier as _;
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 use digest::Digest;
 use proptest::{collection::vec, prelude::*};
+use rand::{rngs::StdRng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use sha2::Sha512;
 
@@ -454,6 +455,104 @@ fn test_publickey_smallorder() {
     }
 }
 
+// Batch verification only checks the verification equation, so signatures with a small order R
+// or public key, which `verify_strict` rejects, must be rejected before batching.
+#[test]
+fn test_check_batch_verifiable_rejects_small_order_points() {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let public_key: Ed25519PublicKey = (&private_key).into();
+    let signature = private_key.sign(&CryptoHashable(0));
+    assert!(signature.check_batch_verifiable(&public_key).is_ok());
+
+    for torsion_point in &EIGHT_TORSION {
+        let small_order_key = Ed25519PublicKey::from_bytes_unchecked(torsion_point).unwrap();
+        assert_eq!(
+            signature.check_batch_verifiable(&small_order_key),
+            Err(CryptoMaterialError::SmallSubgroupError)
+        );
+
+        let mut small_order_r = signature.to_bytes();
+        small_order_r[..32].copy_from_slice(torsion_point);
+        let small_order_r_signature =
+            Ed25519Signature::from_bytes_unchecked(&small_order_r).unwrap();
+        assert_eq!(
+            small_order_r_signature.check_batch_verifiable(&public_key),
+            Err(CryptoMaterialError::SmallSubgroupError)
+        );
+    }
+}
+
+// A signature with a mixed order R = rB + T, where T is of order 8, satisfies the cofactored but
+// not the cofactorless verification equation. The cofactorless batch equation with random
+// coefficients accepts it whenever the coefficient is a multiple of 8, so it must never be batched.
+#[test]
+fn test_batch_verify_rejects_mixed_order_r() {
+    let message = CryptoHashable(0);
+    let message_bytes = signing_message(&message);
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let public_key: Ed25519PublicKey = (&private_key).into();
+
+    // a s.t. A = aB, as derived by ed25519_dalek::ExpandedSecretKey
+    let mut h: Sha512 = Sha512::default();
+    h.update(private_key.to_bytes());
+    let mut key_bytes = [0u8; 32];
+    key_bytes.copy_from_slice(&h.finalize().as_slice()[..32]);
+    key_bytes[0] &= 248;
+    key_bytes[31] &= 127;
+    key_bytes[31] |= 64;
+    let priv_scalar = curve25519_dalek::scalar::Scalar::from_bits(key_bytes);
+
+    let nonce = curve25519_dalek::scalar::Scalar::from_bytes_mod_order([42u8; 32]);
+    let torsion_component = curve25519_dalek::edwards::CompressedEdwardsY(EIGHT_TORSION[1])
+        .decompress()
+        .unwrap();
+    assert_eq!(eight_torsion_order(torsion_component), 8);
+    let mixed_r_point =
+        curve25519_dalek::constants::ED25519_BASEPOINT_POINT.mul(nonce) + torsion_component;
+    assert!(!mixed_r_point.is_small_order());
+
+    // s = r + k a, with k = H(R∥A∥m)
+    let mut h: Sha512 = Sha512::default();
+    h.update(&mixed_r_point.compress().to_bytes());
+    h.update(&public_key.to_bytes());
+    h.update(&message_bytes);
+    let mut output = [0u8; 64];
+    output.copy_from_slice(h.finalize().as_slice());
+    let k = curve25519_dalek::scalar::Scalar::from_bytes_mod_order_wide(&output);
+    let s = nonce + k * priv_scalar;
+
+    let mixed_signature_bytes: Vec<u8> =
+        [mixed_r_point.compress().to_bytes(), s.to_bytes()].concat();
+    let mixed_signature = Ed25519Signature::try_from(&mixed_signature_bytes[..]).unwrap();
+
+    // The cofactored equation holds, the cofactorless one does not.
+    let s_b = curve25519_dalek::constants::ED25519_BASEPOINT_POINT.mul(s);
+    let public_point = curve25519_dalek::edwards::CompressedEdwardsY(public_key.to_bytes())
+        .decompress()
+        .unwrap();
+    assert_eq!(
+        s_b.mul_by_cofactor(),
+        (mixed_r_point + public_point.mul(k)).mul_by_cofactor()
+    );
+    assert!(mixed_signature.verify(&message, &public_key).is_err());
+    assert_eq!(
+        mixed_signature.check_batch_verifiable(&public_key),
+        Err(CryptoMaterialError::SmallSubgroupError)
+    );
+
+    let other_private_key = Ed25519PrivateKey::generate(&mut StdRng::from_seed([1u8; 32]));
+    let other_signature = other_private_key.sign(&message);
+    let keys_and_signatures = vec![
+        ((&other_private_key).into(), other_signature),
+        (public_key, mixed_signature),
+    ];
+    // Batch verification picks fresh random coefficients on every call, without the torsion check
+    // some of these would pass.
+    for _ in 0..64 {
+        assert!(Ed25519Signature::batch_verify(&message, keys_and_signatures.clone()).is_err());
+    }
+}
+
 // The 8-torsion subgroup E[8].
 //
 // In the case of Curve25519, it is cyclic; the i-th element of
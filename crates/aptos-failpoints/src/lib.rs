@@ -0,0 +1,97 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Fail injection for Aptos components.
+//!
+//! Components evaluate failpoints with [`fail_point!`] and [`register`] their names, so that the
+//! failpoints of a node can be listed whether they are active or not. Failpoints are activated by
+//! the `failpoints` section of the node config at startup and toggled at runtime through the node
+//! debug interface (`/failpoints`).
+//!
+//! Actions only take effect in builds with the `failpoints` feature, otherwise [`fail_point!`]
+//! compiles to nothing and [`set`] fails.
+
+use anyhow::{ensure, format_err, Result};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+pub use fail::fail_point;
+
+static REGISTRY: Lazy<Mutex<BTreeSet<&'static str>>> = Lazy::new(|| Mutex::new(BTreeSet::new()));
+
+/// Returns true if the binary is built with failpoints.
+pub fn is_enabled() -> bool {
+    fail::has_failpoints()
+}
+
+/// Registers the failpoints evaluated by a component. Registering a name twice is a no-op.
+pub fn register(names: &[&'static str]) {
+    REGISTRY.lock().extend(names);
+}
+
+/// Returns every registered or active failpoint with its actions, `None` when inactive.
+pub fn list() -> BTreeMap<String, Option<String>> {
+    let mut failpoints: BTreeMap<_, _> = REGISTRY
+        .lock()
+        .iter()
+        .map(|name| (name.to_string(), None))
+        .collect();
+    for (name, actions) in fail::list() {
+        failpoints.insert(name, Some(actions));
+    }
+    failpoints
+}
+
+/// Sets the actions of the failpoint `name`, e.g. `"return"` or `"10%sleep(100)"`. See the `fail`
+/// crate for the syntax.
+pub fn set(name: &str, actions: &str) -> Result<()> {
+    ensure!(
+        is_enabled(),
+        "Failpoints are not enabled in this build, cannot set {}.",
+        name
+    );
+    fail::cfg(name, actions)
+        .map_err(|e| format_err!("Invalid actions {:?} for {}: {}", actions, name, e))
+}
+
+/// Deactivates the failpoint `name`.
+pub fn remove(name: &str) {
+    fail::remove(name);
+}
+
+/// Sets the actions of every failpoint in `failpoints`, as read from the node config.
+pub fn configure(failpoints: &HashMap<String, String>) -> Result<()> {
+    for (name, actions) in failpoints {
+        set(name, actions)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_registered() {
+        register(&["test::registered", "test::registered"]);
+        assert_eq!(list().get("test::registered"), Some(&None));
+    }
+
+    #[test]
+    fn test_set_and_remove() {
+        register(&["test::toggled"]);
+        if !is_enabled() {
+            assert!(set("test::toggled", "return").is_err());
+            return;
+        }
+
+        set("test::toggled", "return").unwrap();
+        assert_eq!(list()["test::toggled"], Some("return".to_string()));
+        remove("test::toggled");
+        assert_eq!(list()["test::toggled"], None);
+        assert!(set("test::toggled", "no such action").is_err());
+    }
+}
@@ -230,6 +230,7 @@ mod tests {
             let account_data = AccountData {
                 authentication_key: auth_vec.into(),
                 sequence_number: account.sequence_number.into(),
+                crsn: None,
             };
             Ok(response(&account_data))
         } else {
@@ -252,6 +253,7 @@ mod tests {
                     gas_used: 0.into(),
                     success: true,
                     vm_status: "Executed".to_string(),
+                    vm_error: None,
                     accumulator_root_hash: HashValue::zero().into(),
                     changes: vec![],
                 };
@@ -52,6 +52,9 @@ pub enum SecurityEvent {
     /// A received block is invalid
     InvalidRetrievedBlock,
 
+    /// The transactions retrieved for a compact proposal are invalid
+    InvalidRetrievedPayload,
+
     /// A block being committed or executed is invalid
     InvalidBlock,
 
@@ -5,7 +5,7 @@ use crate::{self as dl};
 use std::{collections::BTreeMap, fmt};
 use tracing::{
     field::Field,
-    span::{Attributes, Id},
+    span::{Attributes, Id, Record},
     Event, Level, Metadata,
 };
 use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
@@ -146,6 +146,15 @@ where
         span.extensions_mut().insert(data);
     }
 
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("Unable to load span; this is a bug");
+        let mut ext = span.extensions_mut();
+        if let Some(data) = ext.get_mut::<SpanData>() {
+            // fields recorded after the creation of the span, e.g. declared `field::Empty`
+            values.record(data);
+        }
+    }
+
     fn on_event(&self, event: &Event, ctx: Context<S>) {
         let metadata = match translate_metadata(event.metadata()) {
             Some(metadata) => metadata,
@@ -67,4 +67,12 @@ fn verify_tracing_kvs() {
     assert!(s.contains("WARN"));
     assert!(s.contains("true"));
     assert!(s.contains("false"));
+
+    // fields recorded after the span is created
+    let span3 = tracing::span!(Level::ERROR, "recorded", late = tracing::field::Empty);
+    span3.record("late", &"later");
+    span3.in_scope(|| tracing::error!("in recorded span"));
+    let s = logs.write().pop().unwrap();
+    assert!(s.contains("outer.inner.recorded.late"));
+    assert!(s.contains("later"));
 }
@@ -49,6 +49,7 @@
 
 mod json_encoder;
 pub mod json_metrics;
+pub mod metric_pusher;
 pub mod metric_server;
 mod public_metrics;
 pub mod system_metrics;
@@ -80,6 +81,7 @@ pub static NUM_METRICS: Lazy<IntCounterVec> = Lazy::new(|| {
 });
 
 pub fn gather_metrics() -> Vec<prometheus::proto::MetricFamily> {
+    system_metrics::refresh_process_metrics();
     let metric_families = aptos_metrics_core::gather();
     let mut total: u64 = 0;
     let mut families_over_1000: u64 = 0;
@@ -0,0 +1,102 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pushes metrics to a Prometheus pushgateway, for nodes that can't be scraped (e.g. nodes
+//! running behind NAT).
+
+use crate::{metric_server::encode_metrics, system_metrics::refresh_system_metrics, NUM_METRICS};
+use aptos_logger::prelude::*;
+use hyper::{header::CONTENT_TYPE, Body, Client, Method, Request};
+use prometheus::{Encoder, TextEncoder};
+use std::{thread, time::Duration};
+use tokio::runtime;
+
+/// Starts a thread pushing all metrics to the pushgateway at `gateway_url` (plain http) every
+/// `push_interval`. Each push replaces the metrics previously pushed under the same `job` and
+/// grouping `labels`, so the gateway always holds the latest values of this node.
+pub fn start_push_client(
+    gateway_url: String,
+    push_interval: Duration,
+    job: String,
+    labels: Vec<(String, String)>,
+) {
+    // Collect system metrics
+    refresh_system_metrics();
+
+    let url = push_url(&gateway_url, &job, &labels);
+    thread::spawn(move || {
+        let rt = runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        rt.block_on(async move {
+            let client = Client::new();
+            let mut interval = tokio::time::interval(push_interval);
+            loop {
+                interval.tick().await;
+                if let Err(err) = push_metrics(&client, &url).await {
+                    NUM_METRICS.with_label_values(&["push_failures"]).inc();
+                    warn!("Failed to push metrics to {}: {}", url, err);
+                }
+            }
+        })
+    });
+}
+
+async fn push_metrics(
+    client: &Client<hyper::client::HttpConnector>,
+    url: &str,
+) -> Result<(), String> {
+    let encoder = TextEncoder::new();
+    let content_type = encoder.format_type().to_string();
+    let request = Request::builder()
+        .method(Method::PUT)
+        .uri(url)
+        .header(CONTENT_TYPE, content_type)
+        .body(Body::from(encode_metrics(encoder, &[])))
+        .map_err(|err| err.to_string())?;
+    let response = client
+        .request(request)
+        .await
+        .map_err(|err| err.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("pushgateway responded {}", response.status()))
+    }
+}
+
+/// The pushgateway groups pushed metrics by the job and labels encoded in the url path, e.g.
+/// `<gateway_url>/metrics/job/<job>/<label>/<value>`.
+pub(crate) fn push_url(gateway_url: &str, job: &str, labels: &[(String, String)]) -> String {
+    let mut url = format!("{}/metrics", gateway_url.trim_end_matches('/'));
+    push_grouping_label(&mut url, "job", job);
+    for (name, value) in labels {
+        push_grouping_label(&mut url, name, value);
+    }
+    url
+}
+
+/// Appends `/<name>/<value>` to `url`. Values which are empty or have characters that aren't
+/// safe in a path segment (e.g. `/`, quotes or newlines) are base64 encoded, as
+/// `/<name>@base64/<value>`, which the pushgateway decodes.
+fn push_grouping_label(url: &mut String, name: &str, value: &str) {
+    let is_plain = !value.is_empty()
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"-._~".contains(&byte));
+    url.push('/');
+    url.push_str(name);
+    if is_plain {
+        url.push('/');
+        url.push_str(value);
+    } else {
+        url.push_str("@base64/");
+        if value.is_empty() {
+            // the pushgateway's encoding of an empty value, as a path segment can't be empty
+            url.push('=');
+        } else {
+            url.push_str(&base64::encode_config(value, base64::URL_SAFE));
+        }
+    }
+}
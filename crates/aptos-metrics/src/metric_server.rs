@@ -25,7 +25,7 @@ use std::{
 };
 use tokio::runtime;
 
-fn encode_metrics(encoder: impl Encoder, whitelist: &'static [&'static str]) -> Vec<u8> {
+pub(crate) fn encode_metrics(encoder: impl Encoder, whitelist: &'static [&'static str]) -> Vec<u8> {
     let mut metric_families = gather_metrics();
     if !whitelist.is_empty() {
         metric_families = whitelist_metrics(metric_families, whitelist);
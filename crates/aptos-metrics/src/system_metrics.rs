@@ -1,9 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_metrics_core::{register_int_gauge_vec, IntGaugeVec};
+use aptos_metrics_core::{register_int_gauge, register_int_gauge_vec, IntGauge, IntGaugeVec};
 use once_cell::sync::Lazy;
-use sysinfo::{System, SystemExt};
+use std::sync::Mutex;
+use sysinfo::{get_current_pid, NetworkExt, NetworksExt, ProcessExt, System, SystemExt};
 
 static TOTAL_MEMORY_GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!("system_total_memory", "Total system memory", &[]).unwrap()
@@ -16,6 +17,43 @@ static PHYSICAL_CORE_COUNT_GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!("system_physical_core_count", "Physical CPU cores", &[]).unwrap()
 });
 
+static PROCESS_CPU_USAGE_GAUGE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "system_process_cpu_usage_percent",
+        "CPU usage of this process since the previous refresh, 100 per fully used core"
+    )
+    .unwrap()
+});
+
+static PROCESS_MEMORY_GAUGE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "system_process_memory_bytes",
+        "Resident memory of this process"
+    )
+    .unwrap()
+});
+
+static PROCESS_DISK_IO_GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "system_process_disk_io_bytes",
+        "Total bytes read from or written to disk by this process",
+        &["direction"]
+    )
+    .unwrap()
+});
+
+static NETWORK_IO_GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "system_network_io_bytes",
+        "Total bytes received or transmitted over all network interfaces of the host",
+        &["direction"]
+    )
+    .unwrap()
+});
+
+/// Kept across refreshes as CPU usage is computed over the time between two refreshes
+static PROCESS_SYSTEM: Lazy<Mutex<System>> = Lazy::new(|| Mutex::new(System::new()));
+
 pub fn refresh_system_metrics() {
     let mut sys = System::new();
     sys.refresh_system();
@@ -33,3 +71,45 @@ pub fn refresh_system_metrics() {
             .set(physical_core_count as i64);
     }
 }
+
+/// Refreshes the resource usage of this process. Called on every metrics gather so the values are
+/// current whenever they are scraped.
+pub fn refresh_process_metrics() {
+    let pid = match get_current_pid() {
+        Ok(pid) => pid,
+        Err(_) => return,
+    };
+    let mut sys = PROCESS_SYSTEM.lock().unwrap();
+    if sys.refresh_process(pid) {
+        if let Some(process) = sys.process(pid) {
+            PROCESS_CPU_USAGE_GAUGE.set(process.cpu_usage().round() as i64);
+            // sysinfo reports memory in KB
+            PROCESS_MEMORY_GAUGE.set(process.memory().saturating_mul(1024) as i64);
+            let disk_usage = process.disk_usage();
+            PROCESS_DISK_IO_GAUGE
+                .with_label_values(&["read"])
+                .set(disk_usage.total_read_bytes as i64);
+            PROCESS_DISK_IO_GAUGE
+                .with_label_values(&["written"])
+                .set(disk_usage.total_written_bytes as i64);
+        }
+    }
+
+    sys.refresh_networks_list();
+    sys.refresh_networks();
+    let (received, transmitted) =
+        sys.networks()
+            .iter()
+            .fold((0u64, 0u64), |(received, transmitted), (_, data)| {
+                (
+                    received.saturating_add(data.total_received()),
+                    transmitted.saturating_add(data.total_transmitted()),
+                )
+            });
+    NETWORK_IO_GAUGE
+        .with_label_values(&["received"])
+        .set(received as i64);
+    NETWORK_IO_GAUGE
+        .with_label_values(&["transmitted"])
+        .set(transmitted as i64);
+}
@@ -0,0 +1,42 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::metric_pusher::push_url;
+
+#[test]
+fn test_push_url() {
+    assert_eq!(
+        push_url("http://pushgateway:9091", "aptos-node", &[]),
+        "http://pushgateway:9091/metrics/job/aptos-node"
+    );
+    assert_eq!(
+        push_url(
+            "http://pushgateway:9091/",
+            "aptos-node",
+            &[
+                ("role".to_string(), "validator".to_string()),
+                ("chain".to_string(), "testing".to_string()),
+            ]
+        ),
+        "http://pushgateway:9091/metrics/job/aptos-node/role/validator/chain/testing"
+    );
+}
+
+#[test]
+fn test_push_url_encodes_label_values() {
+    assert_eq!(
+        push_url(
+            "http://pushgateway:9091",
+            "aptos/node",
+            &[
+                ("path".to_string(), "a\\b\"c\nd/e".to_string()),
+                ("empty".to_string(), "".to_string()),
+            ]
+        ),
+        format!(
+            "http://pushgateway:9091/metrics/job@base64/{}/path@base64/{}/empty@base64/=",
+            base64::encode_config("aptos/node", base64::URL_SAFE),
+            base64::encode_config("a\\b\"c\nd/e", base64::URL_SAFE),
+        )
+    );
+}
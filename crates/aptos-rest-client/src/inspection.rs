@@ -0,0 +1,271 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Client of the node inspection service, the debug interface served by every node on the port
+//! of its `inspection_service` config. Its endpoints only serve the node config and untyped
+//! metrics, the sync status, connections and mempool stats below are read from the metrics of the
+//! node.
+
+use anyhow::{anyhow, format_err, Result};
+use reqwest::Client as ReqwestClient;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{collections::BTreeMap, time::Duration};
+use url::Url;
+
+const STATE_SYNC_VERSION_METRIC: &str = "aptos_state_sync_version";
+const CONNECTIONS_METRIC: &str = "aptos_connections";
+const MEMPOOL_INDEX_SIZE_METRIC: &str = "core_mempool_index_size";
+
+/// The config of the node and the git revision it was built at, from `GET /node-info`. The config
+/// is left untyped, it can be deserialized into the `NodeConfig` of the version the node runs.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct NodeInfo {
+    #[serde(default)]
+    pub node_config: serde_json::Value,
+    #[serde(default)]
+    pub git_revision: String,
+}
+
+/// Progress of state sync, each version is absent until state sync reached that stage once.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct SyncStatus {
+    /// Latest version written to storage.
+    pub synced_version: Option<u64>,
+    /// Latest version executed, when syncing by executing transactions.
+    pub executed_version: Option<u64>,
+    /// Latest version whose transaction outputs were applied, when syncing outputs.
+    pub applied_version: Option<u64>,
+    /// Latest version synced that ended an epoch.
+    pub synced_epoch_version: Option<u64>,
+}
+
+/// The connections of the node on one of its networks, in one direction. The node does not
+/// report its peers one by one, only the number of connections.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct Connections {
+    pub network_id: String,
+    /// Role of the node on the network, e.g. `validator` or `full_node`.
+    pub role_type: String,
+    /// Short id of the node on the network.
+    pub peer_id: String,
+    /// `inbound` or `outbound`.
+    pub direction: String,
+    pub count: u64,
+}
+
+/// Number of transactions in the mempool of the node.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+pub struct MempoolStats {
+    pub transactions: u64,
+    /// Transactions ready to be pulled into a block.
+    pub ready_transactions: u64,
+    /// Transactions parked until the transactions filling their sequence number gap arrive.
+    pub parked_transactions: u64,
+}
+
+/// The metrics of the node from `GET /metrics`, keyed by name and labels, e.g.
+/// `aptos_connections{direction=inbound,network_id=Public,peer_id=1a2b3c4d,role_type=full_node}`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Metrics(BTreeMap<String, String>);
+
+impl Metrics {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).map(String::as_str)
+    }
+
+    /// Returns the labels and value of each metric named `name`, skipping the values that are not
+    /// integers.
+    pub fn with_name<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = (BTreeMap<&'a str, &'a str>, i64)> + 'a {
+        self.0.iter().filter_map(move |(key, value)| {
+            let labels = key
+                .strip_prefix(name)?
+                .strip_prefix('{')?
+                .strip_suffix('}')?;
+            let labels = labels
+                .split(',')
+                .filter(|label| !label.is_empty())
+                .map(|label| label.split_once('='))
+                .collect::<Option<_>>()?;
+            Some((labels, parse_value(value)?))
+        })
+    }
+
+    fn value_with_label(&self, name: &str, label: &str, label_value: &str) -> Option<i64> {
+        self.with_name(name)
+            .find(|(labels, _)| labels.get(label) == Some(&label_value))
+            .map(|(_, value)| value)
+    }
+}
+
+/// Gauges are reported as floats, e.g. `5` or `5.0`.
+fn parse_value(value: &str) -> Option<i64> {
+    value
+        .parse::<i64>()
+        .ok()
+        .or_else(|| value.parse::<f64>().ok().map(|value| value as i64))
+}
+
+#[derive(Clone, Debug)]
+pub struct InspectionClient {
+    inner: ReqwestClient,
+    base_url: Url,
+}
+
+impl InspectionClient {
+    pub fn new(base_url: Url) -> Self {
+        let inner = ReqwestClient::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap();
+
+        Self { inner, base_url }
+    }
+
+    /// The client of the inspection service served on `port` by the node at `node_url`, e.g. the
+    /// URL of its REST API.
+    pub fn from_node_url(node_url: &Url, port: u16) -> Result<Self> {
+        let mut base_url = node_url.clone();
+        base_url
+            .set_port(Some(port))
+            .map_err(|_| format_err!("Node url {} can't have a port", node_url))?;
+        base_url.set_path("/");
+        Ok(Self::new(base_url))
+    }
+
+    pub async fn get_node_info(&self) -> Result<NodeInfo> {
+        self.get("node-info").await
+    }
+
+    pub async fn get_metrics(&self) -> Result<Metrics> {
+        self.get("metrics").await
+    }
+
+    pub async fn get_sync_status(&self) -> Result<SyncStatus> {
+        let metrics = self.get_metrics().await?;
+        let version = |stage: &str| {
+            metrics
+                .value_with_label(STATE_SYNC_VERSION_METRIC, "type", stage)
+                .map(|version| version as u64)
+        };
+        Ok(SyncStatus {
+            synced_version: version("synced"),
+            executed_version: version("executed_transactions"),
+            applied_version: version("applied_transaction_outputs"),
+            synced_epoch_version: version("synced_epoch"),
+        })
+    }
+
+    /// Lists the open connections of the node, by network and direction.
+    pub async fn get_connections(&self) -> Result<Vec<Connections>> {
+        let metrics = self.get_metrics().await?;
+        Ok(metrics
+            .with_name(CONNECTIONS_METRIC)
+            .filter(|(_, count)| *count > 0)
+            .filter_map(|(labels, count)| {
+                Some(Connections {
+                    network_id: labels.get("network_id")?.to_string(),
+                    role_type: labels.get("role_type")?.to_string(),
+                    peer_id: labels.get("peer_id")?.to_string(),
+                    direction: labels.get("direction")?.to_string(),
+                    count: count as u64,
+                })
+            })
+            .collect())
+    }
+
+    /// Mempool reports its sizes once it received transactions, all of them are 0 before.
+    pub async fn get_mempool_stats(&self) -> Result<MempoolStats> {
+        let metrics = self.get_metrics().await?;
+        let size = |index: &str| {
+            metrics
+                .value_with_label(MEMPOOL_INDEX_SIZE_METRIC, "index", index)
+                .unwrap_or_default() as u64
+        };
+        Ok(MempoolStats {
+            transactions: size("transaction_hash"),
+            ready_transactions: size("priority"),
+            parked_transactions: size("parking_lot"),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let url = self.base_url.join(path)?;
+        let response = self.inner.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Error querying {}: {}", path, response.status()));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(entries: &[(&str, &str)]) -> Metrics {
+        Metrics(
+            entries
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value("5"), Some(5));
+        assert_eq!(parse_value("-5"), Some(-5));
+        assert_eq!(parse_value("5.0"), Some(5));
+        assert_eq!(parse_value("1e3"), Some(1000));
+        assert_eq!(parse_value("five"), None);
+        assert_eq!(parse_value(""), None);
+    }
+
+    #[test]
+    fn test_with_name() {
+        let metrics = metrics(&[
+            (
+                "aptos_connections{direction=inbound,network_id=Public}",
+                "2",
+            ),
+            ("aptos_connections{}", "3.0"),
+            ("aptos_connections_total{direction=inbound}", "4"),
+            ("aptos_connections{direction=outbound}", "NaN-ish"),
+            ("aptos_connections{malformed}", "5"),
+            ("aptos_connections", "6"),
+        ]);
+        let found: Vec<_> = metrics.with_name("aptos_connections").collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    [("direction", "inbound"), ("network_id", "Public")]
+                        .iter()
+                        .copied()
+                        .collect(),
+                    2
+                ),
+                (BTreeMap::new(), 3),
+            ]
+        );
+        assert_eq!(
+            metrics.value_with_label("aptos_connections", "network_id", "Public"),
+            Some(2)
+        );
+        assert_eq!(
+            metrics.value_with_label("aptos_connections", "network_id", "Validator"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_node_url() {
+        let node_url = Url::parse("http://127.0.0.1:8080/v1").unwrap();
+        let client = InspectionClient::from_node_url(&node_url, 9101).unwrap();
+        assert_eq!(client.base_url.as_str(), "http://127.0.0.1:9101/");
+    }
+}
@@ -3,7 +3,10 @@
 
 use anyhow::{anyhow, Result};
 use aptos_api_types::mime_types::BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE;
-pub use aptos_api_types::{self, MoveModuleBytecode, PendingTransaction, Transaction};
+pub use aptos_api_types::{
+    self, MoveModuleBytecode, PendingTransaction, StakePool, StateDiff, Transaction,
+    TransactionSigningMessage, UserTransactionRequest,
+};
 use aptos_crypto::HashValue;
 use aptos_types::{
     account_address::AccountAddress, account_config::aptos_root_address,
@@ -19,6 +22,8 @@ use url::Url;
 pub mod error;
 pub mod faucet;
 pub use faucet::FaucetClient;
+pub mod inspection;
+pub use inspection::InspectionClient;
 pub mod response;
 pub use response::Response;
 mod state;
@@ -192,6 +197,38 @@ impl Client {
         self.json(response).await
     }
 
+    /// Gets the net state changes made after `first_version` up to and including `last_version`
+    /// (the latest version if `None`).
+    pub async fn get_state_diff(
+        &self,
+        first_version: u64,
+        last_version: Option<u64>,
+    ) -> Result<Response<StateDiff>> {
+        let url = self.base_url.join("state_diff")?;
+
+        let mut request = self
+            .inner
+            .get(url)
+            .query(&[("first_version", first_version)]);
+        if let Some(last_version) = last_version {
+            request = request.query(&[("last_version", last_version)])
+        }
+
+        let response = request.send().await?;
+
+        self.json(response).await
+    }
+
+    /// Returns the message the sender of `txn` signs, as computed by the node.
+    pub async fn create_signing_message(
+        &self,
+        txn: &UserTransactionRequest,
+    ) -> Result<Response<TransactionSigningMessage>> {
+        let url = self.base_url.join("transactions/signing_message")?;
+        let response = self.inner.post(url).json(txn).send().await?;
+        self.json(response).await
+    }
+
     pub async fn get_transaction(&self, hash: HashValue) -> Result<Response<Transaction>> {
         self.json(
             self.get_transaction_by_version_or_hash(hash.to_hex_literal())
@@ -289,6 +326,16 @@ impl Client {
         self.json(response).await
     }
 
+    /// Gets the stake delegated to the validator at `address`, with the delegations resolved.
+    pub async fn get_stake_pool(&self, address: AccountAddress) -> Result<Response<StakePool>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/stake_pool", address))?;
+
+        let response = self.inner.get(url).send().await?;
+        self.json(response).await
+    }
+
     pub async fn get_account_modules(
         &self,
         address: AccountAddress,
@@ -327,6 +374,18 @@ impl Client {
         self.json(response).await
     }
 
+    /// Returns whether an account exists at `address`, without fetching its resources.
+    pub async fn account_exists(&self, address: AccountAddress) -> Result<Response<bool>> {
+        let url = self.base_url.join(&format!("accounts/{}", address))?;
+        let response = self.inner.get(url).send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            let state = State::from_headers(response.headers())?;
+            return Ok(Response::new(false, state));
+        }
+        let (_, state) = self.check_response(response).await?;
+        Ok(Response::new(true, state))
+    }
+
     async fn check_response(
         &self,
         response: reqwest::Response,
@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api_types::{Address, U64};
+use aptos_api_types::{Address, AptosErrorCode, ErrorDetails, U64};
 use aptos_types::transaction::authenticator::AuthenticationKey;
 use move_deps::move_core_types::{language_storage::StructTag, parser::parse_struct_tag};
 use serde::{Deserialize, Deserializer, Serialize};
@@ -12,6 +12,8 @@ pub struct RestError {
     pub code: u32,
     pub message: String,
     pub aptos_ledger_version: Option<U64>,
+    pub aptos_error_code: Option<AptosErrorCode>,
+    pub aptos_error_details: Option<ErrorDetails>,
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
@@ -0,0 +1,200 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Private keys encrypted with a password, so that they aren't stored in plain text on disk.
+//!
+//! The key is encrypted with AES-256-GCM, under a key derived from the password with scrypt.
+
+use crate::common::types::{CliError, CliTypedResult};
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead},
+    Aes256Gcm,
+};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+
+const KEYSTORE_VERSION: u8 = 1;
+
+/// Environment variable to read the password from, instead of prompting for it
+pub const PASSWORD_ENV_VAR: &str = "APTOS_KEY_PASSWORD";
+
+// The scrypt parameters recommended for interactive logins
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// The contents of an encrypted key file, the binary fields are hex encoded.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptedKey {
+    version: u8,
+    scrypt_log_n: u8,
+    scrypt_r: u32,
+    scrypt_p: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl EncryptedKey {
+    /// Encrypts the bytes of a private key with `password`
+    pub fn encrypt(key_bytes: &[u8], password: &str) -> CliTypedResult<Self> {
+        Self::encrypt_with_params(key_bytes, password, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)
+    }
+
+    fn encrypt_with_params(
+        key_bytes: &[u8],
+        password: &str,
+        scrypt_log_n: u8,
+        scrypt_r: u32,
+        scrypt_p: u32,
+    ) -> CliTypedResult<Self> {
+        let mut salt = [0u8; SALT_LENGTH];
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let aead = Self::aead(password, &salt, scrypt_log_n, scrypt_r, scrypt_p)?;
+        let ciphertext = aead
+            .encrypt(GenericArray::from_slice(&nonce), key_bytes)
+            .map_err(|_| CliError::UnexpectedError("Failed to encrypt key".to_string()))?;
+
+        Ok(EncryptedKey {
+            version: KEYSTORE_VERSION,
+            scrypt_log_n,
+            scrypt_r,
+            scrypt_p,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the bytes of the private key, failing if `password` is wrong
+    pub fn decrypt(&self, password: &str) -> CliTypedResult<Vec<u8>> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(CliError::UnableToParse(
+                "encrypted key",
+                format!("unsupported version {}", self.version),
+            ));
+        }
+        let salt = decode_hex("salt", &self.salt)?;
+        let nonce = decode_hex("nonce", &self.nonce)?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(CliError::UnableToParse(
+                "encrypted key",
+                "invalid nonce length".to_string(),
+            ));
+        }
+        let ciphertext = decode_hex("ciphertext", &self.ciphertext)?;
+
+        let aead = Self::aead(
+            password,
+            &salt,
+            self.scrypt_log_n,
+            self.scrypt_r,
+            self.scrypt_p,
+        )?;
+        aead.decrypt(GenericArray::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                CliError::CommandArgumentError(
+                    "Unable to decrypt key, the password is wrong or the key file is corrupted"
+                        .to_string(),
+                )
+            })
+    }
+
+    /// Returns the encrypted key stored in a key file, if it is one
+    pub fn from_file_contents(bytes: &[u8]) -> Option<Self> {
+        serde_json::from_slice(bytes).ok()
+    }
+
+    pub fn to_file_contents(&self) -> CliTypedResult<Vec<u8>> {
+        serde_json::to_vec_pretty(self).map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to serialize encrypted key {}", err))
+        })
+    }
+
+    fn aead(
+        password: &str,
+        salt: &[u8],
+        scrypt_log_n: u8,
+        scrypt_r: u32,
+        scrypt_p: u32,
+    ) -> CliTypedResult<Aes256Gcm> {
+        let params = scrypt::Params::new(scrypt_log_n, scrypt_r, scrypt_p)
+            .map_err(|err| CliError::UnableToParse("scrypt parameters", err.to_string()))?;
+        let mut key = [0u8; KEY_LENGTH];
+        scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        Ok(Aes256Gcm::new(GenericArray::from_slice(&key)))
+    }
+}
+
+fn decode_hex(name: &'static str, str: &str) -> CliTypedResult<Vec<u8>> {
+    hex::decode(str).map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+/// Reads the password of an encrypted key from `APTOS_KEY_PASSWORD`, or prompts for it
+pub fn read_password(prompt: &str) -> CliTypedResult<String> {
+    if let Ok(password) = std::env::var(PASSWORD_ENV_VAR) {
+        return Ok(password);
+    }
+    rpassword::prompt_password(format!("{}: ", prompt))
+        .map_err(|err| CliError::IO("password".to_string(), err))
+}
+
+/// Reads the password to encrypt a key with, prompting twice to avoid typos
+pub fn read_new_password() -> CliTypedResult<String> {
+    if let Ok(password) = std::env::var(PASSWORD_ENV_VAR) {
+        return Ok(password);
+    }
+    let password = read_password("Enter a password to encrypt the key with")?;
+    if password.is_empty() {
+        return Err(CliError::CommandArgumentError(
+            "The password can't be empty".to_string(),
+        ));
+    }
+    if password != read_password("Enter the password again")? {
+        return Err(CliError::CommandArgumentError(
+            "The passwords don't match".to_string(),
+        ));
+    }
+    Ok(password)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cheap parameters, so that the tests don't take seconds in debug builds
+    fn encrypt(key_bytes: &[u8], password: &str) -> EncryptedKey {
+        EncryptedKey::encrypt_with_params(key_bytes, password, 4, 8, 1).unwrap()
+    }
+
+    #[test]
+    fn test_encrypted_key_roundtrip() {
+        let key_bytes = [7u8; 32];
+        let encrypted_key = encrypt(&key_bytes, "password");
+
+        let contents = encrypted_key.to_file_contents().unwrap();
+        let encrypted_key = EncryptedKey::from_file_contents(&contents).unwrap();
+        assert_eq!(encrypted_key.decrypt("password").unwrap(), key_bytes);
+    }
+
+    #[test]
+    fn test_encrypted_key_wrong_password() {
+        let encrypted_key = encrypt(&[7u8; 32], "password");
+        assert!(encrypted_key.decrypt("wrong password").is_err());
+    }
+
+    #[test]
+    fn test_plain_key_file_is_not_encrypted() {
+        let key_file = hex::encode_upper([7u8; 32]);
+        assert!(EncryptedKey::from_file_contents(key_file.as_bytes()).is_none());
+    }
+}
@@ -4,6 +4,7 @@
 use crate::{
     common::{
         init::{DEFAULT_FAUCET_URL, DEFAULT_REST_URL},
+        keystore::{read_password, EncryptedKey},
         utils::{
             check_if_file_exists, read_from_file, to_common_result, to_common_success_result,
             write_to_file, write_to_file_with_opts, write_to_user_only_file,
@@ -298,13 +299,22 @@ impl EncodingType {
         })
     }
 
-    /// Loads a key from a file
+    /// Loads a key from a file, prompting for the password if it is encrypted
     pub fn load_key<Key: ValidCryptoMaterial>(
         &self,
         name: &'static str,
         path: &Path,
     ) -> CliTypedResult<Key> {
-        self.decode_key(name, read_from_file(path)?)
+        let data = read_from_file(path)?;
+        // Encrypted keys are stored the same way for all encodings
+        if let Some(encrypted_key) = EncryptedKey::from_file_contents(&data) {
+            let password = read_password(&format!("Enter the password of {}", path.display()))?;
+            let bytes = encrypted_key.decrypt(&password)?;
+            return Key::try_from(bytes.as_slice()).map_err(|err| {
+                CliError::UnableToParse(name, format!("Failed to parse key {:?}", err))
+            });
+        }
+        self.decode_key(name, data)
     }
 
     /// Decodes an encoded key given the known encoding
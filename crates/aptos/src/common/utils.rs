@@ -294,12 +294,25 @@ pub async fn submit_transaction(
     payload: TransactionPayload,
     max_gas: u64,
 ) -> CliTypedResult<Transaction> {
-    let client = Client::new(url);
-
     // Get sender address
     let sender_address = AuthenticationKey::ed25519(&sender_key.public_key()).derived_address();
     let sender_address = AccountAddress::new(*sender_address);
 
+    submit_transaction_as(url, chain_id, sender_address, sender_key, payload, max_gas).await
+}
+
+/// Submits a [`TransactionPayload`] as signed by the `sender_key` for `sender_address`, whose
+/// authentication key may have been rotated away from the one its address was derived from
+pub async fn submit_transaction_as(
+    url: Url,
+    chain_id: ChainId,
+    sender_address: AccountAddress,
+    sender_key: Ed25519PrivateKey,
+    payload: TransactionPayload,
+    max_gas: u64,
+) -> CliTypedResult<Transaction> {
+    let client = Client::new(url);
+
     // Get sequence number for account
     let sequence_number = get_sequence_number(&client, sender_address).await?;
 
@@ -8,7 +8,10 @@ pub mod common;
 pub mod config;
 pub mod genesis;
 pub mod move_tool;
+pub mod node;
 pub mod op;
+pub mod stake;
+pub mod transaction;
 
 use crate::common::types::{CliCommand, CliResult};
 use clap::Parser;
@@ -29,6 +32,12 @@ pub enum Tool {
     Key(op::key::KeyTool),
     #[clap(subcommand)]
     Move(move_tool::MoveTool),
+    #[clap(subcommand)]
+    Node(node::NodeTool),
+    #[clap(subcommand)]
+    Stake(stake::StakeTool),
+    #[clap(subcommand)]
+    Tx(transaction::TransactionTool),
 }
 
 impl Tool {
@@ -41,6 +50,9 @@ impl Tool {
             Tool::Init(tool) => tool.execute_serialized_success().await,
             Tool::Key(tool) => tool.execute().await,
             Tool::Move(tool) => tool.execute().await,
+            Tool::Node(tool) => tool.execute().await,
+            Tool::Stake(tool) => tool.execute().await,
+            Tool::Tx(tool) => tool.execute().await,
         }
     }
 }
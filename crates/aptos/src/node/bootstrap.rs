@@ -0,0 +1,180 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult};
+use aptos_config::config::{
+    NodeConfig, RocksdbConfig, WaypointConfig, NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_types::{transaction::Version, waypoint::Waypoint};
+use aptosdb::AptosDB;
+use async_trait::async_trait;
+use backup_cli::{
+    coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+    metadata::cache::MetadataCacheOpt,
+    storage::{
+        command_adapter::CommandAdapterOpt, local_fs::LocalFsOpt, BackupStorage, StorageOpt,
+    },
+    utils::{
+        ConcurrentDownloadsOpt, GlobalRestoreOpt, GlobalRestoreOptions, RocksdbOpt,
+        TrustedWaypointOpt,
+    },
+};
+use clap::Parser;
+use serde::Serialize;
+use std::{
+    convert::TryInto,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use storage_interface::LedgerReader;
+
+/// Command to bootstrap the DB of a node from a backup
+///
+/// The DB in the storage directory of the node config is restored from the backup, with the
+/// epoch ending ledger infos verified up to the trusted `waypoint`.  The waypoint is then
+/// written into the node config, so that the node starts syncing from the restored state.
+/// The node must not be running.
+///
+#[derive(Debug, Parser)]
+pub struct BootstrapFromBackup {
+    /// Path to the config of the node, its waypoint is updated in place
+    #[clap(long, parse(from_os_str))]
+    node_config: PathBuf,
+
+    /// Trusted waypoint of an epoch ending ledger info in the backup
+    #[clap(long)]
+    waypoint: Waypoint,
+
+    /// Local directory holding the backup
+    #[clap(long, group = "backup_storage", parse(from_os_str))]
+    backup_dir: Option<PathBuf>,
+
+    /// Config of the command adapter to read the backup with, e.g. from cloud storage
+    #[clap(long, group = "backup_storage", parse(from_os_str))]
+    command_adapter_config: Option<PathBuf>,
+
+    /// Content newer than this version is not restored (defaults to everything in the backup)
+    #[clap(long)]
+    target_version: Option<Version>,
+
+    /// Directory to cache the backup metadata in (defaults to a temporary directory)
+    #[clap(long, parse(from_os_str))]
+    metadata_cache_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BootstrapSummary {
+    db_dir: PathBuf,
+    first_version: Option<Version>,
+    last_version: Option<Version>,
+    waypoint: String,
+}
+
+#[async_trait]
+impl CliCommand<BootstrapSummary> for BootstrapFromBackup {
+    fn command_name(&self) -> &'static str {
+        "BootstrapFromBackup"
+    }
+
+    async fn execute(self) -> CliTypedResult<BootstrapSummary> {
+        let mut node_config = NodeConfig::load(&self.node_config)
+            .map_err(|err| CliError::UnableToParse("node config", format!("{:?}", err)))?;
+        let db_dir = node_config.storage.dir();
+        let storage = self.backup_storage().await?;
+
+        let global_opt: GlobalRestoreOptions = GlobalRestoreOpt {
+            dry_run: false,
+            db_dir: Some(db_dir.clone()),
+            target_version: self.target_version,
+            trusted_waypoints: TrustedWaypointOpt {
+                trust_waypoint: vec![self.waypoint],
+            },
+            rocksdb_opt: RocksdbOpt::default(),
+            concurernt_downloads: ConcurrentDownloadsOpt::default(),
+        }
+        .try_into()
+        .map_err(|err: anyhow::Error| CliError::UnexpectedError(err.to_string()))?;
+
+        // The restore handler holds the DB open until the coordinator is done with it
+        RestoreCoordinator::new(
+            RestoreCoordinatorOpt {
+                metadata_cache_opt: MetadataCacheOpt::new(self.metadata_cache_dir),
+                replay_all: false,
+                ledger_history_start_version: 0,
+                skip_epoch_endings: false,
+            },
+            global_opt,
+            storage,
+        )
+        .run()
+        .await
+        .map_err(|err| CliError::UnexpectedError(format!("Restore failed: {:?}", err)))?;
+
+        let (first_version, last_version) = self.verify_db(&db_dir)?;
+
+        node_config.base.waypoint = WaypointConfig::FromConfig(self.waypoint);
+        node_config.save(&self.node_config).map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to save node config {:?}", err))
+        })?;
+
+        Ok(BootstrapSummary {
+            db_dir,
+            first_version,
+            last_version,
+            waypoint: self.waypoint.to_string(),
+        })
+    }
+}
+
+impl BootstrapFromBackup {
+    async fn backup_storage(&self) -> CliTypedResult<Arc<dyn BackupStorage>> {
+        let storage_opt = if let Some(dir) = &self.backup_dir {
+            StorageOpt::LocalFs(LocalFsOpt { dir: dir.clone() })
+        } else if let Some(config) = &self.command_adapter_config {
+            StorageOpt::CommandAdapter(CommandAdapterOpt {
+                config: config.clone(),
+            })
+        } else {
+            return Err(CliError::CommandArgumentError(
+                "One of ['--backup-dir', '--command-adapter-config'] must be used".to_string(),
+            ));
+        };
+        storage_opt
+            .init_storage()
+            .await
+            .map_err(|err| CliError::UnexpectedError(format!("Failed to open backup {}", err)))
+    }
+
+    /// Checks that the restored DB has the ledger info of the waypoint, and returns the range
+    /// of restored transaction versions
+    fn verify_db(&self, db_dir: &Path) -> CliTypedResult<(Option<Version>, Option<Version>)> {
+        let db = AptosDB::open(
+            db_dir,
+            true, /* read_only */
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            RocksdbConfig::default(),
+        )
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to open DB {}", err)))?;
+
+        let ledger_info = db
+            .get_epoch_ending_ledger_info(self.waypoint.version())
+            .map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "The restored DB has no ledger info at the waypoint: {}",
+                    err
+                ))
+            })?;
+        self.waypoint
+            .verify(ledger_info.ledger_info())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+
+        let first_version = db
+            .get_first_txn_version()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let last_version = db
+            .get_latest_transaction_info_option()
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .map(|(version, _)| version);
+        Ok((first_version, last_version))
+    }
+}
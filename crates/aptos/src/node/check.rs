@@ -0,0 +1,595 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliTypedResult};
+use aptos_config::config::{NodeConfig, WaypointConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptosdb::AptosDB;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    convert::TryInto,
+    fs,
+    io::Write,
+    net::{
+        IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket,
+    },
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use storage_interface::LedgerReader;
+use sysinfo::{DiskExt, System, SystemExt};
+
+/// Free space on the data disk below which the node is likely to stall soon
+const MIN_FREE_DISK_BYTES: u64 = 10 << 30;
+/// Free space on the data disk below which a warning is reported
+const LOW_FREE_DISK_BYTES: u64 = 50 << 30;
+/// Amount written by the disk throughput test
+const DISK_THROUGHPUT_TEST_BYTES: usize = 64 << 20;
+/// Sequential write throughput below which a warning is reported
+const MIN_DISK_THROUGHPUT_MB_PER_SEC: f64 = 100.0;
+/// Files the node holds open besides the RocksDB files, e.g., sockets and logs
+const OPEN_FILE_HEADROOM: u64 = 10_000;
+/// The DBs opened by AptosDB, each of which may hold `max_open_files` open
+const NUM_ROCKSDB_INSTANCES: u64 = 2;
+/// Clock skew above which a warning is reported
+const MAX_CLOCK_SKEW_WARN: Duration = Duration::from_millis(500);
+/// Clock skew above which the node is likely to reject proposals or have its own rejected
+const MAX_CLOCK_SKEW_FAIL: Duration = Duration::from_secs(5);
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(2);
+/// Seconds between the NTP epoch (1900) and the UNIX epoch (1970)
+const NTP_UNIX_OFFSET_SECS: f64 = 2_208_988_800.0;
+
+/// Run local diagnostics on a node
+///
+/// Checks the node config, the free space and write throughput of the data disk, the open file
+/// limit, the clock skew against an NTP server, the ports the node listens on and the
+/// consistency of the DB, then prints a report with a pass, warn or fail status for each.
+/// It can be run while the node is running.
+///
+#[derive(Debug, Parser)]
+pub struct CheckNode {
+    /// Path to the config of the node
+    #[clap(long, parse(from_os_str))]
+    node_config: PathBuf,
+
+    /// NTP server to measure the clock skew against
+    #[clap(long, default_value = "pool.ntp.org:123")]
+    ntp_server: String,
+
+    /// Skip the disk throughput test, which writes 64 MiB to the data directory
+    #[clap(long)]
+    skip_disk_throughput: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckResult {
+    name: String,
+    status: CheckStatus,
+    details: String,
+}
+
+impl CheckResult {
+    fn new(name: impl Into<String>, status: CheckStatus, details: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            details: details.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct NodeCheckReport {
+    /// The worst status of all checks
+    status: CheckStatus,
+    checks: Vec<CheckResult>,
+}
+
+#[async_trait]
+impl CliCommand<NodeCheckReport> for CheckNode {
+    fn command_name(&self) -> &'static str {
+        "CheckNode"
+    }
+
+    async fn execute(self) -> CliTypedResult<NodeCheckReport> {
+        let mut checks = vec![];
+        match NodeConfig::load(&self.node_config) {
+            Ok(config) => {
+                checks.push(check_config(&config));
+                checks.push(check_disk_space(config.storage.dir().as_path()));
+                if !self.skip_disk_throughput {
+                    checks.push(check_disk_throughput(&config.base.data_dir));
+                }
+                checks.push(check_open_file_limit(&config));
+                checks.push(check_clock_skew(&self.ntp_server));
+                checks.extend(
+                    listen_addresses(&config)
+                        .into_iter()
+                        .map(|(name, address)| check_port(name, address)),
+                );
+                checks.push(check_db(&config));
+            }
+            Err(err) => {
+                checks.push(CheckResult::new(
+                    "config",
+                    CheckStatus::Fail,
+                    format!("Unable to load {}: {:?}", self.node_config.display(), err),
+                ));
+                checks.push(check_clock_skew(&self.ntp_server));
+            }
+        }
+
+        Ok(NodeCheckReport {
+            status: worst_status(&checks),
+            checks,
+        })
+    }
+}
+
+fn worst_status(checks: &[CheckResult]) -> CheckStatus {
+    checks
+        .iter()
+        .map(|check| check.status)
+        .max()
+        .unwrap_or(CheckStatus::Pass)
+}
+
+/// Checks the config for problems that `NodeConfig::load` does not catch
+fn check_config(config: &NodeConfig) -> CheckResult {
+    let mut problems = vec![];
+    if config.base.waypoint == WaypointConfig::None {
+        problems.push("no waypoint is configured".to_string());
+    }
+    if !config.base.data_dir.is_dir() {
+        problems.push(format!(
+            "data directory {} does not exist",
+            config.base.data_dir.display()
+        ));
+    }
+
+    let mut services_by_port: HashMap<u16, Vec<String>> = HashMap::new();
+    for (name, address) in listen_addresses(config) {
+        services_by_port
+            .entry(address.port())
+            .or_default()
+            .push(name);
+    }
+    let mut shared_ports: Vec<_> = services_by_port
+        .into_iter()
+        .filter(|(_, services)| services.len() > 1)
+        .collect();
+    shared_ports.sort();
+    for (port, services) in shared_ports {
+        problems.push(format!("port {} is used by {}", port, services.join(", ")));
+    }
+
+    if !problems.is_empty() {
+        return CheckResult::new("config", CheckStatus::Fail, problems.join("; "));
+    }
+    if config.failpoints.is_some() {
+        return CheckResult::new("config", CheckStatus::Warn, "failpoints are configured");
+    }
+    CheckResult::new(
+        "config",
+        CheckStatus::Pass,
+        format!("{:?} config is valid", config.base.role),
+    )
+}
+
+fn check_disk_space(path: &Path) -> CheckResult {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut system = System::new();
+    system.refresh_disks_list();
+    let disk = system
+        .disks()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len());
+    let disk = match disk {
+        Some(disk) => disk,
+        None => {
+            return CheckResult::new(
+                "disk_space",
+                CheckStatus::Warn,
+                format!("Unable to find the disk holding {}", path.display()),
+            )
+        }
+    };
+
+    let available = disk.available_space();
+    let status = if available < MIN_FREE_DISK_BYTES {
+        CheckStatus::Fail
+    } else if available < LOW_FREE_DISK_BYTES {
+        CheckStatus::Warn
+    } else {
+        CheckStatus::Pass
+    };
+    CheckResult::new(
+        "disk_space",
+        status,
+        format!(
+            "{} GiB free of {} GiB on {}",
+            available >> 30,
+            disk.total_space() >> 30,
+            disk.mount_point().display()
+        ),
+    )
+}
+
+fn check_disk_throughput(dir: &Path) -> CheckResult {
+    match measure_write_throughput(dir) {
+        Ok(mb_per_sec) => CheckResult::new(
+            "disk_throughput",
+            if mb_per_sec < MIN_DISK_THROUGHPUT_MB_PER_SEC {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            },
+            format!(
+                "{:.0} MB/s sequential write to {}",
+                mb_per_sec,
+                dir.display()
+            ),
+        ),
+        Err(err) => CheckResult::new(
+            "disk_throughput",
+            CheckStatus::Fail,
+            format!("Unable to write to {}: {}", dir.display(), err),
+        ),
+    }
+}
+
+/// Writes `DISK_THROUGHPUT_TEST_BYTES` to a temporary file in `dir` and syncs it to disk
+fn measure_write_throughput(dir: &Path) -> std::io::Result<f64> {
+    let mut file = tempfile::NamedTempFile::new_in(dir)?;
+    let chunk = vec![0xa5u8; 1 << 20];
+    let start = Instant::now();
+    for _ in 0..DISK_THROUGHPUT_TEST_BYTES / chunk.len() {
+        file.write_all(&chunk)?;
+    }
+    file.as_file().sync_all()?;
+    let elapsed = start.elapsed().as_secs_f64().max(f64::EPSILON);
+    Ok(DISK_THROUGHPUT_TEST_BYTES as f64 / 1_000_000.0 / elapsed)
+}
+
+fn check_open_file_limit(config: &NodeConfig) -> CheckResult {
+    let limit = match fs::read_to_string("/proc/self/limits") {
+        Ok(limits) => parse_open_file_limit(&limits),
+        Err(_) => None,
+    };
+    let limit = match limit {
+        Some(limit) => limit,
+        None => {
+            return CheckResult::new(
+                "open_file_limit",
+                CheckStatus::Warn,
+                "Unable to read the open file limit, only supported on Linux",
+            )
+        }
+    };
+
+    let max_open_files = config.storage.rocksdb_config.max_open_files;
+    // A negative max_open_files lets RocksDB keep every file open
+    let required = if max_open_files < 0 {
+        None
+    } else {
+        Some(NUM_ROCKSDB_INSTANCES * max_open_files as u64 + OPEN_FILE_HEADROOM)
+    };
+    match (limit, required) {
+        (None, _) => CheckResult::new("open_file_limit", CheckStatus::Pass, "unlimited"),
+        (Some(limit), Some(required)) if limit < required => CheckResult::new(
+            "open_file_limit",
+            CheckStatus::Fail,
+            format!(
+                "{} open files allowed, the node may need {} with max_open_files set to {}",
+                limit, required, max_open_files
+            ),
+        ),
+        (Some(limit), None) => CheckResult::new(
+            "open_file_limit",
+            CheckStatus::Warn,
+            format!(
+                "{} open files allowed, and RocksDB max_open_files is unlimited",
+                limit
+            ),
+        ),
+        (Some(limit), Some(_)) => CheckResult::new(
+            "open_file_limit",
+            CheckStatus::Pass,
+            format!("{} open files allowed", limit),
+        ),
+    }
+}
+
+/// Parses the soft limit on open files from the contents of `/proc/self/limits`. Returns
+/// `Some(None)` if the limit is unlimited.
+fn parse_open_file_limit(limits: &str) -> Option<Option<u64>> {
+    let line = limits
+        .lines()
+        .find(|line| line.starts_with("Max open files"))?;
+    let soft_limit = line
+        .trim_start_matches("Max open files")
+        .split_whitespace()
+        .next()?;
+    if soft_limit == "unlimited" {
+        Some(None)
+    } else {
+        soft_limit.parse().ok().map(Some)
+    }
+}
+
+fn check_clock_skew(ntp_server: &str) -> CheckResult {
+    match query_clock_offset(ntp_server) {
+        Ok(offset) => {
+            let skew = Duration::from_secs_f64(offset.abs());
+            let status = if skew > MAX_CLOCK_SKEW_FAIL {
+                CheckStatus::Fail
+            } else if skew > MAX_CLOCK_SKEW_WARN {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            CheckResult::new(
+                "clock_skew",
+                status,
+                format!(
+                    "Local clock is {:.3}s {} {}",
+                    offset.abs(),
+                    if offset > 0.0 { "behind" } else { "ahead of" },
+                    ntp_server
+                ),
+            )
+        }
+        Err(err) => CheckResult::new(
+            "clock_skew",
+            CheckStatus::Warn,
+            format!("Unable to query {}: {}", ntp_server, err),
+        ),
+    }
+}
+
+/// Returns the offset of the local clock to the NTP server in seconds, positive if the local
+/// clock is behind, using a single SNTP request
+fn query_clock_offset(ntp_server: &str) -> anyhow::Result<f64> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.set_read_timeout(Some(NETWORK_TIMEOUT))?;
+    socket.connect(ntp_server)?;
+
+    // Leap indicator 0, version 3, mode 3 (client)
+    let mut request = [0u8; 48];
+    request[0] = 0x1b;
+    let sent_at = unix_now();
+    socket.send(&request)?;
+    let mut response = [0u8; 48];
+    let len = socket.recv(&mut response)?;
+    let received_at = unix_now();
+    if len < response.len() {
+        anyhow::bail!("Truncated NTP response");
+    }
+    ntp_offset(&response, sent_at, received_at)
+}
+
+/// Computes the clock offset from an SNTP response, see RFC 4330
+fn ntp_offset(response: &[u8; 48], sent_at: f64, received_at: f64) -> anyhow::Result<f64> {
+    let mode = response[0] & 0x7;
+    let stratum = response[1];
+    if mode != 4 || stratum == 0 {
+        anyhow::bail!("Unexpected NTP response, mode {} stratum {}", mode, stratum);
+    }
+    let server_received_at = ntp_timestamp(&response[32..40]);
+    let server_sent_at = ntp_timestamp(&response[40..48]);
+    Ok(((server_received_at - sent_at) + (server_sent_at - received_at)) / 2.0)
+}
+
+/// Converts an NTP timestamp to seconds since the UNIX epoch
+fn ntp_timestamp(bytes: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes(bytes[0..4].try_into().unwrap());
+    let fraction = u32::from_be_bytes(bytes[4..8].try_into().unwrap());
+    seconds as f64 - NTP_UNIX_OFFSET_SECS + fraction as f64 / (1u64 << 32) as f64
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the UNIX epoch")
+        .as_secs_f64()
+}
+
+/// The addresses the node listens on, by service
+fn listen_addresses(config: &NodeConfig) -> Vec<(String, SocketAddr)> {
+    let mut addresses = vec![
+        ("api".to_string(), config.api.address),
+        ("storage".to_string(), config.storage.address),
+        (
+            "backup_service".to_string(),
+            config.storage.backup_service_address,
+        ),
+    ];
+    let debug_interface = &config.debug_interface;
+    for (name, port) in [
+        ("metrics", debug_interface.metrics_server_port),
+        ("public_metrics", debug_interface.public_metrics_server_port),
+    ] {
+        if let Some(address) = resolve((debug_interface.address.as_str(), port)) {
+            addresses.push((name.to_string(), address));
+        }
+    }
+    let networks = config
+        .validator_network
+        .iter()
+        .chain(&config.full_node_networks);
+    for network in networks {
+        if let Some(address) = resolve(&network.listen_address) {
+            addresses.push((format!("{}_network", network.network_id), address));
+        }
+    }
+    addresses
+}
+
+fn resolve(address: impl ToSocketAddrs) -> Option<SocketAddr> {
+    address.to_socket_addrs().ok()?.next()
+}
+
+/// Passes if something accepts connections on the address, i.e., the node is running, or if the
+/// node could bind to it, i.e., the node is stopped and the port is free
+fn check_port(name: String, address: SocketAddr) -> CheckResult {
+    let name = format!("port_{}", name);
+    let mut local_address = address;
+    if address.ip().is_unspecified() {
+        local_address.set_ip(match address.ip() {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        });
+    }
+    if TcpStream::connect_timeout(&local_address, NETWORK_TIMEOUT).is_ok() {
+        return CheckResult::new(
+            name,
+            CheckStatus::Pass,
+            format!("{} accepts connections", address),
+        );
+    }
+    match TcpListener::bind(address) {
+        Ok(_) => CheckResult::new(
+            name,
+            CheckStatus::Pass,
+            format!("{} is free, the node is not running", address),
+        ),
+        Err(err) => CheckResult::new(
+            name,
+            CheckStatus::Fail,
+            format!(
+                "{} does not accept connections and cannot be bound: {}",
+                address, err
+            ),
+        ),
+    }
+}
+
+/// Opens the DB read only and checks that the latest ledger info is consistent with the
+/// transaction accumulator and, if the waypoint is in the config, with the waypoint
+fn check_db(config: &NodeConfig) -> CheckResult {
+    let db_dir = config.storage.dir();
+    if !db_dir.exists() {
+        return CheckResult::new(
+            "db",
+            CheckStatus::Warn,
+            format!(
+                "No DB at {}, the node will sync from scratch",
+                db_dir.display()
+            ),
+        );
+    }
+    match verify_db(config, &db_dir) {
+        Ok(details) => CheckResult::new("db", CheckStatus::Pass, details),
+        Err(err) => CheckResult::new("db", CheckStatus::Fail, err.to_string()),
+    }
+}
+
+fn verify_db(config: &NodeConfig, db_dir: &Path) -> anyhow::Result<String> {
+    let db = AptosDB::open(
+        db_dir,
+        true, /* read_only */
+        NO_OP_STORAGE_PRUNER_CONFIG,
+        config.storage.rocksdb_config,
+    )?;
+    let ledger_info = match db.get_latest_ledger_info_option()? {
+        Some(ledger_info) => ledger_info,
+        None => return Ok("DB is empty".to_string()),
+    };
+    let ledger_info = ledger_info.ledger_info();
+    let latest_version = db
+        .get_latest_transaction_info_option()?
+        .map(|(version, _)| version);
+    if latest_version < Some(ledger_info.version()) {
+        anyhow::bail!(
+            "Latest ledger info is at version {} but the latest transaction is {:?}",
+            ledger_info.version(),
+            latest_version
+        );
+    }
+    let root_hash = db.get_accumulator_root_hash(ledger_info.version())?;
+    if root_hash != ledger_info.transaction_accumulator_hash() {
+        anyhow::bail!(
+            "Transaction accumulator root {} at version {} does not match the latest ledger info",
+            root_hash,
+            ledger_info.version()
+        );
+    }
+    if let Some(waypoint) = config.base.waypoint.waypoint_from_config() {
+        if waypoint.version() <= ledger_info.version() {
+            let epoch_ending = db.get_epoch_ending_ledger_info(waypoint.version())?;
+            waypoint.verify(epoch_ending.ledger_info())?;
+        }
+    }
+    Ok(format!(
+        "Ledger info at version {}, epoch {}, latest transaction at version {:?}",
+        ledger_info.version(),
+        ledger_info.epoch(),
+        latest_version
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_LIMITS: &str = "\
+Limit                     Soft Limit           Hard Limit           Units
+Max cpu time              unlimited            unlimited            seconds
+Max open files            1024                 1048576              files
+Max locked memory         65536                65536                bytes
+";
+
+    #[test]
+    fn test_parse_open_file_limit() {
+        assert_eq!(parse_open_file_limit(PROC_LIMITS), Some(Some(1024)));
+        assert_eq!(
+            parse_open_file_limit(&PROC_LIMITS.replace("1024 ", "unlimited ")),
+            Some(None)
+        );
+        assert_eq!(parse_open_file_limit("Limit Soft Limit"), None);
+    }
+
+    #[test]
+    fn test_ntp_offset() {
+        // The server is 10s ahead, and each way of the round trip takes 0.5s
+        let sent_at = 1_600_000_000.0;
+        let received_at = sent_at + 1.0;
+        let mut response = [0u8; 48];
+        response[0] = 0x1c; // version 3, mode 4 (server)
+        response[1] = 2;
+        let server_seconds = (sent_at + 10.0 + NTP_UNIX_OFFSET_SECS) as u32;
+        let half_second = 1u32 << 31;
+        for timestamp in [&mut response[32..40], &mut response[40..48]] {
+            timestamp[0..4].copy_from_slice(&server_seconds.to_be_bytes());
+            timestamp[4..8].copy_from_slice(&half_second.to_be_bytes());
+        }
+        let offset = ntp_offset(&response, sent_at, received_at).unwrap();
+        assert!((offset - 10.0).abs() < 1e-3);
+
+        // Kiss-o'-death responses have stratum 0
+        response[1] = 0;
+        assert!(ntp_offset(&response, sent_at, received_at).is_err());
+    }
+
+    #[test]
+    fn test_worst_status() {
+        assert_eq!(worst_status(&[]), CheckStatus::Pass);
+        let checks = vec![
+            CheckResult::new("a", CheckStatus::Pass, ""),
+            CheckResult::new("b", CheckStatus::Fail, ""),
+            CheckResult::new("c", CheckStatus::Warn, ""),
+        ];
+        assert_eq!(worst_status(&checks), CheckStatus::Fail);
+    }
+}
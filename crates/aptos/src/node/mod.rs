@@ -0,0 +1,25 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliResult};
+use clap::Subcommand;
+
+pub mod bootstrap;
+pub mod check;
+
+/// CLI tool for operating nodes
+///
+#[derive(Debug, Subcommand)]
+pub enum NodeTool {
+    BootstrapFromBackup(bootstrap::BootstrapFromBackup),
+    Check(check::CheckNode),
+}
+
+impl NodeTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            NodeTool::BootstrapFromBackup(tool) => tool.execute_serialized().await,
+            NodeTool::Check(tool) => tool.execute_serialized().await,
+        }
+    }
+}
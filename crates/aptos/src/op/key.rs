@@ -3,32 +3,49 @@
 
 use crate::{
     common::{
+        keystore::{read_new_password, EncryptedKey},
         types::{
-            CliError, CliTypedResult, EncodingOptions, EncodingType, ExtractPublicKey, KeyType,
-            PrivateKeyInputOptions, ProfileOptions, SaveFile,
+            account_address_from_public_key, CliConfig, CliError, CliTypedResult, EncodingOptions,
+            EncodingType, ExtractPublicKey, KeyType, PrivateKeyInputOptions, ProfileOptions,
+            SaveFile, TransactionSummary, WriteTransactionOptions,
+        },
+        utils::{
+            append_file_extension, check_if_file_exists, read_line, submit_transaction_as,
+            write_to_file, write_to_user_only_file,
         },
-        utils::{append_file_extension, check_if_file_exists, write_to_file},
     },
     CliCommand, CliResult,
 };
 use aptos_config::config::{Peer, PeerRole};
 use aptos_crypto::{ed25519, x25519, PrivateKey, Uniform, ValidCryptoMaterial};
-use aptos_types::account_address::{from_identity_public_key, AccountAddress};
+use aptos_types::{
+    account_address::{from_identity_public_key, AccountAddress},
+    transaction::authenticator::AuthenticationKey,
+};
 use async_trait::async_trait;
+use bip39::{Language, Mnemonic};
+use cached_framework_packages::aptos_stdlib;
 use clap::{Parser, Subcommand};
 use rand::SeedableRng;
+use serde::Serialize;
 use std::{
     collections::{HashMap, HashSet},
+    convert::TryFrom,
+    fs,
     path::{Path, PathBuf},
 };
 
 pub const PUBLIC_KEY_EXTENSION: &str = "pub";
+const STAGED_KEY_EXTENSION: &str = "staged";
 
-/// CLI tool for generating, inspecting, and interacting with keys.
+/// CLI tool for generating, inspecting, rotating, and interacting with keys.
 #[derive(Debug, Subcommand)]
 pub enum KeyTool {
     Generate(GenerateKey),
     ExtractPeer(ExtractPeer),
+    ImportMnemonic(ImportMnemonic),
+    Rotate(RotateKey),
+    Show(ShowKey),
 }
 
 impl KeyTool {
@@ -36,6 +53,9 @@ impl KeyTool {
         match self {
             KeyTool::Generate(tool) => tool.execute_serialized().await,
             KeyTool::ExtractPeer(tool) => tool.execute_serialized().await,
+            KeyTool::ImportMnemonic(tool) => tool.execute_serialized().await,
+            KeyTool::Rotate(tool) => tool.execute_serialized().await,
+            KeyTool::Show(tool) => tool.execute_serialized().await,
         }
     }
 }
@@ -97,7 +117,8 @@ impl CliCommand<HashMap<AccountAddress, Peer>> for ExtractPeer {
 /// This can be used for generating an identity.  Two files will be created
 /// `output_file` and `output_file.pub`.  `output_file` will contain the private
 /// key encoded with the `encoding` and `output_file.pub` will contain the public
-/// key encoded with the `encoding`.
+/// key encoded with the `encoding`.  With `--encrypt`, the private key is instead
+/// encrypted with a password.
 #[derive(Debug, Parser)]
 pub struct GenerateKey {
     /// Key type: `x25519` or `ed25519`
@@ -197,6 +218,9 @@ pub struct SaveKey {
     file_options: SaveFile,
     #[clap(flatten)]
     encoding_options: EncodingOptions,
+    /// Encrypt the private key with a password, read from `APTOS_KEY_PASSWORD` or prompted for
+    #[clap(long)]
+    encrypt: bool,
 }
 
 impl SaveKey {
@@ -215,17 +239,31 @@ impl SaveKey {
         check_if_file_exists(&self.public_key_file()?, self.file_options.prompt_options)
     }
 
-    /// Saves a key to a file encoded in a string
-    pub fn save_key<Key: PrivateKey + ValidCryptoMaterial>(
+    /// Encodes (and encrypts, if asked to) the private key and its public key
+    fn encode_key<Key: PrivateKey + ValidCryptoMaterial>(
         &self,
         key: &Key,
         key_name: &'static str,
-    ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
-        let encoded_private_key = self.encoding_options.encoding.encode_key(key_name, key)?;
+    ) -> CliTypedResult<(Vec<u8>, Vec<u8>)> {
+        let encoded_private_key = if self.encrypt {
+            EncryptedKey::encrypt(&key.to_bytes(), &read_new_password()?)?.to_file_contents()?
+        } else {
+            self.encoding_options.encoding.encode_key(key_name, key)?
+        };
         let encoded_public_key = self
             .encoding_options
             .encoding
             .encode_key(key_name, &key.public_key())?;
+        Ok((encoded_private_key, encoded_public_key))
+    }
+
+    /// Saves a key to a file encoded in a string
+    pub fn save_key<Key: PrivateKey + ValidCryptoMaterial>(
+        &self,
+        key: &Key,
+        key_name: &'static str,
+    ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        let (encoded_private_key, encoded_public_key) = self.encode_key(key, key_name)?;
 
         // Write private and public keys to files
         let public_key_file = self.public_key_file()?;
@@ -233,9 +271,352 @@ impl SaveKey {
             .save_to_file_confidential(key_name, &encoded_private_key)?;
         write_to_file(&public_key_file, key_name, &encoded_public_key)?;
 
-        let mut map = HashMap::new();
-        map.insert("PrivateKey Path", self.file_options.output_file.clone());
-        map.insert("PublicKey Path", public_key_file);
-        Ok(map)
+        Ok(key_files(
+            self.file_options.output_file.clone(),
+            public_key_file,
+        ))
+    }
+
+    /// Saves a key next to its files, see `StagedKey`
+    pub fn stage_key<Key: PrivateKey + ValidCryptoMaterial>(
+        &self,
+        key: &Key,
+        key_name: &'static str,
+    ) -> CliTypedResult<StagedKey> {
+        let private_key_file = self.file_options.output_file.clone();
+        let public_key_file = self.public_key_file()?;
+        let staged_key = StagedKey {
+            staged_private_key_file: append_file_extension(
+                &private_key_file,
+                STAGED_KEY_EXTENSION,
+            )?,
+            staged_public_key_file: append_file_extension(&public_key_file, STAGED_KEY_EXTENSION)?,
+            private_key_file,
+            public_key_file,
+        };
+
+        let (encoded_private_key, encoded_public_key) = self.encode_key(key, key_name)?;
+        write_to_user_only_file(
+            &staged_key.staged_private_key_file,
+            key_name,
+            &encoded_private_key,
+        )?;
+        write_to_file(
+            &staged_key.staged_public_key_file,
+            key_name,
+            &encoded_public_key,
+        )?;
+        Ok(staged_key)
+    }
+}
+
+fn key_files(
+    private_key_file: PathBuf,
+    public_key_file: PathBuf,
+) -> HashMap<&'static str, PathBuf> {
+    let mut map = HashMap::new();
+    map.insert("PrivateKey Path", private_key_file);
+    map.insert("PublicKey Path", public_key_file);
+    map
+}
+
+/// A key saved to `<file>.staged` until it is known whether it is used, so that neither the key
+/// is lost nor the files of the key in use are overwritten with a key that isn't.
+pub struct StagedKey {
+    private_key_file: PathBuf,
+    public_key_file: PathBuf,
+    staged_private_key_file: PathBuf,
+    staged_public_key_file: PathBuf,
+}
+
+impl StagedKey {
+    /// Moves the key in place of its files, once it is in use
+    pub fn commit(self) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        for (staged_file, file) in [
+            (&self.staged_private_key_file, &self.private_key_file),
+            (&self.staged_public_key_file, &self.public_key_file),
+        ] {
+            fs::rename(staged_file, file)
+                .map_err(|err| CliError::IO(file.display().to_string(), err))?;
+        }
+        Ok(key_files(self.private_key_file, self.public_key_file))
+    }
+
+    /// Removes the key, which isn't used
+    pub fn discard(self) {
+        let _ = fs::remove_file(&self.staged_private_key_file);
+        let _ = fs::remove_file(&self.staged_public_key_file);
+    }
+}
+
+/// Shows the public key, authentication key and derived account address of an `ed25519` key.
+///
+/// A `private-key` can be given encoded on the command line, or a `private-key-file` can be
+/// given to read from, otherwise the key of the profile is used.  With `--mnemonic`, the
+/// mnemonic to import the key with is also shown.
+#[derive(Debug, Parser)]
+pub struct ShowKey {
+    #[clap(flatten)]
+    private_key_input_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+    /// Also show the mnemonic of the private key
+    #[clap(long)]
+    mnemonic: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeySummary {
+    public_key: ed25519::Ed25519PublicKey,
+    authentication_key: String,
+    /// The address of an account created with this key, accounts keep their address when
+    /// their key is rotated
+    derived_account_address: AccountAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mnemonic: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<KeySummary> for ShowKey {
+    fn command_name(&self) -> &'static str {
+        "ShowKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<KeySummary> {
+        let private_key = self.private_key_input_options.extract_private_key(
+            self.encoding_options.encoding,
+            &self.profile_options.profile,
+        )?;
+        let public_key = private_key.public_key();
+        let mnemonic = if self.mnemonic {
+            Some(mnemonic_from_key(&private_key)?)
+        } else {
+            None
+        };
+
+        Ok(KeySummary {
+            authentication_key: AuthenticationKey::ed25519(&public_key).to_string(),
+            derived_account_address: account_address_from_public_key(&public_key),
+            public_key,
+            mnemonic,
+        })
+    }
+}
+
+/// Imports an `ed25519` key from its mnemonic, as shown by `aptos key show --mnemonic`.
+///
+/// The mnemonic is read from stdin, and the key is saved the same way as by `aptos key generate`.
+#[derive(Debug, Parser)]
+pub struct ImportMnemonic {
+    #[clap(flatten)]
+    save_params: SaveKey,
+}
+
+#[async_trait]
+impl CliCommand<HashMap<&'static str, PathBuf>> for ImportMnemonic {
+    fn command_name(&self) -> &'static str {
+        "ImportMnemonic"
+    }
+
+    async fn execute(self) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        self.save_params.check_key_file()?;
+
+        eprintln!("Enter the mnemonic of the key");
+        let private_key = key_from_mnemonic(&read_line("mnemonic")?)?;
+        self.save_params.save_key(&private_key, "ed25519")
+    }
+}
+
+/// The mnemonic of an `ed25519` key encodes the key itself, as 24 BIP-39 words.  It is not a
+/// BIP-32 seed, no other keys are derived from it.
+pub fn mnemonic_from_key(private_key: &ed25519::Ed25519PrivateKey) -> CliTypedResult<String> {
+    Mnemonic::from_entropy(&private_key.to_bytes(), Language::English)
+        .map(|mnemonic| mnemonic.into_phrase())
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to create mnemonic {}", err)))
+}
+
+pub fn key_from_mnemonic(phrase: &str) -> CliTypedResult<ed25519::Ed25519PrivateKey> {
+    let mnemonic = Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|err| CliError::UnableToParse("mnemonic", err.to_string()))?;
+    ed25519::Ed25519PrivateKey::try_from(mnemonic.entropy())
+        .map_err(|err| CliError::UnableToParse("mnemonic", err.to_string()))
+}
+
+/// Rotates the authentication key of an account to a newly generated `ed25519` key.
+///
+/// The new key is saved to `output_file.staged` and `output_file.pub.staged` before the
+/// rotation transaction is submitted, so that it can't be lost, and moved to `output_file`
+/// and `output_file.pub` once the transaction succeeds.  If the transaction fails, the staged
+/// key is removed; if its outcome is unknown, the staged key is kept.  The account keeps its
+/// address.  If the current key was taken from the profile, the profile is updated to the new
+/// key.
+#[derive(Debug, Parser)]
+pub struct RotateKey {
+    #[clap(flatten)]
+    write_options: WriteTransactionOptions,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+    #[clap(flatten)]
+    save_params: SaveKey,
+    /// Address of the account to rotate the key of
+    ///
+    /// Defaults to the profile's account, or to the address derived from the current key
+    #[clap(long, parse(try_from_str = crate::common::types::load_account_arg))]
+    account: Option<AccountAddress>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateSummary {
+    account: AccountAddress,
+    authentication_key: String,
+    key_files: HashMap<&'static str, PathBuf>,
+    transaction: TransactionSummary,
+}
+
+#[async_trait]
+impl CliCommand<RotateSummary> for RotateKey {
+    fn command_name(&self) -> &'static str {
+        "RotateKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<RotateSummary> {
+        self.save_params.check_key_file()?;
+        let encoding = self.save_params.encoding_options.encoding;
+        let profile = &self.profile_options.profile;
+
+        let cli_private_key = self
+            .write_options
+            .private_key_options
+            .extract_private_key_cli(encoding)?;
+        let from_profile = cli_private_key.is_none();
+        let private_key = match cli_private_key {
+            Some(private_key) => private_key,
+            None => self
+                .write_options
+                .private_key_options
+                .extract_private_key(encoding, profile)?,
+        };
+        let profile_account = if CliConfig::config_exists() {
+            CliConfig::load_profile(profile)?.and_then(|profile| profile.account)
+        } else {
+            None
+        };
+        let account = self
+            .account
+            .or(profile_account)
+            .unwrap_or_else(|| account_address_from_public_key(&private_key.public_key()));
+
+        let new_private_key = GenerateKey::generate_ed25519_in_memory();
+        let new_public_key = new_private_key.public_key();
+        let url = self.write_options.rest_options.url(profile)?;
+        let chain_id = self.write_options.chain_id(profile).await?;
+        let staged_key = self.save_params.stage_key(&new_private_key, "ed25519")?;
+
+        let authentication_key = AuthenticationKey::ed25519(&new_public_key);
+        let transaction = submit_transaction_as(
+            url,
+            chain_id,
+            account,
+            private_key,
+            aptos_stdlib::encode_account_rotate_authentication_key(authentication_key.to_vec()),
+            self.write_options.max_gas,
+        )
+        .await
+        .map_err(|err| {
+            // The rotation may still be committed, so the new key is kept where it was staged
+            CliError::UnexpectedError(format!(
+                "Unknown outcome of the key rotation, the new key is kept in {}: {}",
+                staged_key.staged_private_key_file.display(),
+                err
+            ))
+        })?;
+
+        let key_files = if transaction.success() {
+            staged_key.commit()?
+        } else {
+            staged_key.discard();
+            HashMap::new()
+        };
+
+        if transaction.success() && from_profile {
+            let mut config = CliConfig::load()?;
+            if let Some(profile_config) = config
+                .profiles
+                .as_mut()
+                .and_then(|profiles| profiles.get_mut(profile))
+            {
+                profile_config.private_key = Some(new_private_key);
+                profile_config.public_key = Some(new_public_key);
+                profile_config.account = Some(account);
+                config.save()?;
+                eprintln!("Updated profile {} to the new key", profile);
+            }
+        }
+
+        Ok(RotateSummary {
+            account,
+            authentication_key: authentication_key.to_string(),
+            key_files,
+            transaction: TransactionSummary::from(transaction),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::types::PromptOptions;
+
+    #[test]
+    fn test_mnemonic_roundtrip() {
+        let private_key = GenerateKey::generate_ed25519_in_memory();
+        let mnemonic = mnemonic_from_key(&private_key).unwrap();
+        assert_eq!(mnemonic.split_whitespace().count(), 24);
+        assert_eq!(key_from_mnemonic(&mnemonic).unwrap(), private_key);
+        assert!(key_from_mnemonic("not a mnemonic").is_err());
+    }
+
+    #[test]
+    fn test_staged_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let save_key = SaveKey {
+            file_options: SaveFile {
+                output_file: dir.path().join("key"),
+                prompt_options: PromptOptions {
+                    assume_yes: true,
+                    assume_no: false,
+                },
+            },
+            encoding_options: EncodingOptions {
+                encoding: EncodingType::Hex,
+            },
+            encrypt: false,
+        };
+        let old_key = GenerateKey::generate_ed25519_in_memory();
+        let new_key = GenerateKey::generate_ed25519_in_memory();
+        save_key.save_key(&old_key, "ed25519").unwrap();
+        let read_key = || {
+            EncodingType::Hex
+                .load_key::<ed25519::Ed25519PrivateKey>("ed25519", &dir.path().join("key"))
+                .unwrap()
+        };
+
+        // A discarded key leaves the files of the key in use untouched
+        save_key.stage_key(&new_key, "ed25519").unwrap().discard();
+        assert_eq!(read_key(), old_key);
+        assert!(!dir.path().join("key.staged").exists());
+        assert!(!dir.path().join("key.pub.staged").exists());
+
+        // A committed key replaces them
+        let key_files = save_key
+            .stage_key(&new_key, "ed25519")
+            .unwrap()
+            .commit()
+            .unwrap();
+        assert_eq!(read_key(), new_key);
+        assert_eq!(key_files["PublicKey Path"], dir.path().join("key.pub"));
+        assert!(!dir.path().join("key.staged").exists());
     }
 }
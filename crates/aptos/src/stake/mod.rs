@@ -0,0 +1,22 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliResult};
+use clap::Subcommand;
+
+pub mod show;
+
+/// CLI tool for inspecting stake pools
+///
+#[derive(Debug, Subcommand)]
+pub enum StakeTool {
+    Show(show::ShowStakePool),
+}
+
+impl StakeTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            StakeTool::Show(tool) => tool.execute_serialized().await,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    CliCommand, CliConfig, CliError, CliTypedResult, ProfileOptions, RestOptions,
+};
+use aptos_rest_client::{Client, StakePool};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+
+/// Command to show the stake pool of a validator, including its delegations and their lockups
+/// and rewards
+///
+#[derive(Debug, Parser)]
+pub struct ShowStakePool {
+    #[clap(flatten)]
+    rest_options: RestOptions,
+
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+
+    /// Address of the validator owning the stake pool (defaults to the profile's account)
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pool_address: Option<AccountAddress>,
+}
+
+#[async_trait]
+impl CliCommand<StakePool> for ShowStakePool {
+    fn command_name(&self) -> &'static str {
+        "ShowStakePool"
+    }
+
+    async fn execute(self) -> CliTypedResult<StakePool> {
+        let pool_address = if let Some(pool_address) = self.pool_address {
+            pool_address
+        } else if let Some(Some(account)) =
+            CliConfig::load_profile(&self.profile_options.profile)?.map(|p| p.account)
+        {
+            account
+        } else {
+            return Err(CliError::CommandArgumentError(
+                "Please provide a pool address using --pool-address or run aptos init".to_string(),
+            ));
+        };
+
+        let client = Client::new(self.rest_options.url(&self.profile_options.profile)?);
+        Ok(client
+            .get_stake_pool(pool_address)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?
+            .into_inner())
+    }
+}
@@ -0,0 +1,365 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliError, CliTypedResult, ProfileOptions, RestOptions};
+use aptos_crypto::{HashValue, Signature};
+use aptos_rest_client::{
+    aptos_api_types::{MoveFunction, MoveType},
+    Client, MoveModuleBytecode, Transaction, UserTransactionRequest,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        authenticator::TransactionAuthenticator, ScriptFunction, SignedTransaction,
+        TransactionPayload,
+    },
+};
+use async_trait::async_trait;
+use clap::Parser;
+use move_deps::{
+    move_binary_format::CompiledModule,
+    move_core_types::{
+        language_storage::ModuleId,
+        value::{MoveTypeLayout, MoveValue},
+    },
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::convert::TryFrom;
+
+/// Command to decode a signed transaction, for debugging stuck or malformed transactions
+///
+/// The transaction is given either BCS encoded as hex, in which case it's decoded offline, or by
+/// the hash of a pending or committed transaction to fetch from the node.  The arguments of
+/// script functions are decoded with the ABI of the function, taken from the framework or
+/// fetched from the node.  Arguments that can't be decoded are shown as hex.
+///
+#[derive(Debug, Parser)]
+pub struct DecodeTransaction {
+    /// BCS encoded signed transaction, as hex
+    #[clap(long, group = "transaction")]
+    bcs: Option<String>,
+
+    /// Hash of the transaction to fetch from the node
+    #[clap(long, group = "transaction", parse(try_from_str))]
+    hash: Option<HashValue>,
+
+    #[clap(flatten)]
+    rest_options: RestOptions,
+
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecodedTransaction {
+    hash: String,
+    sender: AccountAddress,
+    sequence_number: u64,
+    max_gas_amount: u64,
+    gas_unit_price: u64,
+    expiration_timestamp_secs: u64,
+    /// Only part of BCS encoded transactions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chain_id: Option<u8>,
+    payload: Value,
+    /// Whether the signature is valid, if it can be checked
+    signature_valid: Option<bool>,
+}
+
+#[async_trait]
+impl CliCommand<DecodedTransaction> for DecodeTransaction {
+    fn command_name(&self) -> &'static str {
+        "DecodeTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<DecodedTransaction> {
+        let client = Client::new(self.rest_options.url(&self.profile_options.profile)?);
+        if let Some(bcs_hex) = &self.bcs {
+            let bytes = hex::decode(bcs_hex.trim().trim_start_matches("0x"))
+                .map_err(|err| CliError::UnableToParse("--bcs", err.to_string()))?;
+            let txn: SignedTransaction = bcs::from_bytes(&bytes)
+                .map_err(|err| CliError::UnableToParse("--bcs", err.to_string()))?;
+            decode_signed_transaction(&client, txn).await
+        } else if let Some(hash) = self.hash {
+            fetch_transaction(&client, hash).await
+        } else {
+            Err(CliError::CommandArgumentError(
+                "One of ['--bcs', '--hash'] must be used".to_string(),
+            ))
+        }
+    }
+}
+
+async fn decode_signed_transaction(
+    client: &Client,
+    txn: SignedTransaction,
+) -> CliTypedResult<DecodedTransaction> {
+    let payload = match txn.payload() {
+        TransactionPayload::ScriptFunction(script_function) => {
+            decode_script_function(client, script_function).await
+        }
+        TransactionPayload::Script(script) => json!({
+            "type": "script_payload",
+            "code": format!("0x{}", hex::encode(script.code())),
+            "type_arguments": script.ty_args().iter().map(|tag| tag.to_string()).collect::<Vec<_>>(),
+            "arguments": script.args(),
+        }),
+        TransactionPayload::ModuleBundle(modules) => json!({
+            "type": "module_bundle_payload",
+            "modules": modules
+                .iter()
+                .map(|module| match CompiledModule::deserialize(module.code()) {
+                    Ok(module) => module.self_id().to_string(),
+                    Err(err) => format!("invalid module: {}", err),
+                })
+                .collect::<Vec<_>>(),
+        }),
+        TransactionPayload::WriteSet(_) => json!({ "type": "write_set_payload" }),
+    };
+
+    Ok(DecodedTransaction {
+        hash: txn.clone().committed_hash().to_hex_literal(),
+        sender: txn.sender(),
+        sequence_number: txn.sequence_number(),
+        max_gas_amount: txn.max_gas_amount(),
+        gas_unit_price: txn.gas_unit_price(),
+        expiration_timestamp_secs: txn.expiration_timestamp_secs(),
+        chain_id: Some(txn.chain_id().id()),
+        payload,
+        signature_valid: Some(txn.check_signature().is_ok()),
+    })
+}
+
+async fn fetch_transaction(client: &Client, hash: HashValue) -> CliTypedResult<DecodedTransaction> {
+    let request = match client
+        .get_transaction(hash)
+        .await
+        .map_err(|err| CliError::ApiError(err.to_string()))?
+        .into_inner()
+    {
+        Transaction::PendingTransaction(txn) => txn.request,
+        Transaction::UserTransaction(txn) => txn.request,
+        _ => {
+            return Err(CliError::CommandArgumentError(format!(
+                "Transaction {} is not a user transaction",
+                hash
+            )))
+        }
+    };
+    let signature_valid = check_request_signature(client, &request).await?;
+
+    Ok(DecodedTransaction {
+        hash: hash.to_hex_literal(),
+        sender: request.sender.into(),
+        sequence_number: request.sequence_number.0,
+        max_gas_amount: request.max_gas_amount.0,
+        gas_unit_price: request.gas_unit_price.0,
+        expiration_timestamp_secs: request.expiration_timestamp_secs.0,
+        chain_id: None,
+        // The node already decoded the arguments with their ABI
+        payload: serde_json::to_value(&request.payload)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
+        signature_valid,
+    })
+}
+
+/// Checks the signature of a transaction fetched from the node against the signing message the
+/// node computes for it.  Multi agent signatures are over more than that message, so they
+/// aren't checked.
+async fn check_request_signature(
+    client: &Client,
+    request: &UserTransactionRequest,
+) -> CliTypedResult<Option<bool>> {
+    let authenticator = match request.signature.clone() {
+        Some(signature) => TransactionAuthenticator::try_from(signature)
+            .map_err(|err| CliError::UnableToParse("signature", err.to_string()))?,
+        None => return Ok(Some(false)),
+    };
+    let message = client
+        .create_signing_message(request)
+        .await
+        .map_err(|err| CliError::ApiError(err.to_string()))?
+        .into_inner()
+        .message;
+    let message = message.inner();
+
+    Ok(match authenticator {
+        TransactionAuthenticator::Ed25519 {
+            public_key,
+            signature,
+        } => Some(signature.verify_arbitrary_msg(message, &public_key).is_ok()),
+        TransactionAuthenticator::MultiEd25519 {
+            public_key,
+            signature,
+        } => Some(signature.verify_arbitrary_msg(message, &public_key).is_ok()),
+        TransactionAuthenticator::MultiAgent { .. } => None,
+    })
+}
+
+async fn decode_script_function(client: &Client, script_function: &ScriptFunction) -> Value {
+    let function = format!(
+        "{}::{}",
+        script_function.module(),
+        script_function.function()
+    );
+    let abi = find_function_abi(client, script_function).await;
+
+    // Signer parameters are not passed as arguments
+    let params: Vec<&MoveType> = abi
+        .as_ref()
+        .map(|abi| {
+            abi.params
+                .iter()
+                .filter(|param| !is_signer(param))
+                .collect()
+        })
+        .unwrap_or_default();
+    let arguments: Vec<Value> = script_function
+        .args()
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            params
+                .get(index)
+                .and_then(|param| decode_argument(param, arg))
+                .unwrap_or_else(|| Value::String(format!("0x{}", hex::encode(arg))))
+        })
+        .collect();
+
+    json!({
+        "type": "script_function_payload",
+        "function": function,
+        "type_arguments": script_function
+            .ty_args()
+            .iter()
+            .map(|tag| tag.to_string())
+            .collect::<Vec<_>>(),
+        "arguments": arguments,
+        "abi_found": abi.is_some(),
+    })
+}
+
+/// Looks the function up in the framework, or else in the modules published on chain
+async fn find_function_abi(
+    client: &Client,
+    script_function: &ScriptFunction,
+) -> Option<MoveFunction> {
+    let module_id = script_function.module();
+    let framework_module = cached_framework_packages::module_blobs()
+        .iter()
+        .find(|blob| {
+            CompiledModule::deserialize(blob)
+                .map(|module| &module.self_id() == module_id)
+                .unwrap_or(false)
+        })
+        .cloned();
+    let module = match framework_module {
+        Some(blob) => MoveModuleBytecode::new(blob),
+        None => fetch_module(client, module_id).await?,
+    };
+
+    module
+        .try_parse_abi()
+        .ok()?
+        .abi?
+        .exposed_functions
+        .into_iter()
+        .find(|function| function.name.as_ident_str() == script_function.function())
+}
+
+async fn fetch_module(client: &Client, module_id: &ModuleId) -> Option<MoveModuleBytecode> {
+    client
+        .get_account_modules(*module_id.address())
+        .await
+        .ok()?
+        .into_inner()
+        .into_iter()
+        .find(|module| {
+            CompiledModule::deserialize(module.bytecode.inner())
+                .map(|module| &module.self_id() == module_id)
+                .unwrap_or(false)
+        })
+}
+
+fn is_signer(param: &MoveType) -> bool {
+    match param {
+        MoveType::Signer => true,
+        MoveType::Reference { to, .. } => matches!(**to, MoveType::Signer),
+        _ => false,
+    }
+}
+
+/// Only arguments of primitive types and vectors of them can be decoded, generic and struct
+/// arguments need the type arguments resolved and the struct layouts.
+fn argument_layout(param: &MoveType) -> Option<MoveTypeLayout> {
+    Some(match param {
+        MoveType::Bool => MoveTypeLayout::Bool,
+        MoveType::U8 => MoveTypeLayout::U8,
+        MoveType::U64 => MoveTypeLayout::U64,
+        MoveType::U128 => MoveTypeLayout::U128,
+        MoveType::Address => MoveTypeLayout::Address,
+        MoveType::Vector { items } => MoveTypeLayout::Vector(Box::new(argument_layout(items)?)),
+        _ => return None,
+    })
+}
+
+fn decode_argument(param: &MoveType, arg: &[u8]) -> Option<Value> {
+    let value = MoveValue::simple_deserialize(arg, &argument_layout(param)?).ok()?;
+    Some(move_value_to_json(value))
+}
+
+/// Renders values the way the API does, with `u64` and `u128` as strings and `vector<u8>` as hex
+fn move_value_to_json(value: MoveValue) -> Value {
+    match value {
+        MoveValue::Bool(value) => Value::Bool(value),
+        MoveValue::U8(value) => Value::from(value),
+        MoveValue::U64(value) => Value::String(value.to_string()),
+        MoveValue::U128(value) => Value::String(value.to_string()),
+        MoveValue::Address(address) | MoveValue::Signer(address) => {
+            Value::String(address.to_hex_literal())
+        }
+        MoveValue::Vector(values) => {
+            if values.iter().all(|value| matches!(value, MoveValue::U8(_))) && !values.is_empty() {
+                let bytes: Vec<u8> = values
+                    .into_iter()
+                    .filter_map(|value| match value {
+                        MoveValue::U8(byte) => Some(byte),
+                        _ => None,
+                    })
+                    .collect();
+                Value::String(format!("0x{}", hex::encode(bytes)))
+            } else {
+                Value::Array(values.into_iter().map(move_value_to_json).collect())
+            }
+        }
+        MoveValue::Struct(_) => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_argument() {
+        let address = AccountAddress::random();
+        assert_eq!(
+            decode_argument(&MoveType::Address, &bcs::to_bytes(&address).unwrap()),
+            Some(Value::String(address.to_hex_literal()))
+        );
+        assert_eq!(
+            decode_argument(&MoveType::U64, &bcs::to_bytes(&42u64).unwrap()),
+            Some(Value::String("42".to_string()))
+        );
+        let bytes = MoveType::Vector {
+            items: Box::new(MoveType::U8),
+        };
+        assert_eq!(
+            decode_argument(&bytes, &bcs::to_bytes(&vec![1u8, 2]).unwrap()),
+            Some(Value::String("0x0102".to_string()))
+        );
+        // Truncated arguments can't be decoded
+        assert_eq!(decode_argument(&MoveType::U64, &[1, 2]), None);
+    }
+}
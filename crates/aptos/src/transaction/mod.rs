@@ -0,0 +1,22 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliResult};
+use clap::Subcommand;
+
+pub mod decode;
+
+/// CLI tool for inspecting transactions
+///
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Decode(decode::DecodeTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::Decode(tool) => tool.execute_serialized().await,
+        }
+    }
+}
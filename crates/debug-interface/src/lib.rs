@@ -2,11 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
+use aptos_mempool::MempoolSnapshotEntry;
 use reqwest::{blocking, Url};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 
 pub mod node_debug_service;
 
+/// Body of `POST /failpoints`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FailpointUpdate {
+    pub name: String,
+    /// Actions of the failpoint, `None` deactivates it.
+    pub actions: Option<String>,
+}
+
 /// Implement default utility client for NodeDebugInterface
 pub struct NodeDebugClient {
     client: blocking::Client,
@@ -78,6 +88,54 @@ impl NodeDebugClient {
             })
             .collect()
     }
+
+    /// Retrieves the registered and active failpoints with their actions.
+    pub fn get_failpoints(&self) -> Result<BTreeMap<String, Option<String>>> {
+        let mut url = self.url.clone();
+        url.set_path("failpoints");
+        let response = self.client.get(url).send()?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Error querying failpoints: {}", response.status());
+        }
+        Ok(response.json()?)
+    }
+
+    /// Retrieves every transaction held in mempool.
+    pub fn get_mempool_snapshot(&self) -> Result<Vec<MempoolSnapshotEntry>> {
+        let mut url = self.url.clone();
+        url.set_path("mempool/snapshot");
+        let response = self.client.get(url).send()?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Error querying mempool snapshot: {}", response.status());
+        }
+        Ok(response.json()?)
+    }
+
+    /// Sets the actions of a failpoint, or deactivates it if `actions` is `None`.
+    pub fn set_failpoint(&self, name: &str, actions: Option<&str>) -> Result<()> {
+        let mut url = self.url.clone();
+        url.set_path("failpoints");
+        let response = self
+            .client
+            .post(url)
+            .json(&FailpointUpdate {
+                name: name.to_string(),
+                actions: actions.map(str::to_string),
+            })
+            .send()?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Error setting failpoint {}: {} {}",
+                name,
+                response.status(),
+                response.text()?
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Implement default utility client for AsyncNodeDebugInterface
@@ -153,4 +211,53 @@ impl AsyncNodeDebugClient {
             })
             .collect()
     }
+
+    /// Retrieves the registered and active failpoints with their actions.
+    pub async fn get_failpoints(&self) -> Result<BTreeMap<String, Option<String>>> {
+        let mut url = self.url.clone();
+        url.set_path("failpoints");
+        let response = self.client.get(url).send().await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Error querying failpoints: {}", response.status());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Retrieves every transaction held in mempool.
+    pub async fn get_mempool_snapshot(&self) -> Result<Vec<MempoolSnapshotEntry>> {
+        let mut url = self.url.clone();
+        url.set_path("mempool/snapshot");
+        let response = self.client.get(url).send().await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!("Error querying mempool snapshot: {}", response.status());
+        }
+        Ok(response.json().await?)
+    }
+
+    /// Sets the actions of a failpoint, or deactivates it if `actions` is `None`.
+    pub async fn set_failpoint(&self, name: &str, actions: Option<&str>) -> Result<()> {
+        let mut url = self.url.clone();
+        url.set_path("failpoints");
+        let response = self
+            .client
+            .post(url)
+            .json(&FailpointUpdate {
+                name: name.to_string(),
+                actions: actions.map(str::to_string),
+            })
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            anyhow::bail!(
+                "Error setting failpoint {}: {} {}",
+                name,
+                response.status(),
+                response.text().await?
+            );
+        }
+        Ok(())
+    }
 }
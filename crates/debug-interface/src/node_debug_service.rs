@@ -3,13 +3,25 @@
 
 //! Debug interface to access information in a specific node.
 
+use crate::FailpointUpdate;
 use aptos_config::config::NodeConfig;
 use aptos_logger::{info, Filter, Logger};
+use aptos_mempool::{
+    DeadLetterQueue, MempoolClientRequest, MempoolClientSender, MempoolSnapshotEntry,
+};
 use aptos_metrics::json_metrics::get_git_rev;
+use aptos_types::account_address::AccountAddress;
+use futures::{channel::oneshot, SinkExt};
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, sync::Arc};
+use std::{convert::Infallible, fmt::Write, net::SocketAddr, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
-use warp::Filter as _;
+use warp::{
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    Filter as _, Reply,
+};
 
 #[derive(Debug)]
 pub struct NodeDebugService {
@@ -24,8 +36,40 @@ struct NodeInfo {
     git_revision: String,
 }
 
+/// Query of `GET /mempool/dead-letters`.
+#[derive(Debug, Deserialize)]
+struct DeadLetterQuery {
+    sender: Option<AccountAddress>,
+}
+
+/// Query of `GET /mempool/snapshot`.
+#[derive(Debug, Deserialize)]
+struct SnapshotQuery {
+    #[serde(default)]
+    format: SnapshotFormat,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SnapshotFormat {
+    Json,
+    Csv,
+}
+
+impl Default for SnapshotFormat {
+    fn default() -> Self {
+        SnapshotFormat::Json
+    }
+}
+
 impl NodeDebugService {
-    pub fn new(address: SocketAddr, logger: Option<Arc<Logger>>, node_config: &NodeConfig) -> Self {
+    pub fn new(
+        address: SocketAddr,
+        logger: Option<Arc<Logger>>,
+        node_config: &NodeConfig,
+        dead_letters: DeadLetterQueue,
+        mempool_client: MempoolClientSender,
+    ) -> Self {
         let runtime = Builder::new_multi_thread()
             .thread_name("nodedebug")
             .enable_all()
@@ -80,7 +124,78 @@ impl NodeDebugService {
         };
         let node_info_route = warp::path("node-info").map(move || warp::reply::json(&node_info));
 
-        let routes = log.or(warp::get().and(metrics.or(node_info_route)));
+        // Get /failpoints (registered failpoints and the actions of the active ones)
+        let get_failpoints =
+            warp::path("failpoints").map(|| warp::reply::json(&aptos_failpoints::list()));
+
+        // Get /mempool/dead-letters (transactions discarded during block execution, optionally
+        // only the ones of `?sender=<address>`)
+        let get_dead_letters = warp::path!("mempool" / "dead-letters")
+            .and(warp::query::<DeadLetterQuery>())
+            .map(move |query: DeadLetterQuery| {
+                warp::reply::json(&dead_letters.letters(query.sender))
+            });
+
+        // Get /mempool/snapshot (every transaction held in mempool, as JSON or with
+        // `?format=csv` as a CSV file)
+        let get_mempool_snapshot = warp::path!("mempool" / "snapshot")
+            .and(warp::query::<SnapshotQuery>())
+            .and_then(move |query: SnapshotQuery| {
+                let mempool_client = mempool_client.clone();
+                async move {
+                    let reply: Box<dyn Reply> = match mempool_snapshot(mempool_client).await {
+                        Ok(snapshot) => match query.format {
+                            SnapshotFormat::Json => Box::new(warp::reply::json(&snapshot)),
+                            SnapshotFormat::Csv => Box::new(warp::reply::with_header(
+                                warp::reply::with_header(
+                                    snapshot_csv(&snapshot),
+                                    CONTENT_TYPE,
+                                    "text/csv",
+                                ),
+                                CONTENT_DISPOSITION,
+                                "attachment; filename=\"mempool-snapshot.csv\"",
+                            )),
+                        },
+                        Err(e) => Box::new(warp::reply::with_status(
+                            e.to_string(),
+                            StatusCode::SERVICE_UNAVAILABLE,
+                        )),
+                    };
+                    Ok::<_, Infallible>(reply)
+                }
+            });
+
+        // Post /failpoints
+        let set_failpoint = warp::post()
+            .and(warp::path("failpoints"))
+            .and(warp::body::content_length_limit(1024 * 16))
+            .and(warp::body::json())
+            .map(|update: FailpointUpdate| {
+                info!(
+                    failpoint = update.name.as_str(),
+                    actions = ?update.actions,
+                    "Updating failpoint"
+                );
+                let result = match &update.actions {
+                    Some(actions) => aptos_failpoints::set(&update.name, actions),
+                    None => {
+                        aptos_failpoints::remove(&update.name);
+                        Ok(())
+                    }
+                };
+                match result {
+                    Ok(()) => warp::reply::with_status(String::new(), StatusCode::OK),
+                    Err(e) => warp::reply::with_status(e.to_string(), StatusCode::BAD_REQUEST),
+                }
+            });
+
+        let routes = log.or(set_failpoint).or(warp::get().and(
+            metrics
+                .or(node_info_route)
+                .or(get_failpoints)
+                .or(get_dead_letters)
+                .or(get_mempool_snapshot),
+        ));
 
         runtime
             .handle()
@@ -93,3 +208,36 @@ impl NodeDebugService {
         &self.runtime
     }
 }
+
+async fn mempool_snapshot(
+    mut mempool_client: MempoolClientSender,
+) -> anyhow::Result<Vec<MempoolSnapshotEntry>> {
+    let (callback, snapshot) = oneshot::channel();
+    mempool_client
+        .send(MempoolClientRequest::GetSnapshot(callback))
+        .await?;
+    Ok(snapshot.await?)
+}
+
+fn snapshot_csv(snapshot: &[MempoolSnapshotEntry]) -> String {
+    let mut csv = String::from(
+        "sender,sequence_number,gas_unit_price,ranking_score,parked,insertion_timestamp_usecs,expiration_timestamp_secs\n",
+    );
+    for entry in snapshot {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            entry.sender.to_hex_literal(),
+            entry.sequence_number,
+            entry.gas_unit_price,
+            entry.ranking_score,
+            entry.parked,
+            entry
+                .insertion_timestamp_usecs
+                .map_or_else(String::new, |timestamp| timestamp.to_string()),
+            entry.expiration_timestamp_secs,
+        )
+        .expect("writing to a String cannot fail");
+    }
+    csv
+}
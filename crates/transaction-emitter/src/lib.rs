@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use ::aptos_logger::*;
-use anyhow::{format_err, Context, Result};
+use anyhow::{bail, ensure, format_err, Context, Result};
 use aptos_rest_client::{Client as RestClient, PendingTransaction, Response};
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
@@ -13,7 +13,7 @@ use aptos_sdk::{
         LocalAccount,
     },
 };
-use futures::future::{try_join_all, FutureExt};
+use futures::future::{join_all, try_join_all, FutureExt};
 use itertools::zip;
 use rand::{
     distributions::{Distribution, Standard},
@@ -23,10 +23,11 @@ use rand::{
 use rand_core::SeedableRng;
 use std::{
     cmp::{max, min},
-    collections::HashSet,
-    fmt,
+    collections::{HashMap, HashSet, VecDeque},
+    fmt, fs,
     num::NonZeroU64,
     path::Path,
+    str::FromStr,
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
@@ -56,6 +57,10 @@ const TXN_EXPIRATION_SECONDS: u64 = 180;
 const TXN_MAX_WAIT: Duration = Duration::from_secs(TXN_EXPIRATION_SECONDS as u64 + 30);
 const MAX_CHILD_VASP_NUM: usize = 65536;
 const MAX_VASP_ACCOUNT_NUM: usize = 16;
+/// How often open-loop workers submit the transactions that became due
+const OPEN_LOOP_TICK: Duration = Duration::from_millis(100);
+/// How often open-loop workers check which of their pending transactions were committed
+const OPEN_LOOP_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Clone)]
 pub struct EmitThreadParams {
@@ -72,6 +77,30 @@ impl Default for EmitThreadParams {
     }
 }
 
+/// The kinds of transactions the emitter can generate
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TransactionType {
+    /// Transfers coins to another of the emitter's accounts
+    CoinTransfer,
+    /// Creates a new account
+    AccountGeneration,
+}
+
+impl FromStr for TransactionType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "coin-transfer" => Ok(TransactionType::CoinTransfer),
+            "account-generation" => Ok(TransactionType::AccountGeneration),
+            _ => bail!(
+                "Unknown transaction type '{}', expected coin-transfer or account-generation",
+                s
+            ),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct EmitJobRequest {
     rest_clients: Vec<RestClient>,
@@ -81,6 +110,9 @@ pub struct EmitJobRequest {
     gas_price: u64,
     invalid_transaction_ratio: usize,
     vasp: bool,
+    open_loop_tps: Option<NonZeroU64>,
+    transaction_mix: Vec<(TransactionType, usize)>,
+    mint_accounts: bool,
 }
 
 impl Default for EmitJobRequest {
@@ -93,6 +125,9 @@ impl Default for EmitJobRequest {
             gas_price: 0,
             invalid_transaction_ratio: 0,
             vasp: false,
+            open_loop_tps: None,
+            transaction_mix: vec![(TransactionType::CoinTransfer, 1)],
+            mint_accounts: true,
         }
     }
 }
@@ -145,6 +180,35 @@ impl EmitJobRequest {
             .accounts_per_client(1)
     }
 
+    /// Submits transactions at `target_tps` regardless of how long they take to commit, unlike
+    /// `fixed_tps` which waits for every batch to commit before submitting the next one. Latency
+    /// is measured from the time each transaction was scheduled to be submitted, so it keeps
+    /// growing once the network saturates instead of the submission rate dropping.
+    pub fn open_loop_tps(mut self, target_tps: NonZeroU64) -> Self {
+        let clients_count = self.rest_clients.len() as u64;
+        // Enough accounts for each of them to have a single transaction submitted per second,
+        // which keeps them well under the mempool per account limit
+        let accounts_per_client = (target_tps.get() + clients_count - 1) / clients_count;
+        self.open_loop_tps = Some(target_tps);
+
+        self.workers_per_endpoint(1)
+            .accounts_per_client(accounts_per_client as usize)
+    }
+
+    /// Generates each valid transaction with a type picked at random, weighted by the usize
+    /// paired with it
+    pub fn transaction_mix(mut self, transaction_mix: Vec<(TransactionType, usize)>) -> Self {
+        self.transaction_mix = transaction_mix;
+        self
+    }
+
+    /// Only uses the accounts already added to the emitter (see `TxnEmitter::add_accounts`),
+    /// failing the job if there aren't enough of them instead of minting new ones
+    pub fn without_minting(mut self) -> Self {
+        self.mint_accounts = false;
+        self
+    }
+
     pub fn vasp(mut self) -> Self {
         self.vasp = true;
         self
@@ -188,6 +252,14 @@ pub struct EmitJob {
     stats: Arc<StatsAccumulator>,
 }
 
+/// A transaction submitted by an open-loop worker which isn't known to be committed yet
+struct PendingTxn {
+    /// Index of the sender in the worker's accounts
+    account: usize,
+    sequence_number: u64,
+    scheduled_at: Instant,
+}
+
 struct SubmissionWorker {
     accounts: Vec<LocalAccount>,
     client: RestClient,
@@ -197,6 +269,7 @@ struct SubmissionWorker {
     stats: Arc<StatsAccumulator>,
     txn_factory: TransactionFactory,
     invalid_transaction_ratio: usize,
+    transaction_mix: Vec<(TransactionType, usize)>,
     rng: ::rand::rngs::StdRng,
 }
 
@@ -270,6 +343,152 @@ impl SubmissionWorker {
         self.accounts
     }
 
+    async fn run_open_loop(mut self, gas_price: u64, target_tps: f64) -> Vec<LocalAccount> {
+        let start_time = Instant::now();
+        let mut scheduled = 0u64;
+        let mut next_account = 0;
+        let mut pending = VecDeque::new();
+        // Accounts whose sequence number has to be read again from the chain before they submit
+        // anything else, because one of their transactions was dropped
+        let mut stale_accounts = HashSet::new();
+        let mut last_poll = start_time;
+        let mut ticker = time::interval(OPEN_LOOP_TICK);
+        while !self.stop.load(Ordering::Relaxed) {
+            ticker.tick().await;
+
+            // Build every transaction that became due since the last tick, from distinct accounts
+            // which are not stale
+            let due = (start_time.elapsed().as_secs_f64() * target_tps) as u64;
+            let mut batch = vec![];
+            for _ in 0..self.accounts.len() {
+                if scheduled >= due {
+                    break;
+                }
+                let account = next_account;
+                next_account = (next_account + 1) % self.accounts.len();
+                if stale_accounts.contains(&account) {
+                    continue;
+                }
+                let scheduled_at =
+                    start_time + Duration::from_secs_f64(scheduled as f64 / target_tps);
+                let receiver = self
+                    .all_addresses
+                    .choose(&mut self.rng)
+                    .expect("all_addresses can't be empty");
+                let request = gen_txn_request(
+                    pick_transaction_type(&self.transaction_mix, &mut self.rng),
+                    &mut self.accounts[account],
+                    receiver,
+                    &self.txn_factory,
+                    gas_price,
+                    &mut self.rng,
+                );
+                batch.push((account, request, scheduled_at));
+                scheduled += 1;
+            }
+
+            let results = join_all(
+                batch
+                    .iter()
+                    .map(|(_, request, _)| self.client.submit(request)),
+            )
+            .await;
+            self.stats
+                .submitted
+                .fetch_add(batch.len() as u64, Ordering::Relaxed);
+            for ((account, request, scheduled_at), result) in zip(batch, results) {
+                match result {
+                    Ok(_) => pending.push_back(PendingTxn {
+                        account,
+                        sequence_number: request.sequence_number(),
+                        scheduled_at,
+                    }),
+                    Err(e) => {
+                        warn!("[{:?}] Failed to submit request: {:?}", self.client, e);
+                        self.stats.expired.fetch_add(1, Ordering::Relaxed);
+                        stale_accounts.insert(account);
+                    }
+                }
+            }
+
+            if last_poll.elapsed() >= OPEN_LOOP_POLL_INTERVAL {
+                self.poll_committed(&mut pending, &mut stale_accounts).await;
+                last_poll = Instant::now();
+            }
+        }
+
+        // Give the transactions still in flight a chance to commit, so the ones submitted last
+        // are accounted for as well
+        while !pending.is_empty() {
+            self.poll_committed(&mut pending, &mut stale_accounts).await;
+            time::sleep(OPEN_LOOP_POLL_INTERVAL).await;
+        }
+        self.accounts
+    }
+
+    /// Records the latency of every `pending` transaction which got committed, and drops the ones
+    /// which can't be committed anymore because they expired. The transactions following a
+    /// dropped one are parked behind the gap it left, so its account is restarted from the
+    /// sequence number on chain, giving up on its other pending transactions.
+    async fn poll_committed(
+        &mut self,
+        pending: &mut VecDeque<PendingTxn>,
+        stale_accounts: &mut HashSet<usize>,
+    ) {
+        let accounts = pending
+            .iter()
+            .map(|txn| txn.account)
+            .chain(stale_accounts.iter().copied())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let addresses = accounts
+            .iter()
+            .map(|account| self.accounts[*account].address())
+            .collect::<Vec<_>>();
+        let sequence_numbers = match query_sequence_numbers(&self.client, &addresses).await {
+            Ok(sequence_numbers) => zip(accounts, sequence_numbers).collect::<HashMap<_, _>>(),
+            Err(e) => {
+                warn!(
+                    "[{:?}] Failed to query sequence numbers: {:?}",
+                    self.client, e
+                );
+                return;
+            }
+        };
+
+        let now = Instant::now();
+        let expiration = Duration::from_secs(TXN_EXPIRATION_SECONDS);
+        pending.retain(|txn| {
+            let latency = now.duration_since(txn.scheduled_at);
+            if txn.sequence_number < sequence_numbers[&txn.account] {
+                let latency = latency.as_millis() as u64;
+                self.stats.committed.fetch_add(1, Ordering::Relaxed);
+                self.stats.latency.fetch_add(latency, Ordering::Relaxed);
+                self.stats.latencies.record_data_point(latency, 1);
+                false
+            } else if latency > expiration {
+                self.stats.expired.fetch_add(1, Ordering::Relaxed);
+                stale_accounts.insert(txn.account);
+                false
+            } else {
+                true
+            }
+        });
+
+        for account in stale_accounts.drain() {
+            pending.retain(|txn| {
+                if txn.account == account {
+                    self.stats.expired.fetch_add(1, Ordering::Relaxed);
+                    false
+                } else {
+                    true
+                }
+            });
+            *self.accounts[account].sequence_number_mut() = sequence_numbers[&account];
+        }
+    }
+
     fn gen_requests(&mut self, gas_price: u64) -> Vec<SignedTransaction> {
         let batch_size = max(MAX_TXN_BATCH_SIZE, self.accounts.len());
         let accounts = self
@@ -291,12 +510,13 @@ impl SubmissionWorker {
                 .expect("all_addresses can't be empty");
             let request = if num_valid_tx > 0 {
                 num_valid_tx -= 1;
-                gen_transfer_txn_request(
+                gen_txn_request(
+                    pick_transaction_type(&self.transaction_mix, &mut self.rng),
                     sender,
                     receiver,
-                    SEND_AMOUNT,
                     &self.txn_factory,
                     gas_price,
+                    &mut self.rng,
                 )
             } else {
                 generate_invalid_transaction(
@@ -340,6 +560,11 @@ impl<'t> TxnEmitter<'t> {
         }
     }
 
+    /// Adds already funded accounts for jobs to submit transactions from
+    pub fn add_accounts(&mut self, mut accounts: Vec<LocalAccount>) {
+        self.accounts.append(&mut accounts);
+    }
+
     pub fn take_account(&mut self) -> LocalAccount {
         self.accounts.remove(0)
     }
@@ -455,6 +680,12 @@ impl<'t> TxnEmitter<'t> {
             info!("Already have enough accounts exist, do not need to mint more");
             return Ok(());
         }
+        ensure!(
+            req.mint_accounts,
+            "Job needs {} accounts but only {} were provided and minting is disabled",
+            total_requested_accounts,
+            self.accounts.len()
+        );
         let expected_num_seed_accounts =
             if total_requested_accounts / req.rest_clients.len() > MAX_CHILD_VASP_NUM {
                 total_requested_accounts / MAX_CHILD_VASP_NUM + 1
@@ -588,9 +819,20 @@ impl<'t> TxnEmitter<'t> {
                     stats,
                     txn_factory: self.txn_factory.clone(),
                     invalid_transaction_ratio: req.invalid_transaction_ratio,
+                    transaction_mix: req.transaction_mix.clone(),
                     rng: self.from_rng(),
                 };
-                let join_handle = tokio_handle.spawn(worker.run(req.gas_price).boxed());
+                let join_handle = match req.open_loop_tps {
+                    Some(target_tps) => tokio_handle.spawn(
+                        worker
+                            .run_open_loop(
+                                req.gas_price,
+                                target_tps.get() as f64 / num_clients as f64,
+                            )
+                            .boxed(),
+                    ),
+                    None => tokio_handle.spawn(worker.run(req.gas_price).boxed()),
+                };
                 workers.push(Worker { join_handle });
             }
         }
@@ -880,6 +1122,79 @@ where
         .collect()
 }
 
+fn pick_transaction_type<R: Rng>(
+    transaction_mix: &[(TransactionType, usize)],
+    rng: &mut R,
+) -> TransactionType {
+    transaction_mix
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .map(|(txn_type, _)| *txn_type)
+        .unwrap_or(TransactionType::CoinTransfer)
+}
+
+fn gen_txn_request<R>(
+    txn_type: TransactionType,
+    sender: &mut LocalAccount,
+    receiver: &AccountAddress,
+    txn_factory: &TransactionFactory,
+    gas_price: u64,
+    rng: &mut R,
+) -> SignedTransaction
+where
+    R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+{
+    match txn_type {
+        TransactionType::CoinTransfer => {
+            gen_transfer_txn_request(sender, receiver, SEND_AMOUNT, txn_factory, gas_price)
+        }
+        TransactionType::AccountGeneration => {
+            let new_account = LocalAccount::generate(rng);
+            let preimage = AuthenticationKeyPreimage::ed25519(new_account.public_key());
+            let auth_key = AuthenticationKey::from_preimage(&preimage);
+            sender.sign_with_transaction_builder(
+                txn_factory
+                    .payload(aptos_stdlib::encode_account_create_account(
+                        auth_key.derived_address(),
+                    ))
+                    .gas_unit_price(gas_price),
+            )
+        }
+    }
+}
+
+/// Loads accounts from a file holding one hex encoded Ed25519 private key per line, along with
+/// their current sequence numbers
+pub async fn load_accounts_from_file(
+    client: &RestClient,
+    path: &Path,
+) -> Result<Vec<LocalAccount>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read accounts file {}", path.display()))?;
+    let keys = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            EncodingType::Hex
+                .decode_key::<Ed25519PrivateKey>("account private key", line.as_bytes().to_vec())
+                .map_err(|e| format_err!("Failed to parse accounts file {}: {}", path.display(), e))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let account_keys = keys
+        .into_iter()
+        .map(AccountKey::from_private_key)
+        .collect::<Vec<_>>();
+    let addresses = account_keys
+        .iter()
+        .map(|key| key.authentication_key().derived_address())
+        .collect::<Vec<_>>();
+    let sequence_numbers = query_sequence_numbers(client, &addresses).await?;
+
+    Ok(zip(zip(addresses, account_keys), sequence_numbers)
+        .map(|((address, key), sequence_number)| LocalAccount::new(address, key, sequence_number))
+        .collect())
+}
+
 pub fn gen_transfer_txn_request(
     sender: &mut LocalAccount,
     receiver: &AccountAddress,
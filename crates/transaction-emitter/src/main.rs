@@ -4,23 +4,27 @@
 use anyhow::{bail, format_err, Result};
 use aptos::common::types::EncodingType;
 use aptos_config::{config::DEFAULT_PORT, keys::ConfigKey};
-use aptos_crypto::ed25519::Ed25519PrivateKey;
-use aptos_sdk::{transaction_builder::TransactionFactory, types::chain_id::ChainId};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+use aptos_sdk::{
+    transaction_builder::TransactionFactory,
+    types::{chain_id::ChainId, LocalAccount},
+};
 use futures::future::join_all;
 use itertools::zip;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use rand_core::OsRng;
 use std::{
     cmp::min,
-    path::Path,
+    num::NonZeroU64,
+    path::{Path, PathBuf},
     process,
     time::{Duration, Instant},
 };
 use structopt::StructOpt;
 use termion::color;
 use transaction_emitter::{
-    cluster::Cluster, instance::Instance, query_sequence_numbers, EmitJobRequest, EmitThreadParams,
-    TxnEmitter,
+    cluster::Cluster, instance::Instance, load_accounts_from_file, query_sequence_numbers,
+    EmitJobRequest, EmitThreadParams, TransactionType, TxnEmitter,
 };
 
 #[derive(StructOpt, Debug)]
@@ -62,6 +66,28 @@ struct Args {
     duration: u64,
     #[structopt(long, help = "Percentage of invalid txs", default_value = "0")]
     invalid_tx: usize,
+    #[structopt(
+        long,
+        help = "If set, submits txns at this rate regardless of how long they take to commit"
+    )]
+    open_loop_tps: Option<NonZeroU64>,
+    #[structopt(
+        long,
+        help = "If set, submits txns at this rate, waiting for each batch to commit before the next"
+    )]
+    target_tps: Option<NonZeroU64>,
+    #[structopt(
+        long,
+        help = "Weighted types of txns to emit, e.g. coin-transfer:70,account-generation:30",
+        default_value = "coin-transfer:1"
+    )]
+    transaction_mix: String,
+    #[structopt(
+        long,
+        help = "File with one hex encoded private key per line of already funded accounts to \
+                emit from. If set, no accounts are minted and no mint key is needed"
+    )]
+    accounts_file: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -92,7 +118,16 @@ async fn emit_tx(cluster: &Cluster, args: &Args) -> Result<()> {
     };
     let duration = Duration::from_secs(args.duration);
     let client = cluster.random_instance().rest_client();
-    let mut root_account = cluster.load_aptos_root_account(&client).await?;
+    let provided_accounts = match &args.accounts_file {
+        Some(path) => Some(load_accounts_from_file(&client, path).await?),
+        None => None,
+    };
+    let mut root_account = if provided_accounts.is_some() {
+        // Never used, as the emitter doesn't mint when accounts are provided
+        LocalAccount::generate(&mut OsRng)
+    } else {
+        cluster.load_aptos_root_account(&client).await?
+    };
     let mut emitter = TxnEmitter::new(
         &mut root_account,
         client,
@@ -106,10 +141,22 @@ async fn emit_tx(cluster: &Cluster, args: &Args) -> Result<()> {
             .accounts_per_client(args.accounts_per_client)
             .thread_params(thread_params)
             .invalid_transaction_ratio(args.invalid_tx)
+            .transaction_mix(parse_transaction_mix(&args.transaction_mix)?)
             .gas_price(1);
     if let Some(workers_per_endpoint) = args.workers_per_ac {
         emit_job_request = emit_job_request.workers_per_endpoint(workers_per_endpoint);
     }
+    if let Some(target_tps) = args.target_tps {
+        emit_job_request = emit_job_request.fixed_tps(target_tps);
+    }
+    if let Some(target_tps) = args.open_loop_tps {
+        emit_job_request = emit_job_request.open_loop_tps(target_tps);
+    }
+    if let Some(accounts) = provided_accounts {
+        println!("Loaded {} accounts", accounts.len());
+        emitter.add_accounts(accounts);
+        emit_job_request = emit_job_request.without_minting();
+    }
     if args.vasp {
         emit_job_request = emit_job_request.vasp();
     }
@@ -121,6 +168,22 @@ async fn emit_tx(cluster: &Cluster, args: &Args) -> Result<()> {
     Ok(())
 }
 
+fn parse_transaction_mix(s: &str) -> Result<Vec<(TransactionType, usize)>> {
+    let mix = s
+        .split(',')
+        .map(|entry| -> Result<(TransactionType, usize)> {
+            match entry.split_once(':') {
+                Some((txn_type, weight)) => Ok((txn_type.parse()?, weight.parse()?)),
+                None => Ok((entry.parse()?, 1)),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if mix.iter().all(|(_, weight)| *weight == 0) {
+        bail!("Transaction mix {} needs at least one non zero weight", s);
+    }
+    Ok(mix)
+}
+
 fn parse_host_port(s: &str) -> Result<(String, u32, Option<u32>)> {
     let v = s.split(':').collect::<Vec<&str>>();
     if v.len() == 1 {
@@ -159,6 +222,9 @@ impl BasicSwarmUtil {
 
         let mint_key = if let Some(ref key) = args.mint_key {
             key.private_key()
+        } else if args.accounts_file.is_some() {
+            // Accounts are provided rather than minted, so the mint key is never used
+            Ed25519PrivateKey::generate(&mut OsRng)
         } else {
             EncodingType::BCS
                 .load_key::<Ed25519PrivateKey>("mint key pair", Path::new(&args.mint_file))
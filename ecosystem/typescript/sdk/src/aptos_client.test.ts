@@ -11,6 +11,12 @@ test("gets genesis account", async () => {
   expect(account.sequence_number).not.toBeNull();
 });
 
+test("checks account existence", async () => {
+  const client = new AptosClient(NODE_URL);
+  expect(await client.accountExists("0x1")).toBe(true);
+  expect(await client.accountExists("0xf")).toBe(false);
+});
+
 test("gets transactions", async () => {
   const client = new AptosClient(NODE_URL);
   const transactions = await client.getTransactions();
@@ -79,6 +79,16 @@ export class AptosClient {
     return response.data;
   }
 
+  /** Returns whether an account exists at the given address, without fetching its resources */
+  async accountExists(accountAddress: MaybeHexString): Promise<boolean> {
+    const response = await this.accounts.getAccount(HexString.ensure(accountAddress).hex());
+    if (response.status === 404) {
+      return false;
+    }
+    raiseForStatus(200, response);
+    return true;
+  }
+
   /** Returns transactions sent by the account */
   async getAccountTransactions(
     accountAddress: MaybeHexString,
@@ -33,6 +33,18 @@ pub enum Error {
 
     #[error("Received Empty Blocks")]
     EmptyBlocks,
+
+    #[error(
+        "Transaction infos don't match. version: {}, txn_info:{}, expected_txn_info:{}",
+        version,
+        txn_info,
+        expected_txn_info
+    )]
+    TransactionInfoMismatch {
+        version: Version,
+        txn_info: String,
+        expected_txn_info: String,
+    },
 }
 
 impl From<anyhow::Error> for Error {
@@ -3,7 +3,7 @@
 
 #![forbid(unsafe_code)]
 
-use crate::{ExecutedTrees, StateComputeResult, TransactionData};
+use crate::{Error, ExecutedTrees, StateComputeResult, TransactionData};
 use anyhow::{bail, ensure, Result};
 use aptos_crypto::hash::{CryptoHash, TransactionAccumulatorHasher};
 use aptos_types::{
@@ -99,13 +99,14 @@ impl ExecutedChunk {
                 itertools::zip_eq(self.to_commit.iter(), transaction_infos.iter()).enumerate()
             {
                 if &txn_data.txn_info != expected_txn_info {
-                    bail!(
-                        "Transaction infos don't match. version: {}, txn_info:{}, expected_txn_info:{}",
-                        self.result_view.transaction_accumulator.version() + 1 + idx as u64
+                    bail!(Error::TransactionInfoMismatch {
+                        version: self.result_view.transaction_accumulator.version()
+                            + 1
+                            + idx as u64
                             - self.to_commit.len() as u64,
-                        &txn_data.txn_info,
-                        expected_txn_info,
-                    )
+                        txn_info: txn_data.txn_info.to_string(),
+                        expected_txn_info: expected_txn_info.to_string(),
+                    })
                 }
             }
             unreachable!()
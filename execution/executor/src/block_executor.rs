@@ -18,8 +18,8 @@ use crate::{
     components::{block_tree::BlockTree, chunk_output::ChunkOutput},
     metrics::{
         APTOS_EXECUTOR_COMMIT_BLOCKS_SECONDS, APTOS_EXECUTOR_EXECUTE_BLOCK_SECONDS,
-        APTOS_EXECUTOR_SAVE_TRANSACTIONS_SECONDS, APTOS_EXECUTOR_TRANSACTIONS_SAVED,
-        APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS,
+        APTOS_EXECUTOR_SAVE_TRANSACTIONS_SECONDS, APTOS_EXECUTOR_SPECULATIVE_BLOCK_LOOKUPS,
+        APTOS_EXECUTOR_TRANSACTIONS_SAVED, APTOS_EXECUTOR_VM_EXECUTE_BLOCK_SECONDS,
     },
 };
 use storage_interface::DbReaderWriter;
@@ -76,8 +76,14 @@ where
 
         if let Some(b) = block_vec.pop().expect("Must exist") {
             // this is a retry
+            APTOS_EXECUTOR_SPECULATIVE_BLOCK_LOOKUPS
+                .with_label_values(&["execute", "hit"])
+                .inc();
             return Ok(b.output.as_state_compute_result(parent_accumulator));
         }
+        APTOS_EXECUTOR_SPECULATIVE_BLOCK_LOOKUPS
+            .with_label_values(&["execute", "miss"])
+            .inc();
 
         let output = if parent_block_id != committed_block.id && parent_output.has_reconfiguration()
         {
@@ -139,7 +145,16 @@ where
             "commit_block"
         );
 
-        let blocks = self.block_tree.get_blocks(&block_ids)?;
+        // The blocks were executed by `execute_block`, their outputs are committed as is.
+        let blocks = self.block_tree.get_blocks(&block_ids).map_err(|e| {
+            APTOS_EXECUTOR_SPECULATIVE_BLOCK_LOOKUPS
+                .with_label_values(&["commit", "miss"])
+                .inc();
+            e
+        })?;
+        APTOS_EXECUTOR_SPECULATIVE_BLOCK_LOOKUPS
+            .with_label_values(&["commit", "hit"])
+            .inc_by(blocks.len() as u64);
         let txns_to_commit: Vec<_> = blocks
             .into_iter()
             .map(|block| block.output.transactions_to_commit())
@@ -9,6 +9,7 @@ mod test;
 use crate::{
     components::in_memory_state_calculator::IntoLedgerView,
     logging::{LogEntry, LogSchema},
+    metrics::APTOS_EXECUTOR_SPECULATIVE_BLOCKS,
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_crypto::HashValue;
@@ -125,6 +126,7 @@ impl BlockLookupInner {
                     block_lookup: block_lookup.clone(),
                 });
                 entry.insert(Arc::downgrade(&block));
+                APTOS_EXECUTOR_SPECULATIVE_BLOCKS.set(self.0.len() as i64);
                 Ok((block, false, parent_block))
             }
         }
@@ -169,7 +171,9 @@ impl BlockLookup {
     }
 
     fn remove(&self, id: HashValue) {
-        self.inner.lock().0.remove(&id);
+        let mut inner = self.inner.lock();
+        inner.0.remove(&id);
+        APTOS_EXECUTOR_SPECULATIVE_BLOCKS.set(inner.0.len() as i64);
     }
 }
 
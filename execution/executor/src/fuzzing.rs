@@ -14,7 +14,9 @@ use aptos_types::{
 };
 use aptos_vm::VMExecutor;
 use executor_types::{BlockExecutorTrait, ChunkExecutorTrait};
-use storage_interface::{DbReader, DbReaderWriter, DbWriter, StartupInfo};
+use storage_interface::{
+    DbReaderWriter, DbWriter, EventReader, LedgerReader, StartupInfo, StateReader,
+};
 
 fn create_test_executor() -> BlockExecutor<FakeVM> {
     // setup fake db
@@ -65,7 +67,7 @@ impl VMExecutor for FakeVM {
 /// A fake database implementing DbReader and DbWriter
 pub struct FakeDb;
 
-impl DbReader for FakeDb {
+impl LedgerReader for FakeDb {
     fn get_latest_version(&self) -> Result<Version> {
         Ok(self.get_latest_ledger_info()?.ledger_info().version())
     }
@@ -81,6 +83,10 @@ impl DbReader for FakeDb {
     }
 }
 
+impl StateReader for FakeDb {}
+
+impl EventReader for FakeDb {}
+
 impl DbWriter for FakeDb {
     fn save_transactions(
         &self,
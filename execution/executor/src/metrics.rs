@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_metrics::{register_histogram, register_int_counter, Histogram, IntCounter};
+use aptos_metrics::{
+    register_histogram, register_int_counter, register_int_counter_vec, register_int_gauge,
+    Histogram, IntCounter, IntCounterVec, IntGauge,
+};
 use once_cell::sync::Lazy;
 
 pub static APTOS_EXECUTOR_EXECUTE_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
@@ -97,3 +100,26 @@ pub static APTOS_EXECUTOR_TRANSACTIONS_SAVED: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static APTOS_EXECUTOR_SPECULATIVE_BLOCK_LOOKUPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_executor_speculative_block_lookups",
+        // metric description
+        "The number of lookups of speculatively executed blocks in the block tree, by stage \
+        (execute, commit) and result (hit, miss)",
+        // metric labels
+        &["stage", "result"]
+    )
+    .unwrap()
+});
+
+pub static APTOS_EXECUTOR_SPECULATIVE_BLOCKS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        // metric name
+        "aptos_executor_speculative_blocks",
+        // metric description
+        "The number of blocks whose execution output is held in the block tree"
+    )
+    .unwrap()
+});
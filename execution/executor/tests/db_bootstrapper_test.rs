@@ -48,7 +48,7 @@ use move_deps::move_core_types::{
 use rand::SeedableRng;
 use std::sync::Arc;
 use storage_interface::{
-    state_view::LatestDbStateView, DbReader, DbReaderWriter, StateSnapshotReceiver,
+    state_view::LatestDbStateView, DbReader, DbReaderWriter, StateReader, StateSnapshotReceiver,
 };
 
 #[test]
@@ -0,0 +1,131 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares submitting transactions to mempool one request per transaction with submitting them in
+//! batches, as the API does with a submission batch window.
+
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
+use aptos_mempool::{mocks::MockSharedMempool, MempoolClientRequest};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    mempool_status::MempoolStatusCode,
+    transaction::{RawTransaction, Script, SignedTransaction},
+};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use futures::{channel::oneshot, executor::block_on, future::join_all, SinkExt};
+use rand::{rngs::StdRng, SeedableRng};
+
+const NUM_TXNS: usize = 1_000;
+const BATCH_SIZES: &[usize] = &[1, 8, 32, 100];
+
+fn signed_transactions(count: usize) -> Vec<SignedTransaction> {
+    let mut rng = StdRng::from_seed([0u8; 32]);
+    let private_key = Ed25519PrivateKey::generate(&mut rng);
+    (0..count)
+        .map(|_| {
+            RawTransaction::new_script(
+                AccountAddress::random(),
+                0,
+                Script::new(vec![], vec![], vec![]),
+                100,
+                1,
+                u64::max_value(),
+                ChainId::test(),
+            )
+            .sign(&private_key, private_key.public_key())
+            .expect("Failed to sign raw transaction.")
+            .into_inner()
+        })
+        .collect()
+}
+
+/// Submits all of `txns` concurrently, in requests of `batch_size` transactions, and waits for
+/// all of them to be accepted.
+fn submit(smp: &MockSharedMempool, txns: Vec<SignedTransaction>, batch_size: usize) {
+    let mut callbacks = vec![];
+    let mut requests = vec![];
+    let mut txns = txns.into_iter().peekable();
+    while txns.peek().is_some() {
+        let batch: Vec<_> = txns
+            .by_ref()
+            .take(batch_size)
+            .map(|txn| {
+                let (sender, callback) = oneshot::channel();
+                callbacks.push(callback);
+                (txn, sender)
+            })
+            .collect();
+        requests.push(if batch_size == 1 {
+            let (txn, sender) = batch.into_iter().next().unwrap();
+            MempoolClientRequest::SubmitTransaction(txn, sender)
+        } else {
+            MempoolClientRequest::SubmitTransactionBatch(batch)
+        });
+    }
+
+    block_on(async {
+        join_all(requests.into_iter().map(|request| {
+            let mut client = smp.ac_client.clone();
+            async move { client.send(request).await.unwrap() }
+        }))
+        .await;
+        for status in join_all(callbacks).await {
+            let (mempool_status, _) = status.unwrap().unwrap();
+            assert_eq!(mempool_status.code, MempoolStatusCode::Accepted);
+        }
+    });
+}
+
+fn submission_throughput(c: &mut Criterion) {
+    let mut group = c.benchmark_group("client_submission_throughput");
+    group.throughput(Throughput::Elements(NUM_TXNS as u64));
+    group.sample_size(20);
+    for batch_size in BATCH_SIZES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(batch_size),
+            batch_size,
+            |b, &batch_size| {
+                b.iter_batched(
+                    || (MockSharedMempool::new(), signed_transactions(NUM_TXNS)),
+                    // Returning the mempool leaves shutting it down out of the measurement.
+                    |(smp, txns)| {
+                        submit(&smp, txns, batch_size);
+                        smp
+                    },
+                    BatchSize::PerIteration,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+/// The time to get the statuses of a single request, of `batch_size` transactions.
+fn submission_latency(c: &mut Criterion) {
+    let mut group = c.benchmark_group("client_submission_latency");
+    for batch_size in BATCH_SIZES {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(batch_size),
+            batch_size,
+            |b, &batch_size| {
+                b.iter_batched(
+                    || (MockSharedMempool::new(), signed_transactions(batch_size)),
+                    |(smp, txns)| {
+                        submit(&smp, txns, batch_size);
+                        smp
+                    },
+                    BatchSize::PerIteration,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    name = client_submission;
+    config = Criterion::default();
+    targets = submission_throughput, submission_latency
+);
+criterion_main!(client_submission);
@@ -12,10 +12,12 @@ use crate::{
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
+    shared_mempool::types::MempoolSnapshotEntry,
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{MempoolConfig, NodeConfig};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     account_address::AccountAddress,
     account_config::AccountSequenceInfo,
@@ -24,7 +26,7 @@ use aptos_types::{
 };
 use std::{
     cmp::max,
-    collections::HashSet,
+    collections::{HashMap, HashSet, VecDeque},
     time::{Duration, SystemTime},
 };
 
@@ -38,6 +40,11 @@ pub struct Mempool {
     // takes to pick it up by consensus.
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), SystemTime>,
     pub system_transaction_timeout: Duration,
+    // Transactions of a sender beyond this number are only included in a block once the
+    // transactions of all other senders are.
+    max_block_txns_per_sender: usize,
+    // The clock of the system TTL of the transactions.
+    time_service: TimeService,
 }
 
 impl Mempool {
@@ -49,9 +56,17 @@ impl Mempool {
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            max_block_txns_per_sender: config.mempool.max_block_txns_per_sender,
+            time_service: TimeService::real(),
         }
     }
 
+    /// Uses `time_service` instead of the system clock for the system TTL of the transactions.
+    pub(crate) fn with_time_service(mut self, time_service: TimeService) -> Self {
+        self.time_service = time_service;
+        self
+    }
+
     /// This function will be called once the transaction has been stored.
     pub(crate) fn remove_transaction(
         &mut self,
@@ -123,6 +138,21 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Returns the sequence numbers of all transactions of `address` in mempool, ready or not.
+    pub(crate) fn get_pending_sequence_numbers(&self, address: &AccountAddress) -> Vec<u64> {
+        self.transactions.get_sequence_numbers(address)
+    }
+
+    /// Returns the number of transactions in mempool.
+    pub(crate) fn size(&self) -> usize {
+        self.transactions.size()
+    }
+
+    /// Returns the number of transactions mempool can hold.
+    pub(crate) fn capacity(&self) -> usize {
+        self.transactions.capacity()
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -158,8 +188,7 @@ impl Mempool {
             ));
         }
 
-        let expiration_time =
-            aptos_infallible::duration_since_epoch() + self.system_transaction_timeout;
+        let expiration_time = self.time_service.now_unix_time() + self.system_transaction_timeout;
         if timeline_state != TimelineState::NonQualified {
             self.metrics_cache
                 .insert((txn.sender(), txn.sequence_number()), SystemTime::now());
@@ -181,6 +210,10 @@ impl Mempool {
     /// `batch_size` - size of requested block.
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet,
     ///  mempool should filter out such transactions.
+    ///
+    /// A sender gets at most `max_block_txns_per_sender` transactions into the block before the
+    /// transactions of the other senders; its next transactions are deferred and only fill up the
+    /// remaining space of the block. The transactions of the senders are interleaved round-robin.
     #[allow(clippy::explicit_counter_loop)]
     pub(crate) fn get_block(
         &self,
@@ -195,6 +228,10 @@ impl Mempool {
         // but can't be executed before first txn. Once observed, such txn will be saved in
         // `skipped` DS and rechecked once it's ancestor becomes available
         let mut skipped = HashSet::new();
+        // Number of transactions of each sender in the block, and the next transaction of each
+        // sender that reached `max_block_txns_per_sender`, in priority order.
+        let mut txns_per_sender = HashMap::new();
+        let mut deferred = vec![];
         let seen_size = seen.len();
         let mut txn_walked = 0usize;
         // iterate over the queue of transactions based on gas price
@@ -215,6 +252,12 @@ impl Mempool {
                 || matches!(account_seqtype, AccountSequenceInfo::CRSN { .. })
             {
                 let ptr = TxnPointer::from(txn);
+                let sender_txns = txns_per_sender.entry(txn.address).or_insert(0);
+                if *sender_txns >= self.max_block_txns_per_sender {
+                    deferred.push(ptr);
+                    continue;
+                }
+                *sender_txns += 1;
                 seen.insert(ptr);
                 result.push(ptr);
                 if (result.len() as u64) == batch_size {
@@ -225,6 +268,11 @@ impl Mempool {
                 // that were skipped before for given account
                 let mut skipped_txn = (txn.address, tx_seq + 1);
                 while skipped.contains(&skipped_txn) {
+                    if *sender_txns >= self.max_block_txns_per_sender {
+                        deferred.push(skipped_txn);
+                        break;
+                    }
+                    *sender_txns += 1;
                     seen.insert(skipped_txn);
                     result.push(skipped_txn);
                     if (result.len() as u64) == batch_size {
@@ -236,6 +284,24 @@ impl Mempool {
                 skipped.insert(TxnPointer::from(txn));
             }
         }
+        // fill up the block with the deferred transactions, and the transactions
+        // that were skipped before for their accounts
+        if (result.len() as u64) < batch_size {
+            'deferred: for (address, mut tx_seq) in deferred {
+                loop {
+                    seen.insert((address, tx_seq));
+                    result.push((address, tx_seq));
+                    if (result.len() as u64) == batch_size {
+                        break 'deferred;
+                    }
+                    tx_seq += 1;
+                    if !skipped.contains(&(address, tx_seq)) {
+                        break;
+                    }
+                }
+            }
+        }
+        let result = interleave_senders(result);
         let result_size = result.len();
         // convert transaction pointers to real values
         let mut block_log = TxnsLog::new();
@@ -265,12 +331,19 @@ impl Mempool {
         block
     }
 
+    /// Applies the capacities of an updated mempool config. Transactions already in mempool are
+    /// kept even if they exceed the new capacities.
+    pub(crate) fn update_capacity(&mut self, config: &MempoolConfig) {
+        self.transactions.update_capacity(config);
+    }
+
     /// Periodic core mempool garbage collection.
     /// Removes all expired transactions and clears expired entries in metrics
     /// cache and sequence number cache.
     pub(crate) fn gc(&mut self) {
         let now = SystemTime::now();
-        self.transactions.gc_by_system_ttl(&self.metrics_cache);
+        self.transactions
+            .gc_by_system_ttl(self.time_service.now_unix_time(), &self.metrics_cache);
         self.metrics_cache.gc(now);
         self.sequence_number_cache.gc(now);
     }
@@ -300,8 +373,48 @@ impl Mempool {
         self.transactions.gen_snapshot(&self.metrics_cache)
     }
 
+    /// The transactions held in mempool, in no particular order so that the lock isn't held while
+    /// sorting them.
+    pub(crate) fn snapshot(&self) -> Vec<MempoolSnapshotEntry> {
+        self.transactions.snapshot(&self.metrics_cache)
+    }
+
     #[cfg(test)]
     pub fn get_parking_lot_size(&self) -> usize {
         self.transactions.get_parking_lot_size()
     }
 }
+
+/// Reorders a block so that the transactions of its senders alternate, the senders taking turns in
+/// the order of their first transaction in the block. The transactions of each sender keep their
+/// order.
+fn interleave_senders(block: Vec<TxnPointer>) -> Vec<TxnPointer> {
+    let mut senders = vec![];
+    let mut txns_by_sender: HashMap<AccountAddress, VecDeque<u64>> = HashMap::new();
+    for (address, tx_seq) in &block {
+        txns_by_sender
+            .entry(*address)
+            .or_insert_with(|| {
+                senders.push(*address);
+                VecDeque::new()
+            })
+            .push_back(*tx_seq);
+    }
+
+    let mut result = Vec::with_capacity(block.len());
+    while !senders.is_empty() {
+        senders.retain(|address| {
+            match txns_by_sender
+                .get_mut(address)
+                .and_then(VecDeque::pop_front)
+            {
+                Some(tx_seq) => {
+                    result.push((*address, tx_seq));
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+    result
+}
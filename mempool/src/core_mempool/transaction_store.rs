@@ -12,6 +12,7 @@ use crate::{
     },
     counters,
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
+    shared_mempool::types::MempoolSnapshotEntry,
 };
 use aptos_config::config::MempoolConfig;
 use aptos_crypto::HashValue;
@@ -79,6 +80,12 @@ impl TransactionStore {
         }
     }
 
+    /// Updates the capacities. Transactions already stored are kept even if they exceed them.
+    pub(crate) fn update_capacity(&mut self, config: &MempoolConfig) {
+        self.capacity = config.capacity;
+        self.capacity_per_user = config.capacity_per_user;
+    }
+
     /// Fetch transaction by account address + sequence_number.
     pub(crate) fn get(
         &self,
@@ -102,6 +109,23 @@ impl TransactionStore {
         }
     }
 
+    /// Fetch sequence numbers of all transactions of the account, in ascending order.
+    pub(crate) fn get_sequence_numbers(&self, address: &AccountAddress) -> Vec<u64> {
+        self.transactions
+            .get(address)
+            .map(|txns| txns.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of transactions in the store, the one compared against the capacity.
+    pub(crate) fn size(&self) -> usize {
+        self.system_ttl_index.size()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.capacity
+    }
+
     /// Fetch mempool transaction by account address + sequence_number.
     pub(crate) fn get_mempool_txn(
         &self,
@@ -296,7 +320,8 @@ impl TransactionStore {
 
             match crsn_or_seqno {
                 AccountSequenceInfo::CRSN { min_nonce, size } => {
-                    for i in min_nonce..size {
+                    // The nonces of the window are [min_nonce, min_nonce + size).
+                    for i in min_nonce..min_nonce.saturating_add(size) {
                         if let Some(txn) = txns.get_mut(&i) {
                             self.priority_index.insert(txn);
 
@@ -450,13 +475,12 @@ impl TransactionStore {
             .collect()
     }
 
-    /// Garbage collect old transactions.
+    /// Garbage collect the transactions whose system TTL expired by `now`.
     pub(crate) fn gc_by_system_ttl(
         &mut self,
+        now: Duration,
         metrics_cache: &TtlCache<(AccountAddress, u64), SystemTime>,
     ) {
-        let now = aptos_infallible::duration_since_epoch();
-
         self.gc(now, true, metrics_cache);
     }
 
@@ -565,6 +589,31 @@ impl TransactionStore {
         txns_log
     }
 
+    /// Copies the transactions in no particular order, they are sorted by the caller once the
+    /// mempool lock is released.
+    pub(crate) fn snapshot(
+        &self,
+        metrics_cache: &TtlCache<(AccountAddress, u64), SystemTime>,
+    ) -> Vec<MempoolSnapshotEntry> {
+        self.transactions
+            .iter()
+            .flat_map(|(account, txns)| {
+                txns.iter().map(move |(seq_num, txn)| MempoolSnapshotEntry {
+                    sender: *account,
+                    sequence_number: *seq_num,
+                    gas_unit_price: txn.get_gas_price(),
+                    ranking_score: txn.ranking_score,
+                    parked: self.parking_lot_index.contains(account, seq_num),
+                    insertion_timestamp_usecs: metrics_cache
+                        .get(&(*account, *seq_num))
+                        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+                        .map(|time| time.as_micros() as u64),
+                    expiration_timestamp_secs: txn.expiration_time.as_secs(),
+                })
+            })
+            .collect()
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
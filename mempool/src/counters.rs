@@ -4,7 +4,7 @@
 use aptos_config::network_id::{NetworkId, PeerNetworkId};
 use aptos_metrics::{
     register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
-    register_int_gauge_vec, DurationHistogram, HistogramTimer, HistogramVec, IntCounter,
+    register_int_gauge_vec, DurationHistogram, Histogram, HistogramTimer, HistogramVec, IntCounter,
     IntCounterVec, IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
@@ -34,6 +34,7 @@ pub const GC_PARKED_TXN_LABEL: &str = "parked";
 
 // Mempool service request type labels
 pub const GET_BLOCK_LABEL: &str = "get_block";
+pub const GET_TRANSACTIONS_LABEL: &str = "get_transactions";
 pub const COMMIT_STATE_SYNC_LABEL: &str = "commit_accepted";
 pub const COMMIT_CONSENSUS_LABEL: &str = "commit_rejected";
 
@@ -52,6 +53,9 @@ pub const SUCCESS_LABEL: &str = "success";
 // Bounded executor task labels
 pub const CLIENT_EVENT_LABEL: &str = "client_event";
 pub const CLIENT_EVENT_GET_TXN_LABEL: &str = "client_event_get_txn";
+pub const CLIENT_EVENT_GET_SEQ_NUMS_LABEL: &str = "client_event_get_seq_nums";
+pub const CLIENT_EVENT_GET_UTILIZATION_LABEL: &str = "client_event_get_utilization";
+pub const CLIENT_EVENT_GET_SNAPSHOT_LABEL: &str = "client_event_get_snapshot";
 pub const RECONFIG_EVENT_LABEL: &str = "reconfig";
 pub const PEER_BROADCAST_EVENT_LABEL: &str = "peer_broadcast";
 
@@ -212,6 +216,20 @@ pub fn process_txn_submit_latency_timer_client() -> HistogramTimer {
         .start_timer()
 }
 
+/// Counter for number of transactions in each batch of transactions submitted by clients
+static CLIENT_SUBMISSION_BATCH_SIZE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "mempool_client_submission_batch_size",
+        "Number of transactions in each batch of transactions submitted by clients",
+        vec![1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0, 512.0]
+    )
+    .unwrap()
+});
+
+pub fn client_submission_batch_size(num_txns: usize) {
+    CLIENT_SUBMISSION_BATCH_SIZE.observe(num_txns as f64);
+}
+
 /// Counter for tracking e2e latency for mempool to process get txn by hash requests from clients and peers
 static PROCESS_GET_TXN_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
@@ -61,8 +61,10 @@ pub use shared_mempool::{
     bootstrap, network,
     types::{
         ConsensusRequest, ConsensusResponse, MempoolClientRequest, MempoolClientSender,
-        MempoolEventsReceiver, SubmissionStatus, TransactionSummary,
+        MempoolEventsReceiver, MempoolSnapshotEntry, MempoolUtilization,
+        RejectedTransactionSummary, SubmissionStatus, TransactionSummary,
     },
+    DeadLetter, DeadLetterQueue,
 };
 #[cfg(any(test, feature = "fuzzing"))]
 pub use tests::{fuzzing, mocks};
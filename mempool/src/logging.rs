@@ -128,8 +128,12 @@ pub enum LogEntry {
     CoordinatorRuntime,
     GCRuntime,
     ReconfigUpdate,
+    ConfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetPendingSequenceNumbers,
+    GetUtilization,
+    GetSnapshot,
     GetBlock,
     Consensus,
     StateSyncCommit,
@@ -138,6 +142,7 @@ pub enum LogEntry {
     ReceiveACK,
     InvariantViolated,
     AddTxn,
+    SenderNotAllowed,
     RemoveTxn,
     MempoolFullEvictedTxn,
     GCRemoveTxns,
@@ -20,6 +20,7 @@ use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::on_chain_config::OnChainConfigPayload;
 use bounded_executor::BoundedExecutor;
+use config_notifications::MempoolConfigListener;
 use event_notifications::ReconfigNotificationListener;
 use futures::{
     channel::mpsc,
@@ -126,6 +127,26 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::SubmitTransactionBatch(batch) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_transaction_batch_submission(
+                    smp.clone(),
+                    batch,
+                    task_start_timer,
+                ))
+                .await;
+        }
         MempoolClientRequest::GetTransactionByHash(hash, callback) => {
             // This timer measures how long it took for the bounded executor to *schedule* the
             // task.
@@ -147,6 +168,67 @@ async fn handle_client_request<V>(
                 ))
                 .await;
         }
+        MempoolClientRequest::GetPendingSequenceNumbers(address, callback) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_SEQ_NUMS_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_SEQ_NUMS_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_pending_sequence_numbers(
+                    smp.clone(),
+                    address,
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetUtilization(callback) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_UTILIZATION_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_UTILIZATION_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_utilization(
+                    smp.clone(),
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
+        MempoolClientRequest::GetSnapshot(callback) => {
+            // This timer measures how long it took for the bounded executor to *schedule* the
+            // task.
+            let _timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_SNAPSHOT_LABEL,
+                counters::SPAWN_LABEL,
+            );
+            // This timer measures how long it took for the task to go from scheduled to started.
+            let task_start_timer = counters::task_spawn_latency_timer(
+                counters::CLIENT_EVENT_GET_SNAPSHOT_LABEL,
+                counters::START_LABEL,
+            );
+            bounded_executor
+                .spawn(tasks::process_client_get_snapshot(
+                    smp.clone(),
+                    callback,
+                    task_start_timer,
+                ))
+                .await;
+        }
     }
 }
 
@@ -343,6 +425,22 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
     ));
 }
 
+/// Applies the updates of the mempool config to core mempool.
+pub(crate) async fn config_update_listener(
+    mempool: Arc<Mutex<CoreMempool>>,
+    mut config_updates: MempoolConfigListener,
+) {
+    while let Some(config) = config_updates.next().await {
+        info!(
+            LogSchema::new(LogEntry::ConfigUpdate),
+            capacity = config.capacity,
+            capacity_per_user = config.capacity_per_user,
+            "Updating mempool capacity"
+        );
+        mempool.lock().update_capacity(&config);
+    }
+}
+
 /// Periodically logs a snapshot of transactions in core mempool.
 /// In the future we may want an interactive way to directly query mempool's internal state.
 /// For now, we will rely on this periodic snapshot to observe the internal state.
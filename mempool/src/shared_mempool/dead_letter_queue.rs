@@ -0,0 +1,64 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounded record of the transactions that were admitted to mempool but then discarded during
+//! block execution (e.g., because the prologue failed at execution time), so that integrators can
+//! learn why such "accepted" transactions never appear on-chain.
+//!
+//! Discards are only known to validators, which receive them from consensus, so the queue of a
+//! full node stays empty.
+
+use aptos_crypto::HashValue;
+use aptos_infallible::Mutex;
+use aptos_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, sync::Arc};
+
+/// A transaction discarded during block execution.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct DeadLetter {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub hash: HashValue,
+    /// The status the transaction was discarded with, e.g. `SEQUENCE_NUMBER_TOO_OLD`.
+    pub reason: String,
+    /// When mempool was notified of the discard.
+    pub timestamp_usecs: u64,
+}
+
+/// Keeps the last `capacity` dead letters, the oldest ones are dropped first.
+#[derive(Clone, Debug)]
+pub struct DeadLetterQueue {
+    capacity: usize,
+    letters: Arc<Mutex<VecDeque<DeadLetter>>>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            letters: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        }
+    }
+
+    pub fn push(&self, letter: DeadLetter) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut letters = self.letters.lock();
+        if letters.len() == self.capacity {
+            letters.pop_front();
+        }
+        letters.push_back(letter);
+    }
+
+    /// Returns the dead letters, oldest first, only the ones of `sender` if given.
+    pub fn letters(&self, sender: Option<AccountAddress>) -> Vec<DeadLetter> {
+        self.letters
+            .lock()
+            .iter()
+            .filter(|letter| sender.map_or(true, |sender| letter.sender == sender))
+            .cloned()
+            .collect()
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod dead_letter_queue;
+pub use dead_letter_queue::{DeadLetter, DeadLetterQueue};
 pub mod network;
 mod runtime;
 pub(crate) mod types;
@@ -5,14 +5,16 @@ use crate::{
     core_mempool::CoreMempool,
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
-        coordinator::{coordinator, gc_coordinator, snapshot_job},
+        coordinator::{config_update_listener, coordinator, gc_coordinator, snapshot_job},
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
+        DeadLetterQueue,
     },
     ConsensusRequest,
 };
 use aptos_config::{config::NodeConfig, network_id::NetworkId};
 use aptos_infallible::{Mutex, RwLock};
 
+use config_notifications::MempoolConfigListener;
 use event_notifications::ReconfigNotificationListener;
 use futures::channel::mpsc::{self, Receiver, UnboundedSender};
 use mempool_notifications::MempoolNotificationListener;
@@ -42,6 +44,7 @@ pub(crate) fn start_shared_mempool<V>(
     validator: Arc<RwLock<V>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    dead_letters: DeadLetterQueue,
 ) where
     V: TransactionValidation + 'static,
 {
@@ -61,6 +64,7 @@ pub(crate) fn start_shared_mempool<V>(
         subscribers,
         config.base.role,
         peer_metadata_storage,
+        dead_letters,
     );
 
     executor.spawn(coordinator(
@@ -95,6 +99,8 @@ pub fn bootstrap(
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: ReconfigNotificationListener,
     peer_metadata_storage: Arc<PeerMetadataStorage>,
+    config_updates: MempoolConfigListener,
+    dead_letters: DeadLetterQueue,
 ) -> Runtime {
     let runtime = Builder::new_multi_thread()
         .thread_name("shared-mem")
@@ -103,6 +109,7 @@ pub fn bootstrap(
         .expect("[shared mempool] failed to create runtime");
     let mempool = Arc::new(Mutex::new(CoreMempool::new(config)));
     let vm_validator = Arc::new(RwLock::new(VMValidator::new(Arc::clone(&db))));
+    runtime.spawn(config_update_listener(mempool.clone(), config_updates));
     start_shared_mempool(
         runtime.handle(),
         config,
@@ -116,6 +123,7 @@ pub fn bootstrap(
         vm_validator,
         vec![],
         peer_metadata_storage,
+        dead_letters,
     );
     runtime
 }
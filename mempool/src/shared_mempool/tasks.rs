@@ -5,11 +5,14 @@
 use crate::{
     core_mempool::{CoreMempool, TimelineState, TxnPointer},
     counters,
-    logging::{LogEntry, LogEvent, LogSchema},
+    logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::{BroadcastError, MempoolSyncMsg},
-    shared_mempool::types::{
-        notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-        SubmissionStatusBundle, TransactionSummary,
+    shared_mempool::{
+        types::{
+            notify_subscribers, MempoolSnapshotEntry, MempoolUtilization, ScheduledBroadcast,
+            SharedMempool, SharedMempoolNotification, SubmissionStatusBundle, TransactionSummary,
+        },
+        DeadLetter,
     },
     ConsensusRequest, ConsensusResponse, SubmissionStatus,
 };
@@ -20,6 +23,7 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_metrics::HistogramTimer;
 use aptos_types::{
+    account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     on_chain_config::OnChainConfigPayload,
     transaction::SignedTransaction,
@@ -30,7 +34,7 @@ use network::application::interface::NetworkInterface;
 use rayon::prelude::*;
 use std::{
     cmp,
-    collections::HashSet,
+    collections::{HashMap, HashSet, VecDeque},
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -119,6 +123,49 @@ pub(crate) async fn process_client_transaction_submission<V>(
     }
 }
 
+/// Processes a batch of transactions submitted by a client, replying to each of them on its own
+/// callback.
+pub(crate) async fn process_client_transaction_batch_submission<V>(
+    smp: SharedMempool<V>,
+    batch: Vec<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let _timer = counters::process_txn_submit_latency_timer_client();
+    counters::client_submission_batch_size(batch.len());
+    let mut callbacks: HashMap<HashValue, VecDeque<_>> = HashMap::new();
+    let transactions = batch
+        .into_iter()
+        .map(|(transaction, callback)| {
+            callbacks
+                .entry(transaction.clone().committed_hash())
+                .or_default()
+                .push_back(callback);
+            transaction
+        })
+        .collect();
+    // The statuses don't come back in the order of the transactions, match them up by hash.
+    let statuses = process_incoming_transactions(&smp, transactions, TimelineState::NotReady);
+    log_txn_process_results(&statuses, None);
+
+    for (transaction, status) in statuses {
+        let callback = callbacks
+            .get_mut(&transaction.clone().committed_hash())
+            .and_then(VecDeque::pop_front);
+        if let Some(callback) = callback {
+            if callback.send(Ok(status)).is_err() {
+                error!(LogSchema::event_log(
+                    LogEntry::JsonRpc,
+                    LogEvent::CallbackFail
+                ));
+                counters::CLIENT_CALLBACK_FAIL.inc();
+            }
+        }
+    }
+}
+
 /// Processes get transaction by hash request by client.
 pub(crate) async fn process_client_get_transaction<V>(
     smp: SharedMempool<V>,
@@ -141,6 +188,74 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes get pending sequence numbers of an account request by client.
+pub(crate) async fn process_client_get_pending_sequence_numbers<V>(
+    smp: SharedMempool<V>,
+    address: AccountAddress,
+    callback: oneshot::Sender<Vec<u64>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let sequence_numbers = smp.mempool.lock().get_pending_sequence_numbers(&address);
+
+    if callback.send(sequence_numbers).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetPendingSequenceNumbers,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get mempool utilization request by client.
+pub(crate) async fn process_client_get_utilization<V>(
+    smp: SharedMempool<V>,
+    callback: oneshot::Sender<MempoolUtilization>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let utilization = {
+        let mempool = smp.mempool.lock();
+        MempoolUtilization {
+            size: mempool.size(),
+            capacity: mempool.capacity(),
+        }
+    };
+
+    if callback.send(utilization).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetUtilization,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get mempool snapshot request by client.
+pub(crate) async fn process_client_get_snapshot<V>(
+    smp: SharedMempool<V>,
+    callback: oneshot::Sender<Vec<MempoolSnapshotEntry>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let mut snapshot = smp.mempool.lock().snapshot();
+    snapshot.sort_by_key(|entry| (entry.sender, entry.sequence_number));
+
+    if callback.send(snapshot).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetSnapshot,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -232,6 +347,23 @@ where
 {
     let mut statuses = vec![];
 
+    // Reject the transactions of senders not admitted by the admission policy before doing any work
+    // on them.
+    let policy = &smp.config.sender_admission_policy;
+    let (transactions, not_admitted): (Vec<_>, Vec<_>) = transactions
+        .into_iter()
+        .partition(|t| policy.admits(&t.sender()));
+    for t in not_admitted {
+        let status = MempoolStatus::new(MempoolStatusCode::SenderNotAllowed).with_message(format!(
+            "sender {} is not allowed to submit transactions",
+            t.sender()
+        ));
+        statuses.push((t, (status, None)));
+    }
+    if transactions.is_empty() && !statuses.is_empty() {
+        return statuses;
+    }
+
     let start_storage_read = Instant::now();
     // Track latency: fetching seq number
     let seq_numbers = transactions
@@ -329,6 +461,19 @@ fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<Pe
         None => counters::CLIENT_LABEL.to_string(),
     };
     for (txn, (mempool_status, maybe_vm_status)) in results.iter() {
+        if mempool_status.code == MempoolStatusCode::SenderNotAllowed {
+            // Every rejection is logged, as an audit trail of the senders turned away.
+            let mut log = LogSchema::new(LogEntry::SenderNotAllowed)
+                .account(txn.sender())
+                .txns(TxnsLog::new_txn(txn.sender(), txn.sequence_number()));
+            if let Some(peer) = &sender {
+                log = log.peer(peer);
+            }
+            warn!(
+                log,
+                "Rejected transaction of a sender not allowed by the admission policy"
+            );
+        }
         if let Some(vm_status) = maybe_vm_status {
             trace!(
                 SecurityEvent::InvalidTransactionMempool,
@@ -400,6 +545,23 @@ pub(crate) fn process_consensus_request<V: TransactionValidation>(
                 counters::COMMIT_CONSENSUS_LABEL,
                 transactions.len(),
             );
+            let now_usecs = aptos_infallible::duration_since_epoch().as_micros() as u64;
+            for transaction in &transactions {
+                smp.dead_letters.push(DeadLetter {
+                    sender: transaction.sender,
+                    sequence_number: transaction.sequence_number,
+                    hash: transaction.hash,
+                    reason: format!("{:?}", transaction.reason),
+                    timestamp_usecs: now_usecs,
+                });
+            }
+            let transactions = transactions
+                .into_iter()
+                .map(|txn| TransactionSummary {
+                    sender: txn.sender,
+                    sequence_number: txn.sequence_number,
+                })
+                .collect();
             process_committed_transactions(&smp.mempool, transactions, 0, true);
             (
                 ConsensusResponse::CommitResponse(),
@@ -407,6 +569,24 @@ pub(crate) fn process_consensus_request<V: TransactionValidation>(
                 counters::COMMIT_CONSENSUS_LABEL,
             )
         }
+        ConsensusRequest::GetTransactionsRequest(hashes, callback) => {
+            let txns: Vec<_> = {
+                let mempool = smp.mempool.lock();
+                hashes
+                    .iter()
+                    .map(|hash| mempool.get_by_hash(*hash))
+                    .collect()
+            };
+            counters::mempool_service_transactions(
+                counters::GET_TRANSACTIONS_LABEL,
+                txns.iter().flatten().count(),
+            );
+            (
+                ConsensusResponse::GetTransactionsResponse(txns),
+                callback,
+                counters::GET_TRANSACTIONS_LABEL,
+            )
+        }
     };
     // Send back to callback
     let result = if callback.send(Ok(resp)).is_err() {
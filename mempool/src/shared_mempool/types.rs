@@ -3,8 +3,9 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool, network::MempoolNetworkInterface,
-    shared_mempool::network::MempoolNetworkSender,
+    core_mempool::CoreMempool,
+    network::MempoolNetworkInterface,
+    shared_mempool::{network::MempoolNetworkSender, DeadLetterQueue},
 };
 use anyhow::Result;
 use aptos_config::{
@@ -48,6 +49,7 @@ where
     pub db: Arc<dyn DbReader>,
     pub validator: Arc<RwLock<V>>,
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
+    pub dead_letters: DeadLetterQueue,
 }
 
 impl<V: TransactionValidation + 'static> SharedMempool<V> {
@@ -60,6 +62,7 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
         subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
         role: RoleType,
         peer_metadata_storage: Arc<PeerMetadataStorage>,
+        dead_letters: DeadLetterQueue,
     ) -> Self {
         let network_interface = MempoolNetworkInterface::new(
             peer_metadata_storage,
@@ -74,6 +77,7 @@ impl<V: TransactionValidation + 'static> SharedMempool<V> {
             db,
             validator,
             subscribers,
+            dead_letters,
         }
     }
 }
@@ -159,7 +163,14 @@ pub enum ConsensusRequest {
     /// Notifications about *rejected* committed txns.
     RejectNotification(
         // rejected transactions from consensus
-        Vec<TransactionSummary>,
+        Vec<RejectedTransactionSummary>,
+        // callback to respond to
+        oneshot::Sender<Result<ConsensusResponse>>,
+    ),
+    /// Request to look up transactions by hash, to rebuild the payload of a compact proposal.
+    GetTransactionsRequest(
+        // hashes of the requested transactions
+        Vec<HashValue>,
         // callback to respond to
         oneshot::Sender<Result<ConsensusResponse>>,
     ),
@@ -185,6 +196,9 @@ impl fmt::Display for ConsensusRequest {
                 }
                 format!("RejectNotification [rejected_txns: {}]", txns_str)
             }
+            ConsensusRequest::GetTransactionsRequest(hashes, _) => {
+                format!("GetTransactionsRequest [num_txns: {}]", hashes.len())
+            }
         };
         write!(f, "{}", payload)
     }
@@ -195,6 +209,8 @@ pub enum ConsensusResponse {
     /// Block to submit to consensus
     GetBlockResponse(Vec<SignedTransaction>),
     CommitResponse(),
+    /// The requested transactions, in the order of the request, `None` for the ones not in mempool
+    GetTransactionsResponse(Vec<Option<SignedTransaction>>),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -209,13 +225,68 @@ impl fmt::Display for TransactionSummary {
     }
 }
 
+/// A transaction discarded during block execution.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RejectedTransactionSummary {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub hash: HashValue,
+    pub reason: DiscardedVMStatus,
+}
+
+impl fmt::Display for RejectedTransactionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{} ({:?})",
+            self.sender, self.sequence_number, self.reason
+        )
+    }
+}
+
 pub type SubmissionStatus = (MempoolStatus, Option<DiscardedVMStatus>);
 
 pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
+    /// Submits several transactions at once, validating them together and adding them to mempool
+    /// under a single lock. Each transaction gets its status on its own callback.
+    SubmitTransactionBatch(Vec<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    /// Returns the sequence numbers of all transactions of the account currently held in mempool,
+    /// in ascending order.
+    GetPendingSequenceNumbers(AccountAddress, oneshot::Sender<Vec<u64>>),
+    GetUtilization(oneshot::Sender<MempoolUtilization>),
+    /// Lists every transaction held in mempool, ordered by sender and sequence number.
+    GetSnapshot(oneshot::Sender<Vec<MempoolSnapshotEntry>>),
+}
+
+/// How full mempool is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MempoolUtilization {
+    /// The number of transactions in mempool.
+    pub size: usize,
+    /// The number of transactions mempool can hold.
+    pub capacity: usize,
+}
+
+/// A transaction held in mempool.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MempoolSnapshotEntry {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub gas_unit_price: u64,
+    /// The score ordering the transactions ready for a block, higher first.
+    pub ranking_score: u64,
+    /// Whether the transaction waits for an earlier sequence number of its sender before it can
+    /// be included in a block.
+    pub parked: bool,
+    /// When the transaction entered mempool. Unknown for the transactions received from other
+    /// nodes that are not broadcast further.
+    pub insertion_timestamp_usecs: Option<u64>,
+    /// When mempool drops the transaction if it is not committed before.
+    pub expiration_timestamp_secs: u64,
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
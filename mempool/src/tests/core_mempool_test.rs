@@ -124,6 +124,25 @@ fn test_transaction_ordering_only_crsns() {
     }
 }
 
+#[test]
+fn test_transaction_crsn_nonce_window() {
+    let (mut mempool, mut consensus) = setup_mempool();
+
+    // The nonce window of the account is [100, 228).
+    let transactions = add_txns_to_mempool(
+        &mut mempool,
+        vec![
+            TestTransaction::new(1, 100, 1).crsn(100),
+            TestTransaction::new(1, 200, 2).crsn(100),
+            TestTransaction::new(1, 300, 3).crsn(100),
+        ],
+    );
+    assert_eq!(
+        consensus.get_block(&mut mempool, 10),
+        vec![transactions[1].clone(), transactions[0].clone()]
+    );
+}
+
 #[test]
 fn test_transaction_nonblocking_crsns() {
     let (mut mempool, mut consensus) = setup_mempool();
@@ -487,6 +506,70 @@ fn test_capacity() {
     assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_ok());
 }
 
+#[test]
+fn test_update_capacity() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 1;
+    let mut pool = CoreMempool::new(&config);
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 1, 1)).is_err());
+
+    config.mempool.capacity = 2;
+    pool.update_capacity(&config.mempool);
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 1, 1)).is_ok());
+
+    // Shrinking the capacity keeps the transactions already in mempool.
+    config.mempool.capacity = 1;
+    pool.update_capacity(&config.mempool);
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_err());
+    assert_eq!(pool.get_block(2, HashSet::new()).len(), 2);
+}
+
+#[test]
+fn test_get_block_fairness_between_senders() {
+    let mut config = NodeConfig::random();
+    config.mempool.max_block_txns_per_sender = 2;
+    let mut pool = CoreMempool::new(&config);
+    // Account 0 pays a higher gas price than account 1 for all of its transactions.
+    for seq in 0..5 {
+        add_txn(&mut pool, TestTransaction::new(0, seq, 2)).unwrap();
+    }
+    for seq in 0..3 {
+        add_txn(&mut pool, TestTransaction::new(1, seq, 1)).unwrap();
+    }
+    let block_txns = |block: Vec<SignedTransaction>| -> Vec<_> {
+        block
+            .iter()
+            .map(|txn| (txn.sender(), txn.sequence_number()))
+            .collect()
+    };
+    let (sender_0, sender_1) = (
+        TestTransaction::get_address(0),
+        TestTransaction::get_address(1),
+    );
+
+    // Each account gets at most 2 transactions before the other, interleaved.
+    assert_eq!(
+        block_txns(pool.get_block(4, HashSet::new())),
+        vec![(sender_0, 0), (sender_1, 0), (sender_0, 1), (sender_1, 1)]
+    );
+
+    // The remaining space of the block is filled up with the deferred transactions.
+    assert_eq!(
+        block_txns(pool.get_block(10, HashSet::new())),
+        vec![
+            (sender_0, 0),
+            (sender_1, 0),
+            (sender_0, 1),
+            (sender_1, 1),
+            (sender_0, 2),
+            (sender_1, 2),
+            (sender_0, 3),
+            (sender_0, 4),
+        ]
+    );
+}
+
 #[test]
 fn test_parking_lot_eviction() {
     let mut config = NodeConfig::random();
@@ -677,3 +760,83 @@ fn test_get_transaction_by_hash_after_the_txn_is_updated() {
     let txn_by_new_hash = pool.get_by_hash(new_txn_hash);
     assert_eq!(txn_by_new_hash, Some(new_txn));
 }
+
+#[test]
+fn test_get_pending_sequence_numbers() {
+    let mut pool = setup_mempool().0;
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(0, 3, 1),
+            TestTransaction::new(1, 0, 1),
+        ],
+    );
+
+    assert_eq!(
+        pool.get_pending_sequence_numbers(&TestTransaction::get_address(0)),
+        vec![0, 1, 3]
+    );
+    assert!(pool
+        .get_pending_sequence_numbers(&TestTransaction::get_address(2))
+        .is_empty());
+}
+
+#[test]
+fn test_snapshot() {
+    let mut pool = setup_mempool().0;
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 2, 1),
+            TestTransaction::new(1, 0, 5),
+        ],
+    );
+
+    let mut snapshot = pool.snapshot();
+    snapshot.sort_by_key(|entry| (entry.sender, entry.sequence_number));
+    let mut expected = vec![
+        (TestTransaction::get_address(0), 0, 1, false),
+        (TestTransaction::get_address(0), 2, 1, true),
+        (TestTransaction::get_address(1), 0, 5, false),
+    ];
+    expected.sort();
+    assert_eq!(
+        snapshot
+            .iter()
+            .map(|entry| (
+                entry.sender,
+                entry.sequence_number,
+                entry.gas_unit_price,
+                entry.parked
+            ))
+            .collect::<Vec<_>>(),
+        expected
+    );
+    for entry in snapshot {
+        assert_eq!(entry.ranking_score, entry.gas_unit_price);
+        assert!(entry.insertion_timestamp_usecs.is_some());
+    }
+}
+
+#[test]
+fn test_size_and_capacity() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 10;
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(pool.size(), 0);
+    assert_eq!(pool.capacity(), 10);
+
+    // Transactions waiting for an earlier sequence number count towards the size too.
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 2, 1),
+        ],
+    );
+    assert_eq!(pool.size(), 3);
+}
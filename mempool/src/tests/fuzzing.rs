@@ -3,7 +3,8 @@
 
 use crate::{
     core_mempool::{CoreMempool, TimelineState},
-    shared_mempool::{tasks, types::SharedMempool},
+    network::MempoolSyncMsg,
+    shared_mempool::{tasks, types::SharedMempool, DeadLetterQueue},
 };
 use aptos_config::{config::NodeConfig, network_id::NetworkId};
 use aptos_infallible::{Mutex, RwLock};
@@ -29,6 +30,33 @@ pub fn mempool_incoming_transactions_strategy(
     )
 }
 
+pub fn mempool_sync_msg_strategy() -> impl Strategy<Value = MempoolSyncMsg> {
+    prop_oneof![
+        (
+            proptest::collection::vec(any::<u8>(), 0..32),
+            proptest::collection::vec(any::<SignedTransaction>(), 0..10),
+        )
+            .prop_map(|(request_id, transactions)| {
+                MempoolSyncMsg::BroadcastTransactionsRequest {
+                    request_id,
+                    transactions,
+                }
+            }),
+        (
+            proptest::collection::vec(any::<u8>(), 0..32),
+            any::<bool>(),
+            any::<bool>(),
+        )
+            .prop_map(|(request_id, retry, backoff)| {
+                MempoolSyncMsg::BroadcastTransactionsResponse {
+                    request_id,
+                    retry,
+                    backoff,
+                }
+            }),
+    ]
+}
+
 pub fn test_mempool_process_incoming_transactions_impl(
     txns: Vec<SignedTransaction>,
     timeline_state: TimelineState,
@@ -45,6 +73,7 @@ pub fn test_mempool_process_incoming_transactions_impl(
         vec![],
         config.base.role,
         PeerMetadataStorage::new(&[NetworkId::Validator]),
+        DeadLetterQueue::new(config.mempool.dead_letter_queue_capacity),
     );
 
     let _ = tasks::process_incoming_transactions(&smp, txns, timeline_state);
@@ -5,7 +5,7 @@ use crate::{
     core_mempool::{CoreMempool, TimelineState},
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::start_shared_mempool,
-    ConsensusRequest, MempoolClientSender,
+    ConsensusRequest, DeadLetterQueue, MempoolClientSender,
 };
 use anyhow::{format_err, Result};
 use aptos_config::{
@@ -41,6 +41,7 @@ pub struct MockSharedMempool {
     pub mempool: Arc<Mutex<CoreMempool>>,
     pub consensus_sender: mpsc::Sender<ConsensusRequest>,
     pub mempool_notifier: MempoolNotifier,
+    pub dead_letters: DeadLetterQueue,
 }
 
 impl MockSharedMempool {
@@ -48,13 +49,19 @@ impl MockSharedMempool {
     /// Returns the runtime on which the shared mempool is running
     /// and the channel through which shared mempool receives client events.
     pub fn new() -> Self {
+        Self::new_with_config(NodeConfig::random())
+    }
+
+    /// Creates a mock of a running instance of shared mempool started with `config`.
+    pub fn new_with_config(config: NodeConfig) -> Self {
         let runtime = Builder::new_multi_thread()
             .thread_name("mock-shared-mem")
             .enable_all()
             .build()
             .expect("[mock shared mempool] failed to create runtime");
-        let (ac_client, mempool, consensus_sender, mempool_notifier) = Self::start(
+        let (ac_client, mempool, consensus_sender, mempool_notifier, dead_letters) = Self::start(
             runtime.handle(),
+            config,
             &DbReaderWriter::new(MockDbReaderWriter),
             MockVMValidator,
         );
@@ -65,6 +72,7 @@ impl MockSharedMempool {
             mempool,
             consensus_sender,
             mempool_notifier,
+            dead_letters,
         }
     }
 
@@ -75,8 +83,8 @@ impl MockSharedMempool {
         validator: V,
     ) -> Self {
         let handle = Handle::current();
-        let (ac_client, mempool, consensus_sender, mempool_notifier) =
-            Self::start(&handle, db, validator);
+        let (ac_client, mempool, consensus_sender, mempool_notifier, dead_letters) =
+            Self::start(&handle, NodeConfig::random(), db, validator);
         Self {
             _runtime: None,
             _handle: Some(handle),
@@ -84,11 +92,13 @@ impl MockSharedMempool {
             mempool,
             consensus_sender,
             mempool_notifier,
+            dead_letters,
         }
     }
 
     pub fn start<V: TransactionValidation + 'static>(
         handle: &Handle,
+        mut config: NodeConfig,
         db: &DbReaderWriter,
         validator: V,
     ) -> (
@@ -96,8 +106,8 @@ impl MockSharedMempool {
         Arc<Mutex<CoreMempool>>,
         mpsc::Sender<ConsensusRequest>,
         MempoolNotifier,
+        DeadLetterQueue,
     ) {
-        let mut config = NodeConfig::random();
         config.validator_network = Some(NetworkConfig::network_with_id(NetworkId::Validator));
 
         let mempool = Arc::new(Mutex::new(CoreMempool::new(&config)));
@@ -121,6 +131,7 @@ impl MockSharedMempool {
         let reconfig_event_subscriber = event_subscriber.subscribe_to_reconfigurations().unwrap();
         let network_handles = vec![(NetworkId::Validator, network_sender, network_events)];
         let peer_metadata_storage = PeerMetadataStorage::new(&[NetworkId::Validator]);
+        let dead_letters = DeadLetterQueue::new(config.mempool.dead_letter_queue_capacity);
 
         start_shared_mempool(
             handle,
@@ -135,9 +146,16 @@ impl MockSharedMempool {
             Arc::new(RwLock::new(validator)),
             vec![],
             peer_metadata_storage,
+            dead_letters.clone(),
         );
 
-        (ac_client, mempool, consensus_sender, mempool_notifier)
+        (
+            ac_client,
+            mempool,
+            consensus_sender,
+            mempool_notifier,
+            dead_letters,
+        )
     }
 
     pub fn add_txns(&self, txns: Vec<SignedTransaction>) -> Result<()> {
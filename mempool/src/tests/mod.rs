@@ -13,6 +13,10 @@ mod multi_node_test;
 mod node;
 #[cfg(test)]
 mod shared_mempool_test;
+#[cfg(test)]
+mod simulation;
+#[cfg(test)]
+mod simulation_test;
 
 pub mod fuzzing;
 #[cfg(any(feature = "fuzzing", test))]
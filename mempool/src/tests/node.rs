@@ -6,6 +6,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolSyncMsg},
     shared_mempool::{
         network::MempoolNetworkSender, start_shared_mempool, types::SharedMempoolNotification,
+        DeadLetterQueue,
     },
     tests::common::TestTransaction,
 };
@@ -597,6 +598,7 @@ fn start_node_mempool(
         Arc::new(RwLock::new(MockVMValidator)),
         vec![sender],
         peer_metadata_storage,
+        DeadLetterQueue::new(config.mempool.dead_letter_queue_capacity),
     );
 
     (mempool, runtime, subscriber)
@@ -3,11 +3,14 @@
 
 use crate::{
     mocks::MockSharedMempool,
-    shared_mempool::types::TransactionSummary,
+    shared_mempool::types::RejectedTransactionSummary,
     tests::common::{batch_add_signed_txn, TestTransaction},
-    ConsensusRequest,
+    ConsensusRequest, ConsensusResponse, MempoolClientRequest,
+};
+use aptos_config::config::{NodeConfig, SenderAdmissionPolicy};
+use aptos_types::{
+    mempool_status::MempoolStatusCode, transaction::Transaction, vm_status::StatusCode,
 };
-use aptos_types::transaction::Transaction;
 use futures::{channel::oneshot, executor::block_on, sink::SinkExt};
 use mempool_notifications::MempoolNotificationSender;
 use tokio::runtime::Builder;
@@ -34,9 +37,11 @@ fn test_consensus_events_rejected_txns() {
         assert!(batch_add_signed_txn(&mut pool, txns).is_ok());
     }
 
-    let transactions = vec![TransactionSummary {
+    let transactions = vec![RejectedTransactionSummary {
         sender: committed_txn.sender(),
         sequence_number: committed_txn.sequence_number(),
+        hash: committed_txn.clone().committed_hash(),
+        reason: StatusCode::SEQUENCE_NUMBER_TOO_OLD,
     }];
     let (callback, callback_rcv) = oneshot::channel();
     let req = ConsensusRequest::RejectNotification(transactions, callback);
@@ -50,6 +55,39 @@ fn test_consensus_events_rejected_txns() {
     let (timeline, _) = pool.read_timeline(0, 10);
     assert_eq!(timeline.len(), 1);
     assert_eq!(timeline.get(0).unwrap(), &kept_txn);
+
+    // The rejected txn is recorded with the reason of its discard
+    let dead_letters = smp.dead_letters.letters(None);
+    assert_eq!(dead_letters.len(), 1);
+    assert_eq!(dead_letters[0].sender, committed_txn.sender());
+    assert_eq!(dead_letters[0].hash, committed_txn.committed_hash());
+    assert_eq!(dead_letters[0].reason, "SEQUENCE_NUMBER_TOO_OLD");
+    assert!(smp.dead_letters.letters(Some(kept_txn.sender())).is_empty());
+}
+
+#[test]
+fn test_consensus_get_transactions() {
+    let smp = MockSharedMempool::new();
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let missing_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    {
+        let mut pool = smp.mempool.lock();
+        assert!(batch_add_signed_txn(&mut pool, vec![txn.clone()]).is_ok());
+    }
+
+    let hashes = vec![missing_txn.committed_hash(), txn.clone().committed_hash()];
+    let (callback, callback_rcv) = oneshot::channel();
+    let req = ConsensusRequest::GetTransactionsRequest(hashes, callback);
+    let mut consensus_sender = smp.consensus_sender.clone();
+    block_on(async {
+        assert!(consensus_sender.send(req).await.is_ok());
+        match callback_rcv.await.unwrap().unwrap() {
+            ConsensusResponse::GetTransactionsResponse(txns) => {
+                assert_eq!(txns, vec![None, Some(txn)]);
+            }
+            _ => panic!("unexpected response"),
+        }
+    });
 }
 
 #[test]
@@ -93,3 +131,81 @@ fn test_mempool_notify_committed_txns() {
     assert_eq!(timeline.len(), 1);
     assert_eq!(timeline.get(0).unwrap(), &kept_txn);
 }
+
+#[test]
+fn test_sender_admission_policy_rejects_denied_senders() {
+    let denied_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let allowed_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    let mut config = NodeConfig::random();
+    config.mempool.sender_admission_policy = SenderAdmissionPolicy::Denylist {
+        addresses: vec![denied_txn.sender()].into_iter().collect(),
+    };
+    let smp = MockSharedMempool::new_with_config(config);
+
+    let mut client = smp.ac_client.clone();
+    let mut submit = |txn| {
+        let (callback, callback_rcv) = oneshot::channel();
+        block_on(async {
+            client
+                .send(MempoolClientRequest::SubmitTransaction(txn, callback))
+                .await
+                .unwrap();
+            callback_rcv.await.unwrap().unwrap()
+        })
+    };
+    let (status, vm_status) = submit(denied_txn);
+    assert_eq!(status.code, MempoolStatusCode::SenderNotAllowed);
+    assert!(vm_status.is_none());
+    let (status, _) = submit(allowed_txn.clone());
+    assert_eq!(status.code, MempoolStatusCode::Accepted);
+
+    let pool = smp.mempool.lock();
+    let (timeline, _) = pool.read_timeline(0, 10);
+    assert_eq!(timeline, vec![allowed_txn]);
+}
+
+#[test]
+fn test_client_transaction_batch_submission() {
+    let denied_txn = TestTransaction::new(0, 0, 1).make_signed_transaction();
+    let allowed_txn = TestTransaction::new(1, 0, 1).make_signed_transaction();
+    let mut config = NodeConfig::random();
+    config.mempool.sender_admission_policy = SenderAdmissionPolicy::Denylist {
+        addresses: vec![denied_txn.sender()].into_iter().collect(),
+    };
+    let smp = MockSharedMempool::new_with_config(config);
+
+    // The statuses are sent back on the callback of their own transaction, whatever the order in
+    // which mempool processes them.
+    let txns = vec![allowed_txn.clone(), denied_txn, allowed_txn.clone()];
+    let (batch, callbacks): (Vec<_>, Vec<_>) = txns
+        .into_iter()
+        .map(|txn| {
+            let (callback, callback_rcv) = oneshot::channel();
+            ((txn, callback), callback_rcv)
+        })
+        .unzip();
+    let statuses = block_on(async {
+        smp.ac_client
+            .clone()
+            .send(MempoolClientRequest::SubmitTransactionBatch(batch))
+            .await
+            .unwrap();
+        futures::future::join_all(callbacks).await
+    });
+    let codes: Vec<_> = statuses
+        .into_iter()
+        .map(|status| status.unwrap().unwrap().0.code)
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            MempoolStatusCode::Accepted,
+            MempoolStatusCode::SenderNotAllowed,
+            MempoolStatusCode::Accepted,
+        ]
+    );
+
+    let pool = smp.mempool.lock();
+    let (timeline, _) = pool.read_timeline(0, 10);
+    assert_eq!(timeline, vec![allowed_txn]);
+}
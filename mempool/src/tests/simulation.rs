@@ -0,0 +1,274 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic simulation of the mempool of a validator.
+//!
+//! Clients submit transactions through the admission path of shared mempool (sequence numbers
+//! read from storage, VM validation, insertion into core mempool), consensus pulls blocks and
+//! commits them, and mempool periodically garbage collects its transactions. The events run in
+//! the order of their scheduled time, and in the order they were scheduled for equal times,
+//! against a mock clock, so that a scenario always replays the same way.
+
+use crate::{
+    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    shared_mempool::{
+        tasks,
+        types::{SharedMempool, TransactionSummary},
+        DeadLetterQueue,
+    },
+};
+use anyhow::Result;
+use aptos_config::{config::NodeConfig, network_id::NetworkId};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_time_service::{MockTimeService, TimeService, TimeServiceTrait};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::AccountResource,
+    mempool_status::MempoolStatusCode,
+    proof::SparseMerkleProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{SignedTransaction, Version},
+    vm_status::DiscardedVMStatus,
+};
+use move_deps::move_core_types::move_resource::MoveResource;
+use network::application::storage::PeerMetadataStorage;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+use storage_interface::{EventReader, LedgerReader, StateReader};
+use vm_validator::mocks::mock_vm_validator::MockVMValidator;
+
+/// An event of the simulation.
+#[derive(Clone, Debug)]
+pub(crate) enum Event {
+    /// A client submits the transactions.
+    Submit(Vec<SignedTransaction>),
+    /// Consensus pulls a block of at most `max_txns` transactions, excluding the transactions of
+    /// the blocks it pulled and did not commit yet.
+    PullBlock { max_txns: u64 },
+    /// Consensus commits the oldest block it pulled, with the current time as block timestamp.
+    CommitBlock,
+    /// Mempool garbage collects the transactions whose system TTL expired.
+    Gc,
+}
+
+/// What happened at an event of the simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Outcome {
+    /// The status of each submitted transaction.
+    Submitted(Vec<(TxnPointer, MempoolStatusCode, Option<DiscardedVMStatus>)>),
+    /// The transactions of the pulled block.
+    Pulled(Vec<TxnPointer>),
+    /// The transactions of the committed block.
+    Committed(Vec<TxnPointer>),
+    /// The number of transactions left in mempool after garbage collection.
+    Collected { size: usize },
+}
+
+pub(crate) struct MempoolSimulation {
+    time: MockTimeService,
+    db: Arc<SimulatedDb>,
+    smp: SharedMempool<MockVMValidator>,
+    /// The scheduled events, by time and order of scheduling.
+    events: BTreeMap<(Duration, u64), Event>,
+    next_event_id: u64,
+    /// The blocks pulled by consensus and not committed yet, oldest first.
+    pulled_blocks: VecDeque<Vec<TxnPointer>>,
+}
+
+impl MempoolSimulation {
+    pub(crate) fn new(config: &NodeConfig) -> Self {
+        let time = TimeService::mock();
+        let db = Arc::new(SimulatedDb::default());
+        let smp = SharedMempool::new(
+            Arc::new(Mutex::new(
+                CoreMempool::new(config).with_time_service(time.clone()),
+            )),
+            config.mempool.clone(),
+            HashMap::new(),
+            db.clone(),
+            Arc::new(RwLock::new(MockVMValidator)),
+            vec![],
+            config.base.role,
+            PeerMetadataStorage::new(&[NetworkId::Validator]),
+            DeadLetterQueue::new(config.mempool.dead_letter_queue_capacity),
+        );
+        Self {
+            time: time.into_mock(),
+            db,
+            smp,
+            events: BTreeMap::new(),
+            next_event_id: 0,
+            pulled_blocks: VecDeque::new(),
+        }
+    }
+
+    /// Schedules `event` at `at` since the start of the simulation.
+    pub(crate) fn schedule(&mut self, at: Duration, event: Event) -> &mut Self {
+        self.events.insert((at, self.next_event_id), event);
+        self.next_event_id += 1;
+        self
+    }
+
+    /// Schedules `event` every `period`, up to `until`.
+    pub(crate) fn schedule_every(
+        &mut self,
+        period: Duration,
+        until: Duration,
+        event: Event,
+    ) -> &mut Self {
+        let mut at = period;
+        while at <= until {
+            self.schedule(at, event.clone());
+            at += period;
+        }
+        self
+    }
+
+    /// Runs the scheduled events, and returns what happened at each of them, with its time.
+    pub(crate) fn run(&mut self) -> Vec<(Duration, Outcome)> {
+        let mut trace = vec![];
+        while let Some(key) = self.events.keys().next().copied() {
+            let event = self.events.remove(&key).expect("event must exist");
+            let (at, _) = key;
+            let now = self.time.now_unix_time();
+            if at > now {
+                self.time.advance(at - now);
+            }
+            trace.push((at, self.process(event)));
+        }
+        trace
+    }
+
+    /// The number of transactions in mempool.
+    pub(crate) fn mempool_size(&self) -> usize {
+        self.smp.mempool.lock().size()
+    }
+
+    /// The sequence number of `address` in storage, as of the committed blocks.
+    pub(crate) fn committed_sequence_number(&self, address: &AccountAddress) -> u64 {
+        self.db.sequence_number(address)
+    }
+
+    fn process(&mut self, event: Event) -> Outcome {
+        let now = self.time.now_unix_time();
+        match event {
+            Event::Submit(txns) => {
+                let statuses =
+                    tasks::process_incoming_transactions(&self.smp, txns, TimelineState::NotReady);
+                Outcome::Submitted(
+                    statuses
+                        .into_iter()
+                        .map(|(txn, (status, vm_status))| {
+                            (
+                                (txn.sender(), txn.sequence_number()),
+                                status.code,
+                                vm_status,
+                            )
+                        })
+                        .collect(),
+                )
+            }
+            Event::PullBlock { max_txns } => {
+                let exclude: HashSet<_> = self.pulled_blocks.iter().flatten().copied().collect();
+                let block: Vec<_> = {
+                    let mut mempool = self.smp.mempool.lock();
+                    mempool.gc_by_expiration_time(now);
+                    mempool
+                        .get_block(max_txns, exclude)
+                        .iter()
+                        .map(|txn| (txn.sender(), txn.sequence_number()))
+                        .collect()
+                };
+                if !block.is_empty() {
+                    self.pulled_blocks.push_back(block.clone());
+                }
+                Outcome::Pulled(block)
+            }
+            Event::CommitBlock => {
+                let block = self.pulled_blocks.pop_front().unwrap_or_default();
+                for (sender, sequence_number) in &block {
+                    self.db.commit(*sender, *sequence_number);
+                }
+                let summaries = block
+                    .iter()
+                    .map(|(sender, sequence_number)| TransactionSummary {
+                        sender: *sender,
+                        sequence_number: *sequence_number,
+                    })
+                    .collect();
+                tasks::process_committed_transactions(
+                    &self.smp.mempool,
+                    summaries,
+                    now.as_micros() as u64,
+                    false,
+                );
+                Outcome::Committed(block)
+            }
+            Event::Gc => {
+                let mut mempool = self.smp.mempool.lock();
+                mempool.gc();
+                Outcome::Collected {
+                    size: mempool.size(),
+                }
+            }
+        }
+    }
+}
+
+/// Storage holding the sequence numbers of the accounts, advanced by the committed blocks.
+#[derive(Default)]
+struct SimulatedDb {
+    sequence_numbers: Mutex<HashMap<AccountAddress, u64>>,
+}
+
+impl SimulatedDb {
+    fn sequence_number(&self, address: &AccountAddress) -> u64 {
+        self.sequence_numbers
+            .lock()
+            .get(address)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn commit(&self, address: AccountAddress, sequence_number: u64) {
+        let mut sequence_numbers = self.sequence_numbers.lock();
+        let next = sequence_numbers.entry(address).or_insert(0);
+        *next = (*next).max(sequence_number + 1);
+    }
+}
+
+impl LedgerReader for SimulatedDb {
+    fn get_latest_version_option(&self) -> Result<Option<Version>> {
+        // the version is not used by the simulation
+        Ok(Some(1))
+    }
+}
+
+impl StateReader for SimulatedDb {
+    fn get_state_value_with_proof_by_version(
+        &self,
+        state_key: &StateKey,
+        _: Version,
+    ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)> {
+        let value = match state_key {
+            StateKey::AccessPath(access_path)
+                if access_path.path == AccountResource::resource_path() =>
+            {
+                let resource = AccountResource::new(
+                    self.sequence_number(&access_path.address),
+                    vec![],
+                    access_path.address,
+                );
+                Some(StateValue::from(bcs::to_bytes(&resource)?))
+            }
+            _ => None,
+        };
+        // dummy proof which is not used
+        Ok((value, SparseMerkleProof::new(None, vec![])))
+    }
+}
+
+impl EventReader for SimulatedDb {}
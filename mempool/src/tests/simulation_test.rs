@@ -0,0 +1,180 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::tests::{
+    common::TestTransaction,
+    simulation::{Event, MempoolSimulation, Outcome},
+};
+use aptos_config::config::NodeConfig;
+use aptos_types::{mempool_status::MempoolStatusCode, vm_status::DiscardedVMStatus};
+use std::time::Duration;
+
+fn submit(txns: &[TestTransaction]) -> Event {
+    Event::Submit(txns.iter().map(|t| t.make_signed_transaction()).collect())
+}
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn test_sequence_number_gap_is_filled() {
+    let mut sim = MempoolSimulation::new(&NodeConfig::default());
+    let account = TestTransaction::get_address(0);
+    sim.schedule(
+        secs(0),
+        submit(&[TestTransaction::new(0, 0, 1), TestTransaction::new(0, 2, 1)]),
+    )
+    .schedule(secs(1), Event::PullBlock { max_txns: 10 })
+    .schedule(secs(2), Event::CommitBlock)
+    .schedule(secs(3), submit(&[TestTransaction::new(0, 1, 1)]))
+    .schedule(secs(4), Event::PullBlock { max_txns: 10 })
+    .schedule(secs(5), Event::CommitBlock);
+
+    let trace = sim.run();
+    assert_eq!(trace[1], (secs(1), Outcome::Pulled(vec![(account, 0)])));
+    assert_eq!(
+        trace[4],
+        (secs(4), Outcome::Pulled(vec![(account, 1), (account, 2)]))
+    );
+    assert_eq!(sim.committed_sequence_number(&account), 3);
+    assert_eq!(sim.mempool_size(), 0);
+}
+
+#[test]
+fn test_committed_sequence_number_is_rejected() {
+    let mut sim = MempoolSimulation::new(&NodeConfig::default());
+    let account = TestTransaction::get_address(0);
+    sim.schedule(secs(0), submit(&[TestTransaction::new(0, 0, 1)]))
+        .schedule(secs(1), Event::PullBlock { max_txns: 10 })
+        .schedule(secs(1), Event::CommitBlock)
+        .schedule(secs(2), submit(&[TestTransaction::new(0, 0, 1)]));
+
+    let trace = sim.run();
+    assert_eq!(
+        trace[3],
+        (
+            secs(2),
+            Outcome::Submitted(vec![(
+                (account, 0),
+                MempoolStatusCode::VmError,
+                Some(DiscardedVMStatus::SEQUENCE_NUMBER_TOO_OLD)
+            )])
+        )
+    );
+}
+
+#[test]
+fn test_burst_evicts_parked_transactions_when_full() {
+    let mut config = NodeConfig::default();
+    config.mempool.capacity = 2;
+    let mut sim = MempoolSimulation::new(&config);
+    sim.schedule(
+        secs(0),
+        submit(&[TestTransaction::new(0, 0, 1), TestTransaction::new(0, 2, 1)]),
+    )
+    .schedule(secs(0), submit(&[TestTransaction::new(1, 0, 1)]))
+    .schedule(secs(0), submit(&[TestTransaction::new(2, 0, 1)]));
+
+    let codes: Vec<_> = sim
+        .run()
+        .into_iter()
+        .flat_map(|(_, outcome)| match outcome {
+            Outcome::Submitted(statuses) => statuses,
+            outcome => panic!("unexpected outcome {:?}", outcome),
+        })
+        .map(|(txn, code, _)| (txn, code))
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            (
+                (TestTransaction::get_address(0), 0),
+                MempoolStatusCode::Accepted
+            ),
+            (
+                (TestTransaction::get_address(0), 2),
+                MempoolStatusCode::Accepted
+            ),
+            // evicts the parked transaction of the first account
+            (
+                (TestTransaction::get_address(1), 0),
+                MempoolStatusCode::Accepted
+            ),
+            // nothing left to evict
+            (
+                (TestTransaction::get_address(2), 0),
+                MempoolStatusCode::MempoolIsFull
+            ),
+        ]
+    );
+    assert_eq!(sim.mempool_size(), 2);
+}
+
+#[test]
+fn test_system_ttl_expiration() {
+    let mut config = NodeConfig::default();
+    config.mempool.system_transaction_timeout_secs = 10;
+    let mut sim = MempoolSimulation::new(&config);
+    sim.schedule(secs(0), submit(&[TestTransaction::new(0, 0, 1)]))
+        .schedule(secs(5), submit(&[TestTransaction::new(1, 0, 1)]))
+        .schedule_every(secs(4), secs(16), Event::Gc);
+
+    let sizes: Vec<_> = sim
+        .run()
+        .into_iter()
+        .filter_map(|(at, outcome)| match outcome {
+            Outcome::Collected { size } => Some((at, size)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        sizes,
+        vec![(secs(4), 1), (secs(8), 2), (secs(12), 1), (secs(16), 0)]
+    );
+}
+
+#[test]
+fn test_client_expiration_on_pull() {
+    let mut sim = MempoolSimulation::new(&NodeConfig::default());
+    let expiring = TestTransaction::new(0, 0, 1).make_signed_transaction_with_expiration_time(30);
+    sim.schedule(
+        secs(0),
+        Event::Submit(vec![
+            expiring,
+            TestTransaction::new(1, 0, 1).make_signed_transaction(),
+        ]),
+    )
+    .schedule(secs(31), Event::PullBlock { max_txns: 10 });
+
+    let trace = sim.run();
+    assert_eq!(
+        trace[1],
+        (
+            secs(31),
+            Outcome::Pulled(vec![(TestTransaction::get_address(1), 0)])
+        )
+    );
+    assert_eq!(sim.mempool_size(), 1);
+}
+
+#[test]
+fn test_simulation_is_deterministic() {
+    let run = || {
+        let mut config = NodeConfig::default();
+        config.mempool.capacity = 5;
+        config.mempool.system_transaction_timeout_secs = 7;
+        let mut sim = MempoolSimulation::new(&config);
+        for second in 0..10 {
+            let txns: Vec<_> = (0..4)
+                .map(|account| TestTransaction::new(account, second, second % 3 + 1))
+                .collect();
+            sim.schedule(secs(second), submit(&txns));
+        }
+        sim.schedule_every(secs(2), secs(12), Event::PullBlock { max_txns: 3 })
+            .schedule_every(secs(3), secs(12), Event::CommitBlock)
+            .schedule_every(secs(5), secs(15), Event::Gc);
+        sim.run()
+    };
+    assert_eq!(run(), run());
+}
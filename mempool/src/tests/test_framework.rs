@@ -6,7 +6,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolNetworkSender, MempoolSyncMsg},
     shared_mempool::start_shared_mempool,
     tests::common::TestTransaction,
-    ConsensusRequest, MempoolClientRequest, MempoolClientSender,
+    ConsensusRequest, DeadLetterQueue, MempoolClientRequest, MempoolClientSender,
 };
 use aptos_config::{
     config::NodeConfig,
@@ -501,6 +501,7 @@ fn setup_mempool(
         vm_validator,
         vec![sender],
         peer_metadata_storage,
+        DeadLetterQueue::new(config.mempool.dead_letter_queue_capacity),
     );
 
     (
@@ -11,10 +11,11 @@
 //! long as the latter is in its trusted peers set.
 use aptos_config::{
     config::{
-        DiscoveryMethod, NetworkConfig, Peer, PeerRole, PeerSet, RateLimitConfig, RoleType,
-        CONNECTION_BACKOFF_BASE, CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS,
-        MAX_CONNECTION_DELAY_MS, MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS,
-        MAX_INBOUND_CONNECTIONS, NETWORK_CHANNEL_SIZE,
+        CompressionCodec, DiscoveryMethod, NetworkConfig, OutboundQosConfig, PeerSet,
+        RateLimitConfig, RoleType, TransportProtocol, CONNECTION_BACKOFF_BASE,
+        CONNECTIVITY_CHECK_INTERVAL_MS, MAX_CONCURRENT_NETWORK_REQS, MAX_CONNECTION_DELAY_MS,
+        MAX_FRAME_SIZE, MAX_FULLNODE_OUTBOUND_CONNECTIONS, MAX_INBOUND_CONNECTIONS,
+        NETWORK_CHANNEL_SIZE,
     },
     network_id::NetworkContext,
 };
@@ -23,6 +24,7 @@ use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
+use config_notifications::NetworkConfigListener;
 use event_notifications::{EventSubscriptionService, ReconfigNotificationListener};
 use network::{
     application::storage::PeerMetadataStorage,
@@ -82,12 +84,15 @@ impl NetworkBuilder {
         listen_address: NetworkAddress,
         authentication_mode: AuthenticationMode,
         max_frame_size: usize,
+        compression_codecs: &[CompressionCodec],
         enable_proxy_protocol: bool,
+        transport_protocol: TransportProtocol,
         network_channel_size: usize,
         max_concurrent_network_reqs: usize,
         inbound_connection_limit: usize,
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
+        outbound_qos_config: OutboundQosConfig,
     ) -> Self {
         // A network cannot exist without a PeerManager
         // TODO:  construct this in create and pass it to new() as a parameter. The complication is manual construction of NetworkBuilder in various tests.
@@ -102,10 +107,13 @@ impl NetworkBuilder {
             network_channel_size,
             max_concurrent_network_reqs,
             max_frame_size,
+            compression_codecs,
             enable_proxy_protocol,
+            transport_protocol,
             inbound_connection_limit,
             inbound_rate_limit_config,
             outbound_rate_limit_config,
+            outbound_qos_config,
         );
 
         NetworkBuilder {
@@ -142,12 +150,15 @@ impl NetworkBuilder {
             listen_address,
             authentication_mode,
             MAX_FRAME_SIZE,
+            &[],   /* Disable compression */
             false, /* Disable proxy protocol */
+            TransportProtocol::Tcp,
             NETWORK_CHANNEL_SIZE,
             MAX_CONCURRENT_NETWORK_REQS,
             MAX_INBOUND_CONNECTIONS,
             None,
             None,
+            OutboundQosConfig::default(),
         );
 
         builder.add_connectivity_manager(
@@ -196,12 +207,15 @@ impl NetworkBuilder {
             config.listen_address.clone(),
             authentication_mode,
             config.max_frame_size,
+            &config.compression_codecs,
             config.enable_proxy_protocol,
+            config.transport_protocol,
             config.network_channel_size,
             config.max_concurrent_network_reqs,
             config.max_inbound_connections,
             config.inbound_rate_limit_config,
             config.outbound_rate_limit_config,
+            config.outbound_qos_config.clone(),
         );
 
         network_builder.add_connection_monitoring(
@@ -211,7 +225,7 @@ impl NetworkBuilder {
         );
 
         // Always add a connectivity manager to keep track of known peers
-        let seeds = merge_seeds(config);
+        let seeds = config.merge_seeds().expect("Seeds must be well formed");
 
         network_builder.add_connectivity_manager(
             seeds,
@@ -239,6 +253,7 @@ impl NetworkBuilder {
             };
 
             network_builder.add_discovery_change_listener(
+                chain_id,
                 discovery_method,
                 pubkey,
                 reconfig_listener,
@@ -366,8 +381,29 @@ impl NetworkBuilder {
         self
     }
 
+    /// Replaces the seeds of the connectivity manager with the ones of the network config updates
+    /// received on `config_updates`. Does nothing for a network without a connectivity manager.
+    pub fn add_config_update_listener(
+        &mut self,
+        config_updates: NetworkConfigListener,
+    ) -> &mut Self {
+        if let Some(conn_mgr_reqs_tx) = self.conn_mgr_reqs_tx() {
+            let listener = DiscoveryChangeListener::config(
+                self.network_context,
+                conn_mgr_reqs_tx,
+                config_updates,
+            );
+            self.discovery_listeners
+                .as_mut()
+                .expect("Can only add listeners before starting")
+                .push(listener);
+        }
+        self
+    }
+
     fn add_discovery_change_listener(
         &mut self,
+        chain_id: ChainId,
         discovery_method: &DiscoveryMethod,
         pubkey: PublicKey,
         reconfig_events: Option<ReconfigNotificationListener>,
@@ -394,6 +430,13 @@ impl NetworkBuilder {
                 *interval_duration,
                 self.time_service.clone(),
             ),
+            DiscoveryMethod::Dns(config) => DiscoveryChangeListener::dns(
+                self.network_context,
+                conn_mgr_reqs_tx,
+                chain_id,
+                config,
+                self.time_service.clone(),
+            ),
             DiscoveryMethod::None => return,
         };
 
@@ -456,43 +499,3 @@ impl NetworkBuilder {
         EventsT::new(peer_mgr_reqs_rx, connection_notifs_rx)
     }
 }
-
-/// Retrieve and merge seeds so that they have all keys associated
-fn merge_seeds(config: &NetworkConfig) -> PeerSet {
-    config.verify_seeds().expect("Seeds must be well formed");
-    let mut seeds = config.seeds.clone();
-
-    // Merge old seed configuration with new seed configuration
-    // TODO(gnazario): Once fully migrated, remove `seed_addrs`
-    config
-        .seed_addrs
-        .iter()
-        .map(|(peer_id, addrs)| {
-            (
-                peer_id,
-                Peer::from_addrs(PeerRole::ValidatorFullNode, addrs.clone()),
-            )
-        })
-        .for_each(|(peer_id, peer)| {
-            seeds
-                .entry(*peer_id)
-                // Sad clone due to Rust not realizing these are two distinct paths
-                .and_modify(|seed| seed.extend(peer.clone()).unwrap())
-                .or_insert(peer);
-        });
-
-    // Pull public keys out of addresses
-    seeds.values_mut().for_each(
-        |Peer {
-             addresses, keys, ..
-         }| {
-            addresses
-                .iter()
-                .filter_map(NetworkAddress::find_noise_proto)
-                .for_each(|pubkey| {
-                    keys.insert(pubkey);
-                });
-        },
-    );
-    seeds
-}
@@ -0,0 +1,38 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::DiscoveryError;
+use aptos_config::config::PeerSet;
+use config_notifications::NetworkConfigListener;
+use futures::Stream;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The seeds of a network, updated whenever the node config is reloaded.
+pub struct ConfigStream {
+    config_updates: NetworkConfigListener,
+}
+
+impl ConfigStream {
+    pub(crate) fn new(config_updates: NetworkConfigListener) -> Self {
+        ConfigStream { config_updates }
+    }
+}
+
+impl Stream for ConfigStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.config_updates)
+            .poll_next(cx)
+            .map(|maybe_config| {
+                maybe_config.map(|config| {
+                    config
+                        .merge_seeds()
+                        .map_err(|err| DiscoveryError::Parsing(err.to_string()))
+                })
+            })
+    }
+}
@@ -0,0 +1,352 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Seed peers published in the TXT records of a domain, so that operators don't have to keep
+//! static seed lists up to date in their configs.
+//!
+//! Each seed peer is a TXT record `aptos-seed=<hex encoded, BCS serialized SignedSeedRecord>`.
+//! Records must be signed by the configured key, which the DNS infrastructure doesn't have to be
+//! trusted with, and they name the chain and the domain they are published at so that they can't
+//! be replayed at another one. They also expire, and a record of a peer is superseded by the ones
+//! with a higher sequence number, so that stale records can't be replayed either. Other TXT
+//! records of the domain are ignored.
+
+use crate::DiscoveryError;
+use aptos_config::{
+    config::{DnsDiscovery, Peer, PeerRole, PeerSet},
+    network_id::NetworkContext,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    PrivateKey, SigningKey,
+};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use aptos_infallible::Mutex;
+use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::{chain_id::ChainId, network_address::NetworkAddress, PeerId};
+use futures::{stream::BoxStream, Stream, StreamExt};
+use network::logging::NetworkSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use trust_dns_resolver::TokioAsyncResolver;
+
+const SEED_RECORD_PREFIX: &str = "aptos-seed=";
+
+/// A seed peer of the `chain_id` network, published at `domain`.
+#[derive(Clone, Debug, Deserialize, Serialize, CryptoHasher, BCSCryptoHash, PartialEq)]
+pub struct SeedRecord {
+    pub chain_id: ChainId,
+    pub domain: String,
+    /// Records of the peer with a lower sequence number than the highest one seen are rejected.
+    pub seq_num: u64,
+    /// Unix timestamp (in seconds) after which the record is rejected.
+    pub expiration_timestamp_secs: u64,
+    pub peer_id: PeerId,
+    pub role: PeerRole,
+    /// The addresses must include the `/noise-ik/<public key>` of the peer.
+    pub addresses: Vec<NetworkAddress>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SignedSeedRecord {
+    pub record: SeedRecord,
+    pub signature: Ed25519Signature,
+}
+
+impl SeedRecord {
+    /// Returns the TXT record publishing `self`, signed with `key`.
+    pub fn to_txt_record(&self, key: &Ed25519PrivateKey) -> String {
+        let signed_record = SignedSeedRecord {
+            record: self.clone(),
+            signature: key.sign(self),
+        };
+        format!(
+            "{}{}",
+            SEED_RECORD_PREFIX,
+            hex::encode(bcs::to_bytes(&signed_record).expect("BCS serialization can't fail"))
+        )
+    }
+}
+
+pub struct DnsStream {
+    inner: BoxStream<'static, Result<PeerSet, DiscoveryError>>,
+}
+
+impl DnsStream {
+    pub(crate) fn new(
+        network_context: NetworkContext,
+        chain_id: ChainId,
+        config: &DnsDiscovery,
+        time_service: TimeService,
+    ) -> Self {
+        let validator = RecordValidator {
+            chain_id,
+            domain: config.domain.clone(),
+            verification_key: config.verification_key.clone(),
+            time_service: time_service.clone(),
+            seq_nums: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let inner = time_service
+            .interval(Duration::from_secs(config.interval_secs))
+            .then(move |_| resolve_seed_peers(network_context, validator.clone()))
+            .boxed();
+        DnsStream { inner }
+    }
+}
+
+impl Stream for DnsStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+/// Validates the seed records resolved at `domain`, and remembers the highest sequence number seen
+/// for each peer.
+#[derive(Clone)]
+struct RecordValidator {
+    chain_id: ChainId,
+    domain: String,
+    verification_key: Ed25519PublicKey,
+    time_service: TimeService,
+    seq_nums: Arc<Mutex<HashMap<PeerId, u64>>>,
+}
+
+async fn resolve_seed_peers(
+    network_context: NetworkContext,
+    validator: RecordValidator,
+) -> Result<PeerSet, DiscoveryError> {
+    let resolver = TokioAsyncResolver::tokio_from_system_conf()
+        .map_err(|err| DiscoveryError::Dns(err.to_string()))?;
+    let lookup = resolver
+        .txt_lookup(validator.domain.as_str())
+        .await
+        .map_err(|err| DiscoveryError::Dns(err.to_string()))?;
+    // Long records are split into several strings
+    let txt_records = lookup.iter().map(|txt| {
+        txt.txt_data()
+            .iter()
+            .map(|data| String::from_utf8_lossy(data))
+            .collect::<String>()
+    });
+    Ok(validator.parse_seed_records(&network_context, txt_records))
+}
+
+impl RecordValidator {
+    /// Collects the seed peers of the valid records, invalid ones are logged and skipped. Only the
+    /// records with the highest sequence number of each peer are kept.
+    fn parse_seed_records(
+        &self,
+        network_context: &NetworkContext,
+        txt_records: impl Iterator<Item = String>,
+    ) -> PeerSet {
+        let mut records = vec![];
+        for txt_record in txt_records {
+            let encoded_record = match txt_record.strip_prefix(SEED_RECORD_PREFIX) {
+                Some(encoded_record) => encoded_record,
+                None => continue,
+            };
+            match self.parse_seed_record(encoded_record) {
+                Ok(record) => records.push(record),
+                Err(error) => warn!(
+                    NetworkSchema::new(network_context),
+                    "{} Invalid DNS seed record at {}: {:?}", network_context, self.domain, error
+                ),
+            }
+        }
+
+        let mut seq_nums = self.seq_nums.lock();
+        for record in &records {
+            let seq_num = seq_nums.entry(record.peer_id).or_insert(record.seq_num);
+            *seq_num = (*seq_num).max(record.seq_num);
+        }
+        let mut seed_peers = PeerSet::new();
+        for record in records {
+            if record.seq_num < seq_nums[&record.peer_id] {
+                warn!(
+                    NetworkSchema::new(network_context),
+                    "{} Stale DNS seed record at {} for peer {}: sequence number {} < {}",
+                    network_context,
+                    self.domain,
+                    record.peer_id,
+                    record.seq_num,
+                    seq_nums[&record.peer_id]
+                );
+                continue;
+            }
+            let peer = seed_peers
+                .entry(record.peer_id)
+                .or_insert_with(|| Peer::from_addrs(record.role, Vec::new()));
+            peer.keys.extend(
+                record
+                    .addresses
+                    .iter()
+                    .filter_map(NetworkAddress::find_noise_proto),
+            );
+            peer.addresses.extend(record.addresses);
+        }
+        seed_peers
+    }
+
+    fn parse_seed_record(&self, encoded_record: &str) -> Result<SeedRecord, DiscoveryError> {
+        let bytes = hex::decode(encoded_record.trim())
+            .map_err(|err| DiscoveryError::Parsing(err.to_string()))?;
+        let SignedSeedRecord { record, signature } =
+            bcs::from_bytes(&bytes).map_err(|err| DiscoveryError::Parsing(err.to_string()))?;
+        signature
+            .verify(&record, &self.verification_key)
+            .map_err(|err| DiscoveryError::Parsing(err.to_string()))?;
+        if record.chain_id != self.chain_id {
+            return Err(DiscoveryError::Parsing(format!(
+                "record is for chain {}",
+                record.chain_id
+            )));
+        }
+        if record.domain != self.domain {
+            return Err(DiscoveryError::Parsing(format!(
+                "record is for domain {}",
+                record.domain
+            )));
+        }
+        if record.expiration_timestamp_secs <= self.time_service.now_secs() {
+            return Err(DiscoveryError::Parsing(format!(
+                "record expired at {}",
+                record.expiration_timestamp_secs
+            )));
+        }
+        if record.addresses.is_empty()
+            || record
+                .addresses
+                .iter()
+                .any(|addr| addr.find_noise_proto().is_none())
+        {
+            return Err(DiscoveryError::Parsing(
+                "addresses must include the noise public key of the peer".to_string(),
+            ));
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::{test_utils::TEST_SEED, Uniform};
+    use rand::{rngs::StdRng, SeedableRng};
+    use std::str::FromStr;
+
+    const DOMAIN: &str = "seeds.example.com";
+    const EXPIRATION_SECS: u64 = 100;
+
+    fn seed_record(peer_id: PeerId, domain: &str) -> SeedRecord {
+        SeedRecord {
+            chain_id: ChainId::test(),
+            domain: domain.to_string(),
+            seq_num: 1,
+            expiration_timestamp_secs: EXPIRATION_SECS,
+            peer_id,
+            role: PeerRole::Upstream,
+            addresses: vec![NetworkAddress::from_str("/dns/fullnode.example.com/tcp/6182/ln-noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/ln-handshake/0").unwrap()],
+        }
+    }
+
+    fn validator(key: &Ed25519PrivateKey, time_service: TimeService) -> RecordValidator {
+        RecordValidator {
+            chain_id: ChainId::test(),
+            domain: DOMAIN.to_string(),
+            verification_key: key.public_key(),
+            time_service,
+            seq_nums: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn expected_peers(records: &[&SeedRecord]) -> PeerSet {
+        records
+            .iter()
+            .map(|record| {
+                (
+                    record.peer_id,
+                    Peer::from_addrs(record.role, record.addresses.clone()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_seed_records() {
+        let mut rng = StdRng::from_seed(TEST_SEED);
+        let key = Ed25519PrivateKey::generate(&mut rng);
+        let other_key = Ed25519PrivateKey::generate(&mut rng);
+
+        let record = seed_record(PeerId::random(), DOMAIN);
+        let txt_records = vec![
+            // Unrelated records are ignored
+            "v=spf1 -all".to_string(),
+            record.to_txt_record(&key),
+            // Not signed by the configured key
+            seed_record(PeerId::random(), DOMAIN).to_txt_record(&other_key),
+            // Replayed from another domain
+            seed_record(PeerId::random(), "example.org").to_txt_record(&key),
+            // Replayed from another chain
+            SeedRecord {
+                chain_id: ChainId::new(ChainId::test().id() + 1),
+                ..seed_record(PeerId::random(), DOMAIN)
+            }
+            .to_txt_record(&key),
+            // Expired
+            SeedRecord {
+                expiration_timestamp_secs: 0,
+                ..seed_record(PeerId::random(), DOMAIN)
+            }
+            .to_txt_record(&key),
+            // Tampered with
+            format!("{}00", record.to_txt_record(&key)),
+        ];
+
+        let seed_peers = validator(&key, TimeService::mock())
+            .parse_seed_records(&NetworkContext::mock(), txt_records.into_iter());
+        assert_eq!(seed_peers, expected_peers(&[&record]));
+    }
+
+    #[test]
+    fn test_parse_seed_records_rejects_stale_records() {
+        let mut rng = StdRng::from_seed(TEST_SEED);
+        let key = Ed25519PrivateKey::generate(&mut rng);
+        let time_service = TimeService::mock();
+        let validator = validator(&key, time_service.clone());
+        let parse = |records: &[&SeedRecord]| {
+            validator.parse_seed_records(
+                &NetworkContext::mock(),
+                records.iter().map(|record| record.to_txt_record(&key)),
+            )
+        };
+
+        // Only the records with the highest sequence number of a peer are kept
+        let old_record = seed_record(PeerId::random(), DOMAIN);
+        let new_record = SeedRecord {
+            seq_num: 2,
+            addresses: vec![NetworkAddress::from_str("/dns/fullnode2.example.com/tcp/6182/ln-noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/ln-handshake/0").unwrap()],
+            ..old_record.clone()
+        };
+        assert_eq!(
+            parse(&[&old_record, &new_record]),
+            expected_peers(&[&new_record])
+        );
+
+        // A record replayed after being superseded is rejected
+        assert_eq!(parse(&[&old_record]), PeerSet::new());
+        assert_eq!(parse(&[&new_record]), expected_peers(&[&new_record]));
+
+        // Records are rejected once expired
+        time_service.into_mock().advance_secs(EXPIRATION_SECS);
+        assert_eq!(parse(&[&new_record]), PeerSet::new());
+    }
+}
@@ -1,11 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{counters::DISCOVERY_COUNTS, file::FileStream, validator_set::ValidatorSetStream};
-use aptos_config::{config::PeerSet, network_id::NetworkContext};
+use crate::{
+    config::ConfigStream, counters::DISCOVERY_COUNTS, dns::DnsStream, file::FileStream,
+    validator_set::ValidatorSetStream,
+};
+use aptos_config::{
+    config::{DnsDiscovery, PeerSet},
+    network_id::NetworkContext,
+};
 use aptos_crypto::x25519;
 use aptos_logger::prelude::*;
 use aptos_time_service::TimeService;
+use aptos_types::chain_id::ChainId;
+use config_notifications::NetworkConfigListener;
 use event_notifications::ReconfigNotificationListener;
 use futures::{Stream, StreamExt};
 use network::{
@@ -21,7 +29,9 @@ use std::{
 };
 use tokio::runtime::Handle;
 
+mod config;
 mod counters;
+pub mod dns;
 mod file;
 mod validator_set;
 
@@ -29,6 +39,7 @@ mod validator_set;
 pub enum DiscoveryError {
     IO(std::io::Error),
     Parsing(String),
+    Dns(String),
 }
 
 /// A union type for all implementations of `DiscoveryChangeListenerTrait`
@@ -42,6 +53,8 @@ pub struct DiscoveryChangeListener {
 enum DiscoveryChangeStream {
     ValidatorSet(ValidatorSetStream),
     File(FileStream),
+    Dns(DnsStream),
+    Config(ConfigStream),
 }
 
 impl Stream for DiscoveryChangeStream {
@@ -51,6 +64,8 @@ impl Stream for DiscoveryChangeStream {
         match self.get_mut() {
             Self::ValidatorSet(stream) => Pin::new(stream).poll_next(cx),
             Self::File(stream) => Pin::new(stream).poll_next(cx),
+            Self::Dns(stream) => Pin::new(stream).poll_next(cx),
+            Self::Config(stream) => Pin::new(stream).poll_next(cx),
         }
     }
 }
@@ -95,6 +110,42 @@ impl DiscoveryChangeListener {
         }
     }
 
+    /// Periodically resolves the signed seed records published at the configured domain.
+    pub fn dns(
+        network_context: NetworkContext,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        chain_id: ChainId,
+        config: &DnsDiscovery,
+        time_service: TimeService,
+    ) -> Self {
+        let source_stream = DiscoveryChangeStream::Dns(DnsStream::new(
+            network_context,
+            chain_id,
+            config,
+            time_service,
+        ));
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Dns,
+            network_context,
+            update_channel,
+            source_stream,
+        }
+    }
+
+    /// Replaces the seeds from the config with the ones of every reloaded network config.
+    pub fn config(
+        network_context: NetworkContext,
+        update_channel: channel::Sender<ConnectivityRequest>,
+        config_updates: NetworkConfigListener,
+    ) -> Self {
+        DiscoveryChangeListener {
+            discovery_source: DiscoverySource::Config,
+            network_context,
+            update_channel,
+            source_stream: DiscoveryChangeStream::Config(ConfigStream::new(config_updates)),
+        }
+    }
+
     pub fn start(self, executor: &Handle) {
         executor.spawn(Box::pin(self).run());
     }
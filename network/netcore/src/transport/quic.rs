@@ -0,0 +1,484 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! QUIC Transport
+//!
+//! A connection is a single bidirectional QUIC stream, so that the protocols layered on top of it
+//! (Noise and the AptosNet handshake) are the same as over TCP. All the messages of a connection
+//! share that stream, so a lost packet still holds back the data behind it until it is
+//! retransmitted, as over TCP. What QUIC brings is that connections survive address changes of the
+//! dialer. Peers are still authenticated by Noise, so the QUIC TLS certificates are self-signed and
+//! left unverified.
+//!
+//! The connections dialed by a transport share one client endpoint, i.e. one UDP socket, per IP
+//! version, bound on the first dial.
+//!
+//! Addresses keep their `/tcp/<port>` form, the QUIC endpoint listens on the same port over UDP.
+//! A QUIC listener also accepts TCP connections, and dialing falls back to TCP when the peer
+//! doesn't answer over QUIC, so that nodes can enable QUIC one at a time.
+
+use crate::transport::{
+    tcp::{invalid_addr_error, resolve_with_filter, TcpListenerStream, TcpSocket, TcpTransport},
+    Transport,
+};
+use aptos_types::{
+    network_address::{parse_dns_tcp, parse_ip_tcp, NetworkAddress},
+    PeerId,
+};
+use futures::{
+    future::{BoxFuture, FutureExt},
+    io::{AsyncRead, AsyncWrite},
+    stream::{Stream, StreamExt},
+};
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, SystemTime},
+};
+use tokio_util::compat::{Compat, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+/// The name the QUIC TLS certificates are issued for. It is not verified.
+const SERVER_NAME: &str = "aptos";
+/// The ALPN protocol of AptosNet over QUIC.
+const ALPN_PROTOCOL: &[u8] = b"aptosnet";
+/// Idle connections are closed by QUIC, keep them alive like TCP ones.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
+/// How long to wait for a QUIC connection before falling back to TCP.
+pub const QUIC_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Transport to build QUIC connections, falling back to TCP.
+#[derive(Debug, Clone)]
+pub struct QuicTransport {
+    /// The transport to accept connections of TCP-only peers with, and to fall back to.
+    tcp: TcpTransport,
+    /// How long to wait for a QUIC connection before falling back to TCP.
+    connect_timeout: Duration,
+    /// Shared by the clones of the transport.
+    client_endpoints: Arc<Mutex<ClientEndpoints>>,
+}
+
+impl QuicTransport {
+    pub fn new(tcp: TcpTransport, connect_timeout: Duration) -> Self {
+        Self {
+            tcp,
+            connect_timeout,
+            client_endpoints: Arc::new(Mutex::new(ClientEndpoints::default())),
+        }
+    }
+}
+
+impl Default for QuicTransport {
+    fn default() -> Self {
+        Self::new(TcpTransport::default(), QUIC_CONNECT_TIMEOUT)
+    }
+}
+
+impl Transport for QuicTransport {
+    type Output = QuicSocket;
+    type Error = ::std::io::Error;
+    type Listener = QuicListenerStream;
+    type Inbound = BoxFuture<'static, io::Result<QuicSocket>>;
+    type Outbound = BoxFuture<'static, io::Result<QuicSocket>>;
+
+    fn listen_on(
+        &self,
+        addr: NetworkAddress,
+    ) -> Result<(Self::Listener, NetworkAddress), Self::Error> {
+        // Listen over TCP first, so that the QUIC endpoint gets the same port when it is 0.
+        let (tcp, listen_addr) = self.tcp.listen_on(addr)?;
+        let ((ipaddr, port), _addr_suffix) =
+            parse_ip_tcp(listen_addr.as_slice()).ok_or_else(|| invalid_addr_error(&listen_addr))?;
+        let (endpoint, quic) =
+            quinn::Endpoint::server(server_config()?, SocketAddr::new(ipaddr, port))?;
+
+        Ok((
+            QuicListenerStream {
+                _endpoint: endpoint,
+                quic,
+                tcp,
+            },
+            listen_addr,
+        ))
+    }
+
+    fn dial(&self, peer_id: PeerId, addr: NetworkAddress) -> Result<Self::Outbound, Self::Error> {
+        // This also ensures addr is well formed before spawning a dial task.
+        let tcp_outbound = self.tcp.dial(peer_id, addr.clone())?;
+        let connect_timeout = self.connect_timeout;
+        let client_endpoints = self.client_endpoints.clone();
+
+        Ok(async move {
+            match tokio::time::timeout(connect_timeout, connect(client_endpoints, addr)).await {
+                Ok(Ok(socket)) => Ok(socket),
+                // The peer may not support QUIC, or UDP may be blocked on the way.
+                Ok(Err(_)) | Err(_) => tcp_outbound.await.map(QuicSocket::Tcp),
+            }
+        }
+        .boxed())
+    }
+}
+
+fn other_error(error: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error.to_string())
+}
+
+fn transport_config() -> Arc<quinn::TransportConfig> {
+    let mut config = quinn::TransportConfig::default();
+    config.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    Arc::new(config)
+}
+
+fn server_config() -> io::Result<quinn::ServerConfig> {
+    let certificate =
+        rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()]).map_err(other_error)?;
+    let key = rustls::PrivateKey(certificate.serialize_private_key_der());
+    let certificate = rustls::Certificate(certificate.serialize_der().map_err(other_error)?);
+
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(vec![certificate], key)
+        .map_err(other_error)?;
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+
+    let mut config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    config.transport = transport_config();
+    Ok(config)
+}
+
+fn client_config() -> quinn::ClientConfig {
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN_PROTOCOL.to_vec()];
+
+    let mut config = quinn::ClientConfig::new(Arc::new(crypto));
+    config.transport = transport_config();
+    config
+}
+
+/// Accepts any server certificate: the Noise handshake authenticates the peer.
+struct SkipServerVerification;
+
+impl rustls::client::ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+async fn resolve(addr: &NetworkAddress) -> io::Result<SocketAddr> {
+    let protos = addr.as_slice();
+
+    if let Some(((ipaddr, port), _addr_suffix)) = parse_ip_tcp(protos) {
+        Ok(SocketAddr::new(ipaddr, port))
+    } else if let Some(((ip_filter, dns_name, port), _addr_suffix)) = parse_dns_tcp(protos) {
+        resolve_with_filter(ip_filter, dns_name.as_ref(), port)
+            .await?
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "could not resolve dns name to any address: name: {}, ip filter: {:?}",
+                        dns_name.as_ref(),
+                        ip_filter,
+                    ),
+                )
+            })
+    } else {
+        Err(invalid_addr_error(addr))
+    }
+}
+
+/// The endpoints a transport dials from, one per IP version.
+#[derive(Debug, Default)]
+struct ClientEndpoints {
+    ipv4: Option<quinn::Endpoint>,
+    ipv6: Option<quinn::Endpoint>,
+}
+
+impl ClientEndpoints {
+    /// Returns the endpoint to dial `socket_addr` from, binding it if it is the first one.
+    fn get_or_bind(&mut self, socket_addr: &SocketAddr) -> io::Result<quinn::Endpoint> {
+        let (endpoint, bind_addr) = if socket_addr.is_ipv4() {
+            (&mut self.ipv4, SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+        } else {
+            (&mut self.ipv6, SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0)))
+        };
+        if let Some(endpoint) = endpoint.as_ref() {
+            return Ok(endpoint.clone());
+        }
+
+        let mut new_endpoint = quinn::Endpoint::client(bind_addr)?;
+        new_endpoint.set_default_client_config(client_config());
+        *endpoint = Some(new_endpoint.clone());
+        Ok(new_endpoint)
+    }
+}
+
+async fn connect(
+    client_endpoints: Arc<Mutex<ClientEndpoints>>,
+    addr: NetworkAddress,
+) -> io::Result<QuicSocket> {
+    let socket_addr = resolve(&addr).await?;
+    let endpoint = client_endpoints
+        .lock()
+        .map_err(|_| other_error("QUIC client endpoints lock poisoned"))?
+        .get_or_bind(&socket_addr)?;
+
+    let quinn::NewConnection { connection, .. } = endpoint
+        .connect(socket_addr, SERVER_NAME)
+        .map_err(other_error)?
+        .await
+        .map_err(other_error)?;
+    let (send, recv) = connection.open_bi().await.map_err(other_error)?;
+    Ok(QuicSocket::Quic(QuicStream::new(
+        send,
+        recv,
+        connection,
+        Some(endpoint),
+    )))
+}
+
+async fn accept(connecting: quinn::Connecting) -> io::Result<QuicSocket> {
+    let quinn::NewConnection {
+        connection,
+        mut bi_streams,
+        ..
+    } = connecting.await.map_err(other_error)?;
+    // The dialer opens a single stream.
+    let (send, recv) = bi_streams
+        .next()
+        .await
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "QUIC connection closed before opening a stream",
+            )
+        })?
+        .map_err(other_error)?;
+    Ok(QuicSocket::Quic(QuicStream::new(
+        send, recv, connection, None,
+    )))
+}
+
+/// A stream of inbound QUIC and TCP connections.
+#[must_use = "streams do nothing unless polled"]
+pub struct QuicListenerStream {
+    _endpoint: quinn::Endpoint,
+    quic: quinn::Incoming,
+    tcp: TcpListenerStream,
+}
+
+impl Stream for QuicListenerStream {
+    type Item = io::Result<(BoxFuture<'static, io::Result<QuicSocket>>, NetworkAddress)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+        if let Poll::Ready(Some(connecting)) = self.quic.poll_next_unpin(context) {
+            // Note: the dialer address is displayed as a TCP one, with its UDP port.
+            let dialer_addr = NetworkAddress::from(connecting.remote_address());
+            return Poll::Ready(Some(Ok((accept(connecting).boxed(), dialer_addr))));
+        }
+
+        match self.tcp.poll_next_unpin(context) {
+            Poll::Ready(Some(Ok((inbound, dialer_addr)))) => Poll::Ready(Some(Ok((
+                inbound.map(|socket| socket.map(QuicSocket::Tcp)).boxed(),
+                dialer_addr,
+            )))),
+            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A bidirectional QUIC stream.
+#[derive(Debug)]
+pub struct QuicStream {
+    send: Compat<quinn::SendStream>,
+    recv: Compat<quinn::RecvStream>,
+    // Keep the connection, and the endpoint of the dialer, open as long as the stream, even if the
+    // transport is dropped.
+    _connection: quinn::Connection,
+    _endpoint: Option<quinn::Endpoint>,
+}
+
+impl QuicStream {
+    fn new(
+        send: quinn::SendStream,
+        recv: quinn::RecvStream,
+        connection: quinn::Connection,
+        endpoint: Option<quinn::Endpoint>,
+    ) -> Self {
+        Self {
+            send: send.compat_write(),
+            recv: recv.compat(),
+            _connection: connection,
+            _endpoint: endpoint,
+        }
+    }
+}
+
+/// A connection established by [`QuicTransport`], over TCP for the peers that don't support QUIC.
+#[derive(Debug)]
+pub enum QuicSocket {
+    Quic(QuicStream),
+    Tcp(TcpSocket),
+}
+
+impl AsyncRead for QuicSocket {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            QuicSocket::Quic(stream) => Pin::new(&mut stream.recv).poll_read(context, buf),
+            QuicSocket::Tcp(socket) => Pin::new(socket).poll_read(context, buf),
+        }
+    }
+}
+
+impl AsyncWrite for QuicSocket {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            QuicSocket::Quic(stream) => Pin::new(&mut stream.send).poll_write(context, buf),
+            QuicSocket::Tcp(socket) => Pin::new(socket).poll_write(context, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            QuicSocket::Quic(stream) => Pin::new(&mut stream.send).poll_flush(context),
+            QuicSocket::Tcp(socket) => Pin::new(socket).poll_flush(context),
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            QuicSocket::Quic(stream) => Pin::new(&mut stream.send).poll_close(context),
+            QuicSocket::Tcp(socket) => Pin::new(socket).poll_close(context),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transport::{ConnectionOrigin, TransportExt};
+    use futures::{
+        future::join,
+        io::{AsyncReadExt, AsyncWriteExt},
+    };
+
+    async fn exchange(mut socket: QuicSocket, origin: ConnectionOrigin) -> io::Result<QuicSocket> {
+        match origin {
+            ConnectionOrigin::Inbound => {
+                let mut buf = [0; 5];
+                socket.read_exact(&mut buf).await?;
+                assert_eq!(&buf, b"Earth");
+                socket.write_all(b"Air").await?;
+            }
+            ConnectionOrigin::Outbound => {
+                // The dialer writes first, so that the listener sees the QUIC stream.
+                socket.write_all(b"Earth").await?;
+                let mut buf = [0; 3];
+                socket.read_exact(&mut buf).await?;
+                assert_eq!(&buf, b"Air");
+            }
+        }
+        Ok(socket)
+    }
+
+    #[tokio::test]
+    async fn listen_and_dial_over_quic() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::default()
+            .and_then(|socket, _addr, origin| async move { exchange(socket, origin).await });
+
+        let (listener, addr) = t.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())?;
+        let dial = t.dial(PeerId::random(), addr)?;
+        let listener = listener.into_future().then(|(maybe_result, _stream)| {
+            let (incoming, _addr) = maybe_result.unwrap().unwrap();
+            incoming
+        });
+
+        let (outgoing, incoming) = join(dial, listener).await;
+        assert!(matches!(outgoing?, QuicSocket::Quic(_)));
+        assert!(matches!(incoming?, QuicSocket::Quic(_)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dials_share_the_client_endpoint() -> Result<(), ::std::io::Error> {
+        let t = QuicTransport::default()
+            .and_then(|socket, _addr, origin| async move { exchange(socket, origin).await });
+
+        let (listener, addr) = t.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())?;
+        let mut listener = Box::pin(listener);
+        let mut dialer_addrs = vec![];
+        for _ in 0..2 {
+            let dial = t.dial(PeerId::random(), addr.clone())?;
+            let accept = async {
+                let (incoming, _addr) = listener.next().await.unwrap().unwrap();
+                incoming.await
+            };
+
+            let (outgoing, incoming) = join(dial, accept).await;
+            incoming?;
+            match outgoing? {
+                QuicSocket::Quic(stream) => {
+                    dialer_addrs.push(stream._endpoint.unwrap().local_addr()?)
+                }
+                QuicSocket::Tcp(_) => panic!("expected a QUIC connection"),
+            }
+        }
+        assert_eq!(dialer_addrs[0], dialer_addrs[1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dial_falls_back_to_tcp() -> Result<(), ::std::io::Error> {
+        let tcp = TcpTransport::default().and_then(|socket, _addr, origin| async move {
+            exchange(QuicSocket::Tcp(socket), origin).await
+        });
+        let quic = QuicTransport::new(TcpTransport::default(), Duration::from_millis(100))
+            .and_then(|socket, _addr, origin| async move { exchange(socket, origin).await });
+
+        // The listener only supports TCP
+        let (listener, addr) = tcp.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())?;
+        let dial = quic.dial(PeerId::random(), addr)?;
+        let listener = listener.into_future().then(|(maybe_result, _stream)| {
+            let (incoming, _addr) = maybe_result.unwrap().unwrap();
+            incoming
+        });
+
+        let (outgoing, incoming) = join(dial, listener).await;
+        assert!(matches!(outgoing?, QuicSocket::Tcp(_)));
+        assert!(matches!(incoming?, QuicSocket::Tcp(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_multiaddrs() {
+        let t = QuicTransport::default();
+
+        let result = t.dial(PeerId::random(), "/memory/22".parse().unwrap());
+        assert!(result.is_err());
+    }
+}
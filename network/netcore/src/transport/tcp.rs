@@ -134,7 +134,7 @@ impl Transport for TcpTransport {
 }
 
 /// Try to lookup the dns name, then filter addrs according to the `IpFilter`.
-async fn resolve_with_filter(
+pub(crate) async fn resolve_with_filter(
     ip_filter: IpFilter,
     dns_name: &str,
     port: u16,
@@ -218,7 +218,7 @@ async fn connect_via_proxy(proxy_addr: String, addr: NetworkAddress) -> io::Resu
     }
 }
 
-fn invalid_addr_error(addr: &NetworkAddress) -> io::Error {
+pub(crate) fn invalid_addr_error(addr: &NetworkAddress) -> io::Error {
     io::Error::new(
         io::ErrorKind::InvalidInput,
         format!("Invalid NetworkAddress: '{}'", addr),
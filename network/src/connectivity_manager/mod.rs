@@ -131,6 +131,7 @@ pub struct ConnectivityManager<TBackoff> {
 pub enum DiscoverySource {
     OnChainValidatorSet,
     File,
+    Dns,
     Config,
 }
 
@@ -148,6 +149,7 @@ impl fmt::Display for DiscoverySource {
             match self {
                 DiscoverySource::OnChainValidatorSet => "OnChainValidatorSet",
                 DiscoverySource::File => "File",
+                DiscoverySource::Dns => "Dns",
                 DiscoverySource::Config => "Config",
             }
         )
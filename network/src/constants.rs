@@ -13,6 +13,10 @@ pub const INBOUND_RPC_TIMEOUT_MS: u64 = 10_000;
 pub const MAX_CONCURRENT_OUTBOUND_RPCS: u32 = 100;
 /// Limit on concurrent Inbound RPC requests before backpressure is applied
 pub const MAX_CONCURRENT_INBOUND_RPCS: u32 = 100;
+/// Messages with smaller payloads are not worth the CPU cost of compressing them
+pub const COMPRESSION_THRESHOLD_BYTES: usize = 64 * 1024; /* 64 KiB */
+/// Limit on the outbound messages queued for a peer, across all traffic classes
+pub const MAX_QUEUED_OUTBOUND_MESSAGES: usize = 1024;
 
 // These are only used in tests
 // TODO: Fix this so the tests and the defaults in config are the same
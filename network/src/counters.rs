@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::protocols::wire::handshake::v1::ProtocolId;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::{
+    config::{CompressionCodec, TrafficClass},
+    network_id::NetworkContext,
+};
 use aptos_metrics::{
     register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
     Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
@@ -23,6 +26,8 @@ pub const RECEIVED_LABEL: &str = "received";
 pub const SENT_LABEL: &str = "sent";
 pub const SUCCEEDED_LABEL: &str = "succeeded";
 pub const FAILED_LABEL: &str = "failed";
+pub const COMPRESSED_LABEL: &str = "compressed";
+pub const UNCOMPRESSED_LABEL: &str = "uncompressed";
 
 pub static APTOS_CONNECTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -303,6 +308,101 @@ pub fn direct_send_bytes(
     ])
 }
 
+pub static APTOS_NETWORK_COMPRESSION_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_compression_bytes",
+        "Number of bytes of the compressed messages, before and after compression",
+        &[
+            "role_type",
+            "network_id",
+            "peer_id",
+            "remote_peer_id",
+            "direction",
+            "state"
+        ]
+    )
+    .unwrap()
+});
+
+/// The compression ratio with a peer is the ratio of the `COMPRESSED_LABEL` and
+/// `UNCOMPRESSED_LABEL` counters.
+pub fn compression_bytes(
+    network_context: &NetworkContext,
+    remote_peer_id: &PeerId,
+    direction_label: &'static str,
+    state_label: &'static str,
+) -> IntCounter {
+    APTOS_NETWORK_COMPRESSION_BYTES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        remote_peer_id.short_str().as_str(),
+        direction_label,
+        state_label,
+    ])
+}
+
+pub static APTOS_NETWORK_COMPRESSION_TIME: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_network_compression_time_seconds",
+        "Time to compress outbound messages and decompress inbound messages",
+        &[
+            "role_type",
+            "network_id",
+            "peer_id",
+            "remote_peer_id",
+            "direction",
+            "codec"
+        ]
+    )
+    .unwrap()
+});
+
+pub fn compression_time(
+    network_context: &NetworkContext,
+    remote_peer_id: &PeerId,
+    direction_label: &'static str,
+    codec: CompressionCodec,
+) -> Histogram {
+    APTOS_NETWORK_COMPRESSION_TIME.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        remote_peer_id.short_str().as_str(),
+        direction_label,
+        codec.as_str(),
+    ])
+}
+
+pub static APTOS_NETWORK_OUTBOUND_CLASS_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_outbound_class_bytes",
+        "Number of bytes sent to a peer by traffic class",
+        &[
+            "role_type",
+            "network_id",
+            "peer_id",
+            "remote_peer_id",
+            "traffic_class"
+        ]
+    )
+    .unwrap()
+});
+
+pub fn outbound_class_bytes(
+    network_context: &NetworkContext,
+    remote_peer_id: &PeerId,
+    traffic_class: TrafficClass,
+) -> IntCounter {
+    APTOS_NETWORK_OUTBOUND_CLASS_BYTES.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        remote_peer_id.short_str().as_str(),
+        traffic_class.as_str(),
+    ])
+}
+
 /// Counters(queued,dequeued,dropped) related to inbound network notifications for RPCs and
 /// DirectSends.
 pub static PENDING_NETWORK_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
@@ -11,7 +11,10 @@ use crate::{
     testutils::fake_socket::ReadOnlyTestSocketVec,
     transport::{Connection, ConnectionId, ConnectionMetadata},
 };
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{OutboundQosConfig, PeerRole},
+    network_id::NetworkContext,
+};
 use aptos_proptest_helpers::ValueGenerator;
 use aptos_time_service::TimeService;
 use aptos_types::{network_address::NetworkAddress, PeerId};
@@ -108,6 +111,7 @@ pub fn fuzz(data: &[u8]) {
         constants::MAX_FRAME_SIZE,
         None,
         None,
+        OutboundQosConfig::default(),
     );
     executor.spawn(peer.start());
 
@@ -15,9 +15,10 @@
 //! [`PeerManager`]: crate::peer_manager::PeerManager
 
 use crate::{
+    constants,
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
-        RECEIVED_LABEL, SENT_LABEL,
+        COMPRESSED_LABEL, RECEIVED_LABEL, SENT_LABEL, UNCOMPRESSED_LABEL,
     },
     logging::NetworkSchema,
     peer_manager::{PeerManagerError, TransportNotification},
@@ -25,14 +26,18 @@ use crate::{
         direct_send::Message,
         rpc::{InboundRpcRequest, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
         wire::messaging::v1::{
-            DirectSendMsg, ErrorCode, NetworkMessage, NetworkMessageSink, NetworkMessageStream,
-            Priority, ReadError, WriteError,
+            compress, decompress, CompressedMsg, CompressionError, DirectSendMsg, ErrorCode,
+            NetworkMessage, NetworkMessageSink, NetworkMessageStream, Priority, ReadError,
+            WriteError,
         },
     },
     transport::{self, Connection, ConnectionMetadata},
     ProtocolId,
 };
-use aptos_config::network_id::NetworkContext;
+use aptos_config::{
+    config::{CompressionCodec, OutboundQosConfig, TrafficClass},
+    network_id::NetworkContext,
+};
 use aptos_logger::prelude::*;
 use aptos_rate_limiter::rate_limit::SharedBucket;
 use aptos_time_service::{TimeService, TimeServiceTrait};
@@ -42,18 +47,26 @@ use channel::aptos_channel;
 use futures::{
     self,
     channel::oneshot,
+    future::{self, BoxFuture},
     io::{AsyncRead, AsyncWrite},
-    stream::StreamExt,
+    stream::{FusedStream, FuturesOrdered, Stream, StreamExt},
     FutureExt, SinkExt, TryFutureExt,
 };
+use qos::{OutboundMessage, OutboundQueues};
 use serde::Serialize;
 use short_hex_str::AsShortHexStr;
-use std::{fmt, panic, time::Duration};
-use tokio::runtime::Handle;
+use std::{
+    cmp, fmt, panic,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::{runtime::Handle, task::JoinHandle};
 use tokio_util::compat::{
     FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt,
 };
 
+mod qos;
 #[cfg(test)]
 mod test;
 
@@ -137,6 +150,8 @@ pub struct Peer<TSocket> {
     inbound_rate_limiter: Option<SharedBucket>,
     /// Optional outbound rate limiter
     outbound_rate_limiter: Option<SharedBucket>,
+    /// Prioritization and throttling of outbound messages by traffic class
+    outbound_qos_config: OutboundQosConfig,
 }
 
 impl<TSocket> Peer<TSocket>
@@ -157,6 +172,7 @@ where
         max_frame_size: usize,
         inbound_rate_limiter: Option<SharedBucket>,
         outbound_rate_limiter: Option<SharedBucket>,
+        outbound_qos_config: OutboundQosConfig,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -189,6 +205,7 @@ where
             max_frame_size,
             inbound_rate_limiter,
             outbound_rate_limiter,
+            outbound_qos_config,
         }
     }
 
@@ -216,9 +233,13 @@ where
             self.inbound_rate_limiter.clone(),
         )
         .fuse();
+        // Don't send frames larger than the remote accepts.
         let writer = NetworkMessageSink::new(
             write_socket.compat_write(),
-            self.max_frame_size,
+            cmp::min(
+                self.max_frame_size,
+                self.connection_metadata.capabilities.max_frame_size as usize,
+            ),
             self.outbound_rate_limiter.clone(),
         );
 
@@ -231,6 +252,7 @@ where
             self.time_service.clone(),
             self.connection_metadata.clone(),
             self.network_context,
+            self.outbound_qos_config.clone(),
             writer,
         );
 
@@ -302,37 +324,73 @@ where
     // 2. The second channel is used to instruct the task to close the connection and terminate.
     // If outbound messages are queued when the task receives a close instruction, it discards
     // them and immediately closes the connection.
+    // Queued messages are sent by priority of their traffic class, within the limits of the
+    // `OutboundQosConfig`.
     fn start_writer_task(
         executor: &Handle,
         time_service: TimeService,
         connection_metadata: ConnectionMetadata,
         network_context: NetworkContext,
+        outbound_qos_config: OutboundQosConfig,
         mut writer: NetworkMessageSink<impl AsyncWrite + Unpin + Send + 'static>,
     ) -> (
         channel::Sender<(
+            TrafficClass,
             NetworkMessage,
             oneshot::Sender<Result<(), PeerManagerError>>,
         )>,
         oneshot::Sender<()>,
     ) {
         let remote_peer_id = connection_metadata.remote_peer_id;
+        let compression_codec = connection_metadata.capabilities.compression_codec();
         let (write_reqs_tx, mut write_reqs_rx): (
             channel::Sender<(
+                TrafficClass,
                 NetworkMessage,
                 oneshot::Sender<Result<(), PeerManagerError>>,
             )>,
             _,
         ) = channel::new(1024, &counters::PENDING_WIRE_MESSAGES);
         let (close_tx, close_rx) = oneshot::channel();
+        let compression_executor = executor.clone();
         let writer_task = async move {
             let mut close_rx = close_rx.into_stream();
+            let mut queues = OutboundQueues::new(&outbound_qos_config, &remote_peer_id);
+            // Messages are compressed before being queued, so that they are throttled by their
+            // size on the wire.
+            let mut compressor = Compressor::new(
+                compression_executor,
+                network_context,
+                remote_peer_id,
+                compression_codec,
+            );
             loop {
-                futures::select! {
-                    (message, ack_ch) = write_reqs_rx.select_next_some() => {
-                        if let Err(err) = writer
-                            .send(&message)
-                            .map_ok(|_| ack_ch.send(Ok(())))
-                            .await
+                // Queue up all the pending messages, so that the next one is picked by priority.
+                while let Some(Some(Some((traffic_class, message)))) =
+                    compressor.next().now_or_never()
+                {
+                    queues.push(traffic_class, message);
+                }
+                while queues.num_queued() + compressor.num_pending()
+                    < constants::MAX_QUEUED_OUTBOUND_MESSAGES
+                {
+                    match write_reqs_rx.next().now_or_never() {
+                        Some(Some((traffic_class, message, ack_ch))) => {
+                            compressor.push(&mut queues, traffic_class, (message, ack_ch));
+                        }
+                        _ => break,
+                    }
+                }
+
+                let retry_at = match queues.pop() {
+                    Ok((traffic_class, (message, ack_ch))) => {
+                        // Don't keep sending queued messages once asked to close.
+                        if close_rx.next().now_or_never().is_some() {
+                            break;
+                        }
+                        let message_len = message.data_len();
+                        if let Err(err) =
+                            writer.send(&message).map_ok(|_| ack_ch.send(Ok(()))).await
                         {
                             warn!(
                                 NetworkSchema::new(&network_context)
@@ -345,7 +403,34 @@ where
                             );
                             break;
                         }
+                        counters::outbound_class_bytes(
+                            &network_context,
+                            &remote_peer_id,
+                            traffic_class,
+                        )
+                        .inc_by(message_len as u64);
+                        continue;
+                    }
+                    Err(retry_at) => retry_at,
+                };
+
+                // Nothing can be sent until a new message is queued or the throttles refill.
+                let throttled = match retry_at {
+                    Some(instant) => time_service
+                        .sleep(instant.saturating_duration_since(Instant::now()))
+                        .left_future(),
+                    None => future::pending::<()>().right_future(),
+                };
+                futures::select! {
+                    (traffic_class, message, ack_ch) = write_reqs_rx.select_next_some() => {
+                        compressor.push(&mut queues, traffic_class, (message, ack_ch));
                     },
+                    compressed = compressor.select_next_some() => {
+                        if let Some((traffic_class, message)) = compressed {
+                            queues.push(traffic_class, message);
+                        }
+                    },
+                    _ = throttled.fuse() => {},
                     _ = close_rx.select_next_some() => {
                         break;
                     }
@@ -405,6 +490,7 @@ where
         &mut self,
         message: Result<NetworkMessage, ReadError>,
         write_reqs_tx: &mut channel::Sender<(
+            TrafficClass,
             NetworkMessage,
             oneshot::Sender<Result<(), PeerManagerError>>,
         )>,
@@ -430,7 +516,9 @@ where
                     let message = NetworkMessage::Error(error_code);
 
                     let (ack_tx, _) = oneshot::channel();
-                    write_reqs_tx.send((message, ack_tx)).await?;
+                    write_reqs_tx
+                        .send((TrafficClass::Other, message, ack_tx))
+                        .await?;
                     return Err(err.into());
                 }
                 ReadError::IoError(_) => {
@@ -440,6 +528,13 @@ where
                 }
             },
         };
+        let message = match message {
+            NetworkMessage::CompressedMsg(message) => self
+                .decompress_message(message)?
+                .await
+                .map_err(anyhow::Error::from)??,
+            message => message,
+        };
 
         match message {
             NetworkMessage::DirectSendMsg(message) => self.handle_inbound_direct_send(message),
@@ -472,10 +567,35 @@ where
             NetworkMessage::RpcResponse(response) => {
                 self.outbound_rpcs.handle_inbound_response(response)
             }
+            // Nested compressed messages are rejected by `decompress`
+            NetworkMessage::CompressedMsg(_) => {}
         };
         Ok(())
     }
 
+    /// Decompress an inbound CompressedMsg, which may not be larger than our max frame size.
+    /// Only messages above `COMPRESSION_THRESHOLD_BYTES` are compressed, so they are decompressed on
+    /// the blocking pool, like outbound messages are compressed.
+    fn decompress_message(
+        &self,
+        message: CompressedMsg,
+    ) -> Result<JoinHandle<Result<NetworkMessage, CompressionError>>, PeerManagerError> {
+        let codec = message.codec;
+        if !self
+            .connection_metadata
+            .capabilities
+            .supports_compression_codec(codec)
+        {
+            return Err(CompressionError::UnsupportedCodec(codec).into());
+        }
+        let network_context = self.network_context;
+        let remote_peer_id = self.remote_peer_id();
+        let max_frame_size = self.max_frame_size;
+        Ok(self.executor.spawn_blocking(move || {
+            decompress_message(&network_context, &remote_peer_id, message, max_frame_size)
+        }))
+    }
+
     /// Handle an inbound DirectSendMsg from the remote peer. There's not much to
     /// do here other than bump some counters and forward the message up to the
     /// PeerManager.
@@ -517,6 +637,7 @@ where
         &mut self,
         request: PeerRequest,
         write_reqs_tx: &mut channel::Sender<(
+            TrafficClass,
             NetworkMessage,
             oneshot::Sender<Result<(), PeerManagerError>>,
         )>,
@@ -544,7 +665,10 @@ where
                 });
                 let (ack_tx, _ack_rx) = oneshot::channel();
 
-                match write_reqs_tx.send((message, ack_tx)).await {
+                match write_reqs_tx
+                    .send((protocol_id.traffic_class(), message, ack_tx))
+                    .await
+                {
                     Ok(_) => {
                         counters::direct_send_messages(&self.network_context, SENT_LABEL).inc();
                         counters::direct_send_bytes(&self.network_context, SENT_LABEL)
@@ -641,3 +765,179 @@ where
         );
     }
 }
+
+/// Compress an outbound message with `codec`. If that fails or doesn't make it any smaller, the
+/// message is sent uncompressed instead.
+fn compress_message(
+    network_context: &NetworkContext,
+    remote_peer_id: &PeerId,
+    codec: CompressionCodec,
+    message: NetworkMessage,
+) -> NetworkMessage {
+    let timer = counters::compression_time(network_context, remote_peer_id, SENT_LABEL, codec)
+        .start_timer();
+    let result = compress(codec, &message);
+    timer.stop_and_record();
+    match result {
+        Ok(compressed) if compressed.data.len() < message.data_len() => {
+            counters::compression_bytes(
+                network_context,
+                remote_peer_id,
+                SENT_LABEL,
+                UNCOMPRESSED_LABEL,
+            )
+            .inc_by(message.data_len() as u64);
+            counters::compression_bytes(
+                network_context,
+                remote_peer_id,
+                SENT_LABEL,
+                COMPRESSED_LABEL,
+            )
+            .inc_by(compressed.data.len() as u64);
+            NetworkMessage::CompressedMsg(compressed)
+        }
+        Ok(_) => message,
+        Err(err) => {
+            warn!(
+                NetworkSchema::new(network_context).remote_peer(remote_peer_id),
+                error = %err,
+                "{} Failed to compress message to peer: {}, error: {}",
+                network_context,
+                remote_peer_id.short_str(),
+                err
+            );
+            message
+        }
+    }
+}
+
+/// Decompresses an inbound `message` from `remote_peer_id`, which may not be larger than
+/// `max_frame_size` once decompressed.
+fn decompress_message(
+    network_context: &NetworkContext,
+    remote_peer_id: &PeerId,
+    message: CompressedMsg,
+    max_frame_size: usize,
+) -> Result<NetworkMessage, CompressionError> {
+    let timer = counters::compression_time(
+        network_context,
+        remote_peer_id,
+        RECEIVED_LABEL,
+        message.codec,
+    )
+    .start_timer();
+    let decompressed = decompress(&message, max_frame_size)?;
+    timer.stop_and_record();
+    counters::compression_bytes(
+        network_context,
+        remote_peer_id,
+        RECEIVED_LABEL,
+        COMPRESSED_LABEL,
+    )
+    .inc_by(message.data.len() as u64);
+    counters::compression_bytes(
+        network_context,
+        remote_peer_id,
+        RECEIVED_LABEL,
+        UNCOMPRESSED_LABEL,
+    )
+    .inc_by(decompressed.data_len() as u64);
+    Ok(decompressed)
+}
+
+/// Compresses the large outbound messages of a connection on the blocking pool, so that the
+/// writer task keeps sending the other messages to the peer meanwhile.
+///
+/// Consensus messages (and health checks) are latency critical and never compressed. The messages
+/// of the other classes are released in the order they were pushed, i.e. a message waits for the
+/// compression of the messages pushed before it.
+struct Compressor {
+    executor: Handle,
+    network_context: NetworkContext,
+    remote_peer_id: PeerId,
+    codec: Option<CompressionCodec>,
+    /// Resolves to `None` if the compression task failed, the message is then dropped.
+    pending: FuturesOrdered<BoxFuture<'static, Option<(TrafficClass, OutboundMessage)>>>,
+}
+
+impl Compressor {
+    fn new(
+        executor: Handle,
+        network_context: NetworkContext,
+        remote_peer_id: PeerId,
+        codec: Option<CompressionCodec>,
+    ) -> Self {
+        Self {
+            executor,
+            network_context,
+            remote_peer_id,
+            codec,
+            pending: FuturesOrdered::new(),
+        }
+    }
+
+    fn num_pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Queues the message, or holds it until it and the messages pushed before it are compressed.
+    fn push(
+        &mut self,
+        queues: &mut OutboundQueues,
+        traffic_class: TrafficClass,
+        (message, ack_ch): OutboundMessage,
+    ) {
+        let codec = match self.codec {
+            Some(codec) if traffic_class != TrafficClass::Consensus => codec,
+            _ => return queues.push(traffic_class, (message, ack_ch)),
+        };
+        if message.data_len() < constants::COMPRESSION_THRESHOLD_BYTES {
+            if self.pending.is_empty() {
+                queues.push(traffic_class, (message, ack_ch));
+            } else {
+                self.pending
+                    .push(future::ready(Some((traffic_class, (message, ack_ch)))).boxed());
+            }
+            return;
+        }
+
+        let network_context = self.network_context;
+        let remote_peer_id = self.remote_peer_id;
+        let compression = self.executor.spawn_blocking(move || {
+            compress_message(&network_context, &remote_peer_id, codec, message)
+        });
+        self.pending.push(
+            async move {
+                match compression.await {
+                    Ok(message) => Some((traffic_class, (message, ack_ch))),
+                    Err(err) => {
+                        warn!(
+                            NetworkSchema::new(&network_context).remote_peer(&remote_peer_id),
+                            error = %err,
+                            "{} Failed to compress message to peer: {}, error: {}",
+                            network_context,
+                            remote_peer_id.short_str(),
+                            err
+                        );
+                        None
+                    }
+                }
+            }
+            .boxed(),
+        );
+    }
+}
+
+impl Stream for Compressor {
+    type Item = Option<(TrafficClass, OutboundMessage)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.pending.poll_next_unpin(cx)
+    }
+}
+
+impl FusedStream for Compressor {
+    fn is_terminated(&self) -> bool {
+        self.pending.is_terminated()
+    }
+}
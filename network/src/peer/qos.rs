@@ -0,0 +1,240 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! The queues of the messages waiting to be written to a peer.
+//!
+//! Messages are queued by [`TrafficClass`] and always sent by priority of their class, so that a
+//! backlog of e.g. state sync responses can't delay consensus messages. The per peer and per
+//! class byte rates of the [`OutboundQosConfig`] additionally cap the bandwidth used, a class
+//! that is over its rate lets the lower priority classes through.
+
+use crate::{peer_manager::PeerManagerError, protocols::wire::messaging::v1::NetworkMessage};
+use aptos_config::config::{OutboundQosConfig, TrafficClass};
+use aptos_rate_limiter::rate_limit::Bucket;
+use aptos_types::PeerId;
+use futures::channel::oneshot;
+use std::{
+    cmp,
+    collections::{BTreeMap, HashMap, VecDeque},
+    time::Instant,
+};
+
+pub type OutboundMessage = (
+    NetworkMessage,
+    oneshot::Sender<Result<(), PeerManagerError>>,
+);
+
+/// A token bucket refilled with `byte_rate` bytes every second.
+struct Throttle {
+    bucket: Bucket,
+    size: usize,
+}
+
+impl Throttle {
+    fn new(label: &str, remote_peer_id: &PeerId, byte_rate: usize) -> Self {
+        // A full second of traffic can be sent in a burst
+        let size = cmp::max(byte_rate, 1);
+        Self {
+            bucket: Bucket::new(
+                label.to_string(),
+                remote_peer_id.to_string(),
+                label.to_string(),
+                size,
+                size,
+                size,
+                None,
+            ),
+            size,
+        }
+    }
+
+    /// Messages larger than the bucket take all of its tokens instead of being stuck forever.
+    fn tokens_needed(&self, num_bytes: usize) -> usize {
+        cmp::min(num_bytes, self.size)
+    }
+
+    /// Takes the tokens to send `num_bytes`, or returns when they will be available.
+    fn try_acquire(&mut self, num_bytes: usize) -> Result<(), Instant> {
+        let tokens = self.tokens_needed(num_bytes);
+        self.bucket
+            .acquire_all_tokens(tokens)
+            .map_err(|maybe_instant| maybe_instant.unwrap_or_else(Instant::now))
+    }
+
+    fn release(&mut self, num_bytes: usize) {
+        let tokens = self.tokens_needed(num_bytes);
+        self.bucket.return_tokens(tokens);
+    }
+}
+
+pub struct OutboundQueues {
+    /// Ordered by priority, as `TrafficClass` is.
+    queues: BTreeMap<TrafficClass, VecDeque<OutboundMessage>>,
+    class_throttles: HashMap<TrafficClass, Throttle>,
+    peer_throttle: Option<Throttle>,
+    num_queued: usize,
+}
+
+impl OutboundQueues {
+    pub fn new(config: &OutboundQosConfig, remote_peer_id: &PeerId) -> Self {
+        let class_throttles = config
+            .class_byte_rates
+            .iter()
+            .map(|(class, byte_rate)| {
+                let label = format!("outbound-{}", class);
+                (*class, Throttle::new(&label, remote_peer_id, *byte_rate))
+            })
+            .collect();
+        let peer_throttle = config
+            .peer_byte_rate
+            .map(|byte_rate| Throttle::new("outbound-peer", remote_peer_id, byte_rate));
+        Self {
+            queues: BTreeMap::new(),
+            class_throttles,
+            peer_throttle,
+            num_queued: 0,
+        }
+    }
+
+    pub fn num_queued(&self) -> usize {
+        self.num_queued
+    }
+
+    pub fn push(&mut self, traffic_class: TrafficClass, message: OutboundMessage) {
+        self.queues
+            .entry(traffic_class)
+            .or_insert_with(VecDeque::new)
+            .push_back(message);
+        self.num_queued += 1;
+    }
+
+    /// Pops the oldest message of the highest priority class that the throttles let through.
+    /// Otherwise, returns when to try again, or `None` if there are no queued messages.
+    pub fn pop(&mut self) -> Result<(TrafficClass, OutboundMessage), Option<Instant>> {
+        let mut retry_at: Option<Instant> = None;
+        for (traffic_class, queue) in self.queues.iter_mut() {
+            let num_bytes = match queue.front() {
+                Some((message, _)) => message.data_len(),
+                None => continue,
+            };
+            if let Some(throttle) = self.class_throttles.get_mut(traffic_class) {
+                if let Err(instant) = throttle.try_acquire(num_bytes) {
+                    retry_at = Some(retry_at.map_or(instant, |at| cmp::min(at, instant)));
+                    continue;
+                }
+            }
+            if let Some(throttle) = self.peer_throttle.as_mut() {
+                // Nothing else can be sent to the peer until it's refilled either
+                if let Err(instant) = throttle.try_acquire(num_bytes) {
+                    if let Some(throttle) = self.class_throttles.get_mut(traffic_class) {
+                        throttle.release(num_bytes);
+                    }
+                    return Err(Some(retry_at.map_or(instant, |at| cmp::min(at, instant))));
+                }
+            }
+            self.num_queued -= 1;
+            let message = queue.pop_front().expect("The queue can't be empty");
+            return Ok((*traffic_class, message));
+        }
+        Err(retry_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        protocols::wire::messaging::v1::{DirectSendMsg, Priority},
+        ProtocolId,
+    };
+
+    fn message(protocol_id: ProtocolId, len: usize) -> OutboundMessage {
+        let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: Priority::default(),
+            raw_msg: vec![0; len],
+        });
+        (message, oneshot::channel().0)
+    }
+
+    fn pop_protocol(queues: &mut OutboundQueues) -> Option<ProtocolId> {
+        match queues.pop() {
+            Ok((_, (NetworkMessage::DirectSendMsg(message), _))) => Some(message.protocol_id),
+            Ok(_) => panic!("Unexpected message"),
+            Err(_) => None,
+        }
+    }
+
+    fn push(queues: &mut OutboundQueues, protocol_id: ProtocolId, len: usize) {
+        queues.push(protocol_id.traffic_class(), message(protocol_id, len));
+    }
+
+    #[test]
+    fn messages_are_sent_by_priority() {
+        let mut queues = OutboundQueues::new(&OutboundQosConfig::default(), &PeerId::random());
+        push(&mut queues, ProtocolId::MempoolDirectSend, 10);
+        push(&mut queues, ProtocolId::StateSyncDirectSend, 10);
+        push(&mut queues, ProtocolId::ConsensusDirectSendBcs, 10);
+        push(&mut queues, ProtocolId::ConsensusRpcBcs, 10);
+        assert_eq!(queues.num_queued(), 4);
+
+        assert_eq!(
+            pop_protocol(&mut queues),
+            Some(ProtocolId::ConsensusDirectSendBcs)
+        );
+        assert_eq!(pop_protocol(&mut queues), Some(ProtocolId::ConsensusRpcBcs));
+        assert_eq!(
+            pop_protocol(&mut queues),
+            Some(ProtocolId::StateSyncDirectSend)
+        );
+        assert_eq!(
+            pop_protocol(&mut queues),
+            Some(ProtocolId::MempoolDirectSend)
+        );
+        assert_eq!(queues.num_queued(), 0);
+        assert_eq!(queues.pop().unwrap_err(), None);
+    }
+
+    #[test]
+    fn throttled_class_lets_lower_classes_through() {
+        let config = OutboundQosConfig {
+            peer_byte_rate: None,
+            class_byte_rates: [(TrafficClass::StateSync, 100)].iter().cloned().collect(),
+        };
+        let mut queues = OutboundQueues::new(&config, &PeerId::random());
+        push(&mut queues, ProtocolId::StateSyncDirectSend, 100);
+        push(&mut queues, ProtocolId::StateSyncDirectSend, 100);
+        push(&mut queues, ProtocolId::MempoolDirectSend, 100);
+
+        assert_eq!(
+            pop_protocol(&mut queues),
+            Some(ProtocolId::StateSyncDirectSend)
+        );
+        // State sync is out of tokens until the next refill
+        assert_eq!(
+            pop_protocol(&mut queues),
+            Some(ProtocolId::MempoolDirectSend)
+        );
+        assert!(queues.pop().unwrap_err().is_some());
+        assert_eq!(queues.num_queued(), 1);
+    }
+
+    #[test]
+    fn throttled_peer_blocks_all_classes() {
+        let config = OutboundQosConfig {
+            peer_byte_rate: Some(100),
+            class_byte_rates: [(TrafficClass::Mempool, 1000)].iter().cloned().collect(),
+        };
+        let mut queues = OutboundQueues::new(&config, &PeerId::random());
+        // Larger than the bucket, but still sent when it's full
+        push(&mut queues, ProtocolId::ConsensusDirectSendBcs, 1000);
+        push(&mut queues, ProtocolId::MempoolDirectSend, 10);
+
+        assert_eq!(
+            pop_protocol(&mut queues),
+            Some(ProtocolId::ConsensusDirectSendBcs)
+        );
+        assert!(queues.pop().unwrap_err().is_some());
+        assert_eq!(queues.num_queued(), 1);
+    }
+}
@@ -3,8 +3,8 @@
 
 use crate::{
     constants::{
-        INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS, MAX_CONCURRENT_OUTBOUND_RPCS,
-        MAX_FRAME_SIZE, NETWORK_CHANNEL_SIZE,
+        COMPRESSION_THRESHOLD_BYTES, INBOUND_RPC_TIMEOUT_MS, MAX_CONCURRENT_INBOUND_RPCS,
+        MAX_CONCURRENT_OUTBOUND_RPCS, MAX_FRAME_SIZE, NETWORK_CHANNEL_SIZE,
     },
     peer::{DisconnectReason, Peer, PeerNotification, PeerRequest},
     peer_manager::TransportNotification,
@@ -12,17 +12,20 @@ use crate::{
         direct_send::Message,
         rpc::{error::RpcError, InboundRpcRequest, OutboundRpcRequest},
         wire::{
-            handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
+            handshake::v1::{Capabilities, MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
-                DirectSendMsg, NetworkMessage, NetworkMessageSink, NetworkMessageStream,
-                RpcRequest, RpcResponse,
+                compress, decompress, DirectSendMsg, NetworkMessage, NetworkMessageSink,
+                NetworkMessageStream, RpcRequest, RpcResponse,
             },
         },
     },
     transport::{Connection, ConnectionId, ConnectionMetadata},
     ProtocolId,
 };
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{CompressionCodec, OutboundQosConfig, PeerRole},
+    network_id::NetworkContext,
+};
 use aptos_time_service::{MockTimeService, TimeService};
 use aptos_types::{network_address::NetworkAddress, PeerId};
 use bytes::Bytes;
@@ -90,6 +93,7 @@ fn build_test_peer(
         MAX_FRAME_SIZE,
         None,
         None,
+        OutboundQosConfig::default(),
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
@@ -280,6 +284,141 @@ fn peer_recv_message() {
     rt.block_on(future::join3(peer.start(), server, client));
 }
 
+// Large messages should be compressed when a codec was negotiated, and inbound
+// compressed messages should be decompressed.
+#[test]
+fn peer_send_recv_compressed_message() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (mut peer, mut peer_handle, mut connection, _connection_notifs_rx, mut peer_notifs_rx) =
+        build_test_peer(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+        );
+    peer.connection_metadata.capabilities =
+        Capabilities::new(MAX_FRAME_SIZE).with_compression_codecs(&[CompressionCodec::Lz4]);
+    let (mut client_sink, mut client_stream) = build_network_sink_stream(&mut connection);
+
+    let small_data = Bytes::from("hello world");
+    let large_data = Bytes::from(vec![0; COMPRESSION_THRESHOLD_BYTES]);
+    let network_message = |data: &Bytes| {
+        NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id: PROTOCOL,
+            priority: 0,
+            raw_msg: data.to_vec(),
+        })
+    };
+
+    let client = async {
+        // Client should receive the small message as is, and the large one compressed.
+        let msg = client_stream.next().await.unwrap().unwrap();
+        assert_eq!(msg, network_message(&small_data));
+        let msg = match client_stream.next().await.unwrap().unwrap() {
+            NetworkMessage::CompressedMsg(msg) => msg,
+            msg => panic!("Expected a CompressedMsg, received: {:?}", msg),
+        };
+        assert_eq!(msg.codec, CompressionCodec::Lz4);
+        assert!(msg.data.len() < large_data.len());
+        assert_eq!(
+            decompress(&msg, MAX_FRAME_SIZE).unwrap(),
+            network_message(&large_data)
+        );
+
+        // Client then sends a compressed message and closes the connection.
+        let msg = compress(CompressionCodec::Lz4, &network_message(&large_data)).unwrap();
+        client_sink
+            .send(&NetworkMessage::CompressedMsg(msg))
+            .await
+            .unwrap();
+        client_sink.close().await.unwrap();
+    };
+
+    let server = async {
+        peer_handle.send_direct_send(Message {
+            protocol_id: PROTOCOL,
+            mdata: small_data.clone(),
+        });
+        peer_handle.send_direct_send(Message {
+            protocol_id: PROTOCOL,
+            mdata: large_data.clone(),
+        });
+        // Server should be notified of the decompressed message.
+        let received = peer_notifs_rx.next().await.unwrap();
+        assert_eq!(
+            received,
+            PeerNotification::RecvMessage(Message {
+                protocol_id: PROTOCOL,
+                mdata: large_data.clone(),
+            })
+        );
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
+// Consensus messages should never be compressed, and the messages of the other
+// classes should be sent in order even when only some of them are compressed.
+#[test]
+fn peer_send_compressed_message_in_order() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (mut peer, mut peer_handle, mut connection, _connection_notifs_rx, _peer_notifs_rx) =
+        build_test_peer(
+            rt.handle().clone(),
+            TimeService::mock(),
+            ConnectionOrigin::Inbound,
+        );
+    peer.connection_metadata.capabilities =
+        Capabilities::new(MAX_FRAME_SIZE).with_compression_codecs(&[CompressionCodec::Lz4]);
+    let (mut client_sink, mut client_stream) = build_network_sink_stream(&mut connection);
+
+    let small_data = Bytes::from("hello world");
+    let large_data = Bytes::from(vec![0; COMPRESSION_THRESHOLD_BYTES]);
+    let network_message = |protocol_id: ProtocolId, data: &Bytes| {
+        NetworkMessage::DirectSendMsg(DirectSendMsg {
+            protocol_id,
+            priority: 0,
+            raw_msg: data.to_vec(),
+        })
+    };
+
+    let client = async {
+        let mut mempool_msgs = vec![];
+        for _ in 0..3 {
+            match client_stream.next().await.unwrap().unwrap() {
+                NetworkMessage::CompressedMsg(msg) => {
+                    mempool_msgs.push(decompress(&msg, MAX_FRAME_SIZE).unwrap())
+                }
+                NetworkMessage::DirectSendMsg(msg)
+                    if msg.protocol_id == ProtocolId::ConsensusDirectSendBcs =>
+                {
+                    assert_eq!(msg.raw_msg, large_data.to_vec());
+                }
+                msg => mempool_msgs.push(msg),
+            }
+        }
+        assert_eq!(
+            mempool_msgs,
+            vec![
+                network_message(PROTOCOL, &large_data),
+                network_message(PROTOCOL, &small_data),
+            ]
+        );
+        client_sink.close().await.unwrap();
+    };
+
+    let server = async {
+        for (protocol_id, mdata) in [
+            (PROTOCOL, large_data.clone()),
+            (PROTOCOL, small_data.clone()),
+            (ProtocolId::ConsensusDirectSendBcs, large_data.clone()),
+        ] {
+            peer_handle.send_direct_send(Message { protocol_id, mdata });
+        }
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
 // Two connected Peer actors should be able to send/recv a DirectSend from each
 // other and then shutdown gracefully.
 #[test]
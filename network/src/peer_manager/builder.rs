@@ -10,12 +10,18 @@ use crate::{
         conn_notifs_channel, ConnectionRequest, ConnectionRequestSender, PeerManager,
         PeerManagerNotification, PeerManagerRequest, PeerManagerRequestSender,
     },
-    protocols::{network::AppConfig, wire::handshake::v1::ProtocolIdSet},
-    transport::{self, AptosNetTransport, Connection, APTOS_TCP_TRANSPORT},
+    protocols::{
+        network::AppConfig,
+        wire::handshake::v1::{Capabilities, ProtocolIdSet},
+    },
+    transport::{self, aptos_quic_transport, AptosNetTransport, Connection, APTOS_TCP_TRANSPORT},
     ProtocolId,
 };
 use aptos_config::{
-    config::{PeerSet, RateLimitConfig, HANDSHAKE_VERSION},
+    config::{
+        CompressionCodec, OutboundQosConfig, PeerSet, RateLimitConfig, TransportProtocol,
+        HANDSHAKE_VERSION,
+    },
     network_id::NetworkContext,
 };
 use aptos_crypto::x25519;
@@ -28,6 +34,7 @@ use channel::{self, aptos_channel, message_queues::QueueStyle};
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 use netcore::transport::memory::MemoryTransport;
 use netcore::transport::{
+    quic::{QuicSocket, QuicTransport},
     tcp::{TcpSocket, TcpTransport},
     Transport,
 };
@@ -51,9 +58,11 @@ pub enum AuthenticationMode {
 struct TransportContext {
     chain_id: ChainId,
     supported_protocols: ProtocolIdSet,
+    capabilities: Capabilities,
     authentication_mode: AuthenticationMode,
     trusted_peers: Arc<RwLock<PeerSet>>,
     enable_proxy_protocol: bool,
+    transport_protocol: TransportProtocol,
 }
 
 impl TransportContext {
@@ -81,6 +90,7 @@ struct PeerManagerContext {
     inbound_connection_limit: usize,
     inbound_rate_limit_config: Option<RateLimitConfig>,
     outbound_rate_limit_config: Option<RateLimitConfig>,
+    outbound_qos_config: OutboundQosConfig,
 }
 
 impl PeerManagerContext {
@@ -105,6 +115,7 @@ impl PeerManagerContext {
         inbound_connection_limit: usize,
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
+        outbound_qos_config: OutboundQosConfig,
     ) -> Self {
         Self {
             pm_reqs_tx,
@@ -123,6 +134,7 @@ impl PeerManagerContext {
             inbound_connection_limit,
             inbound_rate_limit_config,
             outbound_rate_limit_config,
+            outbound_qos_config,
         }
     }
 
@@ -146,11 +158,13 @@ impl PeerManagerContext {
 type MemoryPeerManager =
     PeerManager<AptosNetTransport<MemoryTransport>, NoiseStream<memsocket::MemorySocket>>;
 type TcpPeerManager = PeerManager<AptosNetTransport<TcpTransport>, NoiseStream<TcpSocket>>;
+type QuicPeerManager = PeerManager<AptosNetTransport<QuicTransport>, NoiseStream<QuicSocket>>;
 
 enum TransportPeerManager {
     #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
     Memory(MemoryPeerManager),
     Tcp(TcpPeerManager),
+    Quic(QuicPeerManager),
 }
 
 pub struct PeerManagerBuilder {
@@ -178,10 +192,13 @@ impl PeerManagerBuilder {
         channel_size: usize,
         max_concurrent_network_reqs: usize,
         max_frame_size: usize,
+        compression_codecs: &[CompressionCodec],
         enable_proxy_protocol: bool,
+        transport_protocol: TransportProtocol,
         inbound_connection_limit: usize,
         inbound_rate_limit_config: Option<RateLimitConfig>,
         outbound_rate_limit_config: Option<RateLimitConfig>,
+        outbound_qos_config: OutboundQosConfig,
     ) -> Self {
         // Setup channel to send requests to peer manager.
         let (pm_reqs_tx, pm_reqs_rx) = aptos_channel::new(
@@ -199,9 +216,12 @@ impl PeerManagerBuilder {
             transport_context: Some(TransportContext {
                 chain_id,
                 supported_protocols: ProtocolIdSet::empty(),
+                capabilities: Capabilities::new(max_frame_size)
+                    .with_compression_codecs(compression_codecs),
                 authentication_mode,
                 trusted_peers: trusted_peers.clone(),
                 enable_proxy_protocol,
+                transport_protocol,
             }),
             peer_manager_context: Some(PeerManagerContext::new(
                 pm_reqs_tx,
@@ -218,6 +238,7 @@ impl PeerManagerBuilder {
                 inbound_connection_limit,
                 inbound_rate_limit_config,
                 outbound_rate_limit_config,
+                outbound_qos_config,
             )),
             peer_manager: None,
             listen_address,
@@ -259,8 +280,10 @@ impl PeerManagerBuilder {
             .expect("PeerManager can only be built once");
 
         let protos = transport_context.supported_protocols;
+        let capabilities = transport_context.capabilities;
         let chain_id = transport_context.chain_id;
         let enable_proxy_protocol = transport_context.enable_proxy_protocol;
+        let transport_protocol = transport_context.transport_protocol;
 
         let (key, auth_mode) = match transport_context.authentication_mode {
             AuthenticationMode::MaybeMutual(key) => (
@@ -274,6 +297,25 @@ impl PeerManagerBuilder {
         };
 
         self.peer_manager = match self.listen_address.as_slice() {
+            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)]
+                if transport_protocol == TransportProtocol::Quic =>
+            {
+                Some(TransportPeerManager::Quic(self.build_with_transport(
+                    AptosNetTransport::new(
+                        aptos_quic_transport(),
+                        self.network_context,
+                        self.time_service.clone(),
+                        key,
+                        auth_mode,
+                        HANDSHAKE_VERSION,
+                        chain_id,
+                        protos,
+                        capabilities,
+                        enable_proxy_protocol,
+                    ),
+                    executor,
+                )))
+            }
             [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => {
                 Some(TransportPeerManager::Tcp(self.build_with_transport(
                     AptosNetTransport::new(
@@ -285,6 +327,7 @@ impl PeerManagerBuilder {
                         HANDSHAKE_VERSION,
                         chain_id,
                         protos,
+                        capabilities,
                         enable_proxy_protocol,
                     ),
                     executor,
@@ -301,6 +344,7 @@ impl PeerManagerBuilder {
                     HANDSHAKE_VERSION,
                     chain_id,
                     protos,
+                    capabilities,
                     enable_proxy_protocol,
                 ),
                 executor,
@@ -360,6 +404,7 @@ impl PeerManagerBuilder {
             pm_context.inbound_connection_limit,
             inbound_rate_limiters,
             outbound_rate_limiters,
+            pm_context.outbound_qos_config,
         );
 
         // PeerManager constructor appends a public key to the listen_address.
@@ -390,6 +435,7 @@ impl PeerManagerBuilder {
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             TransportPeerManager::Memory(pm) => self.start_peer_manager(pm, executor),
             TransportPeerManager::Tcp(pm) => self.start_peer_manager(pm, executor),
+            TransportPeerManager::Quic(pm) => self.start_peer_manager(pm, executor),
         }
     }
 
@@ -42,6 +42,9 @@ pub enum PeerManagerError {
 
     #[error("Error writing to wire: {0}")]
     WireWriteError(#[from] wire::WriteError),
+
+    #[error("Error decompressing message: {0}")]
+    WireCompressionError(#[from] wire::CompressionError),
 }
 
 impl PeerManagerError {
@@ -59,7 +59,7 @@ use crate::{
     peer_manager::transport::{TransportHandler, TransportRequest},
     protocols::network::SerializedRequest,
 };
-use aptos_config::config::{PeerRole, PeerSet};
+use aptos_config::config::{OutboundQosConfig, PeerRole, PeerSet};
 use aptos_infallible::RwLock;
 pub use senders::*;
 pub use types::*;
@@ -126,6 +126,8 @@ where
     inbound_rate_limiters: IpAddrTokenBucketLimiter,
     /// Keyed storage of all outbound rate limiters
     outbound_rate_limiters: IpAddrTokenBucketLimiter,
+    /// Prioritization and throttling of the messages sent to each peer
+    outbound_qos_config: OutboundQosConfig,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -156,6 +158,7 @@ where
         inbound_connection_limit: usize,
         inbound_rate_limiters: IpAddrTokenBucketLimiter,
         outbound_rate_limiters: IpAddrTokenBucketLimiter,
+        outbound_qos_config: OutboundQosConfig,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = channel::new(
             channel_size,
@@ -200,6 +203,7 @@ where
             inbound_connection_limit,
             inbound_rate_limiters,
             outbound_rate_limiters,
+            outbound_qos_config,
         }
     }
 
@@ -686,6 +690,7 @@ where
             self.max_frame_size,
             Some(inbound_rate_limiter),
             Some(outbound_rate_limiter),
+            self.outbound_qos_config.clone(),
         );
         self.executor.spawn(peer.start());
 
@@ -19,7 +19,7 @@ use crate::{
 };
 use anyhow::anyhow;
 use aptos_config::{
-    config::{PeerRole, MAX_INBOUND_CONNECTIONS},
+    config::{OutboundQosConfig, PeerRole, MAX_INBOUND_CONNECTIONS},
     network_id::NetworkContext,
 };
 use aptos_infallible::RwLock;
@@ -114,6 +114,7 @@ fn build_test_peer_manager(
         MAX_INBOUND_CONNECTIONS,
         TokenBucketRateLimiter::open("inbound"),
         TokenBucketRateLimiter::open("outbound"),
+        OutboundQosConfig::default(),
     );
 
     (
@@ -45,6 +45,8 @@ use futures::{
     channel::oneshot,
     stream::{FuturesUnordered, StreamExt},
 };
+#[cfg(any(test, feature = "fuzzing"))]
+use proptest_derive::Arbitrary;
 use rand::{rngs::SmallRng, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use short_hex_str::AsShortHexStr;
@@ -123,15 +125,18 @@ impl ApplicationNetworkSender<HealthCheckerMsg> for HealthCheckerNetworkSender {
     }
 }
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
 pub enum HealthCheckerMsg {
     Ping(Ping),
     Pong(Pong),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
 pub struct Ping(u32);
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
 pub struct Pong(u32);
 
 /// The actor performing health checks by running the Ping protocol
@@ -59,7 +59,7 @@ use crate::{
     ProtocolId,
 };
 use anyhow::anyhow;
-use aptos_config::network_id::NetworkContext;
+use aptos_config::{config::TrafficClass, network_id::NetworkContext};
 use aptos_id_generator::{IdGenerator, U32IdGenerator};
 use aptos_logger::prelude::*;
 use aptos_time_service::{timeout, TimeService, TimeServiceTrait};
@@ -175,7 +175,9 @@ pub struct InboundRpcs {
     remote_peer_id: PeerId,
     /// The core async queue of pending inbound rpc tasks. The tasks are driven
     /// to completion by the `InboundRpcs::next_completed_response()` method.
-    inbound_rpc_tasks: FuturesUnordered<BoxFuture<'static, Result<RpcResponse, RpcError>>>,
+    /// The responses are tagged with the protocol of their request, which they are sent by.
+    inbound_rpc_tasks:
+        FuturesUnordered<BoxFuture<'static, Result<(ProtocolId, RpcResponse), RpcError>>>,
     /// A blanket timeout on all inbound rpc requests. If the application handler
     /// doesn't respond to the request before this timeout, the request will be
     /// dropped.
@@ -258,11 +260,14 @@ impl InboundRpcs {
             .map(move |result| {
                 // Flatten the errors
                 let maybe_response = match result {
-                    Ok(Ok(Ok(response_bytes))) => Ok(RpcResponse {
-                        request_id,
-                        priority,
-                        raw_response: Vec::from(response_bytes.as_ref()),
-                    }),
+                    Ok(Ok(Ok(response_bytes))) => Ok((
+                        protocol_id,
+                        RpcResponse {
+                            request_id,
+                            priority,
+                            raw_response: Vec::from(response_bytes.as_ref()),
+                        },
+                    )),
                     Ok(Ok(Err(err))) => Err(err),
                     Ok(Err(oneshot::Canceled)) => Err(RpcError::UnexpectedResponseChannelCancel),
                     Err(timeout::Elapsed) => Err(RpcError::TimedOut),
@@ -288,7 +293,7 @@ impl InboundRpcs {
     /// `futures::select!`.
     pub fn next_completed_response(
         &mut self,
-    ) -> impl Future<Output = Result<RpcResponse, RpcError>> + FusedFuture + '_ {
+    ) -> impl Future<Output = Result<(ProtocolId, RpcResponse), RpcError>> + FusedFuture + '_ {
         self.inbound_rpc_tasks.select_next_some()
     }
 
@@ -298,13 +303,14 @@ impl InboundRpcs {
     pub async fn send_outbound_response(
         &mut self,
         write_reqs_tx: &mut channel::Sender<(
+            TrafficClass,
             NetworkMessage,
             oneshot::Sender<Result<(), PeerManagerError>>,
         )>,
-        maybe_response: Result<RpcResponse, RpcError>,
+        maybe_response: Result<(ProtocolId, RpcResponse), RpcError>,
     ) -> Result<(), RpcError> {
         let network_context = &self.network_context;
-        let response = match maybe_response {
+        let (protocol_id, response) = match maybe_response {
             Ok(response) => response,
             Err(err) => {
                 counters::rpc_messages(network_context, RESPONSE_LABEL, FAILED_LABEL).inc();
@@ -323,7 +329,9 @@ impl InboundRpcs {
         );
         let message = NetworkMessage::RpcResponse(response);
         let (ack_tx, _) = oneshot::channel();
-        write_reqs_tx.send((message, ack_tx)).await?;
+        write_reqs_tx
+            .send((protocol_id.traffic_class(), message, ack_tx))
+            .await?;
 
         // Collect counters for sent response.
         counters::rpc_messages(network_context, RESPONSE_LABEL, SENT_LABEL).inc();
@@ -385,6 +393,7 @@ impl OutboundRpcs {
         &mut self,
         request: OutboundRpcRequest,
         write_reqs_tx: &mut channel::Sender<(
+            TrafficClass,
             NetworkMessage,
             oneshot::Sender<Result<(), PeerManagerError>>,
         )>,
@@ -439,7 +448,9 @@ impl OutboundRpcs {
             raw_request: Vec::from(request_data.as_ref()),
         });
         let (ack_tx, _) = oneshot::channel();
-        write_reqs_tx.send((message, ack_tx)).await?;
+        write_reqs_tx
+            .send((protocol_id.traffic_class(), message, ack_tx))
+            .await?;
 
         // Collect counters for requests sent.
         counters::rpc_messages(network_context, REQUEST_LABEL, SENT_LABEL).inc();
@@ -10,14 +10,22 @@
 //! supported over that messaging protocol. On receipt, both ends will determine the highest
 //! intersecting messaging protocol version and use that for the remainder of the session.
 //!
+//! Nodes that announce it in their [`ProtocolIdSet`] then exchange their [`Capabilities`], the
+//! optional wire features they support. Older nodes ignore the announcement, so new wire features
+//! can be rolled out incrementally: they are only used on connections where both ends support them.
+//!
 //! [AptosNet Handshake v1 Specification]: https://github.com/aptos-labs/aptos-core/blob/main/specifications/network/handshake-v1.md
 
 use anyhow::anyhow;
-use aptos_config::network_id::NetworkId;
+use aptos_config::{
+    config::{CompressionCodec, TrafficClass},
+    network_id::NetworkId,
+};
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
+    convert::TryFrom,
     fmt,
     iter::{FromIterator, Iterator},
     ops::{BitAnd, BitOr},
@@ -91,6 +99,23 @@ impl ProtocolId {
         ]
     }
 
+    /// The class messages of a given `ProtocolId` are prioritized and throttled by when sent.
+    pub fn traffic_class(self) -> TrafficClass {
+        use ProtocolId::*;
+        match self {
+            // Health checks are kept with consensus, so that a saturated uplink doesn't get the
+            // connection dropped as unhealthy.
+            ConsensusRpcBcs
+            | ConsensusDirectSendBcs
+            | ConsensusDirectSendJson
+            | ConsensusRpcJson
+            | HealthCheckerRpc => TrafficClass::Consensus,
+            StateSyncDirectSend | StorageServiceRpc => TrafficClass::StateSync,
+            MempoolDirectSend | MempoolRpc => TrafficClass::Mempool,
+            DiscoveryDirectSend => TrafficClass::Other,
+        }
+    }
+
     /// How to encode messages for a given `ProtocolId`
     fn encoding(self) -> Encoding {
         match self {
@@ -135,6 +160,10 @@ impl fmt::Display for ProtocolId {
 // ProtocolIdSet
 //
 
+/// Bit of a [`ProtocolIdSet`] announcing that the node exchanges [`Capabilities`] after the
+/// [`HandshakeMsg`]. No [`ProtocolId`] maps to it, so nodes that predate capabilities ignore it.
+const CAPABILITIES_BIT: u8 = u8::MAX;
+
 /// A compact representation for a set of [`ProtocolId`]s. Internally, this is a
 /// bitvec which supports at most 256 bits.
 ///
@@ -190,6 +219,16 @@ impl ProtocolIdSet {
     pub fn insert(&mut self, protocol: ProtocolId) {
         self.0.set(protocol as u8)
     }
+
+    /// Announce that the node exchanges [`Capabilities`] after the [`HandshakeMsg`].
+    pub fn announce_capabilities(&mut self) {
+        self.0.set(CAPABILITIES_BIT)
+    }
+
+    /// Returns if the node exchanges [`Capabilities`] after the [`HandshakeMsg`].
+    pub fn announces_capabilities(&self) -> bool {
+        self.0.is_set(CAPABILITIES_BIT)
+    }
 }
 
 impl FromIterator<ProtocolId> for ProtocolIdSet {
@@ -237,6 +276,156 @@ impl fmt::Display for MessagingProtocolVersion {
     }
 }
 
+//
+// Capabilities
+//
+
+/// The types of the messages of the messaging protocol.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub enum MessageType {
+    Error = 0,
+    RpcRequest = 1,
+    RpcResponse = 2,
+    DirectSendMsg = 3,
+    CompressedMsg = 4,
+}
+
+impl MessageType {
+    /// The message types every node supports, including the ones that predate capabilities.
+    /// [`MessageType::CompressedMsg`] is only supported by the nodes that enable a codec.
+    pub fn baseline() -> &'static [MessageType] {
+        &[
+            MessageType::Error,
+            MessageType::RpcRequest,
+            MessageType::RpcResponse,
+            MessageType::DirectSendMsg,
+        ]
+    }
+}
+
+/// The keys of the [`Capabilities`] on the wire. Keys are never reused.
+const MESSAGE_TYPES_KEY: u8 = 0;
+const COMPRESSION_CODECS_KEY: u8 = 1;
+const MAX_FRAME_SIZE_KEY: u8 = 2;
+
+/// The optional wire features supported by a node. They are exchanged after the [`HandshakeMsg`]
+/// when both ends announce it, and each end only uses the features supported by both.
+///
+/// On the wire, the capabilities are a map from a key to the BCS serialized value of each
+/// capability. Nodes ignore the keys they don't know, and assume the baseline for the keys that
+/// are missing, so that capabilities can be added without breaking older nodes.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "BTreeMap<u8, Vec<u8>>", into = "BTreeMap<u8, Vec<u8>>")]
+pub struct Capabilities {
+    /// The [`MessageType`]s the node understands
+    message_types: bitvec::BitVec,
+    /// The compression codecs the node can decode
+    compression_codecs: bitvec::BitVec,
+    /// The largest frame the node accepts
+    pub max_frame_size: u32,
+}
+
+impl Capabilities {
+    /// The capabilities of this node version, accepting frames up to `max_frame_size`.
+    pub fn new(max_frame_size: usize) -> Self {
+        Self {
+            message_types: MessageType::baseline()
+                .iter()
+                .map(|message_type| *message_type as u8)
+                .collect(),
+            compression_codecs: bitvec::BitVec::default(),
+            max_frame_size: u32::try_from(max_frame_size).unwrap_or(u32::MAX),
+        }
+    }
+
+    /// The capabilities assumed for nodes that do not exchange them. Their max frame size is
+    /// unknown, so it is left to the local one.
+    pub fn baseline() -> Self {
+        Self::new(u32::MAX as usize)
+    }
+
+    /// Advertise that the node can decode messages compressed with any of `codecs`.
+    pub fn with_compression_codecs(mut self, codecs: &[CompressionCodec]) -> Self {
+        for codec in codecs {
+            self.compression_codecs.set(*codec as u8);
+        }
+        if !codecs.is_empty() {
+            self.message_types.set(MessageType::CompressedMsg as u8);
+        }
+        self
+    }
+
+    pub fn supports_message_type(&self, message_type: MessageType) -> bool {
+        self.message_types.is_set(message_type as u8)
+    }
+
+    pub fn supports_compression_codec(&self, codec: CompressionCodec) -> bool {
+        self.compression_codecs.is_set(codec as u8)
+    }
+
+    /// The codec to compress messages with, if any. Zstd is preferred as it compresses better,
+    /// nodes that would rather spend less CPU can enable only lz4.
+    pub fn compression_codec(&self) -> Option<CompressionCodec> {
+        if !self.supports_message_type(MessageType::CompressedMsg) {
+            return None;
+        }
+        [CompressionCodec::Zstd, CompressionCodec::Lz4]
+            .iter()
+            .copied()
+            .find(|codec| self.supports_compression_codec(*codec))
+    }
+
+    /// Returns the capabilities supported by both `self` and `other`.
+    pub fn intersect(&self, other: &Capabilities) -> Capabilities {
+        Capabilities {
+            message_types: self.message_types.bitand(&other.message_types),
+            compression_codecs: self.compression_codecs.bitand(&other.compression_codecs),
+            max_frame_size: std::cmp::min(self.max_frame_size, other.max_frame_size),
+        }
+    }
+}
+
+impl From<Capabilities> for BTreeMap<u8, Vec<u8>> {
+    fn from(capabilities: Capabilities) -> Self {
+        let mut fields = BTreeMap::new();
+        fields.insert(
+            MESSAGE_TYPES_KEY,
+            bcs::to_bytes(&capabilities.message_types).expect("BitVec serialization can't fail"),
+        );
+        fields.insert(
+            COMPRESSION_CODECS_KEY,
+            bcs::to_bytes(&capabilities.compression_codecs)
+                .expect("BitVec serialization can't fail"),
+        );
+        fields.insert(
+            MAX_FRAME_SIZE_KEY,
+            bcs::to_bytes(&capabilities.max_frame_size).expect("u32 serialization can't fail"),
+        );
+        fields
+    }
+}
+
+impl TryFrom<BTreeMap<u8, Vec<u8>>> for Capabilities {
+    type Error = bcs::Error;
+
+    fn try_from(fields: BTreeMap<u8, Vec<u8>>) -> Result<Self, Self::Error> {
+        let mut capabilities = Capabilities::baseline();
+        if let Some(bytes) = fields.get(&MESSAGE_TYPES_KEY) {
+            capabilities.message_types = bcs::from_bytes(bytes)?;
+        }
+        if let Some(bytes) = fields.get(&COMPRESSION_CODECS_KEY) {
+            capabilities.compression_codecs = bcs::from_bytes(bytes)?;
+        }
+        if let Some(bytes) = fields.get(&MAX_FRAME_SIZE_KEY) {
+            capabilities.max_frame_size = bcs::from_bytes(bytes)?;
+        }
+        // Any other key is a capability of a newer node.
+        Ok(capabilities)
+    }
+}
+
 //
 // HandshakeMsg
 //
@@ -309,7 +498,9 @@ impl HandshakeMsg {
         // at least one common ProtocolId.
         for (our_handshake_version, our_protocols) in self.supported_protocols.iter().rev() {
             if let Some(their_protocols) = other.supported_protocols.get(our_handshake_version) {
-                let common_protocols = our_protocols.intersect(their_protocols);
+                // only keep the known protocols, not the capabilities announcement
+                let common_protocols: ProtocolIdSet =
+                    our_protocols.intersect(their_protocols).iter().collect();
 
                 if !common_protocols.is_empty() {
                     return Ok((*our_handshake_version, common_protocols));
@@ -320,6 +511,13 @@ impl HandshakeMsg {
         // no intersection found
         Err(HandshakeError::NoCommonProtocols)
     }
+
+    /// Returns if the node exchanges [`Capabilities`] after the [`HandshakeMsg`].
+    pub fn announces_capabilities(&self) -> bool {
+        self.supported_protocols
+            .values()
+            .any(ProtocolIdSet::announces_capabilities)
+    }
 }
 
 impl fmt::Debug for HandshakeMsg {
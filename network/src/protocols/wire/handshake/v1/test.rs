@@ -182,3 +182,103 @@ fn ignore_unknown_protocols() {
         ProtocolIdSet::empty(),
     );
 }
+
+// Ensure the capabilities announcement is not negotiated as a protocol, so that peers that
+// predate capabilities ignore it.
+#[test]
+fn capabilities_announcement() {
+    let protos = ProtocolIdSet::from_iter([ProtocolId::MempoolDirectSend]);
+    let old_hs = HandshakeMsg::from_supported(protos.clone());
+    let mut announcing_protos = protos.clone();
+    announcing_protos.announce_capabilities();
+    let new_hs = HandshakeMsg::from_supported(announcing_protos.clone());
+
+    assert!(!old_hs.announces_capabilities());
+    assert!(new_hs.announces_capabilities());
+    assert_eq!(
+        ProtocolIdSet::from_iter(announcing_protos.iter()),
+        protos.clone()
+    );
+
+    // The announcement is not part of the common protocols
+    let (_, common_protos) = new_hs.perform_handshake(&new_hs).unwrap();
+    assert_eq!(common_protos, protos);
+    let (_, common_protos) = old_hs.perform_handshake(&new_hs).unwrap();
+    assert_eq!(common_protos, protos);
+
+    // The announcement alone is not a common protocol
+    let mut only_announcing_protos = ProtocolIdSet::empty();
+    only_announcing_protos.announce_capabilities();
+    let only_announcing_hs = HandshakeMsg::from_supported(only_announcing_protos);
+    assert_eq!(
+        new_hs.perform_handshake(&only_announcing_hs).unwrap_err(),
+        HandshakeError::NoCommonProtocols,
+    );
+}
+
+#[test]
+fn intersect_capabilities() {
+    let small = Capabilities::new(1024);
+    let large = Capabilities::new(4096);
+    assert_eq!(small.intersect(&large), small);
+    assert_eq!(large.intersect(&small), small);
+    assert_eq!(large.intersect(&Capabilities::baseline()), large);
+    for message_type in MessageType::baseline() {
+        assert!(small.intersect(&large).supports_message_type(*message_type));
+    }
+}
+
+#[test]
+fn capabilities_serde() {
+    let capabilities = Capabilities::new(1024).with_compression_codecs(&[CompressionCodec::Lz4]);
+    let bytes = bcs::to_bytes(&capabilities).unwrap();
+    assert_eq!(
+        bcs::from_bytes::<Capabilities>(&bytes).unwrap(),
+        capabilities
+    );
+}
+
+// Ensure the capabilities of a newer node, with capabilities unknown to this one, still parse.
+#[test]
+fn parse_extended_capabilities() {
+    let capabilities = Capabilities::new(1024).with_compression_codecs(&[CompressionCodec::Lz4]);
+    let mut fields = BTreeMap::<u8, Vec<u8>>::from(capabilities.clone());
+    fields.insert(u8::MAX, bcs::to_bytes(&(42u64, "new capability")).unwrap());
+    let bytes = bcs::to_bytes(&fields).unwrap();
+
+    assert_eq!(
+        bcs::from_bytes::<Capabilities>(&bytes).unwrap(),
+        capabilities
+    );
+
+    // The capabilities missing from the message are the baseline ones.
+    fields.remove(&MAX_FRAME_SIZE_KEY);
+    let bytes = bcs::to_bytes(&fields).unwrap();
+    let parsed = bcs::from_bytes::<Capabilities>(&bytes).unwrap();
+    assert_eq!(parsed.max_frame_size, u32::MAX);
+    assert_eq!(parsed.compression_codec(), Some(CompressionCodec::Lz4));
+}
+
+#[test]
+fn negotiate_compression_codec() {
+    let no_codecs = Capabilities::new(1024);
+    let lz4 = Capabilities::new(1024).with_compression_codecs(&[CompressionCodec::Lz4]);
+    let all = Capabilities::new(1024).with_compression_codecs(CompressionCodec::all());
+    assert!(!no_codecs.supports_message_type(MessageType::CompressedMsg));
+    assert!(lz4.supports_message_type(MessageType::CompressedMsg));
+
+    assert_eq!(no_codecs.compression_codec(), None);
+    assert_eq!(all.intersect(&no_codecs).compression_codec(), None);
+    assert_eq!(
+        all.intersect(&Capabilities::baseline()).compression_codec(),
+        None
+    );
+    assert_eq!(
+        all.intersect(&lz4).compression_codec(),
+        Some(CompressionCodec::Lz4)
+    );
+    assert_eq!(
+        all.intersect(&all).compression_codec(),
+        Some(CompressionCodec::Zstd)
+    );
+}
@@ -0,0 +1,97 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compression of [`NetworkMessage`]s, so that large payloads (e.g., state chunks or block
+//! payloads) take less bandwidth. The message is serialized and compressed into a
+//! [`CompressedMsg`], which is sent in its place.
+
+use super::{CompressedMsg, NetworkMessage};
+use aptos_config::config::CompressionCodec;
+use std::convert::TryInto;
+use thiserror::Error;
+
+/// The size of the little endian `u32` lz4 prepends to the compressed data.
+const LZ4_SIZE_PREFIX_LEN: usize = 4;
+
+/// Errors from compressing and decompressing network messages.
+#[derive(Debug, Error)]
+pub enum CompressionError {
+    #[error("network message compression: failed to serialize network message: {0}")]
+    SerializeError(#[source] bcs::Error),
+
+    #[error("network message compression: failed to deserialize network message: {0}")]
+    DeserializeError(#[source] bcs::Error),
+
+    #[error("network message compression: {0} error: {1}")]
+    CodecError(CompressionCodec, String),
+
+    #[error("network message compression: {0} was not negotiated with the peer")]
+    UnsupportedCodec(CompressionCodec),
+
+    #[error("network message compression: decompressed message is larger than {0} bytes")]
+    TooLarge(usize),
+
+    #[error("network message compression: compressed messages cannot be nested")]
+    Nested,
+}
+
+/// Compresses `message` with `codec`.
+pub fn compress(
+    codec: CompressionCodec,
+    message: &NetworkMessage,
+) -> Result<CompressedMsg, CompressionError> {
+    if let NetworkMessage::CompressedMsg(_) = message {
+        return Err(CompressionError::Nested);
+    }
+    let bytes = bcs::to_bytes(message).map_err(CompressionError::SerializeError)?;
+    let data = match codec {
+        CompressionCodec::Lz4 => lz4_flex::compress_prepend_size(&bytes),
+        CompressionCodec::Zstd => zstd::bulk::compress(&bytes, zstd::DEFAULT_COMPRESSION_LEVEL)
+            .map_err(|err| CompressionError::CodecError(codec, err.to_string()))?,
+    };
+    Ok(CompressedMsg { codec, data })
+}
+
+/// Decompresses `message`. Decompressed messages larger than `max_size` are rejected before being
+/// allocated, so that a small frame can't expand into a huge one.
+pub fn decompress(
+    message: &CompressedMsg,
+    max_size: usize,
+) -> Result<NetworkMessage, CompressionError> {
+    let codec = message.codec;
+    let bytes = match codec {
+        CompressionCodec::Lz4 => {
+            if message.data.len() < LZ4_SIZE_PREFIX_LEN {
+                return Err(CompressionError::CodecError(
+                    codec,
+                    "missing size prefix".to_string(),
+                ));
+            }
+            let (size, data) = message.data.split_at(LZ4_SIZE_PREFIX_LEN);
+            let size = u32::from_le_bytes(size.try_into().expect("size prefix is 4 bytes"));
+            if size as usize > max_size {
+                return Err(CompressionError::TooLarge(max_size));
+            }
+            lz4_flex::decompress(data, size as usize)
+                .map_err(|err| CompressionError::CodecError(codec, err.to_string()))?
+        }
+        CompressionCodec::Zstd => {
+            // zstd writes the content size in the frame header, the frames without it are
+            // rejected rather than decompressed into a `max_size` buffer
+            let size = zstd::zstd_safe::get_frame_content_size(&message.data)
+                .map_err(|_| CompressionError::CodecError(codec, "invalid frame".to_string()))?
+                .ok_or_else(|| {
+                    CompressionError::CodecError(codec, "unknown content size".to_string())
+                })?;
+            if size > max_size as u64 {
+                return Err(CompressionError::TooLarge(max_size));
+            }
+            zstd::bulk::decompress(&message.data, size as usize)
+                .map_err(|err| CompressionError::CodecError(codec, err.to_string()))?
+        }
+    };
+    match bcs::from_bytes(&bytes).map_err(CompressionError::DeserializeError)? {
+        NetworkMessage::CompressedMsg(_) => Err(CompressionError::Nested),
+        message => Ok(message),
+    }
+}
@@ -10,6 +10,7 @@
 //! over-the-wire.
 
 use crate::protocols::wire::handshake::v1::ProtocolId;
+use aptos_config::config::CompressionCodec;
 use aptos_rate_limiter::{async_lib::AsyncRateLimiter, rate_limit::SharedBucket};
 use bytes::Bytes;
 use futures::{
@@ -32,9 +33,12 @@ use tokio_util::{
     compat::{Compat, FuturesAsyncReadCompatExt, FuturesAsyncWriteCompatExt},
 };
 
+mod compression;
 #[cfg(test)]
 mod test;
 
+pub use compression::{compress, decompress, CompressionError};
+
 /// Most primitive message type set on the network.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
@@ -43,6 +47,22 @@ pub enum NetworkMessage {
     RpcRequest(RpcRequest),
     RpcResponse(RpcResponse),
     DirectSendMsg(DirectSendMsg),
+    /// Another `NetworkMessage`, compressed. Only sent to peers supporting the codec, see
+    /// [`Capabilities`](crate::protocols::wire::handshake::v1::Capabilities).
+    CompressedMsg(CompressedMsg),
+}
+
+impl NetworkMessage {
+    /// The length of the application payload of the message.
+    pub fn data_len(&self) -> usize {
+        match self {
+            NetworkMessage::Error(_) => 0,
+            NetworkMessage::RpcRequest(request) => request.raw_request.len(),
+            NetworkMessage::RpcResponse(response) => response.raw_response.len(),
+            NetworkMessage::DirectSendMsg(message) => message.raw_msg.len(),
+            NetworkMessage::CompressedMsg(message) => message.data.len(),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -123,6 +143,20 @@ pub struct DirectSendMsg {
     pub raw_msg: Vec<u8>,
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct CompressedMsg {
+    /// The codec the message is compressed with.
+    #[cfg_attr(
+        any(test, feature = "fuzzing"),
+        proptest(strategy = "proptest::sample::select(CompressionCodec::all())")
+    )]
+    pub codec: CompressionCodec,
+    /// The serialized `NetworkMessage`, compressed.
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
 /// Errors from reading and deserializing network messages off the wire.
 #[derive(Debug, Error)]
 pub enum ReadError {
@@ -122,6 +122,79 @@ fn recv_fails_when_larger_than_frame_limit() {
     res_message.unwrap().unwrap_err();
 }
 
+#[test]
+fn decompress_fails_when_larger_than_max_size() {
+    let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: ProtocolId::StorageServiceRpc,
+        priority: 0,
+        raw_msg: vec![0; 1024],
+    });
+    for codec in CompressionCodec::all() {
+        // zeros compress well, so the compressed message fits in a small frame
+        let compressed = compress(*codec, &message).unwrap();
+        assert!(compressed.data.len() < 128);
+
+        assert_eq!(decompress(&compressed, 2048).unwrap(), message);
+        assert!(matches!(
+            decompress(&compressed, 512).unwrap_err(),
+            CompressionError::TooLarge(_) | CompressionError::CodecError(..)
+        ));
+    }
+}
+
+#[test]
+fn decompress_zstd_checks_declared_size() {
+    // a frame header declaring a 1 GiB content size, in a frame of a few bytes
+    let mut data = 0xFD2F_B528u32.to_le_bytes().to_vec();
+    // the frame header descriptor: an 8 byte content size, single segment
+    data.push(0b1110_0000);
+    data.extend_from_slice(&(1u64 << 30).to_le_bytes());
+    let message = CompressedMsg {
+        codec: CompressionCodec::Zstd,
+        data,
+    };
+    assert!(matches!(
+        decompress(&message, 1024).unwrap_err(),
+        CompressionError::TooLarge(1024)
+    ));
+
+    // a frame not declaring its content size is rejected as well
+    let bytes = bcs::to_bytes(&NetworkMessage::Error(ErrorCode::parsing_error(0, 0))).unwrap();
+    let mut compressor = zstd::bulk::Compressor::new(zstd::DEFAULT_COMPRESSION_LEVEL).unwrap();
+    compressor
+        .set_parameter(zstd::stream::raw::CParameter::ContentSizeFlag(false))
+        .unwrap();
+    let message = CompressedMsg {
+        codec: CompressionCodec::Zstd,
+        data: compressor.compress(&bytes).unwrap(),
+    };
+    assert!(matches!(
+        decompress(&message, 1024).unwrap_err(),
+        CompressionError::CodecError(..)
+    ));
+}
+
+#[test]
+fn compressed_messages_cannot_be_nested() {
+    let message = NetworkMessage::Error(ErrorCode::parsing_error(0, 0));
+    let compressed =
+        NetworkMessage::CompressedMsg(compress(CompressionCodec::Lz4, &message).unwrap());
+    assert!(matches!(
+        compress(CompressionCodec::Lz4, &compressed).unwrap_err(),
+        CompressionError::Nested
+    ));
+
+    // A peer could still send one
+    let nested = CompressedMsg {
+        codec: CompressionCodec::Lz4,
+        data: lz4_flex::compress_prepend_size(&bcs::to_bytes(&compressed).unwrap()),
+    };
+    assert!(matches!(
+        decompress(&nested, 1024).unwrap_err(),
+        CompressionError::Nested
+    ));
+}
+
 fn arb_rpc_request(max_frame_size: usize) -> impl Strategy<Value = RpcRequest> {
     (
         any::<ProtocolId>(),
@@ -185,6 +258,15 @@ proptest! {
         assert_canonical_encode_decode(message);
     }
 
+    #[test]
+    fn network_message_compression_roundtrip(
+        message in arb_network_message(1024),
+        codec in proptest::sample::select(CompressionCodec::all()),
+    ) {
+        let compressed = compress(codec, &message).unwrap();
+        assert_eq!(decompress(&compressed, 1024).unwrap(), message);
+    }
+
     /// Test that NetworkMessageSink and NetworkMessageStream can understand each
     /// other and fully preserve the NetworkMessages being sent
     #[test]
@@ -5,8 +5,10 @@ use crate::{
     logging::NetworkSchema,
     noise::{stream::NoiseStream, AntiReplayTimestamps, HandshakeAuthMode, NoiseUpgrader},
     protocols::{
-        identity::exchange_handshake,
-        wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet},
+        identity::{exchange_capabilities, exchange_handshake},
+        wire::handshake::v1::{
+            Capabilities, HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet,
+        },
     },
 };
 use aptos_config::{
@@ -27,7 +29,7 @@ use futures::{
     io::{AsyncRead, AsyncWrite},
     stream::{Stream, StreamExt, TryStreamExt},
 };
-use netcore::transport::{proxy_protocol, tcp, ConnectionOrigin, Transport};
+use netcore::transport::{proxy_protocol, quic, tcp, ConnectionOrigin, Transport};
 use serde::Serialize;
 use short_hex_str::AsShortHexStr;
 use std::{collections::BTreeMap, convert::TryFrom, fmt, io, pin::Pin, sync::Arc, time::Duration};
@@ -53,6 +55,13 @@ pub const APTOS_TCP_TRANSPORT: tcp::TcpTransport = tcp::TcpTransport {
     nodelay: Some(true),
 };
 
+/// quic::QuicTransport with Aptos-specific configuration applied. Unlike the tcp one, it holds the
+/// endpoints it dials from, so each call returns a new transport.
+pub fn aptos_quic_transport() -> quic::QuicTransport {
+    // Fall back to, and accept, Aptos tcp connections.
+    quic::QuicTransport::new(APTOS_TCP_TRANSPORT, quic::QUIC_CONNECT_TIMEOUT)
+}
+
 /// A trait alias for "socket-like" things.
 pub trait TSocket: AsyncRead + AsyncWrite + Send + fmt::Debug + Unpin + 'static {}
 
@@ -95,6 +104,8 @@ pub struct ConnectionMetadata {
     pub messaging_protocol: MessagingProtocolVersion,
     pub application_protocols: ProtocolIdSet,
     pub role: PeerRole,
+    /// The capabilities supported by both ends of the connection
+    pub capabilities: Capabilities,
 }
 
 impl ConnectionMetadata {
@@ -115,6 +126,7 @@ impl ConnectionMetadata {
             messaging_protocol,
             application_protocols,
             role,
+            capabilities: Capabilities::baseline(),
         }
     }
 
@@ -141,6 +153,7 @@ impl ConnectionMetadata {
             addr: NetworkAddress::mock(),
             messaging_protocol: MessagingProtocolVersion::V1,
             application_protocols: ProtocolIdSet::empty(),
+            capabilities: Capabilities::baseline(),
         }
     }
 }
@@ -191,6 +204,7 @@ pub struct UpgradeContext {
     noise: NoiseUpgrader,
     handshake_version: u8,
     supported_protocols: BTreeMap<MessagingProtocolVersion, ProtocolIdSet>,
+    capabilities: Capabilities,
     chain_id: ChainId,
     network_id: NetworkId,
 }
@@ -200,6 +214,7 @@ impl UpgradeContext {
         noise: NoiseUpgrader,
         handshake_version: u8,
         supported_protocols: BTreeMap<MessagingProtocolVersion, ProtocolIdSet>,
+        capabilities: Capabilities,
         chain_id: ChainId,
         network_id: NetworkId,
     ) -> Self {
@@ -207,6 +222,7 @@ impl UpgradeContext {
             noise,
             handshake_version,
             supported_protocols,
+            capabilities,
             chain_id,
             network_id,
         }
@@ -225,6 +241,24 @@ fn add_pp_addr(proxy_protocol_enabled: bool, error: io::Error, addr: &NetworkAdd
     }
 }
 
+/// Exchange [`Capabilities`] with the remote if both ends announced them in their
+/// [`HandshakeMsg`], and return the capabilities supported by both ends. Remotes that do not
+/// exchange them are assumed to only support the baseline ones.
+async fn negotiate_capabilities<T: AsyncRead + AsyncWrite + Unpin>(
+    ctxt: &UpgradeContext,
+    own_handshake: &HandshakeMsg,
+    remote_handshake: &HandshakeMsg,
+    socket: &mut T,
+) -> io::Result<Capabilities> {
+    let remote_capabilities =
+        if own_handshake.announces_capabilities() && remote_handshake.announces_capabilities() {
+            exchange_capabilities(&ctxt.capabilities, socket).await?
+        } else {
+            Capabilities::baseline()
+        };
+    Ok(ctxt.capabilities.intersect(&remote_capabilities))
+}
+
 /// Upgrade an inbound connection. This means we run a Noise IK handshake for
 /// authentication and then negotiate common supported protocols. If
 /// `ctxt.trusted_peers` is `Some(_)`, then we will only allow connections from
@@ -303,19 +337,26 @@ async fn upgrade_inbound<T: TSocket>(
                 &addr,
             )
         })?;
+    let capabilities =
+        negotiate_capabilities(&ctxt, &handshake_msg, &remote_handshake, &mut socket)
+            .await
+            .map_err(|err| add_pp_addr(proxy_protocol_enabled, err, &addr))?;
 
     // return successful connection
     Ok(Connection {
         socket,
-        metadata: ConnectionMetadata::new(
-            remote_peer_id,
-            CONNECTION_ID_GENERATOR.next(),
-            addr,
-            origin,
-            messaging_protocol,
-            application_protocols,
-            peer_role,
-        ),
+        metadata: ConnectionMetadata {
+            capabilities,
+            ..ConnectionMetadata::new(
+                remote_peer_id,
+                CONNECTION_ID_GENERATOR.next(),
+                addr,
+                origin,
+                messaging_protocol,
+                application_protocols,
+                peer_role,
+            )
+        },
     })
 }
 
@@ -373,19 +414,24 @@ pub async fn upgrade_outbound<T: TSocket>(
             );
             io::Error::new(io::ErrorKind::Other, e)
         })?;
+    let capabilities =
+        negotiate_capabilities(&ctxt, &handshake_msg, &remote_handshake, &mut socket).await?;
 
     // return successful connection
     Ok(Connection {
         socket,
-        metadata: ConnectionMetadata::new(
-            remote_peer_id,
-            CONNECTION_ID_GENERATOR.next(),
-            addr,
-            origin,
-            messaging_protocol,
-            application_protocols,
-            PeerRole::Unknown,
-        ),
+        metadata: ConnectionMetadata {
+            capabilities,
+            ..ConnectionMetadata::new(
+                remote_peer_id,
+                CONNECTION_ID_GENERATOR.next(),
+                addr,
+                origin,
+                messaging_protocol,
+                application_protocols,
+                PeerRole::Unknown,
+            )
+        },
     })
 }
 
@@ -425,10 +471,12 @@ where
         auth_mode: HandshakeAuthMode,
         handshake_version: u8,
         chain_id: ChainId,
-        application_protocols: ProtocolIdSet,
+        mut application_protocols: ProtocolIdSet,
+        capabilities: Capabilities,
         enable_proxy_protocol: bool,
     ) -> Self {
         // build supported protocols
+        application_protocols.announce_capabilities();
         let mut supported_protocols = BTreeMap::new();
         supported_protocols.insert(SUPPORTED_MESSAGING_PROTOCOL, application_protocols);
 
@@ -438,6 +486,7 @@ where
             NoiseUpgrader::new(network_context, identity_key, auth_mode),
             handshake_version,
             supported_protocols,
+            capabilities,
             chain_id,
             network_context.network_id(),
         );
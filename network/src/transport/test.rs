@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    protocols::wire::handshake::v1::{MessagingProtocolVersion, ProtocolId, ProtocolIdSet},
+    protocols::wire::handshake::v1::{
+        Capabilities, MessagingProtocolVersion, ProtocolId, ProtocolIdSet,
+    },
     transport::*,
 };
 use aptos_config::{
@@ -27,6 +29,9 @@ use rand::{rngs::StdRng, SeedableRng};
 use std::{collections::HashMap, io, iter::FromIterator, sync::Arc};
 use tokio::runtime::Runtime;
 
+/// The listener accepts smaller frames than the dialer
+const LISTENER_MAX_FRAME_SIZE: usize = 1024;
+
 /// helper to build trusted peer map
 fn build_trusted_peers(
     id1: PeerId,
@@ -155,6 +160,7 @@ where
         HANDSHAKE_VERSION,
         chain_id,
         supported_protocols.clone(),
+        Capabilities::new(LISTENER_MAX_FRAME_SIZE),
         false, /* Disable proxy protocol */
     );
 
@@ -167,6 +173,7 @@ where
         HANDSHAKE_VERSION,
         chain_id,
         supported_protocols.clone(),
+        Capabilities::new(2 * LISTENER_MAX_FRAME_SIZE),
         false, /* Disable proxy protocol */
     );
 
@@ -257,6 +264,10 @@ fn test_transport_success<TTransport>(
             conn.metadata.application_protocols,
             supported_protocols_clone,
         );
+        assert_eq!(
+            conn.metadata.capabilities,
+            Capabilities::new(LISTENER_MAX_FRAME_SIZE)
+        );
 
         // test the socket works
         let msg = write_read_msg(&mut conn.socket, b"foobar").await;
@@ -283,6 +294,10 @@ fn test_transport_success<TTransport>(
             MessagingProtocolVersion::V1
         );
         assert_eq!(conn.metadata.application_protocols, supported_protocols);
+        assert_eq!(
+            conn.metadata.capabilities,
+            Capabilities::new(LISTENER_MAX_FRAME_SIZE)
+        );
 
         // test the socket works
         let msg = write_read_msg(&mut conn.socket, b"barbaz").await;
@@ -563,3 +578,26 @@ fn test_tcp_transport_rejects_unauthed_dialer() {
         expect_ip4_tcp_noise_addr,
     );
 }
+
+//////////////////////////////////////
+// AptosNetTransport<QuicTransport> //
+//////////////////////////////////////
+
+#[test]
+fn test_quic_transport_mutual_auth() {
+    test_transport_success(
+        aptos_quic_transport(),
+        Auth::Mutual,
+        "/ip4/127.0.0.1/tcp/0",
+        expect_ip4_tcp_noise_addr,
+    );
+}
+
+#[test]
+fn test_quic_transport_rejects_unauthed_dialer() {
+    test_transport_rejects_unauthed_dialer(
+        aptos_quic_transport(),
+        "/ip4/127.0.0.1/tcp/0",
+        expect_ip4_tcp_noise_addr,
+    );
+}
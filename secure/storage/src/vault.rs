@@ -10,13 +10,14 @@ use aptos_crypto::{
     hash::CryptoHash,
 };
 use aptos_infallible::RwLock;
-use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_vault_client::Client;
 use chrono::DateTime;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
     collections::HashMap,
-    sync::atomic::{AtomicU64, Ordering},
+    sync::{Arc, Weak},
+    thread,
+    time::{Duration, Instant},
 };
 
 #[cfg(any(test, feature = "testing"))]
@@ -24,6 +25,11 @@ use aptos_vault_client::ReadResponse;
 
 const TRANSIT_NAMESPACE_SEPARATOR: &str = "__";
 
+/// How often the token renewal thread checks whether a renewal is due.
+const RENEWAL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Delay before retrying a failed token renewal.
+const RENEWAL_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
 /// VaultStorage utilizes Vault for maintaining encrypted, authenticated data. This
 /// version currently matches the behavior of OnDiskStorage and InMemoryStorage. In the future,
 /// Vault will be able to create keys, sign messages, and handle permissions across different
@@ -32,10 +38,7 @@ const TRANSIT_NAMESPACE_SEPARATOR: &str = "__";
 /// calls pointers to data keys, Vault has actually a secret that contains multiple key value
 /// pairs.
 pub struct VaultStorage {
-    client: Client,
-    time_service: TimeService,
-    renew_ttl_secs: Option<u32>,
-    next_renewal: AtomicU64,
+    client: Arc<Client>,
     use_cas: bool,
     secret_versions: RwLock<HashMap<String, u32>>,
 }
@@ -50,37 +53,24 @@ impl VaultStorage {
         connection_timeout_ms: Option<u64>,
         response_timeout_ms: Option<u64>,
     ) -> Self {
+        let client = Arc::new(Client::new(
+            host,
+            token,
+            certificate,
+            connection_timeout_ms,
+            response_timeout_ms,
+        ));
+        if renew_ttl_secs.is_some() {
+            spawn_token_renewal(Arc::downgrade(&client), renew_ttl_secs);
+        }
         Self {
-            client: Client::new(
-                host,
-                token,
-                certificate,
-                connection_timeout_ms,
-                response_timeout_ms,
-            ),
-            time_service: TimeService::real(),
-            renew_ttl_secs,
-            next_renewal: AtomicU64::new(0),
+            client,
             use_cas,
             secret_versions: RwLock::new(HashMap::new()),
         }
     }
 
-    // Made into an accessor so we can get auto-renewal
     fn client(&self) -> &Client {
-        if self.renew_ttl_secs.is_some() {
-            let now = self.time_service.now_secs();
-            let next_renewal = self.next_renewal.load(Ordering::Relaxed);
-            if now >= next_renewal {
-                let result = self.client.renew_token_self(self.renew_ttl_secs);
-                if let Ok(ttl) = result {
-                    let next_renewal = now + (ttl as u64) / 2;
-                    self.next_renewal.store(next_renewal, Ordering::Relaxed);
-                } else if let Err(e) = result {
-                    aptos_logger::error!("Unable to renew lease: {}", e.to_string());
-                }
-            }
-        }
         &self.client
     }
 
@@ -144,6 +134,32 @@ impl VaultStorage {
     }
 }
 
+/// Renews the lease of the client's token at half of its duration, until the storage owning the
+/// client is dropped. Renewing in the background keeps the round trip off the request path, e.g.,
+/// of consensus signatures.
+fn spawn_token_renewal(client: Weak<Client>, renew_ttl_secs: Option<u32>) {
+    thread::Builder::new()
+        .name("vault-token-renewal".into())
+        .spawn(move || {
+            let mut next_renewal = Instant::now();
+            while let Some(client) = client.upgrade() {
+                if Instant::now() >= next_renewal {
+                    let delay = match client.renew_token_self(renew_ttl_secs) {
+                        Ok(ttl) => Duration::from_secs((ttl as u64 / 2).max(1)),
+                        Err(e) => {
+                            aptos_logger::error!("Unable to renew lease: {}", e.to_string());
+                            RENEWAL_RETRY_INTERVAL
+                        }
+                    };
+                    next_renewal = Instant::now() + delay;
+                }
+                drop(client);
+                thread::sleep(RENEWAL_POLL_INTERVAL);
+            }
+        })
+        .expect("Unable to spawn the Vault token renewal thread");
+}
+
 impl KVStorage for VaultStorage {
     fn available(&self) -> Result<(), Error> {
         if !self.client().unsealed()? {
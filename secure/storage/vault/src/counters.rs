@@ -0,0 +1,46 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_secure_push_metrics::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, HistogramVec,
+    IntCounter, IntCounterVec,
+};
+use once_cell::sync::Lazy;
+use std::time::Duration;
+
+pub const SUCCESS: &str = "success";
+pub const ERROR: &str = "error";
+pub const TRANSIENT_ERROR: &str = "transient_error";
+pub const CIRCUIT_OPEN: &str = "circuit_open";
+
+static LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_vault_client_request_latency_s",
+        "Time to complete a request to Vault, per attempt",
+        &["operation", "result"]
+    )
+    .unwrap()
+});
+
+pub static RETRIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vault_client_retries",
+        "Number of requests to Vault retried after a transient failure",
+        &["operation"]
+    )
+    .unwrap()
+});
+
+pub static CIRCUIT_BREAKER_TRIPS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_vault_client_circuit_breaker_trips",
+        "Number of times the Vault circuit breaker opened"
+    )
+    .unwrap()
+});
+
+pub fn observe_request(operation: &str, result: &str, latency: Duration) {
+    LATENCY
+        .with_label_values(&[operation, result])
+        .observe(latency.as_secs_f64());
+}
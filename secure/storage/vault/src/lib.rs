@@ -3,19 +3,23 @@
 
 #![forbid(unsafe_code)]
 
+mod counters;
 pub mod dev;
+mod retry;
 
 use aptos_crypto::{
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, ED25519_PRIVATE_KEY_LENGTH},
     PrivateKey,
 };
+use retry::CircuitBreaker;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::{
     collections::BTreeMap,
     convert::{TryFrom, TryInto},
     sync::Arc,
-    time::Duration,
+    thread,
+    time::{Duration, Instant},
 };
 use thiserror::Error;
 use ureq::Response;
@@ -38,6 +42,8 @@ const DEFAULT_RESPONSE_TIMEOUT_MS: u64 = 1_000;
 
 #[derive(Debug, Error, PartialEq)]
 pub enum Error {
+    #[error("Vault circuit breaker is open, {0} was not sent")]
+    CircuitOpen(String),
     #[error("Http error, status code: {0}, status text: {1}, body: {2}")]
     HttpError(u16, String, String),
     #[error("Internal error: {0}")]
@@ -120,6 +126,9 @@ pub struct Client {
     connection_timeout_ms: u64,
     /// Timeout for generic vault responses (e.g., reads and writes).
     response_timeout_ms: u64,
+
+    /// Stops sending requests for a while after repeated transient failures.
+    circuit_breaker: CircuitBreaker,
 }
 
 impl Client {
@@ -152,31 +161,34 @@ impl Client {
             tls_connector,
             connection_timeout_ms,
             response_timeout_ms,
+            circuit_breaker: CircuitBreaker::new(),
         }
     }
 
     pub fn delete_policy(&self, policy_name: &str) -> Result<(), Error> {
-        let request = self
-            .agent
-            .delete(&format!("{}/v1/sys/policy/{}", self.host, policy_name));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/sys/policy/{}", self.host, policy_name);
+        let resp = self.send("delete_policy", true, || {
+            self.upgrade_request(self.agent.delete(&url)).call()
+        })?;
 
         process_generic_response(resp)
     }
 
     pub fn list_policies(&self) -> Result<Vec<String>, Error> {
-        let request = self.agent.get(&format!("{}/v1/sys/policy", self.host));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/sys/policy", self.host);
+        let resp = self.send("list_policies", true, || {
+            self.upgrade_request(self.agent.get(&url)).call()
+        })?;
 
         process_policy_list_response(resp)
     }
 
     /// Retrieves the policy at the given policy name.
     pub fn read_policy(&self, policy_name: &str) -> Result<Policy, Error> {
-        let request = self
-            .agent
-            .get(&format!("{}/v1/sys/policy/{}", self.host, policy_name));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/sys/policy/{}", self.host, policy_name);
+        let resp = self.send("read_policy", true, || {
+            self.upgrade_request(self.agent.get(&url)).call()
+        })?;
 
         process_policy_read_response(resp)
     }
@@ -185,10 +197,12 @@ impl Client {
     /// structured. Vault does not distingush a create and update. An update must first read the
     /// existing policy, amend the contents,  and then be applied via this API.
     pub fn set_policy(&self, policy_name: &str, policy: &Policy) -> Result<(), Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/sys/policy/{}", self.host, policy_name));
-        let resp = self.upgrade_request(request).send_json(policy.try_into()?);
+        let url = format!("{}/v1/sys/policy/{}", self.host, policy_name);
+        let payload: Value = policy.try_into()?;
+        let resp = self.send("set_policy", true, || {
+            self.upgrade_request(self.agent.post(&url))
+                .send_json(payload.clone())
+        })?;
 
         process_generic_response(resp)
     }
@@ -196,96 +210,92 @@ impl Client {
     /// Creates a new token or identity for accessing Vault. The token will have access to anything
     /// under the default policy and any prescribed policies.
     pub fn create_token(&self, policies: Vec<&str>) -> Result<String, Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/auth/token/create", self.host));
-        let resp = self
-            .upgrade_request(request)
-            .send_json(json!({ "policies": policies }));
+        let url = format!("{}/v1/auth/token/create", self.host);
+        let resp = self.send("create_token", false, || {
+            self.upgrade_request(self.agent.post(&url))
+                .send_json(json!({ "policies": policies }))
+        })?;
 
         process_token_create_response(resp)
     }
 
     pub fn renew_token_self(&self, increment: Option<u32>) -> Result<u32, Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/auth/token/renew-self", self.host));
-        let mut request = self.upgrade_request(request);
-        let resp = if let Some(increment) = increment {
-            request.send_json(json!({ "increment": increment }))
-        } else {
-            request.call()
-        };
+        let url = format!("{}/v1/auth/token/renew-self", self.host);
+        let resp = self.send("renew_token_self", true, || {
+            let mut request = self.upgrade_request(self.agent.post(&url));
+            if let Some(increment) = increment {
+                request.send_json(json!({ "increment": increment }))
+            } else {
+                request.call()
+            }
+        })?;
 
         process_token_renew_response(resp)
     }
 
     pub fn revoke_token_self(&self) -> Result<(), Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/auth/token/revoke-self", self.host));
-        let mut request = self.upgrade_request(request);
-        let resp = request.call();
+        let url = format!("{}/v1/auth/token/revoke-self", self.host);
+        let resp = self.send("revoke_token_self", false, || {
+            self.upgrade_request(self.agent.post(&url)).call()
+        })?;
 
         process_generic_response(resp)
     }
 
     /// List all stored secrets
     pub fn list_secrets(&self, secret: &str) -> Result<Vec<String>, Error> {
-        let request = self.agent.request(
-            "LIST",
-            &format!("{}/v1/secret/metadata/{}", self.host, secret),
-        );
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/secret/metadata/{}", self.host, secret);
+        let resp = self.send("list_secrets", true, || {
+            self.upgrade_request(self.agent.request("LIST", &url))
+                .call()
+        })?;
 
         process_secret_list_response(resp)
     }
 
     /// Delete a specific secret store
     pub fn delete_secret(&self, secret: &str) -> Result<(), Error> {
-        let request = self
-            .agent
-            .delete(&format!("{}/v1/secret/metadata/{}", self.host, secret));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/secret/metadata/{}", self.host, secret);
+        let resp = self.send("delete_secret", true, || {
+            self.upgrade_request(self.agent.delete(&url)).call()
+        })?;
 
         process_generic_response(resp)
     }
 
     /// Read a key/value pair from a given secret store.
     pub fn read_secret(&self, secret: &str, key: &str) -> Result<ReadResponse<Value>, Error> {
-        let request = self
-            .agent
-            .get(&format!("{}/v1/secret/data/{}", self.host, secret));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/secret/data/{}", self.host, secret);
+        let resp = self.send("read_secret", true, || {
+            self.upgrade_request(self.agent.get(&url)).call()
+        })?;
 
         process_secret_read_response(secret, key, resp)
     }
 
     pub fn create_ed25519_key(&self, name: &str, exportable: bool) -> Result<(), Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/transit/keys/{}", self.host, name));
-        let resp = self
-            .upgrade_request(request)
-            .send_json(json!({ "type": "ed25519", "exportable": exportable }));
+        let url = format!("{}/v1/transit/keys/{}", self.host, name);
+        let resp = self.send("create_ed25519_key", false, || {
+            self.upgrade_request(self.agent.post(&url))
+                .send_json(json!({ "type": "ed25519", "exportable": exportable }))
+        })?;
 
         process_transit_create_response(name, resp)
     }
 
     pub fn delete_key(&self, name: &str) -> Result<(), Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/transit/keys/{}/config", self.host, name));
-        let resp = self
-            .upgrade_request(request)
-            .send_json(json!({ "deletion_allowed": true }));
+        let url = format!("{}/v1/transit/keys/{}/config", self.host, name);
+        let resp = self.send("delete_key", true, || {
+            self.upgrade_request(self.agent.post(&url))
+                .send_json(json!({ "deletion_allowed": true }))
+        })?;
 
         process_generic_response(resp)?;
 
-        let request = self
-            .agent
-            .delete(&format!("{}/v1/transit/keys/{}", self.host, name));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/transit/keys/{}", self.host, name);
+        let resp = self.send("delete_key", true, || {
+            self.upgrade_request(self.agent.delete(&url)).call()
+        })?;
 
         process_generic_response(resp)
     }
@@ -295,32 +305,31 @@ impl Client {
         name: &str,
         version: Option<u32>,
     ) -> Result<Ed25519PrivateKey, Error> {
-        let request = self.agent.get(&format!(
-            "{}/v1/transit/export/signing-key/{}",
-            self.host, name
-        ));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/transit/export/signing-key/{}", self.host, name);
+        let resp = self.send("export_ed25519_key", true, || {
+            self.upgrade_request(self.agent.get(&url)).call()
+        })?;
 
         process_transit_export_response(name, version, resp)
     }
 
     pub fn import_ed25519_key(&self, name: &str, key: &Ed25519PrivateKey) -> Result<(), Error> {
         let backup = base64::encode(serde_json::to_string(&KeyBackup::new(key))?);
-        let request = self
-            .agent
-            .post(&format!("{}/v1/transit/restore/{}", self.host, name));
-        let resp = self
-            .upgrade_request(request)
-            .send_json(json!({ "backup": backup }));
+        let url = format!("{}/v1/transit/restore/{}", self.host, name);
+        let resp = self.send("import_ed25519_key", false, || {
+            self.upgrade_request(self.agent.post(&url))
+                .send_json(json!({ "backup": backup }))
+        })?;
 
         process_transit_restore_response(resp)
     }
 
     pub fn list_keys(&self) -> Result<Vec<String>, Error> {
-        let request = self
-            .agent
-            .request("LIST", &format!("{}/v1/transit/keys", self.host));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/transit/keys", self.host);
+        let resp = self.send("list_keys", true, || {
+            self.upgrade_request(self.agent.request("LIST", &url))
+                .call()
+        })?;
 
         process_transit_list_response(resp)
     }
@@ -329,19 +338,19 @@ impl Client {
         &self,
         name: &str,
     ) -> Result<Vec<ReadResponse<Ed25519PublicKey>>, Error> {
-        let request = self
-            .agent
-            .get(&format!("{}/v1/transit/keys/{}", self.host, name));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/transit/keys/{}", self.host, name);
+        let resp = self.send("read_ed25519_key", true, || {
+            self.upgrade_request(self.agent.get(&url)).call()
+        })?;
 
         process_transit_read_response(name, resp)
     }
 
     pub fn rotate_key(&self, name: &str) -> Result<(), Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/transit/keys/{}/rotate", self.host, name));
-        let resp = self.upgrade_request(request).call();
+        let url = format!("{}/v1/transit/keys/{}/rotate", self.host, name);
+        let resp = self.send("rotate_key", false, || {
+            self.upgrade_request(self.agent.post(&url)).call()
+        })?;
 
         process_generic_response(resp)
     }
@@ -396,12 +405,11 @@ impl Client {
         name: &str,
         min_available_version: u32,
     ) -> Result<(), Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/transit/keys/{}/trim", self.host, name));
-        let resp = self
-            .upgrade_request(request)
-            .send_json(json!({ "min_available_version": min_available_version }));
+        let url = format!("{}/v1/transit/keys/{}/trim", self.host, name);
+        let resp = self.send("set_minimum_available_version", true, || {
+            self.upgrade_request(self.agent.post(&url))
+                .send_json(json!({ "min_available_version": min_available_version }))
+        })?;
 
         process_generic_response(resp)
     }
@@ -412,12 +420,12 @@ impl Client {
         name: &str,
         min_version: u32,
     ) -> Result<(), Error> {
-        let request = self
-            .agent
-            .post(&format!("{}/v1/transit/keys/{}/config", self.host, name));
-        let resp = self.upgrade_request(request).send_json(
-            json!({ "min_encryption_version": min_version, "min_decryption_version": min_version }),
-        );
+        let url = format!("{}/v1/transit/keys/{}/config", self.host, name);
+        let resp = self.send("set_minimum_encrypt_decrypt_version", true, || {
+            self.upgrade_request(self.agent.post(&url)).send_json(
+                json!({ "min_encryption_version": min_version, "min_decryption_version": min_version }),
+            )
+        })?;
 
         process_generic_response(resp)
     }
@@ -434,10 +442,13 @@ impl Client {
             json!({ "input": base64::encode(&data) })
         };
 
-        let request = self
-            .agent
-            .post(&format!("{}/v1/transit/sign/{}", self.host, name));
-        let resp = self.upgrade_request(request).send_json(data);
+        // Ed25519 signatures are deterministic, so retrying a signature cannot produce a second,
+        // different signature over the same data.
+        let url = format!("{}/v1/transit/sign/{}", self.host, name);
+        let resp = self.send("sign_ed25519", true, || {
+            self.upgrade_request(self.agent.post(&url))
+                .send_json(data.clone())
+        })?;
 
         process_transit_sign_response(resp)
     }
@@ -456,10 +467,11 @@ impl Client {
             json!({ "data": { key: value } })
         };
 
-        let request = self
-            .agent
-            .put(&format!("{}/v1/secret/data/{}", self.host, secret));
-        let resp = self.upgrade_request(request).send_json(payload);
+        let url = format!("{}/v1/secret/data/{}", self.host, secret);
+        let resp = self.send("write_secret", false, || {
+            self.upgrade_request(self.agent.put(&url))
+                .send_json(payload.clone())
+        })?;
 
         if resp.ok() {
             let resp: WriteSecretResponse = serde_json::from_str(&resp.into_string()?)?;
@@ -472,12 +484,64 @@ impl Client {
     /// Returns whether or not the vault is unsealed (can be read from / written to). This can be
     /// queried without authentication.
     pub fn unsealed(&self) -> Result<bool, Error> {
-        let request = self.agent.get(&format!("{}/v1/sys/seal-status", self.host));
-        let resp = self.upgrade_request_without_token(request).call();
+        let url = format!("{}/v1/sys/seal-status", self.host);
+        let resp = self.send("unsealed", true, || {
+            self.upgrade_request_without_token(self.agent.get(&url))
+                .call()
+        })?;
 
         process_unsealed_response(resp)
     }
 
+    /// Sends the request built by `build` and records its latency. Requests that are safe to
+    /// repeat (`retry`) are retried with a jittered backoff when they fail transiently, e.g., on a
+    /// dropped connection or while Vault is overloaded. Requests fail fast while the circuit
+    /// breaker is open.
+    fn send(
+        &self,
+        operation: &'static str,
+        retry: bool,
+        build: impl Fn() -> Response,
+    ) -> Result<Response, Error> {
+        if !self.circuit_breaker.allow_request() {
+            counters::observe_request(operation, counters::CIRCUIT_OPEN, Duration::ZERO);
+            return Err(Error::CircuitOpen(operation.into()));
+        }
+
+        let max_attempts = if retry { retry::MAX_ATTEMPTS } else { 1 };
+        let mut attempt = 1;
+        loop {
+            let timer = Instant::now();
+            let resp = build();
+            let transient = retry::is_transient(&resp);
+            let result = if resp.ok() {
+                counters::SUCCESS
+            } else if transient {
+                counters::TRANSIENT_ERROR
+            } else {
+                counters::ERROR
+            };
+            counters::observe_request(operation, result, timer.elapsed());
+
+            if !transient {
+                self.circuit_breaker.record_success();
+                return Ok(resp);
+            }
+            if attempt >= max_attempts {
+                if self.circuit_breaker.record_failure() {
+                    counters::CIRCUIT_BREAKER_TRIPS.inc();
+                }
+                return Ok(resp);
+            }
+
+            // Drain the response so that the connection can be reused.
+            let _ = resp.into_string();
+            counters::RETRIES.with_label_values(&[operation]).inc();
+            thread::sleep(retry::backoff(attempt));
+            attempt += 1;
+        }
+    }
+
     fn upgrade_request(&self, request: ureq::Request) -> ureq::Request {
         let mut request = self.upgrade_request_without_token(request);
         request.set("X-Vault-Token", &self.token);
@@ -0,0 +1,129 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use rand::Rng;
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use ureq::Response;
+
+/// Attempts made for a request that is safe to repeat before giving up.
+pub const MAX_ATTEMPTS: u32 = 3;
+/// Upper bound of the delay before the first retry, doubled for every following retry.
+const BASE_BACKOFF_MS: u64 = 20;
+/// Consecutive failed requests after which the circuit breaker opens.
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long requests fail fast once the circuit breaker opens.
+const OPEN_DURATION_MS: u64 = 1_000;
+
+/// Whether the request may succeed if sent again: the connection failed (synthetic responses are
+/// created by ureq for transport errors) or Vault is overloaded or unavailable.
+pub fn is_transient(resp: &Response) -> bool {
+    resp.synthetic() || matches!(resp.status(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Delay before the given retry, starting at 1. Half of the exponential delay is fixed and half
+/// is random, so that clients failing together do not retry together.
+pub fn backoff(retry: u32) -> Duration {
+    let max_ms = BASE_BACKOFF_MS << retry.saturating_sub(1).min(10);
+    Duration::from_millis(rand::thread_rng().gen_range(max_ms / 2..=max_ms))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the UNIX epoch")
+        .as_millis() as u64
+}
+
+/// Opens after `FAILURE_THRESHOLD` consecutive requests failed transiently, so that callers fail
+/// fast instead of waiting on timeouts while Vault is unreachable. Once `OPEN_DURATION_MS` has
+/// passed requests are let through again: a success closes the circuit, a failure reopens it.
+pub struct CircuitBreaker {
+    consecutive_failures: AtomicU32,
+    open_until_ms: AtomicU64,
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self {
+            consecutive_failures: AtomicU32::new(0),
+            open_until_ms: AtomicU64::new(0),
+        }
+    }
+
+    pub fn allow_request(&self) -> bool {
+        self.allow_request_at(now_ms())
+    }
+
+    fn allow_request_at(&self, now_ms: u64) -> bool {
+        now_ms >= self.open_until_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Returns true if the failure opened the circuit.
+    pub fn record_failure(&self) -> bool {
+        self.record_failure_at(now_ms())
+    }
+
+    fn record_failure_at(&self, now_ms: u64) -> bool {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures < FAILURE_THRESHOLD {
+            return false;
+        }
+        self.open_until_ms
+            .store(now_ms + OPEN_DURATION_MS, Ordering::Relaxed);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_bounded() {
+        for retry in 1..=MAX_ATTEMPTS {
+            let max_ms = BASE_BACKOFF_MS << (retry - 1);
+            for _ in 0..100 {
+                let delay = backoff(retry).as_millis() as u64;
+                assert!(max_ms / 2 <= delay && delay <= max_ms);
+            }
+        }
+        // Large retry counts do not overflow
+        backoff(u32::MAX);
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        let breaker = CircuitBreaker::new();
+        for _ in 1..FAILURE_THRESHOLD {
+            assert!(!breaker.record_failure_at(0));
+        }
+        assert!(breaker.allow_request_at(0));
+
+        // A success resets the count of consecutive failures
+        breaker.record_success();
+        for _ in 1..FAILURE_THRESHOLD {
+            assert!(!breaker.record_failure_at(0));
+        }
+        assert!(breaker.record_failure_at(0));
+        assert!(!breaker.allow_request_at(0));
+        assert!(!breaker.allow_request_at(OPEN_DURATION_MS - 1));
+
+        // After the open period a single failure reopens the circuit
+        assert!(breaker.allow_request_at(OPEN_DURATION_MS));
+        assert!(breaker.record_failure_at(OPEN_DURATION_MS));
+        assert!(!breaker.allow_request_at(OPEN_DURATION_MS));
+
+        // And a success closes it
+        assert!(breaker.allow_request_at(2 * OPEN_DURATION_MS));
+        breaker.record_success();
+        assert!(!breaker.record_failure_at(2 * OPEN_DURATION_MS));
+        assert!(breaker.allow_request_at(2 * OPEN_DURATION_MS));
+    }
+}
@@ -37,9 +37,9 @@ use std::{convert::TryFrom, fmt, sync::Arc, time::Duration};
 use storage_service_client::StorageServiceClient;
 use storage_service_types::{
     AccountStatesChunkWithProofRequest, Epoch, EpochEndingLedgerInfoRequest,
-    NewTransactionOutputsWithProofRequest, NewTransactionsWithProofRequest, StorageServerSummary,
-    StorageServiceRequest, StorageServiceResponse, TransactionOutputsWithProofRequest,
-    TransactionsWithProofRequest,
+    NewTransactionOutputsWithProofRequest, NewTransactionsWithProofRequest, ServerProtocolVersion,
+    StorageServerSummary, StorageServiceError, StorageServiceRequest, StorageServiceResponse,
+    TransactionOutputsWithProofRequest, TransactionsWithProofRequest,
 };
 use tokio::{runtime::Handle, task::JoinHandle};
 
@@ -58,6 +58,8 @@ const GLOBAL_DATA_METRIC_FREQ_SECS: u64 = 1;
 const IN_FLIGHT_METRICS_SAMPLE_FREQ: u64 = 5;
 const POLLER_LOG_FREQ_SECS: u64 = 1;
 const REGULAR_PEER_SAMPLE_FREQ: u64 = 3;
+const THROTTLED_PEER_BACKOFF_MS: u64 = 1000;
+const THROTTLED_PEER_LOG_FREQ_SECS: u64 = 1;
 
 /// An [`AptosDataClient`] that fulfills requests from remote peers' Storage Service
 /// over AptosNet.
@@ -89,6 +91,8 @@ pub struct AptosNetDataClient {
     global_summary_cache: Arc<RwLock<GlobalDataSummary>>,
     /// Used for generating the next request/response id.
     response_id_generator: Arc<U64IdGenerator>,
+    /// The service to monitor elapsed time (e.g., to back off from throttled peers).
+    time_service: TimeService,
 }
 
 impl AptosNetDataClient {
@@ -108,6 +112,7 @@ impl AptosNetDataClient {
             ))),
             global_summary_cache: Arc::new(RwLock::new(GlobalDataSummary::empty())),
             response_id_generator: Arc::new(U64IdGenerator::new()),
+            time_service: time_service.clone(),
         };
         let poller = DataSummaryPoller::new(
             client.clone(),
@@ -128,6 +133,24 @@ impl AptosNetDataClient {
         self.peer_states.write().update_summary(peer, summary)
     }
 
+    /// Returns true iff the protocol version run by the peer is known.
+    fn knows_server_protocol_version(&self, peer: &PeerNetworkId) -> bool {
+        self.peer_states.read().knows_server_protocol_version(peer)
+    }
+
+    /// Fetches the protocol version run by the peer. This also tells the peer
+    /// that we understand the errors of its protocol version.
+    async fn fetch_server_protocol_version(&self, peer: PeerNetworkId) -> Result<()> {
+        let server_protocol_version: ServerProtocolVersion = self
+            .send_request_to_peer_and_decode(peer, StorageServiceRequest::GetServerProtocolVersion)
+            .await
+            .map(Response::into_payload)?;
+        self.peer_states
+            .write()
+            .update_server_protocol_version(peer, server_protocol_version.protocol_version);
+        Ok(())
+    }
+
     /// Recompute and update the global data summary cache.
     fn update_global_summary_cache(&self) {
         let aggregate = self.peer_states.read().calculate_aggregate_summary();
@@ -169,15 +192,20 @@ impl AptosNetDataClient {
     }
 
     /// Identifies the peers in the given set of prospective peers
-    /// that can service the specified request.
+    /// that can service the specified request (and are not throttled).
     fn identify_serviceable(
         &self,
         prospective_peers: Vec<PeerNetworkId>,
         request: &StorageServiceRequest,
     ) -> Vec<PeerNetworkId> {
+        let now = self.time_service.now();
+        let peer_states = self.peer_states.read();
         prospective_peers
             .into_iter()
-            .filter(|peer| self.peer_states.read().can_service_request(peer, request))
+            .filter(|peer| {
+                peer_states.can_service_request(peer, request)
+                    && !peer_states.is_throttled(peer, now)
+            })
             .collect::<Vec<_>>()
     }
 
@@ -394,6 +422,34 @@ impl AptosNetDataClient {
                 };
                 Ok(Response::new(context, response))
             }
+            Err(storage_service_client::Error::StorageServiceError(
+                StorageServiceError::TooManyRequests(error),
+            )) => {
+                // The peer is overloaded, which doesn't make it a bad peer.
+                // Back off from it for a while instead of lowering its score.
+                let client_err = Error::DataIsUnavailable(error);
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(THROTTLED_PEER_LOG_FREQ_SECS)),
+                    warn!(
+                        (LogSchema::new(LogEntry::StorageServiceResponse)
+                            .event(LogEvent::ResponseError)
+                            .request_type(request.get_label())
+                            .request_id(id)
+                            .peer(&peer)
+                            .message("The peer has too many requests, backing off!")
+                            .error(&client_err))
+                    );
+                );
+
+                increment_counter(&metrics::ERROR_RESPONSES, request.get_label().into());
+
+                let throttled_until =
+                    self.time_service.now() + Duration::from_millis(THROTTLED_PEER_BACKOFF_MS);
+                self.peer_states
+                    .write()
+                    .throttle_peer(peer, throttled_until);
+                Err(client_err)
+            }
             Err(err) => {
                 // Convert network error and storage service error types into
                 // data client errors. Also categorize the error type for scoring
@@ -705,6 +761,19 @@ pub(crate) fn poll_peer(
         // Mark the in-flight poll as started
         data_client.in_flight_request_started(&peer);
 
+        // Fetch the protocol version of the peer (if we don't know it yet)
+        if !data_client.knows_server_protocol_version(&peer) {
+            if let Err(error) = data_client.fetch_server_protocol_version(peer).await {
+                warn!(
+                    (LogSchema::new(LogEntry::StorageSummaryResponse)
+                        .event(LogEvent::PeerPollingError)
+                        .message("Unable to fetch the protocol version of the peer!")
+                        .error(&error)
+                        .peer(&peer))
+                );
+            }
+        }
+
         // Start the peer polling timer
         let timer = start_timer(
             &metrics::REQUEST_LATENCIES,
@@ -15,6 +15,7 @@ use std::{
     cmp::min,
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Instant,
 };
 use storage_service_types::{StorageServerSummary, StorageServiceRequest};
 
@@ -58,6 +59,11 @@ struct PeerState {
     storage_summary: Option<StorageServerSummary>,
     /// For now, a simplified port of the original state-sync v1 scoring system.
     score: f64,
+    /// The protocol version run by the peer, or `None` if we haven't fetched it yet.
+    server_protocol_version: Option<u64>,
+    /// The time until which the peer is not sent requests, because it
+    /// rejected one of our requests as being one too many.
+    throttled_until: Option<Instant>,
 }
 
 impl Default for PeerState {
@@ -65,6 +71,8 @@ impl Default for PeerState {
         Self {
             storage_summary: None,
             score: STARTING_SCORE,
+            server_protocol_version: None,
+            throttled_until: None,
         }
     }
 }
@@ -135,10 +143,10 @@ impl PeerStates {
         // Storage services can always respond to data advertisement requests.
         // We need this outer check, since we need to be able to send data summary
         // requests to new peers (who don't have a peer state yet).
-        // Likewise, we can always send subscription requests to any peers and
-        // all peers should support versioning.
+        // Likewise, all peers should support versioning. Subscription requests
+        // go through the summary, so that they only go to peers which are not
+        // far behind us (and would otherwise leave the request hanging).
         if request.is_get_storage_server_summary()
-            || request.is_data_subscription_request()
             || matches!(request, StorageServiceRequest::GetServerProtocolVersion)
         {
             return true;
@@ -187,6 +195,36 @@ impl PeerStates {
         }
     }
 
+    /// Updates the protocol version run by the peer
+    pub fn update_server_protocol_version(&mut self, peer: PeerNetworkId, protocol_version: u64) {
+        self.peer_to_state
+            .entry(peer)
+            .or_default()
+            .server_protocol_version = Some(protocol_version);
+    }
+
+    /// Returns true iff the protocol version run by the peer is known
+    pub fn knows_server_protocol_version(&self, peer: &PeerNetworkId) -> bool {
+        self.peer_to_state
+            .get(peer)
+            .and_then(|peer_state| peer_state.server_protocol_version)
+            .is_some()
+    }
+
+    /// Stops sending requests to the peer until the given time
+    pub fn throttle_peer(&mut self, peer: PeerNetworkId, throttled_until: Instant) {
+        self.peer_to_state.entry(peer).or_default().throttled_until = Some(throttled_until);
+    }
+
+    /// Returns true iff requests should not be sent to the peer at the given time
+    pub fn is_throttled(&self, peer: &PeerNetworkId, now: Instant) -> bool {
+        self.peer_to_state
+            .get(peer)
+            .and_then(|peer_state| peer_state.throttled_until)
+            .map(|throttled_until| now < throttled_until)
+            .unwrap_or(false)
+    }
+
     /// Returns the number of in-flight priority polls
     pub fn num_in_flight_priority_polls(&self) -> u64 {
         self.in_flight_priority_polls.len() as u64
@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{AptosDataClient, AptosNetDataClient, DataSummaryPoller, Error};
+use super::{
+    AptosDataClient, AptosNetDataClient, DataSummaryPoller, Error, THROTTLED_PEER_BACKOFF_MS,
+};
 use crate::aptosnet::{poll_peer, state::calculate_optimal_chunk_sizes};
 use aptos_config::{
     config::{AptosDataClientConfig, StorageServiceConfig},
@@ -34,11 +36,14 @@ use storage_service_client::{StorageServiceClient, StorageServiceNetworkSender};
 use storage_service_server::network::{NetworkRequest, ResponseSender};
 use storage_service_types::{
     CompleteDataRange, DataSummary, NewTransactionOutputsWithProofRequest,
-    NewTransactionsWithProofRequest, ProtocolMetadata, StorageServerSummary, StorageServiceError,
-    StorageServiceMessage, StorageServiceRequest, StorageServiceResponse,
+    NewTransactionsWithProofRequest, ProtocolMetadata, ServerProtocolVersion, StorageServerSummary,
+    StorageServiceError, StorageServiceMessage, StorageServiceRequest, StorageServiceResponse,
     TransactionOutputsWithProofRequest, TransactionsWithProofRequest,
+    MAX_SUBSCRIPTION_LAG_VERSIONS,
 };
 
+const SERVER_PROTOCOL_VERSION: u64 = 2;
+
 fn mock_ledger_info(version: Version) -> LedgerInfoWithSignatures {
     LedgerInfoWithSignatures::new(
         LedgerInfo::new(
@@ -151,25 +156,39 @@ impl MockNetwork {
             .unwrap();
     }
 
-    /// Get the next request sent from the client.
+    /// Get the next request sent from the client. The protocol version
+    /// requests (sent before the first poll of each peer) are answered directly.
     async fn next_request(&mut self) -> Option<NetworkRequest> {
-        match self.peer_mgr_reqs_rx.next().await {
-            Some(PeerManagerRequest::SendRpc(peer_id, network_request)) => {
-                let protocol = network_request.protocol_id;
-                let data = network_request.data;
-                let res_tx = network_request.res_tx;
-
-                let message: StorageServiceMessage = bcs::from_bytes(data.as_ref()).unwrap();
-                let request = match message {
-                    StorageServiceMessage::Request(request) => request,
-                    _ => panic!("unexpected: {:?}", message),
-                };
-                let response_sender = ResponseSender::new(res_tx);
-
-                Some((peer_id, protocol, request, response_sender))
+        loop {
+            match self.peer_mgr_reqs_rx.next().await {
+                Some(PeerManagerRequest::SendRpc(peer_id, network_request)) => {
+                    let protocol = network_request.protocol_id;
+                    let data = network_request.data;
+                    let res_tx = network_request.res_tx;
+
+                    let message: StorageServiceMessage = bcs::from_bytes(data.as_ref()).unwrap();
+                    let request = match message {
+                        StorageServiceMessage::Request(request) => request,
+                        _ => panic!("unexpected: {:?}", message),
+                    };
+                    let response_sender = ResponseSender::new(res_tx);
+
+                    if request == StorageServiceRequest::GetServerProtocolVersion {
+                        response_sender.send(Ok(StorageServiceResponse::ServerProtocolVersion(
+                            ServerProtocolVersion {
+                                protocol_version: SERVER_PROTOCOL_VERSION,
+                            },
+                        )));
+                        continue;
+                    }
+
+                    return Some((peer_id, protocol, request, response_sender));
+                }
+                Some(PeerManagerRequest::SendDirectSend(_, _)) => {
+                    panic!("Unexpected direct send msg")
+                }
+                None => return None,
             }
-            Some(PeerManagerRequest::SendDirectSend(_, _)) => panic!("Unexpected direct send msg"),
-            None => None,
         }
     }
 }
@@ -651,6 +670,7 @@ async fn prioritized_peer_request_selection() {
 
         // Add a regular peer and verify the peer is selected as the recipient
         let regular_peer_1 = mock_network.add_peer(false);
+        advertise_if_subscription(&client, regular_peer_1, &storage_request);
         assert_eq!(
             client.choose_peer_for_request(&storage_request),
             Ok(regular_peer_1)
@@ -658,6 +678,7 @@ async fn prioritized_peer_request_selection() {
 
         // Add a priority peer and verify the peer is selected as the recipient
         let priority_peer_1 = mock_network.add_peer(true);
+        advertise_if_subscription(&client, priority_peer_1, &storage_request);
         assert_eq!(
             client.choose_peer_for_request(&storage_request),
             Ok(priority_peer_1)
@@ -672,6 +693,7 @@ async fn prioritized_peer_request_selection() {
 
         // Connect a new priority peer and verify it is now selected
         let priority_peer_2 = mock_network.add_peer(true);
+        advertise_if_subscription(&client, priority_peer_2, &storage_request);
         assert_eq!(
             client.choose_peer_for_request(&storage_request),
             Ok(priority_peer_2)
@@ -756,6 +778,65 @@ async fn all_peer_request_selection() {
     assert!(peer_for_request == priority_peer_1 || peer_for_request == regular_peer_1);
 }
 
+#[tokio::test]
+async fn subscription_request_selection() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None);
+
+    // Add a priority peer and a regular peer, neither of which has advertised data
+    let priority_peer = mock_network.add_peer(true);
+    let regular_peer = mock_network.add_peer(false);
+    let known_version = MAX_SUBSCRIPTION_LAG_VERSIONS + 100;
+    let request = new_outputs_request(known_version);
+    assert_matches!(
+        client.choose_peer_for_request(&request),
+        Err(Error::DataIsUnavailable(_))
+    );
+
+    // The priority peer is far behind us, so only the regular peer can be chosen
+    client.update_summary(priority_peer, mock_storage_summary(50));
+    client.update_summary(regular_peer, mock_storage_summary(known_version));
+    assert_eq!(client.choose_peer_for_request(&request), Ok(regular_peer));
+
+    // Once the priority peer catches up, it is preferred again
+    client.update_summary(priority_peer, mock_storage_summary(known_version + 50));
+    assert_eq!(client.choose_peer_for_request(&request), Ok(priority_peer));
+
+    // If all the peers are far behind us, the request cannot be serviced
+    client.update_summary(priority_peer, mock_storage_summary(99));
+    client.update_summary(regular_peer, mock_storage_summary(99));
+    assert_matches!(
+        client.choose_peer_for_request(&request),
+        Err(Error::DataIsUnavailable(_))
+    );
+}
+
+#[tokio::test]
+async fn chained_subscriptions_without_summary_refresh() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, _, client, _) = MockNetwork::new(None);
+
+    // The peer advertises the version we're at
+    let peer = mock_network.add_peer(true);
+    client.update_summary(peer, mock_storage_summary(100));
+    assert_eq!(
+        client.choose_peer_for_request(&new_outputs_request(100)),
+        Ok(peer)
+    );
+
+    // The subscription response moves us past the last advertised version of
+    // the peer, and the next subscription is chained before its summary is
+    // polled again
+    assert_eq!(
+        client.choose_peer_for_request(&new_outputs_request(150)),
+        Ok(peer)
+    );
+    assert_eq!(
+        client.choose_peer_for_request(&new_outputs_request(100 + MAX_SUBSCRIPTION_LAG_VERSIONS)),
+        Ok(peer)
+    );
+}
+
 // 1. 2 peers
 // 2. one advertises bad range, one advertises honest range
 // 3. sending a bunch of requests to the bad range (which will always go to the
@@ -840,6 +921,66 @@ async fn bad_peer_is_eventually_banned_internal() {
     assert_eq!(response.payload, TransactionListWithProof::new_empty());
 }
 
+#[tokio::test]
+async fn overloaded_peer_is_throttled_but_not_banned() {
+    ::aptos_logger::Logger::init_for_testing();
+    let (mut mock_network, mock_time, client, _) = MockNetwork::new(None);
+
+    // Add a peer that advertises txns 0 -> 100
+    let peer = mock_network.add_peer(true);
+    client.update_summary(peer, mock_storage_summary(100));
+
+    // Spawn a handler that rejects the first requests as being too many
+    let num_rejected_requests = 20;
+    tokio::spawn(async move {
+        let mut num_requests = 0;
+        while let Some((_, _, _, response_sender)) = mock_network.next_request().await {
+            num_requests += 1;
+            if num_requests <= num_rejected_requests {
+                response_sender.send(Err(StorageServiceError::TooManyRequests(
+                    "Too many requests!".into(),
+                )));
+            } else {
+                response_sender.send(Ok(StorageServiceResponse::TransactionsWithProof(
+                    TransactionListWithProof::new_empty(),
+                )));
+            }
+        }
+    });
+
+    let storage_request =
+        StorageServiceRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version: 100,
+            start_version: 50,
+            end_version: 100,
+            include_events: false,
+        });
+    for _ in 0..num_rejected_requests {
+        // Verify the rejected request is reported as unavailable data
+        let result = client
+            .get_transactions_with_proof(100, 50, 100, false)
+            .await;
+        assert_matches!(result, Err(Error::DataIsUnavailable(_)));
+
+        // Verify the peer is not chosen until the backoff elapses
+        assert_matches!(
+            client.choose_peer_for_request(&storage_request),
+            Err(Error::DataIsUnavailable(_))
+        );
+        mock_time
+            .advance_async(Duration::from_millis(THROTTLED_PEER_BACKOFF_MS))
+            .await;
+        assert_eq!(client.choose_peer_for_request(&storage_request), Ok(peer));
+    }
+
+    // Verify the peer was not banned for being overloaded
+    let response = client
+        .get_transactions_with_proof(100, 50, 100, false)
+        .await
+        .unwrap();
+    assert_eq!(response.payload, TransactionListWithProof::new_empty());
+}
+
 #[tokio::test]
 async fn bad_peer_is_eventually_banned_callback() {
     ::aptos_logger::Logger::init_for_testing();
@@ -986,6 +1127,8 @@ async fn optimal_chunk_size_calculations() {
     let storage_service_config = StorageServiceConfig {
         max_account_states_chunk_sizes,
         max_concurrent_requests: 0,
+        max_concurrent_chunk_requests: 0,
+        max_concurrent_requests_per_peer: 0,
         max_epoch_chunk_size,
         max_lru_cache_size: 0,
         max_network_channel_size: 0,
@@ -1065,6 +1208,29 @@ fn fetch_peer_to_poll(
     result
 }
 
+/// A helper method that advertises data for the peer if the request is a
+/// subscription (subscriptions are only sent to peers that aren't behind us).
+fn advertise_if_subscription(
+    client: &AptosNetDataClient,
+    peer: PeerNetworkId,
+    request: &StorageServiceRequest,
+) {
+    if request.is_data_subscription_request() {
+        client.update_summary(peer, mock_storage_summary(10_000));
+    }
+}
+
+/// A helper method that creates a subscription to the transaction outputs
+/// after `known_version`
+fn new_outputs_request(known_version: Version) -> StorageServiceRequest {
+    StorageServiceRequest::GetNewTransactionOutputsWithProof(
+        NewTransactionOutputsWithProofRequest {
+            known_version,
+            known_epoch: 1,
+        },
+    )
+}
+
 /// Fetches the number of in flight requests for peers depending on priority
 fn get_num_in_flight_polls(client: AptosNetDataClient, is_priority_peer: bool) -> u64 {
     if is_priority_peer {
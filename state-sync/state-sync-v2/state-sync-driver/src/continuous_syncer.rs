@@ -10,6 +10,7 @@ use crate::{
     utils::{SpeculativeStreamState, PENDING_DATA_LOG_FREQ_SECS},
 };
 use aptos_config::config::ContinuousSyncingMode;
+use aptos_data_client::GlobalDataSummary;
 use aptos_infallible::Mutex;
 use aptos_logger::{prelude::*, sample, sample::SampleRate};
 use aptos_types::{
@@ -29,6 +30,10 @@ pub struct ContinuousSyncer<StorageSyncer, StreamingClient> {
     // The currently active data stream (provided by the data streaming service)
     active_data_stream: Option<DataStreamListener>,
 
+    // Whether the active data stream carries transaction outputs to apply (or
+    // transactions to execute)
+    active_stream_applies_outputs: bool,
+
     // The config of the state sync driver
     driver_configuration: DriverConfiguration,
 
@@ -58,6 +63,7 @@ impl<
     ) -> Self {
         Self {
             active_data_stream: None,
+            active_stream_applies_outputs: false,
             driver_configuration,
             speculative_stream_state: None,
             streaming_client,
@@ -70,6 +76,7 @@ impl<
     pub async fn drive_progress(
         &mut self,
         consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
+        global_data_summary: &GlobalDataSummary,
     ) -> Result<(), Error> {
         if self.active_data_stream.is_some() {
             // We have an active data stream. Process any notifications!
@@ -84,7 +91,7 @@ impl<
             Ok(())
         } else {
             // Fetch a new data stream to start streaming data
-            self.initialize_active_data_stream(consensus_sync_request)
+            self.initialize_active_data_stream(consensus_sync_request, global_data_summary)
                 .await
         }
     }
@@ -93,6 +100,7 @@ impl<
     async fn initialize_active_data_stream(
         &mut self,
         consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
+        global_data_summary: &GlobalDataSummary,
     ) -> Result<(), Error> {
         // Fetch the highest synced version and epoch (in storage)
         let (highest_synced_version, highest_synced_epoch) =
@@ -113,27 +121,27 @@ impl<
         }
 
         // Initialize a new active data stream
-        let active_data_stream = match self.driver_configuration.config.continuous_syncing_mode {
-            ContinuousSyncingMode::ApplyTransactionOutputs => {
-                self.streaming_client
-                    .continuously_stream_transaction_outputs(
-                        highest_synced_version,
-                        highest_synced_epoch,
-                        sync_request_target,
-                    )
-                    .await?
-            }
-            ContinuousSyncingMode::ExecuteTransactions => {
-                self.streaming_client
-                    .continuously_stream_transactions(
-                        highest_synced_version,
-                        highest_synced_epoch,
-                        false,
-                        sync_request_target,
-                    )
-                    .await?
-            }
+        let apply_transaction_outputs =
+            self.should_apply_transaction_outputs(highest_synced_version, global_data_summary);
+        let active_data_stream = if apply_transaction_outputs {
+            self.streaming_client
+                .continuously_stream_transaction_outputs(
+                    highest_synced_version,
+                    highest_synced_epoch,
+                    sync_request_target,
+                )
+                .await?
+        } else {
+            self.streaming_client
+                .continuously_stream_transactions(
+                    highest_synced_version,
+                    highest_synced_epoch,
+                    false,
+                    sync_request_target,
+                )
+                .await?
         };
+        self.active_stream_applies_outputs = apply_transaction_outputs;
         self.speculative_stream_state = Some(SpeculativeStreamState::new(
             highest_epoch_state,
             None,
@@ -144,6 +152,39 @@ impl<
         Ok(())
     }
 
+    /// Returns true iff the next data stream should carry transaction outputs to
+    /// apply, rather than transactions to execute. In adaptive mode, outputs are
+    /// applied when the node is close enough to its peers for them to serve the
+    /// outputs of all the missing versions, see `max_lag_to_apply_outputs`.
+    fn should_apply_transaction_outputs(
+        &self,
+        highest_synced_version: Version,
+        global_data_summary: &GlobalDataSummary,
+    ) -> bool {
+        let config = &self.driver_configuration.config;
+        match config.continuous_syncing_mode {
+            ContinuousSyncingMode::ApplyTransactionOutputs => true,
+            ContinuousSyncingMode::ExecuteTransactions => false,
+            ContinuousSyncingMode::Adaptive => {
+                let apply_transaction_outputs = utils::is_within_output_sync_lag(
+                    highest_synced_version,
+                    config.max_lag_to_apply_outputs,
+                    &global_data_summary.advertised_data,
+                );
+                info!(
+                    "Continuously syncing from version {:?} by {}.",
+                    highest_synced_version,
+                    if apply_transaction_outputs {
+                        "applying transaction outputs"
+                    } else {
+                        "executing transactions"
+                    }
+                );
+                apply_transaction_outputs
+            }
+        }
+    }
+
     /// Attempts to fetch a data notification from the active stream
     async fn fetch_next_data_notification(&mut self) -> Result<DataNotification, Error> {
         let max_stream_wait_time_ms = self.driver_configuration.config.max_stream_wait_time_ms;
@@ -245,53 +286,47 @@ impl<
         .await?;
 
         // Execute/apply and commit the transactions/outputs
-        let num_transactions_or_outputs =
-            match self.driver_configuration.config.continuous_syncing_mode {
-                ContinuousSyncingMode::ApplyTransactionOutputs => {
-                    if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
-                        let num_transaction_outputs = transaction_outputs_with_proof
-                            .transactions_and_outputs
-                            .len();
-                        self.storage_synchronizer.apply_transaction_outputs(
-                            notification_id,
-                            transaction_outputs_with_proof,
-                            ledger_info_with_signatures,
-                            None,
-                        )?;
-                        num_transaction_outputs
-                    } else {
-                        self.terminate_active_stream(
-                            notification_id,
-                            NotificationFeedback::PayloadTypeIsIncorrect,
-                        )
-                        .await?;
-                        return Err(Error::InvalidPayload(
-                            "Did not receive transaction outputs with proof!".into(),
-                        ));
-                    }
-                }
-                ContinuousSyncingMode::ExecuteTransactions => {
-                    if let Some(transaction_list_with_proof) = transaction_list_with_proof {
-                        let num_transactions = transaction_list_with_proof.transactions.len();
-                        self.storage_synchronizer.execute_transactions(
-                            notification_id,
-                            transaction_list_with_proof,
-                            ledger_info_with_signatures,
-                            None,
-                        )?;
-                        num_transactions
-                    } else {
-                        self.terminate_active_stream(
-                            notification_id,
-                            NotificationFeedback::PayloadTypeIsIncorrect,
-                        )
-                        .await?;
-                        return Err(Error::InvalidPayload(
-                            "Did not receive transactions with proof!".into(),
-                        ));
-                    }
-                }
-            };
+        let num_transactions_or_outputs = if self.active_stream_applies_outputs {
+            if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
+                let num_transaction_outputs = transaction_outputs_with_proof
+                    .transactions_and_outputs
+                    .len();
+                self.storage_synchronizer.apply_transaction_outputs(
+                    notification_id,
+                    transaction_outputs_with_proof,
+                    ledger_info_with_signatures,
+                    None,
+                )?;
+                num_transaction_outputs
+            } else {
+                self.terminate_active_stream(
+                    notification_id,
+                    NotificationFeedback::PayloadTypeIsIncorrect,
+                )
+                .await?;
+                return Err(Error::InvalidPayload(
+                    "Did not receive transaction outputs with proof!".into(),
+                ));
+            }
+        } else if let Some(transaction_list_with_proof) = transaction_list_with_proof {
+            let num_transactions = transaction_list_with_proof.transactions.len();
+            self.storage_synchronizer.execute_transactions(
+                notification_id,
+                transaction_list_with_proof,
+                ledger_info_with_signatures,
+                None,
+            )?;
+            num_transactions
+        } else {
+            self.terminate_active_stream(
+                notification_id,
+                NotificationFeedback::PayloadTypeIsIncorrect,
+            )
+            .await?;
+            return Err(Error::InvalidPayload(
+                "Did not receive transactions with proof!".into(),
+            ));
+        };
         let synced_version = payload_start_version
             .checked_add(num_transactions_or_outputs as u64)
             .and_then(|version| version.checked_sub(1)) // synced_version = start + num txns/outputs - 1
@@ -538,7 +538,7 @@ impl<
             // Attempt to continuously sync
             if let Err(error) = self
                 .continuous_syncer
-                .drive_progress(consensus_sync_request)
+                .drive_progress(consensus_sync_request, &global_data_summary)
                 .await
             {
                 sample!(
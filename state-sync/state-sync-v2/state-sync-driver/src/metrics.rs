@@ -9,6 +9,9 @@ pub const DRIVER_CLIENT_NOTIFICATION: &str = "driver_client_notification";
 pub const DRIVER_CONSENSUS_COMMIT_NOTIFICATION: &str = "driver_consensus_commit_notification";
 pub const DRIVER_CONSENSUS_SYNC_NOTIFICATION: &str = "driver_consensus_sync_notification";
 pub const STORAGE_SYNCHRONIZER_PENDING_DATA: &str = "storage_synchronizer_pending_data";
+pub const OUTPUT_VERIFICATION_MATCH: &str = "match";
+pub const OUTPUT_VERIFICATION_MISMATCH: &str = "mismatch";
+pub const OUTPUT_VERIFICATION_ERROR: &str = "error";
 
 /// An enum of storage synchronizer operations performed by state sync
 pub enum StorageSynchronizerOperations {
@@ -73,6 +76,17 @@ pub static STORAGE_SYNCHRONIZER_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for the chunks of outputs re-executed by the storage synchronizer
+/// to verify them, by whether execution matched the outputs (or failed to run).
+pub static STORAGE_SYNCHRONIZER_OUTPUT_VERIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_storage_synchronizer_output_verifications",
+        "Counters related to the verification of outputs by re-execution",
+        &["result"]
+    )
+    .unwrap()
+});
+
 /// Gauges related to the storage synchronizer
 pub static STORAGE_SYNCHRONIZER_GAUGES: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -169,6 +169,7 @@ impl<ChunkExecutor: ChunkExecutorTrait + 'static> StorageSynchronizer<ChunkExecu
         let runtime = runtime.map(|runtime| runtime.handle().clone());
         let executor_handle = spawn_executor(
             chunk_executor.clone(),
+            driver_config.output_verification_sample_rate,
             error_notification_sender.clone(),
             executor_listener,
             committer_notifier,
@@ -344,6 +345,7 @@ enum StorageDataChunk {
 /// Spawns a dedicated executor that executes/applies storage data chunks
 fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     chunk_executor: Arc<ChunkExecutor>,
+    output_verification_sample_rate: u64,
     error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
     mut executor_listener: mpsc::Receiver<StorageDataChunk>,
     mut committer_notifier: mpsc::Sender<NotificationId>,
@@ -352,6 +354,7 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
 ) -> JoinHandle<()> {
     // Create an executor
     let executor = async move {
+        let mut num_output_chunks: u64 = 0;
         loop {
             ::futures::select! {
                 storage_data_chunk = executor_listener.select_next_some() => {
@@ -377,12 +380,24 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
                         },
                         StorageDataChunk::TransactionOutputs(notification_id, outputs_with_proof, target_ledger_info, end_of_epoch_ledger_info) => {
                             let num_outputs = outputs_with_proof.transactions_and_outputs.len();
-                            let result = chunk_executor
-                                .apply_chunk(
+                            num_output_chunks += 1;
+                            let result = if output_verification_sample_rate > 0
+                                && num_output_chunks % output_verification_sample_rate == 0
+                            {
+                                execute_and_verify_outputs(
+                                    chunk_executor.as_ref(),
                                     outputs_with_proof,
                                     &target_ledger_info,
                                     end_of_epoch_ledger_info.as_ref(),
-                                );
+                                )
+                            } else {
+                                chunk_executor
+                                    .apply_chunk(
+                                        outputs_with_proof,
+                                        &target_ledger_info,
+                                        end_of_epoch_ledger_info.as_ref(),
+                                    )
+                            };
                             if result.is_ok() {
                                 metrics::increment_gauge(
                                     &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
@@ -423,6 +438,82 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     spawn(runtime, executor)
 }
 
+/// Executes the transactions of the given chunk of outputs, instead of applying
+/// the outputs, to verify that execution produces the same outputs (and state).
+/// If it doesn't, the outputs are applied anyway (they are proven by the ledger
+/// info, so it's our execution that diverges) and the mismatch is reported.
+/// Any other execution error leaves the verification inconclusive: the outputs
+/// are applied and the error is counted separately from a mismatch.
+fn execute_and_verify_outputs<ChunkExecutor: ChunkExecutorTrait>(
+    chunk_executor: &ChunkExecutor,
+    outputs_with_proof: TransactionOutputListWithProof,
+    target_ledger_info: &LedgerInfoWithSignatures,
+    end_of_epoch_ledger_info: Option<&LedgerInfoWithSignatures>,
+) -> anyhow::Result<()> {
+    let transactions = outputs_with_proof
+        .transactions_and_outputs
+        .iter()
+        .map(|(transaction, _)| transaction.clone())
+        .collect();
+    let transactions_with_proof = TransactionListWithProof::new(
+        transactions,
+        None,
+        outputs_with_proof.first_transaction_output_version,
+        outputs_with_proof.proof.clone(),
+    );
+    let execution_error = match chunk_executor.execute_chunk(
+        transactions_with_proof,
+        target_ledger_info,
+        end_of_epoch_ledger_info,
+    ) {
+        Ok(()) => {
+            metrics::increment_counter(
+                &metrics::STORAGE_SYNCHRONIZER_OUTPUT_VERIFICATIONS,
+                metrics::OUTPUT_VERIFICATION_MATCH,
+            );
+            return Ok(());
+        }
+        Err(error) => error,
+    };
+
+    // If the outputs can't be applied either, the chunk itself is invalid
+    let first_version = outputs_with_proof.first_transaction_output_version;
+    chunk_executor.apply_chunk(
+        outputs_with_proof,
+        target_ledger_info,
+        end_of_epoch_ledger_info,
+    )?;
+    if matches!(
+        execution_error.downcast_ref::<executor_types::Error>(),
+        Some(executor_types::Error::TransactionInfoMismatch { .. })
+    ) {
+        error!(
+            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                "Executing the transactions doesn't match the outputs applied! \
+            First version: {:?}, error: {:?}",
+                first_version, execution_error
+            ))
+        );
+        metrics::increment_counter(
+            &metrics::STORAGE_SYNCHRONIZER_OUTPUT_VERIFICATIONS,
+            metrics::OUTPUT_VERIFICATION_MISMATCH,
+        );
+    } else {
+        warn!(
+            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                "Failed to execute the transactions to verify the outputs applied! \
+            First version: {:?}, error: {:?}",
+                first_version, execution_error
+            ))
+        );
+        metrics::increment_counter(
+            &metrics::STORAGE_SYNCHRONIZER_OUTPUT_VERIFICATIONS,
+            metrics::OUTPUT_VERIFICATION_ERROR,
+        );
+    }
+    Ok(())
+}
+
 /// Spawns a dedicated committer that commits executed (but pending) chunks
 fn spawn_committer<
     ChunkExecutor: ChunkExecutorTrait + 'static,
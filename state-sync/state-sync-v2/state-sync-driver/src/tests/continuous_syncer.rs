@@ -13,12 +13,13 @@ use crate::{
         },
         utils::{
             create_data_stream_listener, create_epoch_ending_ledger_info,
-            create_full_node_driver_configuration, create_startup_info_at_version_epoch,
-            create_transaction_info,
+            create_full_node_driver_configuration, create_global_summary_with_outputs,
+            create_startup_info_at_version_epoch, create_transaction_info,
         },
     },
 };
 use aptos_config::config::ContinuousSyncingMode;
+use aptos_data_client::GlobalDataSummary;
 use aptos_infallible::Mutex;
 use aptos_types::transaction::{TransactionOutputListWithProof, Version};
 use claim::assert_matches;
@@ -72,14 +73,14 @@ async fn test_critical_timeout() {
     // Drive progress to initialize the transaction output stream
     let no_sync_request = Arc::new(Mutex::new(None));
     continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap();
 
     // Drive progress twice and verify we get non-critical timeouts
     for _ in 0..2 {
         let error = continuous_syncer
-            .drive_progress(no_sync_request.clone())
+            .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
             .await
             .unwrap_err();
         assert_matches!(error, Error::DataStreamNotificationTimeout(_));
@@ -87,20 +88,20 @@ async fn test_critical_timeout() {
 
     // Drive progress again and verify we get a critical timeout
     let error = continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap_err();
     assert_matches!(error, Error::CriticalDataStreamTimeout(_));
 
     // Drive progress to initialize the transaction output stream again
     continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap();
 
     // Drive progress again and verify we get a non-critical timeout
     let error = continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap_err();
     assert_matches!(error, Error::DataStreamNotificationTimeout(_));
@@ -159,7 +160,7 @@ async fn test_data_stream_transactions_with_target() {
         consensus_sync_notification,
     ))));
     continuous_syncer
-        .drive_progress(sync_request.clone())
+        .drive_progress(sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap();
 
@@ -175,14 +176,14 @@ async fn test_data_stream_transactions_with_target() {
 
     // Drive progress again and ensure we get a verification error
     let error = continuous_syncer
-        .drive_progress(sync_request.clone())
+        .drive_progress(sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap_err();
     assert_matches!(error, Error::VerificationError(_));
 
     // Drive progress to initialize the transaction output stream
     continuous_syncer
-        .drive_progress(sync_request.clone())
+        .drive_progress(sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap();
 }
@@ -235,7 +236,7 @@ async fn test_data_stream_transaction_outputs() {
     // Drive progress to initialize the transaction output stream
     let no_sync_request = Arc::new(Mutex::new(None));
     continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap();
 
@@ -254,14 +255,103 @@ async fn test_data_stream_transaction_outputs() {
 
     // Drive progress again and ensure we get a verification error
     let error = continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
         .await
         .unwrap_err();
     assert_matches!(error, Error::VerificationError(_));
 
     // Drive progress to initialize the transaction output stream
     continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(no_sync_request.clone(), &GlobalDataSummary::empty())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_adaptive_syncing_mode() {
+    // Create test data
+    let current_synced_epoch = 10;
+    let current_synced_version = 1000;
+    let max_lag_to_apply_outputs = 100;
+
+    // The highest advertised version, the lowest advertised output version, and
+    // whether outputs should be applied
+    let test_cases = [
+        (current_synced_version + 100, 0, true),
+        (current_synced_version, 0, true),
+        (current_synced_version + 101, 0, false),
+        (
+            current_synced_version + 50,
+            current_synced_version + 10,
+            false,
+        ),
+    ];
+    for (highest_version, lowest_output_version, apply_outputs) in test_cases {
+        // Create a driver configuration
+        let mut driver_configuration = create_full_node_driver_configuration();
+        driver_configuration.config.continuous_syncing_mode = ContinuousSyncingMode::Adaptive;
+        driver_configuration.config.max_lag_to_apply_outputs = max_lag_to_apply_outputs;
+
+        // Create the mock streaming client
+        let mut mock_streaming_client = create_mock_streaming_client();
+        let (_notification_sender, data_stream_listener) = create_data_stream_listener();
+        if apply_outputs {
+            mock_streaming_client
+                .expect_continuously_stream_transaction_outputs()
+                .times(1)
+                .with(
+                    eq(current_synced_version),
+                    eq(current_synced_epoch),
+                    eq(None),
+                )
+                .return_once(move |_, _, _| Ok(data_stream_listener));
+        } else {
+            mock_streaming_client
+                .expect_continuously_stream_transactions()
+                .times(1)
+                .with(
+                    eq(current_synced_version),
+                    eq(current_synced_epoch),
+                    eq(false),
+                    eq(None),
+                )
+                .return_once(move |_, _, _, _| Ok(data_stream_listener));
+        }
+
+        // Create the continuous syncer
+        let mut continuous_syncer = create_continuous_syncer(
+            driver_configuration,
+            mock_streaming_client,
+            current_synced_version,
+            current_synced_epoch,
+        );
+
+        // Drive progress to initialize the stream of the expected type
+        let global_data_summary =
+            create_global_summary_with_outputs(highest_version, lowest_output_version);
+        continuous_syncer
+            .drive_progress(Arc::new(Mutex::new(None)), &global_data_summary)
+            .await
+            .unwrap();
+    }
+
+    // Verify transactions are executed when the peers advertise nothing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode = ContinuousSyncingMode::Adaptive;
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (_notification_sender, data_stream_listener) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_continuously_stream_transactions()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(data_stream_listener));
+    let mut continuous_syncer = create_continuous_syncer(
+        driver_configuration,
+        mock_streaming_client,
+        current_synced_version,
+        current_synced_epoch,
+    );
+    continuous_syncer
+        .drive_progress(Arc::new(Mutex::new(None)), &GlobalDataSummary::empty())
         .await
         .unwrap();
 }
@@ -39,7 +39,8 @@ use executor_types::{ChunkCommitNotification, ChunkExecutorTrait};
 use mockall::mock;
 use std::sync::Arc;
 use storage_interface::{
-    DbReader, DbReaderWriter, DbWriter, Order, StartupInfo, StateSnapshotReceiver, TreeState,
+    DbReaderWriter, DbWriter, EventReader, LedgerReader, Order, StartupInfo, StateReader,
+    StateSnapshotReceiver, TreeState,
 };
 use tokio::task::JoinHandle;
 
@@ -151,7 +152,7 @@ mock! {
 // This automatically creates a MockDatabaseReader.
 mock! {
     pub DatabaseReader {}
-    impl DbReader for DatabaseReader {
+    impl LedgerReader for DatabaseReader {
         fn get_epoch_ending_ledger_infos(
             &self,
             start_epoch: u64,
@@ -187,40 +188,14 @@ mock! {
             ledger_version: Version,
         ) -> Result<TransactionOutputListWithProof>;
 
-        fn get_events(
-            &self,
-            event_key: &EventKey,
-            start: u64,
-            order: Order,
-            limit: u64,
-        ) -> Result<Vec<(u64, ContractEvent)>>;
-
-        fn get_events_with_proofs(
-            &self,
-            event_key: &EventKey,
-            start: u64,
-            order: Order,
-            limit: u64,
-            known_version: Option<u64>,
-        ) -> Result<Vec<EventWithProof>>;
-
         fn get_block_timestamp(&self, version: u64) -> Result<u64>;
 
-        fn get_event_by_version_with_proof(
-            &self,
-            event_key: &EventKey,
-            event_version: u64,
-            proof_version: u64,
-        ) -> Result<EventByVersionWithProof>;
-
         fn get_last_version_before_timestamp(
             &self,
             _timestamp: u64,
             _ledger_version: Version,
         ) -> Result<Version>;
 
-        fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>>;
-
         fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>>;
 
         fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures>;
@@ -258,21 +233,6 @@ mock! {
 
         fn get_state_proof(&self, known_version: u64) -> Result<StateProof>;
 
-        fn get_state_value_with_proof(
-            &self,
-            state_key: StateKey,
-            version: Version,
-            ledger_version: Version,
-        ) -> Result<StateValueWithProof>;
-
-        fn get_state_value_with_proof_by_version(
-            &self,
-            state_key: &StateKey,
-            version: Version,
-        ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)>;
-
-        fn get_latest_tree_state(&self) -> Result<TreeState>;
-
         fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures>;
 
         fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>>;
@@ -289,6 +249,25 @@ mock! {
             &self,
             ledger_version: Version,
         ) -> Result<TransactionAccumulatorSummary>;
+    }
+
+    impl StateReader for DatabaseReader {
+        fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>>;
+
+        fn get_state_value_with_proof(
+            &self,
+            state_key: StateKey,
+            version: Version,
+            ledger_version: Version,
+        ) -> Result<StateValueWithProof>;
+
+        fn get_state_value_with_proof_by_version(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)>;
+
+        fn get_latest_tree_state(&self) -> Result<TreeState>;
 
         fn get_state_leaf_count(&self, version: Version) -> Result<usize>;
 
@@ -301,6 +280,32 @@ mock! {
 
         fn get_state_prune_window(&self) -> Result<Option<usize>>;
     }
+
+    impl EventReader for DatabaseReader {
+        fn get_events(
+            &self,
+            event_key: &EventKey,
+            start: u64,
+            order: Order,
+            limit: u64,
+        ) -> Result<Vec<(u64, ContractEvent)>>;
+
+        fn get_events_with_proofs(
+            &self,
+            event_key: &EventKey,
+            start: u64,
+            order: Order,
+            limit: u64,
+            known_version: Option<u64>,
+        ) -> Result<Vec<EventWithProof>>;
+
+        fn get_event_by_version_with_proof(
+            &self,
+            event_key: &EventKey,
+            event_version: u64,
+            proof_version: u64,
+        ) -> Result<EventByVersionWithProof>;
+    }
 }
 
 // This automatically creates a MockDatabaseWriter.
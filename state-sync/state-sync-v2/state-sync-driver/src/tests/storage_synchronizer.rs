@@ -3,6 +3,7 @@
 
 use crate::{
     error::Error,
+    metrics,
     notification_handlers::{
         CommitNotification, CommitNotificationListener, CommittedTransactions,
         ErrorNotificationListener, MempoolNotificationHandler,
@@ -34,7 +35,13 @@ use executor_types::ChunkCommitNotification;
 use futures::StreamExt;
 use mempool_notifications::{CommittedTransaction, MempoolNotificationListener};
 use mockall::predicate::{always, eq};
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use storage_interface::DbReaderWriter;
 use tokio::task::JoinHandle;
 
@@ -128,6 +135,191 @@ async fn test_apply_transaction_outputs_error() {
     verify_no_pending_data(&storage_synchronizer);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apply_transaction_outputs_verified() {
+    // Create test data
+    let transaction_to_commit = create_transaction();
+    let event_to_commit = create_event();
+
+    // Setup the mock executor (the outputs are executed, not applied)
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    chunk_executor
+        .expect_apply_chunk()
+        .with(always(), always(), always())
+        .times(0);
+    let expected_commit_return = Ok(ChunkCommitNotification {
+        committed_events: vec![event_to_commit.clone()],
+        committed_transactions: vec![transaction_to_commit.clone()],
+        reconfiguration_occurred: false,
+    });
+    chunk_executor
+        .expect_commit_chunk()
+        .return_once(move || expected_commit_return);
+
+    // Set up the mock db reader
+    let mut db_reader = create_mock_db_reader();
+    db_reader
+        .expect_get_startup_info()
+        .returning(|| Ok(Some(create_startup_info())));
+
+    // Create the storage synchronizer and verify every chunk of outputs
+    let driver_config = StateSyncDriverConfig {
+        output_verification_sample_rate: 1,
+        ..Default::default()
+    };
+    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_config(
+            driver_config,
+            chunk_executor,
+            create_mock_reader_writer(Some(db_reader), None),
+        );
+
+    // Subscribe to the expected event
+    let mut event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event_to_commit.key()])
+        .unwrap();
+
+    // Attempt to apply a chunk of outputs
+    storage_synchronizer
+        .apply_transaction_outputs(
+            0,
+            create_output_list_with_proof(),
+            create_epoch_ending_ledger_info(),
+            None,
+        )
+        .unwrap();
+
+    // Verify the executed chunk is committed and there's no pending data
+    verify_mempool_and_event_notification(
+        Some(&mut event_listener),
+        &mut mempool_listener,
+        vec![transaction_to_commit],
+        vec![event_to_commit],
+    )
+    .await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apply_transaction_outputs_verification_mismatch() {
+    let num_mismatches = get_output_verifications(metrics::OUTPUT_VERIFICATION_MISMATCH);
+
+    // Apply a chunk of outputs that execution doesn't match
+    let execution_error = executor_types::Error::TransactionInfoMismatch {
+        version: 0,
+        txn_info: "executed".into(),
+        expected_txn_info: "expected".into(),
+    };
+    apply_outputs_with_execution_error(execution_error.into()).await;
+
+    // Verify the mismatch is counted
+    assert_eq!(
+        get_output_verifications(metrics::OUTPUT_VERIFICATION_MISMATCH),
+        num_mismatches + 1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apply_transaction_outputs_verification_error() {
+    let num_errors = get_output_verifications(metrics::OUTPUT_VERIFICATION_ERROR);
+
+    // Apply a chunk of outputs that can't be executed
+    apply_outputs_with_execution_error(format_err!("Failed to execute the chunk!")).await;
+
+    // Verify the error is counted
+    assert_eq!(
+        get_output_verifications(metrics::OUTPUT_VERIFICATION_ERROR),
+        num_errors + 1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_apply_transaction_outputs_sampled() {
+    // Create test data
+    let transaction_to_commit = create_transaction();
+    let event_to_commit = create_event();
+
+    // Setup the mock executor and count the chunks executed and applied
+    let num_executed = Arc::new(AtomicU64::new(0));
+    let num_applied = Arc::new(AtomicU64::new(0));
+    let mut chunk_executor = create_mock_executor();
+    let executed = num_executed.clone();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(move |_, _, _| {
+            executed.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        });
+    let applied = num_applied.clone();
+    chunk_executor
+        .expect_apply_chunk()
+        .with(always(), always(), always())
+        .returning(move |_, _, _| {
+            applied.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        });
+    let (committed_transaction, committed_event) =
+        (transaction_to_commit.clone(), event_to_commit.clone());
+    chunk_executor.expect_commit_chunk().returning(move || {
+        Ok(ChunkCommitNotification {
+            committed_events: vec![committed_event.clone()],
+            committed_transactions: vec![committed_transaction.clone()],
+            reconfiguration_occurred: false,
+        })
+    });
+
+    // Set up the mock db reader
+    let mut db_reader = create_mock_db_reader();
+    db_reader
+        .expect_get_startup_info()
+        .returning(|| Ok(Some(create_startup_info())));
+
+    // Create the storage synchronizer and verify one in every 3 chunks of outputs
+    let driver_config = StateSyncDriverConfig {
+        output_verification_sample_rate: 3,
+        ..Default::default()
+    };
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_config(
+            driver_config,
+            chunk_executor,
+            create_mock_reader_writer(Some(db_reader), None),
+        );
+
+    // Apply several chunks of outputs and verify they're all committed
+    let num_chunks = 7;
+    for notification_id in 0..num_chunks {
+        storage_synchronizer
+            .apply_transaction_outputs(
+                notification_id,
+                create_output_list_with_proof(),
+                create_epoch_ending_ledger_info(),
+                None,
+            )
+            .unwrap();
+    }
+    for _ in 0..num_chunks {
+        verify_mempool_and_event_notification(
+            None,
+            &mut mempool_listener,
+            vec![transaction_to_commit.clone()],
+            vec![event_to_commit.clone()],
+        )
+        .await;
+    }
+    verify_no_pending_data(&storage_synchronizer);
+
+    // Verify only the 3rd and 6th chunks were executed
+    assert_eq!(num_executed.load(Ordering::Relaxed), 2);
+    assert_eq!(num_applied.load(Ordering::Relaxed), 5);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_commit_chunk_error() {
     // Setup the mock executor
@@ -564,6 +756,105 @@ fn create_storage_synchronizer(
     StorageSynchronizer<MockChunkExecutor>,
     JoinHandle<()>,
     JoinHandle<()>,
+) {
+    create_storage_synchronizer_with_config(
+        StateSyncDriverConfig::default(),
+        mock_chunk_executor,
+        mock_reader_writer,
+    )
+}
+
+/// Verifies every chunk of outputs, with execution failing with the given error,
+/// and checks that the outputs are still applied and committed.
+async fn apply_outputs_with_execution_error(execution_error: anyhow::Error) {
+    // Create test data
+    let transaction_to_commit = create_transaction();
+    let event_to_commit = create_event();
+
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .return_once(move |_, _, _| Err(execution_error));
+    chunk_executor
+        .expect_apply_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let expected_commit_return = Ok(ChunkCommitNotification {
+        committed_events: vec![event_to_commit.clone()],
+        committed_transactions: vec![transaction_to_commit.clone()],
+        reconfiguration_occurred: false,
+    });
+    chunk_executor
+        .expect_commit_chunk()
+        .return_once(move || expected_commit_return);
+
+    // Set up the mock db reader
+    let mut db_reader = create_mock_db_reader();
+    db_reader
+        .expect_get_startup_info()
+        .returning(|| Ok(Some(create_startup_info())));
+
+    // Create the storage synchronizer and verify every chunk of outputs
+    let driver_config = StateSyncDriverConfig {
+        output_verification_sample_rate: 1,
+        ..Default::default()
+    };
+    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_config(
+            driver_config,
+            chunk_executor,
+            create_mock_reader_writer(Some(db_reader), None),
+        );
+
+    // Subscribe to the expected event
+    let mut event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event_to_commit.key()])
+        .unwrap();
+
+    // Attempt to apply a chunk of outputs
+    storage_synchronizer
+        .apply_transaction_outputs(
+            0,
+            create_output_list_with_proof(),
+            create_epoch_ending_ledger_info(),
+            None,
+        )
+        .unwrap();
+
+    // Verify the outputs are still applied and committed, and there's no pending data
+    verify_mempool_and_event_notification(
+        Some(&mut event_listener),
+        &mut mempool_listener,
+        vec![transaction_to_commit],
+        vec![event_to_commit],
+    )
+    .await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+/// Returns the number of output verifications with the given result
+fn get_output_verifications(result: &str) -> u64 {
+    metrics::STORAGE_SYNCHRONIZER_OUTPUT_VERIFICATIONS
+        .with_label_values(&[result])
+        .get()
+}
+
+/// Creates a storage synchronizer for testing with the given driver config
+fn create_storage_synchronizer_with_config(
+    driver_config: StateSyncDriverConfig,
+    mock_chunk_executor: MockChunkExecutor,
+    mock_reader_writer: DbReaderWriter,
+) -> (
+    CommitNotificationListener,
+    ErrorNotificationListener,
+    Arc<Mutex<EventSubscriptionService>>,
+    MempoolNotificationListener,
+    StorageSynchronizer<MockChunkExecutor>,
+    JoinHandle<()>,
+    JoinHandle<()>,
 ) {
     aptos_logger::Logger::init_for_testing();
 
@@ -585,7 +876,7 @@ fn create_storage_synchronizer(
 
     // Create the storage synchronizer
     let (storage_synchronizer, executor_handle, committer_handle) = StorageSynchronizer::new(
-        StateSyncDriverConfig::default(),
+        driver_config,
         Arc::new(mock_chunk_executor),
         commit_notification_sender,
         error_notification_sender,
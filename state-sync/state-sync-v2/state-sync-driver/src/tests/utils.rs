@@ -84,6 +84,21 @@ pub fn create_global_summary(highest_ended_epoch: Epoch) -> GlobalDataSummary {
     global_data_summary
 }
 
+/// Creates a global data summary where the highest synced version is
+/// `highest_version`, and the transaction outputs from `lowest_output_version`
+/// are advertised.
+pub fn create_global_summary_with_outputs(
+    highest_version: Version,
+    lowest_output_version: Version,
+) -> GlobalDataSummary {
+    let mut global_data_summary = GlobalDataSummary::empty();
+    let advertised_data = &mut global_data_summary.advertised_data;
+    advertised_data.synced_ledger_infos = vec![create_ledger_info_at_version(highest_version)];
+    advertised_data.transaction_outputs =
+        vec![CompleteDataRange::new(lowest_output_version, highest_version).unwrap()];
+    global_data_summary
+}
+
 /// Creates a new ledger info with signatures at the specified version
 pub fn create_ledger_info_at_version(version: Version) -> LedgerInfoWithSignatures {
     let block_info = BlockInfo::new(0, 0, HashValue::zero(), HashValue::zero(), version, 0, None);
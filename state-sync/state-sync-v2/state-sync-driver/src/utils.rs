@@ -9,6 +9,7 @@ use crate::{
         CommitNotification, CommittedTransactions, MempoolNotificationHandler,
     },
 };
+use aptos_data_client::AdvertisedData;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::{
@@ -204,6 +205,30 @@ pub fn fetch_latest_synced_version(storage: Arc<dyn DbReader>) -> Result<Version
         .map(|(latest_synced_version, _)| latest_synced_version)
 }
 
+/// Returns true iff the node is at most `max_lag` versions behind the highest
+/// synced version advertised by its peers, and the peers advertise the
+/// transaction outputs of all the versions it is missing. If so, the node can
+/// catch up by applying the outputs rather than executing the transactions.
+pub fn is_within_output_sync_lag(
+    highest_synced_version: Version,
+    max_lag: u64,
+    advertised_data: &AdvertisedData,
+) -> bool {
+    let highest_advertised_version = match advertised_data.highest_synced_ledger_info() {
+        Some(ledger_info_with_sigs) => ledger_info_with_sigs.ledger_info().version(),
+        None => return false, // We don't know how far behind we are
+    };
+    if highest_advertised_version <= highest_synced_version {
+        return true; // We're not behind
+    }
+    highest_advertised_version - highest_synced_version <= max_lag
+        && AdvertisedData::contains_range(
+            highest_synced_version + 1,
+            highest_advertised_version,
+            &advertised_data.transaction_outputs,
+        )
+}
+
 /// Fetches the startup info from the specified storage
 fn fetch_startup_info(storage: Arc<dyn DbReader>) -> Result<StartupInfo, Error> {
     let startup_info = storage.get_startup_info().map_err(|error| {
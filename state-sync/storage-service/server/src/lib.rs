@@ -7,6 +7,7 @@ use crate::{
     logging::{LogEntry, LogSchema},
     metrics::{increment_counter, start_timer, LRU_CACHE_HIT, LRU_CACHE_PROBE},
     network::{ResponseSender, StorageServiceNetworkEvents},
+    request_limiter::RequestLimiter,
 };
 use ::network::ProtocolId;
 use aptos_config::config::StorageServiceConfig;
@@ -43,12 +44,13 @@ use tokio::runtime::Handle;
 mod logging;
 mod metrics;
 pub mod network;
+mod request_limiter;
 
 #[cfg(test)]
 mod tests;
 
 /// Storage server constants.
-const STORAGE_SERVER_VERSION: u64 = 1;
+const STORAGE_SERVER_VERSION: u64 = 2;
 const SUMMARY_LOG_FREQUENCY_SECS: u64 = 5;
 
 #[derive(Clone, Debug, Deserialize, Error, PartialEq, Serialize)]
@@ -57,6 +59,8 @@ pub enum Error {
     InvalidRequest(String),
     #[error("Storage error encountered: {0}")]
     StorageErrorEncountered(String),
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
     #[error("Unexpected error encountered: {0}")]
     UnexpectedErrorEncountered(String),
 }
@@ -67,6 +71,7 @@ impl Error {
         match self {
             Error::InvalidRequest(_) => "invalid_request",
             Error::StorageErrorEncountered(_) => "storage_error",
+            Error::TooManyRequests(_) => "too_many_requests",
             Error::UnexpectedErrorEncountered(_) => "unexpected_error",
         }
     }
@@ -209,6 +214,10 @@ pub struct StorageServiceServer<T> {
     storage: T,
     time_service: TimeService,
 
+    // Limits the requests processed concurrently for each peer and
+    // for each request class.
+    request_limiter: RequestLimiter,
+
     // A cached storage server summary to avoid hitting the DB for every
     // request. This is refreshed periodically.
     cached_storage_server_summary: Arc<RwLock<StorageServerSummary>>,
@@ -237,6 +246,7 @@ impl<T: StorageReaderInterface> StorageServiceServer<T> {
         let lru_storage_cache = Arc::new(Mutex::new(LruCache::new(
             config.max_lru_cache_size as usize,
         )));
+        let request_limiter = RequestLimiter::new(config);
 
         Self {
             config,
@@ -244,6 +254,7 @@ impl<T: StorageReaderInterface> StorageServiceServer<T> {
             storage,
             network_requests,
             time_service,
+            request_limiter,
             cached_storage_server_summary,
             data_subscriptions,
             lru_storage_cache,
@@ -370,6 +381,21 @@ impl<T: StorageReaderInterface> StorageServiceServer<T> {
                     peer, protocol,
                 )));
 
+            // Reject the request if the peer has too many requests in flight,
+            // or if it is a chunk request and the server already processes too
+            // many of them. Rejecting (instead of queuing) pushes the load to
+            // other peers and keeps chunk requests from occupying all the
+            // tasks of the executor, so the cheap requests are never starved.
+            self.request_limiter.note_request(peer, &request);
+            let request_permit = match self.request_limiter.try_acquire(peer, &request) {
+                Ok(request_permit) => request_permit,
+                Err(error) => {
+                    let response_error = self.request_limiter.rejection_error(peer, &error);
+                    reject_request(protocol, &request, error, response_error, response_sender);
+                    continue;
+                }
+            };
+
             // All handler methods are currently CPU-bound and synchronous
             // I/O-bound, so we want to spawn on the blocking thread pool to
             // avoid starving other async tasks on the same runtime.
@@ -393,12 +419,32 @@ impl<T: StorageReaderInterface> StorageServiceServer<T> {
                         request,
                         response_sender,
                     );
+                    drop(request_permit);
                 })
                 .await;
         }
     }
 }
 
+/// Responds to a request that is rejected by the request limiter
+fn reject_request(
+    protocol: ProtocolId,
+    request: &StorageServiceRequest,
+    error: Error,
+    response_error: StorageServiceError,
+    response_sender: ResponseSender,
+) {
+    increment_counter(
+        &metrics::STORAGE_ERRORS_ENCOUNTERED,
+        protocol,
+        error.get_label().into(),
+    );
+    debug!(LogSchema::new(LogEntry::StorageServiceError)
+        .error(&error)
+        .request(request));
+    response_sender.send(Err(response_error));
+}
+
 /// Identifies the data subscriptions that can be handled now.
 /// Returns the list of peers that made those subscriptions
 /// alongside the ledger info at the target version for the peer.
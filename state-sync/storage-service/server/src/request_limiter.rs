@@ -0,0 +1,138 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Error;
+use aptos_config::config::StorageServiceConfig;
+use aptos_infallible::Mutex;
+use aptos_types::PeerId;
+use lru::LruCache;
+use std::{collections::HashMap, sync::Arc};
+use storage_service_types::{StorageServiceError, StorageServiceRequest};
+
+/// The number of peers remembered to know `StorageServiceError::TooManyRequests`
+const MAX_VERSIONED_PEERS: usize = 10_000;
+
+/// The requests currently processed by the storage server
+#[derive(Debug, Default)]
+struct InFlightRequests {
+    chunk_requests: u64,
+    requests_per_peer: HashMap<PeerId, u64>,
+}
+
+/// Limits the requests processed concurrently by the storage server, so that
+/// a single syncing peer cannot monopolize the server (and the IO of the node).
+///
+/// Each peer may only have `max_concurrent_requests_per_peer` requests in
+/// flight. Chunk requests (see `StorageServiceRequest::is_chunk_request`) are
+/// further limited to `max_concurrent_chunk_requests` across all peers, which
+/// leaves room for the cheap requests (e.g., storage summaries and epoch ending
+/// ledger infos) even when many peers fetch large chunks.
+///
+/// `StorageServiceError::TooManyRequests` was added in version 2 of the
+/// protocol, so the clients that never asked for the protocol version of the
+/// server (i.e., older clients) are rejected with an `InternalError` instead.
+#[derive(Clone)]
+pub struct RequestLimiter {
+    config: StorageServiceConfig,
+    in_flight_requests: Arc<Mutex<InFlightRequests>>,
+    versioned_peers: Arc<Mutex<LruCache<PeerId, ()>>>,
+}
+
+impl RequestLimiter {
+    pub fn new(config: StorageServiceConfig) -> Self {
+        Self {
+            config,
+            in_flight_requests: Arc::new(Mutex::new(InFlightRequests::default())),
+            versioned_peers: Arc::new(Mutex::new(LruCache::new(MAX_VERSIONED_PEERS))),
+        }
+    }
+
+    /// Records that the peer asked for the protocol version of the server, and
+    /// thus knows the errors of the current version.
+    pub fn note_request(&self, peer: PeerId, request: &StorageServiceRequest) {
+        if matches!(request, StorageServiceRequest::GetServerProtocolVersion) {
+            self.versioned_peers.lock().put(peer, ());
+        }
+    }
+
+    /// Returns the error to send to the peer for a rejected request
+    pub fn rejection_error(&self, peer: PeerId, error: &Error) -> StorageServiceError {
+        if self.versioned_peers.lock().get(&peer).is_some() {
+            StorageServiceError::TooManyRequests(error.to_string())
+        } else {
+            StorageServiceError::InternalError(error.to_string())
+        }
+    }
+
+    /// Returns a permit to process the given request of the peer, or an error
+    /// if the request would exceed one of the limits. The request counts as
+    /// in flight until the permit is dropped.
+    pub fn try_acquire(
+        &self,
+        peer: PeerId,
+        request: &StorageServiceRequest,
+    ) -> Result<RequestPermit, Error> {
+        let mut in_flight_requests = self.in_flight_requests.lock();
+
+        let peer_requests = in_flight_requests
+            .requests_per_peer
+            .get(&peer)
+            .copied()
+            .unwrap_or(0);
+        if peer_requests >= self.config.max_concurrent_requests_per_peer {
+            return Err(Error::TooManyRequests(format!(
+                "Peer {:?} already has {} requests in flight!",
+                peer, peer_requests
+            )));
+        }
+
+        let is_chunk_request = request.is_chunk_request();
+        if is_chunk_request
+            && in_flight_requests.chunk_requests >= self.config.max_concurrent_chunk_requests
+        {
+            return Err(Error::TooManyRequests(format!(
+                "The server already processes {} chunk requests!",
+                in_flight_requests.chunk_requests
+            )));
+        }
+
+        // Count the request as in flight
+        *in_flight_requests
+            .requests_per_peer
+            .entry(peer)
+            .or_default() += 1;
+        if is_chunk_request {
+            in_flight_requests.chunk_requests += 1;
+        }
+
+        Ok(RequestPermit {
+            peer,
+            is_chunk_request,
+            in_flight_requests: self.in_flight_requests.clone(),
+        })
+    }
+}
+
+/// A permit to process a request. The request stops counting as in flight
+/// when the permit is dropped.
+#[derive(Debug)]
+pub struct RequestPermit {
+    peer: PeerId,
+    is_chunk_request: bool,
+    in_flight_requests: Arc<Mutex<InFlightRequests>>,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        let mut in_flight_requests = self.in_flight_requests.lock();
+        if let Some(peer_requests) = in_flight_requests.requests_per_peer.get_mut(&self.peer) {
+            *peer_requests -= 1;
+            if *peer_requests == 0 {
+                in_flight_requests.requests_per_peer.remove(&self.peer);
+            }
+        }
+        if self.is_chunk_request {
+            in_flight_requests.chunk_requests -= 1;
+        }
+    }
+}
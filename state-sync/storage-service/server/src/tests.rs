@@ -3,7 +3,10 @@
 
 #![forbid(unsafe_code)]
 
-use crate::{network::StorageServiceNetworkEvents, StorageReader, StorageServiceServer};
+use crate::{
+    network::StorageServiceNetworkEvents, request_limiter::RequestLimiter, Error, StorageReader,
+    StorageServiceServer,
+};
 use anyhow::{format_err, Result};
 use aptos_config::config::StorageServiceConfig;
 use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, SigningKey, Uniform};
@@ -50,7 +53,7 @@ use network::{
     },
 };
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
-use storage_interface::{DbReader, Order, StartupInfo, TreeState};
+use storage_interface::{EventReader, LedgerReader, Order, StartupInfo, StateReader, TreeState};
 use storage_service_types::{
     AccountStatesChunkWithProofRequest, CompleteDataRange, DataSummary, Epoch,
     EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
@@ -62,7 +65,7 @@ use tokio::time::timeout;
 
 /// Various test constants for storage
 const MAX_RESPONSE_TIMEOUT_SECS: u64 = 10;
-const PROTOCOL_VERSION: u64 = 1;
+const PROTOCOL_VERSION: u64 = 2;
 
 #[tokio::test]
 async fn test_cachable_requests_eviction() {
@@ -1020,6 +1023,91 @@ async fn test_get_epoch_ending_ledger_infos_invalid() {
     }
 }
 
+#[tokio::test]
+async fn test_too_many_requests() {
+    // Create the storage client and server with no room for requests
+    let storage_config = StorageServiceConfig {
+        max_concurrent_requests_per_peer: 0,
+        ..Default::default()
+    };
+    let (mut mock_client, service, _) = MockClient::new_with_config(None, storage_config);
+    tokio::spawn(service.start());
+
+    // Verify a client that never asked for the protocol version is rejected
+    // with an error it can decode
+    let response = mock_client
+        .process_request(StorageServiceRequest::GetStorageServerSummary)
+        .await
+        .unwrap_err();
+    assert_matches!(response, StorageServiceError::InternalError(_));
+
+    // Verify the client is told about the rate limiting once it asks for the
+    // protocol version
+    let response = mock_client
+        .process_request(StorageServiceRequest::GetServerProtocolVersion)
+        .await
+        .unwrap_err();
+    assert_matches!(response, StorageServiceError::TooManyRequests(_));
+    let response = mock_client
+        .process_request(StorageServiceRequest::GetStorageServerSummary)
+        .await
+        .unwrap_err();
+    assert_matches!(response, StorageServiceError::TooManyRequests(_));
+}
+
+#[test]
+fn test_request_limiter() {
+    // Create a request limiter
+    let storage_config = StorageServiceConfig {
+        max_concurrent_chunk_requests: 2,
+        max_concurrent_requests_per_peer: 2,
+        ..Default::default()
+    };
+    let request_limiter = RequestLimiter::new(storage_config);
+    let chunk_request =
+        StorageServiceRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+            proof_version: 10,
+            start_version: 0,
+            end_version: 10,
+            include_events: false,
+        });
+    let summary_request = StorageServiceRequest::GetStorageServerSummary;
+
+    // Verify the per peer limit
+    let peer = PeerId::random();
+    let permit = request_limiter.try_acquire(peer, &summary_request).unwrap();
+    let chunk_permit = request_limiter.try_acquire(peer, &chunk_request).unwrap();
+    assert_matches!(
+        request_limiter.try_acquire(peer, &summary_request),
+        Err(Error::TooManyRequests(_))
+    );
+    drop(permit);
+    let permit = request_limiter.try_acquire(peer, &summary_request).unwrap();
+
+    // Verify the chunk request limit across peers
+    let other_peer = PeerId::random();
+    let other_chunk_permit = request_limiter
+        .try_acquire(other_peer, &chunk_request)
+        .unwrap();
+    assert_matches!(
+        request_limiter.try_acquire(other_peer, &chunk_request),
+        Err(Error::TooManyRequests(_))
+    );
+    let other_permit = request_limiter
+        .try_acquire(other_peer, &summary_request)
+        .unwrap();
+    drop(chunk_permit);
+    assert_matches!(
+        request_limiter.try_acquire(other_peer, &summary_request),
+        Err(Error::TooManyRequests(_))
+    );
+    drop(other_permit);
+    request_limiter
+        .try_acquire(other_peer, &chunk_request)
+        .unwrap();
+    drop((permit, other_chunk_permit));
+}
+
 /// A wrapper around the inbound network interface/channel for easily sending
 /// mock client requests to a [`StorageServiceServer`].
 struct MockClient {
@@ -1029,9 +1117,15 @@ struct MockClient {
 impl MockClient {
     fn new(
         db_reader: Option<MockDatabaseReader>,
+    ) -> (Self, StorageServiceServer<StorageReader>, MockTimeService) {
+        Self::new_with_config(db_reader, StorageServiceConfig::default())
+    }
+
+    fn new_with_config(
+        db_reader: Option<MockDatabaseReader>,
+        storage_config: StorageServiceConfig,
     ) -> (Self, StorageServiceServer<StorageReader>, MockTimeService) {
         initialize_logger();
-        let storage_config = StorageServiceConfig::default();
         let storage = StorageReader::new(
             storage_config,
             Arc::new(db_reader.unwrap_or_else(create_mock_db_reader)),
@@ -1048,7 +1142,7 @@ impl MockClient {
         let executor = tokio::runtime::Handle::current();
         let mock_time_service = TimeService::mock();
         let storage_server = StorageServiceServer::new(
-            StorageServiceConfig::default(),
+            storage_config,
             executor,
             storage,
             mock_time_service.clone(),
@@ -1423,7 +1517,7 @@ pub fn create_mock_db_reader() -> MockDatabaseReader {
 // mock test crate to be shared across the codebase.
 mock! {
     pub DatabaseReader {}
-    impl DbReader for DatabaseReader {
+    impl LedgerReader for DatabaseReader {
         fn get_epoch_ending_ledger_infos(
             &self,
             start_epoch: u64,
@@ -1463,40 +1557,14 @@ mock! {
             ledger_version: Version,
         ) -> Result<TransactionOutputListWithProof>;
 
-        fn get_events(
-            &self,
-            event_key: &EventKey,
-            start: u64,
-            order: Order,
-            limit: u64,
-        ) -> Result<Vec<(u64, ContractEvent)>>;
-
-        fn get_events_with_proofs(
-            &self,
-            event_key: &EventKey,
-            start: u64,
-            order: Order,
-            limit: u64,
-            known_version: Option<u64>,
-        ) -> Result<Vec<EventWithProof>>;
-
         fn get_block_timestamp(&self, version: u64) -> Result<u64>;
 
-        fn get_event_by_version_with_proof(
-            &self,
-            event_key: &EventKey,
-            event_version: u64,
-            proof_version: u64,
-        ) -> Result<EventByVersionWithProof>;
-
         fn get_last_version_before_timestamp(
             &self,
             _timestamp: u64,
             _ledger_version: Version,
         ) -> Result<Version>;
 
-        fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>>;
-
         fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>>;
 
         fn get_latest_ledger_info(&self) -> Result<LedgerInfoWithSignatures>;
@@ -1534,21 +1602,6 @@ mock! {
 
         fn get_state_proof(&self, known_version: u64) -> Result<StateProof>;
 
-        fn get_state_value_with_proof(
-            &self,
-            state_key: StateKey,
-            version: Version,
-            ledger_version: Version,
-        ) -> Result<StateValueWithProof>;
-
-        fn get_state_value_with_proof_by_version(
-            &self,
-            state_key: &StateKey,
-            version: Version,
-        ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)>;
-
-        fn get_latest_tree_state(&self) -> Result<TreeState>;
-
         fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures>;
 
         fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>>;
@@ -1565,6 +1618,25 @@ mock! {
             &self,
             ledger_version: Version,
         ) -> Result<TransactionAccumulatorSummary>;
+    }
+
+    impl StateReader for DatabaseReader {
+        fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>>;
+
+        fn get_state_value_with_proof(
+            &self,
+            state_key: StateKey,
+            version: Version,
+            ledger_version: Version,
+        ) -> Result<StateValueWithProof>;
+
+        fn get_state_value_with_proof_by_version(
+            &self,
+            state_key: &StateKey,
+            version: Version,
+        ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)>;
+
+        fn get_latest_tree_state(&self) -> Result<TreeState>;
 
         fn get_state_leaf_count(&self, version: Version) -> Result<usize>;
 
@@ -1577,4 +1649,30 @@ mock! {
 
         fn get_state_prune_window(&self) -> Result<Option<usize>>;
     }
+
+    impl EventReader for DatabaseReader {
+        fn get_events(
+            &self,
+            event_key: &EventKey,
+            start: u64,
+            order: Order,
+            limit: u64,
+        ) -> Result<Vec<(u64, ContractEvent)>>;
+
+        fn get_events_with_proofs(
+            &self,
+            event_key: &EventKey,
+            start: u64,
+            order: Order,
+            limit: u64,
+            known_version: Option<u64>,
+        ) -> Result<Vec<EventWithProof>>;
+
+        fn get_event_by_version_with_proof(
+            &self,
+            event_key: &EventKey,
+            event_version: u64,
+            proof_version: u64,
+        ) -> Result<EventByVersionWithProof>;
+    }
 }
@@ -26,6 +26,14 @@ use thiserror::Error;
 /// A type alias for different epochs.
 pub type Epoch = u64;
 
+/// How far behind the known version of a subscription the advertised synced
+/// version of a peer can be for the peer to still be sent the subscription.
+/// Peer summaries are only refreshed periodically, so a node following the
+/// tip moves past the last advertised version of its peers with every
+/// subscription response. This is well over the versions committed between
+/// two summary polls, while peers that are catching up are still avoided.
+pub const MAX_SUBSCRIPTION_LAG_VERSIONS: u64 = 10_000;
+
 pub type Result<T, E = StorageServiceError> = ::std::result::Result<T, E>;
 
 /// A storage service error that can be returned to the client on a failure
@@ -36,6 +44,8 @@ pub enum StorageServiceError {
     InternalError(String),
     #[error("Invalid storage request: {0}")]
     InvalidRequest(String),
+    #[error("Too many storage requests: {0}")]
+    TooManyRequests(String),
 }
 
 /// A single storage service message sent or received over AptosNet.
@@ -88,6 +98,14 @@ impl StorageServiceRequest {
         matches!(self, &Self::GetNewTransactionOutputsWithProof(_))
             || matches!(self, &Self::GetNewTransactionsWithProof(_))
     }
+
+    /// Returns true iff the request fetches a chunk of transactions, transaction
+    /// outputs or account states, which is expensive to read from storage.
+    pub fn is_chunk_request(&self) -> bool {
+        matches!(self, &Self::GetAccountStatesChunkWithProof(_))
+            || matches!(self, &Self::GetTransactionOutputsWithProof(_))
+            || matches!(self, &Self::GetTransactionsWithProof(_))
+    }
 }
 
 /// A storage service response.
@@ -440,10 +458,13 @@ impl DataSummary {
     pub fn can_service(&self, request: &StorageServiceRequest) -> bool {
         use StorageServiceRequest::*;
         match request {
-            GetNewTransactionsWithProof(_)
-            | GetNewTransactionOutputsWithProof(_)
-            | GetServerProtocolVersion
-            | GetStorageServerSummary => true,
+            GetServerProtocolVersion | GetStorageServerSummary => true,
+            GetNewTransactionsWithProof(request) => {
+                self.can_service_new_data(request.known_version)
+            }
+            GetNewTransactionOutputsWithProof(request) => {
+                self.can_service_new_data(request.known_version)
+            }
             GetAccountStatesChunkWithProof(request) => {
                 let proof_version = request.version;
 
@@ -516,6 +537,22 @@ impl DataSummary {
             }
         }
     }
+
+    /// Returns true iff the peer is synced to `known_version`, or at most
+    /// `MAX_SUBSCRIPTION_LAG_VERSIONS` behind it, and so can respond to a
+    /// request for the data after it soon. Requests to a peer which is further
+    /// behind are only answered once it catches up, if ever.
+    fn can_service_new_data(&self, known_version: Version) -> bool {
+        self.synced_ledger_info
+            .as_ref()
+            .map(|li| {
+                li.ledger_info()
+                    .version()
+                    .saturating_add(MAX_SUBSCRIPTION_LAG_VERSIONS)
+                    >= known_version
+            })
+            .unwrap_or(false)
+    }
 }
 
 #[derive(Clone, Debug, Error)]
@@ -712,6 +749,23 @@ mod tests {
         get_account_state_chunks_request(version, 0, 1000)
     }
 
+    fn get_new_txns_request(known_version: Version) -> StorageServiceRequest {
+        StorageServiceRequest::GetNewTransactionsWithProof(NewTransactionsWithProofRequest {
+            known_version,
+            known_epoch: 1,
+            include_events: true,
+        })
+    }
+
+    fn get_new_txn_outputs_request(known_version: Version) -> StorageServiceRequest {
+        StorageServiceRequest::GetNewTransactionOutputsWithProof(
+            NewTransactionOutputsWithProofRequest {
+                known_version,
+                known_epoch: 1,
+            },
+        )
+    }
+
     #[test]
     fn test_complete_data_range() {
         // good ranges
@@ -850,6 +904,36 @@ mod tests {
         assert!(!summary.can_service(&get_account_states_request(99)));
     }
 
+    #[test]
+    fn test_data_summary_can_service_new_data_requests() {
+        let summary = DataSummary {
+            synced_ledger_info: Some(mock_ledger_info(250)),
+            ..Default::default()
+        };
+
+        // synced to the known version or beyond => can service
+        assert!(summary.can_service(&get_new_txns_request(0)));
+        assert!(summary.can_service(&get_new_txns_request(249)));
+        assert!(summary.can_service(&get_new_txns_request(250)));
+        assert!(summary.can_service(&get_new_txn_outputs_request(100)));
+        assert!(summary.can_service(&get_new_txn_outputs_request(250)));
+
+        // slightly behind the known version => can service
+        let max_known_version = 250 + MAX_SUBSCRIPTION_LAG_VERSIONS;
+        assert!(summary.can_service(&get_new_txns_request(251)));
+        assert!(summary.can_service(&get_new_txns_request(max_known_version)));
+        assert!(summary.can_service(&get_new_txn_outputs_request(max_known_version)));
+
+        // too far behind the known version => cannot service
+        assert!(!summary.can_service(&get_new_txns_request(max_known_version + 1)));
+        assert!(!summary.can_service(&get_new_txn_outputs_request(max_known_version + 1)));
+
+        // nothing synced => cannot service
+        let summary = DataSummary::default();
+        assert!(!summary.can_service(&get_new_txns_request(0)));
+        assert!(!summary.can_service(&get_new_txn_outputs_request(0)));
+    }
+
     #[test]
     fn test_protocol_metadata_can_service() {
         let metadata = ProtocolMetadata {
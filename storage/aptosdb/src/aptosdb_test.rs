@@ -11,11 +11,11 @@ use aptos_temppath::TempPath;
 use aptos_types::{
     proof::SparseMerkleLeafNode,
     state_store::{state_key::StateKey, state_value::StateValue},
-    transaction::{ExecutionStatus, TransactionInfo, PRE_GENESIS_VERSION},
+    transaction::{ExecutionStatus, TransactionInfo, Version, PRE_GENESIS_VERSION},
 };
 use proptest::prelude::*;
 use std::time::Duration;
-use storage_interface::{DbReader, Order, TreeState};
+use storage_interface::{LedgerReader, Order, StateReader, TreeState};
 use test_helper::{test_save_blocks_impl, test_sync_transactions_impl};
 
 proptest! {
@@ -30,6 +30,59 @@ proptest! {
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
         test_sync_transactions_impl(input);
     }
+
+    #[test]
+    fn test_cached_state_value_proofs(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            let ledger_version = ledger_info_with_sigs.ledger_info().version();
+
+            // The proofs of the first block are asked for again against every new ledger version,
+            // so that stale proofs would fail to verify.
+            let (first_txns_to_commit, _) = &input[0];
+            for (version, txn_to_commit) in first_txns_to_commit.iter().enumerate() {
+                let version = version as Version;
+                for state_key in txn_to_commit.state_updates().keys() {
+                    let proof = db
+                        .get_state_value_with_proof(state_key.clone(), version, ledger_version)
+                        .unwrap();
+                    proof
+                        .verify(ledger_info_with_sigs.ledger_info(), version, state_key.clone())
+                        .unwrap();
+                    prop_assert_eq!(
+                        db.get_state_value_with_proof(state_key.clone(), version, ledger_version)
+                            .unwrap(),
+                        proof
+                    );
+                }
+            }
+            cur_ver += txns_to_commit.len() as Version;
+        }
+    }
+
+    #[cfg(feature = "table-iteration")]
+    #[test]
+    fn test_get_table_items_checks_version(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        // nothing is committed yet
+        prop_assert!(db.get_table_items(1, 0, None, 10).is_err());
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            cur_ver += txns_to_commit.len() as Version;
+        }
+        let latest_version = cur_ver - 1;
+        prop_assert!(db.get_table_items(1, latest_version, None, 10).is_ok());
+        prop_assert!(db.get_table_items(1, latest_version + 1, None, 10).is_err());
+    }
 }
 
 #[test]
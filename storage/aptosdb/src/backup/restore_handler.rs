@@ -17,7 +17,7 @@ use aptos_types::{
 };
 use schemadb::DB;
 use std::sync::Arc;
-use storage_interface::{DbReader, TreeState};
+use storage_interface::{LedgerReader, TreeState};
 
 /// Provides functionalities for AptosDB data restore.
 #[derive(Clone)]
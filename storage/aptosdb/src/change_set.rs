@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::ledger_counters::LedgerCounterBumps;
+use crate::{ledger_counters::LedgerCounterBumps, ledger_store::FrozenSubtrees};
 use aptos_types::transaction::Version;
 use schemadb::SchemaBatch;
 use std::collections::HashMap;
@@ -16,6 +16,8 @@ pub struct ChangeSet {
     pub batch: SchemaBatch,
     /// Counter bumps to be made on commit.
     counter_bumps: HashMap<Version, LedgerCounterBumps>,
+    /// Frozen subtrees of the transaction accumulator to be cached on commit.
+    pub(crate) frozen_subtrees: Option<FrozenSubtrees>,
 }
 
 impl ChangeSet {
@@ -24,6 +26,7 @@ impl ChangeSet {
         Self {
             batch: SchemaBatch::new(),
             counter_bumps: HashMap::new(),
+            frozen_subtrees: None,
         }
     }
 
@@ -38,6 +41,7 @@ impl ChangeSet {
         Self {
             batch: SchemaBatch::new(),
             counter_bumps,
+            frozen_subtrees: None,
         }
     }
 }
@@ -48,4 +52,6 @@ impl ChangeSet {
 pub(crate) struct SealedChangeSet {
     /// A batch of db alternations.
     pub batch: SchemaBatch,
+    /// Frozen subtrees of the transaction accumulator to be cached on commit.
+    pub frozen_subtrees: Option<FrozenSubtrees>,
 }
@@ -0,0 +1,335 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This file defines the cold store, a secondary RocksDB instance that holds the transactions,
+//! write sets and events older than a version horizon, so that the main DB only keeps the recent
+//! ledger history on fast disks.
+//!
+//! History is moved in batches by `AptosDB::move_ledger_history_to_cold_store`: the rows of a batch
+//! are first written to the cold store, then the horizon is raised and only then they are deleted
+//! from the main DB. Reads go through [`TieredDb`], which serves the versions below the horizon
+//! from the cold store and the other ones from the main DB.
+//!
+//! The two DBs can't be written atomically together. A crash between the two writes of a batch
+//! leaves its rows in both of them, which [`ColdStore::reconcile`] deletes from the main DB when
+//! it is opened again.
+
+use crate::schema::{
+    event::EventSchema, transaction::TransactionSchema, write_set::WriteSetSchema, EVENT_CF_NAME,
+    TRANSACTION_CF_NAME, WRITE_SET_CF_NAME,
+};
+use anyhow::{ensure, Result};
+use aptos_logger::prelude::*;
+use aptos_types::transaction::Version;
+use schemadb::{
+    schema::{Schema, SeekKeyCodec},
+    ColumnFamilyName, Options, ReadOptions, SchemaBatch, SchemaIterator, DB, DEFAULT_CF_NAME,
+};
+use std::{
+    iter,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+#[derive(Debug)]
+pub struct ColdStore {
+    db: DB,
+    // All versions below the horizon are in the cold store and none of the others.
+    horizon: AtomicU64,
+}
+
+impl ColdStore {
+    fn column_families() -> Vec<ColumnFamilyName> {
+        vec![
+            DEFAULT_CF_NAME,
+            EVENT_CF_NAME,
+            TRANSACTION_CF_NAME,
+            WRITE_SET_CF_NAME,
+        ]
+    }
+
+    pub fn open(path: impl AsRef<Path>, readonly: bool, rocksdb_opts: &Options) -> Result<Self> {
+        let db = if readonly {
+            DB::open_readonly(
+                path.as_ref(),
+                "cold_store_ro",
+                Self::column_families(),
+                rocksdb_opts,
+            )?
+        } else {
+            let mut rocksdb_opts = rocksdb_opts.clone();
+            rocksdb_opts.create_if_missing(true);
+            rocksdb_opts.create_missing_column_families(true);
+            DB::open(
+                path.as_ref(),
+                "cold_store",
+                Self::column_families(),
+                &rocksdb_opts,
+            )?
+        };
+
+        // Moved batches are written atomically, so the horizon follows the last moved transaction.
+        let mut iter = db.rev_iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_last();
+        let horizon = match iter.next().transpose()? {
+            Some((version, _)) => version + 1,
+            None => 0,
+        };
+        info!(
+            path = ?path.as_ref(),
+            horizon = horizon,
+            "Opened cold store."
+        );
+
+        Ok(Self {
+            db,
+            horizon: AtomicU64::new(horizon),
+        })
+    }
+
+    /// Returns the version below which the ledger history is served from the cold store.
+    pub fn horizon(&self) -> Version {
+        self.horizon.load(Ordering::Acquire)
+    }
+
+    /// Moves the transactions, write sets and events in `[begin, end)` from `hot` to the cold
+    /// store, where `begin` must be the current horizon.
+    pub(crate) fn move_from(&self, hot: &DB, begin: Version, end: Version) -> Result<()> {
+        let horizon = self.horizon();
+        ensure!(
+            begin == horizon,
+            "Ledger history must be moved in order: moving from version {}, horizon is {}.",
+            begin,
+            horizon,
+        );
+
+        let mut cold_batch = SchemaBatch::new();
+        let mut hot_batch = SchemaBatch::new();
+        for res in versions_in_range::<TransactionSchema>(hot, begin, end)? {
+            let (version, transaction) = res?;
+            cold_batch.put::<TransactionSchema>(&version, &transaction)?;
+            hot_batch.delete::<TransactionSchema>(&version)?;
+        }
+        for res in versions_in_range::<WriteSetSchema>(hot, begin, end)? {
+            let (version, write_set) = res?;
+            cold_batch.put::<WriteSetSchema>(&version, &write_set)?;
+            hot_batch.delete::<WriteSetSchema>(&version)?;
+        }
+        for res in versions_in_range::<EventSchema>(hot, begin, end)? {
+            let (key, event) = res?;
+            cold_batch.put::<EventSchema>(&key, &event)?;
+        }
+        hot_batch.delete_range::<EventSchema>(&(begin, 0), &(end, 0))?;
+
+        // Readers of the versions being moved keep using the main DB until the horizon is raised,
+        // so the rows are only deleted from it once they are readable from the cold store.
+        self.db.write_schemas(cold_batch)?;
+        self.horizon.store(end, Ordering::Release);
+        hot.write_schemas(hot_batch)
+    }
+
+    /// Completes a move interrupted after the rows were written to the cold store but before they
+    /// were deleted from `hot`. Returns the number of versions deleted from `hot`.
+    pub(crate) fn reconcile(&self, hot: &DB) -> Result<u64> {
+        let horizon = self.horizon();
+        let mut iter = hot.iter::<TransactionSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        let begin = match iter.next().transpose()? {
+            Some((version, _)) if version < horizon => version,
+            _ => return Ok(0),
+        };
+
+        let mut hot_batch = SchemaBatch::new();
+        hot_batch.delete_range::<TransactionSchema>(&begin, &horizon)?;
+        hot_batch.delete_range::<WriteSetSchema>(&begin, &horizon)?;
+        hot_batch.delete_range::<EventSchema>(&(begin, 0), &(horizon, 0))?;
+        hot.write_schemas(hot_batch)?;
+        warn!(
+            begin = begin,
+            horizon = horizon,
+            "Deleted the ledger history left in the main DB by an interrupted move to the cold store."
+        );
+        Ok(horizon - begin)
+    }
+}
+
+/// The keys of the ledger history schemas, which all start with the version.
+pub(crate) trait VersionedKey {
+    fn version(&self) -> Version;
+}
+
+impl VersionedKey for Version {
+    fn version(&self) -> Version {
+        *self
+    }
+}
+
+impl VersionedKey for (Version, u64) {
+    fn version(&self) -> Version {
+        self.0
+    }
+}
+
+fn versions_in_range<S>(
+    db: &DB,
+    begin: Version,
+    end: Version,
+) -> Result<impl Iterator<Item = Result<(S::Key, S::Value)>> + '_>
+where
+    S: Schema,
+    S::Key: VersionedKey,
+    Version: SeekKeyCodec<S>,
+{
+    let mut iter = db.iter::<S>(ReadOptions::default())?;
+    iter.seek(&begin)?;
+    Ok(iter.take_while(move |res| match res {
+        Ok((key, _)) => key.version() < end,
+        Err(_) => true,
+    }))
+}
+
+/// Reads the ledger history from the main DB and, below its horizon, from the cold store.
+#[derive(Clone, Debug)]
+pub(crate) struct TieredDb {
+    hot: Arc<DB>,
+    cold: Option<Arc<ColdStore>>,
+}
+
+impl TieredDb {
+    pub fn new(hot: Arc<DB>, cold: Option<Arc<ColdStore>>) -> Self {
+        Self { hot, cold }
+    }
+
+    fn horizon(&self) -> Version {
+        self.cold.as_ref().map_or(0, |cold| cold.horizon())
+    }
+
+    /// Returns the DBs that may hold `version`, the one expected to hold it first.
+    pub fn dbs(&self, version: Version) -> Vec<&DB> {
+        match &self.cold {
+            Some(cold) if version < cold.horizon() => vec![&cold.db],
+            // The horizon may be raised and the version deleted from the main DB in the meantime.
+            Some(cold) => vec![self.hot.as_ref(), &cold.db],
+            None => vec![self.hot.as_ref()],
+        }
+    }
+
+    pub fn get<S>(&self, key: &S::Key) -> Result<Option<S::Value>>
+    where
+        S: Schema,
+        S::Key: VersionedKey,
+    {
+        for db in self.dbs(key.version()) {
+            if let Some(value) = db.get::<S>(key)? {
+                return Ok(Some(value));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns an iterator over the rows at or after `version`, in version order.
+    pub fn iter<S>(&self, version: Version) -> Result<TieredIter<S>>
+    where
+        S: Schema,
+        S::Key: VersionedKey,
+        Version: SeekKeyCodec<S>,
+    {
+        // The main DB iterator is created before the horizon is read and the cold store iterator
+        // after it, so that the snapshots they read hold every version on one side of the horizon.
+        let mut hot = self.hot.iter::<S>(ReadOptions::default())?;
+        let horizon = self.horizon();
+        hot.seek(&std::cmp::max(version, horizon))?;
+        let cold = match &self.cold {
+            Some(cold) if version < horizon => {
+                let mut iter = cold.db.iter::<S>(ReadOptions::default())?;
+                iter.seek(&version)?;
+                Some(iter)
+            }
+            _ => None,
+        };
+
+        Ok(TieredIter {
+            first: cold,
+            first_in_tier: Box::new(move |v| v < horizon),
+            rest: Some(hot),
+        })
+    }
+
+    /// Returns an iterator over the rows at or before `version`, in reverse version order.
+    pub fn rev_iter<S>(&self, version: Version) -> Result<TieredIter<S>>
+    where
+        S: Schema<Key = Version>,
+    {
+        let mut hot = self.hot.rev_iter::<S>(ReadOptions::default())?;
+        let horizon = self.horizon();
+        hot.seek_for_prev(&version)?;
+        let cold = match &self.cold {
+            Some(cold) if horizon > 0 => {
+                let mut iter = cold.db.rev_iter::<S>(ReadOptions::default())?;
+                iter.seek_for_prev(&std::cmp::min(version, horizon - 1))?;
+                Some(iter)
+            }
+            _ => None,
+        };
+
+        Ok(TieredIter {
+            first: if version >= horizon { Some(hot) } else { None },
+            first_in_tier: Box::new(move |v| v >= horizon),
+            rest: cold,
+        })
+    }
+
+    /// Returns the first row, from the cold store if it holds any.
+    pub fn first<S>(&self) -> Result<Option<(S::Key, S::Value)>>
+    where
+        S: Schema,
+    {
+        for db in self
+            .cold
+            .iter()
+            .map(|cold| &cold.db)
+            .chain(iter::once(&*self.hot))
+        {
+            let mut iter = db.iter::<S>(ReadOptions::default())?;
+            iter.seek_to_first();
+            if let Some(row) = iter.next().transpose()? {
+                return Ok(Some(row));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Chains the iterators of the two tiers, the first one until it leaves its tier.
+pub(crate) struct TieredIter<'a, S: Schema> {
+    first: Option<SchemaIterator<'a, S>>,
+    first_in_tier: Box<dyn Fn(Version) -> bool>,
+    rest: Option<SchemaIterator<'a, S>>,
+}
+
+impl<'a, S> Iterator for TieredIter<'a, S>
+where
+    S: Schema,
+    S::Key: VersionedKey,
+{
+    type Item = Result<(S::Key, S::Value)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(first) = &mut self.first {
+            match first.next() {
+                Some(Ok((key, value))) if (self.first_in_tier)(key.version()) => {
+                    return Some(Ok((key, value)))
+                }
+                Some(Err(e)) => return Some(Err(e)),
+                _ => self.first = None,
+            }
+        }
+        self.rest.as_mut()?.next()
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,205 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    test_helper::{arb_blocks_to_commit, verify_committed_transactions},
+    AptosDB,
+};
+use aptos_config::config::{RocksdbConfig, StoragePrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG};
+use aptos_temppath::TempPath;
+use proptest::prelude::*;
+use storage_interface::LedgerReader;
+
+fn open_db(db_dir: &TempPath, cold_store_dir: &TempPath) -> AptosDB {
+    open_db_with_pruner(db_dir, cold_store_dir, NO_OP_STORAGE_PRUNER_CONFIG)
+}
+
+fn open_db_with_pruner(
+    db_dir: &TempPath,
+    cold_store_dir: &TempPath,
+    storage_pruner_config: StoragePrunerConfig,
+) -> AptosDB {
+    AptosDB::open_with_cold_store(
+        db_dir,
+        Some(cold_store_dir.path()),
+        false, /* readonly */
+        storage_pruner_config,
+        RocksdbConfig::default(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_move_out_of_order_is_an_error() {
+    let db_dir = TempPath::new();
+    let cold_store_dir = TempPath::new();
+    let db = open_db(&db_dir, &cold_store_dir);
+
+    let cold_store = db.cold_store.as_ref().unwrap();
+    assert!(cold_store.move_from(&db.db, 1, 2).is_err());
+    assert_eq!(cold_store.horizon(), 0);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_move_ledger_history(input in arb_blocks_to_commit()) {
+        let db_dir = TempPath::new();
+        let cold_store_dir = TempPath::new();
+        let db = open_db(&db_dir, &cold_store_dir);
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            cur_ver += txns_to_commit.len() as Version;
+        }
+        let latest_ledger_info = input.last().unwrap().1.clone();
+        let target_horizon = latest_ledger_info.ledger_info().version() / 2;
+
+        prop_assert_eq!(
+            db.move_ledger_history_to_cold_store(target_horizon, 2 /* batch_size */)
+                .unwrap(),
+            target_horizon
+        );
+        prop_assert!(db
+            .move_ledger_history_to_cold_store(target_horizon + cur_ver, 2 /* batch_size */)
+            .is_err());
+        prop_assert_eq!(db.transaction_store.get_first_txn_version().unwrap(), Some(0));
+        if target_horizon > 0 {
+            prop_assert!(db.db.get::<TransactionSchema>(&0).unwrap().is_none());
+        }
+
+        // The moved history is read from the cold store, also after reopening the DB.
+        drop(db);
+        let db = open_db(&db_dir, &cold_store_dir);
+        prop_assert_eq!(db.cold_store.as_ref().unwrap().horizon(), target_horizon);
+        let mut cur_ver = 0;
+        for (txns_to_commit, _) in &input {
+            verify_committed_transactions(
+                &db,
+                txns_to_commit,
+                cur_ver,
+                &latest_ledger_info,
+                false, /* is_latest */
+            );
+            for (i, txn_to_commit) in txns_to_commit.iter().enumerate() {
+                let version = cur_ver + i as Version;
+                prop_assert_eq!(
+                    &db.transaction_store.get_write_set(version).unwrap(),
+                    txn_to_commit.write_set()
+                );
+                prop_assert_eq!(
+                    db.event_store.get_events_by_version(version).unwrap(),
+                    txn_to_commit.events().to_vec()
+                );
+            }
+            cur_ver += txns_to_commit.len() as Version;
+        }
+        let txns = db
+            .transaction_store
+            .get_transaction_iter(0, cur_ver as usize)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        prop_assert_eq!(
+            txns,
+            input
+                .iter()
+                .flat_map(|(txns_to_commit, _)| txns_to_commit)
+                .map(|txn_to_commit| txn_to_commit.transaction().clone())
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_first_version_after_move_and_reopen(input in arb_blocks_to_commit()) {
+        let db_dir = TempPath::new();
+        let cold_store_dir = TempPath::new();
+        let db = open_db(&db_dir, &cold_store_dir);
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            cur_ver += txns_to_commit.len() as Version;
+        }
+        let latest_version = input.last().unwrap().1.ledger_info().version();
+        db.move_ledger_history_to_cold_store(latest_version / 2 + 1, 2 /* batch_size */)
+            .unwrap();
+
+        // With pruning enabled, the first versions come from the ledger pruner, which must count
+        // the history in the cold store as readable.
+        drop(db);
+        let pruning_config = StoragePrunerConfig::new(
+            Some(u64::MAX), /* state_store_prune_window */
+            Some(u64::MAX), /* ledger_store_prune_window */
+            10_000,         /* pruning_batch_size */
+        );
+        let db = open_db_with_pruner(&db_dir, &cold_store_dir, pruning_config);
+        prop_assert_eq!(db.get_first_txn_version().unwrap(), Some(0));
+        prop_assert_eq!(db.get_first_write_set_version().unwrap(), Some(0));
+    }
+
+    #[test]
+    fn test_reconcile_interrupted_move(input in arb_blocks_to_commit()) {
+        let db_dir = TempPath::new();
+        let cold_store_dir = TempPath::new();
+        let db = open_db(&db_dir, &cold_store_dir);
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in &input {
+            db.save_transactions(txns_to_commit, cur_ver, Some(ledger_info_with_sigs))
+                .unwrap();
+            cur_ver += txns_to_commit.len() as Version;
+        }
+        let latest_ledger_info = input.last().unwrap().1.clone();
+        let target_horizon = latest_ledger_info.ledger_info().version() / 2 + 1;
+
+        // Simulate a crash after the last batch was written to the cold store, but before it was
+        // deleted from the main DB.
+        let cold_store = db.cold_store.as_ref().unwrap();
+        let mut cold_batch = SchemaBatch::new();
+        for res in versions_in_range::<TransactionSchema>(&db.db, 0, target_horizon).unwrap() {
+            let (version, transaction) = res.unwrap();
+            cold_batch.put::<TransactionSchema>(&version, &transaction).unwrap();
+        }
+        for res in versions_in_range::<WriteSetSchema>(&db.db, 0, target_horizon).unwrap() {
+            let (version, write_set) = res.unwrap();
+            cold_batch.put::<WriteSetSchema>(&version, &write_set).unwrap();
+        }
+        for res in versions_in_range::<EventSchema>(&db.db, 0, target_horizon).unwrap() {
+            let (key, event) = res.unwrap();
+            cold_batch.put::<EventSchema>(&key, &event).unwrap();
+        }
+        cold_store.db.write_schemas(cold_batch).unwrap();
+
+        drop(db);
+        let db = open_db(&db_dir, &cold_store_dir);
+        prop_assert_eq!(db.cold_store.as_ref().unwrap().horizon(), target_horizon);
+        prop_assert!(db.db.get::<TransactionSchema>(&0).unwrap().is_none());
+        prop_assert!(db.db.get::<WriteSetSchema>(&0).unwrap().is_none());
+        prop_assert_eq!(
+            versions_in_range::<EventSchema>(&db.db, 0, target_horizon).unwrap().count(),
+            0
+        );
+
+        let mut cur_ver = 0;
+        for (txns_to_commit, _) in &input {
+            verify_committed_transactions(
+                &db,
+                txns_to_commit,
+                cur_ver,
+                &latest_ledger_info,
+                false, /* is_latest */
+            );
+            cur_ver += txns_to_commit.len() as Version;
+        }
+    }
+}
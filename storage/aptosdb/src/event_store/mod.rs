@@ -8,11 +8,15 @@
 use super::AptosDB;
 use crate::{
     change_set::ChangeSet,
+    cold_store::{ColdStore, TieredDb, TieredIter},
     errors::AptosDbError,
     ledger_counters::{LedgerCounter, LedgerCounterBumps},
     schema::{
-        event::EventSchema, event_accumulator::EventAccumulatorSchema,
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
+        event_by_key::EventByKeySchema,
+        event_by_type::{hash_struct_tag, EventByTypeSchema},
+        event_by_version::EventByVersionSchema,
     },
 };
 use accumulator::{HashReader, MerkleAccumulator};
@@ -30,7 +34,8 @@ use aptos_types::{
     proof::{position::Position, EventAccumulatorProof, EventProof},
     transaction::Version,
 };
-use schemadb::{schema::ValueCodec, ReadOptions, SchemaBatch, SchemaIterator, DB};
+use move_deps::move_core_types::language_storage::{StructTag, TypeTag};
+use schemadb::{schema::ValueCodec, ReadOptions, SchemaBatch, DB};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     convert::{TryFrom, TryInto},
@@ -41,11 +46,16 @@ use std::{
 #[derive(Debug)]
 pub struct EventStore {
     db: Arc<DB>,
+    // Events below the horizon of the cold store are read from it.
+    history: TieredDb,
 }
 
 impl EventStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, cold_store: Option<Arc<ColdStore>>) -> Self {
+        Self {
+            history: TieredDb::new(Arc::clone(&db), cold_store),
+            db,
+        }
     }
 
     /// Get all of the events given a transaction version.
@@ -54,9 +64,8 @@ impl EventStore {
     pub fn get_events_by_version(&self, version: Version) -> Result<Vec<ContractEvent>> {
         let mut events = vec![];
 
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
         // Grab the first event and then iterate until we get all events for this version.
-        iter.seek(&version)?;
+        let mut iter = self.history.iter::<EventSchema>(version)?;
         while let Some(((ver, index), event)) = iter.next().transpose()? {
             if ver != version {
                 break;
@@ -72,11 +81,8 @@ impl EventStore {
         start_version: Version,
         num_versions: usize,
     ) -> Result<EventsByVersionIter> {
-        let mut iter = self.db.iter::<EventSchema>(Default::default())?;
-        iter.seek(&start_version)?;
-
         Ok(EventsByVersionIter {
-            inner: iter.peekable(),
+            inner: self.history.iter::<EventSchema>(start_version)?.peekable(),
             expected_next_version: start_version,
             end_version: start_version
                 .checked_add(num_versions as u64)
@@ -89,7 +95,7 @@ impl EventStore {
         version: Version,
         index: u64,
     ) -> Result<ContractEvent> {
-        self.db
+        self.history
             .get::<EventSchema>(&(version, index))?
             .ok_or_else(|| {
                 AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)).into()
@@ -106,12 +112,19 @@ impl EventStore {
         let event = self.get_event_by_version_and_index(version, index)?;
 
         // Get the number of events in total for the transaction at `version`.
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
-        iter.seek_for_prev(&(version + 1))?;
-        let num_events = match iter.next().transpose()? {
-            Some(((ver, index), _)) if ver == version => (index + 1),
-            _ => unreachable!(), // since we've already got at least one event above
-        };
+        let mut num_events = None;
+        for db in self.history.dbs(version) {
+            let mut iter = db.iter::<EventSchema>(ReadOptions::default())?;
+            iter.seek_for_prev(&(version + 1))?;
+            if let Some(((ver, index), _)) = iter.next().transpose()? {
+                if ver == version {
+                    num_events = Some(index + 1);
+                    break;
+                }
+            }
+        }
+        // since we've already got at least one event above
+        let num_events = num_events.expect("Events of the version must exist.");
 
         // Get proof.
         let proof =
@@ -208,6 +221,58 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Returns events of type `struct_tag` emitted at or after the `start_index`-th event of the
+    /// transaction at `start_version`, identified by transaction version and index among all events
+    /// emitted by the same transaction. Result won't contain records with a transaction version >
+    /// `ledger_version` and is in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64)>> {
+        let struct_tag_hash = hash_struct_tag(struct_tag)?;
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&(struct_tag_hash, start_version, start_index))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((hash, ver, idx), ()) = res?;
+            if hash != struct_tag_hash || ver > ledger_version {
+                break;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
+    /// Like [`Self::lookup_events_by_type`], but returns the events themselves.
+    pub fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64, ContractEvent)>> {
+        self.lookup_events_by_type(
+            struct_tag,
+            start_version,
+            start_index,
+            limit,
+            ledger_version,
+        )?
+        .into_iter()
+        .map(|(version, index)| {
+            let event = self.get_event_by_version_and_index(version, index)?;
+            Ok((version, index, event))
+        })
+        .collect()
+    }
+
     fn lookup_event_by_key(
         &self,
         event_key: &EventKey,
@@ -252,6 +317,12 @@ impl EventStore {
                     &(*event.key(), version, event.sequence_number()),
                     &(idx as u64),
                 )?;
+                if let TypeTag::Struct(struct_tag) = event.type_tag() {
+                    cs.batch.put::<EventByTypeSchema>(
+                        &(hash_struct_tag(struct_tag)?, version, idx as u64),
+                        &(),
+                    )?;
+                }
                 Ok(())
             })?;
 
@@ -425,6 +496,13 @@ impl EventStore {
                     event.sequence_number(),
                 ))?;
                 db_batch.delete::<EventByKeySchema>(&(*event.key(), event.sequence_number()))?;
+                if let TypeTag::Struct(struct_tag) = event.type_tag() {
+                    db_batch.delete::<EventByTypeSchema>(&(
+                        hash_struct_tag(struct_tag)?,
+                        current_version as u64,
+                        current_index as u64,
+                    ))?;
+                }
                 db_batch.delete::<EventSchema>(&(current_version as u64, current_index as u64))?;
             }
             current_version += 1;
@@ -492,7 +570,7 @@ impl HashReader for EmptyReader {
 }
 
 pub struct EventsByVersionIter<'a> {
-    inner: Peekable<SchemaIterator<'a, EventSchema>>,
+    inner: Peekable<TieredIter<'a, EventSchema>>,
     expected_next_version: Version,
     end_version: Version,
 }
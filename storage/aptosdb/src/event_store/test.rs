@@ -367,3 +367,88 @@ proptest! {
         test_get_last_version_before_timestamp_impl(new_block_events)
     }
 }
+
+fn test_get_events_by_type_impl(event_batches: Vec<Vec<ContractEvent>>) {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.event_store;
+
+    let mut expected: HashMap<StructTag, Vec<(Version, u64)>> = HashMap::new();
+    for (ver, events) in event_batches.iter().enumerate() {
+        save(store, ver as Version, events);
+        for (idx, event) in events.iter().enumerate() {
+            if let TypeTag::Struct(struct_tag) = event.type_tag() {
+                expected
+                    .entry(struct_tag.clone())
+                    .or_default()
+                    .push((ver as Version, idx as u64));
+            }
+        }
+    }
+    let ledger_version = event_batches.len() as Version;
+
+    for (struct_tag, indices) in expected {
+        // All at once.
+        let events = store
+            .get_events_by_type(&struct_tag, 0, 0, u64::max_value(), ledger_version)
+            .unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .map(|(ver, idx, _)| (*ver, *idx))
+                .collect::<Vec<_>>(),
+            indices
+        );
+        for (ver, idx, event) in &events {
+            assert_eq!(event, &event_batches[*ver as usize][*idx as usize]);
+        }
+
+        // Page by page, resuming after the last event of the previous page.
+        let mut paged = vec![];
+        let (mut start_version, mut start_index) = (0, 0);
+        loop {
+            let page = store
+                .lookup_events_by_type(&struct_tag, start_version, start_index, 2, ledger_version)
+                .unwrap();
+            match page.last() {
+                Some((ver, idx)) => {
+                    start_version = *ver;
+                    start_index = idx + 1;
+                }
+                None => break,
+            }
+            paged.extend(page);
+        }
+        assert_eq!(paged, indices);
+
+        // Nothing past the ledger version.
+        let (first_version, _) = indices[0];
+        if first_version > 0 {
+            assert!(store
+                .lookup_events_by_type(&struct_tag, 0, 0, 100, first_version - 1)
+                .unwrap()
+                .is_empty());
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_get_events_by_type(
+        mut universe in any_with::<AccountInfoUniverse>(3),
+        gen_batches in vec(vec((any::<Index>(), any::<ContractEventGen>()), 0..=2), 0..50),
+    ) {
+        let event_batches = gen_batches
+            .into_iter()
+            .map(|gens| {
+                gens.into_iter()
+                    .map(|(index, gen)| gen.materialize(*index, &mut universe))
+                    .collect()
+            })
+            .collect();
+
+        test_get_events_by_type_impl(event_batches);
+    }
+}
@@ -19,19 +19,22 @@ use aptos_crypto::{
     hash::{CryptoHash, TransactionAccumulatorHasher},
     HashValue,
 };
+use aptos_logger::prelude::*;
 use aptos_types::{
     epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
     proof::{
-        definition::LeafCount, position::Position, AccumulatorConsistencyProof,
-        TransactionAccumulatorProof, TransactionAccumulatorRangeProof, TransactionInfoWithProof,
+        definition::LeafCount,
+        position::{FrozenSubTreeIterator, Position},
+        AccumulatorConsistencyProof, TransactionAccumulatorProof, TransactionAccumulatorRangeProof,
+        TransactionInfoWithProof,
     },
     transaction::{TransactionInfo, Version},
 };
 use arc_swap::ArcSwap;
 use itertools::Itertools;
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
-use std::{ops::Deref, sync::Arc};
+use std::{collections::HashMap, ops::Deref, sync::Arc};
 
 #[derive(Debug)]
 pub struct LedgerStore {
@@ -41,10 +44,41 @@ pub struct LedgerStore {
     /// cache it in memory in order to avoid reading DB and deserializing the object frequently. It
     /// should be updated every time new ledger info and signatures are persisted.
     latest_ledger_info: ArcSwap<Option<LedgerInfoWithSignatures>>,
+
+    /// Appending to the transaction accumulator reads the roots of its frozen subtrees, so the
+    /// ones of the latest committed accumulator are cached in memory. It should be updated every
+    /// time transaction infos are persisted.
+    frozen_subtrees: ArcSwap<Option<FrozenSubtrees>>,
+}
+
+/// The roots of the frozen subtrees of the transaction accumulator with `num_leaves` leaves.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FrozenSubtrees {
+    num_leaves: LeafCount,
+    roots: HashMap<Position, HashValue>,
+}
+
+impl FrozenSubtrees {
+    /// Reads the frozen subtrees of the accumulator with `num_leaves` leaves. Their roots are
+    /// taken from `new_nodes` if they were just frozen, read via `reader` otherwise.
+    fn read(
+        reader: &impl HashReader,
+        num_leaves: LeafCount,
+        new_nodes: &[(Position, HashValue)],
+    ) -> Result<Self> {
+        let new_nodes: HashMap<_, _> = new_nodes.iter().cloned().collect();
+        let roots = FrozenSubTreeIterator::new(num_leaves)
+            .map(|position| match new_nodes.get(&position) {
+                Some(hash) => Ok((position, *hash)),
+                None => Ok((position, reader.get(position)?)),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { num_leaves, roots })
+    }
 }
 
 impl LedgerStore {
-    pub fn new(db: Arc<DB>) -> Self {
+    pub fn new(db: Arc<DB>) -> Result<Self> {
         // Upon restart, read the latest ledger info and signatures and cache them in memory.
         let ledger_info = {
             let mut iter = db
@@ -57,10 +91,52 @@ impl LedgerStore {
                 .map(|kv| kv.1)
         };
 
-        Self {
+        let ledger_store = Self {
             db,
             latest_ledger_info: ArcSwap::from(Arc::new(ledger_info)),
+            frozen_subtrees: ArcSwap::from(Arc::new(None)),
+        };
+        ledger_store.check_root_hash()?;
+        match ledger_store.read_frozen_subtrees() {
+            Ok(frozen_subtrees) => ledger_store.set_frozen_subtrees(frozen_subtrees),
+            Err(error) => error!(
+                error = ?error,
+                "Failed to load the frozen subtrees of the transaction accumulator, appending to it reads them from DB."
+            ),
         }
+        Ok(ledger_store)
+    }
+
+    /// Checks that the transaction accumulator matches the root hash of the latest ledger info,
+    /// so that a corrupted accumulator is neither cached nor appended to.
+    fn check_root_hash(&self) -> Result<()> {
+        if let Some(ledger_info_with_sigs) = self.get_latest_ledger_info_option() {
+            let ledger_info = ledger_info_with_sigs.ledger_info();
+            // The accumulator may not reach the ledger info yet, e.g. while restoring a backup,
+            // the epoch ending ledger infos are restored before the transactions.
+            if let Ok(root_hash) = self.get_root_hash(ledger_info.version()) {
+                ensure!(
+                    root_hash == ledger_info.transaction_accumulator_hash(),
+                    "DB corruption: transaction accumulator root hash at version {} is {}, latest ledger info expects {}",
+                    ledger_info.version(),
+                    root_hash,
+                    ledger_info.transaction_accumulator_hash(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the frozen subtrees of the transaction accumulator of the latest transaction.
+    fn read_frozen_subtrees(&self) -> Result<FrozenSubtrees> {
+        let num_leaves = self
+            .get_latest_transaction_info_option()?
+            .map_or(0, |(version, _)| version + 1);
+        FrozenSubtrees::read(self, num_leaves, &[])
+    }
+
+    pub fn set_frozen_subtrees(&self, frozen_subtrees: FrozenSubtrees) {
+        self.frozen_subtrees.store(Arc::new(Some(frozen_subtrees)));
     }
 
     pub fn get_epoch(&self, version: Version) -> Result<u64> {
@@ -322,16 +398,30 @@ impl LedgerStore {
                 cs.batch.put::<TransactionInfoSchema>(&version, txn_info)
             })?;
 
-        // write hash of txn_info into the accumulator
+        // write hash of txn_info into the accumulator, reading the existing frozen subtrees from
+        // the cache if it is up to date
         let txn_hashes: Vec<HashValue> = txn_infos.iter().map(TransactionInfo::hash).collect();
-        let (root_hash, writes) = Accumulator::append(
-            self,
+        let frozen_subtrees_ptr = self.frozen_subtrees.load();
+        let frozen_subtrees: &Option<_> = frozen_subtrees_ptr.deref();
+        let reader = CachedHashReader {
+            ledger_store: self,
+            frozen_subtrees: frozen_subtrees
+                .as_ref()
+                .filter(|frozen_subtrees| frozen_subtrees.num_leaves == first_version),
+        };
+        let (root_hash, writes) = MerkleAccumulator::<_, TransactionAccumulatorHasher>::append(
+            &reader,
             first_version, /* num_existing_leaves */
             &txn_hashes,
         )?;
         writes
             .iter()
             .try_for_each(|(pos, hash)| cs.batch.put::<TransactionAccumulatorSchema>(pos, hash))?;
+        cs.frozen_subtrees = Some(FrozenSubtrees::read(
+            &reader,
+            first_version + txn_infos.len() as u64,
+            &writes,
+        )?);
         Ok(root_hash)
     }
 
@@ -380,6 +470,25 @@ impl HashReader for LedgerStore {
     }
 }
 
+/// Reads the roots of the frozen subtrees of the transaction accumulator from the cache, and the
+/// other nodes from DB.
+struct CachedHashReader<'a> {
+    ledger_store: &'a LedgerStore,
+    frozen_subtrees: Option<&'a FrozenSubtrees>,
+}
+
+impl<'a> HashReader for CachedHashReader<'a> {
+    fn get(&self, position: Position) -> Result<HashValue> {
+        match self
+            .frozen_subtrees
+            .and_then(|frozen_subtrees| frozen_subtrees.roots.get(&position))
+        {
+            Some(hash) => Ok(*hash),
+            None => self.ledger_store.get(position),
+        }
+    }
+}
+
 pub struct TransactionInfoIter<'a> {
     inner: SchemaIterator<'a, TransactionInfoSchema>,
     expected_next_version: Version,
@@ -4,7 +4,12 @@
 use super::*;
 use crate::AptosDB;
 use aptos_temppath::TempPath;
+use aptos_types::{
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+};
 use proptest::{collection::vec, prelude::*};
+use std::collections::BTreeMap;
 
 fn verify(
     store: &LedgerStore,
@@ -41,9 +46,16 @@ fn save(store: &LedgerStore, first_version: Version, txn_infos: &[TransactionInf
         .put_transaction_infos(first_version, txn_infos, &mut cs)
         .unwrap();
     store.db.write_schemas(cs.batch).unwrap();
+    if let Some(frozen_subtrees) = cs.frozen_subtrees {
+        store.set_frozen_subtrees(frozen_subtrees);
+    }
     root_hash
 }
 
+fn frozen_subtrees(store: &LedgerStore) -> Option<FrozenSubtrees> {
+    store.frozen_subtrees.load().deref().clone()
+}
+
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
 
@@ -70,6 +82,71 @@ proptest! {
         verify(store, &batch1, 0, ledger_version1, root_hash1);
     }
 
+    #[test]
+    fn test_frozen_subtrees_cache(
+        batch1 in vec(any::<TransactionInfo>(), 1..100),
+        batch2 in vec(any::<TransactionInfo>(), 1..100),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.ledger_store;
+        let num_leaves = (batch1.len() + batch2.len()) as u64;
+
+        // The cached frozen subtrees are the ones read from DB, at startup as well.
+        save(store, 0, &batch1);
+        let root_hash = save(store, batch1.len() as u64, &batch2);
+        let cached = frozen_subtrees(store).unwrap();
+        prop_assert_eq!(cached.num_leaves, num_leaves);
+        prop_assert_eq!(
+            &cached,
+            &FrozenSubtrees::read(store, num_leaves, &[]).unwrap()
+        );
+        prop_assert_eq!(
+            frozen_subtrees(&LedgerStore::new(Arc::clone(&store.db)).unwrap()),
+            Some(cached)
+        );
+
+        // Appending to an accumulator other than the cached one reads DB.
+        let mut cs = ChangeSet::new();
+        prop_assert_eq!(
+            store.put_transaction_infos(batch1.len() as u64, &batch2, &mut cs).unwrap(),
+            root_hash
+        );
+        prop_assert_eq!(cs.frozen_subtrees.unwrap().num_leaves, num_leaves);
+    }
+
+    #[test]
+    fn test_root_hash_mismatch_fails_open(batch in vec(any::<TransactionInfo>(), 1..100)) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.ledger_store;
+        let root_hash = save(store, 0, &batch);
+        let put_ledger_info = |transaction_accumulator_hash| {
+            let block_info = BlockInfo::new(
+                0,
+                0,
+                HashValue::zero(),
+                transaction_accumulator_hash,
+                batch.len() as u64 - 1,
+                0,
+                None,
+            );
+            let ledger_info = LedgerInfoWithSignatures::new(
+                LedgerInfo::new(block_info, HashValue::zero()),
+                BTreeMap::new(),
+            );
+            let mut cs = ChangeSet::new();
+            store.put_ledger_info(&ledger_info, &mut cs).unwrap();
+            store.db.write_schemas(cs.batch).unwrap();
+        };
+
+        put_ledger_info(root_hash);
+        prop_assert!(LedgerStore::new(Arc::clone(&store.db)).is_ok());
+        // The ledger info of the same epoch is overwritten.
+        put_ledger_info(HashValue::random());
+        prop_assert!(LedgerStore::new(Arc::clone(&store.db)).is_err());
+    }
+
     #[test]
     fn test_transaction_info_get_iterator(
         (infos, start_version, num_transaction_infos) in
@@ -19,9 +19,11 @@ pub mod metrics;
 pub mod schema;
 
 mod change_set;
+mod cold_store;
 mod event_store;
 mod ledger_counters;
 mod ledger_store;
+mod migration;
 mod pruner;
 mod state_store;
 mod system_store;
@@ -33,27 +35,31 @@ mod aptosdb_test;
 use crate::{
     backup::{backup_handler::BackupHandler, restore_handler::RestoreHandler, restore_utils},
     change_set::{ChangeSet, SealedChangeSet},
+    cold_store::ColdStore,
     errors::AptosDbError,
     event_store::EventStore,
     ledger_counters::LedgerCounters,
     ledger_store::LedgerStore,
     metrics::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
-        OTHER_TIMERS_SECONDS, ROCKSDB_PROPERTIES, STATE_ITEM_COUNT,
+        OTHER_TIMERS_SECONDS, ROCKSDB_PROPERTIES, STATE_ITEM_COUNT, STATE_PROOF_CACHE,
     },
+    migration::{migrations, Migrator},
     pruner::{utils, Pruner},
     schema::*,
     state_store::StateStore,
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
-use anyhow::{ensure, Result};
+use anyhow::{ensure, format_err, Result};
 use aptos_config::config::{RocksdbConfig, StoragePrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_crypto::hash::{HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
+    account_config::AccountResource,
     contract_event::{ContractEvent, EventByVersionWithProof, EventWithProof},
     epoch_change::EpochChangeProof,
     event::EventKey,
@@ -75,9 +81,13 @@ use aptos_types::{
         TransactionOutput, TransactionOutputListWithProof, TransactionToCommit,
         TransactionWithProof, Version,
     },
-    write_set::WriteSet,
+    write_set::{WriteOp, WriteSet},
 };
 use itertools::zip_eq;
+use move_deps::move_core_types::{
+    language_storage::{ResourceKey, StructTag},
+    move_resource::MoveStructType,
+};
 use once_cell::sync::Lazy;
 use schemadb::{ColumnFamilyName, Options, SchemaBatch, DB, DEFAULT_CF_NAME};
 use std::{
@@ -89,10 +99,21 @@ use std::{
     thread::JoinHandle,
     time::{Duration, Instant},
 };
-use storage_interface::{DbReader, DbWriter, Order, StartupInfo, StateSnapshotReceiver, TreeState};
+#[cfg(feature = "table-iteration")]
+use storage_interface::TableItems;
+use storage_interface::{
+    proof_cache::StateProofCache, BlockGasStats, DbWriter, EventReader, LedgerReader,
+    MinuteGasStats, Order, StartupInfo, StateReader, StateSnapshotReceiver, TreeState,
+};
 
 const MAX_LIMIT: u64 = 5000;
 
+/// The maximum number of rows of the table item key index scanned by a `get_table_items` call.
+#[cfg(feature = "table-iteration")]
+const MAX_TABLE_ITEM_ROWS_SCANNED: usize = 4 * MAX_LIMIT as usize;
+
+const STATE_PROOF_CACHE_CAPACITY: usize = 4096;
+
 // TODO: Either implement an iteration API to allow a very old client to loop through a long history
 // or guarantee that there is always a recent enough waypoint and client knows to boot from there.
 const MAX_NUM_EPOCH_ENDING_LEDGER_INFO: usize = 100;
@@ -219,6 +240,8 @@ pub struct AptosDB {
     state_store: Arc<StateStore>,
     event_store: Arc<EventStore>,
     system_store: Arc<SystemStore>,
+    cold_store: Option<Arc<ColdStore>>,
+    state_proof_cache: StateProofCache,
     pruner: Option<Pruner>,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
 }
@@ -227,15 +250,20 @@ impl AptosDB {
     fn column_families() -> Vec<ColumnFamilyName> {
         vec![
             /* LedgerInfo CF = */ DEFAULT_CF_NAME,
+            BLOCK_GAS_STATS_CF_NAME,
+            DB_METADATA_CF_NAME,
             EPOCH_BY_VERSION_CF_NAME,
             EVENT_ACCUMULATOR_CF_NAME,
             EVENT_BY_KEY_CF_NAME,
+            EVENT_BY_TYPE_CF_NAME,
             EVENT_BY_VERSION_CF_NAME,
             EVENT_CF_NAME,
             JELLYFISH_MERKLE_NODE_CF_NAME,
             LEDGER_COUNTERS_CF_NAME,
+            MINUTE_GAS_STATS_CF_NAME,
             STALE_NODE_INDEX_CF_NAME,
             STATE_VALUE_INDEX_CF_NAME,
+            TABLE_ITEM_KEY_CF_NAME,
             TRANSACTION_CF_NAME,
             TRANSACTION_ACCUMULATOR_CF_NAME,
             TRANSACTION_BY_ACCOUNT_CF_NAME,
@@ -245,20 +273,28 @@ impl AptosDB {
         ]
     }
 
-    fn new_with_db(db: DB, storage_pruner_config: StoragePrunerConfig) -> Self {
+    fn new_with_db(
+        db: DB,
+        cold_store: Option<ColdStore>,
+        storage_pruner_config: StoragePrunerConfig,
+    ) -> Result<Self> {
         let db = Arc::new(db);
-        let transaction_store = Arc::new(TransactionStore::new(Arc::clone(&db)));
-        let event_store = Arc::new(EventStore::new(Arc::clone(&db)));
-        let ledger_store = Arc::new(LedgerStore::new(Arc::clone(&db)));
+        let cold_store = cold_store.map(Arc::new);
+        let transaction_store =
+            Arc::new(TransactionStore::new(Arc::clone(&db), cold_store.clone()));
+        let event_store = Arc::new(EventStore::new(Arc::clone(&db), cold_store.clone()));
+        let ledger_store = Arc::new(LedgerStore::new(Arc::clone(&db))?);
         let system_store = Arc::new(SystemStore::new(Arc::clone(&db)));
 
-        AptosDB {
+        Ok(AptosDB {
             db: Arc::clone(&db),
             event_store: Arc::clone(&event_store),
             ledger_store: Arc::clone(&ledger_store),
             state_store: Arc::new(StateStore::new(Arc::clone(&db))),
             transaction_store: Arc::clone(&transaction_store),
             system_store: Arc::clone(&system_store),
+            cold_store,
+            state_proof_cache: StateProofCache::new(STATE_PROOF_CACHE_CAPACITY),
             pruner: match storage_pruner_config {
                 NO_OP_STORAGE_PRUNER_CONFIG => None,
                 _ => Some(Pruner::new(
@@ -270,7 +306,7 @@ impl AptosDB {
                 )),
             },
             _rocksdb_property_reporter: RocksdbPropertyReporter::new(Arc::clone(&db)),
-        }
+        })
     }
 
     pub fn open<P: AsRef<Path> + Clone>(
@@ -278,6 +314,24 @@ impl AptosDB {
         readonly: bool,
         storage_pruner_config: StoragePrunerConfig,
         rocksdb_config: RocksdbConfig,
+    ) -> Result<Self> {
+        Self::open_with_cold_store(
+            db_root_path,
+            None,
+            readonly,
+            storage_pruner_config,
+            rocksdb_config,
+        )
+    }
+
+    /// Opens the DB together with the cold store at `cold_store_dir`, if any, from which the
+    /// ledger history moved out of the DB is read.
+    pub fn open_with_cold_store<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        cold_store_dir: Option<&Path>,
+        readonly: bool,
+        storage_pruner_config: StoragePrunerConfig,
+        rocksdb_config: RocksdbConfig,
     ) -> Result<Self> {
         ensure!(
             storage_pruner_config.eq(&NO_OP_STORAGE_PRUNER_CONFIG) || !readonly,
@@ -306,8 +360,21 @@ impl AptosDB {
                 &rocksdb_opts,
             )?
         };
+        let migrator = Migrator::new(migrations());
+        if readonly {
+            migrator.ensure_migrated(&db)?;
+        } else {
+            migrator.migrate(&db)?;
+        }
 
-        let ret = Self::new_with_db(db, storage_pruner_config);
+        let cold_store = cold_store_dir
+            .map(|dir| ColdStore::open(dir.join("cold_store"), readonly, &rocksdb_opts))
+            .transpose()?;
+
+        let ret = Self::new_with_db(db, cold_store, storage_pruner_config)?;
+        if !readonly {
+            ret.reconcile()?;
+        }
         info!(
             path = path,
             time_ms = %instant.elapsed().as_millis(),
@@ -327,16 +394,33 @@ impl AptosDB {
         rocksdb_config.max_open_files = -1;
         let rocksdb_opts = gen_rocksdb_options(&rocksdb_config);
 
-        Ok(Self::new_with_db(
-            DB::open_as_secondary(
-                primary_path,
-                secondary_path,
-                "aptosdb_sec",
-                Self::column_families(),
-                &rocksdb_opts,
-            )?,
-            NO_OP_STORAGE_PRUNER_CONFIG,
-        ))
+        let db = DB::open_as_secondary(
+            primary_path,
+            secondary_path,
+            "aptosdb_sec",
+            Self::column_families(),
+            &rocksdb_opts,
+        )?;
+        // The primary migrates the DB.
+        Migrator::new(migrations()).check(&db)?;
+        Self::new_with_db(db, None /* cold_store */, NO_OP_STORAGE_PRUNER_CONFIG)
+    }
+
+    /// Checks that the DB under `db_root_path`, if there is one, has a schema version this node can
+    /// open. Only the DB metadata is read, so that it can run before the DB is opened read-write.
+    pub fn check_schema_version<P: AsRef<Path>>(db_root_path: P) -> Result<()> {
+        let path = db_root_path.as_ref().join("aptosdb");
+        if !path.exists() {
+            return Ok(());
+        }
+        let db = DB::open_readonly(
+            path,
+            "aptosdb_schema_check",
+            vec![DEFAULT_CF_NAME, DB_METADATA_CF_NAME],
+            &Options::default(),
+        )?;
+        Migrator::new(migrations()).check(&db)?;
+        Ok(())
     }
 
     /// This opens db in non-readonly mode, without the pruner.
@@ -351,6 +435,69 @@ impl AptosDB {
         .expect("Unable to open AptosDB")
     }
 
+    /// Repairs what a crash may have left behind when the DB was last written.
+    ///
+    /// The ledger and the state of committed transactions, and their ledger info, are written in a
+    /// single synced write batch (see `commit`), so a crash can't persist part of a commit. The
+    /// ledger history moved to the cold store is the only data written to two DBs.
+    fn reconcile(&self) -> Result<()> {
+        if let Some(cold_store) = &self.cold_store {
+            cold_store.reconcile(&self.db)?;
+        }
+        Ok(())
+    }
+
+    /// Errors if the state at `version` is pruned already.
+    #[cfg(feature = "table-iteration")]
+    fn error_if_state_pruned(&self, data_type: &str, version: Version) -> Result<()> {
+        if let Some(pruner) = self.pruner.as_ref() {
+            let min_readable_version = pruner.get_least_readable_state_version();
+            ensure!(
+                version >= min_readable_version,
+                "{} at version {} is pruned, min available version is {}.",
+                data_type,
+                version,
+                min_readable_version
+            );
+        }
+        Ok(())
+    }
+
+    /// Moves the transactions, write sets and events below `target_horizon` to the cold store, at
+    /// most `batch_size` versions at a time, and returns the new horizon of the cold store. They
+    /// stay readable throughout.
+    pub fn move_ledger_history_to_cold_store(
+        &self,
+        target_horizon: Version,
+        batch_size: usize,
+    ) -> Result<Version> {
+        let cold_store = self
+            .cold_store
+            .as_ref()
+            .ok_or_else(|| format_err!("The DB is opened without a cold store."))?;
+        let latest_version = self
+            .ledger_store
+            .get_latest_ledger_info()?
+            .ledger_info()
+            .version();
+        ensure!(
+            target_horizon <= latest_version,
+            "Target horizon {} is beyond the latest version {}.",
+            target_horizon,
+            latest_version,
+        );
+        ensure!(batch_size > 0, "Batch size must be positive.");
+
+        let mut horizon = cold_store.horizon();
+        while horizon < target_horizon {
+            let end = std::cmp::min(horizon + batch_size as u64, target_horizon);
+            cold_store.move_from(&self.db, horizon, end)?;
+            info!(horizon = end, "Moved ledger history to the cold store.");
+            horizon = end;
+        }
+        Ok(horizon)
+    }
+
     /// This force the db to update rocksdb properties immediately.
     pub fn update_rocksdb_properties(&self) -> Result<()> {
         update_rocksdb_properties(&self.db)
@@ -378,12 +525,13 @@ impl AptosDB {
         end_epoch: u64,
         limit: usize,
     ) -> Result<(Vec<LedgerInfoWithSignatures>, bool)> {
-        ensure!(
-            start_epoch <= end_epoch,
-            "Bad epoch range [{}, {})",
-            start_epoch,
-            end_epoch,
-        );
+        if start_epoch > end_epoch {
+            return Err(AptosDbError::InvalidArgument(format!(
+                "Bad epoch range [{}, {})",
+                start_epoch, end_epoch,
+            ))
+            .into());
+        }
         // Note that the latest epoch can be the same with the current epoch (in most cases), or
         // current_epoch + 1 (when the latest ledger_info carries next validator set)
         let latest_epoch = self
@@ -491,6 +639,18 @@ impl AptosDB {
         })
     }
 
+    /// Flushes the memtables of all column families to disk, so that reopening the DB doesn't
+    /// need to replay the WAL. Called when the node shuts down.
+    pub fn flush(&self) -> Result<()> {
+        let start = Instant::now();
+        self.db.flush_all().map(|_| {
+            info!(
+                time_ms = %start.elapsed().as_millis(),
+                "Flushed AptosDB."
+            );
+        })
+    }
+
     // ================================== Private APIs ==================================
     fn get_events_with_proof_by_event_key(
         &self,
@@ -585,7 +745,13 @@ impl AptosDB {
             None
         };
 
-        Ok((SealedChangeSet { batch: cs.batch }, counters))
+        Ok((
+            SealedChangeSet {
+                batch: cs.batch,
+                frozen_subtrees: cs.frozen_subtrees,
+            },
+            counters,
+        ))
     }
 
     fn save_transactions_impl(
@@ -650,6 +816,14 @@ impl AptosDB {
                 .put_transaction_infos(first_version, &txn_infos, cs)?
         };
 
+        {
+            let _timer = OTHER_TIMERS_SECONDS
+                .with_label_values(&["save_transactions_gas_stats"])
+                .start_timer();
+            self.system_store
+                .put_block_gas_stats(first_version, txns_to_commit, cs)?;
+        }
+
         Ok(new_root_hash)
     }
 
@@ -658,6 +832,9 @@ impl AptosDB {
     /// LedgerCounters.
     fn commit(&self, sealed_cs: SealedChangeSet) -> Result<()> {
         self.db.write_schemas(sealed_cs.batch)?;
+        if let Some(frozen_subtrees) = sealed_cs.frozen_subtrees {
+            self.ledger_store.set_frozen_subtrees(frozen_subtrees);
+        }
 
         Ok(())
     }
@@ -669,7 +846,7 @@ impl AptosDB {
     }
 }
 
-impl DbReader for AptosDB {
+impl LedgerReader for AptosDB {
     fn get_epoch_ending_ledger_infos(
         &self,
         start_epoch: u64,
@@ -682,28 +859,6 @@ impl DbReader for AptosDB {
         })
     }
 
-    fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
-        gauged_api("get_latest_state_value", || {
-            let ledger_info_with_sigs = self.ledger_store.get_latest_ledger_info()?;
-            let version = ledger_info_with_sigs.ledger_info().version();
-            let (blob, _proof) = self
-                .state_store
-                .get_value_with_proof_by_version(&state_key, version)?;
-            Ok(blob)
-        })
-    }
-
-    fn get_state_values_by_key_prefix(
-        &self,
-        key_prefix: &StateKeyPrefix,
-        version: Version,
-    ) -> Result<HashMap<StateKey, StateValue>> {
-        gauged_api("get_state_values_by_key_prefix", || {
-            self.state_store
-                .get_values_by_key_prefix(key_prefix, version)
-        })
-    }
-
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         gauged_api("get_latest_ledger_info_option", || {
             Ok(self.ledger_store.get_latest_ledger_info_option())
@@ -927,40 +1082,34 @@ impl DbReader for AptosDB {
         })
     }
 
-    fn get_events(
+    /// Gets the gas paid by user transactions in up to `limit` blocks started at or before
+    /// `ledger_version`, latest block first.
+    ///
+    /// Used by the API to estimate gas prices from recent activity.
+    fn get_block_gas_stats(
         &self,
-        event_key: &EventKey,
-        start: u64,
-        order: Order,
+        ledger_version: Version,
         limit: u64,
-    ) -> Result<Vec<(u64, ContractEvent)>> {
-        gauged_api("get_events", || {
-            let events_with_proofs =
-                self.get_events_with_proofs(event_key, start, order, limit, None)?;
-            let events = events_with_proofs
-                .into_iter()
-                .map(|e| (e.transaction_version, e.event))
-                .collect();
-            Ok(events)
+    ) -> Result<Vec<BlockGasStats>> {
+        gauged_api("get_block_gas_stats", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            self.system_store
+                .get_block_gas_stats(ledger_version, limit as usize)
         })
     }
 
-    fn get_events_with_proofs(
+    /// Gets the gas paid by user transactions in the blocks started within each of up to `limit`
+    /// minutes up to the one `timestamp_usecs` is in, latest minute first. The stats of the
+    /// latest minute grow as blocks are committed.
+    fn get_minute_gas_stats(
         &self,
-        event_key: &EventKey,
-        start: u64,
-        order: Order,
+        timestamp_usecs: u64,
         limit: u64,
-        known_version: Option<u64>,
-    ) -> Result<Vec<EventWithProof>> {
-        gauged_api("get_events_with_proofs", || {
-            let version = match known_version {
-                Some(version) => version,
-                None => self.get_latest_version()?,
-            };
-            let events =
-                self.get_events_with_proof_by_event_key(event_key, start, order, limit, version)?;
-            Ok(events)
+    ) -> Result<Vec<MinuteGasStats>> {
+        gauged_api("get_minute_gas_stats", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            self.system_store
+                .get_minute_gas_stats(timestamp_usecs, limit as usize)
         })
     }
 
@@ -1005,6 +1154,197 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        gauged_api("get_startup_info", || {
+            self.ledger_store
+                .get_startup_info()?
+                .map(
+                    |(latest_ledger_info, latest_epoch_state_if_not_in_li, synced_version_opt)| {
+                        let committed_tree_state =
+                            self.get_tree_state(Some(latest_ledger_info.ledger_info().version()))?;
+                        let synced_tree_state = synced_version_opt
+                            .map(|v| self.get_tree_state(Some(v)))
+                            .transpose()?;
+
+                        Ok(StartupInfo::new(
+                            latest_ledger_info,
+                            latest_epoch_state_if_not_in_li,
+                            committed_tree_state,
+                            synced_tree_state,
+                        ))
+                    },
+                )
+                .transpose()
+        })
+    }
+
+    fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        gauged_api("get_block_timestamp", || {
+            let ts = match self.transaction_store.get_block_metadata(version)? {
+                Some((_v, block_meta)) => block_meta.into_inner().1,
+                // genesis timestamp is 0
+                None => 0,
+            };
+            Ok(ts)
+        })
+    }
+
+    fn get_last_version_before_timestamp(
+        &self,
+        timestamp: u64,
+        ledger_version: Version,
+    ) -> Result<Version> {
+        gauged_api("get_last_version_before_timestamp", || {
+            self.event_store
+                .get_last_version_before_timestamp(timestamp, ledger_version)
+        })
+    }
+
+    fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
+        gauged_api("get_latest_transaction_info_option", || {
+            self.ledger_store.get_latest_transaction_info_option()
+        })
+    }
+
+    fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
+        gauged_api("get_accumulator_root_hash", || {
+            self.ledger_store.get_root_hash(version)
+        })
+    }
+
+    fn get_accumulator_consistency_proof(
+        &self,
+        client_known_version: Option<Version>,
+        ledger_version: Version,
+    ) -> Result<AccumulatorConsistencyProof> {
+        gauged_api("get_accumulator_consistency_proof", || {
+            self.ledger_store
+                .get_consistency_proof(client_known_version, ledger_version)
+        })
+    }
+
+    fn get_ledger_prune_window(&self) -> Result<Option<usize>> {
+        gauged_api("get_ledger_prune_window", || {
+            Ok(self
+                .pruner
+                .as_ref()
+                .map(|x| x.get_ledger_pruner_window() as usize))
+        })
+    }
+}
+
+impl StateReader for AptosDB {
+    fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
+        gauged_api("get_latest_state_value", || {
+            let ledger_info_with_sigs = self.ledger_store.get_latest_ledger_info()?;
+            let version = ledger_info_with_sigs.ledger_info().version();
+            let (blob, _proof) = self
+                .state_store
+                .get_value_with_proof_by_version(&state_key, version)?;
+            Ok(blob)
+        })
+    }
+
+    fn get_state_values_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        gauged_api("get_state_values_by_key_prefix", || {
+            self.state_store
+                .get_values_by_key_prefix(key_prefix, version)
+        })
+    }
+
+    fn get_state_values_by_key_prefixes(
+        &self,
+        key_prefixes: &[StateKeyPrefix],
+        version: Version,
+    ) -> Result<Vec<HashMap<StateKey, StateValue>>> {
+        gauged_api("get_state_values_by_key_prefixes", || {
+            self.state_store
+                .get_values_by_key_prefixes(key_prefixes, version)
+        })
+    }
+
+    fn get_first_write_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        gauged_api("get_first_write_version", || {
+            self.state_store.get_first_write_version(state_key, version)
+        })
+    }
+
+    fn get_creation_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        gauged_api("get_creation_version", || {
+            self.state_store.get_creation_version(state_key, version)
+        })
+    }
+
+    fn get_write_versions(
+        &self,
+        state_key: &StateKey,
+        start_version: Version,
+        limit: u64,
+        version: Version,
+    ) -> Result<Vec<Version>> {
+        gauged_api("get_write_versions", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            self.state_store
+                .get_write_versions(state_key, start_version, limit as usize, version)
+        })
+    }
+
+    #[cfg(feature = "table-iteration")]
+    fn get_table_items(
+        &self,
+        handle: u128,
+        version: Version,
+        cursor: Option<&[u8]>,
+        limit: u64,
+    ) -> Result<TableItems> {
+        gauged_api("get_table_items", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            let latest_version = self.get_latest_version()?;
+            ensure!(
+                version <= latest_version,
+                "The queried version {} is greater than the committed version {}.",
+                version,
+                latest_version
+            );
+            self.error_if_state_pruned("Table items", version)?;
+            self.state_store.get_table_items(
+                handle,
+                version,
+                cursor,
+                limit as usize,
+                MAX_TABLE_ITEM_ROWS_SCANNED,
+            )
+        })
+    }
+
+    /// Get the net state changes between the states at `first_version` and `last_version`.
+    ///
+    /// Used by the API and the indexer to materialize state incrementally without replaying
+    /// every transaction in the range.
+    fn get_state_diff(
+        &self,
+        first_version: Version,
+        last_version: Version,
+    ) -> Result<Vec<(StateKey, WriteOp)>> {
+        gauged_api("get_state_diff", || {
+            error_if_too_many_requested(last_version.saturating_sub(first_version), MAX_LIMIT)?;
+            self.transaction_store
+                .get_state_diff(first_version, last_version)
+        })
+    }
+
     fn get_state_value_with_proof(
         &self,
         state_store_key: StateKey,
@@ -1012,12 +1352,13 @@ impl DbReader for AptosDB {
         ledger_version: Version,
     ) -> Result<StateValueWithProof> {
         gauged_api("get_state_value_with_proof", || {
-            ensure!(
-                version <= ledger_version,
-                "The queried version {} should be equal to or older than ledger version {}.",
-                version,
-                ledger_version
-            );
+            if version > ledger_version {
+                return Err(AptosDbError::InvalidArgument(format!(
+                    "The queried version {} should be equal to or older than ledger version {}.",
+                    version, ledger_version
+                ))
+                .into());
+            }
             {
                 let latest_version = self.get_latest_version()?;
                 ensure!(
@@ -1031,9 +1372,26 @@ impl DbReader for AptosDB {
             let txn_info_with_proof = self
                 .ledger_store
                 .get_transaction_info_with_proof(version, ledger_version)?;
-            let (state_store_value, sparse_merkle_proof) = self
-                .state_store
-                .get_value_with_proof_by_version(&state_store_key, version)?;
+            let (state_store_value, sparse_merkle_proof) =
+                match self.state_proof_cache.get(&state_store_key, version) {
+                    Some(cached) => {
+                        STATE_PROOF_CACHE.with_label_values(&["hit"]).inc();
+                        cached
+                    }
+                    None => {
+                        STATE_PROOF_CACHE.with_label_values(&["miss"]).inc();
+                        let (state_store_value, sparse_merkle_proof) = self
+                            .state_store
+                            .get_value_with_proof_by_version(&state_store_key, version)?;
+                        self.state_proof_cache.insert(
+                            state_store_key,
+                            version,
+                            state_store_value.clone(),
+                            sparse_merkle_proof.clone(),
+                        );
+                        (state_store_value, sparse_merkle_proof)
+                    }
+                };
             Ok(StateValueWithProof::new(
                 version,
                 state_store_value,
@@ -1042,27 +1400,29 @@ impl DbReader for AptosDB {
         })
     }
 
-    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
-        gauged_api("get_startup_info", || {
-            self.ledger_store
-                .get_startup_info()?
-                .map(
-                    |(latest_ledger_info, latest_epoch_state_if_not_in_li, synced_version_opt)| {
-                        let committed_tree_state =
-                            self.get_tree_state(Some(latest_ledger_info.ledger_info().version()))?;
-                        let synced_tree_state = synced_version_opt
-                            .map(|v| self.get_tree_state(Some(v)))
-                            .transpose()?;
-
-                        Ok(StartupInfo::new(
-                            latest_ledger_info,
-                            latest_epoch_state_if_not_in_li,
-                            committed_tree_state,
-                            synced_tree_state,
-                        ))
-                    },
-                )
-                .transpose()
+    fn get_account_non_existence_proof(
+        &self,
+        address: AccountAddress,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<StateValueWithProof> {
+        gauged_api("get_account_non_existence_proof", || {
+            let state_key = StateKey::AccessPath(AccessPath::resource_access_path(
+                ResourceKey::new(address, AccountResource::struct_tag()),
+            ));
+            let state_value_with_proof =
+                self.get_state_value_with_proof(state_key, version, ledger_version)?;
+            ensure!(
+                state_value_with_proof
+                    .value
+                    .as_ref()
+                    .and_then(|value| value.maybe_bytes.as_ref())
+                    .is_none(),
+                "Account {} exists at version {}.",
+                address,
+                version,
+            );
+            Ok(state_value_with_proof)
         })
     }
 
@@ -1091,14 +1451,99 @@ impl DbReader for AptosDB {
         })
     }
 
-    fn get_block_timestamp(&self, version: u64) -> Result<u64> {
-        gauged_api("get_block_timestamp", || {
-            let ts = match self.transaction_store.get_block_metadata(version)? {
-                Some((_v, block_meta)) => block_meta.into_inner().1,
-                // genesis timestamp is 0
-                None => 0,
+    fn get_state_leaf_count(&self, version: Version) -> Result<usize> {
+        gauged_api("get_state_leaf_count", || {
+            self.state_store.get_value_count(version)
+        })
+    }
+
+    fn get_state_value_chunk_with_proof(
+        &self,
+        version: Version,
+        first_index: usize,
+        chunk_size: usize,
+    ) -> Result<StateValueChunkWithProof> {
+        gauged_api("get_state_value_chunk_with_proof", || {
+            self.state_store
+                .get_value_chunk_with_proof(version, first_index, chunk_size)
+        })
+    }
+
+    fn get_state_prune_window(&self) -> Result<Option<usize>> {
+        gauged_api("get_state_prune_window", || {
+            Ok(self
+                .pruner
+                .as_ref()
+                .map(|x| x.get_state_store_pruner_window() as usize))
+        })
+    }
+}
+
+impl EventReader for AptosDB {
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        gauged_api("get_events", || {
+            let events_with_proofs =
+                self.get_events_with_proofs(event_key, start, order, limit, None)?;
+            let events = events_with_proofs
+                .into_iter()
+                .map(|e| (e.transaction_version, e.event))
+                .collect();
+            Ok(events)
+        })
+    }
+
+    fn get_events_with_proofs(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+        known_version: Option<u64>,
+    ) -> Result<Vec<EventWithProof>> {
+        gauged_api("get_events_with_proofs", || {
+            let version = match known_version {
+                Some(version) => version,
+                None => self.get_latest_version()?,
             };
-            Ok(ts)
+            let events =
+                self.get_events_with_proof_by_event_key(event_key, start, order, limit, version)?;
+            Ok(events)
+        })
+    }
+
+    /// Returns up to `limit` events of type `struct_tag`, across all event streams, starting at the
+    /// `start_index`-th event of the transaction at `start_version`, along with the version and
+    /// index of each event. Events of pruned transactions are skipped, so a feed can be paged
+    /// through from version 0 by resuming after the last event of the previous page.
+    ///
+    /// Only events committed since the index was introduced are indexed.
+    fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64, ContractEvent)>> {
+        gauged_api("get_events_by_type", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+            let (start_version, start_index) = match self.get_first_txn_version()? {
+                Some(first_version) if first_version > start_version => (first_version, 0),
+                _ => (start_version, start_index),
+            };
+            self.event_store.get_events_by_type(
+                struct_tag,
+                start_version,
+                start_index,
+                limit,
+                ledger_version,
+            )
         })
     }
 
@@ -1115,12 +1560,13 @@ impl DbReader for AptosDB {
                 "cannot construct proofs for a version that doesn't exist yet: proof_version: {}, latest_version: {}",
                 proof_version, latest_version,
             );
-            ensure!(
-                event_version <= proof_version,
-                "event_version {} must be <= proof_version {}",
-                event_version,
-                proof_version,
-            );
+            if event_version > proof_version {
+                return Err(AptosDbError::InvalidArgument(format!(
+                    "event_version {} must be <= proof_version {}",
+                    event_version, proof_version,
+                ))
+                .into());
+            }
 
             // Get the latest sequence number of an event at or before the
             // requested event_version.
@@ -1180,76 +1626,6 @@ impl DbReader for AptosDB {
             ))
         })
     }
-
-    fn get_last_version_before_timestamp(
-        &self,
-        timestamp: u64,
-        ledger_version: Version,
-    ) -> Result<Version> {
-        gauged_api("get_last_version_before_timestamp", || {
-            self.event_store
-                .get_last_version_before_timestamp(timestamp, ledger_version)
-        })
-    }
-
-    fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
-        gauged_api("get_latest_transaction_info_option", || {
-            self.ledger_store.get_latest_transaction_info_option()
-        })
-    }
-
-    fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
-        gauged_api("get_accumulator_root_hash", || {
-            self.ledger_store.get_root_hash(version)
-        })
-    }
-
-    fn get_accumulator_consistency_proof(
-        &self,
-        client_known_version: Option<Version>,
-        ledger_version: Version,
-    ) -> Result<AccumulatorConsistencyProof> {
-        gauged_api("get_accumulator_consistency_proof", || {
-            self.ledger_store
-                .get_consistency_proof(client_known_version, ledger_version)
-        })
-    }
-
-    fn get_state_leaf_count(&self, version: Version) -> Result<usize> {
-        gauged_api("get_state_leaf_count", || {
-            self.state_store.get_value_count(version)
-        })
-    }
-
-    fn get_state_value_chunk_with_proof(
-        &self,
-        version: Version,
-        first_index: usize,
-        chunk_size: usize,
-    ) -> Result<StateValueChunkWithProof> {
-        gauged_api("get_state_value_chunk_with_proof", || {
-            self.state_store
-                .get_value_chunk_with_proof(version, first_index, chunk_size)
-        })
-    }
-
-    fn get_state_prune_window(&self) -> Result<Option<usize>> {
-        gauged_api("get_state_prune_window", || {
-            Ok(self
-                .pruner
-                .as_ref()
-                .map(|x| x.get_state_store_pruner_window() as usize))
-        })
-    }
-
-    fn get_ledger_prune_window(&self) -> Result<Option<usize>> {
-        gauged_api("get_ledger_prune_window", || {
-            Ok(self
-                .pruner
-                .as_ref()
-                .map(|x| x.get_ledger_pruner_window() as usize))
-        })
-    }
 }
 
 impl DbWriter for AptosDB {
@@ -1452,7 +1828,11 @@ impl DbWriter for AptosDB {
 
 // Convert requested range and order to a range in ascending order.
 fn get_first_seq_num_and_limit(order: Order, cursor: u64, limit: u64) -> Result<(u64, u64)> {
-    ensure!(limit > 0, "limit should > 0, got {}", limit);
+    if limit == 0 {
+        return Err(
+            AptosDbError::InvalidArgument(format!("limit should > 0, got {}", limit)).into(),
+        );
+    }
 
     Ok(if order == Order::Ascending {
         (cursor, limit)
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics::{
-    register_histogram_vec, register_int_counter, register_int_gauge, register_int_gauge_vec,
-    HistogramVec, IntCounter, IntGauge, IntGaugeVec,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -59,6 +59,18 @@ pub static STATE_ITEM_COUNT: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static STATE_PROOF_CACHE: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_storage_state_proof_cache",
+        // metric description
+        "Lookups of state value proofs in the proof cache",
+        // metric labels (dimensions)
+        &["result"]
+    )
+    .unwrap()
+});
+
 pub static PRUNER_WINDOW: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         // metric name
@@ -157,3 +169,25 @@ pub(crate) static BACKUP_STATE_SNAPSHOT_LEAF_IDX: Lazy<IntGauge> = Lazy::new(||
     )
     .unwrap()
 });
+
+// Schema migration gauges:
+
+pub(crate) static SCHEMA_VERSION: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_storage_schema_version",
+        "Version of the schema of the DB, see `migration`."
+    )
+    .unwrap()
+});
+
+pub(crate) static MIGRATION_ITEMS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_storage_migration_items",
+        // metric description
+        "Number of items migrated by each schema migration.",
+        // metric labels (dimensions)
+        &["migration"]
+    )
+    .unwrap()
+});
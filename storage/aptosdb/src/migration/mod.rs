@@ -0,0 +1,187 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Versioning of the schema of the DB, and the migrations from one version to the next, so that
+//! a change of the layout of a column family or a new index is applied to the DB of an existing
+//! node when it is upgraded, instead of requiring it to resync.
+//!
+//! The schema version is recorded in the DB. When the DB is opened read-write, the migrations to
+//! the schema version of the node are applied in order, before anything else reads the DB. Each
+//! migration runs in batches, and records where it stopped in the same write as each batch, so
+//! that a node restarted in the middle of a migration resumes it where it stopped. Only the main
+//! DB is migrated, not the cold store.
+
+use crate::{
+    metrics::{MIGRATION_ITEMS, SCHEMA_VERSION},
+    schema::db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+};
+use anyhow::{bail, ensure, Result};
+use aptos_logger::prelude::*;
+use schemadb::{SchemaBatch, DB};
+use std::time::Instant;
+
+/// The first schema with a version. The DBs created before schema versions were recorded are at
+/// this version.
+pub(crate) const BASE_SCHEMA_VERSION: u64 = 1;
+
+/// The migrations of the schema, in order: the first one migrates the DB from
+/// `BASE_SCHEMA_VERSION`, and the schema version of the node is the one the last one migrates the
+/// DB to.
+pub(crate) fn migrations() -> Vec<Box<dyn Migration>> {
+    vec![]
+}
+
+/// A change of the schema, applied to the DBs at the version just below `schema_version`.
+pub(crate) trait Migration: Send + Sync {
+    /// The schema version the migration brings the DB to.
+    fn schema_version(&self) -> u64;
+
+    fn name(&self) -> &'static str;
+
+    /// Adds the next batch of the migration to `batch`, resuming from `progress`, which is None
+    /// for the first batch.
+    fn migrate_batch(
+        &self,
+        db: &DB,
+        progress: Option<&[u8]>,
+        batch: &mut SchemaBatch,
+    ) -> Result<MigrationStep>;
+}
+
+/// What a batch of a migration did.
+#[derive(Debug)]
+pub(crate) struct MigrationStep {
+    /// Where the next batch resumes, None if the migration is done.
+    pub progress: Option<Vec<u8>>,
+    pub num_items: usize,
+}
+
+pub(crate) struct Migrator {
+    migrations: Vec<Box<dyn Migration>>,
+}
+
+impl Migrator {
+    pub fn new(migrations: Vec<Box<dyn Migration>>) -> Self {
+        for (i, migration) in migrations.iter().enumerate() {
+            assert_eq!(
+                migration.schema_version(),
+                BASE_SCHEMA_VERSION + i as u64 + 1,
+                "Migration {} is out of order.",
+                migration.name(),
+            );
+        }
+        Self { migrations }
+    }
+
+    /// The schema version of the node.
+    pub fn schema_version(&self) -> u64 {
+        BASE_SCHEMA_VERSION + self.migrations.len() as u64
+    }
+
+    /// Returns the schema version of `db`, which must not be newer than the one of the node, nor
+    /// older than `BASE_SCHEMA_VERSION`.
+    pub fn check(&self, db: &DB) -> Result<u64> {
+        let version = match db.get::<DbMetadataSchema>(&DbMetadataKey::SchemaVersion)? {
+            Some(DbMetadataValue::SchemaVersion(version)) => version,
+            Some(value) => bail!("Unexpected schema version {:?}.", value),
+            None => BASE_SCHEMA_VERSION,
+        };
+        ensure!(
+            version >= BASE_SCHEMA_VERSION,
+            "The DB is at schema version {}, below the first schema version {}. The DB is \
+             corrupted.",
+            version,
+            BASE_SCHEMA_VERSION,
+        );
+        ensure!(
+            version <= self.schema_version(),
+            "The DB is at schema version {}, newer than the version {} of this node. Upgrade the \
+             node, or restore the DB from a backup taken by this version.",
+            version,
+            self.schema_version(),
+        );
+        SCHEMA_VERSION.set(version as i64);
+        Ok(version)
+    }
+
+    /// Checks that `db`, which is opened read-only, needs no migration.
+    pub fn ensure_migrated(&self, db: &DB) -> Result<()> {
+        let version = self.check(db)?;
+        ensure!(
+            version == self.schema_version(),
+            "The DB is at schema version {} and needs to be migrated to version {}, which happens \
+             when it is opened read-write, e.g. by the node.",
+            version,
+            self.schema_version(),
+        );
+        Ok(())
+    }
+
+    /// Applies to `db` the migrations to the schema version of the node.
+    pub fn migrate(&self, db: &DB) -> Result<()> {
+        let version = self.check(db)?;
+        for migration in &self.migrations[(version - BASE_SCHEMA_VERSION) as usize..] {
+            Self::run(db, migration.as_ref())?;
+        }
+        // Also records the version of new DBs, and of the ones created before versions were.
+        let mut batch = SchemaBatch::new();
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::SchemaVersion,
+            &DbMetadataValue::SchemaVersion(self.schema_version()),
+        )?;
+        db.write_schemas(batch)?;
+        SCHEMA_VERSION.set(self.schema_version() as i64);
+        Ok(())
+    }
+
+    fn run(db: &DB, migration: &dyn Migration) -> Result<()> {
+        let progress_key = DbMetadataKey::MigrationProgress(migration.schema_version());
+        let mut progress = match db.get::<DbMetadataSchema>(&progress_key)? {
+            Some(DbMetadataValue::MigrationProgress(progress)) => Some(progress),
+            Some(value) => bail!("Unexpected migration progress {:?}.", value),
+            None => None,
+        };
+        info!(
+            migration = migration.name(),
+            schema_version = migration.schema_version(),
+            resuming = progress.is_some(),
+            "Migrating the DB schema."
+        );
+        let instant = Instant::now();
+        loop {
+            let mut batch = SchemaBatch::new();
+            let step = migration.migrate_batch(db, progress.as_deref(), &mut batch)?;
+            match &step.progress {
+                Some(progress) => batch.put::<DbMetadataSchema>(
+                    &progress_key,
+                    &DbMetadataValue::MigrationProgress(progress.clone()),
+                )?,
+                None => {
+                    batch.delete::<DbMetadataSchema>(&progress_key)?;
+                    batch.put::<DbMetadataSchema>(
+                        &DbMetadataKey::SchemaVersion,
+                        &DbMetadataValue::SchemaVersion(migration.schema_version()),
+                    )?;
+                }
+            }
+            db.write_schemas(batch)?;
+            MIGRATION_ITEMS
+                .with_label_values(&[migration.name()])
+                .inc_by(step.num_items as u64);
+            progress = match step.progress {
+                Some(progress) => Some(progress),
+                None => break,
+            };
+        }
+        SCHEMA_VERSION.set(migration.schema_version() as i64);
+        info!(
+            migration = migration.name(),
+            time_ms = %instant.elapsed().as_millis(),
+            "Migrated the DB schema."
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
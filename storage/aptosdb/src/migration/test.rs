@@ -0,0 +1,195 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{schema::epoch_by_version::EpochByVersionSchema, AptosDB};
+use aptos_temppath::TempPath;
+use aptos_types::transaction::Version;
+use schemadb::ReadOptions;
+
+/// Adds `shift` to the epoch of every version of `EpochByVersionSchema`, `batch_size` versions at
+/// a time, and fails on the batch `fail_at_batch`, if any.
+struct ShiftEpochs {
+    schema_version: u64,
+    shift: u64,
+    batch_size: usize,
+    fail_at_batch: Option<usize>,
+    num_batches: std::sync::atomic::AtomicUsize,
+}
+
+impl ShiftEpochs {
+    fn new(schema_version: u64, shift: u64) -> Self {
+        Self {
+            schema_version,
+            shift,
+            batch_size: 3,
+            fail_at_batch: None,
+            num_batches: Default::default(),
+        }
+    }
+
+    fn failing_at_batch(mut self, batch: usize) -> Self {
+        self.fail_at_batch = Some(batch);
+        self
+    }
+}
+
+impl Migration for ShiftEpochs {
+    fn schema_version(&self) -> u64 {
+        self.schema_version
+    }
+
+    fn name(&self) -> &'static str {
+        "shift_epochs"
+    }
+
+    fn migrate_batch(
+        &self,
+        db: &DB,
+        progress: Option<&[u8]>,
+        batch: &mut SchemaBatch,
+    ) -> Result<MigrationStep> {
+        let num_batches = self
+            .num_batches
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        ensure!(self.fail_at_batch != Some(num_batches), "Injected failure.");
+
+        let start: Version = progress
+            .map(bcs::from_bytes::<Version>)
+            .transpose()?
+            .unwrap_or(0);
+        let mut iter = db.iter::<EpochByVersionSchema>(ReadOptions::default())?;
+        iter.seek(&start)?;
+        let mut num_items = 0;
+        let mut next = None;
+        for res in iter {
+            let (version, epoch) = res?;
+            if num_items == self.batch_size {
+                next = Some(bcs::to_bytes(&version)?);
+                break;
+            }
+            batch.put::<EpochByVersionSchema>(&version, &(epoch + self.shift))?;
+            num_items += 1;
+        }
+        Ok(MigrationStep {
+            progress: next,
+            num_items,
+        })
+    }
+}
+
+fn epochs(db: &DB) -> Vec<u64> {
+    db.iter::<EpochByVersionSchema>(ReadOptions::default())
+        .unwrap()
+        .map(|res| res.unwrap().1)
+        .collect()
+}
+
+fn put_epochs(db: &DB, num_epochs: u64) {
+    for epoch in 0..num_epochs {
+        db.put::<EpochByVersionSchema>(&(epoch * 10), &epoch)
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_new_db_is_at_current_version() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    assert_eq!(
+        db.db
+            .get::<DbMetadataSchema>(&DbMetadataKey::SchemaVersion)
+            .unwrap(),
+        Some(DbMetadataValue::SchemaVersion(
+            Migrator::new(migrations()).schema_version()
+        )),
+    );
+}
+
+#[test]
+fn test_migrations_resume_after_failure() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir).db;
+    put_epochs(&db, 10);
+
+    let failing = Migrator::new(vec![Box::new(ShiftEpochs::new(2, 100).failing_at_batch(2))]);
+    assert!(failing.migrate(&db).is_err());
+    // the first two batches are in, and so is where the third one resumes
+    assert_eq!(epochs(&db), vec![100, 101, 102, 103, 104, 105, 6, 7, 8, 9]);
+    assert_eq!(failing.check(&db).unwrap(), BASE_SCHEMA_VERSION);
+    assert!(db
+        .get::<DbMetadataSchema>(&DbMetadataKey::MigrationProgress(2))
+        .unwrap()
+        .is_some());
+
+    let migrator = Migrator::new(vec![Box::new(ShiftEpochs::new(2, 100))]);
+    migrator.migrate(&db).unwrap();
+    assert_eq!(epochs(&db), (100..110).collect::<Vec<_>>());
+    assert_eq!(migrator.check(&db).unwrap(), 2);
+    assert!(db
+        .get::<DbMetadataSchema>(&DbMetadataKey::MigrationProgress(2))
+        .unwrap()
+        .is_none());
+
+    // nothing left to migrate
+    migrator.migrate(&db).unwrap();
+    assert_eq!(epochs(&db), (100..110).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_only_pending_migrations_run() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir).db;
+    put_epochs(&db, 5);
+
+    Migrator::new(vec![Box::new(ShiftEpochs::new(2, 100))])
+        .migrate(&db)
+        .unwrap();
+    let migrator = Migrator::new(vec![
+        Box::new(ShiftEpochs::new(2, 100)),
+        Box::new(ShiftEpochs::new(3, 1000)),
+    ]);
+    assert!(migrator.ensure_migrated(&db).is_err());
+    migrator.migrate(&db).unwrap();
+    assert_eq!(epochs(&db), (1100..1105).collect::<Vec<_>>());
+    migrator.ensure_migrated(&db).unwrap();
+}
+
+#[test]
+fn test_newer_schema_is_rejected() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir).db;
+    db.put::<DbMetadataSchema>(
+        &DbMetadataKey::SchemaVersion,
+        &DbMetadataValue::SchemaVersion(BASE_SCHEMA_VERSION + 1),
+    )
+    .unwrap();
+
+    let migrator = Migrator::new(vec![]);
+    assert!(migrator.check(&db).is_err());
+    assert!(migrator.migrate(&db).is_err());
+}
+
+#[test]
+fn test_older_schema_is_rejected() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir).db;
+    db.put::<DbMetadataSchema>(
+        &DbMetadataKey::SchemaVersion,
+        &DbMetadataValue::SchemaVersion(BASE_SCHEMA_VERSION - 1),
+    )
+    .unwrap();
+
+    let migrator = Migrator::new(vec![Box::new(ShiftEpochs::new(2, 100))]);
+    assert!(migrator.check(&db).is_err());
+    assert!(migrator.migrate(&db).is_err());
+}
+
+#[test]
+#[should_panic]
+fn test_migrations_out_of_order() {
+    Migrator::new(vec![
+        Box::new(ShiftEpochs::new(3, 1000)),
+        Box::new(ShiftEpochs::new(2, 100)),
+    ]);
+}
@@ -0,0 +1,28 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+use crate::{pruner::db_sub_pruner::DBSubPruner, system_store::SystemStore};
+use schemadb::SchemaBatch;
+use std::sync::Arc;
+
+pub struct GasStatsPruner {
+    system_store: Arc<SystemStore>,
+}
+
+impl DBSubPruner for GasStatsPruner {
+    fn prune(
+        &self,
+        db_batch: &mut SchemaBatch,
+        least_readable_version: u64,
+        target_version: u64,
+    ) -> anyhow::Result<()> {
+        self.system_store
+            .prune_gas_stats(least_readable_version, target_version, db_batch)?;
+        Ok(())
+    }
+}
+
+impl GasStatsPruner {
+    pub fn new(system_store: Arc<SystemStore>) -> Self {
+        GasStatsPruner { system_store }
+    }
+}
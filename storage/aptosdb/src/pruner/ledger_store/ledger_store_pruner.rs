@@ -6,22 +6,24 @@ use crate::{
         db_pruner::DBPruner,
         db_sub_pruner::DBSubPruner,
         event_store::event_store_pruner::EventStorePruner,
-        ledger_store::ledger_counter_pruner::LedgerCounterPruner,
+        ledger_store::{
+            gas_stats_pruner::GasStatsPruner, ledger_counter_pruner::LedgerCounterPruner,
+        },
         transaction_store::{
             transaction_store_pruner::TransactionStorePruner, write_set_pruner::WriteSetPruner,
         },
     },
-    transaction::TransactionSchema,
+    system_store::SystemStore,
     EventStore, LedgerStore, TransactionStore,
 };
 use aptos_types::transaction::{AtomicVersion, Version};
-use schemadb::{ReadOptions, SchemaBatch, DB};
+use schemadb::{SchemaBatch, DB};
 use std::sync::{atomic::Ordering, Arc};
 
 pub const LEDGER_PRUNER_NAME: &str = "ledger pruner";
 
 pub struct LedgerPruner {
-    db: Arc<DB>,
+    transaction_store: Arc<TransactionStore>,
     /// Keeps track of the target version that the pruner needs to achieve.
     target_version: AtomicVersion,
     least_readable_version: AtomicVersion,
@@ -29,6 +31,7 @@ pub struct LedgerPruner {
     event_store_pruner: Arc<dyn DBSubPruner + Send + Sync>,
     write_set_pruner: Arc<dyn DBSubPruner + Send + Sync>,
     ledger_counter_pruner: Arc<dyn DBSubPruner + Send + Sync>,
+    gas_stats_pruner: Arc<dyn DBSubPruner + Send + Sync>,
 }
 
 impl DBPruner for LedgerPruner {
@@ -57,6 +60,8 @@ impl DBPruner for LedgerPruner {
             least_readable_version,
             current_target_version,
         )?;
+        self.gas_stats_pruner
+            .prune(db_batch, least_readable_version, current_target_version)?;
 
         self.event_store_pruner
             .prune(db_batch, least_readable_version, current_target_version)?;
@@ -66,10 +71,8 @@ impl DBPruner for LedgerPruner {
     }
 
     fn initialize_least_readable_version(&self) -> anyhow::Result<Version> {
-        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
-        iter.seek_to_first();
-        let version = iter.next().transpose()?.map_or(0, |(version, _)| version);
-        Ok(version)
+        // The history moved to the cold store is still readable, so it is looked up there first.
+        Ok(self.transaction_store.get_first_txn_version()?.unwrap_or(0))
     }
 
     fn least_readable_version(&self) -> Version {
@@ -101,7 +104,9 @@ impl LedgerPruner {
         ledger_store: Arc<LedgerStore>,
     ) -> Self {
         let pruner = LedgerPruner {
-            db,
+            gas_stats_pruner: Arc::new(GasStatsPruner::new(Arc::new(SystemStore::new(
+                Arc::clone(&db),
+            )))),
             target_version: AtomicVersion::new(0),
             least_readable_version: AtomicVersion::new(0),
             ledger_counter_pruner: Arc::new(LedgerCounterPruner::new(ledger_store)),
@@ -109,7 +114,8 @@ impl LedgerPruner {
                 transaction_store.clone(),
             )),
             event_store_pruner: Arc::new(EventStorePruner::new(event_store)),
-            write_set_pruner: Arc::new(WriteSetPruner::new(transaction_store)),
+            write_set_pruner: Arc::new(WriteSetPruner::new(Arc::clone(&transaction_store))),
+            transaction_store,
         };
         pruner.initialize();
         pruner
@@ -1,5 +1,6 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod gas_stats_pruner;
 pub(crate) mod ledger_counter_pruner;
 pub(crate) mod ledger_store_pruner;
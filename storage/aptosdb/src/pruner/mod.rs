@@ -130,6 +130,11 @@ impl Pruner {
         self.ledger_prune_window
     }
 
+    #[cfg(feature = "table-iteration")]
+    pub fn get_least_readable_state_version(&self) -> Version {
+        self.least_readable_version.lock()[PrunerIndex::StateStorePrunerIndex as usize]
+    }
+
     pub fn get_least_readable_ledger_version(&self) -> Version {
         self.least_readable_version.lock()[LedgerPrunerIndex as usize]
     }
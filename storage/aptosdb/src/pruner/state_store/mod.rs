@@ -1,15 +1,23 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "table-iteration")]
+use crate::schema::table_item_key::TableItemKeySchema;
 use crate::{
     jellyfish_merkle_node::JellyfishMerkleNodeSchema, metrics::PRUNER_LEAST_READABLE_VERSION,
     pruner::db_pruner::DBPruner, stale_node_index::StaleNodeIndexSchema, OTHER_TIMERS_SECONDS,
 };
 use aptos_infallible::Mutex;
+#[cfg(feature = "table-iteration")]
+use aptos_jellyfish_merkle::node_type::Node;
 use aptos_jellyfish_merkle::StaleNodeIndex;
 use aptos_logger::{error, warn};
+#[cfg(feature = "table-iteration")]
+use aptos_types::state_store::state_key::StateKey;
 use aptos_types::transaction::{AtomicVersion, Version};
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
+#[cfg(feature = "table-iteration")]
+use std::collections::BTreeSet;
 use std::{
     iter::Peekable,
     sync::{atomic::Ordering, Arc},
@@ -183,6 +191,8 @@ pub fn prune_state_store(
             .start_timer();
         let new_least_readable_version = indices.last().expect("Should exist.").stale_since_version;
         let mut batch = SchemaBatch::new();
+        #[cfg(feature = "table-iteration")]
+        prune_table_item_keys(&db, &indices, new_least_readable_version, &mut batch)?;
         indices
             .into_iter()
             .try_for_each(|index| batch.delete::<JellyfishMerkleNodeSchema>(&index.node_key))?;
@@ -191,6 +201,49 @@ pub fn prune_state_store(
     }
 }
 
+/// Removes the rows of the table item key index that no version from `least_readable_version` on
+/// needs, for the table items whose stale leaves in `indices` are pruned: the writes superseded by
+/// a later write up to `least_readable_version`, and that write too if it removed the item.
+///
+/// The rows are only ever added by later writes, so the ones removed here cannot race with the
+/// commits. Reading the stale nodes to find the leaves makes pruning slower with the index on.
+#[cfg(feature = "table-iteration")]
+fn prune_table_item_keys(
+    db: &DB,
+    indices: &[StaleNodeIndex],
+    least_readable_version: Version,
+    batch: &mut SchemaBatch,
+) -> anyhow::Result<()> {
+    let mut table_items = BTreeSet::new();
+    for index in indices {
+        if let Some(Node::Leaf(leaf)) = db.get::<JellyfishMerkleNodeSchema>(&index.node_key)? {
+            if let StateKey::TableItem { handle, key } = &leaf.value().key {
+                table_items.insert((*handle, key.clone()));
+            }
+        }
+    }
+
+    for (handle, key) in table_items {
+        let mut iter = db.iter::<TableItemKeySchema>(ReadOptions::default())?;
+        iter.seek(&(handle, key.clone(), 0))?;
+        let mut rows = vec![];
+        for row in iter {
+            let ((row_handle, row_key, version), removed) = row?;
+            if row_handle != handle || row_key != key || version > least_readable_version {
+                break;
+            }
+            rows.push(((row_handle, row_key, version), removed));
+        }
+        // the latest write up to the least readable version is still needed if it kept the item
+        if let Some((_, false)) = rows.last() {
+            rows.pop();
+        }
+        rows.iter()
+            .try_for_each(|(row, _)| batch.delete::<TableItemKeySchema>(row))?;
+    }
+    Ok(())
+}
+
 struct StaleNodeIndicesByVersionIterator<'a> {
     inner: Peekable<SchemaIterator<'a, StaleNodeIndexSchema>>,
     target_least_readable_version: Version,
@@ -191,3 +191,72 @@ fn test_worker_quit_eagerly() {
         verify_state_in_store(state_store, key, Some(&value2), 2);
     }
 }
+
+#[cfg(feature = "table-iteration")]
+#[test]
+fn test_state_store_pruner_prunes_table_item_keys() {
+    use crate::{
+        pruner::state_store::prune_state_store, schema::table_item_key::TableItemKeySchema,
+    };
+    use schemadb::ReadOptions;
+
+    let removed_key = StateKey::table_item(1, b"removed".to_vec());
+    let updated_key = StateKey::table_item(1, b"updated".to_vec());
+    let value = |v: u8| StateValue::from(vec![v]);
+
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::new_for_test(&tmp_dir);
+    let db = aptos_db.db;
+    let state_store = &StateStore::new(Arc::clone(&db));
+
+    put_value_set(
+        &db,
+        state_store,
+        vec![
+            (removed_key.clone(), value(0)),
+            (updated_key.clone(), value(0)),
+        ],
+        0, /* version */
+    );
+    put_value_set(
+        &db,
+        state_store,
+        vec![
+            (removed_key, StateValue::empty()),
+            (updated_key.clone(), value(1)),
+        ],
+        1, /* version */
+    );
+    put_value_set(
+        &db,
+        state_store,
+        vec![(updated_key, value(2))],
+        2, /* version */
+    );
+
+    let rows = || {
+        let mut iter = db
+            .iter::<TableItemKeySchema>(ReadOptions::default())
+            .unwrap();
+        iter.seek_to_first();
+        iter.map(|row| row.unwrap().0).collect::<Vec<_>>()
+    };
+    assert_eq!(rows().len(), 5);
+
+    // From version 1 on, neither the removed item nor the first write of the updated one is needed.
+    assert_eq!(prune_state_store(Arc::clone(&db), 0, 1, 100).unwrap(), 1);
+    assert_eq!(
+        rows(),
+        vec![(1, b"updated".to_vec(), 1), (1, b"updated".to_vec(), 2)]
+    );
+    assert_eq!(
+        state_store
+            .get_table_items(1, 1, None, 10, 100)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>(),
+        vec![b"updated".to_vec()]
+    );
+}
@@ -96,7 +96,7 @@ fn verify_txn_store_pruner(
     let tmp_dir = TempPath::new();
     let aptos_db = AptosDB::new_for_test(&tmp_dir);
     let transaction_store = &aptos_db.transaction_store;
-    let ledger_store = LedgerStore::new(Arc::clone(&aptos_db.db));
+    let ledger_store = LedgerStore::new(Arc::clone(&aptos_db.db)).unwrap();
     let num_transaction = txns.len();
 
     let pruner = Pruner::new(
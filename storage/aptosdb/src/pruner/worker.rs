@@ -40,14 +40,17 @@ impl Worker {
     ) -> Self {
         let db_pruners =
             utils::create_db_pruners(db.clone(), transaction_store, ledger_store, event_store);
-        Self {
+        let mut worker = Self {
             db: Arc::clone(&db),
             db_pruners,
             command_receiver,
             least_readable_versions,
             blocking_recv: true,
             max_version_to_prune_per_batch,
-        }
+        };
+        // Report the versions the pruners were initialized with until the first pruning.
+        worker.record_progress();
+        worker
     }
 
     pub(crate) fn work(mut self) {
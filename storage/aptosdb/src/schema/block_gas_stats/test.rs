@@ -0,0 +1,23 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::{collection::vec, prelude::*};
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        version in any::<Version>(),
+        timestamp_usecs in any::<u64>(),
+        gas in vec((any::<u64>(), any::<u64>()), 0..10),
+    ) {
+        let mut stats = BlockGasStats::new(timestamp_usecs);
+        for (gas_unit_price, gas_used) in gas {
+            stats.add_transaction(gas_unit_price, gas_used);
+        }
+        assert_encode_decode::<BlockGasStatsSchema>(&version, &stats);
+    }
+}
+
+test_no_panic_decoding!(BlockGasStatsSchema);
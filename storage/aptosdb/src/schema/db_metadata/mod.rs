@@ -0,0 +1,70 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the metadata of the DB itself, e.g. the version
+//! of its schema, see `migration`.
+//!
+//! ```text
+//! |<-------key------->|<-------value------->|
+//! | bcs(DbMetadataKey) | bcs(DbMetadataValue) |
+//! ```
+
+use crate::schema::DB_METADATA_CF_NAME;
+use anyhow::Result;
+#[cfg(test)]
+use proptest_derive::Arbitrary;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub(crate) enum DbMetadataKey {
+    /// The version of the schema of the DB.
+    SchemaVersion,
+    /// Where the migration to the given schema version stopped, while it is in progress.
+    MigrationProgress(u64),
+    /// A state snapshot was restored at the given version.
+    StateSnapshotRestore(u64),
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[cfg_attr(test, derive(Arbitrary))]
+pub(crate) enum DbMetadataValue {
+    SchemaVersion(u64),
+    /// Opaque to everything but the migration.
+    MigrationProgress(Vec<u8>),
+    StateSnapshotRestore,
+}
+
+define_schema!(
+    DbMetadataSchema,
+    DbMetadataKey,
+    DbMetadataValue,
+    DB_METADATA_CF_NAME
+);
+
+impl KeyCodec<DbMetadataSchema> for DbMetadataKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self).map_err(Into::into)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        bcs::from_bytes(data).map_err(Into::into)
+    }
+}
+
+impl ValueCodec<DbMetadataSchema> for DbMetadataValue {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self).map_err(Into::into)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        bcs::from_bytes(data).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test;
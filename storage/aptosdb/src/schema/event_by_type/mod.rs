@@ -0,0 +1,71 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which the events of a type
+//! (represented by <txn_version, event_idx> tuples so that they can be fetched from `EventSchema`)
+//! can be found in the order they were emitted, across all event streams.
+//!
+//! ```text
+//! |<-----------------key----------------->|<-value->|
+//! | struct_tag_hash | txn_ver | event_idx |   ()    |
+//! ```
+//!
+//! Struct tags are variable length, so the key is prefixed with the hash of the BCS serialized
+//! struct tag instead, see `hash_struct_tag`.
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_deps::move_core_types::language_storage::StructTag;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (HashValue, Version, Index);
+
+pub(crate) fn hash_struct_tag(struct_tag: &StructTag) -> Result<HashValue> {
+    Ok(HashValue::sha3_256_of(&bcs::to_bytes(struct_tag)?))
+}
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref struct_tag_hash, version, index) = *self;
+
+        let mut encoded = struct_tag_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, HashValue::LENGTH + size_of::<(Version, Index)>())?;
+
+        let struct_tag_hash = HashValue::from_slice(&data[..HashValue::LENGTH])?;
+        let version = (&data[HashValue::LENGTH..]).read_u64::<BigEndian>()?;
+        let index = (&data[HashValue::LENGTH + size_of::<Version>()..]).read_u64::<BigEndian>()?;
+
+        Ok((struct_tag_hash, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
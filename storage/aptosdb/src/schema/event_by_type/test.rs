@@ -0,0 +1,19 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        struct_tag_hash in any::<HashValue>(),
+        version in any::<Version>(),
+        index in any::<u64>(),
+    ) {
+        assert_encode_decode::<EventByTypeSchema>(&(struct_tag_hash, version, index), &());
+    }
+}
+
+test_no_panic_decoding!(EventByTypeSchema);
@@ -0,0 +1,54 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the gas paid by the user transactions of the
+//! blocks started within each minute.
+//!
+//! ```text
+//! |<-----------key----------->|<--------value-------->|
+//! | start of minute timestamp | minute gas stats bytes |
+//! ```
+//!
+//! The timestamp is serialized in big endian so that records in RocksDB will be in order of it's
+//! numeric value.
+
+use crate::schema::{ensure_slice_len_eq, MINUTE_GAS_STATS_CF_NAME};
+use anyhow::Result;
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+use storage_interface::MinuteGasStats;
+
+define_schema!(
+    MinuteGasStatsSchema,
+    u64,
+    MinuteGasStats,
+    MINUTE_GAS_STATS_CF_NAME
+);
+
+impl KeyCodec<MinuteGasStatsSchema> for u64 {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u64>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<MinuteGasStatsSchema> for MinuteGasStats {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        bcs::to_bytes(self).map_err(Into::into)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        bcs::from_bytes(data).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,99 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an index of the keys of the items of each table,
+//! which the state Merkle tree cannot enumerate since it is keyed by the hashes of the state keys.
+//! Every write of a table item adds a row, the value tells whether the write removed the item.
+//!
+//! ```text
+//! |<-------------key------------->|<--value-->|
+//! | handle | item_key | version |  removed  |
+//! ```
+//!
+//! `handle` and `version` are serialized in big endian and `item_key` is escaped and terminated
+//! (0x00 becomes 0x00 0xff and the key ends with 0x00 0x00) so that the rows are stored in the
+//! order of the handle, then the item key, then the version: the rows of a table are next to each
+//! other and the rows of an item key never interleave with the ones of a longer key. The rows
+//! superseded before the least readable version are removed by the state store pruner.
+
+use crate::schema::{ensure_slice_len_eq, TABLE_ITEM_KEY_CF_NAME};
+use anyhow::{bail, ensure, Result};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+const ESCAPE: u8 = 0x00;
+const ESCAPED_ZERO: u8 = 0xff;
+const TERMINATOR: u8 = 0x00;
+
+type Key = (u128 /* handle */, Vec<u8> /* item key */, Version);
+
+define_schema!(TableItemKeySchema, Key, bool, TABLE_ITEM_KEY_CF_NAME);
+
+impl KeyCodec<TableItemKeySchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded = self.0.to_be_bytes().to_vec();
+        for byte in &self.1 {
+            encoded.push(*byte);
+            if *byte == ESCAPE {
+                encoded.push(ESCAPED_ZERO);
+            }
+        }
+        encoded.extend([ESCAPE, TERMINATOR]);
+        encoded.extend(self.2.to_be_bytes());
+        Ok(encoded)
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() >= size_of::<u128>() + 2 + size_of::<Version>(),
+            "Unexpected data len {}, expected at least {}.",
+            data.len(),
+            size_of::<u128>() + 2 + size_of::<Version>(),
+        );
+        let handle = data.read_u128::<BigEndian>()?;
+        let mut key = vec![];
+        loop {
+            match data {
+                [ESCAPE, TERMINATOR, rest @ ..] => {
+                    data = rest;
+                    break;
+                }
+                [ESCAPE, ESCAPED_ZERO, rest @ ..] => {
+                    key.push(ESCAPE);
+                    data = rest;
+                }
+                [ESCAPE, ..] | [] => bail!("Unterminated table item key."),
+                [byte, rest @ ..] => {
+                    key.push(*byte);
+                    data = rest;
+                }
+            }
+        }
+        ensure_slice_len_eq(data, size_of::<Version>())?;
+        let version = data.read_u64::<BigEndian>()?;
+        Ok((handle, key, version))
+    }
+}
+
+impl ValueCodec<TableItemKeySchema> for bool {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(vec![*self as u8])
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 1)?;
+        match data[0] {
+            0 => Ok(false),
+            1 => Ok(true),
+            byte => bail!("Unexpected removed flag {}.", byte),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test;
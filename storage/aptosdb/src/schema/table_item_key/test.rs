@@ -0,0 +1,30 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::{collection::vec, prelude::*};
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        handle in any::<u128>(),
+        item_key in vec(any::<u8>(), 0..100),
+        version in any::<Version>(),
+        removed in any::<bool>(),
+    ) {
+        assert_encode_decode::<TableItemKeySchema>(&(handle, item_key, version), &removed);
+    }
+
+    #[test]
+    fn test_encoding_preserves_order(
+        key1 in (0u128..2, vec(prop_oneof![Just(0u8), Just(1), Just(0xff)], 0..4), 0u64..3),
+        key2 in (0u128..2, vec(prop_oneof![Just(0u8), Just(1), Just(0xff)], 0..4), 0u64..3),
+    ) {
+        let encoded1 = <Key as KeyCodec<TableItemKeySchema>>::encode_key(&key1).unwrap();
+        let encoded2 = <Key as KeyCodec<TableItemKeySchema>>::encode_key(&key2).unwrap();
+        prop_assert_eq!(key1.cmp(&key2), encoded1.cmp(&encoded2));
+    }
+}
+
+test_no_panic_decoding!(TableItemKeySchema);
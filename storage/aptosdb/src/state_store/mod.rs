@@ -6,11 +6,15 @@
 #[cfg(test)]
 mod state_store_test;
 
+#[cfg(feature = "table-iteration")]
+use crate::schema::table_item_key::TableItemKeySchema;
 use crate::{
     change_set::ChangeSet,
     ledger_counters::LedgerCounter,
     schema::{
-        jellyfish_merkle_node::JellyfishMerkleNodeSchema, stale_node_index::StaleNodeIndexSchema,
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        jellyfish_merkle_node::JellyfishMerkleNodeSchema,
+        stale_node_index::StaleNodeIndexSchema,
     },
     state_value_index::StateValueIndexSchema,
     AptosDbError,
@@ -33,9 +37,11 @@ use aptos_types::{
     transaction::{Version, PRE_GENESIS_VERSION},
 };
 use itertools::process_results;
-use schemadb::{SchemaBatch, DB};
+use schemadb::{SchemaBatch, SchemaIterator, DB};
 use std::{cmp::Ordering, collections::HashMap, sync::Arc};
 use storage_interface::StateSnapshotReceiver;
+#[cfg(feature = "table-iteration")]
+use storage_interface::TableItems;
 
 type LeafNode = aptos_jellyfish_merkle::node_type::LeafNode<StateKeyAndValue>;
 type Node = aptos_jellyfish_merkle::node_type::Node<StateKeyAndValue>;
@@ -121,11 +127,10 @@ impl StateStore {
     }
 
     fn get_node_keys_by_key_prefix(
-        &self,
+        iter: &mut SchemaIterator<StateValueIndexSchema>,
         key_prefix: &StateKeyPrefix,
         desired_version: Version,
     ) -> Result<HashMap<StateKey, NodeKey>> {
-        let mut iter = self.db.iter::<StateValueIndexSchema>(Default::default())?;
         let mut result = HashMap::new();
         iter.seek(&(key_prefix))?;
         while let Some(((state_key, first_version), num_nibbles)) = iter.next().transpose()? {
@@ -189,9 +194,35 @@ impl StateStore {
         &self,
         key_prefix: &StateKeyPrefix,
         version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        let mut iter = self.db.iter::<StateValueIndexSchema>(Default::default())?;
+        self.get_values_by_key_prefix_with_iter(&mut iter, key_prefix, version)
+    }
+
+    /// Batched version of `get_values_by_key_prefix`, returning the key, value pairs of each
+    /// prefix in order. All prefixes are read with the same DB iterator.
+    pub fn get_values_by_key_prefixes(
+        &self,
+        key_prefixes: &[StateKeyPrefix],
+        version: Version,
+    ) -> Result<Vec<HashMap<StateKey, StateValue>>> {
+        let mut iter = self.db.iter::<StateValueIndexSchema>(Default::default())?;
+        key_prefixes
+            .iter()
+            .map(|key_prefix| {
+                self.get_values_by_key_prefix_with_iter(&mut iter, key_prefix, version)
+            })
+            .collect()
+    }
+
+    fn get_values_by_key_prefix_with_iter(
+        &self,
+        iter: &mut SchemaIterator<StateValueIndexSchema>,
+        key_prefix: &StateKeyPrefix,
+        version: Version,
     ) -> Result<HashMap<StateKey, StateValue>> {
         let mut result = HashMap::new();
-        for (state_key, node_key) in self.get_node_keys_by_key_prefix(key_prefix, version)? {
+        for (state_key, node_key) in Self::get_node_keys_by_key_prefix(iter, key_prefix, version)? {
             let state_value = self
                 .get_value_by_node_key(&node_key)?
                 .ok_or_else(|| anyhow!("Failure reading value for node_key {:?}", node_key))?;
@@ -200,6 +231,67 @@ impl StateStore {
         Ok(result)
     }
 
+    /// Returns the version `state_key` was first written at, if that is at or before `version`.
+    /// The state value index keeps an entry for every version a key was written at, so its first
+    /// entry for the key is the one of the first write.
+    pub fn get_first_write_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        let mut iter = self.db.iter::<StateValueIndexSchema>(Default::default())?;
+        iter.seek(&(state_key.clone(), 0))?;
+        Ok(iter
+            .next()
+            .transpose()?
+            .and_then(|((db_state_key, db_version), _num_nibbles)| {
+                (db_state_key == *state_key && db_version <= version).then(|| db_version)
+            }))
+    }
+
+    /// Returns the version `state_key` was created at, if that is at or before `version`. Unlike
+    /// [`Self::get_first_write_version`], this is `None` when the first write is the one of a
+    /// restored state snapshot, since the key was created at some version the DB doesn't know.
+    pub fn get_creation_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        match self.get_first_write_version(state_key, version)? {
+            Some(first_version)
+                if self
+                    .db
+                    .get::<DbMetadataSchema>(&DbMetadataKey::StateSnapshotRestore(first_version))?
+                    .is_none() =>
+            {
+                Ok(Some(first_version))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns up to `limit` versions the state key was written at, from `start_version` up to
+    /// `version`, in ascending order.
+    pub fn get_write_versions(
+        &self,
+        state_key: &StateKey,
+        start_version: Version,
+        limit: usize,
+        version: Version,
+    ) -> Result<Vec<Version>> {
+        let mut iter = self.db.iter::<StateValueIndexSchema>(Default::default())?;
+        iter.seek(&(state_key.clone(), start_version))?;
+        let mut versions = vec![];
+        for item in iter.take(limit) {
+            let ((db_state_key, db_version), _num_nibbles) = item?;
+            if db_state_key != *state_key || db_version > version {
+                break;
+            }
+            versions.push(db_version);
+        }
+        Ok(versions)
+    }
+
     /// Get the state value given the state key and root hash of state Merkle tree by using the
     /// state value index. Only used for testing for now but should replace the
     /// `get_value_with_proof_by_version` call for VM execution to fetch the value without proof.
@@ -253,6 +345,66 @@ impl StateStore {
             }))
     }
 
+    /// Returns up to `limit` items of the table `handle` at `version` in the order of their keys,
+    /// starting after the key `cursor` if any. The keys are read from the table item key index,
+    /// which only covers the items written by a build with the `table-iteration` feature and not
+    /// the ones restored from a state snapshot, and only the versions that are not pruned. At
+    /// most `max_rows` rows of the index are scanned, counting the ones of the items removed by
+    /// or added after `version`, the page then ends with the cursor of the next one.
+    #[cfg(feature = "table-iteration")]
+    pub fn get_table_items(
+        &self,
+        handle: u128,
+        version: Version,
+        cursor: Option<&[u8]>,
+        limit: usize,
+        max_rows: usize,
+    ) -> Result<TableItems> {
+        let mut iter = self.db.iter::<TableItemKeySchema>(Default::default())?;
+        match cursor {
+            // past all the rows of the cursor
+            Some(cursor) => iter.seek(&(handle, cursor.to_vec(), Version::MAX))?,
+            None => iter.seek(&(handle, vec![], 0))?,
+        }
+        let mut items = vec![];
+        let mut last_key = cursor.map(<[u8]>::to_vec);
+        let mut num_rows = 0;
+        loop {
+            if items.len() >= limit || num_rows >= max_rows {
+                return Ok(TableItems {
+                    items,
+                    next_cursor: last_key,
+                });
+            }
+            let (item_handle, key, _version) = match iter.next().transpose()? {
+                Some((row, _removed)) => row,
+                None => break,
+            };
+            if item_handle != handle {
+                break;
+            }
+            num_rows += 1;
+            // The index has a row per write of the item.
+            if last_key.as_ref() == Some(&key) {
+                continue;
+            }
+            last_key = Some(key.clone());
+            // The index also has the keys of the items removed or added after `version`.
+            let state_key = StateKey::table_item(handle, key.clone());
+            if let Some(state_key_value) =
+                JellyfishMerkleTree::new(self).get(state_key.hash(), version)?
+            {
+                if state_key_value.value.maybe_bytes.is_some() {
+                    items.push((key, state_key_value.value));
+                }
+            }
+        }
+        Ok(TableItems {
+            items,
+            next_cursor: None,
+        })
+    }
+
     /// Gets the proof that proves a range of accounts.
     pub fn get_value_range_proof(
         &self,
@@ -271,6 +423,9 @@ impl StateStore {
         first_version: Version,
         cs: &mut ChangeSet,
     ) -> Result<Vec<HashValue>> {
+        #[cfg(feature = "table-iteration")]
+        Self::put_table_item_keys(&value_state_sets, first_version, cs)?;
+
         let value_sets = value_state_sets
             .into_iter()
             .map(|value_set| {
@@ -324,6 +479,26 @@ impl StateStore {
         Ok(new_root_hash_vec)
     }
 
+    /// Adds the writes of the table items of `value_state_sets`, starting at `first_version`, to
+    /// the table item key index.
+    #[cfg(feature = "table-iteration")]
+    fn put_table_item_keys(
+        value_state_sets: &[&HashMap<StateKey, StateValue>],
+        first_version: Version,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        (first_version..)
+            .zip(value_state_sets)
+            .flat_map(|(version, value_set)| value_set.iter().map(move |kv| (version, kv)))
+            .try_for_each(|(version, (state_key, value))| match state_key {
+                StateKey::TableItem { handle, key } => cs.batch.put::<TableItemKeySchema>(
+                    &(*handle, key.clone(), version),
+                    &value.maybe_bytes.is_none(),
+                ),
+                _ => Ok(()),
+            })
+    }
+
     pub fn get_root_hash(&self, version: Version) -> Result<HashValue> {
         JellyfishMerkleTree::new(self).get_root_hash(version)
     }
@@ -399,6 +574,12 @@ impl StateStore {
         version: Version,
         expected_root_hash: HashValue,
     ) -> Result<Box<dyn StateSnapshotReceiver<StateKeyAndValue>>> {
+        // The value index of the restored keys starts at `version`, remember it wasn't the
+        // version they were written at.
+        self.db.put::<DbMetadataSchema>(
+            &DbMetadataKey::StateSnapshotRestore(version),
+            &DbMetadataValue::StateSnapshotRestore,
+        )?;
         Ok(Box::new(JellyfishMerkleRestore::new_overwrite(
             Arc::clone(self),
             version,
@@ -229,6 +229,205 @@ fn test_get_values_by_key_prefix() {
     assert_eq!(*key_value_map.get(&key5).unwrap(), value5_v2);
 }
 
+#[test]
+fn test_get_first_write_version() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::Raw(String::from("test_key2").into_bytes());
+    let value = StateValue::from(String::from("test_val").into_bytes());
+
+    put_value_set(store, vec![(key1.clone(), value.clone())], 0);
+    put_value_set(
+        store,
+        vec![(key1.clone(), value.clone()), (key2.clone(), value)],
+        1,
+    );
+
+    assert_eq!(store.get_first_write_version(&key1, 1).unwrap(), Some(0));
+    assert_eq!(store.get_first_write_version(&key2, 1).unwrap(), Some(1));
+    // Not written yet at version 0.
+    assert_eq!(store.get_first_write_version(&key2, 0).unwrap(), None);
+    let key3 = StateKey::Raw(String::from("test_key3").into_bytes());
+    assert_eq!(store.get_first_write_version(&key3, 1).unwrap(), None);
+}
+
+#[test]
+fn test_get_creation_version() {
+    let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::Raw(String::from("test_key2").into_bytes());
+    let value = StateValue::from(String::from("test_val").into_bytes());
+
+    let tmp_dir1 = TempPath::new();
+    let db1 = AptosDB::new_for_test(&tmp_dir1);
+    let store1 = &db1.state_store;
+    put_value_set(store1, vec![(key1.clone(), value.clone())], 0);
+    let root_hash = put_value_set(store1, vec![(key1.clone(), value.clone())], 1);
+
+    // Restore the state at version 1, where key1 already exists.
+    let tmp_dir2 = TempPath::new();
+    let db2 = AptosDB::new_for_test(&tmp_dir2);
+    let store2 = &db2.state_store;
+    let mut restore = store2.get_snapshot_receiver(1, root_hash).unwrap();
+    let chunk = store1.get_value_chunk_with_proof(1, 0, 1).unwrap();
+    restore.add_chunk(chunk.raw_values, chunk.proof).unwrap();
+    restore.finish_box().unwrap();
+    put_value_set(store2, vec![(key2.clone(), value)], 2);
+
+    assert_eq!(store1.get_creation_version(&key1, 1).unwrap(), Some(0));
+    // The first write known after the restore is the snapshot, not the creation.
+    assert_eq!(store2.get_first_write_version(&key1, 2).unwrap(), Some(1));
+    assert_eq!(store2.get_creation_version(&key1, 2).unwrap(), None);
+    assert_eq!(store2.get_creation_version(&key2, 2).unwrap(), Some(2));
+    assert_eq!(store2.get_creation_version(&key2, 1).unwrap(), None);
+}
+
+#[test]
+fn test_get_write_versions() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
+    let key2 = StateKey::Raw(String::from("test_key2").into_bytes());
+    let value = StateValue::from(String::from("test_val").into_bytes());
+
+    put_value_set(store, vec![(key1.clone(), value.clone())], 0);
+    put_value_set(store, vec![(key2.clone(), value.clone())], 1);
+    put_value_set(store, vec![(key1.clone(), value.clone())], 2);
+    put_value_set(store, vec![(key1.clone(), value)], 3);
+
+    assert_eq!(
+        store.get_write_versions(&key1, 0, 10, 3).unwrap(),
+        vec![0, 2, 3]
+    );
+    assert_eq!(store.get_write_versions(&key1, 1, 1, 3).unwrap(), vec![2]);
+    // Writes after the requested version are left out.
+    assert_eq!(
+        store.get_write_versions(&key1, 0, 10, 2).unwrap(),
+        vec![0, 2]
+    );
+    assert_eq!(store.get_write_versions(&key2, 2, 10, 3).unwrap(), vec![]);
+}
+
+#[cfg(feature = "table-iteration")]
+#[test]
+fn test_get_table_items() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+
+    let item = |handle: u128, key: &[u8]| StateKey::table_item(handle, key.to_vec());
+    let value = |v: &str| StateValue::from(v.as_bytes().to_vec());
+
+    // Keys of different lengths must not interleave.
+    put_value_set(
+        store,
+        vec![
+            (item(1, b"b"), value("b_v0")),
+            (item(1, b"a"), value("a_v0")),
+            (item(1, b"a\0"), value("a0_v0")),
+            (item(2, b"a"), value("other")),
+        ],
+        0,
+    );
+    put_value_set(
+        store,
+        vec![
+            (item(1, b"c"), value("c_v1")),
+            (item(1, b"a"), StateValue::empty()),
+        ],
+        1,
+    );
+
+    let keys = |page: TableItems| {
+        page.items
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        store.get_table_items(1, 0, None, 10, 100).unwrap(),
+        TableItems {
+            items: vec![
+                (b"a".to_vec(), value("a_v0")),
+                (b"a\0".to_vec(), value("a0_v0")),
+                (b"b".to_vec(), value("b_v0")),
+            ],
+            next_cursor: None,
+        }
+    );
+    // Removed items are skipped.
+    assert_eq!(
+        keys(store.get_table_items(1, 1, None, 10, 100).unwrap()),
+        vec![b"a\0".to_vec(), b"b".to_vec(), b"c".to_vec()]
+    );
+    // Paging.
+    let page = store.get_table_items(1, 1, None, 2, 100).unwrap();
+    assert_eq!(page.next_cursor, Some(b"b".to_vec()));
+    assert_eq!(keys(page), vec![b"a\0".to_vec(), b"b".to_vec()]);
+    let page = store
+        .get_table_items(1, 1, Some(b"b".as_ref()), 2, 100)
+        .unwrap();
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(keys(page), vec![b"c".to_vec()]);
+    assert_eq!(
+        store.get_table_items(3, 1, None, 10, 100).unwrap(),
+        TableItems::default()
+    );
+
+    // The rows of the removed item and the superseded row of "a" count towards the scan limit.
+    let page = store.get_table_items(1, 1, None, 10, 2).unwrap();
+    assert_eq!(page.next_cursor, Some(b"a".to_vec()));
+    assert!(page.items.is_empty());
+    let page = store
+        .get_table_items(1, 1, page.next_cursor.as_deref(), 10, 2)
+        .unwrap();
+    assert_eq!(page.next_cursor, Some(b"b".to_vec()));
+    assert_eq!(keys(page), vec![b"a\0".to_vec(), b"b".to_vec()]);
+    let page = store
+        .get_table_items(1, 1, Some(b"b".as_ref()), 10, 2)
+        .unwrap();
+    assert_eq!(page.next_cursor, None);
+    assert_eq!(keys(page), vec![b"c".to_vec()]);
+}
+
+#[test]
+fn test_get_values_by_key_prefixes() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.state_store;
+    let address1 = AccountAddress::new([12u8; AccountAddress::LENGTH]);
+    let address2 = AccountAddress::new([22u8; AccountAddress::LENGTH]);
+    let address3 = AccountAddress::new([32u8; AccountAddress::LENGTH]);
+
+    let key1 = StateKey::AccessPath(AccessPath::new(address1, b"state_key1".to_vec()));
+    let key2 = StateKey::AccessPath(AccessPath::new(address2, b"state_key2".to_vec()));
+    let value1 = StateValue::from(String::from("value1").into_bytes());
+    let value2 = StateValue::from(String::from("value2").into_bytes());
+    put_value_set(
+        store,
+        vec![
+            (key1.clone(), value1.clone()),
+            (key2.clone(), value2.clone()),
+        ],
+        0,
+    );
+
+    // The prefixes are not sorted and one of them has no values.
+    let key_prefixes: Vec<_> = [address2, address3, address1]
+        .iter()
+        .map(|address| StateKeyPrefix::new(StateKeyTag::AccessPath, address.to_vec()))
+        .collect();
+    let key_value_maps = store.get_values_by_key_prefixes(&key_prefixes, 0).unwrap();
+    assert_eq!(key_value_maps.len(), 3);
+    assert_eq!(key_value_maps[0].len(), 1);
+    assert_eq!(*key_value_maps[0].get(&key2).unwrap(), value2);
+    assert!(key_value_maps[1].is_empty());
+    assert_eq!(key_value_maps[2].len(), 1);
+    assert_eq!(*key_value_maps[2].get(&key1).unwrap(), value1);
+}
+
 #[test]
 fn test_retired_records() {
     let key1 = StateKey::Raw(String::from("test_key1").into_bytes());
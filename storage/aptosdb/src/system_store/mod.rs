@@ -5,14 +5,22 @@
 //! structures but information with regard to system running status, statistics, etc.
 
 use crate::{
-    change_set::ChangeSet, ledger_counters::LedgerCounters,
-    schema::ledger_counters::LedgerCountersSchema,
+    change_set::ChangeSet,
+    ledger_counters::LedgerCounters,
+    schema::{
+        block_gas_stats::BlockGasStatsSchema, ledger_counters::LedgerCountersSchema,
+        minute_gas_stats::MinuteGasStatsSchema,
+    },
 };
 use anyhow::Result;
 use aptos_logger::prelude::*;
-use aptos_types::transaction::Version;
-use schemadb::DB;
-use std::sync::Arc;
+use aptos_types::transaction::{Transaction, TransactionToCommit, Version};
+use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    sync::Arc,
+};
+use storage_interface::{BlockGasStats, MinuteGasStats};
 
 #[derive(Debug)]
 pub(crate) struct SystemStore {
@@ -61,6 +69,160 @@ impl SystemStore {
 
         Ok(counters)
     }
+
+    /// Accumulates the gas paid by user transactions into the stats of the block they belong to,
+    /// keyed by the version of the block metadata transaction, and into the stats of the minute
+    /// the block started in.
+    ///
+    /// A block can be committed across several batches (e.g. during state sync), so the stats
+    /// of the block in progress at `first_version` and of the minutes touched are read out of db
+    /// and continued.
+    pub fn put_block_gas_stats(
+        &self,
+        first_version: Version,
+        txns_to_commit: &[TransactionToCommit],
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        let mut block = if first_version > 0 {
+            self.get_block_gas_stats_iter(first_version - 1)?
+                .next()
+                .transpose()?
+        } else {
+            None
+        };
+        let mut minutes = BTreeMap::new();
+
+        for (version, txn_to_commit) in (first_version..).zip(txns_to_commit) {
+            match txn_to_commit.transaction() {
+                Transaction::BlockMetadata(block_metadata) => {
+                    if let Some((block_version, stats)) = block.take() {
+                        cs.batch
+                            .put::<BlockGasStatsSchema>(&block_version, &stats)?;
+                    }
+                    let timestamp_usecs = block_metadata.timestamp_usecs();
+                    self.minute_gas_stats(&mut minutes, timestamp_usecs)?
+                        .add_block();
+                    block = Some((version, BlockGasStats::new(timestamp_usecs)));
+                }
+                Transaction::UserTransaction(txn) => {
+                    // Transactions not committed as part of a block (e.g. in tests) are ignored.
+                    if let Some((_, stats)) = block.as_mut() {
+                        let gas_used = txn_to_commit.transaction_info().gas_used();
+                        stats.add_transaction(txn.gas_unit_price(), gas_used);
+                        self.minute_gas_stats(&mut minutes, stats.timestamp_usecs)?
+                            .add_transaction(txn.gas_unit_price(), gas_used);
+                    }
+                }
+                Transaction::GenesisTransaction(_) | Transaction::StateCheckpoint => (),
+            }
+        }
+        if let Some((block_version, stats)) = block {
+            cs.batch
+                .put::<BlockGasStatsSchema>(&block_version, &stats)?;
+        }
+        for (minute, stats) in minutes {
+            cs.batch.put::<MinuteGasStatsSchema>(&minute, &stats)?;
+        }
+        Ok(())
+    }
+
+    /// The stats of the minute `timestamp_usecs` is in, read out of db on first use.
+    fn minute_gas_stats<'a>(
+        &self,
+        minutes: &'a mut BTreeMap<u64, MinuteGasStats>,
+        timestamp_usecs: u64,
+    ) -> Result<&'a mut MinuteGasStats> {
+        let minute = MinuteGasStats::minute_of(timestamp_usecs);
+        Ok(match minutes.entry(minute) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(
+                self.db
+                    .get::<MinuteGasStatsSchema>(&minute)?
+                    .unwrap_or_else(|| MinuteGasStats::new(minute)),
+            ),
+        })
+    }
+
+    /// Returns the gas stats of up to `limit` minutes up to the one `timestamp_usecs` is in,
+    /// latest first. Minutes without blocks are skipped.
+    pub fn get_minute_gas_stats(
+        &self,
+        timestamp_usecs: u64,
+        limit: usize,
+    ) -> Result<Vec<MinuteGasStats>> {
+        let mut iter = self
+            .db
+            .rev_iter::<MinuteGasStatsSchema>(ReadOptions::default())?;
+        iter.seek_for_prev(&MinuteGasStats::minute_of(timestamp_usecs))?;
+        iter.take(limit)
+            .map(|res| res.map(|(_minute, stats)| stats))
+            .collect()
+    }
+
+    /// Deletes the gas stats of the blocks started in `[begin, end)`, and of the minutes which
+    /// only had such blocks.
+    pub fn prune_gas_stats(
+        &self,
+        begin: Version,
+        end: Version,
+        db_batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        let mut iter = self
+            .db
+            .iter::<BlockGasStatsSchema>(ReadOptions::default())?;
+        iter.seek(&begin)?;
+        let mut last_pruned_timestamp_usecs = None;
+        for res in iter {
+            let (version, stats) = res?;
+            if version >= end {
+                break;
+            }
+            db_batch.delete::<BlockGasStatsSchema>(&version)?;
+            last_pruned_timestamp_usecs = Some(stats.timestamp_usecs);
+        }
+
+        // Blocks are committed in timestamp order, so the minutes before the one of the last
+        // pruned block have no block left.
+        if let Some(timestamp_usecs) = last_pruned_timestamp_usecs {
+            let until = MinuteGasStats::minute_of(timestamp_usecs);
+            let mut iter = self
+                .db
+                .iter::<MinuteGasStatsSchema>(ReadOptions::default())?;
+            iter.seek_to_first();
+            for res in iter {
+                let (minute, _stats) = res?;
+                if minute >= until {
+                    break;
+                }
+                db_batch.delete::<MinuteGasStatsSchema>(&minute)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the gas stats of up to `limit` blocks started at or before `ledger_version`,
+    /// latest first.
+    pub fn get_block_gas_stats(
+        &self,
+        ledger_version: Version,
+        limit: usize,
+    ) -> Result<Vec<BlockGasStats>> {
+        self.get_block_gas_stats_iter(ledger_version)?
+            .take(limit)
+            .map(|res| res.map(|(_version, stats)| stats))
+            .collect()
+    }
+
+    fn get_block_gas_stats_iter(
+        &self,
+        ledger_version: Version,
+    ) -> Result<SchemaIterator<BlockGasStatsSchema>> {
+        let mut iter = self
+            .db
+            .rev_iter::<BlockGasStatsSchema>(ReadOptions::default())?;
+        iter.seek_for_prev(&ledger_version)?;
+        Ok(iter)
+    }
 }
 
 #[cfg(test)]
@@ -7,7 +7,15 @@ use crate::{
     ledger_counters::{LedgerCounter, LedgerCounterBumps},
     AptosDB,
 };
+use aptos_crypto::{ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform};
 use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress,
+    block_metadata::BlockMetadata,
+    test_helpers::transaction_test_helpers::get_test_signed_transaction,
+    transaction::{ExecutionStatus, TransactionInfo},
+    write_set::WriteSet,
+};
 use std::collections::HashMap;
 
 fn bump_ledger_counters(
@@ -102,3 +110,203 @@ fn test_inc_ledger_counters() {
         assert_eq!(counters.get(LedgerCounter::EventsCreated), 15);
     }
 }
+
+fn block_metadata_to_commit(timestamp_usecs: u64) -> TransactionToCommit {
+    let block_metadata = BlockMetadata::new(
+        HashValue::zero(),
+        0,
+        0,
+        vec![],
+        AccountAddress::ZERO,
+        timestamp_usecs,
+    );
+    txn_to_commit(Transaction::BlockMetadata(block_metadata), 0)
+}
+
+fn user_txn_to_commit(gas_unit_price: u64, gas_used: u64) -> TransactionToCommit {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let txn = get_test_signed_transaction(
+        AccountAddress::random(),
+        0,
+        &private_key,
+        private_key.public_key(),
+        None,
+        0,
+        gas_unit_price,
+        None,
+    );
+    txn_to_commit(Transaction::UserTransaction(txn), gas_used)
+}
+
+fn txn_to_commit(txn: Transaction, gas_used: u64) -> TransactionToCommit {
+    TransactionToCommit::new(
+        txn,
+        TransactionInfo::new_placeholder(gas_used, ExecutionStatus::Success),
+        HashMap::new(),
+        None,
+        WriteSet::default(),
+        vec![],
+    )
+}
+
+fn put_block_gas_stats(
+    store: &SystemStore,
+    first_version: Version,
+    txns_to_commit: &[TransactionToCommit],
+) {
+    let mut cs = ChangeSet::new();
+    store
+        .put_block_gas_stats(first_version, txns_to_commit, &mut cs)
+        .unwrap();
+    store.db.write_schemas(cs.batch).unwrap();
+}
+
+#[test]
+fn test_block_gas_stats() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.system_store;
+
+    put_block_gas_stats(
+        store,
+        1,
+        &[
+            block_metadata_to_commit(1_000),
+            user_txn_to_commit(1, 10),
+            user_txn_to_commit(5, 20),
+        ],
+    );
+    // The first block continues in the second batch.
+    put_block_gas_stats(
+        store,
+        4,
+        &[
+            user_txn_to_commit(3, 1),
+            block_metadata_to_commit(2_000),
+            user_txn_to_commit(2, 4),
+        ],
+    );
+
+    let first_block = BlockGasStats {
+        timestamp_usecs: 1_000,
+        gas_used: 31,
+        fees_burned: 113,
+        gas_unit_prices: vec![1, 5, 3],
+    };
+    let second_block = BlockGasStats {
+        timestamp_usecs: 2_000,
+        gas_used: 4,
+        fees_burned: 8,
+        gas_unit_prices: vec![2],
+    };
+    assert_eq!(
+        store.get_block_gas_stats(6, 10).unwrap(),
+        vec![second_block.clone(), first_block.clone()]
+    );
+    assert_eq!(store.get_block_gas_stats(6, 1).unwrap(), vec![second_block]);
+    assert_eq!(store.get_block_gas_stats(4, 10).unwrap(), vec![first_block]);
+    assert_eq!(store.get_block_gas_stats(0, 10).unwrap(), vec![]);
+}
+
+fn put_minute_test_blocks(store: &SystemStore) {
+    put_block_gas_stats(
+        store,
+        1,
+        &[
+            block_metadata_to_commit(1_000),
+            user_txn_to_commit(1, 10),
+            user_txn_to_commit(5, 20),
+        ],
+    );
+    put_block_gas_stats(
+        store,
+        4,
+        &[
+            user_txn_to_commit(3, 1),
+            block_metadata_to_commit(2_000),
+            user_txn_to_commit(2, 4),
+            block_metadata_to_commit(61_000_000),
+            user_txn_to_commit(7, 2),
+        ],
+    );
+}
+
+#[test]
+fn test_minute_gas_stats() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.system_store;
+    put_minute_test_blocks(store);
+
+    let first_minute = MinuteGasStats {
+        timestamp_usecs: 0,
+        num_blocks: 2,
+        num_user_transactions: 4,
+        gas_used: 35,
+        fees_burned: 121,
+        min_gas_unit_price: 1,
+        max_gas_unit_price: 5,
+    };
+    let second_minute = MinuteGasStats {
+        timestamp_usecs: 60_000_000,
+        num_blocks: 1,
+        num_user_transactions: 1,
+        gas_used: 2,
+        fees_burned: 14,
+        min_gas_unit_price: 7,
+        max_gas_unit_price: 7,
+    };
+    assert_eq!(
+        store.get_minute_gas_stats(61_000_000, 10).unwrap(),
+        vec![second_minute.clone(), first_minute.clone()]
+    );
+    assert_eq!(
+        store.get_minute_gas_stats(u64::MAX, 1).unwrap(),
+        vec![second_minute]
+    );
+    assert_eq!(
+        store.get_minute_gas_stats(59_999_999, 10).unwrap(),
+        vec![first_minute]
+    );
+}
+
+#[test]
+fn test_prune_gas_stats() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let store = &db.system_store;
+    put_minute_test_blocks(store);
+
+    let prune = |begin, end| {
+        let mut batch = SchemaBatch::new();
+        store.prune_gas_stats(begin, end, &mut batch).unwrap();
+        store.db.write_schemas(batch).unwrap();
+    };
+    let block_timestamps = || -> Vec<u64> {
+        store
+            .get_block_gas_stats(u64::MAX, 10)
+            .unwrap()
+            .into_iter()
+            .map(|stats| stats.timestamp_usecs)
+            .collect()
+    };
+    let minutes = || -> Vec<u64> {
+        store
+            .get_minute_gas_stats(u64::MAX, 10)
+            .unwrap()
+            .into_iter()
+            .map(|stats| stats.timestamp_usecs)
+            .collect()
+    };
+
+    // Blocks are started at versions 1, 5 and 7.
+    prune(0, 7);
+    assert_eq!(block_timestamps(), vec![61_000_000]);
+    // A minute is only pruned along with a block of a later minute, as the pruner can't tell
+    // whether the minute had more blocks.
+    assert_eq!(minutes(), vec![60_000_000, 0]);
+
+    prune(7, 9);
+    assert_eq!(block_timestamps(), Vec::<u64>::new());
+    assert_eq!(minutes(), vec![60_000_000]);
+}
@@ -5,6 +5,7 @@
 
 use crate::{
     change_set::ChangeSet,
+    cold_store::{ColdStore, TieredDb, TieredIter},
     errors::AptosDbError,
     schema::{
         transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema,
@@ -19,20 +20,26 @@ use aptos_types::{
     account_address::AccountAddress,
     block_metadata::BlockMetadata,
     proof::position::Position,
+    state_store::state_key::StateKey,
     transaction::{Transaction, Version},
-    write_set::WriteSet,
+    write_set::{WriteOp, WriteSet},
 };
 use schemadb::{ReadOptions, SchemaBatch, SchemaIterator, DB};
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 #[derive(Debug)]
 pub struct TransactionStore {
     db: Arc<DB>,
+    // Transactions and write sets below the horizon of the cold store are read from it.
+    history: TieredDb,
 }
 
 impl TransactionStore {
-    pub fn new(db: Arc<DB>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<DB>, cold_store: Option<Arc<ColdStore>>) -> Self {
+        Self {
+            history: TieredDb::new(Arc::clone(&db), cold_store),
+            db,
+        }
     }
 
     /// Gets the version of a transaction by the sender `address` and `sequence_number`.
@@ -97,7 +104,7 @@ impl TransactionStore {
 
     /// Get signed transaction given `version`
     pub fn get_transaction(&self, version: Version) -> Result<Transaction> {
-        self.db
+        self.history
             .get::<TransactionSchema>(&version)?
             .ok_or_else(|| AptosDbError::NotFound(format!("Txn {}", version)).into())
     }
@@ -108,10 +115,8 @@ impl TransactionStore {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<TransactionIter> {
-        let mut iter = self.db.iter::<TransactionSchema>(ReadOptions::default())?;
-        iter.seek(&start_version)?;
         Ok(TransactionIter {
-            inner: iter,
+            inner: self.history.iter::<TransactionSchema>(start_version)?,
             expected_next_version: start_version,
             end_version: start_version
                 .checked_add(num_transactions as u64)
@@ -121,9 +126,7 @@ impl TransactionStore {
 
     /// Get the first version that txn starts existent.
     pub fn get_first_txn_version(&self) -> Result<Option<Version>> {
-        let mut iter = self.db.iter::<TransactionSchema>(Default::default())?;
-        iter.seek_to_first();
-        iter.next().map(|res| res.map(|(v, _)| v)).transpose()
+        Ok(self.history.first::<TransactionSchema>()?.map(|(v, _)| v))
     }
 
     /// Returns the block metadata carried on the block metadata transaction at or preceding
@@ -137,8 +140,7 @@ impl TransactionStore {
         // Linear search via `DB::rev_iter()` here, NOT expecting performance hit, due to the fact
         // that the iterator caches data block and that there are limited number of transactions in
         // each block.
-        let mut iter = self.db.rev_iter::<TransactionSchema>(Default::default())?;
-        iter.seek(&version)?;
+        let iter = self.history.rev_iter::<TransactionSchema>(version)?;
         for res in iter.take(MAX_VERSIONS_TO_SEARCH) {
             let (v, txn) = res?;
             if let Transaction::BlockMetadata(block_meta) = txn {
@@ -173,9 +175,11 @@ impl TransactionStore {
 
     /// Get executed transaction vm output given `version`
     pub fn get_write_set(&self, version: Version) -> Result<WriteSet> {
-        self.db.get::<WriteSetSchema>(&version)?.ok_or_else(|| {
-            AptosDbError::NotFound(format!("WriteSet at version {}", version)).into()
-        })
+        self.history
+            .get::<WriteSetSchema>(&version)?
+            .ok_or_else(|| {
+                AptosDbError::NotFound(format!("WriteSet at version {}", version)).into()
+            })
     }
 
     /// Get write sets in `[begin_version, end_version)` half-open range.
@@ -196,8 +200,7 @@ impl TransactionStore {
             end_version
         );
 
-        let mut iter = self.db.iter::<WriteSetSchema>(Default::default())?;
-        iter.seek(&begin_version)?;
+        let mut iter = self.history.iter::<WriteSetSchema>(begin_version)?;
 
         let mut ret = Vec::with_capacity((end_version - begin_version) as usize);
         for current_version in begin_version..end_version {
@@ -217,11 +220,50 @@ impl TransactionStore {
         Ok(ret)
     }
 
+    /// Get the net state changes made by the transactions in `(first_version, last_version]`,
+    /// i.e. the last write op applied to every key touched in the range, sorted by key.
+    ///
+    /// Write sets are consumed one at a time off the DB iterator, so memory is bounded by the
+    /// number of distinct keys touched rather than by the number of write sets in the range.
+    pub fn get_state_diff(
+        &self,
+        first_version: Version,
+        last_version: Version,
+    ) -> Result<Vec<(StateKey, WriteOp)>> {
+        ensure!(
+            first_version <= last_version,
+            "first_version {} > last_version {}",
+            first_version,
+            last_version
+        );
+        if first_version == last_version {
+            return Ok(Vec::new());
+        }
+
+        let mut iter = self.history.iter::<WriteSetSchema>(first_version + 1)?;
+
+        let mut changes = BTreeMap::new();
+        for current_version in first_version + 1..=last_version {
+            let (version, write_set) = iter
+                .next()
+                .transpose()?
+                .ok_or_else(|| format_err!("Write set missing for version {}", current_version))?;
+            ensure!(
+                version == current_version,
+                "Write set missing for version {}, got version {}",
+                current_version,
+                version,
+            );
+            // Later writes to the same key replace earlier ones.
+            changes.extend(write_set.into_iter());
+        }
+
+        Ok(changes.into_iter().collect())
+    }
+
     /// Get the first version that write set starts existent.
     pub fn get_first_write_set_version(&self) -> Result<Option<Version>> {
-        let mut iter = self.db.iter::<WriteSetSchema>(Default::default())?;
-        iter.seek_to_first();
-        iter.next().map(|res| res.map(|(v, _)| v)).transpose()
+        Ok(self.history.first::<WriteSetSchema>()?.map(|(v, _)| v))
     }
 
     /// Save executed transaction vm output given `version`
@@ -330,7 +372,7 @@ impl TransactionStore {
 }
 
 pub struct TransactionIter<'a> {
-    inner: SchemaIterator<'a, TransactionSchema>,
+    inner: TieredIter<'a, TransactionSchema>,
     expected_next_version: Version,
     end_version: Version,
 }
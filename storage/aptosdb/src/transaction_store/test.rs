@@ -38,6 +38,16 @@ proptest! {
         store.db.write_schemas(cs.batch).unwrap();
         assert_eq!(store.get_write_sets(0, write_sets.len() as Version).unwrap(), write_sets);
 
+        // net changes made after version 0, later writes to a key replace earlier ones
+        let mut expected_diff = BTreeMap::new();
+        for ws in write_sets.iter().skip(1) {
+            expected_diff.extend(ws.clone().into_iter());
+        }
+        assert_eq!(
+            store.get_state_diff(0, write_sets.len() as Version - 1).unwrap(),
+            expected_diff.into_iter().collect::<Vec<_>>()
+        );
+
         assert_eq!(store.get_first_txn_version().unwrap(), Some(0));
         assert_eq!(store.get_first_write_set_version().unwrap(), Some(0));
 
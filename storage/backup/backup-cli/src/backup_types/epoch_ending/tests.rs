@@ -30,7 +30,7 @@ use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     sync::Arc,
 };
-use storage_interface::DbReader;
+use storage_interface::LedgerReader;
 use tokio::{runtime::Runtime, time::Duration};
 use warp::Filter;
 
@@ -17,7 +17,7 @@ use aptos_temppath::TempPath;
 use aptos_types::transaction::PRE_GENESIS_VERSION;
 use aptosdb::AptosDB;
 use std::{convert::TryInto, sync::Arc};
-use storage_interface::DbReader;
+use storage_interface::StateReader;
 use tokio::time::Duration;
 
 #[test]
@@ -25,7 +25,7 @@ use aptosdb::AptosDB;
 use executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
 use proptest::prelude::*;
 use std::{convert::TryInto, sync::Arc};
-use storage_interface::DbReader;
+use storage_interface::LedgerReader;
 use tokio::time::Duration;
 
 #[derive(Debug)]
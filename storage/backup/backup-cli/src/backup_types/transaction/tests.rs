@@ -17,7 +17,7 @@ use aptos_temppath::TempPath;
 use aptos_types::transaction::Version;
 use aptosdb::AptosDB;
 use std::{convert::TryInto, mem::size_of, sync::Arc};
-use storage_interface::DbReader;
+use storage_interface::LedgerReader;
 use tokio::time::Duration;
 
 #[test]
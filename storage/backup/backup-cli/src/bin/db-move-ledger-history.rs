@@ -0,0 +1,50 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
+use aptos_logger::{prelude::*, Level, Logger};
+use aptos_types::transaction::Version;
+use aptosdb::AptosDB;
+use backup_cli::utils::RocksdbOpt;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(StructOpt)]
+#[structopt(
+    name = "db-move-ledger-history",
+    about = "Move the transactions, write sets and events older than a version from the DB to its \
+             cold store. The node using the DB must be stopped."
+)]
+struct Opt {
+    #[structopt(long = "target-db-dir", parse(from_os_str))]
+    db_dir: PathBuf,
+    #[structopt(
+        long,
+        parse(from_os_str),
+        help = "The cold storage dir configured for the node."
+    )]
+    cold_storage_dir: PathBuf,
+    #[structopt(long, help = "Versions below this one are moved to the cold store.")]
+    horizon: Version,
+    #[structopt(long, default_value = "10000")]
+    batch_size: usize,
+    #[structopt(flatten)]
+    rocksdb_opt: RocksdbOpt,
+}
+
+fn main() -> Result<()> {
+    Logger::new().level(Level::Info).read_env().init();
+
+    let opt = Opt::from_args();
+    let db = AptosDB::open_with_cold_store(
+        opt.db_dir,
+        Some(opt.cold_storage_dir.as_path()),
+        false,                       /* read_only */
+        NO_OP_STORAGE_PRUNER_CONFIG, /* pruner config */
+        opt.rocksdb_opt.into(),
+    )?;
+    let horizon = db.move_ledger_history_to_cold_store(opt.horizon, opt.batch_size)?;
+    info!(horizon = horizon, "Ledger history moved to the cold store.");
+    Ok(())
+}
@@ -30,7 +30,7 @@ pub struct CommandAdapterOpt {
         long = "config",
         help = "Config file for the command adapter backup store."
     )]
-    config: PathBuf,
+    pub config: PathBuf,
 }
 
 /// A BackupStorage that delegates required APIs to configured command lines.
@@ -16,8 +16,8 @@ use aptos_types::{
 use serde::de::DeserializeOwned;
 use std::net::SocketAddr;
 use storage_interface::{
-    DbReader, DbWriter, Error, GetStateValueWithProofByVersionRequest, SaveTransactionsRequest,
-    StartupInfo, StorageRequest,
+    DbWriter, Error, EventReader, GetStateValueWithProofByVersionRequest, LedgerReader,
+    SaveTransactionsRequest, StartupInfo, StateReader, StorageRequest,
 };
 
 pub struct StorageClient {
@@ -81,7 +81,13 @@ impl StorageClient {
     }
 }
 
-impl DbReader for StorageClient {
+impl LedgerReader for StorageClient {
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        Ok(Self::get_startup_info(self)?)
+    }
+}
+
+impl StateReader for StorageClient {
     fn get_state_value_with_proof_by_version(
         &self,
         state_key: &StateKey,
@@ -91,12 +97,10 @@ impl DbReader for StorageClient {
             self, state_key, version,
         )?)
     }
-
-    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
-        Ok(Self::get_startup_info(self)?)
-    }
 }
 
+impl EventReader for StorageClient {}
+
 impl DbWriter for StorageClient {
     fn save_transactions(
         &self,
@@ -0,0 +1,60 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{errors::AptosDbError, DbReader, EventReader, LedgerReader, StateReader};
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::task::spawn_blocking;
+
+/// Runs reads of a [`DbReader`] on tokio's blocking thread pool, so that async callers don't stall
+/// the worker thread they are polled on while storage hits the disk.
+///
+/// Each method hands a focused view of the storage to `f`, e.g.
+/// `db.events(move |db| db.get_events(&key, 0, Order::Ascending, 10)).await`, and returns its
+/// error as an [`AptosDbError`], so that callers can tell the category of the failure.
+#[derive(Clone)]
+pub struct AsyncDbReader {
+    db: Arc<dyn DbReader>,
+}
+
+impl AsyncDbReader {
+    pub fn new(db: Arc<dyn DbReader>) -> Self {
+        Self { db }
+    }
+
+    /// Reads the ledger history with `f`.
+    pub async fn ledger<T, F>(&self, f: F) -> Result<T, AptosDbError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn LedgerReader) -> Result<T> + Send + 'static,
+    {
+        let db = self.db.clone();
+        Ok(spawn_blocking(move || f(db.as_ledger_reader()))
+            .await
+            .map_err(anyhow::Error::from)??)
+    }
+
+    /// Reads the state with `f`.
+    pub async fn state<T, F>(&self, f: F) -> Result<T, AptosDbError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn StateReader) -> Result<T> + Send + 'static,
+    {
+        let db = self.db.clone();
+        Ok(spawn_blocking(move || f(db.as_state_reader()))
+            .await
+            .map_err(anyhow::Error::from)??)
+    }
+
+    /// Reads the events with `f`.
+    pub async fn events<T, F>(&self, f: F) -> Result<T, AptosDbError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&dyn EventReader) -> Result<T> + Send + 'static,
+    {
+        let db = self.db.clone();
+        Ok(spawn_blocking(move || f(db.as_event_reader()))
+            .await
+            .map_err(anyhow::Error::from)??)
+    }
+}
@@ -0,0 +1,51 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the error types of the storage APIs. The APIs return `anyhow::Error`, which
+//! converts into an [`AptosDbError`] telling the category of the failure. The async reads of
+//! [`AsyncDbReader`](crate::async_reader::AsyncDbReader) return the [`AptosDbError`] directly.
+
+use thiserror::Error;
+
+/// This enum defines errors commonly used among the storage APIs.
+#[derive(Debug, Error)]
+pub enum AptosDbError {
+    /// A requested item is not found.
+    #[error("{0} not found.")]
+    NotFound(String),
+    /// Requested too many items.
+    #[error("Too many items requested: at least {0} requested, max is {1}")]
+    TooManyRequested(u64, u64),
+    /// An argument of the request is invalid, e.g. an empty or inverted range.
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    /// Any other failure, e.g. an I/O error of the underlying DB.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for AptosDbError {
+    /// Recovers the category of the error, which is `Other` unless it is, or was given context
+    /// on top of, an `AptosDbError`.
+    fn from(error: anyhow::Error) -> Self {
+        error.downcast().unwrap_or_else(Self::Other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AptosDbError;
+    use anyhow::format_err;
+
+    #[test]
+    fn test_from_anyhow_error() {
+        let error = AptosDbError::from(
+            anyhow::Error::from(AptosDbError::TooManyRequested(11, 10)).context("reading"),
+        );
+        assert!(matches!(error, AptosDbError::TooManyRequested(11, 10)));
+
+        let error = AptosDbError::from(format_err!("I/O error"));
+        assert!(matches!(error, AptosDbError::Other(_)));
+        assert_eq!(error.to_string(), "I/O error");
+    }
+}
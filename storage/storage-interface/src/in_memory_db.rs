@@ -0,0 +1,1206 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides an in-memory `DbReader` and `DbWriter` for tests.
+
+use crate::{
+    errors::AptosDbError, BlockGasStats, DbWriter, EventReader, LedgerReader, MinuteGasStats,
+    Order, StartupInfo, StateReader, TreeState,
+};
+use accumulator::{HashReader, MerkleAccumulator};
+use anyhow::{ensure, format_err, Result};
+use aptos_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher},
+    HashValue,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithProof},
+    epoch_change::EpochChangeProof,
+    event::EventKey,
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{
+        position::Position, AccumulatorConsistencyProof, EventAccumulatorProof, EventProof,
+        SparseMerkleProof, StateStoreValueProof, TransactionInfoListWithProof,
+        TransactionInfoWithProof,
+    },
+    state_proof::StateProof,
+    state_store::{
+        state_key::StateKey,
+        state_key_prefix::StateKeyPrefix,
+        state_value::{StateValue, StateValueWithProof},
+    },
+    transaction::{
+        AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
+        TransactionOutput, TransactionOutputListWithProof, TransactionStatus, TransactionToCommit,
+        TransactionWithProof, Version,
+    },
+    write_set::{WriteOp, WriteSet},
+};
+use move_deps::move_core_types::language_storage::{StructTag, TypeTag};
+use parking_lot::RwLock;
+use scratchpad::test_utils::naive_smt::NaiveSmt;
+use std::collections::{BTreeMap, HashMap};
+
+type TransactionAccumulator = MerkleAccumulator<HashStore, TransactionAccumulatorHasher>;
+type EventAccumulator = MerkleAccumulator<HashStore, EventAccumulatorHasher>;
+
+/// A `DbReader` and `DbWriter` keeping everything in memory, for testing components that sit on
+/// top of storage without spinning up AptosDB.
+///
+/// Every version of every state value is kept. Proofs are real: transaction infos are proven by
+/// the transaction accumulator, events by the event accumulator of their transaction and state
+/// values by the sparse Merkle tree of the state at the requested version. As the trees are
+/// rebuilt on every query, it is only meant for small ledgers.
+#[derive(Default)]
+pub struct InMemoryDb {
+    inner: RwLock<Inner>,
+}
+
+impl InMemoryDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Default)]
+struct HashStore(HashMap<Position, HashValue>);
+
+impl HashReader for HashStore {
+    fn get(&self, position: Position) -> Result<HashValue> {
+        self.0
+            .get(&position)
+            .cloned()
+            .ok_or_else(|| format_err!("Position {:?} absent.", position))
+    }
+}
+
+struct CommittedTransaction {
+    transaction: Transaction,
+    info: TransactionInfo,
+    write_set: WriteSet,
+    events: Vec<ContractEvent>,
+}
+
+#[derive(Default)]
+struct Inner {
+    transactions: Vec<CommittedTransaction>,
+    /// Frozen nodes of the transaction accumulator.
+    accumulator: HashStore,
+    /// Every value written to a state key, by the version it was written at.
+    state: BTreeMap<StateKey, BTreeMap<Version, StateValue>>,
+    latest_ledger_info: Option<LedgerInfoWithSignatures>,
+    epoch_ending_ledger_infos: BTreeMap<u64, LedgerInfoWithSignatures>,
+    versions_by_hash: HashMap<HashValue, Version>,
+    /// (version, index in the transaction) of every event, by key and sequence number.
+    events: HashMap<EventKey, BTreeMap<u64, (Version, u64)>>,
+    /// Versions of user transactions, by sender and sequence number.
+    account_transactions: HashMap<AccountAddress, BTreeMap<u64, Version>>,
+}
+
+impl Inner {
+    fn latest_version(&self) -> Option<Version> {
+        (self.transactions.len() as u64).checked_sub(1)
+    }
+
+    fn ensure_ledger_version(&self, ledger_version: Version) -> Result<()> {
+        ensure!(
+            self.latest_version()
+                .map_or(false, |latest| ledger_version <= latest),
+            "Ledger version {} is newer than the latest version {:?}.",
+            ledger_version,
+            self.latest_version(),
+        );
+        Ok(())
+    }
+
+    fn transaction(&self, version: Version) -> Result<&CommittedTransaction> {
+        self.transactions
+            .get(version as usize)
+            .ok_or_else(|| AptosDbError::NotFound(format!("Txn {}", version)).into())
+    }
+
+    fn transaction_info_with_proof(
+        &self,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<TransactionInfoWithProof> {
+        ensure!(
+            version <= ledger_version,
+            "Version {} is newer than ledger version {}.",
+            version,
+            ledger_version,
+        );
+        self.ensure_ledger_version(ledger_version)?;
+        Ok(TransactionInfoWithProof::new(
+            TransactionAccumulator::get_proof(&self.accumulator, ledger_version + 1, version)?,
+            self.transaction(version)?.info.clone(),
+        ))
+    }
+
+    fn transaction_with_proof(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        let txn = self.transaction(version)?;
+        Ok(TransactionWithProof::new(
+            version,
+            txn.transaction.clone(),
+            fetch_events.then(|| txn.events.clone()),
+            self.transaction_info_with_proof(version, ledger_version)?,
+        ))
+    }
+
+    /// Returns the transactions in `[start_version, start_version + limit)` that are not newer
+    /// than `ledger_version`.
+    fn transaction_range(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<&[CommittedTransaction]> {
+        self.ensure_ledger_version(ledger_version)?;
+        if start_version > ledger_version {
+            return Ok(&[]);
+        }
+        let end_version = (ledger_version + 1).min(start_version.saturating_add(limit));
+        Ok(&self.transactions[start_version as usize..end_version as usize])
+    }
+
+    fn transaction_infos_with_proof(
+        &self,
+        start_version: Version,
+        txns: &[CommittedTransaction],
+        ledger_version: Version,
+    ) -> Result<TransactionInfoListWithProof> {
+        Ok(TransactionInfoListWithProof::new(
+            TransactionAccumulator::get_range_proof(
+                &self.accumulator,
+                ledger_version + 1,
+                Some(start_version),
+                txns.len() as u64,
+            )?,
+            txns.iter().map(|txn| txn.info.clone()).collect(),
+        ))
+    }
+
+    fn event_with_proof(
+        &self,
+        version: Version,
+        index: u64,
+        ledger_version: Version,
+    ) -> Result<EventWithProof> {
+        let events = &self.transaction(version)?.events;
+        let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+        let mut store = HashStore::default();
+        let (_, frozen_nodes) = EventAccumulator::append(&store, 0, &event_hashes)?;
+        store.0.extend(frozen_nodes);
+        let event_proof: EventAccumulatorProof =
+            EventAccumulator::get_proof(&store, event_hashes.len() as u64, index)?;
+
+        Ok(EventWithProof::new(
+            version,
+            index,
+            events[index as usize].clone(),
+            EventProof::new(
+                self.transaction_info_with_proof(version, ledger_version)?,
+                event_proof,
+            ),
+        ))
+    }
+
+    fn events_with_proofs(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        let indices = match self.events.get(event_key) {
+            Some(indices) => indices,
+            None => return Ok(vec![]),
+        };
+        let visible = indices
+            .iter()
+            .filter(|(_, (version, _))| *version <= ledger_version);
+        let selected: Vec<_> = match order {
+            Order::Ascending => visible
+                .filter(|(seq_num, _)| **seq_num >= start)
+                .take(limit as usize)
+                .collect(),
+            Order::Descending => {
+                let latest = visible.clone().next_back().map(|(seq_num, _)| *seq_num);
+                // `u64::MAX` asks for the latest events, a cursor beyond the latest sequence
+                // number is out of bound.
+                let cursor = if start == u64::MAX {
+                    latest
+                } else {
+                    Some(start)
+                };
+                match (cursor, latest) {
+                    (Some(cursor), Some(latest)) if cursor <= latest => visible
+                        .rev()
+                        .filter(|(seq_num, _)| **seq_num <= cursor)
+                        .take(limit as usize)
+                        .collect(),
+                    _ => vec![],
+                }
+            }
+        };
+        selected
+            .into_iter()
+            .map(|(_, (version, index))| self.event_with_proof(*version, *index, ledger_version))
+            .collect()
+    }
+
+    fn state_value(&self, state_key: &StateKey, version: Version) -> Option<StateValue> {
+        self.state
+            .get(state_key)?
+            .range(..=version)
+            .next_back()
+            .map(|(_, value)| value.clone())
+    }
+
+    fn state_values(&self, version: Version) -> impl Iterator<Item = (&StateKey, &StateValue)> {
+        self.state.iter().filter_map(move |(key, values)| {
+            values
+                .range(..=version)
+                .next_back()
+                .map(|(_, value)| (key, value))
+        })
+    }
+
+    /// Builds the sparse Merkle tree of the state at `version`.
+    fn state_tree(&self, version: Version) -> NaiveSmt {
+        let leaves: Vec<_> = self
+            .state_values(version)
+            .map(|(key, value)| (key.hash(), value))
+            .collect();
+        NaiveSmt::new(&leaves)
+    }
+
+    fn state_value_with_proof(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> (Option<StateValue>, SparseMerkleProof<StateValue>) {
+        (
+            self.state_value(state_key, version),
+            self.state_tree(version).get_proof(&state_key.hash()),
+        )
+    }
+
+    fn tree_state(&self, version: Option<Version>) -> Result<TreeState> {
+        let num_transactions = version.map_or(0, |v| v + 1);
+        let frozen_subtree_hashes =
+            TransactionAccumulator::get_frozen_subtree_hashes(&self.accumulator, num_transactions)?;
+        Ok(match version {
+            Some(version) => TreeState::new_at_state_checkpoint(
+                num_transactions,
+                frozen_subtree_hashes,
+                self.state_tree(version).get_root_hash(),
+            ),
+            None => TreeState::new_empty(),
+        })
+    }
+
+    /// Returns the epoch `version` belongs to.
+    fn epoch(&self, version: Version) -> u64 {
+        match self
+            .epoch_ending_ledger_infos
+            .values()
+            .find(|li| li.ledger_info().version() >= version)
+        {
+            Some(li) => li.ledger_info().epoch(),
+            // Past the last epoch change, or before any.
+            None => self
+                .epoch_ending_ledger_infos
+                .values()
+                .last()
+                .map_or(0, |li| li.ledger_info().next_block_epoch()),
+        }
+    }
+
+    fn epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures>> {
+        if start_epoch > end_epoch {
+            return Err(AptosDbError::InvalidArgument(format!(
+                "Bad epoch range [{}, {})",
+                start_epoch, end_epoch,
+            ))
+            .into());
+        }
+        (start_epoch..end_epoch)
+            .map(|epoch| {
+                self.epoch_ending_ledger_infos
+                    .get(&epoch)
+                    .cloned()
+                    .ok_or_else(|| format_err!("No ledger info ending epoch {}.", epoch))
+            })
+            .collect()
+    }
+
+    fn latest_ledger_info(&self) -> Result<&LedgerInfoWithSignatures> {
+        self.latest_ledger_info
+            .as_ref()
+            .ok_or_else(|| format_err!("Latest LedgerInfo not found."))
+    }
+
+    fn save_ledger_info(&mut self, ledger_info_with_sigs: &LedgerInfoWithSignatures) {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        if ledger_info.ends_epoch() {
+            self.epoch_ending_ledger_infos
+                .insert(ledger_info.epoch(), ledger_info_with_sigs.clone());
+        }
+        self.latest_ledger_info = Some(ledger_info_with_sigs.clone());
+    }
+}
+
+impl LedgerReader for InMemoryDb {
+    fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: u64,
+        end_epoch: u64,
+    ) -> Result<EpochChangeProof> {
+        let ledger_infos = self
+            .inner
+            .read()
+            .epoch_ending_ledger_infos(start_epoch, end_epoch)?;
+        Ok(EpochChangeProof::new(ledger_infos, /* more = */ false))
+    }
+
+    fn get_transactions(
+        &self,
+        start_version: Version,
+        batch_size: u64,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionListWithProof> {
+        let inner = self.inner.read();
+        let txns = inner.transaction_range(start_version, batch_size, ledger_version)?;
+        if txns.is_empty() {
+            return Ok(TransactionListWithProof::new_empty());
+        }
+        Ok(TransactionListWithProof::new(
+            txns.iter().map(|txn| txn.transaction.clone()).collect(),
+            fetch_events.then(|| txns.iter().map(|txn| txn.events.clone()).collect()),
+            Some(start_version),
+            inner.transaction_infos_with_proof(start_version, txns, ledger_version)?,
+        ))
+    }
+
+    fn get_transaction_by_hash(
+        &self,
+        hash: HashValue,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<Option<TransactionWithProof>> {
+        let inner = self.inner.read();
+        inner
+            .versions_by_hash
+            .get(&hash)
+            .filter(|version| **version <= ledger_version)
+            .map(|version| inner.transaction_with_proof(*version, ledger_version, fetch_events))
+            .transpose()
+    }
+
+    fn get_transaction_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+        fetch_events: bool,
+    ) -> Result<TransactionWithProof> {
+        self.inner
+            .read()
+            .transaction_with_proof(version, ledger_version, fetch_events)
+    }
+
+    fn get_first_txn_version(&self) -> Result<Option<Version>> {
+        Ok(self.inner.read().latest_version().map(|_| 0))
+    }
+
+    fn get_first_write_set_version(&self) -> Result<Option<Version>> {
+        Ok(self.inner.read().latest_version().map(|_| 0))
+    }
+
+    fn get_transaction_outputs(
+        &self,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<TransactionOutputListWithProof> {
+        let inner = self.inner.read();
+        let txns = inner.transaction_range(start_version, limit, ledger_version)?;
+        if txns.is_empty() {
+            return Ok(TransactionOutputListWithProof::new_empty());
+        }
+        let txns_and_outputs = txns
+            .iter()
+            .map(|txn| {
+                (
+                    txn.transaction.clone(),
+                    TransactionOutput::new(
+                        txn.write_set.clone(),
+                        txn.events.clone(),
+                        txn.info.gas_used(),
+                        TransactionStatus::Keep(txn.info.status().clone()),
+                    ),
+                )
+            })
+            .collect();
+        Ok(TransactionOutputListWithProof::new(
+            txns_and_outputs,
+            Some(start_version),
+            inner.transaction_infos_with_proof(start_version, txns, ledger_version)?,
+        ))
+    }
+
+    fn get_write_sets(
+        &self,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<Vec<WriteSet>> {
+        let inner = self.inner.read();
+        ensure!(
+            start_version <= end_version && end_version <= inner.transactions.len() as u64,
+            "Bad version range [{}, {})",
+            start_version,
+            end_version,
+        );
+        Ok(
+            inner.transactions[start_version as usize..end_version as usize]
+                .iter()
+                .map(|txn| txn.write_set.clone())
+                .collect(),
+        )
+    }
+
+    fn get_block_gas_stats(
+        &self,
+        ledger_version: Version,
+        limit: u64,
+    ) -> Result<Vec<BlockGasStats>> {
+        let inner = self.inner.read();
+        let mut blocks: Vec<(Version, BlockGasStats)> = vec![];
+        for (version, txn) in inner.transactions.iter().enumerate() {
+            match &txn.transaction {
+                Transaction::BlockMetadata(block_metadata) => blocks.push((
+                    version as u64,
+                    BlockGasStats::new(block_metadata.timestamp_usecs()),
+                )),
+                Transaction::UserTransaction(user_txn) => {
+                    // Transactions not committed as part of a block are ignored.
+                    if let Some((_, stats)) = blocks.last_mut() {
+                        stats.add_transaction(user_txn.gas_unit_price(), txn.info.gas_used());
+                    }
+                }
+                Transaction::GenesisTransaction(_) | Transaction::StateCheckpoint => (),
+            }
+        }
+        Ok(blocks
+            .into_iter()
+            .rev()
+            .filter(|(version, _)| *version <= ledger_version)
+            .take(limit as usize)
+            .map(|(_, stats)| stats)
+            .collect())
+    }
+
+    fn get_minute_gas_stats(
+        &self,
+        timestamp_usecs: u64,
+        limit: u64,
+    ) -> Result<Vec<MinuteGasStats>> {
+        let inner = self.inner.read();
+        let mut minutes: Vec<MinuteGasStats> = vec![];
+        for txn in &inner.transactions {
+            match &txn.transaction {
+                Transaction::BlockMetadata(block_metadata) => {
+                    let minute = MinuteGasStats::minute_of(block_metadata.timestamp_usecs());
+                    if minutes.last().map(|stats| stats.timestamp_usecs) != Some(minute) {
+                        minutes.push(MinuteGasStats::new(minute));
+                    }
+                    minutes.last_mut().expect("pushed above").add_block();
+                }
+                Transaction::UserTransaction(user_txn) => {
+                    // Transactions not committed as part of a block are ignored.
+                    if let Some(stats) = minutes.last_mut() {
+                        stats.add_transaction(user_txn.gas_unit_price(), txn.info.gas_used());
+                    }
+                }
+                Transaction::GenesisTransaction(_) | Transaction::StateCheckpoint => (),
+            }
+        }
+        let until = MinuteGasStats::minute_of(timestamp_usecs);
+        Ok(minutes
+            .into_iter()
+            .rev()
+            .filter(|stats| stats.timestamp_usecs <= until)
+            .take(limit as usize)
+            .collect())
+    }
+
+    fn get_block_timestamp(&self, version: u64) -> Result<u64> {
+        let inner = self.inner.read();
+        inner.transaction(version)?;
+        Ok(inner.transactions[..=version as usize]
+            .iter()
+            .rev()
+            .find_map(|txn| match &txn.transaction {
+                Transaction::BlockMetadata(block_metadata) => {
+                    Some(block_metadata.timestamp_usecs())
+                }
+                _ => None,
+            })
+            // genesis timestamp is 0
+            .unwrap_or(0))
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        Ok(self.inner.read().latest_ledger_info.clone())
+    }
+
+    fn get_startup_info(&self) -> Result<Option<StartupInfo>> {
+        let inner = self.inner.read();
+        let latest_ledger_info = match &inner.latest_ledger_info {
+            Some(li) => li.clone(),
+            None => return Ok(None),
+        };
+        let ledger_info = latest_ledger_info.ledger_info();
+        let latest_epoch_state_if_not_in_li = match ledger_info.next_epoch_state() {
+            Some(_) => None,
+            // The ledger info ending the previous epoch carries the current validator set.
+            None => Some(
+                ledger_info
+                    .epoch()
+                    .checked_sub(1)
+                    .and_then(|epoch| inner.epoch_ending_ledger_infos.get(&epoch))
+                    .and_then(|li| li.ledger_info().next_epoch_state().cloned())
+                    .ok_or_else(|| {
+                        format_err!("No epoch state for epoch {}.", ledger_info.epoch())
+                    })?,
+            ),
+        };
+
+        let li_version = ledger_info.version();
+        let latest_version = inner
+            .latest_version()
+            .ok_or_else(|| format_err!("No transaction committed."))?;
+        let committed_tree_state = inner.tree_state(Some(li_version))?;
+        let synced_tree_state = if latest_version > li_version {
+            Some(inner.tree_state(Some(latest_version))?)
+        } else {
+            None
+        };
+
+        Ok(Some(StartupInfo::new(
+            latest_ledger_info,
+            latest_epoch_state_if_not_in_li,
+            committed_tree_state,
+            synced_tree_state,
+        )))
+    }
+
+    fn get_account_transaction(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Option<TransactionWithProof>> {
+        let inner = self.inner.read();
+        inner
+            .account_transactions
+            .get(&address)
+            .and_then(|versions| versions.get(&seq_num))
+            .filter(|version| **version <= ledger_version)
+            .map(|version| inner.transaction_with_proof(*version, ledger_version, include_events))
+            .transpose()
+    }
+
+    fn get_account_transactions(
+        &self,
+        address: AccountAddress,
+        seq_num: u64,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<AccountTransactionsWithProof> {
+        let inner = self.inner.read();
+        let versions = match inner.account_transactions.get(&address) {
+            Some(versions) => versions,
+            None => return Ok(AccountTransactionsWithProof::new_empty()),
+        };
+        let txns = versions
+            .range(seq_num..)
+            .map(|(_, version)| *version)
+            .take_while(|version| *version <= ledger_version)
+            .take(limit as usize)
+            .map(|version| inner.transaction_with_proof(version, ledger_version, include_events))
+            .collect::<Result<_>>()?;
+        Ok(AccountTransactionsWithProof::new(txns))
+    }
+
+    fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+    ) -> Result<StateProof> {
+        let inner = self.inner.read();
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        ensure!(
+            known_version <= ledger_info.version(),
+            "Client known_version {} larger than ledger version {}.",
+            known_version,
+            ledger_info.version(),
+        );
+        let known_epoch = inner.epoch(known_version);
+        let end_epoch = ledger_info.next_block_epoch();
+        let ledger_infos = if known_epoch < end_epoch {
+            inner.epoch_ending_ledger_infos(known_epoch, end_epoch)?
+        } else {
+            vec![]
+        };
+        Ok(StateProof::new(
+            ledger_info_with_sigs,
+            EpochChangeProof::new(ledger_infos, /* more = */ false),
+        ))
+    }
+
+    fn get_state_proof(&self, known_version: u64) -> Result<StateProof> {
+        let ledger_info_with_sigs = self.inner.read().latest_ledger_info()?.clone();
+        self.get_state_proof_with_ledger_info(known_version, ledger_info_with_sigs)
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        let inner = self.inner.read();
+        let epoch = inner.epoch(known_version);
+        inner
+            .epoch_ending_ledger_infos
+            .get(&epoch)
+            .cloned()
+            .ok_or_else(|| format_err!("No ledger info ending epoch {}.", epoch))
+    }
+
+    fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .latest_version()
+            .map(|version| (version, inner.transactions[version as usize].info.clone())))
+    }
+
+    fn get_accumulator_root_hash(&self, version: Version) -> Result<HashValue> {
+        let inner = self.inner.read();
+        inner.ensure_ledger_version(version)?;
+        TransactionAccumulator::get_root_hash(&inner.accumulator, version + 1)
+    }
+
+    fn get_accumulator_consistency_proof(
+        &self,
+        client_known_version: Option<Version>,
+        ledger_version: Version,
+    ) -> Result<AccumulatorConsistencyProof> {
+        let inner = self.inner.read();
+        inner.ensure_ledger_version(ledger_version)?;
+        TransactionAccumulator::get_consistency_proof(
+            &inner.accumulator,
+            ledger_version + 1,
+            client_known_version.map_or(0, |v| v + 1),
+        )
+    }
+
+    fn get_ledger_prune_window(&self) -> Result<Option<usize>> {
+        Ok(None)
+    }
+}
+
+impl StateReader for InMemoryDb {
+    fn get_state_diff(
+        &self,
+        first_version: Version,
+        last_version: Version,
+    ) -> Result<Vec<(StateKey, WriteOp)>> {
+        let inner = self.inner.read();
+        ensure!(
+            first_version <= last_version,
+            "first_version {} > last_version {}",
+            first_version,
+            last_version
+        );
+        let mut changes = BTreeMap::new();
+        for version in first_version + 1..=last_version {
+            // Later writes to the same key replace earlier ones.
+            for (key, op) in &inner.transaction(version)?.write_set {
+                changes.insert(key.clone(), op.clone());
+            }
+        }
+        Ok(changes.into_iter().collect())
+    }
+
+    fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .latest_version()
+            .and_then(|version| inner.state_value(&state_key, version)))
+    }
+
+    fn get_state_values_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        let inner = self.inner.read();
+        let mut values = HashMap::new();
+        for (key, value) in inner.state_values(version) {
+            if key_prefix.is_prefix(key)? {
+                values.insert(key.clone(), value.clone());
+            }
+        }
+        Ok(values)
+    }
+
+    fn get_first_write_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        let inner = self.inner.read();
+        Ok(inner
+            .state
+            .get(state_key)
+            .and_then(|writes| writes.keys().next().copied())
+            .filter(|first_version| *first_version <= version))
+    }
+
+    fn get_creation_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        // never restored from a state snapshot
+        self.get_first_write_version(state_key, version)
+    }
+
+    fn get_write_versions(
+        &self,
+        state_key: &StateKey,
+        start_version: Version,
+        limit: u64,
+        version: Version,
+    ) -> Result<Vec<Version>> {
+        if start_version > version {
+            return Ok(vec![]);
+        }
+        let inner = self.inner.read();
+        Ok(inner
+            .state
+            .get(state_key)
+            .map(|writes| {
+                writes
+                    .range(start_version..=version)
+                    .map(|(write_version, _)| *write_version)
+                    .take(limit as usize)
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn get_state_value_with_proof(
+        &self,
+        state_key: StateKey,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<StateValueWithProof> {
+        let inner = self.inner.read();
+        let (value, sparse_merkle_proof) = inner.state_value_with_proof(&state_key, version);
+        Ok(StateValueWithProof::new(
+            version,
+            value,
+            StateStoreValueProof::new(
+                inner.transaction_info_with_proof(version, ledger_version)?,
+                sparse_merkle_proof,
+            ),
+        ))
+    }
+
+    fn get_state_value_with_proof_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)> {
+        Ok(self.inner.read().state_value_with_proof(state_key, version))
+    }
+
+    fn get_latest_tree_state(&self) -> Result<TreeState> {
+        let inner = self.inner.read();
+        inner.tree_state(inner.latest_version())
+    }
+
+    fn get_state_leaf_count(&self, version: Version) -> Result<usize> {
+        Ok(self.inner.read().state_values(version).count())
+    }
+
+    fn get_state_prune_window(&self) -> Result<Option<usize>> {
+        Ok(None)
+    }
+}
+
+impl EventReader for InMemoryDb {
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        Ok(self
+            .get_events_with_proofs(event_key, start, order, limit, None)?
+            .into_iter()
+            .map(|e| (e.transaction_version, e.event))
+            .collect())
+    }
+
+    fn get_events_with_proofs(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+        known_version: Option<u64>,
+    ) -> Result<Vec<EventWithProof>> {
+        let inner = self.inner.read();
+        let ledger_version = match known_version {
+            Some(version) => version,
+            None => inner
+                .latest_version()
+                .ok_or_else(|| format_err!("No transaction committed."))?,
+        };
+        inner.events_with_proofs(event_key, start, order, limit, ledger_version)
+    }
+
+    fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64, ContractEvent)>> {
+        let inner = self.inner.read();
+        let mut events = vec![];
+        for (version, txn) in inner.transactions.iter().enumerate() {
+            let version = version as Version;
+            if version < start_version || version > ledger_version {
+                continue;
+            }
+            for (index, event) in txn.events.iter().enumerate() {
+                let index = index as u64;
+                if version == start_version && index < start_index {
+                    continue;
+                }
+                if matches!(event.type_tag(), TypeTag::Struct(tag) if tag == struct_tag) {
+                    events.push((version, index, event.clone()));
+                }
+            }
+        }
+        events.truncate(limit as usize);
+        Ok(events)
+    }
+}
+
+impl DbWriter for InMemoryDb {
+    fn save_ledger_infos(&self, ledger_infos: &[LedgerInfoWithSignatures]) -> Result<()> {
+        let mut inner = self.inner.write();
+        for ledger_info in ledger_infos {
+            inner.save_ledger_info(ledger_info);
+        }
+        Ok(())
+    }
+
+    fn save_transactions(
+        &self,
+        txns_to_commit: &[TransactionToCommit],
+        first_version: Version,
+        ledger_info_with_sigs: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        let mut inner = self.inner.write();
+        ensure!(
+            first_version == inner.transactions.len() as u64,
+            "First version {} doesn't match the number of committed transactions {}.",
+            first_version,
+            inner.transactions.len(),
+        );
+
+        let leaves: Vec<_> = txns_to_commit
+            .iter()
+            .map(|txn| txn.transaction_info().hash())
+            .collect();
+        let (root_hash, frozen_nodes) =
+            TransactionAccumulator::append(&inner.accumulator, first_version, &leaves)?;
+        if let Some(ledger_info_with_sigs) = ledger_info_with_sigs {
+            let ledger_info = ledger_info_with_sigs.ledger_info();
+            ensure!(
+                ledger_info.version() + 1 == first_version + txns_to_commit.len() as u64,
+                "Ledger info version {} doesn't match the last transaction committed.",
+                ledger_info.version(),
+            );
+            ensure!(
+                ledger_info.transaction_accumulator_hash() == root_hash,
+                "Root hash mismatch: ledger info {}, transactions {}.",
+                ledger_info.transaction_accumulator_hash(),
+                root_hash,
+            );
+        }
+        inner.accumulator.0.extend(frozen_nodes);
+
+        for (version, txn) in (first_version..).zip(txns_to_commit) {
+            for (key, value) in txn.state_updates() {
+                inner
+                    .state
+                    .entry(key.clone())
+                    .or_default()
+                    .insert(version, value.clone());
+            }
+            for (index, event) in txn.events().iter().enumerate() {
+                inner
+                    .events
+                    .entry(*event.key())
+                    .or_default()
+                    .insert(event.sequence_number(), (version, index as u64));
+            }
+            if let Transaction::UserTransaction(user_txn) = txn.transaction() {
+                inner
+                    .account_transactions
+                    .entry(user_txn.sender())
+                    .or_default()
+                    .insert(user_txn.sequence_number(), version);
+            }
+            inner
+                .versions_by_hash
+                .insert(txn.transaction().hash(), version);
+            inner.transactions.push(CommittedTransaction {
+                transaction: txn.transaction().clone(),
+                info: txn.transaction_info().clone(),
+                write_set: txn.write_set().clone(),
+                events: txn.events().to_vec(),
+            });
+        }
+
+        if let Some(ledger_info_with_sigs) = ledger_info_with_sigs {
+            inner.save_ledger_info(ledger_info_with_sigs);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::async_reader::AsyncDbReader;
+    use aptos_types::{
+        block_info::BlockInfo,
+        block_metadata::BlockMetadata,
+        ledger_info::LedgerInfo,
+        proof::accumulator::InMemoryAccumulator,
+        transaction::{ExecutionStatus, TransactionToCommit},
+    };
+    use move_deps::move_core_types::language_storage::TypeTag;
+    use std::sync::Arc;
+
+    fn state_key(i: u64) -> StateKey {
+        StateKey::Raw(i.to_le_bytes().to_vec())
+    }
+
+    fn event_key() -> EventKey {
+        EventKey::new_from_address(&AccountAddress::ONE, 0)
+    }
+
+    /// Commits the block metadata transaction of `round`, which writes the round number to
+    /// `state_key(round % 2)` and emits the `round`-th event, and returns the ledger info
+    /// certifying it.
+    fn commit_round(db: &InMemoryDb, round: u64) -> LedgerInfoWithSignatures {
+        let version = db.get_latest_version_option().unwrap().map_or(0, |v| v + 1);
+        let txn = Transaction::BlockMetadata(BlockMetadata::new(
+            HashValue::random(),
+            1,
+            round,
+            vec![],
+            AccountAddress::ONE,
+            round,
+        ));
+        let state_updates: HashMap<_, _> = std::iter::once((
+            state_key(round % 2),
+            StateValue::from(round.to_le_bytes().to_vec()),
+        ))
+        .collect();
+        let events = vec![ContractEvent::new(
+            event_key(),
+            round,
+            TypeTag::Bool,
+            vec![],
+        )];
+
+        let mut state: BTreeMap<_, _> = db
+            .inner
+            .read()
+            .state_values(version)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        state.extend(state_updates.clone());
+        let leaves: Vec<_> = state
+            .iter()
+            .map(|(key, value)| (key.hash(), value))
+            .collect();
+        let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+        let info = TransactionInfo::new(
+            txn.hash(),
+            HashValue::zero(),
+            InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes).root_hash(),
+            Some(NaiveSmt::new(&leaves).get_root_hash()),
+            0,
+            ExecutionStatus::Success,
+        );
+        let txn_to_commit =
+            TransactionToCommit::new(txn, info, state_updates, None, WriteSet::default(), events);
+        db.save_transactions(&[txn_to_commit], version, None)
+            .unwrap();
+
+        let ledger_info = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(
+                BlockInfo::new(
+                    1,
+                    round,
+                    HashValue::zero(),
+                    db.get_accumulator_root_hash(version).unwrap(),
+                    version,
+                    round,
+                    None,
+                ),
+                HashValue::zero(),
+            ),
+            BTreeMap::new(),
+        );
+        db.save_ledger_infos(&[ledger_info.clone()]).unwrap();
+        ledger_info
+    }
+
+    #[test]
+    fn test_transactions_with_proof() {
+        let db = InMemoryDb::new();
+        let ledger_info = (1..=3)
+            .map(|round| commit_round(&db, round))
+            .last()
+            .unwrap();
+
+        db.get_transactions(1, 10, 2, true)
+            .unwrap()
+            .verify(ledger_info.ledger_info(), Some(1))
+            .unwrap();
+        let txn = db.get_transaction_by_version(2, 2, false).unwrap();
+        txn.proof.verify(ledger_info.ledger_info(), 2).unwrap();
+        assert_eq!(
+            db.get_transaction_by_hash(txn.transaction.hash(), 2, false)
+                .unwrap(),
+            Some(txn)
+        );
+        assert_eq!(
+            db.get_transactions(3, 10, 2, true).unwrap(),
+            TransactionListWithProof::new_empty()
+        );
+    }
+
+    #[test]
+    fn test_state_value_with_proof() {
+        let db = InMemoryDb::new();
+        let ledger_info = (1..=3)
+            .map(|round| commit_round(&db, round))
+            .last()
+            .unwrap();
+
+        // `state_key(0)` is written at version 1 only.
+        for (version, expected) in [(0, None), (1, Some(2u64)), (2, Some(2))] {
+            let value_with_proof = db
+                .get_state_value_with_proof(state_key(0), version, 2)
+                .unwrap();
+            value_with_proof
+                .verify(ledger_info.ledger_info(), version, state_key(0))
+                .unwrap();
+            assert_eq!(
+                value_with_proof.value,
+                expected.map(|round| StateValue::from(round.to_le_bytes().to_vec()))
+            );
+        }
+        assert_eq!(db.get_state_leaf_count(0).unwrap(), 1);
+        assert_eq!(db.get_state_leaf_count(2).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_events_with_proof() {
+        let db = InMemoryDb::new();
+        let ledger_info = (1..=3)
+            .map(|round| commit_round(&db, round))
+            .last()
+            .unwrap();
+
+        let events = db
+            .get_events_with_proofs(&event_key(), u64::MAX, Order::Descending, 2, None)
+            .unwrap();
+        assert_eq!(
+            events
+                .iter()
+                .map(|e| e.event.sequence_number())
+                .collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+        for event in events {
+            event
+                .verify(
+                    ledger_info.ledger_info(),
+                    &event_key(),
+                    event.event.sequence_number(),
+                    event.transaction_version,
+                    event.event_index,
+                )
+                .unwrap();
+        }
+        assert!(db
+            .get_events(&event_key(), 4, Order::Descending, 2)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_async_reads() {
+        let db = Arc::new(InMemoryDb::new());
+        for round in 1..=3 {
+            commit_round(&db, round);
+        }
+        let async_db = AsyncDbReader::new(db.clone());
+
+        assert_eq!(
+            async_db.ledger(|db| db.get_latest_version()).await.unwrap(),
+            2
+        );
+        assert_eq!(
+            async_db
+                .state(|db| db.get_state_value_with_proof(state_key(0), 2, 2))
+                .await
+                .unwrap(),
+            db.get_state_value_with_proof(state_key(0), 2, 2).unwrap()
+        );
+        assert_eq!(
+            async_db
+                .events(|db| db.get_events(&event_key(), 0, Order::Ascending, 10))
+                .await
+                .unwrap(),
+            db.get_events(&event_key(), 0, Order::Ascending, 10)
+                .unwrap()
+        );
+    }
+}
@@ -30,15 +30,21 @@ use aptos_types::{
         AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
-    write_set::WriteSet,
+    write_set::{WriteOp, WriteSet},
 };
+use move_deps::move_core_types::language_storage::StructTag;
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
 
+pub mod async_reader;
+pub mod errors;
+#[cfg(any(test, feature = "testing"))]
+pub mod in_memory_db;
 pub mod in_memory_state;
 #[cfg(any(feature = "testing", feature = "fuzzing"))]
 pub mod mock;
+pub mod proof_cache;
 pub mod state_view;
 pub mod verified_state_view;
 
@@ -160,6 +166,102 @@ impl TreeState {
     }
 }
 
+/// A page of the items of a table, see [`DbReader::get_table_items`].
+#[cfg(feature = "table-iteration")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TableItems {
+    /// The key, value pairs of the items in the order of their keys.
+    pub items: Vec<(Vec<u8>, StateValue)>,
+    /// The cursor to read the next page after, `None` on the last page.
+    pub next_cursor: Option<Vec<u8>>,
+}
+
+/// Gas paid by the user transactions of a block, kept by storage so that gas prices can be
+/// estimated from recent activity.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BlockGasStats {
+    pub timestamp_usecs: u64,
+    pub gas_used: u64,
+    /// Gas used multiplied by the gas unit price, summed over the transactions.
+    pub fees_burned: u64,
+    /// Gas unit price of every user transaction in the block, in commit order.
+    pub gas_unit_prices: Vec<u64>,
+}
+
+impl BlockGasStats {
+    pub fn new(timestamp_usecs: u64) -> Self {
+        Self {
+            timestamp_usecs,
+            ..Self::default()
+        }
+    }
+
+    pub fn add_transaction(&mut self, gas_unit_price: u64, gas_used: u64) {
+        self.gas_used = self.gas_used.saturating_add(gas_used);
+        self.fees_burned = self
+            .fees_burned
+            .saturating_add(gas_used.saturating_mul(gas_unit_price));
+        self.gas_unit_prices.push(gas_unit_price);
+    }
+
+    pub fn num_user_transactions(&self) -> usize {
+        self.gas_unit_prices.len()
+    }
+}
+
+/// Gas paid by the user transactions of the blocks started within a minute, kept by storage next
+/// to the [`BlockGasStats`] for the trends over longer periods than a few blocks.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct MinuteGasStats {
+    /// Timestamp of the start of the minute.
+    pub timestamp_usecs: u64,
+    pub num_blocks: u64,
+    pub num_user_transactions: u64,
+    pub gas_used: u64,
+    /// Gas used multiplied by the gas unit price, summed over the transactions.
+    pub fees_burned: u64,
+    /// Lowest gas unit price paid, 0 without user transactions.
+    pub min_gas_unit_price: u64,
+    /// Highest gas unit price paid, 0 without user transactions.
+    pub max_gas_unit_price: u64,
+}
+
+impl MinuteGasStats {
+    pub const USECS_PER_MINUTE: u64 = 60 * 1_000_000;
+
+    /// Empty stats of the minute `timestamp_usecs` is in.
+    pub fn new(timestamp_usecs: u64) -> Self {
+        Self {
+            timestamp_usecs: Self::minute_of(timestamp_usecs),
+            ..Self::default()
+        }
+    }
+
+    /// Timestamp of the start of the minute `timestamp_usecs` is in.
+    pub fn minute_of(timestamp_usecs: u64) -> u64 {
+        timestamp_usecs - timestamp_usecs % Self::USECS_PER_MINUTE
+    }
+
+    pub fn add_block(&mut self) {
+        self.num_blocks += 1;
+    }
+
+    pub fn add_transaction(&mut self, gas_unit_price: u64, gas_used: u64) {
+        if self.num_user_transactions == 0 {
+            self.min_gas_unit_price = gas_unit_price;
+            self.max_gas_unit_price = gas_unit_price;
+        } else {
+            self.min_gas_unit_price = self.min_gas_unit_price.min(gas_unit_price);
+            self.max_gas_unit_price = self.max_gas_unit_price.max(gas_unit_price);
+        }
+        self.num_user_transactions += 1;
+        self.gas_used = self.gas_used.saturating_add(gas_used);
+        self.fees_burned = self
+            .fees_burned
+            .saturating_add(gas_used.saturating_mul(gas_unit_price));
+    }
+}
+
 pub trait StateSnapshotReceiver<V>: Send {
     fn add_chunk(
         &mut self,
@@ -209,10 +311,10 @@ pub enum Order {
     Descending,
 }
 
-/// Trait that is implemented by a DB that supports certain public (to client) read APIs
-/// expected of an Aptos DB
+/// Reads the ledger history of an Aptos DB: the ledger infos, the transactions and their outputs,
+/// and the transaction accumulator.
 #[allow(unused_variables)]
-pub trait DbReader: Send + Sync {
+pub trait LedgerReader: Send + Sync {
     /// See [AptosDB::get_epoch_ending_ledger_infos].
     ///
     /// [AptosDB::get_epoch_ending_ledger_infos]:
@@ -299,26 +401,27 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
-    /// Returns events by given event key
-    fn get_events(
+    /// See [`AptosDB::get_block_gas_stats`].
+    ///
+    /// [`AptosDB::get_block_gas_stats`]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_block_gas_stats
+    fn get_block_gas_stats(
         &self,
-        event_key: &EventKey,
-        start: u64,
-        order: Order,
+        ledger_version: Version,
         limit: u64,
-    ) -> Result<Vec<(u64, ContractEvent)>> {
+    ) -> Result<Vec<BlockGasStats>> {
         unimplemented!()
     }
 
-    /// Returns events by given event key
-    fn get_events_with_proofs(
+    /// See [`AptosDB::get_minute_gas_stats`].
+    ///
+    /// [`AptosDB::get_minute_gas_stats`]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_minute_gas_stats
+    fn get_minute_gas_stats(
         &self,
-        event_key: &EventKey,
-        start: u64,
-        order: Order,
+        timestamp_usecs: u64,
         limit: u64,
-        known_version: Option<u64>,
-    ) -> Result<Vec<EventWithProof>> {
+    ) -> Result<Vec<MinuteGasStats>> {
         unimplemented!()
     }
 
@@ -330,17 +433,6 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
-    /// Returns the [`aptos_types::account_config::events::new_block::NewBlockEvent`] for the block
-    /// containing the requested `version` and proof that the block actually contains the `version`.
-    fn get_event_by_version_with_proof(
-        &self,
-        event_key: &EventKey,
-        event_version: u64,
-        proof_version: u64,
-    ) -> Result<EventByVersionWithProof> {
-        unimplemented!()
-    }
-
     /// Gets the version of the last transaction committed before timestamp,
     /// a committed block at or after the required timestamp must exist (otherwise it's possible
     /// the next block committed as a timestamp smaller than the one in the request).
@@ -352,25 +444,6 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
-    /// See [AptosDB::get_latest_account_state].
-    ///
-    /// [AptosDB::get_latest_account_state]:
-    /// ../aptosdb/struct.AptosDB.html#method.get_latest_account_state
-    fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
-        unimplemented!()
-    }
-
-    /// Returns the key, value pairs for a particular state key prefix at at desired version. This
-    /// API can be used to get all resources of an account by passing the account address as the
-    /// key prefix.
-    fn get_state_values_by_key_prefix(
-        &self,
-        key_prefix: &StateKeyPrefix,
-        version: Version,
-    ) -> Result<HashMap<StateKey, StateValue>> {
-        unimplemented!()
-    }
-
     /// Returns the latest ledger info, if any.
     fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
         unimplemented!()
@@ -452,39 +525,6 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
-    /// Returns the account state corresponding to the given version and account address with proof
-    /// based on `ledger_version`
-    fn get_state_value_with_proof(
-        &self,
-        state_key: StateKey,
-        version: Version,
-        ledger_version: Version,
-    ) -> Result<StateValueWithProof> {
-        unimplemented!()
-    }
-
-    /// Gets an account state by account address, out of the ledger state indicated by the state
-    /// Merkle tree root with a sparse merkle proof proving state tree root.
-    /// See [AptosDB::get_account_state_with_proof_by_version].
-    ///
-    /// [AptosDB::get_account_state_with_proof_by_version]:
-    /// ../aptosdb/struct.AptosDB.html#method.get_account_state_with_proof_by_version
-    ///
-    /// This is used by aptos core (executor) internally.
-    fn get_state_value_with_proof_by_version(
-        &self,
-        state_key: &StateKey,
-        version: Version,
-    ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)> {
-        unimplemented!()
-    }
-
-    /// Gets the latest TreeState no matter if db has been bootstrapped.
-    /// Used by the Db-bootstrapper.
-    fn get_latest_tree_state(&self) -> Result<TreeState> {
-        unimplemented!()
-    }
-
     /// Get the ledger info of the epoch that `known_version` belongs to.
     fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
         unimplemented!()
@@ -528,7 +568,7 @@ pub trait DbReader: Send + Sync {
     /// at the given `ledger_version`.
     ///
     /// Note: this is roughly equivalent to calling
-    /// `DbReader::get_accumulator_consistency_proof(None, ledger_version)`.
+    /// `LedgerReader::get_accumulator_consistency_proof(None, ledger_version)`.
     fn get_accumulator_summary(
         &self,
         ledger_version: Version,
@@ -541,6 +581,154 @@ pub trait DbReader: Send + Sync {
         )
     }
 
+    /// Get the ledger prune window config value.
+    fn get_ledger_prune_window(&self) -> Result<Option<usize>> {
+        unimplemented!()
+    }
+}
+
+/// Reads the state of an Aptos DB, and its proofs.
+#[allow(unused_variables)]
+pub trait StateReader: Send + Sync {
+    /// See [`AptosDB::get_state_diff`].
+    ///
+    /// [`AptosDB::get_state_diff`]: ../aptosdb/struct.AptosDB.html#method.get_state_diff
+    fn get_state_diff(
+        &self,
+        first_version: Version,
+        last_version: Version,
+    ) -> Result<Vec<(StateKey, WriteOp)>> {
+        unimplemented!()
+    }
+
+    /// See [AptosDB::get_latest_account_state].
+    ///
+    /// [AptosDB::get_latest_account_state]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_latest_account_state
+    fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
+        unimplemented!()
+    }
+
+    /// Returns the key, value pairs for a particular state key prefix at at desired version. This
+    /// API can be used to get all resources of an account by passing the account address as the
+    /// key prefix.
+    fn get_state_values_by_key_prefix(
+        &self,
+        key_prefix: &StateKeyPrefix,
+        version: Version,
+    ) -> Result<HashMap<StateKey, StateValue>> {
+        unimplemented!()
+    }
+
+    /// Returns the version `state_key` was first written at, or `None` if it wasn't written at or
+    /// before `version`. If the DB was restored from a state snapshot, this can be the version of
+    /// the snapshot rather than the one of the transaction that created the key.
+    fn get_first_write_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        unimplemented!()
+    }
+
+    /// Returns the version `state_key` was created at, or `None` if it wasn't written at or before
+    /// `version` or if it was created before the state snapshot the DB was restored from. Used
+    /// e.g. to tell when a resource or module was created.
+    fn get_creation_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<Version>> {
+        unimplemented!()
+    }
+
+    /// Returns up to `limit` versions `state_key` was written at, in ascending order, starting at
+    /// `start_version` and no later than `version`. Used e.g. to list the upgrades of a module.
+    fn get_write_versions(
+        &self,
+        state_key: &StateKey,
+        start_version: Version,
+        limit: u64,
+        version: Version,
+    ) -> Result<Vec<Version>> {
+        unimplemented!()
+    }
+
+    /// Batched version of [`Self::get_state_values_by_key_prefix`], returning the key, value pairs
+    /// of each prefix in the order of `key_prefixes`.
+    fn get_state_values_by_key_prefixes(
+        &self,
+        key_prefixes: &[StateKeyPrefix],
+        version: Version,
+    ) -> Result<Vec<HashMap<StateKey, StateValue>>> {
+        key_prefixes
+            .iter()
+            .map(|key_prefix| self.get_state_values_by_key_prefix(key_prefix, version))
+            .collect()
+    }
+
+    /// Returns up to `limit` items of the table `handle` at `version` as key, value pairs in the
+    /// order of their keys, starting after the key `cursor` if any. A read scans a bounded number
+    /// of keys, including the ones of items removed by `version`, so a page may hold fewer than
+    /// `limit` items and still be followed by others: the page ends with the cursor of the next
+    /// one unless it is the last.
+    #[cfg(feature = "table-iteration")]
+    fn get_table_items(
+        &self,
+        handle: u128,
+        version: Version,
+        cursor: Option<&[u8]>,
+        limit: u64,
+    ) -> Result<TableItems> {
+        unimplemented!()
+    }
+
+    /// Returns the account state corresponding to the given version and account address with proof
+    /// based on `ledger_version`
+    fn get_state_value_with_proof(
+        &self,
+        state_key: StateKey,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<StateValueWithProof> {
+        unimplemented!()
+    }
+
+    /// Returns a sparse Merkle non-inclusion proof, based on `ledger_version`, showing that the
+    /// account `address` does not exist at `version`, i.e. that it has no `0x1::Account::Account`
+    /// resource. If the resource was deleted, the proof shows its leaf carries no bytes instead.
+    /// Fails if the account exists.
+    fn get_account_non_existence_proof(
+        &self,
+        address: AccountAddress,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<StateValueWithProof> {
+        unimplemented!()
+    }
+
+    /// Gets an account state by account address, out of the ledger state indicated by the state
+    /// Merkle tree root with a sparse merkle proof proving state tree root.
+    /// See [AptosDB::get_account_state_with_proof_by_version].
+    ///
+    /// [AptosDB::get_account_state_with_proof_by_version]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_account_state_with_proof_by_version
+    ///
+    /// This is used by aptos core (executor) internally.
+    fn get_state_value_with_proof_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<(Option<StateValue>, SparseMerkleProof<StateValue>)> {
+        unimplemented!()
+    }
+
+    /// Gets the latest TreeState no matter if db has been bootstrapped.
+    /// Used by the Db-bootstrapper.
+    fn get_latest_tree_state(&self) -> Result<TreeState> {
+        unimplemented!()
+    }
+
     /// Returns total number of leaves in state store at given version.
     fn get_state_leaf_count(&self, version: Version) -> Result<usize> {
         unimplemented!()
@@ -560,11 +748,87 @@ pub trait DbReader: Send + Sync {
     fn get_state_prune_window(&self) -> Result<Option<usize>> {
         unimplemented!()
     }
+}
 
-    /// Get the ledger prune window config value.
-    fn get_ledger_prune_window(&self) -> Result<Option<usize>> {
+/// Reads the events of an Aptos DB.
+#[allow(unused_variables)]
+pub trait EventReader: Send + Sync {
+    /// Returns events by given event key
+    fn get_events(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+    ) -> Result<Vec<(u64, ContractEvent)>> {
+        unimplemented!()
+    }
+
+    /// Returns events by given event key
+    fn get_events_with_proofs(
+        &self,
+        event_key: &EventKey,
+        start: u64,
+        order: Order,
+        limit: u64,
+        known_version: Option<u64>,
+    ) -> Result<Vec<EventWithProof>> {
         unimplemented!()
     }
+
+    /// See [`AptosDB::get_events_by_type`].
+    ///
+    /// [`AptosDB::get_events_by_type`]:
+    /// ../aptosdb/struct.AptosDB.html#method.get_events_by_type
+    fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, u64, ContractEvent)>> {
+        unimplemented!()
+    }
+
+    /// Returns the [`aptos_types::account_config::events::new_block::NewBlockEvent`] for the block
+    /// containing the requested `version` and proof that the block actually contains the `version`.
+    fn get_event_by_version_with_proof(
+        &self,
+        event_key: &EventKey,
+        event_version: u64,
+        proof_version: u64,
+    ) -> Result<EventByVersionWithProof> {
+        unimplemented!()
+    }
+}
+
+/// Trait that is implemented by a DB that supports certain public (to client) read APIs
+/// expected of an Aptos DB. It is implemented for every type implementing the focused readers, so
+/// a DB implements [`LedgerReader`], [`StateReader`] and [`EventReader`] instead.
+pub trait DbReader: LedgerReader + StateReader + EventReader {
+    /// Views the DB as a [`LedgerReader`], for consumers that only read the ledger history.
+    fn as_ledger_reader(&self) -> &dyn LedgerReader;
+
+    /// Views the DB as a [`StateReader`], for consumers that only read the state.
+    fn as_state_reader(&self) -> &dyn StateReader;
+
+    /// Views the DB as an [`EventReader`], for consumers that only read the events.
+    fn as_event_reader(&self) -> &dyn EventReader;
+}
+
+impl<R: LedgerReader + StateReader + EventReader> DbReader for R {
+    fn as_ledger_reader(&self) -> &dyn LedgerReader {
+        self
+    }
+
+    fn as_state_reader(&self) -> &dyn StateReader {
+        self
+    }
+
+    fn as_event_reader(&self) -> &dyn EventReader {
+        self
+    }
 }
 
 impl MoveStorage for &dyn DbReader {
@@ -3,7 +3,7 @@
 
 //! This module provides mock dbreader for tests.
 
-use crate::{DbReader, DbWriter};
+use crate::{DbWriter, EventReader, LedgerReader, StateReader};
 use anyhow::{anyhow, Result};
 use aptos_types::{
     account_address::AccountAddress,
@@ -18,7 +18,14 @@ use move_deps::move_core_types::move_resource::MoveResource;
 /// This is a mock of the DbReaderWriter in tests.
 pub struct MockDbReaderWriter;
 
-impl DbReader for MockDbReaderWriter {
+impl LedgerReader for MockDbReaderWriter {
+    fn get_latest_version_option(&self) -> Result<Option<Version>> {
+        // return a dummy version for tests
+        Ok(Some(1))
+    }
+}
+
+impl StateReader for MockDbReaderWriter {
     fn get_latest_state_value(&self, state_key: StateKey) -> Result<Option<StateValue>> {
         match state_key {
             StateKey::AccessPath(access_path) => {
@@ -32,11 +39,6 @@ impl DbReader for MockDbReaderWriter {
         }
     }
 
-    fn get_latest_version_option(&self) -> Result<Option<Version>> {
-        // return a dummy version for tests
-        Ok(Some(1))
-    }
-
     fn get_state_value_with_proof_by_version(
         &self,
         state_key: &StateKey,
@@ -50,6 +52,8 @@ impl DbReader for MockDbReaderWriter {
     }
 }
 
+impl EventReader for MockDbReaderWriter {}
+
 fn get_mock_account_state() -> AccountState {
     let account_resource = AccountResource::new(0, vec![], AccountAddress::random());
 
@@ -0,0 +1,96 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    proof::SparseMerkleProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use lru::LruCache;
+use parking_lot::Mutex;
+
+/// Caches the state values and sparse Merkle proofs recently read from the state store, keyed by
+/// state key and version. Explorers and light clients tend to ask for the proofs of the same few
+/// accounts over and over.
+///
+/// The sparse Merkle proof of a state key at a version proves it against the state root of that
+/// version, so it never changes and stays valid whatever the ledger version the caller proves it
+/// against. Only the transaction accumulator proof depends on the ledger version, and it has to be
+/// built by the caller on every request.
+#[derive(Debug)]
+pub struct StateProofCache {
+    proofs: Mutex<LruCache<(StateKey, Version), CachedProof>>,
+}
+
+type CachedProof = (Option<StateValue>, SparseMerkleProof<StateValue>);
+
+impl StateProofCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            proofs: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// Returns the cached value of `state_key` at `version` and its sparse Merkle proof, if any.
+    pub fn get(&self, state_key: &StateKey, version: Version) -> Option<CachedProof> {
+        // `LruCache::get` takes the key by reference, which can't be built without a clone.
+        self.proofs
+            .lock()
+            .get(&(state_key.clone(), version))
+            .cloned()
+    }
+
+    pub fn insert(
+        &self,
+        state_key: StateKey,
+        version: Version,
+        value: Option<StateValue>,
+        proof: SparseMerkleProof<StateValue>,
+    ) {
+        self.proofs.lock().put((state_key, version), (value, proof));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+
+    fn proof(sibling: HashValue) -> SparseMerkleProof<StateValue> {
+        SparseMerkleProof::new(None, vec![sibling])
+    }
+
+    #[test]
+    fn test_get_by_key_and_version() {
+        let cache = StateProofCache::new(10);
+        let state_key = StateKey::Raw(b"key".to_vec());
+        let value = Some(StateValue::from(b"value".to_vec()));
+        let sibling = HashValue::random();
+        assert_eq!(cache.get(&state_key, 1), None);
+
+        cache.insert(state_key.clone(), 1, value.clone(), proof(sibling));
+        assert_eq!(cache.get(&state_key, 1), Some((value, proof(sibling))));
+        assert_eq!(cache.get(&state_key, 2), None);
+        assert_eq!(cache.get(&StateKey::Raw(b"other".to_vec()), 1), None);
+
+        cache.insert(state_key.clone(), 2, None, proof(sibling));
+        assert_eq!(cache.get(&state_key, 2), Some((None, proof(sibling))));
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let cache = StateProofCache::new(2);
+        let keys: Vec<_> = (0..3u8).map(|i| StateKey::Raw(vec![i])).collect();
+        let sibling = HashValue::random();
+
+        cache.insert(keys[0].clone(), 0, None, proof(sibling));
+        cache.insert(keys[1].clone(), 0, None, proof(sibling));
+        // Touching the first key makes the second one the least recently used.
+        assert!(cache.get(&keys[0], 0).is_some());
+        cache.insert(keys[2].clone(), 0, None, proof(sibling));
+
+        assert!(cache.get(&keys[0], 0).is_some());
+        assert!(cache.get(&keys[1], 0).is_none());
+        assert!(cache.get(&keys[2], 0).is_some());
+    }
+}
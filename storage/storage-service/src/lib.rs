@@ -18,7 +18,7 @@ use std::{
     sync::Arc,
     thread::{self, JoinHandle},
 };
-use storage_interface::{DbReader, DbWriter, Error, StartupInfo};
+use storage_interface::{DbWriter, Error, LedgerReader, StartupInfo, StateReader};
 
 /// Starts storage service with a given AptosDB
 pub fn start_storage_service_with_db(
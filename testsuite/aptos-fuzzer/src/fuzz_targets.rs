@@ -19,6 +19,7 @@ mod state_sync;
 mod storage;
 mod transaction;
 mod vm;
+mod wire;
 
 static ALL_TARGETS: Lazy<BTreeMap<&'static str, Box<dyn FuzzTargetImpl>>> = Lazy::new(|| {
     // List fuzz targets here in this format:
@@ -40,6 +41,8 @@ static ALL_TARGETS: Lazy<BTreeMap<&'static str, Box<dyn FuzzTargetImpl>>> = Lazy
         Box::new(proof::AccountStateProofFuzzer::default()),
         Box::new(proof::EventProofFuzzer::default()),
         Box::new(proof::TransactionInfoListWithProofFuzzer::default()),
+        Box::new(proof::TamperedAccumulatorProofFuzzer::default()),
+        Box::new(proof::TamperedSparseMerkleProofFuzzer::default()),
         // Network
         Box::new(network::NetworkNoiseInitiator::default()),
         Box::new(network::NetworkNoiseResponder::default()),
@@ -91,6 +94,16 @@ static ALL_TARGETS: Lazy<BTreeMap<&'static str, Box<dyn FuzzTargetImpl>>> = Lazy
         Box::new(transaction::TwoSignedTransactions::default()),
         // VM
         Box::new(vm::CompiledModuleTarget::default()),
+        // Wire messages
+        Box::new(wire::ConsensusMsgBcs::default()),
+        Box::new(wire::ConsensusMsgJson::default()),
+        Box::new(wire::MempoolSyncMsgBcs::default()),
+        Box::new(wire::MempoolRpcBcs::default()),
+        Box::new(wire::StateSyncMessageBcs::default()),
+        Box::new(wire::StorageServiceMessageBcs::default()),
+        Box::new(wire::HealthCheckerMsgBcs::default()),
+        Box::new(wire::HandshakeMsgBcs::default()),
+        Box::new(wire::NetworkMessageBcs::default()),
     ];
     targets
         .into_iter()
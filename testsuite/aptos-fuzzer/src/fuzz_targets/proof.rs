@@ -7,6 +7,7 @@ use aptos_proptest_helpers::ValueGenerator;
 use aptos_types::{
     ledger_info::LedgerInfo,
     proof::{
+        adversarial::{arb_accumulator_proof_case, arb_sparse_merkle_proof_case, ProofTampering},
         EventProof, SparseMerkleProof, StateStoreValueProof, TestAccumulatorProof,
         TestAccumulatorRangeProof, TransactionInfoListWithProof, TransactionInfoWithProof,
     },
@@ -231,3 +232,66 @@ impl FuzzTargetImpl for TransactionInfoListWithProofFuzzer {
             .verify(&input.ledger_info, input.first_transaction_version);
     }
 }
+
+#[derive(Clone, Debug, Default)]
+pub struct TamperedAccumulatorProofFuzzer;
+
+impl FuzzTargetImpl for TamperedAccumulatorProofFuzzer {
+    fn description(&self) -> &'static str {
+        "Proof: TestAccumulatorProof tampered with, which must never verify"
+    }
+
+    fn generate(&self, _idx: usize, _gen: &mut ValueGenerator) -> Option<Vec<u8>> {
+        Some(corpus_from_strategy((
+            arb_accumulator_proof_case(),
+            any::<ProofTampering>(),
+        )))
+    }
+
+    fn fuzz(&self, data: &[u8]) {
+        let (case, tampering) = fuzz_data_to_value(
+            data,
+            (arb_accumulator_proof_case(), any::<ProofTampering>()),
+        );
+        assert!(
+            case.verify_tampered(&tampering).is_err(),
+            "tampered proof verified: {:?} {:?}",
+            case,
+            tampering
+        );
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TamperedSparseMerkleProofFuzzer;
+
+impl FuzzTargetImpl for TamperedSparseMerkleProofFuzzer {
+    fn description(&self) -> &'static str {
+        "Proof: SparseMerkleProof tampered with, which must never verify"
+    }
+
+    fn generate(&self, _idx: usize, _gen: &mut ValueGenerator) -> Option<Vec<u8>> {
+        Some(corpus_from_strategy((
+            arb_sparse_merkle_proof_case(),
+            any::<ProofTampering>(),
+        )))
+    }
+
+    fn fuzz(&self, data: &[u8]) {
+        let (case, tampering) = fuzz_data_to_value(
+            data,
+            (arb_sparse_merkle_proof_case(), any::<ProofTampering>()),
+        );
+        assert!(
+            case.verify_tampered(&tampering).is_err(),
+            "tampered proof verified: {:?} {:?}",
+            case,
+            tampering
+        );
+        assert!(
+            case.verify_forged_non_inclusion().is_err(),
+            "forged non-inclusion proof verified: {:?}",
+            case
+        );
+    }
+}
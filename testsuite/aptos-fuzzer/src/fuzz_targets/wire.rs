@@ -0,0 +1,314 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Deserialization of the messages received from peers, byte for byte as they come off the wire.
+//! The corpus seeds are values generated from proptest strategies, serialized with the encoding
+//! of their protocol, so that the fuzzer mutates actual wire messages.
+
+use crate::FuzzTargetImpl;
+use aptos_proptest_helpers::ValueGenerator;
+use network::ProtocolId;
+use proptest::prelude::*;
+
+/// Declares a fuzz target deserializing `$msg` with the encoding of `$protocol`, seeded with
+/// values generated by `$strategy`.
+macro_rules! wire_fuzz_target {
+    ($name:ident, $description:expr, $protocol:expr, $msg:ty, $strategy:expr) => {
+        #[derive(Clone, Debug, Default)]
+        pub struct $name;
+
+        impl FuzzTargetImpl for $name {
+            fn description(&self) -> &'static str {
+                $description
+            }
+
+            fn generate(&self, _idx: usize, gen: &mut ValueGenerator) -> Option<Vec<u8>> {
+                let msg: $msg = gen.generate($strategy);
+                Some(
+                    $protocol
+                        .to_bytes(&msg)
+                        .expect("serialization should succeed"),
+                )
+            }
+
+            fn fuzz(&self, data: &[u8]) {
+                let _ = $protocol.from_bytes::<$msg>(data);
+            }
+        }
+    };
+}
+
+//
+// Consensus
+//
+
+use consensus::network_interface::{network_interface_fuzzing::arb_consensus_msg, ConsensusMsg};
+
+wire_fuzz_target!(
+    ConsensusMsgBcs,
+    "Consensus messages received from peers, encoded with BCS",
+    ProtocolId::ConsensusDirectSendBcs,
+    ConsensusMsg,
+    arb_consensus_msg()
+);
+
+wire_fuzz_target!(
+    ConsensusMsgJson,
+    "Consensus messages received from peers, encoded with JSON",
+    ProtocolId::ConsensusDirectSendJson,
+    ConsensusMsg,
+    arb_consensus_msg()
+);
+
+//
+// Mempool
+//
+
+use aptos_mempool::{fuzzing::mempool_sync_msg_strategy, network::MempoolSyncMsg};
+
+wire_fuzz_target!(
+    MempoolSyncMsgBcs,
+    "Mempool transaction broadcasts and acks received from peers",
+    ProtocolId::MempoolDirectSend,
+    MempoolSyncMsg,
+    mempool_sync_msg_strategy()
+);
+
+wire_fuzz_target!(
+    MempoolRpcBcs,
+    "Mempool transaction broadcasts and acks received from peers over RPC",
+    ProtocolId::MempoolRpc,
+    MempoolSyncMsg,
+    mempool_sync_msg_strategy()
+);
+
+//
+// State sync
+//
+
+use state_sync_v1::{fuzzing::arb_state_sync_msg, network::StateSyncMessage};
+
+wire_fuzz_target!(
+    StateSyncMessageBcs,
+    "State sync chunk requests and responses received from peers",
+    ProtocolId::StateSyncDirectSend,
+    StateSyncMessage,
+    arb_state_sync_msg()
+);
+
+use aptos_types::{
+    epoch_change::EpochChangeProof, ledger_info::LedgerInfoWithSignatures,
+    state_store::state_value::StateValueChunkWithProof, transaction::TransactionListWithProof,
+};
+use storage_service_types::{
+    AccountStatesChunkWithProofRequest, CompleteDataRange, DataSummary,
+    EpochEndingLedgerInfoRequest, NewTransactionOutputsWithProofRequest,
+    NewTransactionsWithProofRequest, ProtocolMetadata, ServerProtocolVersion, StorageServerSummary,
+    StorageServiceError, StorageServiceMessage, StorageServiceRequest, StorageServiceResponse,
+    TransactionOutputsWithProofRequest, TransactionsWithProofRequest,
+};
+
+fn arb_storage_service_request() -> impl Strategy<Value = StorageServiceRequest> {
+    prop_oneof![
+        (any::<u64>(), any::<u64>(), any::<u64>()).prop_map(
+            |(version, start_account_index, end_account_index)| {
+                StorageServiceRequest::GetAccountStatesChunkWithProof(
+                    AccountStatesChunkWithProofRequest {
+                        version,
+                        start_account_index,
+                        end_account_index,
+                    },
+                )
+            }
+        ),
+        (any::<u64>(), any::<u64>()).prop_map(|(start_epoch, expected_end_epoch)| {
+            StorageServiceRequest::GetEpochEndingLedgerInfos(EpochEndingLedgerInfoRequest {
+                start_epoch,
+                expected_end_epoch,
+            })
+        }),
+        (any::<u64>(), any::<u64>()).prop_map(|(known_version, known_epoch)| {
+            StorageServiceRequest::GetNewTransactionOutputsWithProof(
+                NewTransactionOutputsWithProofRequest {
+                    known_version,
+                    known_epoch,
+                },
+            )
+        }),
+        (any::<u64>(), any::<u64>(), any::<bool>()).prop_map(
+            |(known_version, known_epoch, include_events)| {
+                StorageServiceRequest::GetNewTransactionsWithProof(
+                    NewTransactionsWithProofRequest {
+                        known_version,
+                        known_epoch,
+                        include_events,
+                    },
+                )
+            }
+        ),
+        any::<u64>().prop_map(StorageServiceRequest::GetNumberOfAccountsAtVersion),
+        Just(StorageServiceRequest::GetServerProtocolVersion),
+        Just(StorageServiceRequest::GetStorageServerSummary),
+        (any::<u64>(), any::<u64>(), any::<u64>()).prop_map(
+            |(proof_version, start_version, end_version)| {
+                StorageServiceRequest::GetTransactionOutputsWithProof(
+                    TransactionOutputsWithProofRequest {
+                        proof_version,
+                        start_version,
+                        end_version,
+                    },
+                )
+            }
+        ),
+        (any::<u64>(), any::<u64>(), any::<u64>(), any::<bool>()).prop_map(
+            |(proof_version, start_version, end_version, include_events)| {
+                StorageServiceRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                    proof_version,
+                    start_version,
+                    end_version,
+                    include_events,
+                })
+            }
+        ),
+    ]
+}
+
+fn arb_data_range() -> impl Strategy<Value = Option<CompleteDataRange<u64>>> {
+    (any::<u64>(), any::<u64>())
+        .prop_map(|(lowest, highest)| CompleteDataRange::new(lowest, highest).ok())
+}
+
+fn arb_storage_server_summary() -> impl Strategy<Value = StorageServerSummary> {
+    (
+        any::<[u64; 4]>(),
+        proptest::option::of(any::<LedgerInfoWithSignatures>()),
+        arb_data_range(),
+        arb_data_range(),
+        arb_data_range(),
+        arb_data_range(),
+    )
+        .prop_map(
+            |(
+                chunk_sizes,
+                synced_ledger_info,
+                epoch_ending_ledger_infos,
+                transactions,
+                transaction_outputs,
+                account_states,
+            )| StorageServerSummary {
+                protocol_metadata: ProtocolMetadata {
+                    max_epoch_chunk_size: chunk_sizes[0],
+                    max_transaction_chunk_size: chunk_sizes[1],
+                    max_transaction_output_chunk_size: chunk_sizes[2],
+                    max_account_states_chunk_size: chunk_sizes[3],
+                },
+                data_summary: DataSummary {
+                    synced_ledger_info,
+                    epoch_ending_ledger_infos,
+                    transactions,
+                    transaction_outputs,
+                    account_states,
+                },
+            },
+        )
+}
+
+/// The responses whose payload has a strategy, transaction output lists have none.
+fn arb_storage_service_response() -> impl Strategy<Value = StorageServiceResponse> {
+    prop_oneof![
+        any::<StateValueChunkWithProof>()
+            .prop_map(StorageServiceResponse::AccountStatesChunkWithProof),
+        any::<EpochChangeProof>().prop_map(StorageServiceResponse::EpochEndingLedgerInfos),
+        (
+            any::<TransactionListWithProof>(),
+            any::<LedgerInfoWithSignatures>()
+        )
+            .prop_map(StorageServiceResponse::NewTransactionsWithProof),
+        any::<u64>().prop_map(StorageServiceResponse::NumberOfAccountsAtVersion),
+        any::<u64>().prop_map(|protocol_version| {
+            StorageServiceResponse::ServerProtocolVersion(ServerProtocolVersion {
+                protocol_version,
+            })
+        }),
+        arb_storage_server_summary().prop_map(StorageServiceResponse::StorageServerSummary),
+        any::<TransactionListWithProof>().prop_map(StorageServiceResponse::TransactionsWithProof),
+    ]
+}
+
+fn arb_storage_service_message() -> impl Strategy<Value = StorageServiceMessage> {
+    prop_oneof![
+        arb_storage_service_request().prop_map(StorageServiceMessage::Request),
+        arb_storage_service_response()
+            .prop_map(|response| StorageServiceMessage::Response(Ok(response))),
+        prop_oneof![
+            any::<String>().prop_map(StorageServiceError::InternalError),
+            any::<String>().prop_map(StorageServiceError::InvalidRequest),
+            any::<String>().prop_map(StorageServiceError::TooManyRequests),
+        ]
+        .prop_map(|error| StorageServiceMessage::Response(Err(error))),
+    ]
+}
+
+wire_fuzz_target!(
+    StorageServiceMessageBcs,
+    "State sync v2 storage service requests and responses received from peers",
+    ProtocolId::StorageServiceRpc,
+    StorageServiceMessage,
+    arb_storage_service_message()
+);
+
+//
+// Network
+//
+
+use network::{
+    fuzzing::perform_handshake_input,
+    protocols::{
+        health_checker::HealthCheckerMsg,
+        wire::{handshake::v1::HandshakeMsg, messaging::v1::NetworkMessage},
+    },
+};
+
+wire_fuzz_target!(
+    HealthCheckerMsgBcs,
+    "Health checker pings and pongs received from peers",
+    ProtocolId::HealthCheckerRpc,
+    HealthCheckerMsg,
+    any::<HealthCheckerMsg>()
+);
+
+#[derive(Clone, Debug, Default)]
+pub struct HandshakeMsgBcs;
+
+impl FuzzTargetImpl for HandshakeMsgBcs {
+    fn description(&self) -> &'static str {
+        "Handshake messages received from peers when connecting"
+    }
+
+    fn generate(&self, _idx: usize, gen: &mut ValueGenerator) -> Option<Vec<u8>> {
+        let msg = gen.generate(perform_handshake_input().prop_map(|(_, remote)| remote));
+        Some(bcs::to_bytes(&msg).expect("serialization should succeed"))
+    }
+
+    fn fuzz(&self, data: &[u8]) {
+        let _ = bcs::from_bytes::<HandshakeMsg>(data);
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NetworkMessageBcs;
+
+impl FuzzTargetImpl for NetworkMessageBcs {
+    fn description(&self) -> &'static str {
+        "Network messages framing the application messages received from peers"
+    }
+
+    fn generate(&self, _idx: usize, gen: &mut ValueGenerator) -> Option<Vec<u8>> {
+        let msg = gen.generate(any::<NetworkMessage>());
+        Some(bcs::to_bytes(&msg).expect("serialization should succeed"))
+    }
+
+    fn fuzz(&self, data: &[u8]) {
+        let _ = bcs::from_bytes::<NetworkMessage>(data);
+    }
+}
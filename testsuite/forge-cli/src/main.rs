@@ -7,8 +7,9 @@ use forge::{ForgeConfig, Options, Result, *};
 use std::{env, num::NonZeroUsize, process, time::Duration};
 use structopt::StructOpt;
 use testcases::{
-    compatibility_test::SimpleValidatorUpgrade, fixed_tps_test::FixedTpsTest,
-    gas_price_test::NonZeroGasPrice, generate_traffic, partial_nodes_down_test::PartialNodesDown,
+    compatibility_test::SimpleValidatorUpgrade, disk_pressure_test::DiskPressureTest,
+    fixed_tps_test::FixedTpsTest, gas_price_test::NonZeroGasPrice, generate_traffic,
+    multi_region_test::MultiRegionLatency, partial_nodes_down_test::PartialNodesDown,
     performance_test::PerformanceBenchmark, reconfiguration_test::ReconfigurationTest,
     state_sync_performance::StateSyncPerformance,
 };
@@ -319,6 +320,7 @@ fn get_test_suite(suite_name: &str) -> ForgeConfig<'static> {
         "land_blocking_compat" => land_blocking_test_compat_suite(),
         "land_blocking" => land_blocking_test_suite(),
         "pre_release" => pre_release_suite(),
+        "multi_region" => multi_region_suite(),
         single_test => single_test_suite(single_test),
     }
 }
@@ -357,6 +359,13 @@ fn land_blocking_test_suite() -> ForgeConfig<'static> {
         .with_network_tests(&[&PerformanceBenchmark])
 }
 
+fn multi_region_suite() -> ForgeConfig<'static> {
+    ForgeConfig::default()
+        .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
+        .with_region_profile(RegionProfile::mainnet_like())
+        .with_network_tests(&[&MultiRegionLatency, &PerformanceBenchmark])
+}
+
 fn land_blocking_test_compat_suite() -> ForgeConfig<'static> {
     // please keep tests order in this suite
     // since later tests node version rely on first test
@@ -376,6 +385,7 @@ fn pre_release_suite() -> ForgeConfig<'static> {
             &PerformanceBenchmark,
             &NonZeroGasPrice,
             &PartialNodesDown,
+            &DiskPressureTest,
             &ReconfigurationTest,
             &StateSyncPerformance,
         ])
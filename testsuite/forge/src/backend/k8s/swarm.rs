@@ -3,7 +3,8 @@
 
 use crate::{
     backend::k8s::node::K8sNode, create_k8s_client, query_sequence_numbers, remove_helm_release,
-    set_validator_image_tag, ChainInfo, FullNode, Node, Result, Swarm, Validator, Version,
+    set_validator_image_tag, ChainInfo, FullNode, Node, Result, Swarm, SwarmChaos, Validator,
+    Version,
 };
 use ::aptos_logger::*;
 use anyhow::{anyhow, bail, format_err};
@@ -20,13 +21,16 @@ use kube::{
     api::{Api, ListParams},
     client::Client as K8sClient,
 };
-use std::{collections::HashMap, convert::TryFrom, env, process::Command, str, sync::Arc};
+use std::{
+    collections::HashMap, convert::TryFrom, env, path::Path, process::Command, str, sync::Arc,
+};
 use tokio::time::Duration;
 
 const JSON_RPC_PORT: u32 = 80;
 const REST_API_PORT: u32 = 80;
 const VALIDATOR_LB: &str = "validator-validator-lb";
 const FULLNODES_LB: &str = "validator-fullnode-lb";
+const NODE_DATA_DIR: &str = "/opt/aptos/data";
 
 pub struct K8sSwarm {
     validators: HashMap<PeerId, K8sNode>,
@@ -99,6 +103,48 @@ impl K8sSwarm {
     fn get_kube_client(&self) -> K8sClient {
         self.kube_client.clone()
     }
+
+    fn exec_on_validator(&self, id: PeerId, script: &str) -> Result<()> {
+        let validator = self
+            .validators
+            .get(&id)
+            .ok_or_else(|| anyhow!("Invalid id: {}", id))?;
+        let pod_name = format!("{}-0", validator.sts_name);
+        let exec_args = ["exec", &pod_name, "--", "sh", "-c", script];
+        let output = Command::new("kubectl").args(&exec_args).output()?;
+        if !output.status.success() {
+            bail!(
+                "Failed to exec on {}: {}",
+                pod_name,
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Returns the IPs each validator can be reached at: the one of its service, which other
+    /// validators dial, and the one of its pod, which replies on dialed connections come from
+    fn validator_addresses(&self) -> Result<HashMap<PeerId, Vec<String>>> {
+        self.validators
+            .iter()
+            .map(|(peer_id, validator)| {
+                let pod_name = format!("{}-0", validator.sts_name);
+                let output = Command::new("kubectl")
+                    .args(&["get", "pod", &pod_name, "-o", "jsonpath={.status.podIP}"])
+                    .output()?;
+                if !output.status.success() {
+                    bail!(
+                        "Failed to get the IP of {}: {}",
+                        pod_name,
+                        String::from_utf8_lossy(&output.stderr)
+                    );
+                }
+                let pod_ip = String::from_utf8(output.stdout)?.trim().to_string();
+                Ok((*peer_id, vec![validator.ip.clone(), pod_ip]))
+            })
+            .collect()
+    }
 }
 
 #[async_trait::async_trait]
@@ -193,6 +239,38 @@ impl Swarm for K8sSwarm {
         ChainInfo::new(&mut self.root_account, rest_api_url, self.chain_id)
     }
 
+    fn inject_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        match chaos {
+            SwarmChaos::DiskPressure(pressure) => self.exec_on_validator(
+                pressure.validator,
+                &pressure.inject_script(Path::new(NODE_DATA_DIR)),
+            ),
+            // Requires the validators to run in privileged mode, to be allowed to use tc
+            SwarmChaos::NetworkDelay(delay) => {
+                let addresses = self.validator_addresses()?;
+                for source in delay.sources() {
+                    self.exec_on_validator(source, &delay.inject_script(source, &addresses))?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn remove_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        match chaos {
+            SwarmChaos::DiskPressure(pressure) => self.exec_on_validator(
+                pressure.validator,
+                &pressure.remove_script(Path::new(NODE_DATA_DIR)),
+            ),
+            SwarmChaos::NetworkDelay(delay) => {
+                for source in delay.sources() {
+                    self.exec_on_validator(source, &delay.remove_script())?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     // Returns env CENTRAL_LOGGING_ADDRESS if present (without timestamps)
     // otherwise returns a kubectl logs command to retrieve the logs manually
     fn logs_location(&mut self) -> String {
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    ChainInfo, FullNode, HealthCheckError, LocalNode, LocalVersion, Node, NodeExt, Swarm, SwarmExt,
-    Validator, Version,
+    ChainInfo, FullNode, HealthCheckError, LocalNode, LocalVersion, Node, NodeExt, Swarm,
+    SwarmChaos, SwarmExt, Validator, Version,
 };
 use anyhow::{anyhow, bail, Result};
 use aptos_config::config::NodeConfig;
@@ -18,6 +18,7 @@ use std::{
     num::NonZeroUsize,
     ops,
     path::{Path, PathBuf},
+    process::Command,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -142,6 +143,8 @@ impl LocalSwarmBuilder {
             // this delays empty block by (30-1) * 30ms
             self.template.consensus.mempool_poll_count = 30;
         }
+        // All the nodes of the swarm share the resources of the host
+        self.template.preflight.check_resources = false;
 
         let (root_keys, genesis, genesis_waypoint, validators) = ValidatorBuilder::new(
             &dir,
@@ -267,9 +270,10 @@ impl LocalSwarm {
     pub async fn add_validator_fullnode(
         &mut self,
         version: &Version,
-        template: NodeConfig,
+        mut template: NodeConfig,
         validator_peer_id: PeerId,
     ) -> Result<PeerId> {
+        template.preflight.check_resources = false;
         let validator = self
             .validators
             .get_mut(&validator_peer_id)
@@ -312,7 +316,8 @@ impl LocalSwarm {
         Ok(peer_id)
     }
 
-    fn add_fullnode(&mut self, version: &Version, template: NodeConfig) -> Result<PeerId> {
+    fn add_fullnode(&mut self, version: &Version, mut template: NodeConfig) -> Result<PeerId> {
+        template.preflight.check_resources = false;
         let name = self.node_name_counter.to_string();
         self.node_name_counter += 1;
         let fullnode_config = FullnodeConfig::public_fullnode(
@@ -366,6 +371,25 @@ impl LocalSwarm {
         self.fullnodes.get_mut(&peer_id)
     }
 
+    fn run_chaos_script(&self, id: PeerId, script: impl Fn(&Path) -> String) -> Result<()> {
+        let validator = self
+            .validators
+            .get(&id)
+            .ok_or_else(|| anyhow!("Invalid id: {}", id))?;
+        let script = script(&validator.config().storage.dir());
+        let output = Command::new("sh").arg("-c").arg(&script).output()?;
+        if !output.status.success() {
+            bail!(
+                "Chaos script '{}' failed on {}: {}",
+                script,
+                validator.name(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn dir(&self) -> &Path {
         self.dir.as_ref()
     }
@@ -477,6 +501,26 @@ impl Swarm for LocalSwarm {
         )
     }
 
+    fn inject_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        match chaos {
+            SwarmChaos::DiskPressure(pressure) => {
+                self.run_chaos_script(pressure.validator, |dir| pressure.inject_script(dir))
+            }
+            // All local nodes share the loopback interface, so there is no way to delay the
+            // traffic between some of them only
+            SwarmChaos::NetworkDelay(_) => bail!("LocalSwarm does not support network delays"),
+        }
+    }
+
+    fn remove_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        match chaos {
+            SwarmChaos::DiskPressure(pressure) => {
+                self.run_chaos_script(pressure.validator, |dir| pressure.remove_script(dir))
+            }
+            SwarmChaos::NetworkDelay(_) => bail!("LocalSwarm does not support network delays"),
+        }
+    }
+
     fn logs_location(&mut self) -> String {
         self.dir.persist();
         self.dir.display().to_string()
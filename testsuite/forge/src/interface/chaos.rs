@@ -0,0 +1,150 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_sdk::types::PeerId;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    path::Path,
+};
+
+/// Name of the file used to consume disk space when injecting `SwarmChaos::DiskPressure`
+pub const DISK_PRESSURE_FILLER: &str = "disk-pressure-filler";
+
+/// Network interface on which `SwarmChaos::NetworkDelay` delays a node's outgoing traffic
+const NETWORK_DEVICE: &str = "eth0";
+
+/// Failures that can be injected into, and later removed from, a running Swarm
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SwarmChaos {
+    DiskPressure(SwarmDiskPressure),
+    NetworkDelay(SwarmNetworkDelay),
+}
+
+impl fmt::Display for SwarmChaos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwarmChaos::DiskPressure(pressure) => write!(
+                f,
+                "DiskPressure(validator: {}, free_bytes: {})",
+                pressure.validator, pressure.free_bytes
+            ),
+            SwarmChaos::NetworkDelay(delay) => write!(
+                f,
+                "NetworkDelay(group_delays: {})",
+                delay.group_delays.len()
+            ),
+        }
+    }
+}
+
+/// Fills the volume holding a Validator's storage until only `free_bytes` remain available
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwarmDiskPressure {
+    pub validator: PeerId,
+    pub free_bytes: u64,
+}
+
+impl SwarmDiskPressure {
+    /// Shell script which allocates a filler file in `dir` so that only `free_bytes` remain
+    /// available on its volume. Does nothing if the volume is already that full.
+    pub fn inject_script(&self, dir: &Path) -> String {
+        let filler = dir.join(DISK_PRESSURE_FILLER);
+        format!(
+            "avail=$(df -P -B1 {dir} | tail -1 | awk '{{print $4}}'); \
+             size=$((avail - {free})); \
+             if [ \"$size\" -gt 0 ]; then fallocate -l \"$size\" {filler}; fi",
+            dir = dir.display(),
+            free = self.free_bytes,
+            filler = filler.display(),
+        )
+    }
+
+    /// Shell script which releases the space taken by `inject_script`
+    pub fn remove_script(&self, dir: &Path) -> String {
+        format!("rm -f {}", dir.join(DISK_PRESSURE_FILLER).display())
+    }
+}
+
+/// Delays the traffic sent between groups of Validators
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwarmNetworkDelay {
+    pub group_delays: Vec<GroupNetworkDelay>,
+}
+
+/// Delays all traffic sent from any Validator in `source` to any Validator in `target`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GroupNetworkDelay {
+    pub source: Vec<PeerId>,
+    pub target: Vec<PeerId>,
+    pub delay_ms: u64,
+}
+
+impl SwarmNetworkDelay {
+    /// Validators with at least some of their outgoing traffic delayed
+    pub fn sources(&self) -> BTreeSet<PeerId> {
+        self.group_delays
+            .iter()
+            .flat_map(|delay| delay.source.iter().copied())
+            .collect()
+    }
+
+    /// Shell script which, run on `source`, delays its traffic to every target of its groups.
+    /// `addresses` holds the IPs each Validator can be reached at. Requires `tc` and the
+    /// NET_ADMIN capability.
+    pub fn inject_script(
+        &self,
+        source: PeerId,
+        addresses: &HashMap<PeerId, Vec<String>>,
+    ) -> String {
+        let mut commands = vec![
+            format!(
+                "tc qdisc add dev {} root handle 1: htb default 1",
+                NETWORK_DEVICE
+            ),
+            format!(
+                "tc class add dev {} parent 1: classid 1:1 htb rate 100gbit",
+                NETWORK_DEVICE
+            ),
+        ];
+        let delays = self
+            .group_delays
+            .iter()
+            .filter(|delay| delay.source.contains(&source));
+        // Class 1:1 is the undelayed default, every delay gets a class of its own after it
+        for (class, delay) in (2..).zip(delays) {
+            commands.push(format!(
+                "tc class add dev {dev} parent 1: classid 1:{class:x} htb rate 100gbit",
+                dev = NETWORK_DEVICE,
+                class = class,
+            ));
+            commands.push(format!(
+                "tc qdisc add dev {dev} parent 1:{class:x} handle {class:x}: netem delay {delay}ms",
+                dev = NETWORK_DEVICE,
+                class = class,
+                delay = delay.delay_ms,
+            ));
+            let target_ips = delay
+                .target
+                .iter()
+                .filter_map(|target| addresses.get(target))
+                .flatten();
+            for ip in target_ips {
+                commands.push(format!(
+                    "tc filter add dev {dev} protocol ip parent 1: prio 1 u32 match ip dst {ip}/32 \
+                     flowid 1:{class:x}",
+                    dev = NETWORK_DEVICE,
+                    ip = ip,
+                    class = class,
+                ));
+            }
+        }
+
+        commands.join(" && ")
+    }
+
+    /// Shell script which removes the delays added by `inject_script`
+    pub fn remove_script(&self) -> String {
+        format!("tc qdisc del dev {} root", NETWORK_DEVICE)
+    }
+}
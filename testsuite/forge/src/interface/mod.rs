@@ -3,6 +3,8 @@
 
 mod admin;
 pub use admin::*;
+mod chaos;
+pub use chaos::*;
 mod aptos;
 pub use aptos::*;
 mod network;
@@ -15,6 +17,10 @@ mod swarm;
 pub use swarm::*;
 mod node;
 pub use node::*;
+mod regions;
+pub use regions::*;
+mod resources;
+pub use resources::*;
 mod chain_info;
 pub use chain_info::*;
 
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::Test;
-use crate::{CoreContext, Result, Swarm, TestReport};
+use crate::{CoreContext, Result, Swarm, SwarmRegions, TestReport};
 use transaction_emitter::EmitJobRequest;
 
 /// The testing interface which defines a test written with full control over an existing network.
@@ -18,6 +18,7 @@ pub struct NetworkContext<'t> {
     swarm: &'t mut dyn Swarm,
     pub report: &'t mut TestReport,
     pub global_job: EmitJobRequest,
+    regions: Option<&'t SwarmRegions>,
 }
 
 impl<'t> NetworkContext<'t> {
@@ -26,12 +27,14 @@ impl<'t> NetworkContext<'t> {
         swarm: &'t mut dyn Swarm,
         report: &'t mut TestReport,
         global_job: EmitJobRequest,
+        regions: Option<&'t SwarmRegions>,
     ) -> Self {
         Self {
             core,
             swarm,
             report,
             global_job,
+            regions,
         }
     }
 
@@ -42,4 +45,10 @@ impl<'t> NetworkContext<'t> {
     pub fn core(&mut self) -> &mut CoreContext {
         &mut self.core
     }
+
+    /// Returns the regions the Validators were spread over, if the Swarm was launched with a
+    /// `RegionProfile`
+    pub fn regions(&self) -> Option<&'t SwarmRegions> {
+        self.regions
+    }
 }
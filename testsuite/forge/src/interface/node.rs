@@ -175,6 +175,12 @@ pub trait NodeExt: Node {
         })
     }
 
+    /// Sets the actions of a failpoint on this Node, or deactivates it if `actions` is `None`.
+    /// The Node must be built with failpoints.
+    async fn set_failpoint(&self, name: &str, actions: Option<&str>) -> Result<()> {
+        self.debug_client().set_failpoint(name, actions).await
+    }
+
     async fn get_connected_peers(
         &self,
         network_id: NetworkId,
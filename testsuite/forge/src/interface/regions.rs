@@ -0,0 +1,128 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GroupNetworkDelay, Result, SwarmChaos, SwarmNetworkDelay};
+use anyhow::ensure;
+use aptos_sdk::types::PeerId;
+
+/// Round trip latencies in ms between a few cloud regions, approximating where mainnet validators
+/// are deployed
+const MAINNET_LIKE_REGIONS: [&str; 4] = ["us-east", "us-west", "eu-west", "ap-northeast"];
+const MAINNET_LIKE_LATENCIES_MS: [[u64; 4]; 4] = [
+    [2, 65, 75, 150],
+    [65, 2, 140, 105],
+    [75, 140, 2, 220],
+    [150, 105, 220, 2],
+];
+
+/// Spreads the Validators of a Swarm over simulated regions, with the traffic between two regions
+/// delayed according to a matrix of round trip latencies
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RegionProfile {
+    regions: Vec<String>,
+    latencies_ms: Vec<Vec<u64>>,
+}
+
+impl RegionProfile {
+    /// `latencies_ms[i][j]` is the round trip latency between `regions[i]` and `regions[j]`
+    pub fn new(regions: Vec<String>, latencies_ms: Vec<Vec<u64>>) -> Result<Self> {
+        let count = regions.len();
+        ensure!(count > 0, "at least one region is required");
+        ensure!(
+            latencies_ms.len() == count && latencies_ms.iter().all(|row| row.len() == count),
+            "latency matrix must be {}x{} for {} regions",
+            count,
+            count,
+            count
+        );
+
+        Ok(Self {
+            regions,
+            latencies_ms,
+        })
+    }
+
+    pub fn mainnet_like() -> Self {
+        Self {
+            regions: MAINNET_LIKE_REGIONS.iter().map(|r| r.to_string()).collect(),
+            latencies_ms: MAINNET_LIKE_LATENCIES_MS
+                .iter()
+                .map(|row| row.to_vec())
+                .collect(),
+        }
+    }
+
+    pub fn regions(&self) -> &[String] {
+        &self.regions
+    }
+
+    /// Round trip latency between the regions at indices `from` and `to`
+    pub fn latency_ms(&self, from: usize, to: usize) -> u64 {
+        self.latencies_ms[from][to]
+    }
+
+    /// Assigns `validators` to the regions in a round robin fashion
+    pub fn assign(&self, validators: impl IntoIterator<Item = PeerId>) -> SwarmRegions {
+        let mut validators = validators.into_iter().collect::<Vec<_>>();
+        // Swarms don't return their validators in a stable order
+        validators.sort();
+        let mut assignment = vec![Vec::new(); self.regions.len()];
+        for (i, validator) in validators.into_iter().enumerate() {
+            assignment[i % self.regions.len()].push(validator);
+        }
+
+        SwarmRegions {
+            profile: self.clone(),
+            validators: assignment,
+        }
+    }
+}
+
+/// The regions each of the Validators of a Swarm were placed in by a `RegionProfile`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwarmRegions {
+    profile: RegionProfile,
+    validators: Vec<Vec<PeerId>>,
+}
+
+impl SwarmRegions {
+    pub fn profile(&self) -> &RegionProfile {
+        &self.profile
+    }
+
+    /// Returns the name of every region along with the Validators placed in it
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[PeerId])> {
+        self.profile
+            .regions
+            .iter()
+            .map(String::as_str)
+            .zip(self.validators.iter().map(Vec::as_slice))
+    }
+
+    pub fn region_of(&self, validator: PeerId) -> Option<&str> {
+        self.iter()
+            .find(|(_, validators)| validators.contains(&validator))
+            .map(|(region, _)| region)
+    }
+
+    /// Chaos simulating the latencies between the regions. Each direction is delayed by half of
+    /// the round trip latency.
+    pub fn network_delay(&self) -> SwarmChaos {
+        let mut group_delays = vec![];
+        for (from, source) in self.validators.iter().enumerate() {
+            for (to, target) in self.validators.iter().enumerate() {
+                let delay_ms = self.profile.latency_ms(from, to) / 2;
+                if delay_ms == 0 || source.is_empty() || target.is_empty() {
+                    continue;
+                }
+                group_delays.push(GroupNetworkDelay {
+                    source: source.clone(),
+                    target: target.clone(),
+                    delay_ms,
+                });
+            }
+        }
+
+        SwarmChaos::NetworkDelay(SwarmNetworkDelay { group_delays })
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::Result;
+use debug_interface::AsyncNodeDebugClient;
+use futures::future::join_all;
+use std::{
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+use tokio::{runtime::Runtime, sync::oneshot};
+use url::Url;
+
+const CPU_USAGE_METRIC: &str = "system_process_cpu_usage_percent{}";
+const MEMORY_METRIC: &str = "system_process_memory_bytes{}";
+const DISK_READ_METRIC: &str = "system_process_disk_io_bytes{direction=read}";
+const DISK_WRITTEN_METRIC: &str = "system_process_disk_io_bytes{direction=written}";
+const NETWORK_RECEIVED_METRIC: &str = "system_network_io_bytes{direction=received}";
+const NETWORK_TRANSMITTED_METRIC: &str = "system_network_io_bytes{direction=transmitted}";
+
+/// A single reading of the resource usage a Node exports through its metrics
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NodeResourceSample {
+    /// CPU usage since the previous reading, 100 per fully used core
+    pub cpu_usage_percent: i64,
+    pub memory_bytes: i64,
+    pub disk_read_bytes: i64,
+    pub disk_written_bytes: i64,
+    pub network_received_bytes: i64,
+    pub network_transmitted_bytes: i64,
+}
+
+impl NodeResourceSample {
+    pub async fn query(client: &AsyncNodeDebugClient) -> Result<Self> {
+        let metrics = client.get_node_metrics().await?;
+        let metric = |name: &str| metrics.get(name).copied().unwrap_or_default();
+
+        Ok(Self {
+            cpu_usage_percent: metric(CPU_USAGE_METRIC),
+            memory_bytes: metric(MEMORY_METRIC),
+            disk_read_bytes: metric(DISK_READ_METRIC),
+            disk_written_bytes: metric(DISK_WRITTEN_METRIC),
+            network_received_bytes: metric(NETWORK_RECEIVED_METRIC),
+            network_transmitted_bytes: metric(NETWORK_TRANSMITTED_METRIC),
+        })
+    }
+}
+
+/// Resource usage of a single Node summarized over a sampling window
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeResourceUsage {
+    pub name: String,
+    pub avg_cpu_usage_percent: f64,
+    pub max_cpu_usage_percent: i64,
+    pub max_memory_bytes: i64,
+    pub disk_read_bytes_per_sec: f64,
+    pub disk_written_bytes_per_sec: f64,
+    pub network_received_bytes_per_sec: f64,
+    pub network_transmitted_bytes_per_sec: f64,
+}
+
+impl NodeResourceUsage {
+    /// Summarizes `samples`, each paired with the time it was taken at. Returns `None` unless
+    /// there are at least two samples, as rates can't be computed from a single one.
+    pub fn from_samples(name: String, samples: &[(Instant, NodeResourceSample)]) -> Option<Self> {
+        let ((first_at, first), (last_at, last)) = match samples {
+            [first, .., last] => (first, last),
+            _ => return None,
+        };
+        let elapsed = last_at
+            .duration_since(*first_at)
+            .as_secs_f64()
+            .max(f64::EPSILON);
+        let rate = |f: fn(&NodeResourceSample) -> i64| {
+            f(last).saturating_sub(f(first)).max(0) as f64 / elapsed
+        };
+        // The first reading covers the time before sampling started
+        let cpu = samples[1..].iter().map(|(_, s)| s.cpu_usage_percent);
+
+        Some(Self {
+            name,
+            avg_cpu_usage_percent: cpu.clone().sum::<i64>() as f64 / (samples.len() - 1) as f64,
+            max_cpu_usage_percent: cpu.max().unwrap_or_default(),
+            max_memory_bytes: samples
+                .iter()
+                .map(|(_, s)| s.memory_bytes)
+                .max()
+                .unwrap_or_default(),
+            disk_read_bytes_per_sec: rate(|s| s.disk_read_bytes),
+            disk_written_bytes_per_sec: rate(|s| s.disk_written_bytes),
+            network_received_bytes_per_sec: rate(|s| s.network_received_bytes),
+            network_transmitted_bytes_per_sec: rate(|s| s.network_transmitted_bytes),
+        })
+    }
+}
+
+/// Samples the resource usage of a set of Nodes in the background until stopped
+pub struct ResourceMonitor {
+    stop: oneshot::Sender<()>,
+    handle: JoinHandle<Vec<NodeResourceUsage>>,
+}
+
+impl ResourceMonitor {
+    /// Starts sampling each of the `(name, debug endpoint)` pairs in `nodes` every `interval`
+    pub fn start(nodes: Vec<(String, Url)>, interval: Duration) -> Self {
+        let (stop, mut stopped) = oneshot::channel();
+        let handle = thread::spawn(move || {
+            let clients = nodes
+                .iter()
+                .map(|(_, url)| AsyncNodeDebugClient::from_url(url.clone()))
+                .collect::<Vec<_>>();
+            let mut samples = vec![Vec::new(); nodes.len()];
+            let runtime = Runtime::new().unwrap();
+            runtime.block_on(async {
+                // Once stopped, a last sample is taken so the summary covers the whole window
+                let mut stopping = false;
+                loop {
+                    let now = Instant::now();
+                    let results = join_all(clients.iter().map(NodeResourceSample::query)).await;
+                    // Nodes may be down on purpose while they're being sampled, skip them
+                    for (node_samples, result) in samples.iter_mut().zip(results) {
+                        if let Ok(sample) = result {
+                            node_samples.push((now, sample));
+                        }
+                    }
+                    if stopping {
+                        break;
+                    }
+
+                    tokio::select! {
+                        _ = tokio::time::sleep(interval) => {}
+                        _ = &mut stopped => stopping = true,
+                    }
+                }
+            });
+
+            nodes
+                .into_iter()
+                .zip(samples)
+                .filter_map(|((name, _), samples)| NodeResourceUsage::from_samples(name, &samples))
+                .collect()
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Stops sampling and returns the resource usage of every Node which could be sampled
+    pub fn stop(self) -> Vec<NodeResourceUsage> {
+        let _ = self.stop.send(());
+        self.handle.join().unwrap_or_default()
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{ChainInfo, FullNode, NodeExt, Result, Validator, Version};
+use crate::{
+    ChainInfo, FullNode, NodeExt, ResourceMonitor, Result, SwarmChaos, Validator, Version,
+};
 use anyhow::{anyhow, bail};
 use aptos_config::config::NodeConfig;
 use aptos_rest_client::Client as RestClient;
@@ -62,6 +64,12 @@ pub trait Swarm: Sync {
     /// Construct a ChainInfo from this Swarm
     fn chain_info(&mut self) -> ChainInfo<'_>;
 
+    /// Injects the provided chaos into the Swarm
+    fn inject_chaos(&mut self, chaos: SwarmChaos) -> Result<()>;
+
+    /// Removes previously injected chaos from the Swarm
+    fn remove_chaos(&mut self, chaos: SwarmChaos) -> Result<()>;
+
     fn logs_location(&mut self) -> String;
 }
 
@@ -122,6 +130,20 @@ pub trait SwarmExt: Swarm {
         Ok(())
     }
 
+    /// Starts sampling the resource usage of all Validators and FullNodes every `interval`
+    fn start_resource_monitor(&self, interval: Duration) -> ResourceMonitor {
+        let nodes = self
+            .validators()
+            .map(|node| (node.name().to_string(), node.debug_endpoint()))
+            .chain(
+                self.full_nodes()
+                    .map(|node| (node.name().to_string(), node.debug_endpoint())),
+            )
+            .collect();
+
+        ResourceMonitor::start(nodes, interval)
+    }
+
     /// Perform a safety check, ensuring that no forks have occurred in the network.
     fn fork_check(&self) -> Result<()> {
         // Checks if root_hashes are equal across all nodes at a given version
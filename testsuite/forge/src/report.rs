@@ -1,14 +1,19 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::TxnStats;
+use crate::{NodeResourceUsage, TxnStats};
 use serde::Serialize;
-use std::{fmt, time::Duration};
+use std::{cmp::Ordering, fmt, time::Duration};
+
+const MB: f64 = 1024.0 * 1024.0;
 
 #[derive(Default, Debug, Serialize)]
 pub struct TestReport {
     metrics: Vec<ReportedMetric>,
     text: String,
+    /// Resource usage sampled while generating the traffic which will be reported next
+    #[serde(skip)]
+    resource_usage: Vec<NodeResourceUsage>,
 }
 
 #[derive(Debug, Serialize)]
@@ -38,6 +43,73 @@ impl TestReport {
         self.text.push_str(&text);
     }
 
+    /// Stages the resource usage of the nodes taking traffic, to be reported alongside the next
+    /// `report_txn_stats`
+    pub fn set_resource_usage(&mut self, usage: Vec<NodeResourceUsage>) {
+        self.resource_usage = usage;
+    }
+
+    pub fn report_resource_usage(&mut self, test_name: String, usage: &[NodeResourceUsage]) {
+        if usage.is_empty() {
+            return;
+        }
+        let nodes = usage.len() as f64;
+        let avg_cpu = usage.iter().map(|u| u.avg_cpu_usage_percent).sum::<f64>() / nodes;
+        let busiest = usage
+            .iter()
+            .max_by(|a, b| {
+                a.avg_cpu_usage_percent
+                    .partial_cmp(&b.avg_cpu_usage_percent)
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap();
+        let max_memory = usage.iter().map(|u| u.max_memory_bytes).max().unwrap() as f64 / MB;
+        let avg_disk_written = usage
+            .iter()
+            .map(|u| u.disk_written_bytes_per_sec)
+            .sum::<f64>()
+            / nodes
+            / MB;
+        let avg_network_received = usage
+            .iter()
+            .map(|u| u.network_received_bytes_per_sec)
+            .sum::<f64>()
+            / nodes
+            / MB;
+        let avg_network_transmitted = usage
+            .iter()
+            .map(|u| u.network_transmitted_bytes_per_sec)
+            .sum::<f64>()
+            / nodes
+            / MB;
+        self.report_metric(test_name.clone(), "avg_cpu_percent", avg_cpu);
+        self.report_metric(
+            test_name.clone(),
+            "max_cpu_percent",
+            busiest.avg_cpu_usage_percent,
+        );
+        self.report_metric(test_name.clone(), "max_memory_mb", max_memory);
+        self.report_metric(test_name.clone(), "avg_disk_write_mbps", avg_disk_written);
+        self.report_metric(test_name.clone(), "avg_net_in_mbps", avg_network_received);
+        self.report_metric(
+            test_name.clone(),
+            "avg_net_out_mbps",
+            avg_network_transmitted,
+        );
+        self.report_text(format!(
+            "{} : {:.0}% avg cpu ({} busiest at {:.0}%), {:.0} MB max memory, \
+             {:.1} MB/s disk writes, {:.1} MB/s net in, {:.1} MB/s net out",
+            test_name,
+            avg_cpu,
+            busiest.name,
+            busiest.avg_cpu_usage_percent,
+            max_memory,
+            avg_disk_written,
+            avg_network_received,
+            avg_network_transmitted
+        ));
+    }
+
     pub fn report_txn_stats(&mut self, test_name: String, stats: TxnStats, window: Duration) {
         let submitted_txn = stats.submitted;
         let expired_txn = stats.expired;
@@ -62,6 +134,8 @@ impl TestReport {
             "{} : {:.0} TPS, {:.1} ms latency, {:.1} ms p99 latency,{}",
             test_name, avg_tps, avg_latency_client, p99_latency, expired_text
         ));
+        let usage = std::mem::take(&mut self.resource_usage);
+        self.report_resource_usage(test_name, &usage);
     }
 
     pub fn print_report(&self) {
@@ -122,6 +122,9 @@ pub struct ForgeConfig<'cfg> {
 
     /// The initial genesis modules to use when starting a network
     genesis_config: Option<GenesisConfig>,
+
+    /// The simulated regions to spread the validators over, if any
+    region_profile: Option<RegionProfile>,
 }
 
 impl<'cfg> ForgeConfig<'cfg> {
@@ -164,6 +167,11 @@ impl<'cfg> ForgeConfig<'cfg> {
         self
     }
 
+    pub fn with_region_profile(mut self, region_profile: RegionProfile) -> Self {
+        self.region_profile = Some(region_profile);
+        self
+    }
+
     pub fn number_of_tests(&self) -> usize {
         self.admin_tests.len() + self.network_tests.len() + self.aptos_tests.len()
     }
@@ -186,6 +194,7 @@ impl<'cfg> Default for ForgeConfig<'cfg> {
             initial_validator_count: NonZeroUsize::new(1).unwrap(),
             initial_version: InitialVersion::Newest,
             genesis_config: None,
+            region_profile: None,
         }
     }
 }
@@ -272,6 +281,18 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
                 self.tests.genesis_config.as_ref(),
             ))?;
 
+            let regions = match &self.tests.region_profile {
+                Some(profile) => {
+                    let regions = profile.assign(swarm.validators().map(|v| v.peer_id()));
+                    for (region, validators) in regions.iter() {
+                        println!("Placing {} validators in {}", validators.len(), region);
+                    }
+                    swarm.inject_chaos(regions.network_delay())?;
+                    Some(regions)
+                }
+                None => None,
+            };
+
             // Run AptosTests
             for test in self.filter_tests(self.tests.aptos_tests.iter()) {
                 let mut aptos_ctx = AptosContext::new(
@@ -300,11 +321,16 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
                     &mut *swarm,
                     &mut report,
                     self.global_job_request.clone(),
+                    regions.as_ref(),
                 );
                 let result = run_test(|| test.run(&mut network_ctx));
                 summary.handle_result(test.name().to_owned(), result)?;
             }
 
+            if let Some(regions) = &regions {
+                swarm.remove_chaos(regions.network_delay())?;
+            }
+
             report.print_report();
 
             io::stdout().flush()?;
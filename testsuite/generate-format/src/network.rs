@@ -50,6 +50,7 @@ pub fn get_registry() -> Result<Registry> {
     tracer.trace_type::<handshake::v1::ProtocolId>(&samples)?;
     tracer.trace_type::<address::Protocol>(&samples)?;
     tracer.trace_type::<aptos_config::network_id::NetworkId>(&samples)?;
+    tracer.trace_type::<aptos_config::config::CompressionCodec>(&samples)?;
 
     tracer.registry()
 }
@@ -1,12 +1,21 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::operational_tooling::launch_swarm_with_op_tool_and_backend;
+use crate::{
+    operational_tooling::launch_swarm_with_op_tool_and_backend,
+    smoke_test_environment::new_local_swarm_with_aptos,
+    test_utils::{create_and_fund_account, transfer_coins_non_blocking},
+};
 use aptos_config::config::SecureBackend;
 use aptos_secure_storage::{KVStorage, Storage};
-use aptos_types::network_address::NetworkAddress;
-use forge::NodeExt;
-use std::{convert::TryInto, str::FromStr};
+use aptos_transaction_builder::aptos_stdlib;
+use aptos_types::{network_address::NetworkAddress, on_chain_config::FeatureFlag};
+use forge::{NodeExt, Swarm, SwarmExt};
+use std::{
+    convert::TryInto,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 #[ignore]
 #[tokio::test]
@@ -60,6 +69,71 @@ async fn test_consensus_observer_mode_storage_error() {
     assert_eq!(sequence_number_0, sequence_number_1);
 }
 
+#[tokio::test]
+async fn test_shuffled_proposals_are_consistent() {
+    let mut swarm = new_local_swarm_with_aptos(4).await;
+
+    let client = swarm.validators().next().unwrap().rest_client();
+    let transaction_factory = swarm.chain_info().transaction_factory();
+    let enable_shuffling = swarm
+        .chain_info()
+        .root_account
+        .sign_with_transaction_builder(transaction_factory.payload(
+            aptos_stdlib::encode_features_change_feature_flags(
+                vec![FeatureFlag::ShuffleTransactions as u64],
+                vec![],
+            ),
+        ));
+    // Shuffling is switched on for every validator at once, at the reconfiguration.
+    client.submit_and_wait(&enable_shuffling).await.unwrap();
+
+    // Several senders each submitting a burst of transfers, so that blocks hold transactions
+    // from more than one sender.
+    let mut senders = vec![];
+    for _ in 0..4 {
+        senders.push(create_and_fund_account(&mut swarm, 1000).await);
+    }
+    let receiver = create_and_fund_account(&mut swarm, 10).await;
+    let mut last_txns = vec![];
+    for _ in 0..5 {
+        for sender in senders.iter_mut() {
+            let txn =
+                transfer_coins_non_blocking(&client, &transaction_factory, sender, &receiver, 1)
+                    .await;
+            last_txns.push(txn);
+        }
+    }
+    for txn in &last_txns {
+        client.wait_for_signed_transaction(txn).await.unwrap();
+    }
+    swarm
+        .wait_for_all_nodes_to_catchup(Instant::now() + Duration::from_secs(60))
+        .await
+        .unwrap();
+
+    // Every validator committed the same transactions in the same order. Rounds keep
+    // advancing, so only the prefix every validator has is compared.
+    let mut committed = vec![];
+    for validator in swarm.validators() {
+        let transactions = validator
+            .rest_client()
+            .get_transactions(Some(0), Some(1000))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(transactions.iter().all(|txn| txn.success()));
+        let hashes: Vec<_> = transactions
+            .iter()
+            .map(|txn| txn.transaction_info().unwrap().hash)
+            .collect();
+        committed.push(hashes);
+    }
+    let common_len = committed.iter().map(Vec::len).min().unwrap();
+    assert!(committed
+        .windows(2)
+        .all(|pair| pair[0][..common_len] == pair[1][..common_len]));
+}
+
 // TODO(https://github.com/aptos-labs/aptos-core/issues/317): add back after support update consensus config in aptos-framework
 // #[allow(dead_code)]
 // async fn test_onchain_upgrade(new_onfig: OnChainConsensusConfig) {
@@ -14,7 +14,7 @@ use aptos::{
     op::key::{GenerateKey, PUBLIC_KEY_EXTENSION},
 };
 use aptos_config::{
-    config::{PeerRole, SecureBackend},
+    config::{OnDiskStorageConfig, PeerRole, SecureBackend},
     network_id::NetworkId,
 };
 use aptos_crypto::{
@@ -49,6 +49,7 @@ use std::{
     collections::HashSet,
     convert::{TryFrom, TryInto},
     fs,
+    os::unix::fs::PermissionsExt,
     path::{Path, PathBuf},
     str::FromStr,
     time::{Duration, Instant},
@@ -442,6 +443,7 @@ async fn test_single_node_operations() {
     test_print_account(&swarm, &op_tool, &backend, &mut storage).await;
     test_print_key(&swarm, &op_tool, &backend, &mut storage).await;
     test_print_waypoints(&swarm, &op_tool, &backend, &mut storage).await;
+    test_safety_storage_migration(&swarm, &op_tool, &backend, &mut storage).await;
     test_verify_validator_state(&swarm, &op_tool, &backend, &mut storage).await;
 }
 
@@ -999,6 +1001,84 @@ async fn test_print_waypoints(
     assert_eq!(inserted_waypoint, genesis_waypoint);
 }
 
+async fn test_safety_storage_migration(
+    swarm: &LocalSwarm,
+    op_tool: &OperationalTool,
+    backend: &SecureBackend,
+    storage: &mut Storage,
+) {
+    // Export the safety rules storage and check the snapshot matches storage
+    let node_config_path = swarm.validators().next().unwrap().config_path();
+    let snapshot_file = node_config_path.with_file_name("safety-storage.json");
+    op_tool
+        .export_safety_storage(&snapshot_file, backend)
+        .await
+        .unwrap();
+    let snapshot = op_tool
+        .inspect_safety_storage_snapshot(&snapshot_file)
+        .await
+        .unwrap();
+    assert_eq!(
+        storage.get::<AccountAddress>(OWNER_ACCOUNT).unwrap().value,
+        snapshot.author
+    );
+    assert_eq!(
+        storage.get_public_key(CONSENSUS_KEY).unwrap().public_key,
+        snapshot.consensus_public_key
+    );
+    // The snapshot holds private keys, it is only readable by its owner and never overwritten
+    let mode = fs::metadata(&snapshot_file).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    op_tool
+        .export_safety_storage(&snapshot_file, backend)
+        .await
+        .unwrap_err();
+
+    // There's no safety data to check the snapshot against in an empty backend
+    let mut empty_config = OnDiskStorageConfig::default();
+    empty_config.path = node_config_path.with_file_name("empty-safety-storage.json");
+    op_tool
+        .import_safety_storage(&snapshot_file, &SecureBackend::OnDiskStorage(empty_config))
+        .await
+        .unwrap_err();
+
+    // Migrate the safety rules storage to a new backend
+    let mut destination_config = OnDiskStorageConfig::default();
+    destination_config.path = node_config_path.with_file_name("migrated-safety-storage.json");
+    let destination = SecureBackend::OnDiskStorage(destination_config);
+    op_tool
+        .migrate_safety_storage(backend, &destination)
+        .await
+        .unwrap();
+    let migrated = op_tool.inspect_safety_storage(&destination).await.unwrap();
+    assert_eq!(snapshot.author, migrated.author);
+    assert_eq!(snapshot.consensus_public_key, migrated.consensus_public_key);
+    assert_eq!(snapshot.execution_public_key, migrated.execution_public_key);
+
+    // Wait for the validator to vote past the exported snapshot and migrate again
+    let deadline = Instant::now() + Duration::from_secs(30);
+    loop {
+        let current = op_tool.inspect_safety_storage(backend).await.unwrap();
+        if current.safety_data.epoch > snapshot.safety_data.epoch
+            || current.safety_data.last_voted_round > snapshot.safety_data.last_voted_round
+        {
+            break;
+        }
+        assert!(Instant::now() < deadline, "Validator did not vote");
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    op_tool
+        .migrate_safety_storage(backend, &destination)
+        .await
+        .unwrap();
+
+    // Restoring the older snapshot would roll back the last voted round
+    op_tool
+        .import_safety_storage(&snapshot_file, &destination)
+        .await
+        .unwrap_err();
+}
+
 // TODO(https://github.com/aptos-labs/aptos-core/issues/317)
 #[ignore]
 #[tokio::test]
@@ -135,6 +135,26 @@ async fn test_full_node_continuous_sync_transactions() {
     test_full_node_sync(vfn_peer_id, swarm, false).await;
 }
 
+#[tokio::test]
+async fn test_full_node_continuous_sync_adaptive() {
+    // Create a validator swarm of 1 validator node
+    let mut swarm = new_local_swarm_with_aptos(1).await;
+
+    // Create a fullnode config that picks how to sync depending on its lag
+    let mut vfn_config = NodeConfig::default_for_validator_full_node();
+    vfn_config.state_sync.state_sync_driver.enable_state_sync_v2 = true;
+    vfn_config
+        .state_sync
+        .state_sync_driver
+        .continuous_syncing_mode = ContinuousSyncingMode::Adaptive;
+
+    // Create the fullnode
+    let vfn_peer_id = create_full_node(vfn_config, &mut swarm).await;
+
+    // Test the ability of the fullnode to sync
+    test_full_node_sync(vfn_peer_id, swarm, false).await;
+}
+
 /// Creates a new full node using the given config and swarm
 async fn create_full_node(full_node_config: NodeConfig, swarm: &mut LocalSwarm) -> PeerId {
     let validator_peer_id = swarm.validators().next().unwrap().peer_id();
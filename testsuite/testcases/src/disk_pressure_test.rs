@@ -0,0 +1,115 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::generate_traffic;
+use anyhow::{bail, ensure};
+use forge::{
+    NetworkContext, NetworkTest, NodeExt, Result, SwarmChaos, SwarmDiskPressure, SwarmExt, Test,
+};
+use std::time::Instant;
+use tokio::{runtime::Runtime, time::Duration};
+
+/// Space left on the pressured validator's volume, small enough that storage writes fail
+/// within a few blocks
+const FREE_BYTES: u64 = 16 * 1024 * 1024;
+
+pub struct DiskPressureTest;
+
+impl Test for DiskPressureTest {
+    fn name(&self) -> &'static str {
+        "disk-pressure"
+    }
+}
+
+impl NetworkTest for DiskPressureTest {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let runtime = Runtime::new()?;
+        let duration = Duration::from_secs(120);
+        let mut all_validators = ctx
+            .swarm()
+            .validators()
+            .map(|v| v.peer_id())
+            .collect::<Vec<_>>();
+        if all_validators.len() < 4 {
+            bail!("disk pressure test requires >= 4 validators");
+        }
+        let pressured = all_validators.remove(0);
+        let chaos = SwarmChaos::DiskPressure(SwarmDiskPressure {
+            validator: pressured,
+            free_bytes: FREE_BYTES,
+        });
+
+        // 1. Fill the disk of a single validator while the rest of the network takes traffic
+        let msg = format!("1. Injecting {}", chaos);
+        println!("{}", msg);
+        ctx.report.report_text(msg);
+        ctx.swarm().inject_chaos(chaos.clone())?;
+        let txn_stat = generate_traffic(ctx, &all_validators, duration, 1, None)?;
+        ensure!(
+            txn_stat.committed > 0,
+            "network stopped committing while a single validator was under disk pressure"
+        );
+        ctx.report
+            .report_txn_stats(self.name().to_string(), txn_stat, duration);
+
+        // 2. The pressured validator must fall behind (or stop serving) rather than keep
+        // committing into a full volume
+        let pressured_version = runtime.block_on(async {
+            ctx.swarm()
+                .validator(pressured)
+                .unwrap()
+                .rest_client()
+                .get_ledger_information()
+                .await
+                .map(|r| r.into_inner().version)
+                .ok()
+        });
+        let mut healthy_version = 0;
+        for validator in &all_validators {
+            let version = runtime.block_on(
+                ctx.swarm()
+                    .validator(*validator)
+                    .unwrap()
+                    .rest_client()
+                    .get_ledger_information(),
+            )?;
+            healthy_version = healthy_version.max(version.into_inner().version);
+        }
+        if let Some(pressured_version) = pressured_version {
+            ensure!(
+                pressured_version < healthy_version,
+                "validator under disk pressure kept up with the network: {} >= {}",
+                pressured_version,
+                healthy_version
+            );
+        }
+        let msg = format!(
+            "2. Pressured validator at version {:?}, network at version {}",
+            pressured_version, healthy_version
+        );
+        println!("{}", msg);
+        ctx.report.report_text(msg);
+
+        // 3. Free the space and restart the validator so it has to reopen its DB from disk
+        let msg = "3. Removing disk pressure and restarting the validator".to_string();
+        println!("{}", msg);
+        ctx.report.report_text(msg);
+        ctx.swarm().remove_chaos(chaos)?;
+        let node = ctx.swarm().validator_mut(pressured).unwrap();
+        node.stop()?;
+        runtime.block_on(node.start())?;
+        runtime.block_on(node.wait_until_healthy(Instant::now() + Duration::from_secs(120)))?;
+
+        // 4. It must catch up with the rest of the network without any divergence
+        runtime.block_on(
+            ctx.swarm()
+                .wait_for_all_nodes_to_catchup(Instant::now() + Duration::from_secs(120)),
+        )?;
+        ctx.swarm().fork_check()?;
+        let msg = "4. Pressured validator recovered and caught up".to_string();
+        println!("{}", msg);
+        ctx.report.report_text(msg);
+
+        Ok(())
+    }
+}
@@ -2,8 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod compatibility_test;
+pub mod disk_pressure_test;
 pub mod fixed_tps_test;
 pub mod gas_price_test;
+pub mod multi_region_test;
 pub mod partial_nodes_down_test;
 pub mod performance_test;
 pub mod reconfiguration_test;
@@ -11,7 +13,9 @@ pub mod state_sync_performance;
 
 use anyhow::ensure;
 use aptos_sdk::{transaction_builder::TransactionFactory, types::PeerId};
-use forge::{NetworkContext, NodeExt, Result, TxnEmitter, TxnStats, Version};
+use forge::{
+    EmitJobRequest, NetworkContext, NodeExt, Result, SwarmExt, TxnEmitter, TxnStats, Version,
+};
 use rand::SeedableRng;
 use std::{
     convert::TryInto,
@@ -19,6 +23,8 @@ use std::{
 };
 use tokio::runtime::Runtime;
 
+const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
 async fn batch_update(
     ctx: &mut NetworkContext<'_>,
     validators_to_update: &[PeerId],
@@ -47,6 +53,40 @@ pub fn generate_traffic<'t>(
     duration: Duration,
     gas_price: u64,
     fixed_tps: Option<u64>,
+) -> Result<TxnStats> {
+    emit_traffic(
+        ctx,
+        validators,
+        duration,
+        gas_price,
+        |request| match fixed_tps {
+            Some(target_tps) => request.fixed_tps(target_tps.try_into().unwrap()),
+            None => request,
+        },
+    )
+}
+
+/// Generates traffic at `target_tps` regardless of how long transactions take to commit, so that
+/// latency degradation under saturation shows up in the returned stats rather than being hidden
+/// by a lower submission rate
+pub fn generate_open_loop_traffic<'t>(
+    ctx: &mut NetworkContext<'t>,
+    validators: &[PeerId],
+    duration: Duration,
+    gas_price: u64,
+    target_tps: u64,
+) -> Result<TxnStats> {
+    emit_traffic(ctx, validators, duration, gas_price, |request| {
+        request.open_loop_tps(target_tps.try_into().unwrap())
+    })
+}
+
+fn emit_traffic<'t>(
+    ctx: &mut NetworkContext<'t>,
+    validators: &[PeerId],
+    duration: Duration,
+    gas_price: u64,
+    configure: impl FnOnce(EmitJobRequest) -> EmitJobRequest,
 ) -> Result<TxnStats> {
     ensure!(gas_price > 0, "gas_price is required to be non zero");
     let rt = Runtime::new()?;
@@ -57,7 +97,8 @@ pub fn generate_traffic<'t>(
         .filter(|v| validators.contains(&v.peer_id()))
         .map(|n| n.rest_client())
         .collect::<Vec<_>>();
-    let mut emit_job_request = ctx.global_job.clone();
+    let emit_job_request = ctx.global_job.clone();
+    let resource_monitor = ctx.swarm().start_resource_monitor(RESOURCE_SAMPLE_INTERVAL);
     let chain_info = ctx.swarm().chain_info();
     let transaction_factory = TransactionFactory::new(chain_info.chain_id).with_gas_unit_price(1);
     let mut emitter = TxnEmitter::new(
@@ -67,13 +108,13 @@ pub fn generate_traffic<'t>(
         rng,
     );
 
-    emit_job_request = emit_job_request
-        .rest_clients(validator_clients)
-        .gas_price(gas_price);
-    if let Some(target_tps) = fixed_tps {
-        emit_job_request = emit_job_request.fixed_tps(target_tps.try_into().unwrap());
-    }
-    let stats = rt.block_on(emitter.emit_txn_for(duration, emit_job_request))?;
+    let emit_job_request = configure(
+        emit_job_request
+            .rest_clients(validator_clients)
+            .gas_price(gas_price),
+    );
+    let stats = rt.block_on(emitter.emit_txn_for(duration, emit_job_request));
+    ctx.report.set_resource_usage(resource_monitor.stop());
 
-    Ok(stats)
+    stats
 }
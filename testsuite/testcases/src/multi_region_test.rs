@@ -0,0 +1,79 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+use aptos_sdk::transaction_builder::aptos_stdlib;
+use forge::{NetworkContext, NetworkTest, NodeExt, Result, Test};
+use std::time::Instant;
+use tokio::runtime::Runtime;
+
+/// Number of transactions timed for every pair of regions
+const SAMPLES_PER_PAIR: u32 = 5;
+
+/// Measures, for every pair of regions, how long it takes for a transaction submitted to a
+/// validator in the first region to be committed and visible on a validator in the second one.
+/// Requires the swarm to be launched with a `RegionProfile`.
+pub struct MultiRegionLatency;
+
+impl Test for MultiRegionLatency {
+    fn name(&self) -> &'static str {
+        "multi-region-latency"
+    }
+}
+
+impl NetworkTest for MultiRegionLatency {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()> {
+        let regions = ctx
+            .regions()
+            .ok_or_else(|| anyhow!("{} requires a region profile", self.name()))?;
+        let runtime = Runtime::new()?;
+
+        // Use the first validator of each region as its entry point
+        let region_clients = regions
+            .iter()
+            .filter_map(|(region, validators)| {
+                let validator = ctx.swarm().validator(*validators.first()?)?;
+                Some((region.to_string(), validator.rest_client()))
+            })
+            .collect::<Vec<_>>();
+
+        let chain_info = ctx.swarm().chain_info();
+        let transaction_factory = chain_info.transaction_factory().with_gas_unit_price(1);
+        let root_account = chain_info.root_account;
+        let mut latencies = vec![];
+        for (from_region, from_client) in &region_clients {
+            for (to_region, to_client) in &region_clients {
+                let start = Instant::now();
+                for _ in 0..SAMPLES_PER_PAIR {
+                    let txn =
+                        root_account.sign_with_transaction_builder(transaction_factory.payload(
+                            aptos_stdlib::encode_test_coin_transfer(root_account.address(), 1),
+                        ));
+                    runtime.block_on(async {
+                        from_client.submit(&txn).await?;
+                        to_client.wait_for_signed_transaction(&txn).await
+                    })?;
+                }
+                let latency = start.elapsed() / SAMPLES_PER_PAIR;
+                latencies.push((from_region.clone(), to_region.clone(), latency));
+            }
+        }
+
+        for (from_region, to_region, latency) in latencies {
+            ctx.report.report_metric(
+                self.name(),
+                format!("commit_latency_{}_to_{}", from_region, to_region),
+                latency.as_millis() as f64,
+            );
+            ctx.report.report_text(format!(
+                "{} : {} -> {} committed in {} ms",
+                self.name(),
+                from_region,
+                to_region,
+                latency.as_millis()
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -4,7 +4,9 @@
 pub mod account;
 pub mod addresses;
 pub mod event;
+pub mod resource_account;
 
 pub use account::*;
 pub use addresses::*;
 pub use event::*;
+pub use resource_account::*;
@@ -0,0 +1,60 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::account_address::AccountAddress;
+use aptos_crypto::HashValue;
+
+/// Domain separator appended to the preimage of a derived (resource account) address. It is
+/// chosen from the top of the `u8` range so that it can never collide with the scheme id of an
+/// authentication key preimage (`Scheme::Ed25519`, `Scheme::MultiEd25519`, ...).
+pub const DERIVE_RESOURCE_ACCOUNT_SCHEME: u8 = 255;
+
+/// Derives the address of a resource account owned by `source` from `seed`:
+/// `sha3_256(source | seed | DERIVE_RESOURCE_ACCOUNT_SCHEME)`.
+///
+/// The same source and seed always map to the same address, so the faucet, CLI and SDK can all
+/// compute where a resource account lives without querying the chain.
+pub fn create_resource_address(source: AccountAddress, seed: &[u8]) -> AccountAddress {
+    let mut preimage = source.to_vec();
+    preimage.extend_from_slice(seed);
+    preimage.push(DERIVE_RESOURCE_ACCOUNT_SCHEME);
+    AccountAddress::new(*HashValue::sha3_256_of(&preimage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_resource_address() {
+        let source = AccountAddress::from_hex_literal("0x1").unwrap();
+        assert_eq!(
+            create_resource_address(source, b"seed"),
+            AccountAddress::from_hex_literal(
+                "0xf38401f1afe8001e6403d419628d8190fe67f0442d24d106d2592946d205aba4"
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            create_resource_address(source, &[]),
+            AccountAddress::from_hex_literal(
+                "0x914ed009b2a4325e1d4c6f167a52e9d49e7e9b8aa82c8af2ab43ddc260c50c7f"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_create_resource_address_depends_on_source_and_seed() {
+        let source = AccountAddress::from_hex_literal("0x1").unwrap();
+        let other = AccountAddress::from_hex_literal("0x2").unwrap();
+        assert_ne!(
+            create_resource_address(source, b"seed"),
+            create_resource_address(other, b"seed")
+        );
+        assert_ne!(
+            create_resource_address(source, b"seed"),
+            create_resource_address(source, b"seed2")
+        );
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account_address::AccountAddress, utility_coin::TEST_COIN_TYPE};
+use move_deps::move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    language_storage::{StructTag, TypeTag},
+    move_resource::{MoveResource, MoveStructType},
+};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+/// A Move coin type, e.g. `0x1::TestCoin::TestCoin`, used to instantiate the generic coin
+/// resources.
+pub trait CoinType {
+    fn type_tag() -> TypeTag;
+}
+
+/// The coin used to pay for gas.
+#[derive(Debug)]
+pub struct TestCoin;
+
+impl CoinType for TestCoin {
+    fn type_tag() -> TypeTag {
+        TEST_COIN_TYPE.clone()
+    }
+}
+
+/// Metadata of a coin type, published under the account that defines the coin type.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoinInfoResource<T> {
+    name: String,
+    symbol: String,
+    decimals: u64,
+    supply: Option<u64>,
+    #[serde(skip)]
+    coin_type: PhantomData<T>,
+}
+
+impl<T> CoinInfoResource<T> {
+    pub fn new(name: String, symbol: String, decimals: u64, supply: Option<u64>) -> Self {
+        Self {
+            name,
+            symbol,
+            decimals,
+            supply,
+            coin_type: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Number of decimals used to display balances, e.g. a balance of `505` with `2` decimals
+    /// is displayed as `5.05`.
+    pub fn decimals(&self) -> u64 {
+        self.decimals
+    }
+
+    /// Amount of the coin in existence, `None` if the supply is not tracked.
+    pub fn supply(&self) -> Option<u64> {
+        self.supply
+    }
+}
+
+/// Struct tag of `0x1::Coin::CoinInfo<coin_type>`, for coin types only known at runtime.
+pub fn coin_info_struct_tag(coin_type: TypeTag) -> StructTag {
+    StructTag {
+        address: AccountAddress::ONE,
+        module: CoinInfoResource::<TestCoin>::MODULE_NAME.to_owned(),
+        name: CoinInfoResource::<TestCoin>::STRUCT_NAME.to_owned(),
+        type_params: vec![coin_type],
+    }
+}
+
+impl<T: CoinType> MoveStructType for CoinInfoResource<T> {
+    const MODULE_NAME: &'static IdentStr = ident_str!("Coin");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("CoinInfo");
+
+    fn type_params() -> Vec<TypeTag> {
+        vec![T::type_tag()]
+    }
+}
+
+impl<T: CoinType> MoveResource for CoinInfoResource<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coin_info_resource_layout() {
+        // `ASCII::String` and `Option<u64>` share their BCS layout with `String` and `Option`.
+        let move_layout =
+            bcs::to_bytes(&(b"Test Coin".to_vec(), b"TC".to_vec(), 6u64, vec![1_000u64])).unwrap();
+        let info: CoinInfoResource<TestCoin> = bcs::from_bytes(&move_layout).unwrap();
+        assert_eq!(info.name(), "Test Coin");
+        assert_eq!(info.symbol(), "TC");
+        assert_eq!(info.decimals(), 6);
+        assert_eq!(info.supply(), Some(1_000));
+
+        assert_eq!(
+            CoinInfoResource::<TestCoin>::struct_tag(),
+            coin_info_struct_tag(TEST_COIN_TYPE.clone())
+        );
+    }
+}
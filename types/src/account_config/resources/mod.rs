@@ -3,12 +3,18 @@
 
 pub mod chain_account_info;
 pub mod chain_id;
+pub mod coin_info;
 pub mod coin_store;
 pub mod core_account;
 pub mod crsn;
+pub mod stake_pool;
+pub mod token;
 
 pub use chain_account_info::*;
 pub use chain_id::*;
+pub use coin_info::*;
 pub use coin_store::*;
 pub use core_account::*;
 pub use crsn::*;
+pub use stake_pool::*;
+pub use token::*;
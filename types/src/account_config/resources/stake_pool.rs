@@ -0,0 +1,192 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account_address::AccountAddress, state_store::state_key::StateKey};
+use move_deps::move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
+use serde::{Deserialize, Serialize};
+
+/// Stake delegated to a validator, published under the validator's account. Delegations are
+/// kept in tables keyed by the delegator address, only the table handles are part of the
+/// resource.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StakePoolResource {
+    voting_power: u64,
+    next_epoch_voting_power: u64,
+    active: DelegationTable,
+    inactive: DelegationTable,
+    pending_active: DelegationTable,
+    pending_inactive: DelegationTable,
+}
+
+impl StakePoolResource {
+    /// Sum of the active and pending inactive stake, as of the start of the current epoch.
+    pub fn voting_power(&self) -> u64 {
+        self.voting_power
+    }
+
+    /// Sum of the active and pending active stake, takes effect at the next epoch.
+    pub fn next_epoch_voting_power(&self) -> u64 {
+        self.next_epoch_voting_power
+    }
+
+    pub fn active(&self) -> &DelegationTable {
+        &self.active
+    }
+
+    /// Stake that can be withdrawn.
+    pub fn inactive(&self) -> &DelegationTable {
+        &self.inactive
+    }
+
+    pub fn pending_active(&self) -> &DelegationTable {
+        &self.pending_active
+    }
+
+    pub fn pending_inactive(&self) -> &DelegationTable {
+        &self.pending_inactive
+    }
+}
+
+impl MoveStructType for StakePoolResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("Stake");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("StakePool");
+}
+
+impl MoveResource for StakePoolResource {}
+
+/// `0x1::IterableTable::IterableTable<address, Delegation>`: a table whose entries form a
+/// doubly linked list from `head` to `tail`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DelegationTable {
+    handle: u128,
+    length: u64,
+    head: Option<AccountAddress>,
+    tail: Option<AccountAddress>,
+}
+
+impl DelegationTable {
+    pub fn handle(&self) -> u128 {
+        self.handle
+    }
+
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Delegator of the first delegation in the table, if any.
+    pub fn head(&self) -> Option<AccountAddress> {
+        self.head
+    }
+
+    /// State key of the table entry holding the delegation of `delegator`.
+    pub fn entry_state_key(&self, delegator: &AccountAddress) -> StateKey {
+        StateKey::table_item(self.handle, delegator.to_vec())
+    }
+}
+
+/// A table entry of a `DelegationTable`, linking to the neighbouring delegators.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DelegationTableEntry {
+    delegation: Delegation,
+    prev: Option<AccountAddress>,
+    next: Option<AccountAddress>,
+}
+
+impl DelegationTableEntry {
+    pub fn delegation(&self) -> &Delegation {
+        &self.delegation
+    }
+
+    pub fn prev(&self) -> Option<AccountAddress> {
+        self.prev
+    }
+
+    pub fn next(&self) -> Option<AccountAddress> {
+        self.next
+    }
+}
+
+/// Stake delegated by a single account, along with the rewards it accrued.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Delegation {
+    coins: u64,
+    rewards: u64,
+    locked_until_secs: u64,
+}
+
+impl Delegation {
+    pub fn new(coins: u64, rewards: u64, locked_until_secs: u64) -> Self {
+        Self {
+            coins,
+            rewards,
+            locked_until_secs,
+        }
+    }
+
+    pub fn coins(&self) -> u64 {
+        self.coins
+    }
+
+    /// Rewards distributed to the delegation at epoch boundaries, paid out on withdrawal.
+    pub fn rewards(&self) -> u64 {
+        self.rewards
+    }
+
+    /// Timestamp in seconds until which the stake can't be unlocked.
+    pub fn locked_until_secs(&self) -> u64 {
+        self.locked_until_secs
+    }
+
+    pub fn is_locked(&self, now_secs: u64) -> bool {
+        now_secs < self.locked_until_secs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stake_pool_resource_layout() {
+        // `Coin<TestCoin>` shares its BCS layout with `u64`.
+        let table = |handle: u128, head: Option<AccountAddress>| (handle, 1u64, head, head);
+        let move_layout = bcs::to_bytes(&(
+            100u64,
+            150u64,
+            table(1, Some(AccountAddress::ONE)),
+            table(2, None),
+            table(3, None),
+            table(4, None),
+        ))
+        .unwrap();
+        let pool: StakePoolResource = bcs::from_bytes(&move_layout).unwrap();
+        assert_eq!(pool.voting_power(), 100);
+        assert_eq!(pool.next_epoch_voting_power(), 150);
+        assert_eq!(pool.active().handle(), 1);
+        assert_eq!(pool.active().head(), Some(AccountAddress::ONE));
+        assert_eq!(pool.pending_inactive().handle(), 4);
+
+        let entry: DelegationTableEntry = bcs::from_bytes(
+            &bcs::to_bytes(&(
+                (100u64, 5u64, 86_400u64),
+                None::<AccountAddress>,
+                None::<AccountAddress>,
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(entry.delegation().coins(), 100);
+        assert_eq!(entry.delegation().rewards(), 5);
+        assert!(entry.delegation().is_locked(86_399));
+        assert!(!entry.delegation().is_locked(86_400));
+        assert_eq!(entry.next(), None);
+    }
+}
@@ -0,0 +1,309 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    account_address::AccountAddress, event::EventHandle, state_store::state_key::StateKey,
+};
+use anyhow::Result;
+use move_deps::move_core_types::{
+    ident_str,
+    identifier::IdentStr,
+    move_resource::{MoveResource, MoveStructType},
+};
+use serde::{Deserialize, Serialize};
+
+/// `0x1::Table::Table<K, V>`: only the handle and the number of entries are part of the
+/// resource, the entries are stored as table items keyed by the BCS bytes of the keys.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TableHandle {
+    handle: u128,
+    length: u64,
+}
+
+impl TableHandle {
+    pub fn handle(&self) -> u128 {
+        self.handle
+    }
+
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// State key of the table entry of `key`.
+    pub fn entry_state_key<K: Serialize>(&self, key: &K) -> Result<StateKey> {
+        Ok(StateKey::table_item(self.handle, bcs::to_bytes(key)?))
+    }
+}
+
+/// Unique identity of a token: its creator, collection and name.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct TokenId {
+    creator: AccountAddress,
+    collection: String,
+    name: String,
+}
+
+impl TokenId {
+    pub fn new(creator: AccountAddress, collection: String, name: String) -> Self {
+        Self {
+            creator,
+            collection,
+            name,
+        }
+    }
+
+    pub fn creator(&self) -> AccountAddress {
+        self.creator
+    }
+
+    pub fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// Amount of a token held in a `TokenStore`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Token {
+    id: TokenId,
+    value: u64,
+}
+
+impl Token {
+    pub fn id(&self) -> &TokenId {
+        &self.id
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+/// The tokens held by an account, in a table keyed by `TokenId`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenStoreResource {
+    tokens: TableHandle,
+    deposit_events: EventHandle,
+    withdraw_events: EventHandle,
+}
+
+impl TokenStoreResource {
+    /// Table of `TokenId` to `Token`.
+    pub fn tokens(&self) -> &TableHandle {
+        &self.tokens
+    }
+
+    /// Emits a `TokenDepositEvent` for every token received, except for the deposits made
+    /// through `Token::direct_deposit_without_event`.
+    pub fn deposit_events(&self) -> &EventHandle {
+        &self.deposit_events
+    }
+
+    pub fn withdraw_events(&self) -> &EventHandle {
+        &self.withdraw_events
+    }
+}
+
+impl MoveStructType for TokenStoreResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("Token");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("TokenStore");
+}
+
+impl MoveResource for TokenStoreResource {}
+
+/// The collections and token metadata of a creator.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CollectionsResource {
+    collections: TableHandle,
+    token_data: TableHandle,
+    burn_capabilities: TableHandle,
+    mint_capabilities: TableHandle,
+    create_collection_events: EventHandle,
+    create_token_events: EventHandle,
+}
+
+impl CollectionsResource {
+    /// Table of collection name to `Collection`.
+    pub fn collections(&self) -> &TableHandle {
+        &self.collections
+    }
+
+    /// Table of `TokenId` to `TokenData`.
+    pub fn token_data(&self) -> &TableHandle {
+        &self.token_data
+    }
+
+    pub fn create_collection_events(&self) -> &EventHandle {
+        &self.create_collection_events
+    }
+
+    pub fn create_token_events(&self) -> &EventHandle {
+        &self.create_token_events
+    }
+}
+
+impl MoveStructType for CollectionsResource {
+    const MODULE_NAME: &'static IdentStr = ident_str!("Token");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("Collections");
+}
+
+impl MoveResource for CollectionsResource {}
+
+/// Metadata of a collection.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Collection {
+    description: String,
+    name: String,
+    uri: String,
+    count: u64,
+    maximum: Option<u64>,
+}
+
+impl Collection {
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Number of distinct tokens created in the collection.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn maximum(&self) -> Option<u64> {
+        self.maximum
+    }
+}
+
+/// Metadata of a token, shared by all of its holders.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenData {
+    collection: String,
+    description: String,
+    name: String,
+    maximum: Option<u64>,
+    supply: Option<u64>,
+    uri: String,
+}
+
+impl TokenData {
+    pub fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn maximum(&self) -> Option<u64> {
+        self.maximum
+    }
+
+    /// Only tracked for tokens created with `monitor_supply`.
+    pub fn supply(&self) -> Option<u64> {
+        self.supply
+    }
+
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+}
+
+/// Emitted when a token is deposited into a `TokenStore`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenDepositEvent {
+    id: TokenId,
+    amount: u64,
+}
+
+impl TokenDepositEvent {
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<Self> {
+        bcs::from_bytes(bytes).map_err(Into::into)
+    }
+
+    pub fn id(&self) -> &TokenId {
+        &self.id
+    }
+
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+}
+
+impl MoveStructType for TokenDepositEvent {
+    const MODULE_NAME: &'static IdentStr = ident_str!("Token");
+    const STRUCT_NAME: &'static IdentStr = ident_str!("DepositEvent");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_resources_layout() {
+        // `ASCII::String` shares its BCS layout with `String`, `Table` is a handle and a length.
+        let id = (AccountAddress::ONE, "Collection", "Token");
+        let token: Token = bcs::from_bytes(&bcs::to_bytes(&(id, 2u64)).unwrap()).unwrap();
+        assert_eq!(
+            token.id(),
+            &TokenId::new(
+                AccountAddress::ONE,
+                "Collection".to_string(),
+                "Token".to_string()
+            )
+        );
+        assert_eq!(token.value(), 2);
+
+        let token_data: TokenData = bcs::from_bytes(
+            &bcs::to_bytes(&(
+                "Collection",
+                "A token",
+                "Token",
+                None::<u64>,
+                Some(2u64),
+                "https://aptos.dev",
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(token_data.name(), "Token");
+        assert_eq!(token_data.maximum(), None);
+        assert_eq!(token_data.supply(), Some(2));
+        assert_eq!(token_data.uri(), "https://aptos.dev");
+
+        let store: TokenStoreResource = bcs::from_bytes(
+            &bcs::to_bytes(&(
+                (7u128, 1u64),
+                EventHandle::random_handle(1),
+                EventHandle::random_handle(0),
+            ))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(store.tokens().handle(), 7);
+        assert_eq!(store.tokens().len(), 1);
+        assert_eq!(store.deposit_events().count(), 1);
+        assert_eq!(
+            store.tokens().entry_state_key(token.id()).unwrap(),
+            StateKey::table_item(7, bcs::to_bytes(&id).unwrap())
+        );
+    }
+}
@@ -3,7 +3,10 @@
 
 use crate::{
     access_path::AccessPath,
-    account_config::{AccountResource, CRSNResource, ChainIdResource, CoinStoreResource},
+    account_config::{
+        AccountResource, CRSNResource, ChainIdResource, CoinInfoResource, CoinStoreResource,
+        CoinType, StakePoolResource,
+    },
     on_chain_config::{
         access_path_for_config, ConfigurationResource, OnChainConfig, ValidatorSet, Version,
     },
@@ -65,6 +68,15 @@ pub trait AccountView {
         self.get_resource::<CoinStoreResource>()
     }
 
+    /// Returns the metadata of coin type `T`, if `T` is defined under this account.
+    fn get_coin_info_resource<T: CoinType>(&self) -> anyhow::Result<Option<CoinInfoResource<T>>> {
+        self.get_resource::<CoinInfoResource<T>>()
+    }
+
+    fn get_stake_pool_resource(&self) -> anyhow::Result<Option<StakePoolResource>> {
+        self.get_resource::<StakePoolResource>()
+    }
+
     fn get_state_key_for_path(&self, path: Vec<u8>) -> anyhow::Result<StateKey> {
         let account_address = self
             .get_account_address()?
@@ -0,0 +1,106 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::OnChainConfig;
+use serde::{Deserialize, Serialize};
+
+/// Protocol features that can be enabled on chain. The discriminant is the feature's index in the
+/// `Features` bit vector and must never be reused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FeatureFlag {
+    /// Transactions with secondary signers, on chains that predate `APTOS_VERSION_3`.
+    MultiAgentTransactions = 0,
+    /// Consensus proposals carry transaction hashes instead of the full payload.
+    CompactProposals = 1,
+    /// Proposals have their transactions deterministically shuffled, and validators don't vote for
+    /// proposals that aren't.
+    ShuffleTransactions = 2,
+}
+
+impl FeatureFlag {
+    pub const ALL: &'static [FeatureFlag] = &[
+        FeatureFlag::MultiAgentTransactions,
+        FeatureFlag::CompactProposals,
+        FeatureFlag::ShuffleTransactions,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FeatureFlag::MultiAgentTransactions => "multi_agent_transactions",
+            FeatureFlag::CompactProposals => "compact_proposals",
+            FeatureFlag::ShuffleTransactions => "shuffle_transactions",
+        }
+    }
+}
+
+/// The protocol features enabled on chain, as a bit vector: feature `i` is enabled if bit `i % 8`
+/// of byte `i / 8` is set. Nothing is enabled when the config isn't published.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct Features {
+    features: Vec<u8>,
+}
+
+impl Features {
+    pub fn new(enabled: &[FeatureFlag]) -> Self {
+        let mut features = Self::default();
+        for flag in enabled {
+            let (byte_index, bit_mask) = Self::position(*flag);
+            if features.features.len() <= byte_index {
+                features.features.resize(byte_index + 1, 0);
+            }
+            features.features[byte_index] |= bit_mask;
+        }
+        features
+    }
+
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        let (byte_index, bit_mask) = Self::position(flag);
+        self.features
+            .get(byte_index)
+            .map_or(false, |byte| byte & bit_mask != 0)
+    }
+
+    /// The known features that are enabled; bits of features this node doesn't know are ignored.
+    pub fn enabled_features(&self) -> Vec<FeatureFlag> {
+        FeatureFlag::ALL
+            .iter()
+            .copied()
+            .filter(|flag| self.is_enabled(*flag))
+            .collect()
+    }
+
+    fn position(flag: FeatureFlag) -> (usize, u8) {
+        let index = flag as usize;
+        (index / 8, 1 << (index % 8))
+    }
+}
+
+impl OnChainConfig for Features {
+    const IDENTIFIER: &'static str = "Features";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_enabled() {
+        assert!(Features::default().enabled_features().is_empty());
+
+        let features = Features::new(&[FeatureFlag::CompactProposals]);
+        assert!(features.is_enabled(FeatureFlag::CompactProposals));
+        assert!(!features.is_enabled(FeatureFlag::MultiAgentTransactions));
+        assert_eq!(
+            features.enabled_features(),
+            vec![FeatureFlag::CompactProposals]
+        );
+
+        // Bits of features this node doesn't know about are ignored.
+        let features: Features =
+            bcs::from_bytes(&bcs::to_bytes(&vec![0b1000_0001u8]).unwrap()).unwrap();
+        assert_eq!(
+            features.enabled_features(),
+            vec![FeatureFlag::MultiAgentTransactions]
+        );
+    }
+}
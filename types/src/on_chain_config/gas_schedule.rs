@@ -0,0 +1,63 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::on_chain_config::{OnChainConfig, VMConfig};
+use anyhow::Result;
+use move_deps::move_core_types::gas_schedule::CostTable;
+use serde::{Deserialize, Serialize};
+
+/// The gas schedule the VM charges transactions with.
+///
+/// On chain, the schedule is the `gas_schedule` field of the `VMConfig` resource, so this config
+/// shares `VMConfig`'s config id and is delivered as part of the same reconfiguration payload.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct GasSchedule {
+    pub cost_table: CostTable,
+}
+
+impl From<VMConfig> for GasSchedule {
+    fn from(config: VMConfig) -> Self {
+        Self {
+            cost_table: config.gas_schedule,
+        }
+    }
+}
+
+impl OnChainConfig for GasSchedule {
+    const IDENTIFIER: &'static str = VMConfig::IDENTIFIER;
+
+    fn deserialize_into_config(bytes: &[u8]) -> Result<Self> {
+        VMConfig::deserialize_into_config(bytes).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::on_chain_config::OnChainConfigPayload;
+    use std::{collections::HashMap, sync::Arc};
+
+    #[test]
+    fn test_gas_schedule_from_vm_config_payload() {
+        let cost_table = CostTable {
+            instruction_table: vec![],
+            native_table: vec![],
+            gas_constants: Default::default(),
+        };
+        // `VMConfig` stores the instruction and native tables as opaque bytes on chain.
+        let bytes = bcs::to_bytes(&(
+            bcs::to_bytes(&cost_table.instruction_table).unwrap(),
+            bcs::to_bytes(&cost_table.native_table).unwrap(),
+            cost_table.gas_constants.clone(),
+        ))
+        .unwrap();
+        let payload =
+            OnChainConfigPayload::new(1, Arc::new(HashMap::from([(VMConfig::CONFIG_ID, bytes)])));
+
+        assert_eq!(GasSchedule::CONFIG_ID, VMConfig::CONFIG_ID);
+        assert_eq!(
+            payload.get::<GasSchedule>().unwrap(),
+            GasSchedule { cost_table }
+        );
+    }
+}
@@ -19,7 +19,10 @@ use std::{collections::HashMap, fmt, sync::Arc};
 
 mod aptos_version;
 mod consensus_config;
+mod features;
+mod gas_schedule;
 mod registered_currencies;
+mod script_function_allow_list;
 mod validator_set;
 mod vm_config;
 mod vm_publishing_option;
@@ -29,7 +32,10 @@ pub use self::{
         Version, APTOS_MAX_KNOWN_VERSION, APTOS_VERSION_2, APTOS_VERSION_3, APTOS_VERSION_4,
     },
     consensus_config::{ConsensusConfigV1, ConsensusConfigV2, OnChainConsensusConfig},
+    features::{FeatureFlag, Features},
+    gas_schedule::GasSchedule,
     registered_currencies::RegisteredCurrencies,
+    script_function_allow_list::ScriptFunctionAllowList,
     validator_set::ValidatorSet,
     vm_config::VMConfig,
     vm_publishing_option::VMPublishingOption,
@@ -73,6 +79,11 @@ pub const ON_CHAIN_CONFIG_REGISTRY: &[ConfigID] = &[
     OnChainConsensusConfig::CONFIG_ID,
 ];
 
+/// Configs that aren't published at genesis, they are part of reconfiguration payloads once
+/// published
+pub const OPTIONAL_ON_CHAIN_CONFIGS: &[ConfigID] =
+    &[ScriptFunctionAllowList::CONFIG_ID, Features::CONFIG_ID];
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct OnChainConfigPayload {
     epoch: u64,
@@ -0,0 +1,96 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{account_address::AccountAddress, on_chain_config::OnChainConfig};
+use move_deps::move_core_types::{
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+};
+use serde::{Deserialize, Serialize};
+
+/// The script functions user transactions are allowed to call, for permissioned networks.
+/// Functions are named `<address>::<module>::<function>`, e.g. `0x1::Coin::transfer`.  Any
+/// script function can be called when the list isn't enabled, or isn't published at all.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ScriptFunctionAllowList {
+    enabled: bool,
+    allowed_functions: Vec<Vec<u8>>,
+}
+
+impl ScriptFunctionAllowList {
+    pub fn new(enabled: bool, allowed_functions: Vec<String>) -> Self {
+        Self {
+            enabled,
+            allowed_functions: allowed_functions
+                .into_iter()
+                .map(String::into_bytes)
+                .collect(),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn allowed_functions(&self) -> Vec<String> {
+        self.allowed_functions
+            .iter()
+            .map(|function| String::from_utf8_lossy(function).into_owned())
+            .collect()
+    }
+
+    /// Entries that aren't valid function names never match.
+    pub fn is_allowed(&self, module: &ModuleId, function: &IdentStr) -> bool {
+        !self.enabled
+            || self.allowed_functions.iter().any(|allowed| {
+                parse_function_name(allowed).map_or(false, |(allowed_module, allowed_function)| {
+                    &allowed_module == module && allowed_function.as_ident_str() == function
+                })
+            })
+    }
+}
+
+fn parse_function_name(name: &[u8]) -> Option<(ModuleId, Identifier)> {
+    let name = std::str::from_utf8(name).ok()?;
+    let mut parts = name.trim().split("::");
+    let address = AccountAddress::from_hex_literal(parts.next()?).ok()?;
+    let module = Identifier::new(parts.next()?).ok()?;
+    let function = Identifier::new(parts.next()?).ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((ModuleId::new(address, module), function))
+}
+
+impl OnChainConfig for ScriptFunctionAllowList {
+    const IDENTIFIER: &'static str = "ScriptFunctionAllowList";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_deps::move_core_types::ident_str;
+
+    #[test]
+    fn test_is_allowed() {
+        let coin = ModuleId::new(AccountAddress::ONE, ident_str!("Coin").to_owned());
+        let transfer = ident_str!("transfer");
+        let mint = ident_str!("mint");
+
+        assert!(ScriptFunctionAllowList::default().is_allowed(&coin, mint));
+
+        let allow_list = ScriptFunctionAllowList::new(
+            true,
+            vec![
+                "0x1::Coin::transfer".to_string(),
+                "0x1::Coin".to_string(),
+                "not a function".to_string(),
+            ],
+        );
+        assert!(allow_list.is_allowed(&coin, transfer));
+        assert!(!allow_list.is_allowed(&coin, mint));
+
+        let disabled = ScriptFunctionAllowList::new(false, vec![]);
+        assert!(disabled.is_allowed(&coin, mint));
+    }
+}
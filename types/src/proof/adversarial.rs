@@ -0,0 +1,228 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Honest proofs of small trees, and the ways a peer could tamper with them. Unlike the proofs
+//! of `proptest_proof`, the proofs generated here verify, so that every tampered proof is
+//! expected to fail the verification.
+
+use crate::{
+    proof::{
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleProof,
+        TestAccumulatorInternalNode, TestAccumulatorProof,
+    },
+    state_store::state_value::StateValue,
+};
+use aptos_crypto::{
+    hash::{CryptoHash, ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH},
+    HashValue,
+};
+use proptest::{
+    collection::{btree_map, btree_set, vec},
+    prelude::*,
+    sample::Index,
+};
+use proptest_derive::Arbitrary;
+
+/// A way to tamper with the proof of an element.
+#[derive(Clone, Debug, Arbitrary)]
+pub enum ProofTampering {
+    /// Flips a bit of a sibling.
+    FlipSiblingBit { sibling: Index, bit: Index },
+    /// Drops the siblings of the top levels of the tree.
+    TruncateSiblings { len: Index },
+    /// Claims the proof for another element of the tree.
+    SwapLeaf { other: Index },
+}
+
+fn flip_bit(hash: HashValue, bit: Index) -> HashValue {
+    let bit = bit.index(HashValue::LENGTH_IN_BITS);
+    let mut bytes = hash.to_vec();
+    bytes[bit / 8] ^= 1 << (bit % 8);
+    HashValue::from_slice(&bytes).expect("the length is unchanged")
+}
+
+fn tamper_siblings(siblings: &[HashValue], tampering: &ProofTampering) -> Vec<HashValue> {
+    let mut siblings = siblings.to_vec();
+    match tampering {
+        ProofTampering::FlipSiblingBit { sibling, bit } => {
+            let sibling = sibling.index(siblings.len());
+            siblings[sibling] = flip_bit(siblings[sibling], *bit);
+        }
+        ProofTampering::TruncateSiblings { len } => {
+            siblings.truncate(len.index(siblings.len()));
+        }
+        ProofTampering::SwapLeaf { .. } => {}
+    }
+    siblings
+}
+
+/// The index of an element other than `index` among `len` elements.
+fn other_index(index: usize, other: &Index, len: usize) -> usize {
+    (index + 1 + other.index(len - 1)) % len
+}
+
+/// An accumulator of distinct leaves, and the proof of one of them.
+#[derive(Debug)]
+pub struct AccumulatorProofCase {
+    pub leaves: Vec<HashValue>,
+    pub index: usize,
+    pub root_hash: HashValue,
+    pub proof: TestAccumulatorProof,
+}
+
+impl AccumulatorProofCase {
+    fn new(leaves: Vec<HashValue>, index: usize) -> Self {
+        let mut siblings = vec![];
+        let mut level = leaves.clone();
+        let mut position = index;
+        while level.len() > 1 {
+            siblings.push(
+                level
+                    .get(position ^ 1)
+                    .copied()
+                    .unwrap_or(*ACCUMULATOR_PLACEHOLDER_HASH),
+            );
+            level = level
+                .chunks(2)
+                .map(|pair| {
+                    TestAccumulatorInternalNode::new(
+                        pair[0],
+                        pair.get(1)
+                            .copied()
+                            .unwrap_or(*ACCUMULATOR_PLACEHOLDER_HASH),
+                    )
+                    .hash()
+                })
+                .collect();
+            position /= 2;
+        }
+        Self {
+            root_hash: level[0],
+            leaves,
+            index,
+            proof: TestAccumulatorProof::new(siblings),
+        }
+    }
+
+    /// Verifies the element of the case, tampered with as described by `tampering`.
+    pub fn verify_tampered(&self, tampering: &ProofTampering) -> anyhow::Result<()> {
+        let siblings = tamper_siblings(self.proof.siblings(), tampering);
+        let element_hash = match tampering {
+            ProofTampering::SwapLeaf { other } => {
+                self.leaves[other_index(self.index, other, self.leaves.len())]
+            }
+            _ => self.leaves[self.index],
+        };
+        TestAccumulatorProof::new(siblings).verify(self.root_hash, element_hash, self.index as u64)
+    }
+}
+
+/// Returns a strategy for accumulators of at least two distinct leaves, with the proof of one of
+/// them.
+pub fn arb_accumulator_proof_case() -> impl Strategy<Value = AccumulatorProofCase> {
+    (btree_set(any::<HashValue>(), 2..64), any::<Index>()).prop_map(|(leaves, index)| {
+        let leaves: Vec<_> = leaves.into_iter().collect();
+        let index = index.index(leaves.len());
+        AccumulatorProofCase::new(leaves, index)
+    })
+}
+
+/// A sparse Merkle tree of at least two leaves, and the inclusion proof of one of them.
+#[derive(Debug)]
+pub struct SparseMerkleProofCase {
+    pub leaves: Vec<(HashValue, StateValue)>,
+    pub index: usize,
+    pub root_hash: HashValue,
+    pub proof: SparseMerkleProof<StateValue>,
+}
+
+impl SparseMerkleProofCase {
+    fn new(leaves: Vec<(HashValue, StateValue)>, index: usize) -> Self {
+        let nodes: Vec<_> = leaves
+            .iter()
+            .map(|(key, value)| SparseMerkleLeafNode::new(*key, value.hash()))
+            .collect();
+        let key = nodes[index].key();
+        let mut siblings = vec![];
+        let mut subtree = nodes.clone();
+        let mut depth = 0;
+        while subtree.len() > 1 {
+            let (same_side, other_side): (Vec<_>, Vec<_>) = subtree
+                .into_iter()
+                .partition(|node| node.key().bit(depth) == key.bit(depth));
+            siblings.push(Self::subtree_hash(&other_side, depth + 1));
+            subtree = same_side;
+            depth += 1;
+        }
+        siblings.reverse();
+        Self {
+            root_hash: Self::subtree_hash(&nodes, 0),
+            leaves,
+            index,
+            proof: SparseMerkleProof::new(Some(nodes[index]), siblings),
+        }
+    }
+
+    fn subtree_hash(nodes: &[SparseMerkleLeafNode], depth: usize) -> HashValue {
+        match nodes {
+            [] => *SPARSE_MERKLE_PLACEHOLDER_HASH,
+            [leaf] => leaf.hash(),
+            _ => {
+                let (left, right): (Vec<_>, Vec<_>) = nodes
+                    .iter()
+                    .copied()
+                    .partition(|node| !node.key().bit(depth));
+                SparseMerkleInternalNode::new(
+                    Self::subtree_hash(&left, depth + 1),
+                    Self::subtree_hash(&right, depth + 1),
+                )
+                .hash()
+            }
+        }
+    }
+
+    /// Verifies the inclusion of the element of the case, tampered with as described by
+    /// `tampering`. Swapping the leaf puts the leaf of another element at the position of the
+    /// element in the tree.
+    pub fn verify_tampered(&self, tampering: &ProofTampering) -> anyhow::Result<()> {
+        let siblings = tamper_siblings(self.proof.siblings(), tampering);
+        let (key, value) = match tampering {
+            ProofTampering::SwapLeaf { other } => {
+                &self.leaves[other_index(self.index, other, self.leaves.len())]
+            }
+            _ => &self.leaves[self.index],
+        };
+        SparseMerkleProof::new(
+            Some(SparseMerkleLeafNode::new(*key, value.hash())),
+            siblings,
+        )
+        .verify(self.root_hash, *key, Some(value))
+    }
+
+    /// Verifies the non-inclusion of the element of the case, with its proof stripped of its
+    /// leaf.
+    pub fn verify_forged_non_inclusion(&self) -> anyhow::Result<()> {
+        SparseMerkleProof::<StateValue>::new(None, self.proof.siblings().to_vec()).verify(
+            self.root_hash,
+            self.leaves[self.index].0,
+            None,
+        )
+    }
+}
+
+/// Returns a strategy for sparse Merkle trees of at least two leaves, with the inclusion proof of
+/// one of them.
+pub fn arb_sparse_merkle_proof_case() -> impl Strategy<Value = SparseMerkleProofCase> {
+    (
+        btree_map(any::<HashValue>(), vec(any::<u8>(), 0..16), 2..64),
+        any::<Index>(),
+    )
+        .prop_map(|(leaves, index)| {
+            let leaves: Vec<_> = leaves
+                .into_iter()
+                .map(|(key, bytes)| (key, StateValue::from(bytes)))
+                .collect();
+            let index = index.index(leaves.len());
+            SparseMerkleProofCase::new(leaves, index)
+        })
+}
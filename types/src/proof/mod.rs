@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod accumulator;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod adversarial;
 pub mod definition;
 pub mod position;
 #[cfg(any(test, feature = "fuzzing"))]
@@ -0,0 +1,44 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::proof::adversarial::{
+    arb_accumulator_proof_case, arb_sparse_merkle_proof_case, ProofTampering,
+};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn test_accumulator_proof_honest(case in arb_accumulator_proof_case()) {
+        prop_assert!(case
+            .proof
+            .verify(case.root_hash, case.leaves[case.index], case.index as u64)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_accumulator_proof_tampered(
+        case in arb_accumulator_proof_case(),
+        tampering in any::<ProofTampering>(),
+    ) {
+        prop_assert!(case.verify_tampered(&tampering).is_err());
+    }
+
+    #[test]
+    fn test_sparse_merkle_proof_honest(case in arb_sparse_merkle_proof_case()) {
+        let (key, value) = &case.leaves[case.index];
+        prop_assert!(case.proof.verify(case.root_hash, *key, Some(value)).is_ok());
+    }
+
+    #[test]
+    fn test_sparse_merkle_proof_tampered(
+        case in arb_sparse_merkle_proof_case(),
+        tampering in any::<ProofTampering>(),
+    ) {
+        prop_assert!(case.verify_tampered(&tampering).is_err());
+    }
+
+    #[test]
+    fn test_sparse_merkle_proof_forged_non_inclusion(case in arb_sparse_merkle_proof_case()) {
+        prop_assert!(case.verify_forged_non_inclusion().is_err());
+    }
+}
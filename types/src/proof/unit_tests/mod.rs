@@ -1,5 +1,6 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod adversarial_proof_test;
 mod proof_conversion_test;
 mod proof_test;
@@ -204,6 +204,56 @@ impl ValidatorVerifier {
         Ok(())
     }
 
+    /// Verifies the signatures of the same message by several authors and returns the result of
+    /// each signature, in order. The signatures are batch verified, and verified one by one to
+    /// identify the invalid ones if batching fails. Signatures that batch verification would not
+    /// check as strictly as `verify` does, e.g. with a small or mixed order R, are left out of the
+    /// batch and only verified one by one.
+    pub fn batch_verify_signatures<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        signatures: &[(AccountAddress, &Ed25519Signature)],
+    ) -> Vec<std::result::Result<(), VerifyError>> {
+        let (batch, keys_and_signatures) = self.batch_verifiable_signatures(signatures);
+        let mut results: Vec<_> = signatures
+            .iter()
+            .enumerate()
+            .map(|(index, (author, signature))| {
+                if batch.binary_search(&index).is_ok() {
+                    Ok(())
+                } else {
+                    self.verify(*author, message, signature)
+                }
+            })
+            .collect();
+        // A single signature is verified as fast on its own.
+        if batch.len() < 2 || Ed25519Signature::batch_verify(message, keys_and_signatures).is_err()
+        {
+            for index in batch {
+                let (author, signature) = signatures[index];
+                results[index] = self.verify(author, message, signature);
+            }
+        }
+        results
+    }
+
+    /// Returns the indices of the `signatures` of known authors that batch verification checks as
+    /// strictly as `verify` does, and their public keys and signatures.
+    fn batch_verifiable_signatures(
+        &self,
+        signatures: &[(AccountAddress, &Ed25519Signature)],
+    ) -> (Vec<usize>, Vec<(Ed25519PublicKey, Ed25519Signature)>) {
+        signatures
+            .iter()
+            .enumerate()
+            .filter_map(|(index, (author, signature))| {
+                self.get_public_key(author)
+                    .filter(|pub_key| signature.check_batch_verifiable(pub_key).is_ok())
+                    .map(|pub_key| (index, (pub_key, (*signature).clone())))
+            })
+            .unzip()
+    }
+
     /// Ensure there are not more than the maximum expected signatures (all possible signatures).
     fn check_num_of_signatures(
         &self,
@@ -380,7 +430,7 @@ mod tests {
     use super::*;
     use crate::validator_signer::ValidatorSigner;
     use aptos_crypto::test_utils::{TestAptosCrypto, TEST_SEED};
-    use std::collections::BTreeMap;
+    use std::{collections::BTreeMap, convert::TryFrom};
 
     #[test]
     fn test_check_voting_power() {
@@ -435,6 +485,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_batch_verify_signatures() {
+        let (validator_signers, validator_verifier) = random_validator_verifier(4, None, false);
+        let dummy_struct = TestAptosCrypto("Hello, World".to_string());
+        let mut signatures: Vec<_> = validator_signers
+            .iter()
+            .map(|signer| (signer.author(), signer.sign(&dummy_struct)))
+            .collect();
+        let results = validator_verifier.batch_verify_signatures(
+            &dummy_struct,
+            &signatures
+                .iter()
+                .map(|(author, signature)| (*author, signature))
+                .collect::<Vec<_>>(),
+        );
+        assert!(results.iter().all(|result| result.is_ok()));
+
+        // An invalid signature, a signature with a small order R and an unknown author are
+        // identified by the fallback.
+        let other_struct = TestAptosCrypto("Goodbye, World".to_string());
+        signatures[1].1 = validator_signers[1].sign(&other_struct);
+        let mut small_order_r = signatures[2].1.to_bytes();
+        // the encoding of the identity point
+        small_order_r[..32].copy_from_slice(&[0; 32]);
+        small_order_r[0] = 1;
+        signatures[2].1 = Ed25519Signature::try_from(&small_order_r[..]).unwrap();
+        let unknown_validator_signer = ValidatorSigner::random([4; 32]);
+        signatures.push((
+            unknown_validator_signer.author(),
+            unknown_validator_signer.sign(&dummy_struct),
+        ));
+        let results = validator_verifier.batch_verify_signatures(
+            &dummy_struct,
+            &signatures
+                .iter()
+                .map(|(author, signature)| (*author, signature))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            results,
+            vec![
+                Ok(()),
+                Err(VerifyError::InvalidSignature),
+                Err(VerifyError::InvalidSignature),
+                Ok(()),
+                Err(VerifyError::UnknownAuthor),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_batch_verify_signatures_leaves_out_small_order_signature() {
+        let (validator_signers, validator_verifier) = random_validator_verifier(4, None, false);
+        let dummy_struct = TestAptosCrypto("Hello, World".to_string());
+        let mut signatures: Vec<_> = validator_signers
+            .iter()
+            .map(|signer| (signer.author(), signer.sign(&dummy_struct)))
+            .collect();
+        let mut small_order_r = signatures[2].1.to_bytes();
+        // the encoding of the identity point
+        small_order_r[..32].copy_from_slice(&[0; 32]);
+        small_order_r[0] = 1;
+        signatures[2].1 = Ed25519Signature::try_from(&small_order_r[..]).unwrap();
+        let signatures: Vec<_> = signatures
+            .iter()
+            .map(|(author, signature)| (*author, signature))
+            .collect();
+
+        // Only the small order signature is left out of the batch.
+        let (batch, _) = validator_verifier.batch_verifiable_signatures(&signatures);
+        assert_eq!(batch, vec![0, 1, 3]);
+        assert_eq!(
+            validator_verifier.batch_verify_signatures(&dummy_struct, &signatures),
+            vec![Ok(()), Ok(()), Err(VerifyError::InvalidSignature), Ok(())]
+        );
+    }
+
     #[test]
     fn test_equal_vote_quorum_validators() {
         const NUM_SIGNERS: u8 = 7;
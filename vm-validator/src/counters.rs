@@ -0,0 +1,27 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics::{register_int_counter_vec, register_int_gauge, IntCounterVec, IntGauge};
+use once_cell::sync::Lazy;
+
+pub const VALIDATION_CACHE_HIT: &str = "hit";
+pub const VALIDATION_CACHE_PROBE: &str = "probe";
+
+/// Counter for validation result cache events, the hit rate is `hit / probe`
+pub static VALIDATION_CACHE_EVENT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_vm_validator_validation_cache",
+        "Counters for validation result cache events in the VM validator",
+        &["event"]
+    )
+    .unwrap()
+});
+
+/// Number of entries currently held in the validation result cache
+pub static VALIDATION_CACHE_SIZE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_vm_validator_validation_cache_size",
+        "Number of entries in the validation result cache of the VM validator"
+    )
+    .unwrap()
+});
@@ -3,5 +3,6 @@
 
 #![forbid(unsafe_code)]
 
+mod counters;
 pub mod mocks;
 pub mod vm_validator;
@@ -7,6 +7,7 @@ use aptos_transaction_builder::aptos_stdlib;
 use aptos_types::{
     account_address, account_config,
     chain_id::ChainId,
+    on_chain_config::{OnChainConfig, OnChainConfigPayload, ScriptFunctionAllowList, VMConfig},
     test_helpers::transaction_test_helpers,
     transaction::{Module, Script, TransactionPayload},
     vm_status::StatusCode,
@@ -15,9 +16,10 @@ use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
 use move_deps::move_core_types::{
     account_address::AccountAddress,
-    gas_schedule::{GasAlgebra, GasConstants},
+    gas_schedule::{GasAlgebra, GasConstants, GasPrice},
 };
 use rand::SeedableRng;
+use std::{collections::HashMap, sync::Arc};
 use storage_interface::DbReaderWriter;
 
 const MAX_TRANSACTION_SIZE_IN_BYTES: u64 = 262144;
@@ -448,3 +450,96 @@ fn test_validate_chain_id() {
     let ret = vm_validator.validate_transaction(transaction).unwrap();
     assert_eq!(ret.status().unwrap(), StatusCode::BAD_CHAIN_ID);
 }
+
+#[test]
+fn test_restart_uses_gas_schedule_from_reconfiguration() {
+    let mut vm_validator = TestValidator::new();
+
+    let gas_constants = GasConstants {
+        max_price_per_gas_unit: GasPrice::new(1),
+        ..GasConstants::default()
+    };
+    // `VMConfig` stores the instruction and native tables as opaque bytes on chain.
+    let vm_config = bcs::to_bytes(&(
+        bcs::to_bytes(&Vec::<u8>::new()).unwrap(),
+        bcs::to_bytes(&Vec::<u8>::new()).unwrap(),
+        gas_constants,
+    ))
+    .unwrap();
+    let payload = OnChainConfigPayload::new(
+        1,
+        Arc::new(HashMap::from([(VMConfig::CONFIG_ID, vm_config)])),
+    );
+    vm_validator.vm_validator.restart(payload).unwrap();
+
+    let address = account_config::aptos_root_address();
+    let transaction = transaction_test_helpers::get_test_signed_transaction(
+        address,
+        1,
+        &vm_genesis::GENESIS_KEYPAIR.0,
+        vm_genesis::GENESIS_KEYPAIR.1.clone(),
+        None,
+        0,
+        2, /* max gas price */
+        None,
+    );
+    let ret = vm_validator.validate_transaction(transaction).unwrap();
+    assert_eq!(
+        ret.status().unwrap(),
+        StatusCode::GAS_UNIT_PRICE_ABOVE_MAX_BOUND
+    );
+}
+
+#[test]
+fn test_restart_uses_script_function_allow_list_from_reconfiguration() {
+    let mut vm_validator = TestValidator::new();
+
+    let allow_list = ScriptFunctionAllowList::new(true, vec!["0x1::Coin::transfer".to_string()]);
+    let payload = OnChainConfigPayload::new(
+        1,
+        Arc::new(HashMap::from([(
+            ScriptFunctionAllowList::CONFIG_ID,
+            bcs::to_bytes(&allow_list).unwrap(),
+        )])),
+    );
+    vm_validator.vm_validator.restart(payload).unwrap();
+
+    let address = account_config::aptos_root_address();
+    let transaction = transaction_test_helpers::get_test_signed_txn(
+        address,
+        1,
+        &vm_genesis::GENESIS_KEYPAIR.0,
+        vm_genesis::GENESIS_KEYPAIR.1.clone(),
+        Some(aptos_stdlib::encode_test_coin_mint(address, 100)),
+    );
+    let ret = vm_validator.validate_transaction(transaction).unwrap();
+    assert_eq!(ret.status().unwrap(), StatusCode::UNKNOWN_SCRIPT);
+}
+
+#[test]
+fn test_validation_result_is_cached_until_commit() {
+    let mut vm_validator = TestValidator::new();
+
+    let address = account_config::aptos_root_address();
+    let transaction = transaction_test_helpers::get_test_signed_transaction(
+        address,
+        1,
+        &vm_genesis::GENESIS_KEYPAIR.0,
+        vm_genesis::GENESIS_KEYPAIR.1.clone(),
+        None,
+        0,
+        u64::MAX, /* max gas price */
+        None,
+    );
+    let first = vm_validator
+        .validate_transaction(transaction.clone())
+        .unwrap();
+    let second = vm_validator
+        .validate_transaction(transaction.clone())
+        .unwrap();
+    assert_eq!(first, second);
+    assert_eq!(vm_validator.validation_cache.lock().len(), 1);
+
+    vm_validator.vm_validator.notify_commit();
+    assert_eq!(vm_validator.validation_cache.lock().len(), 0);
+}
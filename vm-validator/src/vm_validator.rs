@@ -1,18 +1,24 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::counters::{
+    VALIDATION_CACHE_EVENT, VALIDATION_CACHE_HIT, VALIDATION_CACHE_PROBE, VALIDATION_CACHE_SIZE,
+};
 use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_failpoints::fail_point;
+use aptos_infallible::Mutex;
 use aptos_state_view::{account_with_state_view::AsAccountWithStateView, StateViewId};
 use aptos_types::{
     account_address::AccountAddress,
     account_config::AccountSequenceInfo,
     account_view::AccountView,
-    on_chain_config::OnChainConfigPayload,
-    transaction::{SignedTransaction, VMValidatorResult},
+    on_chain_config::{Features, GasSchedule, OnChainConfigPayload, ScriptFunctionAllowList},
+    transaction::{SignedTransaction, VMValidatorResult, Version},
 };
 use aptos_vm::AptosVM;
 use executor::components::in_memory_state_calculator::IntoLedgerView;
-use fail::fail_point;
+use lru::LruCache;
 use std::sync::Arc;
 use storage_interface::{
     state_view::LatestDbStateView, verified_state_view::VerifiedStateView, DbReader,
@@ -35,26 +41,40 @@ pub trait TransactionValidation: Send + Sync + Clone {
     fn notify_commit(&mut self);
 }
 
-fn latest_state_view(db_reader: &Arc<dyn DbReader>) -> VerifiedStateView {
+/// Failpoints evaluated by the validator.
+pub const FAILPOINTS: &[&str] = &[
+    "vm_validator::validate_transaction",
+    "vm_validator::get_account_sequence_number",
+];
+
+/// Maximum number of validation results kept for the current state version.
+const VALIDATION_CACHE_CAPACITY: usize = 10_000;
+
+fn latest_state_view(db_reader: &Arc<dyn DbReader>) -> (Version, VerifiedStateView) {
     let ledger_view = db_reader
         .get_latest_tree_state()
         .expect("Should not fail.")
         .into_ledger_view(db_reader)
         .expect("Should not fail.");
+    let base_version = ledger_view.version().expect("Must be bootstrapped.");
 
-    ledger_view.state_view(
+    let state_view = ledger_view.state_view(
         &ledger_view,
-        StateViewId::TransactionValidation {
-            base_version: ledger_view.version().expect("Must be bootstrapped."),
-        },
+        StateViewId::TransactionValidation { base_version },
         db_reader.clone(),
-    )
+    );
+    (base_version, state_view)
 }
 
 pub struct VMValidator {
     db_reader: Arc<dyn DbReader>,
     cached_state_view: VerifiedStateView,
+    cached_state_version: Version,
     vm: AptosVM,
+    /// Results of transactions already validated against `cached_state_view`, keyed by
+    /// (transaction hash, state version), so that clients retrying a submission don't re-run
+    /// the prologue until the state moves on.
+    validation_cache: Mutex<LruCache<(HashValue, Version), VMValidatorResult>>,
 }
 
 impl Clone for VMValidator {
@@ -65,15 +85,23 @@ impl Clone for VMValidator {
 
 impl VMValidator {
     pub fn new(db_reader: Arc<dyn DbReader>) -> Self {
-        let cached_state_view = latest_state_view(&db_reader);
+        aptos_failpoints::register(FAILPOINTS);
+        let (cached_state_version, cached_state_view) = latest_state_view(&db_reader);
 
         let vm = AptosVM::new_for_validation(&cached_state_view);
         VMValidator {
             db_reader,
             cached_state_view,
+            cached_state_version,
             vm,
+            validation_cache: Mutex::new(LruCache::new(VALIDATION_CACHE_CAPACITY)),
         }
     }
+
+    fn clear_validation_cache(&self) {
+        self.validation_cache.lock().clear();
+        VALIDATION_CACHE_SIZE.set(0);
+    }
 }
 
 impl TransactionValidation for VMValidator {
@@ -87,18 +115,48 @@ impl TransactionValidation for VMValidator {
         });
         use aptos_vm::VMValidator;
 
-        Ok(self.vm.validate_transaction(txn, &self.cached_state_view))
+        let key = (txn.clone().committed_hash(), self.cached_state_version);
+        VALIDATION_CACHE_EVENT
+            .with_label_values(&[VALIDATION_CACHE_PROBE])
+            .inc();
+        if let Some(result) = self.validation_cache.lock().get(&key) {
+            VALIDATION_CACHE_EVENT
+                .with_label_values(&[VALIDATION_CACHE_HIT])
+                .inc();
+            return Ok(result.clone());
+        }
+
+        let result = self.vm.validate_transaction(txn, &self.cached_state_view);
+        let mut cache = self.validation_cache.lock();
+        cache.put(key, result.clone());
+        VALIDATION_CACHE_SIZE.set(cache.len() as i64);
+        Ok(result)
     }
 
-    fn restart(&mut self, _config: OnChainConfigPayload) -> Result<()> {
+    fn restart(&mut self, config: OnChainConfigPayload) -> Result<()> {
         self.notify_commit();
 
         self.vm = AptosVM::new_for_validation(&self.cached_state_view);
+        // The reconfiguration payload carries the configs of the new epoch; prefer them over the
+        // ones read from the state view, which may not have caught up with the reconfiguration.
+        if let Ok(gas_schedule) = config.get::<GasSchedule>() {
+            self.vm.set_gas_schedule(gas_schedule);
+        }
+        if let Ok(allow_list) = config.get::<ScriptFunctionAllowList>() {
+            self.vm.set_script_function_allow_list(allow_list);
+        }
+        if let Ok(features) = config.get::<Features>() {
+            self.vm.set_features(features);
+        }
         Ok(())
     }
 
     fn notify_commit(&mut self) {
-        self.cached_state_view = latest_state_view(&self.db_reader);
+        let (cached_state_version, cached_state_view) = latest_state_view(&self.db_reader);
+        self.cached_state_version = cached_state_version;
+        self.cached_state_view = cached_state_view;
+        // Results computed against older versions can never be hit again.
+        self.clear_validation_cache();
     }
 }
 